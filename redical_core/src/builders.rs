@@ -0,0 +1,612 @@
+use std::str::FromStr;
+
+use redical_ical::properties::CalendarProperty;
+use redical_ical::values::date_time::DateTime;
+
+use crate::calendar::Calendar;
+use crate::event::Event;
+use crate::event_occurrence_override::EventOccurrenceOverride;
+use crate::queries::event_instance_query::EventInstanceQuery;
+use crate::queries::event_query::EventQuery;
+use crate::queries::indexed_property_filters::WhereOperator;
+
+// Render a UTC timestamp as an iCalendar UTC date-time value (e.g. 20210105T183000Z).
+fn render_utc_date_time(timestamp: i64) -> String {
+    DateTime::from(timestamp).render_formatted_date_time(None)
+}
+
+fn render_where_operator(where_operator: &WhereOperator) -> &'static str {
+    match where_operator {
+        WhereOperator::And => "AND",
+        WhereOperator::Or => "OR",
+    }
+}
+
+/// Builds an [`Event`] from typed properties, as an alternative to parsing a single iCalendar
+/// string via [`Event::parse_ical`].
+///
+/// Each property is rendered as an iCalendar content line and parsed (and validated) by
+/// [`EventBuilder::build`], so the built Event is identical to one set via the Redis commands.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EventBuilder {
+    uid: String,
+    content_lines: Vec<String>,
+    occurrence_overrides: Vec<(i64, Vec<String>)>,
+}
+
+impl EventBuilder {
+    pub fn new(uid: &str) -> Self {
+        EventBuilder {
+            uid: uid.to_owned(),
+            content_lines: Vec::new(),
+            occurrence_overrides: Vec::new(),
+        }
+    }
+
+    /// Add a raw iCalendar content line (e.g. `DESCRIPTION:Event description`) for properties
+    /// without a dedicated builder method.
+    pub fn property(mut self, content_line: &str) -> Self {
+        self.content_lines.push(content_line.to_owned());
+
+        self
+    }
+
+    pub fn dtstart(self, timestamp: i64) -> Self {
+        let content_line = format!("DTSTART:{}", render_utc_date_time(timestamp));
+
+        self.property(&content_line)
+    }
+
+    /// Add a DTSTART local to the provided timezone (e.g. `Europe/London` and `20210105T183000`).
+    pub fn dtstart_with_tzid(self, tzid: &str, local_date_time: &str) -> Self {
+        let content_line = format!("DTSTART;TZID={tzid}:{local_date_time}");
+
+        self.property(&content_line)
+    }
+
+    pub fn dtend(self, timestamp: i64) -> Self {
+        let content_line = format!("DTEND:{}", render_utc_date_time(timestamp));
+
+        self.property(&content_line)
+    }
+
+    pub fn duration(self, seconds: i64) -> Self {
+        let content_line = format!("DURATION:PT{seconds}S");
+
+        self.property(&content_line)
+    }
+
+    /// Add an RRULE from it's value (e.g. `FREQ=WEEKLY;COUNT=10;BYDAY=TU`).
+    pub fn rrule(self, rrule: &str) -> Self {
+        let content_line = format!("RRULE:{rrule}");
+
+        self.property(&content_line)
+    }
+
+    pub fn rdate(self, timestamp: i64) -> Self {
+        let content_line = format!("RDATE:{}", render_utc_date_time(timestamp));
+
+        self.property(&content_line)
+    }
+
+    pub fn exdate(self, timestamp: i64) -> Self {
+        let content_line = format!("EXDATE:{}", render_utc_date_time(timestamp));
+
+        self.property(&content_line)
+    }
+
+    pub fn categories(self, categories: &[&str]) -> Self {
+        let content_line = format!("CATEGORIES:{}", categories.join(","));
+
+        self.property(&content_line)
+    }
+
+    pub fn location_type(self, location_types: &[&str]) -> Self {
+        let content_line = format!("LOCATION-TYPE:{}", location_types.join(","));
+
+        self.property(&content_line)
+    }
+
+    pub fn location(self, location: &str) -> Self {
+        let content_line = format!("LOCATION:{location}");
+
+        self.property(&content_line)
+    }
+
+    pub fn related_to(self, reltype: &str, related_to_uid: &str) -> Self {
+        let content_line = format!("RELATED-TO;RELTYPE={reltype}:{related_to_uid}");
+
+        self.property(&content_line)
+    }
+
+    pub fn geo(self, latitude: f64, longitude: f64) -> Self {
+        let content_line = format!("GEO:{latitude};{longitude}");
+
+        self.property(&content_line)
+    }
+
+    pub fn class(self, class: &str) -> Self {
+        let content_line = format!("CLASS:{class}");
+
+        self.property(&content_line)
+    }
+
+    pub fn last_modified(self, timestamp: i64) -> Self {
+        let content_line = format!("LAST-MODIFIED:{}", render_utc_date_time(timestamp));
+
+        self.property(&content_line)
+    }
+
+    /// Override the occurrence starting at the provided timestamp with the provided raw iCalendar
+    /// content lines (e.g. `CATEGORIES:CANCELLED`).
+    pub fn occurrence_override(mut self, dtstart_timestamp: i64, content_lines: &[&str]) -> Self {
+        self.occurrence_overrides.push((
+            dtstart_timestamp,
+            content_lines.iter().map(|content_line| content_line.to_string()).collect(),
+        ));
+
+        self
+    }
+
+    /// Parse and validate the built Event (along with any occurrence overrides).
+    ///
+    /// The Event indexes are left to be built when it is inserted into a [`Calendar`] (e.g. via
+    /// [`Calendar::insert_event_and_reindex`]).
+    pub fn build(self) -> Result<Event, String> {
+        let mut event = Event::parse_ical(self.uid.as_str(), self.content_lines.join(" ").as_str())?;
+
+        event.validate()?;
+
+        for (dtstart_timestamp, content_lines) in self.occurrence_overrides {
+            let event_occurrence_override = EventOccurrenceOverride::parse_ical(
+                render_utc_date_time(dtstart_timestamp).as_str(),
+                content_lines.join(" ").as_str(),
+            )?;
+
+            event.override_occurrence(&event_occurrence_override, false)?;
+        }
+
+        Ok(event)
+    }
+}
+
+/// Builds a [`Calendar`] populated with Events, building it's indexes once all the Events have
+/// been inserted (as with `RDCL.CAL_IDX_REBUILD` after a bulk import).
+#[derive(Debug, PartialEq, Clone)]
+pub struct CalendarBuilder {
+    uid: String,
+    default_tzid: Option<String>,
+    events: Vec<EventBuilder>,
+    indexes_active: bool,
+}
+
+impl CalendarBuilder {
+    pub fn new(uid: &str) -> Self {
+        CalendarBuilder {
+            uid: uid.to_owned(),
+            default_tzid: None,
+            events: Vec::new(),
+            indexes_active: true,
+        }
+    }
+
+    pub fn event(mut self, event: EventBuilder) -> Self {
+        self.events.push(event);
+
+        self
+    }
+
+    /// Declare the timezone (e.g. `Europe/London`) in which the floating date-times of the
+    /// Calendar's events are interpreted (as with `RDCL.CAL_SET key X-DEFAULT-TZID:Europe/London`).
+    pub fn default_tzid(mut self, tzid: &str) -> Self {
+        self.default_tzid = Some(tzid.to_owned());
+
+        self
+    }
+
+    /// Build the Calendar with it's indexes disabled (as with `RDCL.CAL_IDX_DISABLE`), which
+    /// disables querying it.
+    pub fn without_indexes(mut self) -> Self {
+        self.indexes_active = false;
+
+        self
+    }
+
+    pub fn build(self) -> Result<Calendar, String> {
+        let mut calendar = Calendar::new(self.uid);
+
+        if let Some(default_tzid) = self.default_tzid {
+            calendar.insert(CalendarProperty::from_str(format!("X-DEFAULT-TZID:{default_tzid}").as_str())?)?;
+        }
+
+        for event in self.events {
+            let mut event = event.build()?;
+
+            calendar.localize_event(&mut event)?;
+
+            if calendar.events.contains_key(&event.uid.uid.to_string()) {
+                return Err(format!("Duplicate event UID: {}", event.uid.uid));
+            }
+
+            calendar.insert_event(event);
+        }
+
+        if self.indexes_active {
+            calendar.rebuild_indexes()?;
+        } else {
+            calendar.disable_indexes();
+        }
+
+        Ok(calendar)
+    }
+}
+
+/// Builds an [`EventInstanceQuery`] or [`EventQuery`] from typed conditions, as an alternative to
+/// parsing a query string (e.g. `X-CATEGORIES:APPOINTMENT X-LIMIT:10`).
+///
+/// Each where condition added is combined with the others using AND.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct QueryBuilder {
+    query_properties: Vec<String>,
+}
+
+impl QueryBuilder {
+    pub fn new() -> Self {
+        QueryBuilder::default()
+    }
+
+    /// Add a raw query property (e.g. `X-DURATION;OP=GTE:PT1H`) for conditions without a
+    /// dedicated builder method.
+    pub fn property(mut self, query_property: &str) -> Self {
+        self.query_properties.push(query_property.to_owned());
+
+        self
+    }
+
+    /// Only include results starting after the provided timestamp.
+    pub fn from(self, timestamp: i64) -> Self {
+        let query_property = format!("X-FROM:{}", render_utc_date_time(timestamp));
+
+        self.property(&query_property)
+    }
+
+    /// Only include results starting before the provided timestamp.
+    pub fn until(self, timestamp: i64) -> Self {
+        let query_property = format!("X-UNTIL:{}", render_utc_date_time(timestamp));
+
+        self.property(&query_property)
+    }
+
+    pub fn uids(self, uids: &[&str]) -> Self {
+        let query_property = format!("X-UID:{}", uids.join(","));
+
+        self.property(&query_property)
+    }
+
+    pub fn categories(self, categories: &[&str], where_operator: WhereOperator) -> Self {
+        let query_property = format!(
+            "X-CATEGORIES;OP={}:{}",
+            render_where_operator(&where_operator),
+            categories.join(","),
+        );
+
+        self.property(&query_property)
+    }
+
+    pub fn location_types(self, location_types: &[&str], where_operator: WhereOperator) -> Self {
+        let query_property = format!(
+            "X-LOCATION-TYPE;OP={}:{}",
+            render_where_operator(&where_operator),
+            location_types.join(","),
+        );
+
+        self.property(&query_property)
+    }
+
+    pub fn locations(self, locations: &[&str], where_operator: WhereOperator) -> Self {
+        let query_property = format!(
+            "X-LOCATION;OP={}:{}",
+            render_where_operator(&where_operator),
+            locations.join(","),
+        );
+
+        self.property(&query_property)
+    }
+
+    pub fn related_to(self, reltype: &str, related_to_uids: &[&str], where_operator: WhereOperator) -> Self {
+        let query_property = format!(
+            "X-RELATED-TO;RELTYPE={reltype};OP={}:{}",
+            render_where_operator(&where_operator),
+            related_to_uids.join(","),
+        );
+
+        self.property(&query_property)
+    }
+
+    pub fn classes(self, classes: &[&str], where_operator: WhereOperator) -> Self {
+        let query_property = format!(
+            "X-CLASS;OP={}:{}",
+            render_where_operator(&where_operator),
+            classes.join(","),
+        );
+
+        self.property(&query_property)
+    }
+
+    /// Only include results within the provided distance (in kilometers) of the coordinates.
+    pub fn within_distance_km(self, distance_km: f64, latitude: f64, longitude: f64) -> Self {
+        let query_property = format!("X-GEO;DIST={distance_km}KM:{latitude};{longitude}");
+
+        self.property(&query_property)
+    }
+
+    pub fn order_by_dtstart(self) -> Self {
+        self.property("X-ORDER-BY:DTSTART")
+    }
+
+    pub fn order_by_dtstart_geo_dist(self, latitude: f64, longitude: f64) -> Self {
+        let query_property = format!("X-ORDER-BY:DTSTART-GEO-DIST;{latitude};{longitude}");
+
+        self.property(&query_property)
+    }
+
+    pub fn order_by_geo_dist_dtstart(self, latitude: f64, longitude: f64) -> Self {
+        let query_property = format!("X-ORDER-BY:GEO-DIST-DTSTART;{latitude};{longitude}");
+
+        self.property(&query_property)
+    }
+
+    /// Return the results in the provided timezone (e.g. `Europe/London`).
+    pub fn tzid(self, tzid: &str) -> Self {
+        let query_property = format!("X-TZID:{tzid}");
+
+        self.property(&query_property)
+    }
+
+    /// Only return the first result for each Event UID.
+    pub fn distinct_uids(self) -> Self {
+        self.property("X-DISTINCT:UID")
+    }
+
+    /// Only return up to the provided number of results for each Event UID.
+    pub fn limit_per_event(self, limit_per_event: usize) -> Self {
+        let query_property = format!("X-LIMIT-PER-EVENT:{limit_per_event}");
+
+        self.property(&query_property)
+    }
+
+    /// Only render the provided properties (e.g. `UID`, `DTSTART`, `SUMMARY`) for each result.
+    pub fn return_properties(self, property_names: &[&str]) -> Self {
+        let query_property = format!("X-RETURN:{}", property_names.join(","));
+
+        self.property(&query_property)
+    }
+
+    /// Only return the number of results matching the query.
+    pub fn count_only(self) -> Self {
+        self.property("X-COUNT:TRUE")
+    }
+
+    /// Collapse the overlapping (or adjacent) event instances matching the query into merged
+    /// busy intervals (only applicable to `RDCL.EVI_QUERY`).
+    pub fn merge_intervals(self) -> Self {
+        self.property("X-MERGE-INTERVALS:TRUE")
+    }
+
+    pub fn offset(self, offset: usize) -> Self {
+        let query_property = format!("X-OFFSET:{offset}");
+
+        self.property(&query_property)
+    }
+
+    pub fn limit(self, limit: usize) -> Self {
+        let query_property = format!("X-LIMIT:{limit}");
+
+        self.property(&query_property)
+    }
+
+    /// The equivalent query string (as accepted by `RDCL.EVI_QUERY` and `RDCL.EVT_QUERY`).
+    pub fn to_query_string(&self) -> String {
+        self.query_properties.join(" ")
+    }
+
+    pub fn build_event_instance_query(&self) -> Result<EventInstanceQuery, String> {
+        EventInstanceQuery::from_str(self.to_query_string().as_str())
+    }
+
+    pub fn build_event_query(&self) -> Result<EventQuery, String> {
+        EventQuery::from_str(self.to_query_string().as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use redical_ical::ICalendarComponent;
+
+    use crate::queries::query::Query;
+
+    fn timestamp(date_time: &str) -> i64 {
+        DateTime::from_str(date_time).unwrap().get_utc_timestamp(None)
+    }
+
+    #[test]
+    fn test_event_builder() {
+        let event =
+            EventBuilder::new("EVENT_UID")
+                .dtstart(timestamp("20210105T183000Z"))
+                .duration(3600)
+                .rrule("FREQ=WEEKLY;COUNT=3;INTERVAL=1")
+                .categories(&["CATEGORY_ONE", "CATEGORY_TWO"])
+                .class("PUBLIC")
+                .last_modified(timestamp("20210501T090000Z"))
+                .build()
+                .unwrap();
+
+        let mut expected_event =
+            Event::parse_ical(
+                "EVENT_UID",
+                [
+                    "DTSTART:20210105T183000Z",
+                    "DURATION:PT3600S",
+                    "RRULE:FREQ=WEEKLY;COUNT=3;INTERVAL=1",
+                    "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO",
+                    "CLASS:PUBLIC",
+                    "LAST-MODIFIED:20210501T090000Z",
+                ].join(" ").as_str(),
+            ).unwrap();
+
+        expected_event.validate().unwrap();
+
+        assert_eq!(event, expected_event);
+
+        assert!(
+            EventBuilder::new("EVENT_UID")
+                .categories(&["CATEGORY_ONE"])
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_event_builder_occurrence_override() {
+        let event =
+            EventBuilder::new("EVENT_UID")
+                .dtstart(timestamp("20210105T183000Z"))
+                .rrule("FREQ=WEEKLY;COUNT=3;INTERVAL=1")
+                .occurrence_override(timestamp("20210112T183000Z"), &["CATEGORIES:CANCELLED"])
+                .build()
+                .unwrap();
+
+        assert_eq!(
+            event
+                .instances()
+                .unwrap()
+                .map(|event_instance| event_instance.to_rendered_content_lines())
+                .collect::<Vec<Vec<String>>>(),
+            vec![
+                vec![
+                    String::from("DTEND:20210105T183000Z"),
+                    String::from("DTSTART:20210105T183000Z"),
+                    String::from("DURATION:PT0S"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210105T183000Z"),
+                    String::from("UID:EVENT_UID"),
+                ],
+                vec![
+                    String::from("CATEGORIES:CANCELLED"),
+                    String::from("DTEND:20210112T183000Z"),
+                    String::from("DTSTART:20210112T183000Z"),
+                    String::from("DURATION:PT0S"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210112T183000Z"),
+                    String::from("UID:EVENT_UID"),
+                ],
+                vec![
+                    String::from("DTEND:20210119T183000Z"),
+                    String::from("DTSTART:20210119T183000Z"),
+                    String::from("DURATION:PT0S"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210119T183000Z"),
+                    String::from("UID:EVENT_UID"),
+                ],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_calendar_builder_and_query_builder() {
+        let mut calendar =
+            CalendarBuilder::new("CALENDAR_UID")
+                .event(
+                    EventBuilder::new("EVENT_ONE")
+                        .dtstart(timestamp("20210105T183000Z"))
+                        .rrule("FREQ=WEEKLY;COUNT=2;INTERVAL=1")
+                        .categories(&["CATEGORY_ONE"])
+                )
+                .event(
+                    EventBuilder::new("EVENT_TWO")
+                        .dtstart(timestamp("20210106T183000Z"))
+                        .rdate(timestamp("20210106T183000Z"))
+                        .categories(&["CATEGORY_TWO"])
+                )
+                .build()
+                .unwrap();
+
+        assert!(calendar.indexes_active);
+        assert_eq!(calendar.events.len(), 2);
+
+        let query =
+            QueryBuilder::new()
+                .categories(&["CATEGORY_ONE", "CATEGORY_TWO"], WhereOperator::Or)
+                .from(timestamp("20210106T000000Z"))
+                .order_by_dtstart()
+                .limit(10);
+
+        assert_eq!(
+            query.to_query_string(),
+            String::from("X-CATEGORIES;OP=OR:CATEGORY_ONE,CATEGORY_TWO X-FROM:20210106T000000Z X-ORDER-BY:DTSTART X-LIMIT:10"),
+        );
+
+        let event_instance_dtstarts =
+            query
+                .build_event_instance_query()
+                .unwrap()
+                .execute(&calendar)
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|query_result| (query_result.result.uid.uid.to_string(), query_result.result.dtstart.date_time.render_formatted_date_time(None)))
+                .collect::<Vec<(String, String)>>();
+
+        assert_eq!(
+            event_instance_dtstarts,
+            vec![
+                (String::from("EVENT_TWO"), String::from("20210106T183000Z")),
+                (String::from("EVENT_ONE"), String::from("20210112T183000Z")),
+            ],
+        );
+
+        // Events inserted (and removed) after building the Calendar are reflected in the indexes.
+        calendar.insert_event_and_reindex(
+            EventBuilder::new("EVENT_THREE")
+                .dtstart(timestamp("20210107T183000Z"))
+                .rdate(timestamp("20210107T183000Z"))
+                .categories(&["CATEGORY_ONE"])
+                .build()
+                .unwrap()
+        ).unwrap();
+
+        calendar.remove_event_and_reindex(&String::from("EVENT_ONE")).unwrap();
+
+        let event_uids =
+            QueryBuilder::new()
+                .categories(&["CATEGORY_ONE"], WhereOperator::Or)
+                .build_event_query()
+                .unwrap()
+                .execute(&calendar)
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|query_result| query_result.result.uid.uid.to_string())
+                .collect::<Vec<String>>();
+
+        assert_eq!(event_uids, vec![String::from("EVENT_THREE")]);
+
+        assert!(
+            CalendarBuilder::new("CALENDAR_UID")
+                .event(EventBuilder::new("EVENT_ONE").dtstart(0).rdate(0))
+                .event(EventBuilder::new("EVENT_ONE").dtstart(0).rdate(0))
+                .build()
+                .is_err()
+        );
+
+        assert!(
+            !CalendarBuilder::new("CALENDAR_UID")
+                .without_indexes()
+                .build()
+                .unwrap()
+                .indexes_active
+        );
+    }
+}
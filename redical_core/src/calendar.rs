@@ -1,47 +1,125 @@
-use std::collections::{BTreeSet, BTreeMap, HashMap};
+use std::collections::{BTreeSet, BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
+use std::time::{Duration, Instant};
 
-use crate::inverted_index::{IndexedConclusion, InvertedCalendarIndex};
+use chrono_tz::Tz;
+
+use regex::Regex;
+
+use geo::HaversineDistance;
+
+use crate::interning::InternedString;
+
+use crate::inverted_index::{IndexedConclusion, InvertedCalendarIndex, InvertedCalendarIndexTerm, InvertedEventIndex, TermNormalization};
 
 use crate::utils::{KeyValuePair, UpdatedHashMapMembers};
 
-use crate::geo_index::{GeoPoint, GeoSpatialCalendarIndex};
+use crate::geo_index::{GeoDistance, GeoPoint, GeoSpatialCalendarIndex};
+
+use crate::time_bucket_index::TimeBucketCalendarIndex;
 
 use crate::event::Event;
 
+use crate::event_occurrence_iterator::DstPolicy;
+
+use crate::event_occurrence_override::EventOccurrenceOverride;
+
+use redical_ical::values::term_normalization::TermNormalizationValue;
+
 use redical_ical::{
     ICalendarComponent,
+    ICalendarEntity,
     RenderingContext,
     content_line::ContentLine,
     properties::{
+        ICalendarDateTimeProperty,
         ICalendarProperty,
         CalendarProperty,
+        ColorProperty,
+        NameProperty,
+        PassiveProperty,
+        RecurrenceIDProperty,
+        RefreshIntervalProperty,
+        SourceProperty,
         UIDProperty,
+        XDefaultDstPolicyProperty,
+        XDefaultTzidProperty,
+        XIndexedPropertiesProperty,
+        XRequiredPropertiesProperty,
+        XAllowedPropertiesProperty,
+        XPruneOlderThanProperty,
+        XTermNormalizationProperty,
     },
 };
 
+/// The number of elements of each Calendar collection sampled when approximating it's memory
+/// usage, matching the default of Redis `MEMORY USAGE`.
+pub const DEFAULT_MEMORY_USAGE_SAMPLE_SIZE: usize = 5;
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Calendar {
     pub uid: UIDProperty,
+    pub default_tzid: Option<XDefaultTzidProperty>,
+    pub default_dst_policy: Option<XDefaultDstPolicyProperty>,
+    pub term_normalization: Option<XTermNormalizationProperty>,
+    pub indexed_property_names: Option<XIndexedPropertiesProperty>,
+    pub required_property_names: Option<XRequiredPropertiesProperty>,
+    pub allowed_property_names: Option<XAllowedPropertiesProperty>,
+    pub prune_older_than: Option<XPruneOlderThanProperty>,
+    // The RFC 7986 calendar description properties, with a NAME permitted for each language.
+    pub names: Option<HashSet<NameProperty>>,
+    pub color: Option<ColorProperty>,
+    pub refresh_interval: Option<RefreshIntervalProperty>,
+    pub source: Option<SourceProperty>,
+    pub passive_properties: BTreeSet<PassiveProperty>,
     pub events: BTreeMap<String, Box<Event>>,
+    pub event_expirations: BTreeMap<String, i64>,
     pub indexes_active: bool,
-    pub indexed_categories: InvertedCalendarIndex<String>,
-    pub indexed_location_type: InvertedCalendarIndex<String>,
+    // Monotonically increasing counter bumped on every mutation of the Calendar (see
+    // `bump_generation`), so that clients can detect whether cached results are stale.
+    pub generation: u64,
+    // The UTC timestamp of the last full rebuild of the indexes (see `rebuild_indexes_at`), only
+    // retained in memory.
+    pub indexes_rebuilt_at: Option<i64>,
+    pub indexed_categories: InvertedCalendarIndex<InternedString>,
+    pub indexed_location_type: InvertedCalendarIndex<InternedString>,
     pub indexed_related_to: InvertedCalendarIndex<KeyValuePair>,
     pub indexed_geo: GeoSpatialCalendarIndex,
-    pub indexed_class: InvertedCalendarIndex<String>,
+    pub indexed_class: InvertedCalendarIndex<InternedString>,
+    pub indexed_location: InvertedCalendarIndex<InternedString>,
+    pub indexed_x_properties: InvertedCalendarIndex<KeyValuePair>,
+    pub indexed_time_buckets: TimeBucketCalendarIndex,
 }
 
 impl Calendar {
     pub fn new(uid: String) -> Self {
         Calendar {
             uid: uid.into(),
+            default_tzid: None,
+            default_dst_policy: None,
+            term_normalization: None,
+            indexed_property_names: None,
+            required_property_names: None,
+            allowed_property_names: None,
+            prune_older_than: None,
+            names: None,
+            color: None,
+            refresh_interval: None,
+            source: None,
+            passive_properties: BTreeSet::new(),
             events: BTreeMap::new(),
+            event_expirations: BTreeMap::new(),
             indexes_active: true,
+            generation: 0,
+            indexes_rebuilt_at: None,
             indexed_categories: InvertedCalendarIndex::new(),
             indexed_location_type: InvertedCalendarIndex::new(),
             indexed_related_to: InvertedCalendarIndex::new(),
             indexed_geo: GeoSpatialCalendarIndex::new(),
             indexed_class: InvertedCalendarIndex::new(),
+            indexed_location: InvertedCalendarIndex::new(),
+            indexed_x_properties: InvertedCalendarIndex::new(),
+            indexed_time_buckets: TimeBucketCalendarIndex::new(),
         }
     }
 
@@ -54,11 +132,338 @@ impl Calendar {
                     );
                 }
             },
+
+            CalendarProperty::DefaultTzid(default_tzid_property) => {
+                self.default_tzid = Some(default_tzid_property);
+            },
+
+            CalendarProperty::DefaultDstPolicy(default_dst_policy_property) => {
+                self.default_dst_policy = Some(default_dst_policy_property);
+            },
+
+            CalendarProperty::TermNormalization(term_normalization_property) => {
+                self.term_normalization = Some(term_normalization_property);
+
+                // Without any events there are no existing terms to re-normalise, otherwise they
+                // are only re-normalised when the indexes are next rebuilt.
+                if self.events.is_empty() {
+                    self.clear_indexes();
+                }
+            },
+
+            CalendarProperty::IndexedProperties(indexed_properties_property) => {
+                self.indexed_property_names = Some(indexed_properties_property);
+
+                // Unlike the term normalisation, every X-property is already indexed against each
+                // event, so only the Calendar X-property index needs rebuilding to reflect them.
+                if self.indexes_active {
+                    self.rebuild_indexed_x_properties()?;
+                }
+            },
+
+            CalendarProperty::RequiredProperties(required_properties_property) => {
+                self.required_property_names = Some(required_properties_property);
+            },
+
+            CalendarProperty::AllowedProperties(allowed_properties_property) => {
+                self.allowed_property_names = Some(allowed_properties_property);
+            },
+
+            CalendarProperty::PruneOlderThan(prune_older_than_property) => {
+                self.prune_older_than = Some(prune_older_than_property);
+            },
+
+            CalendarProperty::Name(name_property) => {
+                self.names
+                    .get_or_insert(HashSet::new())
+                    .insert(name_property);
+            },
+
+            CalendarProperty::Color(color_property) => {
+                self.color = Some(color_property);
+            },
+
+            CalendarProperty::RefreshInterval(refresh_interval_property) => {
+                self.refresh_interval = Some(refresh_interval_property);
+            },
+
+            CalendarProperty::Source(source_property) => {
+                self.source = Some(source_property);
+            },
+
+            CalendarProperty::Passive(passive_property) => {
+                self.passive_properties.insert(passive_property);
+            },
+        }
+
+        Ok(self)
+    }
+
+    // Insert all the provided properties, with the NAME and passive properties (e.g. X-WR-CALNAME)
+    // replacing any existing ones sharing their name, rather than accumulating alongside them.
+    pub fn insert_properties(&mut self, properties: Vec<CalendarProperty>) -> Result<&Self, String> {
+        if properties.iter().any(|property| matches!(property, CalendarProperty::Name(_))) {
+            self.names = None;
+        }
+
+        let replaced_property_names: HashSet<String> =
+            properties
+                .iter()
+                .filter_map(|property| {
+                    match property {
+                        CalendarProperty::Passive(passive_property) => Some(passive_property.get_property_name()),
+                        _ => None,
+                    }
+                })
+                .collect();
+
+        self.passive_properties.retain(|passive_property| {
+            !replaced_property_names.contains(&passive_property.get_property_name())
+        });
+
+        for property in properties {
+            self.insert(property)?;
         }
 
         Ok(self)
     }
 
+    // Mark the Calendar as mutated, returning it's new generation.
+    pub fn bump_generation(&mut self) -> u64 {
+        self.generation += 1;
+
+        self.generation
+    }
+
+    // The timezone in which date-times specified without a UTC designator or TZID are
+    // interpreted (and query results are rendered in by default), if declared.
+    pub fn get_default_tz(&self) -> Option<Tz> {
+        self.default_tzid.as_ref().map(Tz::from)
+    }
+
+    // How event instances landing on a local time skipped or repeated by a DST transition are
+    // resolved by queries not specifying their own policy, if declared.
+    pub fn get_default_dst_policy(&self) -> Option<DstPolicy> {
+        self.default_dst_policy
+            .as_ref()
+            .map(|default_dst_policy| DstPolicy::from(&default_dst_policy.dst_policy))
+    }
+
+    // Whether the queried CATEGORIES, LOCATION-TYPE, CLASS, and UID values are matched against
+    // those indexed case-insensitively (as declared via X-TERM-NORMALIZATION CASE-FOLD), rather
+    // than exactly.
+    pub fn is_term_matching_case_insensitive(&self) -> bool {
+        self.get_term_normalization().case_fold
+    }
+
+    // How the CATEGORIES, LOCATION-TYPE, LOCATION, and CLASS terms are normalised before they are
+    // indexed (and queried), as declared via X-TERM-NORMALIZATION.
+    pub fn get_term_normalization(&self) -> TermNormalization {
+        let Some(term_normalization) = self.term_normalization.as_ref() else {
+            return TermNormalization::default();
+        };
+
+        TermNormalization::new(
+            term_normalization.contains(&TermNormalizationValue::CaseFold),
+            term_normalization.contains(&TermNormalizationValue::Trim),
+            term_normalization.contains(&TermNormalizationValue::StripDiacritics),
+        )
+    }
+
+    // Whether the passive X-property of the given (uppercased) name is declared to be indexed by
+    // the Calendar X-INDEXED-PROPERTIES property.
+    pub fn is_x_property_indexed(&self, property_name: &str) -> bool {
+        self.indexed_property_names
+            .as_ref()
+            .is_some_and(|indexed_property_names| indexed_property_names.contains(property_name))
+    }
+
+    // The UTC timestamp before which (relative to now) events and event occurrence overrides are
+    // periodically pruned, if a retention period is declared.
+    pub fn get_prune_until(&self, now: i64) -> Option<i64> {
+        self.prune_older_than
+            .as_ref()
+            .map(|prune_older_than| now - prune_older_than.duration.get_duration_in_seconds())
+    }
+
+    // Validate the event against the properties the Calendar requires (X-REQUIRED-PROPERTIES) and
+    // allows (X-ALLOWED-PROPERTIES) its events to specify, returning every violation found so that
+    // producers can correct all of them at once.
+    //
+    // The UID and LAST-MODIFIED properties (as well as the required properties) are always allowed.
+    pub fn validate_event_schema(&self, event: &Event) -> Result<(), String> {
+        if self.required_property_names.is_none() && self.allowed_property_names.is_none() {
+            return Ok(());
+        }
+
+        let event_property_names = event.get_property_names();
+
+        let mut violations: Vec<String> = Vec::new();
+
+        if let Some(required_property_names) = self.required_property_names.as_ref() {
+            let missing_property_names: Vec<&str> =
+                required_property_names.property_names
+                    .iter()
+                    .filter(|property_name| !event_property_names.contains(*property_name))
+                    .map(String::as_str)
+                    .collect();
+
+            if !missing_property_names.is_empty() {
+                violations.push(format!("missing required properties: {}", missing_property_names.join(", ")));
+            }
+        }
+
+        if let Some(allowed_property_names) = self.allowed_property_names.as_ref() {
+            let disallowed_property_names: Vec<&str> =
+                event_property_names
+                    .iter()
+                    .filter(|property_name| {
+                        !["UID", "LAST-MODIFIED"].contains(&property_name.as_str())
+                            && !allowed_property_names.contains(property_name)
+                            && !self.required_property_names
+                                .as_ref()
+                                .is_some_and(|required_property_names| required_property_names.contains(property_name))
+                    })
+                    .map(String::as_str)
+                    .collect();
+
+            if !disallowed_property_names.is_empty() {
+                violations.push(format!("disallowed properties: {}", disallowed_property_names.join(", ")));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("calendar schema violated - {}", violations.join("; ")))
+        }
+    }
+
+    // Interpret the floating date-times of the (validated) event in the Calendar default timezone
+    // (if declared) instead of UTC, re-validating it so that it's parsed RRuleSet reflects them.
+    pub fn localize_event(&self, event: &mut Event) -> Result<(), String> {
+        let Some(default_tz) = self.get_default_tz() else {
+            return Ok(());
+        };
+
+        event.schedule_properties.apply_default_timezone(&default_tz)?;
+        event.validate()?;
+
+        Ok(())
+    }
+
+    // Interpret the floating date-times of the (validated) event occurrence override in the
+    // Calendar default timezone (if declared) instead of UTC.
+    pub fn localize_event_occurrence_override(&self, event_occurrence_override: &mut EventOccurrenceOverride) -> Result<(), String> {
+        let Some(default_tz) = self.get_default_tz() else {
+            return Ok(());
+        };
+
+        event_occurrence_override.apply_default_timezone(&default_tz)
+    }
+
+    // The UIDs of the events matching the provided UID, either exactly or case-insensitively (as
+    // declared via X-TERM-NORMALIZATION CASE-FOLD). As event UIDs are not indexed, matching them
+    // case-insensitively compares against every event UID.
+    pub fn get_matching_event_uids(&self, uid: &str) -> Vec<&String> {
+        if !self.is_term_matching_case_insensitive() {
+            return self.events.get_key_value(uid).map(|(event_uid, _event)| event_uid).into_iter().collect();
+        }
+
+        let case_folded_uid = uid.to_lowercase();
+
+        self.events
+            .keys()
+            .filter(|event_uid| event_uid.to_lowercase() == case_folded_uid)
+            .collect()
+    }
+
+    // The UIDs of the events starting with the provided UID prefix (e.g. `TENANT_123_`), range
+    // scanned over the ordered event UIDs, unless matched case-insensitively (as declared via
+    // X-TERM-NORMALIZATION CASE-FOLD) which compares against every event UID.
+    pub fn get_prefixed_event_uids(&self, uid_prefix: &str) -> Vec<&String> {
+        if !self.is_term_matching_case_insensitive() {
+            return self.events
+                .range::<str, _>((Bound::Included(uid_prefix), Bound::Unbounded))
+                .map(|(event_uid, _event)| event_uid)
+                .take_while(|event_uid| event_uid.starts_with(uid_prefix))
+                .collect();
+        }
+
+        let case_folded_uid_prefix = uid_prefix.to_lowercase();
+
+        self.events
+            .keys()
+            .filter(|event_uid| event_uid.to_lowercase().starts_with(&case_folded_uid_prefix))
+            .collect()
+    }
+
+    // The UIDs of every event matching the (compiled query) regular expression, the case
+    // sensitivity of which is already determined by the term normalization policy when compiled.
+    pub fn get_regex_matching_event_uids(&self, uid_regex: &Regex) -> Vec<&String> {
+        self.events
+            .keys()
+            .filter(|event_uid| uid_regex.is_match(event_uid))
+            .collect()
+    }
+
+    // The GEO index term of the provided candidate events (e.g. those already matched by the
+    // other side of an AND) within the given distance of the given point.
+    //
+    // Rather than scanning every indexed point within the distance, only the GEO points of each
+    // candidate event are checked (taking their indexed conclusions from the calendar GEO index).
+    // This falls back to searching the calendar GEO index whenever there are at least as many
+    // candidate events as indexed points, or any candidate event is missing it's GEO index.
+    pub fn locate_candidates_within_distance(
+        &self,
+        long_lat: &GeoPoint,
+        distance: &GeoDistance,
+        candidates: &InvertedCalendarIndexTerm,
+    ) -> InvertedCalendarIndexTerm {
+        if candidates.events.len() >= self.indexed_geo.len() {
+            return self.indexed_geo.locate_within_distance(long_lat, distance);
+        }
+
+        let candidate_indexed_geo: Option<Vec<(&InternedString, &InvertedEventIndex<GeoPoint>)>> =
+            candidates
+                .events
+                .keys()
+                .map(|event_uid| {
+                    self.events
+                        .get(event_uid.as_str())
+                        .and_then(|event| event.indexed_geo.as_ref())
+                        .map(|indexed_geo| (event_uid, indexed_geo))
+                })
+                .collect();
+
+        let Some(candidate_indexed_geo) = candidate_indexed_geo else {
+            return self.indexed_geo.locate_within_distance(long_lat, distance);
+        };
+
+        let distance_meters = distance.to_meters_float();
+
+        let mut inverted_calendar_index_term = InvertedCalendarIndexTerm::new();
+
+        for (event_uid, indexed_geo) in candidate_indexed_geo {
+            for geo_point in indexed_geo.terms.keys() {
+                if geo_point.haversine_distance(long_lat) > distance_meters {
+                    continue;
+                }
+
+                let Some(indexed_conclusion) = self.indexed_geo.get_indexed_conclusion(event_uid, geo_point) else {
+                    continue;
+                };
+
+                inverted_calendar_index_term = InvertedCalendarIndexTerm::merge_or(
+                    &inverted_calendar_index_term,
+                    &InvertedCalendarIndexTerm::new_with_event(event_uid.to_owned(), indexed_conclusion.to_owned()),
+                );
+            }
+        }
+
+        inverted_calendar_index_term
+    }
+
     pub fn get_event(&self, event_uid: &String) -> Option<&Event> {
         self.events.get(event_uid).map(|boxed_event| boxed_event.as_ref())
     }
@@ -86,14 +491,145 @@ impl Calendar {
     }
 
     pub fn remove_event(&mut self, event_uid: &String) -> Option<Box<Event>> {
+        self.event_expirations.remove(event_uid);
+
         self.events.remove(event_uid)
     }
 
+    // Insert (or replace) the event, updating the Calendar indexes (if active) with the difference
+    // between the replaced event and the inserted one, returning the replaced event.
+    pub fn insert_event_and_reindex(&mut self, mut event: Event) -> Result<Option<Event>, String> {
+        let event_uid = event.uid.uid.to_string();
+
+        // Take the replaced event out so that it can be diffed against while updating the indexes.
+        let existing_event = self.events.remove(&event_uid);
+
+        if self.indexes_active {
+            event.rebuild_indexes()?;
+
+            CalendarIndexUpdater::new(&event_uid, self).update_event_indexes(existing_event.as_deref(), Some(&event))?;
+        }
+
+        self.events.insert(event_uid, Box::new(event));
+
+        Ok(existing_event.map(|existing_event| *existing_event))
+    }
+
+    // Remove the event, also removing it from the Calendar indexes (if active), returning the
+    // removed event.
+    pub fn remove_event_and_reindex(&mut self, event_uid: &String) -> Result<Option<Box<Event>>, String> {
+        let Some(existing_event) = self.remove_event(event_uid) else {
+            return Ok(None);
+        };
+
+        if self.indexes_active {
+            CalendarIndexUpdater::new(event_uid, self).update_event_indexes(Some(&existing_event), None)?;
+        }
+
+        Ok(Some(existing_event))
+    }
+
+    // Set the timestamp after which the event expires (and is removed), returning false if the
+    // event does not exist.
+    pub fn set_event_expiration(&mut self, event_uid: &String, expires_at: i64) -> bool {
+        if !self.events.contains_key(event_uid) {
+            return false;
+        }
+
+        self.event_expirations.insert(event_uid.to_owned(), expires_at);
+
+        true
+    }
+
+    pub fn remove_event_expiration(&mut self, event_uid: &String) -> Option<i64> {
+        self.event_expirations.remove(event_uid)
+    }
+
+    // The UIDs of the events which have expired as of the provided timestamp.
+    pub fn get_expired_event_uids(&self, timestamp: i64) -> Vec<String> {
+        self.event_expirations
+            .iter()
+            .filter(|(_, expires_at)| **expires_at <= timestamp)
+            .map(|(event_uid, _)| event_uid.to_owned())
+            .collect()
+    }
+
+    // Returns up to the provided count of events (ordered by UID) following the provided event UID
+    // (or from the first event), along with the UID of the last event returned if there are more
+    // to follow.
+    //
+    // As the events are ordered by UID, each event present for the entirety of a full scan is
+    // returned exactly once, regardless of other events being inserted or removed in between.
+    pub fn scan_events(&self, after_event_uid: Option<&String>, count: usize) -> (Vec<&Event>, Option<String>) {
+        let lower_bound = match after_event_uid {
+            Some(after_event_uid) => Bound::Excluded(after_event_uid.to_owned()),
+            None => Bound::Unbounded,
+        };
+
+        let mut events_iter = self.events.range((lower_bound, Bound::Unbounded)).peekable();
+
+        let mut events = Vec::new();
+
+        while events.len() < count {
+            let Some((_, event)) = events_iter.next() else {
+                break;
+            };
+
+            events.push(event.as_ref());
+        }
+
+        let next_event_uid =
+            if events_iter.peek().is_some() {
+                events.last().map(|event| event.uid.uid.to_string())
+            } else {
+                None
+            };
+
+        (events, next_event_uid)
+    }
+
+    // Returns the events (ordered by UID) related to the provided UID with the provided RELTYPE
+    // (e.g. the children of a parent event via `RELATED-TO;RELTYPE=PARENT:PARENT_UID`), as per the
+    // RELATED-TO index.
+    //
+    // Consistent with event queries, events only related to the UID by some of their occurrence
+    // overrides are not returned.
+    pub fn get_related_events(&self, reltype: &str, related_uid: &str) -> Vec<&Event> {
+        let reltype_uid_pair = KeyValuePair::new(reltype.to_ascii_uppercase(), related_uid.to_owned());
+
+        let Some(indexed_term) = self.indexed_related_to.get_term(&reltype_uid_pair) else {
+            return Vec::new();
+        };
+
+        let mut event_uids: Vec<&InternedString> =
+            indexed_term
+                .events
+                .iter()
+                .filter(|(_event_uid, indexed_conclusion)| matches!(indexed_conclusion, IndexedConclusion::Include(_)))
+                .map(|(event_uid, _indexed_conclusion)| event_uid)
+                .collect();
+
+        event_uids.sort();
+
+        event_uids
+            .into_iter()
+            .filter_map(|event_uid| self.events.get(event_uid.as_str()))
+            .map(|event| event.as_ref())
+            .collect()
+    }
+
+    // Clear the indexes, with the (re)built indexes normalising their terms as currently declared.
     fn clear_indexes(&mut self) {
-        self.indexed_categories = InvertedCalendarIndex::new();
+        let term_normalization = self.get_term_normalization();
+
+        self.indexed_categories = InvertedCalendarIndex::new_with_normalization(term_normalization);
+        self.indexed_location_type = InvertedCalendarIndex::new_with_normalization(term_normalization);
         self.indexed_related_to = InvertedCalendarIndex::new();
         self.indexed_geo = GeoSpatialCalendarIndex::new();
-        self.indexed_class = InvertedCalendarIndex::new();
+        self.indexed_class = InvertedCalendarIndex::new_with_normalization(term_normalization);
+        self.indexed_location = InvertedCalendarIndex::new_with_normalization(term_normalization);
+        self.indexed_x_properties = InvertedCalendarIndex::new();
+        self.indexed_time_buckets = TimeBucketCalendarIndex::new();
     }
 
     // Disable and clear the indexes on the Calendar.
@@ -109,7 +645,7 @@ impl Calendar {
     }
 
     // Rebuild the Calendar indexes from scratch, very helpful to perform at the tail
-    // end of a bulk data import.
+    // end of a bulk data import, or after changing how the indexed terms are normalised.
     pub fn rebuild_indexes(&mut self) -> Result<bool, String> {
         // Clear the indexes first to ensure full clean rebuild.
         self.clear_indexes();
@@ -122,9 +658,14 @@ impl Calendar {
         let indexed_location_type = &mut self.indexed_location_type;
         let indexed_geo = &mut self.indexed_geo;
         let indexed_class = &mut self.indexed_class;
+        let indexed_location = &mut self.indexed_location;
+        let indexed_x_properties = &mut self.indexed_x_properties;
+        let indexed_time_buckets = &mut self.indexed_time_buckets;
+
+        let indexed_property_names = &self.indexed_property_names;
 
         for event in self.events.values_mut() {
-            let event_uid = event.uid.uid.to_string();
+            let event_uid = InternedString::from(event.uid.uid.to_string());
 
             event.rebuild_indexes()?;
 
@@ -157,20 +698,101 @@ impl Calendar {
                     indexed_class.insert(event_uid.to_owned(), indexed_term.to_owned(), indexed_conclusion)?;
                 }
             }
+
+            if let Some(indexed_event_location) = &event.indexed_location {
+                for (indexed_term, indexed_conclusion) in &indexed_event_location.terms {
+                    indexed_location.insert(event_uid.to_owned(), indexed_term.to_owned(), indexed_conclusion)?;
+                }
+            }
+
+            if let (Some(indexed_property_names), Some(indexed_event_x_properties)) = (indexed_property_names, &event.indexed_x_properties) {
+                for (indexed_term, indexed_conclusion) in &indexed_event_x_properties.terms {
+                    if indexed_property_names.contains(&indexed_term.key) {
+                        indexed_x_properties.insert(event_uid.to_owned(), indexed_term.to_owned(), indexed_conclusion)?;
+                    }
+                }
+            }
+
+            indexed_time_buckets.insert(event)?;
+        }
+
+        Ok(true)
+    }
+
+    // Rebuild only the Calendar X-property index from the (already indexed) event X-properties,
+    // so that it reflects the X-properties currently declared to be indexed.
+    fn rebuild_indexed_x_properties(&mut self) -> Result<bool, String> {
+        self.indexed_x_properties = InvertedCalendarIndex::new();
+
+        let Some(indexed_property_names) = self.indexed_property_names.as_ref() else {
+            return Ok(true);
+        };
+
+        for event in self.events.values() {
+            let event_uid = InternedString::from(event.uid.uid.to_string());
+
+            if let Some(indexed_event_x_properties) = &event.indexed_x_properties {
+                for (indexed_term, indexed_conclusion) in &indexed_event_x_properties.terms {
+                    if indexed_property_names.contains(&indexed_term.key) {
+                        self.indexed_x_properties.insert(event_uid.to_owned(), indexed_term.to_owned(), indexed_conclusion)?;
+                    }
+                }
+            }
         }
 
         Ok(true)
     }
 
+    // Rebuild the Calendar indexes from scratch (as with `rebuild_indexes`), recording the provided
+    // UTC timestamp as the time they were last rebuilt, and returning statistics describing the
+    // rebuild (e.g. for reporting progress).
+    pub fn rebuild_indexes_at(&mut self, timestamp: i64) -> Result<IndexRebuildStats, String> {
+        let started_at = Instant::now();
+
+        self.rebuild_indexes()?;
+
+        self.indexes_rebuilt_at = Some(timestamp);
+
+        Ok(
+            IndexRebuildStats {
+                duration: started_at.elapsed(),
+                event_count: self.events.len(),
+                term_count: self.get_indexed_term_count(),
+            }
+        )
+    }
+
     // Iterates through associated events and finds those that have their last occurrence between
     // the from and until timestamps.
+    //
+    // When the indexes are active, the events ending between them are looked up from the time
+    // bucket index instead, so that only the events without a known end (i.e. with an excessive
+    // number of occurrences) are expanded.
     pub fn prune_events(&mut self, from: i64, until: i64) -> Result<HashMap<String, Box<Event>>, String> {
         let mut pruned_events = HashMap::new();
 
-        let event_uids_to_prune = self.events.iter()
-            .filter(|(_, event)| event.is_last_occurrence_between(from, until).unwrap_or(false))
-            .map(|(uid, _)| uid.to_string())
-            .collect::<Vec<String>>();
+        let event_uids_to_prune =
+            if self.indexes_active {
+                let mut event_uids_to_prune = self.indexed_time_buckets.get_event_uids_ending_between(from, until);
+
+                event_uids_to_prune.extend(
+                    self.indexed_time_buckets
+                        .get_event_uids_without_ends()
+                        .into_iter()
+                        .filter(|uid| {
+                            self.events.get(uid).is_some_and(|event| {
+                                !event.is_open_ended() && event.is_last_occurrence_between(from, until).unwrap_or(false)
+                            })
+                        })
+                );
+
+                event_uids_to_prune.into_iter().collect::<Vec<String>>()
+            } else {
+                self.events.iter()
+                    .filter(|(_, event)| event.is_last_occurrence_between(from, until).unwrap_or(false))
+                    .map(|(uid, _)| uid.to_string())
+                    .collect::<Vec<String>>()
+            };
 
         for uid in event_uids_to_prune.iter() {
             if let Some(pruned_event) = self.remove_event(uid) {
@@ -180,6 +802,259 @@ impl Calendar {
 
         Ok(pruned_events)
     }
+
+    // Approximate the memory footprint of the Calendar by summing the sizes of the stored structs
+    // with the length of their rendered iCalendar content (standing in for the heap allocated
+    // property values) and the index terms. This is intentionally cheap rather than exact.
+    //
+    // As Redis does for it's own data types (e.g. `MEMORY USAGE key SAMPLES count`), the footprint
+    // of each collection (events, index terms, and time buckets) is extrapolated from the first
+    // `sample_size` elements, so that it is not proportional to the size of the Calendar. A sample
+    // size of 0 sums every element instead.
+    pub fn approximate_memory_usage(&self, sample_size: usize) -> usize {
+        let mut memory_usage = std::mem::size_of::<Calendar>() + self.uid.uid.to_string().len();
+
+        memory_usage += Self::approximate_sampled_memory_usage(self.event_expirations.keys(), sample_size, |event_uid| std::mem::size_of::<i64>() + event_uid.len());
+
+        memory_usage += Self::approximate_sampled_memory_usage(self.events.iter(), sample_size, |(event_uid, event)| {
+            let mut event_memory_usage = std::mem::size_of::<Event>() + event_uid.len();
+
+            event_memory_usage += event.to_rendered_content_lines().iter().map(String::len).sum::<usize>();
+
+            for event_occurrence_override in event.overrides.values() {
+                event_memory_usage += std::mem::size_of::<EventOccurrenceOverride>();
+                event_memory_usage += event_occurrence_override.to_rendered_content_lines().iter().map(String::len).sum::<usize>();
+            }
+
+            event_memory_usage += Self::approximate_inverted_event_index_memory_usage(event.indexed_categories.as_ref(), Self::approximate_interned_string_memory_usage);
+            event_memory_usage += Self::approximate_inverted_event_index_memory_usage(event.indexed_location_type.as_ref(), Self::approximate_interned_string_memory_usage);
+            event_memory_usage += Self::approximate_inverted_event_index_memory_usage(event.indexed_related_to.as_ref(), |term| term.to_string().len());
+            event_memory_usage += Self::approximate_inverted_event_index_memory_usage(event.indexed_geo.as_ref(), |_term| std::mem::size_of::<GeoPoint>());
+            event_memory_usage += Self::approximate_inverted_event_index_memory_usage(event.indexed_class.as_ref(), Self::approximate_interned_string_memory_usage);
+            event_memory_usage += Self::approximate_inverted_event_index_memory_usage(event.indexed_location.as_ref(), Self::approximate_interned_string_memory_usage);
+            event_memory_usage += Self::approximate_inverted_event_index_memory_usage(event.indexed_x_properties.as_ref(), |term| term.to_string().len());
+
+            event_memory_usage
+        });
+
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_categories.terms.iter(), sample_size, |(term, indexed_term)| Self::approximate_interned_string_memory_usage(term) + Self::approximate_indexed_term_memory_usage(indexed_term));
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_location_type.terms.iter(), sample_size, |(term, indexed_term)| Self::approximate_interned_string_memory_usage(term) + Self::approximate_indexed_term_memory_usage(indexed_term));
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_related_to.terms.iter(), sample_size, |(term, indexed_term)| term.to_string().len() + Self::approximate_indexed_term_memory_usage(indexed_term));
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_class.terms.iter(), sample_size, |(term, indexed_term)| Self::approximate_interned_string_memory_usage(term) + Self::approximate_indexed_term_memory_usage(indexed_term));
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_location.terms.iter(), sample_size, |(term, indexed_term)| Self::approximate_interned_string_memory_usage(term) + Self::approximate_indexed_term_memory_usage(indexed_term));
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_x_properties.terms.iter(), sample_size, |(term, indexed_term)| term.to_string().len() + Self::approximate_indexed_term_memory_usage(indexed_term));
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_geo.iter(), sample_size, |(_geo_point, indexed_term)| std::mem::size_of::<GeoPoint>() + Self::approximate_indexed_term_memory_usage(indexed_term));
+
+        let time_bucket_memory_usage = |event_uids: &HashSet<String>| {
+            std::mem::size_of::<i64>()
+                + event_uids.iter().map(|event_uid| std::mem::size_of::<String>() + event_uid.len()).sum::<usize>()
+        };
+
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_time_buckets.buckets.values(), sample_size, time_bucket_memory_usage);
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_time_buckets.open_ended.values(), sample_size, time_bucket_memory_usage);
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_time_buckets.ends.values(), sample_size, time_bucket_memory_usage);
+
+        memory_usage += Self::approximate_sampled_memory_usage(self.indexed_time_buckets.events.iter(), sample_size, |(event_uid, event_time_buckets)| {
+            std::mem::size_of::<String>()
+                + event_uid.len()
+                + std::mem::size_of_val(event_time_buckets)
+                + (event_time_buckets.buckets.len() * std::mem::size_of::<i64>())
+        });
+
+        memory_usage
+    }
+
+    // Sum the approximate memory usage of each element, extrapolated from the average of the first
+    // `sample_size` elements when there are more of them (unless the sample size is 0).
+    fn approximate_sampled_memory_usage<T>(elements: impl ExactSizeIterator<Item = T>, sample_size: usize, memory_usage: impl Fn(T) -> usize) -> usize {
+        let element_count = elements.len();
+
+        if sample_size == 0 || element_count <= sample_size {
+            return elements.map(memory_usage).sum();
+        }
+
+        let sampled_memory_usage = elements.take(sample_size).map(memory_usage).sum::<usize>();
+
+        sampled_memory_usage.saturating_mul(element_count) / sample_size
+    }
+
+    fn approximate_indexed_term_memory_usage(indexed_term: &InvertedCalendarIndexTerm) -> usize {
+        indexed_term
+            .events
+            .iter()
+            .map(|(event_uid, indexed_conclusion)| {
+                Self::approximate_interned_string_memory_usage(event_uid)
+                    + Self::approximate_indexed_conclusion_memory_usage(indexed_conclusion)
+            })
+            .sum()
+    }
+
+    // Interned strings are shared by every index map (and Calendar) referencing them, so only the
+    // reference held is attributed rather than the string itself.
+    fn approximate_interned_string_memory_usage(_interned_string: &InternedString) -> usize {
+        std::mem::size_of::<InternedString>()
+    }
+
+    fn approximate_inverted_event_index_memory_usage<K>(inverted_event_index: Option<&InvertedEventIndex<K>>, term_memory_usage: impl Fn(&K) -> usize) -> usize
+    where
+        K: std::hash::Hash + Clone + std::cmp::Eq,
+    {
+        inverted_event_index.map_or(0, |inverted_event_index| {
+            inverted_event_index
+                .terms
+                .iter()
+                .map(|(term, indexed_conclusion)| {
+                    term_memory_usage(term) + Self::approximate_indexed_conclusion_memory_usage(indexed_conclusion)
+                })
+                .sum()
+        })
+    }
+
+    fn approximate_indexed_conclusion_memory_usage(indexed_conclusion: &IndexedConclusion) -> usize {
+        let exceptions_count = match indexed_conclusion {
+            IndexedConclusion::Include(exceptions) | IndexedConclusion::Exclude(exceptions) => {
+                exceptions.as_ref().map_or(0, |exceptions| exceptions.len())
+            },
+        };
+
+        std::mem::size_of::<IndexedConclusion>() + (exceptions_count * std::mem::size_of::<i64>())
+    }
+
+    // Render the Calendar, it's events and their overrides as a sequence of components (each being
+    // the content lines in canonical order) for digesting. Indexes are omitted as they are
+    // derived entirely from the events and overrides, as is the Calendar UID as it reflects the key
+    // the Calendar is stored on (e.g. the destination key of a COPY or RESTORE).
+    pub fn to_digest_components(&self) -> Vec<Vec<String>> {
+        let calendar_uid_content_line = self.uid.render_ical();
+
+        let mut digest_components = vec![
+            self.to_rendered_content_lines()
+                .into_iter()
+                .filter(|content_line| content_line != &calendar_uid_content_line)
+                .collect(),
+        ];
+
+        for event in self.events.values() {
+            digest_components.push(event.to_rendered_content_lines());
+
+            for (timestamp, event_occurrence_override) in &event.overrides {
+                let mut override_content_lines = vec![
+                    event.uid.render_ical(),
+                    RecurrenceIDProperty::new_from_utc_timestamp(timestamp).render_ical(),
+                ];
+
+                override_content_lines.extend(event_occurrence_override.to_rendered_content_lines());
+
+                digest_components.push(override_content_lines);
+            }
+        }
+
+        digest_components
+    }
+
+    pub fn get_stats(&self) -> CalendarStats {
+        let override_count = self.events.values().map(|event| event.overrides.len()).sum();
+
+        let dtstart_timestamps =
+            self.events
+                .values()
+                .filter_map(|event| event.schedule_properties.get_dtstart_timestamp());
+
+        let (earliest_dtstart, latest_dtstart) =
+            dtstart_timestamps.fold((None, None), |(earliest, latest): (Option<i64>, Option<i64>), dtstart_timestamp| {
+                (
+                    Some(earliest.map_or(dtstart_timestamp, |earliest| earliest.min(dtstart_timestamp))),
+                    Some(latest.map_or(dtstart_timestamp, |latest| latest.max(dtstart_timestamp))),
+                )
+            });
+
+        CalendarStats {
+            event_count: self.events.len(),
+            override_count,
+            indexes_active: self.indexes_active,
+            indexed_categories_term_count: self.indexed_categories.terms.len(),
+            indexed_location_type_term_count: self.indexed_location_type.terms.len(),
+            indexed_related_to_term_count: self.indexed_related_to.terms.len(),
+            indexed_geo_term_count: self.indexed_geo.len(),
+            indexed_class_term_count: self.indexed_class.terms.len(),
+            indexed_location_term_count: self.indexed_location.terms.len(),
+            approximate_memory_usage: self.approximate_memory_usage(DEFAULT_MEMORY_USAGE_SAMPLE_SIZE),
+            earliest_dtstart,
+            latest_dtstart,
+        }
+    }
+
+    // Returns each distinct term (ordered) within the named index (CATEGORIES, LOCATION-TYPE,
+    // LOCATION, CLASS, or RELATED-TO with each term rendered as "RELTYPE;UID") alongside the number of
+    // events it is indexed against, optionally filtered to the terms starting with the prefix.
+    // The total number of distinct terms indexed across all of the Calendar indexes (excluding the
+    // time buckets).
+    pub fn get_indexed_term_count(&self) -> usize {
+        self.indexed_categories.terms.len()
+            + self.indexed_location_type.terms.len()
+            + self.indexed_related_to.terms.len()
+            + self.indexed_geo.len()
+            + self.indexed_class.terms.len()
+            + self.indexed_location.terms.len()
+            + self.indexed_x_properties.terms.len()
+    }
+
+    pub fn get_indexed_term_counts(&self, index: &str, prefix: Option<&str>) -> Result<Vec<(String, usize)>, String> {
+        fn collect_term_counts<K>(
+            inverted_calendar_index: &InvertedCalendarIndex<K>,
+            prefix: Option<&str>,
+            render_term: impl Fn(&K) -> String,
+        ) -> Vec<(String, usize)>
+        where
+            K: std::hash::Hash + Clone + Ord,
+        {
+            inverted_calendar_index
+                .terms
+                .iter()
+                .map(|(term, indexed_term)| (render_term(term), indexed_term.events.len()))
+                .filter(|(term, _event_count)| prefix.is_none_or(|prefix| term.starts_with(prefix)))
+                .collect::<BTreeMap<String, usize>>()
+                .into_iter()
+                .collect()
+        }
+
+        match index.to_ascii_uppercase().as_str() {
+            "CATEGORIES" => Ok(collect_term_counts(&self.indexed_categories, prefix, |category| category.to_string())),
+            "LOCATION-TYPE" => Ok(collect_term_counts(&self.indexed_location_type, prefix, |location_type| location_type.to_string())),
+            "LOCATION" => Ok(collect_term_counts(&self.indexed_location, prefix, |location| location.to_string())),
+            "CLASS" => Ok(collect_term_counts(&self.indexed_class, prefix, |class| class.to_string())),
+            "RELATED-TO" => Ok(collect_term_counts(&self.indexed_related_to, prefix, |reltype_uids| format!("{};{}", reltype_uids.key, reltype_uids.value))),
+
+            index => Err(format!("Unknown index: {index} (expected CATEGORIES, LOCATION-TYPE, LOCATION, CLASS, or RELATED-TO)")),
+        }
+    }
+}
+
+/// Calendar-level statistics, useful for monitoring calendars without having to scan all of
+/// their events.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct CalendarStats {
+    pub event_count: usize,
+    pub override_count: usize,
+    pub indexes_active: bool,
+    pub indexed_categories_term_count: usize,
+    pub indexed_location_type_term_count: usize,
+    pub indexed_related_to_term_count: usize,
+    pub indexed_geo_term_count: usize,
+    pub indexed_class_term_count: usize,
+    pub indexed_location_term_count: usize,
+    pub approximate_memory_usage: usize,
+    pub earliest_dtstart: Option<i64>,
+    pub latest_dtstart: Option<i64>,
+}
+
+/// Statistics describing a full rebuild of the Calendar indexes, useful for tracking reindexing
+/// progress.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct IndexRebuildStats {
+    pub duration: Duration,
+    pub event_count: usize,
+    pub term_count: usize,
 }
 
 impl ICalendarComponent for Calendar {
@@ -188,6 +1063,56 @@ impl ICalendarComponent for Calendar {
 
         serializable_properties.insert(self.uid.to_content_line_with_context(context));
 
+        if let Some(default_tzid) = self.default_tzid.as_ref() {
+            serializable_properties.insert(default_tzid.to_content_line_with_context(context));
+        }
+
+        if let Some(default_dst_policy) = self.default_dst_policy.as_ref() {
+            serializable_properties.insert(default_dst_policy.to_content_line_with_context(context));
+        }
+
+        if let Some(term_normalization) = self.term_normalization.as_ref() {
+            serializable_properties.insert(term_normalization.to_content_line_with_context(context));
+        }
+
+        if let Some(indexed_property_names) = self.indexed_property_names.as_ref() {
+            serializable_properties.insert(indexed_property_names.to_content_line_with_context(context));
+        }
+
+        if let Some(required_property_names) = self.required_property_names.as_ref() {
+            serializable_properties.insert(required_property_names.to_content_line_with_context(context));
+        }
+
+        if let Some(allowed_property_names) = self.allowed_property_names.as_ref() {
+            serializable_properties.insert(allowed_property_names.to_content_line_with_context(context));
+        }
+
+        if let Some(prune_older_than) = self.prune_older_than.as_ref() {
+            serializable_properties.insert(prune_older_than.to_content_line_with_context(context));
+        }
+
+        if let Some(names) = self.names.as_ref() {
+            for name in names {
+                serializable_properties.insert(name.to_content_line_with_context(context));
+            }
+        }
+
+        if let Some(color) = self.color.as_ref() {
+            serializable_properties.insert(color.to_content_line_with_context(context));
+        }
+
+        if let Some(refresh_interval) = self.refresh_interval.as_ref() {
+            serializable_properties.insert(refresh_interval.to_content_line_with_context(context));
+        }
+
+        if let Some(source) = self.source.as_ref() {
+            serializable_properties.insert(source.to_content_line_with_context(context));
+        }
+
+        for passive_property in &self.passive_properties {
+            serializable_properties.insert(passive_property.to_content_line_with_context(context));
+        }
+
         serializable_properties
     }
 }
@@ -208,7 +1133,7 @@ impl<'a> CalendarIndexUpdater<'a> {
 
     pub fn update_indexed_categories(
         &mut self,
-        updated_event_categories_diff: &UpdatedHashMapMembers<String, IndexedConclusion>,
+        updated_event_categories_diff: &UpdatedHashMapMembers<InternedString, IndexedConclusion>,
     ) -> Result<bool, String> {
         let indexed_categories = &mut self.calendar.indexed_categories;
 
@@ -240,7 +1165,7 @@ impl<'a> CalendarIndexUpdater<'a> {
 
     pub fn update_indexed_location_type(
         &mut self,
-        updated_event_location_type_diff: &UpdatedHashMapMembers<String, IndexedConclusion>,
+        updated_event_location_type_diff: &UpdatedHashMapMembers<InternedString, IndexedConclusion>,
     ) -> Result<bool, String> {
         let indexed_location_type = &mut self.calendar.indexed_location_type;
 
@@ -303,24 +1228,61 @@ impl<'a> CalendarIndexUpdater<'a> {
         Ok(true)
     }
 
-    pub fn update_indexed_geo(
+    // Only the X-properties declared to be indexed by the Calendar are added, whereas every removed
+    // X-property is removed regardless (in case they were indexed prior to being undeclared).
+    pub fn update_indexed_x_properties(
         &mut self,
-        updated_event_geo_diff: &UpdatedHashMapMembers<GeoPoint, IndexedConclusion>,
+        updated_event_x_properties_diff: &UpdatedHashMapMembers<KeyValuePair, IndexedConclusion>,
     ) -> Result<bool, String> {
-        let indexed_geo = &mut self.calendar.indexed_geo;
-
-        for (removed_long_lat_coord, _) in updated_event_geo_diff.removed.iter() {
-            indexed_geo.remove(self.event_uid.clone(), removed_long_lat_coord)?;
+        for (removed_x_property, _) in updated_event_x_properties_diff.removed.iter() {
+            self.calendar.indexed_x_properties.remove(self.event_uid.clone(), removed_x_property.clone())?;
         }
 
-        for (updated_long_lat_coord, updated_indexed_conclusion) in
-            updated_event_geo_diff.updated.iter()
+        for (updated_x_property, updated_indexed_conclusion) in
+            updated_event_x_properties_diff.updated.iter()
         {
-            indexed_geo.insert(
-                self.event_uid.clone(),
-                updated_long_lat_coord,
-                updated_indexed_conclusion,
-            )?;
+            if self.calendar.is_x_property_indexed(&updated_x_property.key) {
+                self.calendar.indexed_x_properties.insert(
+                    self.event_uid.clone(),
+                    updated_x_property.clone(),
+                    updated_indexed_conclusion,
+                )?;
+            }
+        }
+
+        for (added_x_property, added_indexed_conclusion) in
+            updated_event_x_properties_diff.added.iter()
+        {
+            if self.calendar.is_x_property_indexed(&added_x_property.key) {
+                self.calendar.indexed_x_properties.insert(
+                    self.event_uid.clone(),
+                    added_x_property.clone(),
+                    added_indexed_conclusion,
+                )?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    pub fn update_indexed_geo(
+        &mut self,
+        updated_event_geo_diff: &UpdatedHashMapMembers<GeoPoint, IndexedConclusion>,
+    ) -> Result<bool, String> {
+        let indexed_geo = &mut self.calendar.indexed_geo;
+
+        for (removed_long_lat_coord, _) in updated_event_geo_diff.removed.iter() {
+            indexed_geo.remove(self.event_uid.clone(), removed_long_lat_coord)?;
+        }
+
+        for (updated_long_lat_coord, updated_indexed_conclusion) in
+            updated_event_geo_diff.updated.iter()
+        {
+            indexed_geo.insert(
+                self.event_uid.clone(),
+                updated_long_lat_coord,
+                updated_indexed_conclusion,
+            )?;
         }
 
         for (added_long_lat_coord, added_indexed_conclusion) in updated_event_geo_diff.added.iter()
@@ -338,7 +1300,7 @@ impl<'a> CalendarIndexUpdater<'a> {
 
     pub fn update_indexed_class(
         &mut self,
-        updated_event_class_diff: &UpdatedHashMapMembers<String, IndexedConclusion>,
+        updated_event_class_diff: &UpdatedHashMapMembers<InternedString, IndexedConclusion>,
     ) -> Result<bool, String> {
         let indexed_class = &mut self.calendar.indexed_class;
 
@@ -364,6 +1326,115 @@ impl<'a> CalendarIndexUpdater<'a> {
 
         Ok(true)
     }
+
+    pub fn update_indexed_location(
+        &mut self,
+        updated_event_location_diff: &UpdatedHashMapMembers<InternedString, IndexedConclusion>,
+    ) -> Result<bool, String> {
+        let indexed_location = &mut self.calendar.indexed_location;
+
+        for (removed_location, _) in updated_event_location_diff.removed.iter() {
+            indexed_location.remove(self.event_uid.clone(), removed_location.clone())?;
+        }
+
+        for (updated_location, updated_indexed_conclusion) in updated_event_location_diff.updated.iter() {
+            indexed_location.insert(
+                self.event_uid.clone(),
+                updated_location.clone(),
+                updated_indexed_conclusion,
+            )?;
+        }
+
+        for (added_location, added_indexed_conclusion) in updated_event_location_diff.added.iter() {
+            indexed_location.insert(
+                self.event_uid.clone(),
+                added_location.clone(),
+                added_indexed_conclusion,
+            )?;
+        }
+
+        Ok(true)
+    }
+
+    // Update every Calendar index with the difference between the existing and updated event
+    // indexed terms, where no existing event represents it being added and no updated event
+    // represents it being removed.
+    pub fn update_event_indexes(&mut self, existing_event: Option<&Event>, updated_event: Option<&Event>) -> Result<bool, String> {
+        self.update_indexed_categories(
+            &InvertedEventIndex::diff_indexed_terms(
+                existing_event.and_then(|event| event.indexed_categories.as_ref()),
+                updated_event.and_then(|event| event.indexed_categories.as_ref()),
+            )
+        )?;
+
+        self.update_indexed_location_type(
+            &InvertedEventIndex::diff_indexed_terms(
+                existing_event.and_then(|event| event.indexed_location_type.as_ref()),
+                updated_event.and_then(|event| event.indexed_location_type.as_ref()),
+            )
+        )?;
+
+        self.update_indexed_related_to(
+            &InvertedEventIndex::diff_indexed_terms(
+                existing_event.and_then(|event| event.indexed_related_to.as_ref()),
+                updated_event.and_then(|event| event.indexed_related_to.as_ref()),
+            )
+        )?;
+
+        self.update_indexed_geo(
+            &InvertedEventIndex::diff_indexed_terms(
+                existing_event.and_then(|event| event.indexed_geo.as_ref()),
+                updated_event.and_then(|event| event.indexed_geo.as_ref()),
+            )
+        )?;
+
+        self.update_indexed_class(
+            &InvertedEventIndex::diff_indexed_terms(
+                existing_event.and_then(|event| event.indexed_class.as_ref()),
+                updated_event.and_then(|event| event.indexed_class.as_ref()),
+            )
+        )?;
+
+        self.update_indexed_location(
+            &InvertedEventIndex::diff_indexed_terms(
+                existing_event.and_then(|event| event.indexed_location.as_ref()),
+                updated_event.and_then(|event| event.indexed_location.as_ref()),
+            )
+        )?;
+
+        self.update_indexed_x_properties(
+            &InvertedEventIndex::diff_indexed_terms(
+                existing_event.and_then(|event| event.indexed_x_properties.as_ref()),
+                updated_event.and_then(|event| event.indexed_x_properties.as_ref()),
+            )
+        )?;
+
+        self.update_indexed_time_buckets(updated_event)?;
+
+        Ok(true)
+    }
+
+    // Unlike the other indexes, the time buckets are derived from the event schedule and
+    // overrides as a whole, so we just replace the existing entry for the event (or remove it if
+    // the event has been removed).
+    pub fn update_indexed_time_buckets(
+        &mut self,
+        updated_event: Option<&Event>,
+    ) -> Result<bool, String> {
+        let indexed_time_buckets = &mut self.calendar.indexed_time_buckets;
+
+        match updated_event {
+            Some(updated_event) => {
+                indexed_time_buckets.insert(updated_event)
+            }
+
+            None => {
+                indexed_time_buckets.remove(&self.event_uid);
+
+                Ok(true)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -372,12 +1443,14 @@ mod tests {
 
     use std::str::FromStr;
     use redical_ical::values::date_time::DateTime;
+    use redical_ical::values::tzid::Tzid;
 
     fn build_and_associate_event(calendar: &mut Calendar, uid: &str, rrule_ical: &str) -> Event {
         let mut event = Event::new(uid.to_string());
         let rrule_set = rrule_ical.parse().unwrap();
 
         event.schedule_properties.parsed_rrule_set = Some(rrule_set);
+        event.rebuild_indexes().unwrap();
 
         calendar.insert_event(event.clone());
 
@@ -389,62 +1462,1192 @@ mod tests {
         let from  = DateTime::from_str("20250101T090000Z").unwrap().get_utc_timestamp(None);
         let until = DateTime::from_str("20250102T090000Z").unwrap().get_utc_timestamp(None);
 
+        // Pruning both with the indexes (looking up the events ending in the range), and without
+        // them (expanding every event).
+        for is_indexed in [true, false] {
+            let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+            // Recurring event that does not terminate
+            let event_one = build_and_associate_event(
+                &mut calendar,
+                "EVENT_ONE",
+                "DTSTART:20241231T163000Z\nRRULE:FREQ=DAILY",
+            );
+
+            // Recurring event that terminates after the prune range
+            let event_two = build_and_associate_event(
+                &mut calendar,
+                "EVENT_TWO",
+                "DTSTART:20241231T163000Z\nRRULE:FREQ=DAILY;COUNT=10",
+            );
+
+            // Recurring event that terminates inside prune range
+            let event_three = build_and_associate_event(
+                &mut calendar,
+                "EVENT_THREE",
+                "DTSTART:20241231T163000Z\nRRULE:FREQ=DAILY;COUNT=2",
+            );
+
+            // Single event that terminates before prune range
+            let event_four = build_and_associate_event(
+                &mut calendar,
+                "EVENT_FOUR",
+                "DTSTART:20241231T163000Z\nRDATE:20241231T163000Z",
+            );
+
+            // Single event that terminates inside prune range
+            let event_five = build_and_associate_event(
+                &mut calendar,
+                "EVENT_FIVE",
+                "DTSTART:20250101T123000Z\nRDATE:20250101T123000Z",
+            );
+
+            // Recurring event with too many occurrences to index the end of, that terminates
+            // inside prune range (20250101T094000Z)
+            let event_six = build_and_associate_event(
+                &mut calendar,
+                "EVENT_SIX",
+                "DTSTART:20241225T110000Z\nRRULE:FREQ=MINUTELY;COUNT=10001",
+            );
+
+            if is_indexed {
+                calendar.rebuild_indexes().unwrap();
+
+                assert_eq!(calendar.indexed_time_buckets.get_event_uids_without_ends(), HashSet::from([String::from("EVENT_ONE"), String::from("EVENT_SIX")]));
+            } else {
+                calendar.disable_indexes();
+            }
+
+            assert_eq!(calendar.events.len(), 6);
+
+            let pruned_events = calendar.prune_events(from, until).unwrap();
+
+            assert_eq!(calendar.events.len(), 3);
+            assert_eq!(pruned_events.len(), 3);
+
+            // Expected pruned events are removed
+            assert_eq!(
+                pruned_events,
+                HashMap::from([
+                    ("EVENT_THREE".to_string(), Box::new(event_three)),
+                    ("EVENT_FIVE".to_string(), Box::new(event_five)),
+                    ("EVENT_SIX".to_string(), Box::new(event_six)),
+                ]),
+            );
+
+            // Other events are still in the Calendar
+            assert_eq!(calendar.events.get("EVENT_ONE"), Some(&Box::new(event_one)));
+            assert_eq!(calendar.events.get("EVENT_TWO"), Some(&Box::new(event_two)));
+            assert_eq!(calendar.events.get("EVENT_FOUR"), Some(&Box::new(event_four)));
+
+            // Pruning an inverted range prunes nothing.
+            assert_eq!(calendar.prune_events(until, from).unwrap(), HashMap::new());
+        }
+    }
+
+    #[test]
+    fn test_default_timezone() {
+        let timestamp = |date_time: &str| DateTime::from_str(date_time).unwrap().get_utc_timestamp(None);
+
+        let build_event = |ical: &str| {
+            let mut event = Event::parse_ical("EVENT_UID", ical).unwrap();
+
+            event.validate().unwrap();
+
+            event
+        };
+
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        assert_eq!(calendar.get_default_tz(), None);
+
+        // Without a default timezone, floating date-times are interpreted as UTC.
+        let mut event = build_event("DTSTART:20210105T183000 RRULE:FREQ=DAILY;INTERVAL=1;COUNT=3");
+
+        calendar.localize_event(&mut event).unwrap();
+
+        assert_eq!(event.schedule_properties.dtstart.as_ref().unwrap().params.tzid, None);
+        assert_eq!(event.schedule_properties.get_dtstart_timestamp(), Some(timestamp("20210105T183000Z")));
+
+        calendar.insert(CalendarProperty::from_str("X-DEFAULT-TZID:America/New_York").unwrap()).unwrap();
+
+        assert_eq!(calendar.get_default_tz(), Some(Tz::America__New_York));
+
+        assert_eq!(
+            calendar.to_rendered_content_lines(),
+            vec![
+                String::from("UID:CALENDAR_UID"),
+                String::from("X-DEFAULT-TZID:America/New_York"),
+            ],
+        );
+
+        let mut event = build_event(
+            "DTSTART:20210105T183000 DTEND:20210106T003000Z RRULE:FREQ=DAILY;INTERVAL=1;COUNT=3 RDATE:20210110T183000,20210111T183000 EXDATE;TZID=Europe/Vilnius:20210106T183000",
+        );
+
+        calendar.localize_event(&mut event).unwrap();
+
+        let default_tzid = Some(Tzid(Tz::America__New_York));
+
+        // Floating date-times are interpreted in the default timezone.
+        assert_eq!(event.schedule_properties.dtstart.as_ref().unwrap().params.tzid, default_tzid);
+        assert_eq!(event.schedule_properties.get_dtstart_timestamp(), Some(timestamp("20210105T233000Z")));
+
+        assert!(event.schedule_properties.rdates.as_ref().unwrap().iter().all(|rdate| rdate.params.tzid == default_tzid));
+
+        // UTC date-times and date-times with an explicit TZID are unchanged.
+        assert_eq!(event.schedule_properties.dtend.as_ref().unwrap().params.tzid, None);
+        assert!(event.schedule_properties.exdates.as_ref().unwrap().iter().all(|exdate| exdate.params.tzid == Some(Tzid(Tz::Europe__Vilnius))));
+
+        // Floating date-times within a DST transition gap of the default timezone are rejected.
+        let mut event = build_event("DTSTART:20210314T023000");
+
+        assert!(calendar.localize_event(&mut event).is_err());
+    }
+
+    #[test]
+    fn test_default_dst_policy() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        let mut event = Event::parse_ical(
+            "EVENT_UID",
+            "DTSTART;TZID=Europe/London:20210327T013000 RRULE:FREQ=DAILY;INTERVAL=1;COUNT=3 LAST-MODIFIED:20210501T090000Z",
+        ).unwrap();
+
+        event.validate().unwrap();
+
+        calendar.insert_event_and_reindex(event).unwrap();
+
+        let query_dtstart_timestamps = |calendar: &Calendar, query: &str| -> Vec<i64> {
+            let mut query = crate::queries::event_instance_query::EventInstanceQuery::from_str(query).unwrap();
+
+            crate::queries::query::Query::execute(&mut query, calendar)
+                .unwrap()
+                .results
+                .iter()
+                .map(|query_result| query_result.result.dtstart.get_utc_timestamp())
+                .collect()
+        };
+
+        assert_eq!(calendar.get_default_dst_policy(), None);
+
+        // Without a default DST policy, the occurrence within the skipped hour is shifted forward.
+        assert_eq!(
+            query_dtstart_timestamps(&calendar, "X-LIMIT:10"),
+            vec![1616808600, 1616895000, 1616977800],
+        );
+
+        calendar.insert(CalendarProperty::from_str("X-DEFAULT-DST-POLICY:SKIP").unwrap()).unwrap();
+
+        assert_eq!(calendar.get_default_dst_policy(), Some(DstPolicy::Skip));
+
+        assert_eq!(
+            calendar.to_rendered_content_lines(),
+            vec![
+                String::from("UID:CALENDAR_UID"),
+                String::from("X-DEFAULT-DST-POLICY:SKIP"),
+            ],
+        );
+
+        assert_eq!(
+            query_dtstart_timestamps(&calendar, "X-LIMIT:10"),
+            vec![1616808600, 1616977800],
+        );
+
+        // The policy specified by the query takes precedence over the Calendar default.
+        assert_eq!(
+            query_dtstart_timestamps(&calendar, "X-DST-POLICY:SHIFT-FORWARD X-LIMIT:10"),
+            vec![1616808600, 1616895000, 1616977800],
+        );
+    }
+
+    #[test]
+    fn test_case_folded_term_matching() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        for (event_uid, event_ical) in [
+            ("EVENT_ONE", "DTSTART:20210105T183000Z CATEGORIES:SPORT CLASS:PUBLIC"),
+            ("EVENT_TWO", "DTSTART:20210106T183000Z CATEGORIES:Sport CLASS:Public"),
+            ("Event_Three", "DTSTART:20210107T183000Z CATEGORIES:Music CLASS:PRIVATE"),
+        ] {
+            let mut event = Event::parse_ical(event_uid, event_ical).unwrap();
+
+            event.validate().unwrap();
+
+            calendar.insert_event_and_reindex(event).unwrap();
+        }
+
+        let query_event_uids = |calendar: &Calendar, query: &str| -> Vec<String> {
+            let mut query = crate::queries::event_query::EventQuery::from_str(query).unwrap();
+
+            crate::queries::query::Query::execute(&mut query, calendar)
+                .unwrap()
+                .results
+                .iter()
+                .map(|query_result| query_result.result.uid.uid.to_string())
+                .collect()
+        };
+
+        assert!(!calendar.is_term_matching_case_insensitive());
+
+        // Without a term normalization policy, terms are matched case-sensitively.
+        assert_eq!(query_event_uids(&calendar, "X-CATEGORIES:sport"), Vec::<String>::new());
+        assert_eq!(query_event_uids(&calendar, "X-CATEGORIES:Sport"), vec![String::from("EVENT_TWO")]);
+        assert_eq!(query_event_uids(&calendar, "X-UID:EVENT_THREE"), Vec::<String>::new());
+
+        calendar.insert(CalendarProperty::from_str("X-TERM-NORMALIZATION:CASE-FOLD").unwrap()).unwrap();
+
+        assert!(calendar.is_term_matching_case_insensitive());
+
+        assert_eq!(
+            calendar.to_rendered_content_lines(),
+            vec![
+                String::from("UID:CALENDAR_UID"),
+                String::from("X-TERM-NORMALIZATION:CASE-FOLD"),
+            ],
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CATEGORIES:sport"),
+            vec![String::from("EVENT_ONE"), String::from("EVENT_TWO")],
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CLASS:public"),
+            vec![String::from("EVENT_ONE"), String::from("EVENT_TWO")],
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CLASS-NOT:public"),
+            vec![String::from("Event_Three")],
+        );
+
+        assert_eq!(query_event_uids(&calendar, "X-UID:EVENT_THREE"), vec![String::from("Event_Three")]);
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-UID-NOT:event_three"),
+            vec![String::from("EVENT_ONE"), String::from("EVENT_TWO")],
+        );
+
+        // Removing the only event indexed with a casing variant leaves the others matching.
+        calendar.remove_event_and_reindex(&String::from("EVENT_TWO")).unwrap();
+
+        assert_eq!(query_event_uids(&calendar, "X-CATEGORIES:SPORT"), vec![String::from("EVENT_ONE")]);
+    }
+
+    #[test]
+    fn test_term_normalization() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        for (event_uid, event_ical) in [
+            ("EVENT_ONE", "DTSTART:20210105T183000Z CATEGORIES:Café"),
+            ("EVENT_TWO", "DTSTART:20210106T183000Z CATEGORIES:CAFE"),
+            ("EVENT_THREE", "DTSTART:20210107T183000Z CATEGORIES:Cafe"),
+        ] {
+            let mut event = Event::parse_ical(event_uid, event_ical).unwrap();
+
+            event.validate().unwrap();
+
+            calendar.insert_event_and_reindex(event).unwrap();
+        }
+
+        let query_event_uids = |calendar: &Calendar, query: &str| -> Vec<String> {
+            let mut query = crate::queries::event_query::EventQuery::from_str(query).unwrap();
+
+            crate::queries::query::Query::execute(&mut query, calendar)
+                .unwrap()
+                .results
+                .iter()
+                .map(|query_result| query_result.result.uid.uid.to_string())
+                .collect()
+        };
+
+        assert_eq!(calendar.get_term_normalization(), TermNormalization::default());
+
+        // Without a term normalization policy, terms are matched exactly.
+        assert_eq!(query_event_uids(&calendar, "X-CATEGORIES:Cafe"), vec![String::from("EVENT_THREE")]);
+
+        calendar.insert(CalendarProperty::from_str("X-TERM-NORMALIZATION:STRIP-DIACRITICS").unwrap()).unwrap();
+
+        assert_eq!(calendar.get_term_normalization(), TermNormalization::new(false, false, true));
+        assert!(!calendar.is_term_matching_case_insensitive());
+
+        // The existing terms are only re-normalised once the indexes are rebuilt.
+        assert_eq!(query_event_uids(&calendar, "X-CATEGORIES:Cafe"), vec![String::from("EVENT_THREE")]);
+
+        calendar.rebuild_indexes().unwrap();
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CATEGORIES:Cafe"),
+            vec![String::from("EVENT_ONE"), String::from("EVENT_THREE")],
+        );
+
+        // Queried terms are normalised the same way as those indexed.
+        assert_eq!(
+            query_event_uids(&calendar, "X-CATEGORIES:Café"),
+            vec![String::from("EVENT_ONE"), String::from("EVENT_THREE")],
+        );
+
+        assert_eq!(query_event_uids(&calendar, "X-CATEGORIES:CAFÉ"), vec![String::from("EVENT_TWO")]);
+        assert_eq!(query_event_uids(&calendar, "X-CATEGORIES-NOT:Cafe"), vec![String::from("EVENT_TWO")]);
+
+        // Events subsequently inserted are normalised as they are indexed.
+        let mut event = Event::parse_ical("EVENT_FOUR", "DTSTART:20210108T183000Z CATEGORIES:Cafè").unwrap();
+
+        event.validate().unwrap();
+
+        calendar.insert_event_and_reindex(event).unwrap();
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CATEGORIES:Cafe"),
+            vec![String::from("EVENT_ONE"), String::from("EVENT_THREE"), String::from("EVENT_FOUR")],
+        );
+
+        // Case folding is applied without rebuilding the indexes.
+        calendar.insert(CalendarProperty::from_str("X-TERM-NORMALIZATION:CASE-FOLD,STRIP-DIACRITICS").unwrap()).unwrap();
+
+        assert!(calendar.is_term_matching_case_insensitive());
+
+        assert_eq!(
+            calendar.to_rendered_content_lines(),
+            vec![
+                String::from("UID:CALENDAR_UID"),
+                String::from("X-TERM-NORMALIZATION:CASE-FOLD,STRIP-DIACRITICS"),
+            ],
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CATEGORIES:cafe"),
+            vec![String::from("EVENT_ONE"), String::from("EVENT_TWO"), String::from("EVENT_THREE"), String::from("EVENT_FOUR")],
+        );
+    }
+
+    #[test]
+    fn test_wildcard_querying() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        for (event_uid, event_ical) in [
+            ("TENANT_123_ONE", "DTSTART:20210105T183000Z CATEGORIES:COURSE/MATHS/ALGEBRA"),
+            ("TENANT_123_TWO", "DTSTART:20210106T183000Z CATEGORIES:COURSE/HISTORY"),
+            ("TENANT_1234_ONE", "DTSTART:20210107T183000Z CATEGORIES:COURSES"),
+            ("Tenant_123_Three", "DTSTART:20210108T183000Z CATEGORIES:WORKSHOP/MATHS"),
+        ] {
+            let mut event = Event::parse_ical(event_uid, event_ical).unwrap();
+
+            event.validate().unwrap();
+
+            calendar.insert_event_and_reindex(event).unwrap();
+        }
+
+        let query_event_uids = |calendar: &Calendar, query: &str| -> Vec<String> {
+            let mut query = crate::queries::event_query::EventQuery::from_str(query).unwrap();
+
+            crate::queries::query::Query::execute(&mut query, calendar)
+                .unwrap()
+                .results
+                .iter()
+                .map(|query_result| query_result.result.uid.uid.to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            calendar.get_prefixed_event_uids("TENANT_123_"),
+            vec![&String::from("TENANT_123_ONE"), &String::from("TENANT_123_TWO")],
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-UID:TENANT_123_*"),
+            vec![String::from("TENANT_123_ONE"), String::from("TENANT_123_TWO")],
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-UID-NOT:TENANT_123_*"),
+            vec![String::from("TENANT_1234_ONE"), String::from("Tenant_123_Three")],
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CATEGORIES:COURSE/*"),
+            vec![String::from("TENANT_123_ONE"), String::from("TENANT_123_TWO")],
+        );
+
+        // Wildcard and exact values can be combined.
+        assert_eq!(
+            query_event_uids(&calendar, "X-CATEGORIES;OP=OR:COURSE/MATHS/*,WORKSHOP/MATHS"),
+            vec![String::from("TENANT_123_ONE"), String::from("Tenant_123_Three")],
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-UID:TENANT_123_* X-CATEGORIES:COURSE/MATHS/*"),
+            vec![String::from("TENANT_123_ONE")],
+        );
+
+        calendar.insert(CalendarProperty::from_str("X-TERM-NORMALIZATION:CASE-FOLD").unwrap()).unwrap();
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-UID:tenant_123_*"),
+            vec![String::from("TENANT_123_ONE"), String::from("TENANT_123_TWO"), String::from("Tenant_123_Three")],
+        );
+    }
+
+    #[test]
+    fn test_regex_querying() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        for (event_uid, event_ical) in [
+            ("TENANT_123_ONE", "DTSTART:20210105T183000Z CATEGORIES:COURSE/MATHS/2021 CLASS:X-TIER-1"),
+            ("TENANT_456_ONE", "DTSTART:20210106T183000Z CATEGORIES:COURSE/HISTORY/2022 CLASS:X-TIER-22"),
+            ("TENANT_ABC_ONE", "DTSTART:20210107T183000Z CATEGORIES:COURSE/ART/2021 CLASS:PUBLIC"),
+            ("Tenant_789_Two", "DTSTART:20210108T183000Z CATEGORIES:Course/Maths/2023 CLASS:X-Tier-3"),
+        ] {
+            let mut event = Event::parse_ical(event_uid, event_ical).unwrap();
+
+            event.validate().unwrap();
+
+            calendar.insert_event_and_reindex(event).unwrap();
+        }
+
+        let query_event_uids = |calendar: &Calendar, query: &str| -> Result<Vec<String>, String> {
+            let mut query = crate::queries::event_query::EventQuery::from_str(query)?;
+
+            Ok(
+                crate::queries::query::Query::execute(&mut query, calendar)?
+                    .results
+                    .iter()
+                    .map(|query_result| query_result.result.uid.uid.to_string())
+                    .collect()
+            )
+        };
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-UID;OP=REGEX:TENANT_[0-9]+_ONE"),
+            Ok(vec![String::from("TENANT_123_ONE"), String::from("TENANT_456_ONE")]),
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-UID-NOT;OP=REGEX:TENANT_[0-9]+_ONE"),
+            Ok(vec![String::from("TENANT_ABC_ONE"), String::from("Tenant_789_Two")]),
+        );
+
+        // Patterns match whole terms.
+        assert_eq!(
+            query_event_uids(&calendar, "X-UID;OP=REGEX:TENANT_[0-9]+"),
+            Ok(Vec::<String>::new()),
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CATEGORIES;OP=REGEX:COURSE/(MATHS|HISTORY)/20[0-9]{2}"),
+            Ok(vec![String::from("TENANT_123_ONE"), String::from("TENANT_456_ONE")]),
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CATEGORIES;OP=REGEX:COURSE/.*/2021"),
+            Ok(vec![String::from("TENANT_123_ONE"), String::from("TENANT_ABC_ONE")]),
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CLASS;OP=REGEX:X-TIER-[0-9]+"),
+            Ok(vec![String::from("TENANT_123_ONE"), String::from("TENANT_456_ONE")]),
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CLASS;OP=REGEX:X-TIER-[0-9] X-CATEGORIES;OP=REGEX:.*/MATHS/.*"),
+            Ok(vec![String::from("TENANT_123_ONE")]),
+        );
+
+        assert!(
+            query_event_uids(&calendar, "X-CATEGORIES;OP=REGEX:COURSE/(MATHS/.*")
+                .unwrap_err()
+                .starts_with("invalid REGEX pattern: COURSE/(MATHS/.*")
+        );
+
+        // Patterns are matched case-insensitively when the terms are case folded.
+        calendar.insert(CalendarProperty::from_str("X-TERM-NORMALIZATION:CASE-FOLD").unwrap()).unwrap();
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-UID;OP=REGEX:tenant_[0-9]+_.*"),
+            Ok(vec![String::from("TENANT_123_ONE"), String::from("TENANT_456_ONE"), String::from("Tenant_789_Two")]),
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CATEGORIES;OP=REGEX:course/maths/.*"),
+            Ok(vec![String::from("TENANT_123_ONE"), String::from("Tenant_789_Two")]),
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-CLASS;OP=REGEX:x-tier-[0-9]"),
+            Ok(vec![String::from("TENANT_123_ONE"), String::from("Tenant_789_Two")]),
+        );
+    }
+
+    #[test]
+    fn test_location_querying() {
         let mut calendar = Calendar::new("CALENDAR_UID".to_string());
 
-        // Recurring event that does not terminate
-        let event_one = build_and_associate_event(
-            &mut calendar,
+        let mut event_one = Event::parse_ical(
             "EVENT_ONE",
-            "DTSTART:20241231T163000Z\nRRULE:FREQ=DAILY",
+            "DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;COUNT=2;INTERVAL=1 LOCATION:Royal  Albert Hall",
+        ).unwrap();
+
+        event_one.validate().unwrap();
+
+        event_one.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210112T183000Z", "LOCATION:Wembley Arena").unwrap(),
+            false,
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(event_one).unwrap();
+
+        for (event_uid, event_ical) in [
+            ("EVENT_TWO", "DTSTART:20210106T183000Z LOCATION:Wembley Arena"),
+            ("EVENT_THREE", "DTSTART:20210107T183000Z LOCATION-TYPE:ONLINE"),
+        ] {
+            let mut event = Event::parse_ical(event_uid, event_ical).unwrap();
+
+            event.validate().unwrap();
+
+            calendar.insert_event_and_reindex(event).unwrap();
+        }
+
+        let query_event_uids = |calendar: &Calendar, query: &str| -> Vec<String> {
+            let mut query = crate::queries::event_query::EventQuery::from_str(query).unwrap();
+
+            crate::queries::query::Query::execute(&mut query, calendar)
+                .unwrap()
+                .results
+                .iter()
+                .map(|query_result| query_result.result.uid.uid.to_string())
+                .collect()
+        };
+
+        let query_event_instances = |calendar: &Calendar, query: &str| -> Vec<(String, i64)> {
+            let mut query = crate::queries::event_instance_query::EventInstanceQuery::from_str(query).unwrap();
+
+            crate::queries::query::Query::execute(&mut query, calendar)
+                .unwrap()
+                .results
+                .iter()
+                .map(|query_result| (query_result.result.uid.uid.to_string(), query_result.result.dtstart.get_utc_timestamp()))
+                .collect()
+        };
+
+        // The LOCATION text is indexed as a whole, with its whitespace normalized.
+        assert_eq!(query_event_uids(&calendar, "X-LOCATION:Royal Albert Hall"), vec![String::from("EVENT_ONE")]);
+        assert_eq!(query_event_uids(&calendar, "X-LOCATION:Royal"), Vec::<String>::new());
+        assert_eq!(query_event_uids(&calendar, "X-LOCATION:royal albert hall"), Vec::<String>::new());
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-LOCATION;OP=OR:Royal Albert Hall,Wembley Arena"),
+            vec![String::from("EVENT_ONE"), String::from("EVENT_TWO")],
         );
 
-        // Recurring event that terminates after the prune range
-        let event_two = build_and_associate_event(
-            &mut calendar,
-            "EVENT_TWO",
-            "DTSTART:20241231T163000Z\nRRULE:FREQ=DAILY;COUNT=10",
+        assert_eq!(
+            query_event_uids(&calendar, "X-LOCATION-NOT:Royal Albert Hall"),
+            vec![String::from("EVENT_TWO"), String::from("EVENT_THREE")],
+        );
+
+        // Distinct from the LOCATION-TYPE index.
+        assert_eq!(query_event_uids(&calendar, "X-LOCATION-TYPE:ONLINE"), vec![String::from("EVENT_THREE")]);
+        assert_eq!(query_event_uids(&calendar, "X-LOCATION:ONLINE"), Vec::<String>::new());
+
+        // The overridden occurrence LOCATION is indexed in place of the base event LOCATION.
+        assert_eq!(
+            query_event_instances(&calendar, "X-LOCATION:Wembley Arena"),
+            vec![
+                (String::from("EVENT_TWO"), 1609957800),
+                (String::from("EVENT_ONE"), 1610476200),
+            ],
+        );
+
+        assert_eq!(
+            query_event_instances(&calendar, "X-LOCATION:Royal Albert Hall"),
+            vec![(String::from("EVENT_ONE"), 1609871400)],
+        );
+
+        calendar.insert(CalendarProperty::from_str("X-TERM-NORMALIZATION:CASE-FOLD").unwrap()).unwrap();
+
+        assert_eq!(query_event_uids(&calendar, "X-LOCATION:royal albert hall"), vec![String::from("EVENT_ONE")]);
+    }
+
+    #[test]
+    fn test_x_property_querying() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        let mut event_one = Event::parse_ical(
+            "EVENT_ONE",
+            "DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;COUNT=2;INTERVAL=1 X-INSTRUCTOR:Jane Doe X-LEVEL:Beginner",
+        ).unwrap();
+
+        event_one.validate().unwrap();
+
+        // Only the overridden X-INSTRUCTOR is replaced, the X-LEVEL still applies to the occurrence.
+        event_one.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210112T183000Z", "X-INSTRUCTOR:John Doe").unwrap(),
+            false,
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(event_one).unwrap();
+
+        for (event_uid, event_ical) in [
+            ("EVENT_TWO", "DTSTART:20210106T183000Z X-INSTRUCTOR:John Doe X-ROOM:A1"),
+            ("EVENT_THREE", "DTSTART:20210107T183000Z CATEGORIES:YOGA"),
+        ] {
+            let mut event = Event::parse_ical(event_uid, event_ical).unwrap();
+
+            event.validate().unwrap();
+
+            calendar.insert_event_and_reindex(event).unwrap();
+        }
+
+        let query_event_uids = |calendar: &Calendar, query: &str| -> Vec<String> {
+            let mut query = crate::queries::event_query::EventQuery::from_str(query).unwrap();
+
+            crate::queries::query::Query::execute(&mut query, calendar)
+                .unwrap()
+                .results
+                .iter()
+                .map(|query_result| query_result.result.uid.uid.to_string())
+                .collect()
+        };
+
+        let query_event_instances = |calendar: &Calendar, query: &str| -> Vec<(String, i64)> {
+            let mut query = crate::queries::event_instance_query::EventInstanceQuery::from_str(query).unwrap();
+
+            crate::queries::query::Query::execute(&mut query, calendar)
+                .unwrap()
+                .results
+                .iter()
+                .map(|query_result| (query_result.result.uid.uid.to_string(), query_result.result.dtstart.get_utc_timestamp()))
+                .collect()
+        };
+
+        // No X-properties are indexed until the Calendar declares them.
+        assert_eq!(query_event_uids(&calendar, "X-PROP;NAME=X-INSTRUCTOR:Jane Doe"), Vec::<String>::new());
+
+        calendar.insert(CalendarProperty::from_str("X-INDEXED-PROPERTIES:X-INSTRUCTOR,X-LEVEL").unwrap()).unwrap();
+
+        assert_eq!(query_event_uids(&calendar, "X-PROP;NAME=X-INSTRUCTOR:Jane Doe"), vec![String::from("EVENT_ONE")]);
+        assert_eq!(query_event_uids(&calendar, "X-PROP;NAME=X-LEVEL:Jane Doe"), Vec::<String>::new());
+        assert_eq!(query_event_uids(&calendar, "X-PROP;NAME=X-ROOM:A1"), Vec::<String>::new());
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-PROP;NAME=X-INSTRUCTOR;OP=OR:Jane Doe,John Doe"),
+            vec![String::from("EVENT_ONE"), String::from("EVENT_TWO")],
+        );
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-PROP-NOT;NAME=X-INSTRUCTOR:Jane Doe"),
+            vec![String::from("EVENT_TWO"), String::from("EVENT_THREE")],
+        );
+
+        assert_eq!(
+            query_event_instances(&calendar, "X-PROP;NAME=X-INSTRUCTOR:John Doe"),
+            vec![
+                (String::from("EVENT_TWO"), 1609957800),
+                (String::from("EVENT_ONE"), 1610476200),
+            ],
+        );
+
+        assert_eq!(
+            query_event_instances(&calendar, "X-PROP;NAME=X-LEVEL:Beginner"),
+            vec![
+                (String::from("EVENT_ONE"), 1609871400),
+                (String::from("EVENT_ONE"), 1610476200),
+            ],
+        );
+
+        // Events inserted after the declaration are indexed as they are inserted.
+        let mut event_four = Event::parse_ical("EVENT_FOUR", "DTSTART:20210108T183000Z X-LEVEL:Beginner X-ROOM:A1").unwrap();
+
+        event_four.validate().unwrap();
+
+        calendar.insert_event_and_reindex(event_four).unwrap();
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-PROP;NAME=X-LEVEL:Beginner"),
+            vec![String::from("EVENT_ONE"), String::from("EVENT_FOUR")],
+        );
+
+        // Re-declaring the indexed X-properties replaces those previously declared.
+        calendar.insert(CalendarProperty::from_str("X-INDEXED-PROPERTIES:X-ROOM").unwrap()).unwrap();
+
+        assert_eq!(query_event_uids(&calendar, "X-PROP;NAME=X-INSTRUCTOR:Jane Doe"), Vec::<String>::new());
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-PROP;NAME=X-ROOM:A1"),
+            vec![String::from("EVENT_TWO"), String::from("EVENT_FOUR")],
+        );
+
+        calendar.rebuild_indexes().unwrap();
+
+        assert_eq!(
+            query_event_uids(&calendar, "X-PROP;NAME=X-ROOM:A1"),
+            vec![String::from("EVENT_TWO"), String::from("EVENT_FOUR")],
+        );
+    }
+
+    #[test]
+    fn test_insert_properties() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        let calendar_properties = |input: &str| -> Vec<CalendarProperty> {
+            redical_ical::properties::CalendarProperties::from_str(input).unwrap().0
+        };
+
+        calendar.insert_properties(
+            calendar_properties("NAME;LANGUAGE=en:Team: Alpha NAME;LANGUAGE=fr:Équipe Alpha COLOR:turquoise X-DEFAULT-TZID:Europe/London"),
+        ).unwrap();
+
+        assert_eq!(
+            calendar.to_rendered_content_lines(),
+            vec![
+                String::from("COLOR:turquoise"),
+                String::from("NAME;LANGUAGE=en:Team: Alpha"),
+                String::from("NAME;LANGUAGE=fr:Équipe Alpha"),
+                String::from("UID:CALENDAR_UID"),
+                String::from("X-DEFAULT-TZID:Europe/London"),
+            ],
+        );
+
+        assert_eq!(calendar.names.as_ref().map(HashSet::len), Some(2));
+        assert_eq!(calendar.color, Some(ColorProperty::from_str("COLOR:turquoise").unwrap()));
+
+        // NAME and passive properties replace any existing sharing their name, leaving the others untouched.
+        calendar.insert_properties(
+            calendar_properties("NAME:Team Beta X-WR-CALDESC:Description REFRESH-INTERVAL;VALUE=DURATION:PT12H SOURCE:https://example.com/team.ics"),
+        ).unwrap();
+
+        assert_eq!(
+            calendar.to_rendered_content_lines(),
+            vec![
+                String::from("COLOR:turquoise"),
+                String::from("NAME:Team Beta"),
+                String::from("REFRESH-INTERVAL;VALUE=DURATION:PT12H"),
+                String::from("SOURCE:https://example.com/team.ics"),
+                String::from("UID:CALENDAR_UID"),
+                String::from("X-DEFAULT-TZID:Europe/London"),
+                String::from("X-WR-CALDESC:Description"),
+            ],
+        );
+
+        assert_eq!(calendar.refresh_interval, Some(RefreshIntervalProperty::from_str("REFRESH-INTERVAL;VALUE=DURATION:PT12H").unwrap()));
+        assert_eq!(calendar.source, Some(SourceProperty::from_str("SOURCE:https://example.com/team.ics").unwrap()));
+
+        assert_eq!(
+            calendar.insert_properties(calendar_properties("UID:OTHER_CALENDAR_UID")).map(|_| ()),
+            Err(String::from("Inserted calendar UID: OTHER_CALENDAR_UID does not match existing UID: CALENDAR_UID")),
+        );
+    }
+
+    #[test]
+    fn test_bump_generation() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        assert_eq!(calendar.generation, 0);
+
+        assert_eq!(calendar.bump_generation(), 1);
+        assert_eq!(calendar.bump_generation(), 2);
+
+        assert_eq!(calendar.generation, 2);
+    }
+
+    #[test]
+    fn test_prune_until() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        assert_eq!(calendar.get_prune_until(1_700_000_000), None);
+
+        calendar.insert(CalendarProperty::from_str("X-PRUNE-OLDER-THAN:P90D").unwrap()).unwrap();
+
+        assert_eq!(calendar.get_prune_until(1_700_000_000), Some(1_700_000_000 - (90 * 24 * 60 * 60)));
+
+        assert_eq!(
+            calendar.to_rendered_content_lines(),
+            vec![
+                String::from("UID:CALENDAR_UID"),
+                String::from("X-PRUNE-OLDER-THAN:P90D"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_validate_event_schema() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        let event = Event::parse_ical(
+            "EVENT_ONE",
+            "DTSTART:20210105T183000Z CATEGORIES:CATEGORY_ONE X-LEVEL:Beginner BEGIN:VALARM UID:ALARM_ONE ACTION:DISPLAY TRIGGER:-PT15M END:VALARM",
+        ).unwrap();
+
+        // Without any declared schema every event is valid.
+        assert_eq!(calendar.validate_event_schema(&event), Ok(()));
+
+        calendar.insert(CalendarProperty::from_str("X-REQUIRED-PROPERTIES:GEO,CATEGORIES").unwrap()).unwrap();
+
+        assert_eq!(
+            calendar.validate_event_schema(&event),
+            Err(String::from("calendar schema violated - missing required properties: GEO")),
+        );
+
+        calendar.insert(CalendarProperty::from_str("X-ALLOWED-PROPERTIES:DTSTART,RRULE").unwrap()).unwrap();
+
+        // UID, LAST-MODIFIED and the required properties are always allowed.
+        assert_eq!(
+            calendar.validate_event_schema(&event),
+            Err(String::from("calendar schema violated - missing required properties: GEO; disallowed properties: VALARM, X-LEVEL")),
+        );
+
+        let event = Event::parse_ical(
+            "EVENT_ONE",
+            "DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3 CATEGORIES:CATEGORY_ONE GEO:51.751365550307604;-1.2601196837753945 LAST-MODIFIED:20210501T090000Z",
+        ).unwrap();
+
+        assert_eq!(calendar.validate_event_schema(&event), Ok(()));
+
+        assert_eq!(
+            calendar.to_rendered_content_lines(),
+            vec![
+                String::from("UID:CALENDAR_UID"),
+                String::from("X-ALLOWED-PROPERTIES:DTSTART,RRULE"),
+                String::from("X-REQUIRED-PROPERTIES:GEO,CATEGORIES"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_event_expirations() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        build_and_associate_event(&mut calendar, "EVENT_ONE", "DTSTART:20241231T163000Z\nRDATE:20241231T163000Z");
+        build_and_associate_event(&mut calendar, "EVENT_TWO", "DTSTART:20241231T163000Z\nRDATE:20241231T163000Z");
+
+        assert!(calendar.set_event_expiration(&String::from("EVENT_ONE"), 100));
+        assert!(calendar.set_event_expiration(&String::from("EVENT_TWO"), 200));
+        assert!(!calendar.set_event_expiration(&String::from("EVENT_MISSING"), 100));
+
+        assert_eq!(calendar.get_expired_event_uids(99), Vec::<String>::new());
+        assert_eq!(calendar.get_expired_event_uids(100), vec![String::from("EVENT_ONE")]);
+        assert_eq!(calendar.get_expired_event_uids(200), vec![String::from("EVENT_ONE"), String::from("EVENT_TWO")]);
+
+        assert_eq!(calendar.remove_event_expiration(&String::from("EVENT_TWO")), Some(200));
+        assert_eq!(calendar.remove_event_expiration(&String::from("EVENT_TWO")), None);
+        assert_eq!(calendar.get_expired_event_uids(200), vec![String::from("EVENT_ONE")]);
+
+        // Removing the event also removes its expiration.
+        calendar.remove_event(&String::from("EVENT_ONE"));
+
+        assert_eq!(calendar.event_expirations, BTreeMap::new());
+    }
+
+    #[test]
+    fn test_scan_events() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        build_and_associate_event(&mut calendar, "EVENT_ONE", "DTSTART:20241231T163000Z\nRDATE:20241231T163000Z");
+        build_and_associate_event(&mut calendar, "EVENT_THREE", "DTSTART:20241231T163000Z\nRDATE:20241231T163000Z");
+        build_and_associate_event(&mut calendar, "EVENT_TWO", "DTSTART:20241231T163000Z\nRDATE:20241231T163000Z");
+
+        let scanned_event_uids = |(events, next_event_uid): (Vec<&Event>, Option<String>)| {
+            (
+                events.iter().map(|event| event.uid.uid.to_string()).collect::<Vec<String>>(),
+                next_event_uid,
+            )
+        };
+
+        assert_eq!(
+            scanned_event_uids(calendar.scan_events(None, 2)),
+            (vec![String::from("EVENT_ONE"), String::from("EVENT_THREE")], Some(String::from("EVENT_THREE"))),
         );
 
-        // Recurring event that terminates inside prune range
-        let event_three = build_and_associate_event(
-            &mut calendar,
-            "EVENT_THREE",
-            "DTSTART:20241231T163000Z\nRRULE:FREQ=DAILY;COUNT=2",
+        assert_eq!(
+            scanned_event_uids(calendar.scan_events(Some(&String::from("EVENT_THREE")), 2)),
+            (vec![String::from("EVENT_TWO")], None),
         );
 
-        // Single event that terminates before prune range
-        let event_four = build_and_associate_event(
-            &mut calendar,
-            "EVENT_FOUR",
-            "DTSTART:20241231T163000Z\nRDATE:20241231T163000Z",
+        // Events removed (or inserted before the cursor) part way through do not affect the scan.
+        calendar.remove_event(&String::from("EVENT_THREE"));
+
+        build_and_associate_event(&mut calendar, "EVENT_A", "DTSTART:20241231T163000Z\nRDATE:20241231T163000Z");
+
+        assert_eq!(
+            scanned_event_uids(calendar.scan_events(Some(&String::from("EVENT_THREE")), 2)),
+            (vec![String::from("EVENT_TWO")], None),
         );
 
-        // Single event that terminates inside prune range
-        let event_five = build_and_associate_event(
-            &mut calendar,
-            "EVENT_FIVE",
-            "DTSTART:20250101T123000Z\nRDATE:20250101T123000Z",
+        assert_eq!(
+            scanned_event_uids(calendar.scan_events(None, 3)),
+            (vec![String::from("EVENT_A"), String::from("EVENT_ONE"), String::from("EVENT_TWO")], None),
         );
+    }
+
+    #[test]
+    fn test_get_related_events() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
 
-        assert_eq!(calendar.events.len(), 5);
+        let events_ical = [
+            ("CHILD_TWO", "DTSTART:20210105T090000Z RELATED-TO;RELTYPE=PARENT:PARENT_UID"),
+            ("CHILD_ONE", "DTSTART:20210105T090000Z RELATED-TO:PARENT_UID RELATED-TO;RELTYPE=SIBLING:CHILD_TWO"),
+            ("UNRELATED", "DTSTART:20210105T090000Z RELATED-TO;RELTYPE=PARENT:OTHER_PARENT_UID"),
+            ("PARENT_UID", "DTSTART:20210105T090000Z RELATED-TO;RELTYPE=CHILD:CHILD_ONE"),
+        ];
+
+        for (event_uid, event_ical) in events_ical {
+            let mut event = Event::parse_ical(event_uid, event_ical).unwrap();
+
+            event.validate().unwrap();
+
+            calendar.insert_event_and_reindex(event).unwrap();
+        }
+
+        let related_event_uids = |reltype: &str, related_uid: &str| -> Vec<String> {
+            calendar
+                .get_related_events(reltype, related_uid)
+                .iter()
+                .map(|event| event.uid.uid.to_string())
+                .collect()
+        };
+
+        assert_eq!(related_event_uids("PARENT", "PARENT_UID"), vec![String::from("CHILD_ONE"), String::from("CHILD_TWO")]);
+        assert_eq!(related_event_uids("parent", "PARENT_UID"), vec![String::from("CHILD_ONE"), String::from("CHILD_TWO")]);
+        assert_eq!(related_event_uids("SIBLING", "CHILD_TWO"), vec![String::from("CHILD_ONE")]);
+        assert_eq!(related_event_uids("CHILD", "CHILD_ONE"), vec![String::from("PARENT_UID")]);
+        assert_eq!(related_event_uids("CHILD", "PARENT_UID"), Vec::<String>::new());
+        assert_eq!(related_event_uids("PARENT", "NON_EXISTENT_UID"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_get_indexed_term_counts() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        let events_ical = [
+            ("EVENT_ONE", "DTSTART:20210105T090000Z CATEGORIES:SPORT_FOOTBALL,SPORT_TENNIS CLASS:PUBLIC RELATED-TO:PARENT_UID"),
+            ("EVENT_TWO", "DTSTART:20210105T090000Z CATEGORIES:SPORT_FOOTBALL,MUSIC LOCATION-TYPE:HALL LOCATION:Royal  Albert Hall RELATED-TO;RELTYPE=CHILD:CHILD_UID"),
+            ("EVENT_THREE", "DTSTART:20210105T090000Z CATEGORIES:SPORT_FOOTBALL CLASS:PRIVATE RELATED-TO:PARENT_UID"),
+        ];
+
+        for (event_uid, event_ical) in events_ical {
+            let mut event = Event::parse_ical(event_uid, event_ical).unwrap();
+
+            event.validate().unwrap();
+
+            calendar.insert_event_and_reindex(event).unwrap();
+        }
+
+        assert_eq!(
+            calendar.get_indexed_term_counts("CATEGORIES", None),
+            Ok(vec![
+                (String::from("MUSIC"), 1),
+                (String::from("SPORT_FOOTBALL"), 3),
+                (String::from("SPORT_TENNIS"), 1),
+            ]),
+        );
 
-        let pruned_events = calendar.prune_events(from, until).unwrap();
-        
-        assert_eq!(calendar.events.len(), 3);
-        assert_eq!(pruned_events.len(), 2);
-        
-        // Expected pruned events are removed
         assert_eq!(
-            pruned_events,
-            HashMap::from([
-                ("EVENT_THREE".to_string(), Box::new(event_three)),
-                ("EVENT_FIVE".to_string(), Box::new(event_five)),
+            calendar.get_indexed_term_counts("categories", Some("SPORT_")),
+            Ok(vec![
+                (String::from("SPORT_FOOTBALL"), 3),
+                (String::from("SPORT_TENNIS"), 1),
             ]),
         );
 
-        // Other events are still in the Calendar
-        assert_eq!(calendar.events.get("EVENT_ONE"), Some(&Box::new(event_one)));
-        assert_eq!(calendar.events.get("EVENT_TWO"), Some(&Box::new(event_two)));
-        assert_eq!(calendar.events.get("EVENT_FOUR"), Some(&Box::new(event_four)));
+        assert_eq!(
+            calendar.get_indexed_term_counts("LOCATION-TYPE", None),
+            Ok(vec![(String::from("HALL"), 1)]),
+        );
+
+        assert_eq!(
+            calendar.get_indexed_term_counts("LOCATION", None),
+            Ok(vec![(String::from("Royal Albert Hall"), 1)]),
+        );
+
+        assert_eq!(
+            calendar.get_indexed_term_counts("CLASS", None),
+            Ok(vec![(String::from("PRIVATE"), 1), (String::from("PUBLIC"), 1)]),
+        );
+
+        assert_eq!(
+            calendar.get_indexed_term_counts("RELATED-TO", Some("PARENT;")),
+            Ok(vec![(String::from("PARENT;PARENT_UID"), 2)]),
+        );
+
+        assert_eq!(
+            calendar.get_indexed_term_counts("CATEGORIES", Some("NON_EXISTENT")),
+            Ok(vec![]),
+        );
+
+        assert_eq!(
+            calendar.get_indexed_term_counts("GEO", None),
+            Err(String::from("Unknown index: GEO (expected CATEGORIES, LOCATION-TYPE, LOCATION, CLASS, or RELATED-TO)")),
+        );
+
+        assert_eq!(calendar.get_indexed_term_count(), 9);
+
+        let index_rebuild_stats = calendar.rebuild_indexes_at(1_700_000_000).unwrap();
+
+        assert_eq!(index_rebuild_stats.event_count, 3);
+        assert_eq!(index_rebuild_stats.term_count, 9);
+        assert_eq!(calendar.indexes_rebuilt_at, Some(1_700_000_000));
+    }
+
+    #[test]
+    fn test_approximate_memory_usage() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        for index in 10..30 {
+            let mut event = Event::parse_ical(
+                format!("EVENT_{index}").as_str(),
+                "DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3 CATEGORIES:CATEGORY_ONE CLASS:PUBLIC",
+            ).unwrap();
+
+            event.validate().unwrap();
+
+            calendar.insert_event(event);
+        }
+
+        calendar.rebuild_indexes().unwrap();
+
+        let memory_usage = calendar.approximate_memory_usage(0);
+
+        assert!(memory_usage > std::mem::size_of::<Calendar>() + (20 * std::mem::size_of::<Event>()));
+
+        // The events are all the same size, so extrapolating from a sample of them is exact.
+        assert_eq!(calendar.approximate_memory_usage(DEFAULT_MEMORY_USAGE_SAMPLE_SIZE), memory_usage);
+        assert_eq!(calendar.approximate_memory_usage(1), memory_usage);
+
+        // Sampling more elements than there are is the same as sampling all of them.
+        assert_eq!(calendar.approximate_memory_usage(100), memory_usage);
+    }
+
+    #[test]
+    fn test_get_stats() {
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        assert_eq!(
+            calendar.get_stats(),
+            CalendarStats {
+                indexes_active: true,
+                approximate_memory_usage: calendar.approximate_memory_usage(DEFAULT_MEMORY_USAGE_SAMPLE_SIZE),
+                ..CalendarStats::default()
+            },
+        );
+
+        let mut event_one = Event::parse_ical(
+            "EVENT_ONE",
+            "DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3 CATEGORIES:CATEGORY_ONE,CATEGORY_TWO CLASS:PUBLIC LOCATION:Oxford Town Hall GEO:51.751365550307604;-1.2601196837753945",
+        ).unwrap();
+
+        event_one.validate().unwrap();
+
+        event_one.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210112T183000Z", "CATEGORIES:CATEGORY_THREE").unwrap(),
+            false,
+        ).unwrap();
+
+        let mut event_two = Event::parse_ical(
+            "EVENT_TWO",
+            "DTSTART:20201231T090000Z RRULE:FREQ=DAILY;INTERVAL=1;COUNT=3 RELATED-TO;RELTYPE=PARENT:PARENT_UID",
+        ).unwrap();
+
+        event_two.validate().unwrap();
+
+        calendar.insert_event(event_one);
+        calendar.insert_event(event_two);
+
+        calendar.rebuild_indexes().unwrap();
+
+        let calendar_stats = calendar.get_stats();
+
+        assert!(calendar_stats.approximate_memory_usage > std::mem::size_of::<Calendar>());
+
+        assert_eq!(
+            calendar_stats,
+            CalendarStats {
+                event_count: 2,
+                override_count: 1,
+                indexes_active: true,
+                indexed_categories_term_count: 3,
+                indexed_location_type_term_count: 0,
+                indexed_related_to_term_count: 1,
+                indexed_geo_term_count: 1,
+                indexed_class_term_count: 1,
+                indexed_location_term_count: 1,
+                approximate_memory_usage: calendar_stats.approximate_memory_usage,
+                earliest_dtstart: Some(DateTime::from_str("20201231T090000Z").unwrap().get_utc_timestamp(None)),
+                latest_dtstart: Some(DateTime::from_str("20210105T183000Z").unwrap().get_utc_timestamp(None)),
+            },
+        );
+
+        let indexed_approximate_memory_usage = calendar_stats.approximate_memory_usage;
+
+        calendar.disable_indexes();
+
+        let calendar_stats = calendar.get_stats();
+
+        // Clearing the Calendar indexes (including the time buckets) reduces the memory footprint.
+        assert!(calendar_stats.approximate_memory_usage < indexed_approximate_memory_usage);
+
+        assert!(!calendar_stats.indexes_active);
+        assert_eq!(calendar_stats.indexed_categories_term_count, 0);
+        assert_eq!(calendar_stats.indexed_geo_term_count, 0);
+    }
+
+    #[test]
+    fn test_to_digest_components() {
+        let event_one = Event::parse_ical(
+            "EVENT_ONE",
+            "DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3 CATEGORIES:CATEGORY_ONE LAST-MODIFIED:20210501T090000Z",
+        ).unwrap();
+
+        let mut event_two = Event::parse_ical(
+            "EVENT_TWO",
+            "DTSTART:20201231T090000Z RRULE:FREQ=DAILY;INTERVAL=1;COUNT=3 LAST-MODIFIED:20210501T090000Z",
+        ).unwrap();
+
+        event_two.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210101T090000Z", "CATEGORIES:CATEGORY_TWO LAST-MODIFIED:20210501T090000Z").unwrap(),
+            false,
+        ).unwrap();
+
+        let mut calendar = Calendar::new("CALENDAR_UID".to_string());
+
+        calendar.insert_event(event_one.clone());
+        calendar.insert_event(event_two.clone());
+
+        assert_eq!(
+            calendar.to_digest_components(),
+            vec![
+                vec![],
+                vec![
+                    String::from("CATEGORIES:CATEGORY_ONE"),
+                    String::from("DTSTART:20210105T183000Z"),
+                    String::from("LAST-MODIFIED:20210501T090000Z"),
+                    String::from("RRULE:COUNT=3;FREQ=WEEKLY;INTERVAL=1"),
+                    String::from("UID:EVENT_ONE"),
+                ],
+                vec![
+                    String::from("DTSTART:20201231T090000Z"),
+                    String::from("LAST-MODIFIED:20210501T090000Z"),
+                    String::from("RRULE:COUNT=3;FREQ=DAILY;INTERVAL=1"),
+                    String::from("UID:EVENT_TWO"),
+                ],
+                vec![
+                    String::from("UID:EVENT_TWO"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210101T090000Z"),
+                    String::from("CATEGORIES:CATEGORY_TWO"),
+                    String::from("DTSTART:20210101T090000Z"),
+                    String::from("LAST-MODIFIED:20210501T090000Z"),
+                ],
+            ],
+        );
+
+        // Assert the digest components are independent of insertion order, the index state, and
+        // the Calendar UID.
+        let mut reordered_calendar = Calendar::new("RENAMED_CALENDAR_UID".to_string());
+
+        reordered_calendar.insert_event(event_two);
+        reordered_calendar.insert_event(event_one);
+
+        reordered_calendar.rebuild_indexes().unwrap();
+
+        assert_eq!(reordered_calendar.to_digest_components(), calendar.to_digest_components());
     }
 }
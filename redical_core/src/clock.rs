@@ -0,0 +1,73 @@
+use std::cell::Cell;
+
+use chrono::{DateTime, Utc};
+
+thread_local! {
+    static FIXED_NOW: Cell<Option<DateTime<Utc>>> = const { Cell::new(None) };
+}
+
+/// The current UTC date-time used for defaulted values (e.g. a LAST-MODIFIED not provided), which
+/// is the wall clock unless fixed for the current thread with `with_now`.
+pub fn now() -> DateTime<Utc> {
+    FIXED_NOW.with(|fixed_now| fixed_now.get()).unwrap_or_else(Utc::now)
+}
+
+// Restores the previously fixed now (if any) once dropped, even if the function with the fixed
+// now panics.
+struct FixedNowGuard(Option<DateTime<Utc>>);
+
+impl Drop for FixedNowGuard {
+    fn drop(&mut self) {
+        FIXED_NOW.with(|fixed_now| fixed_now.set(self.0));
+    }
+}
+
+/// Run the provided function with `now` fixed to the provided UTC date-time on the current thread
+/// (so that defaulted values are deterministic, e.g. for replication or tests), or as is if not
+/// provided.
+pub fn with_now<T>(now: Option<DateTime<Utc>>, function: impl FnOnce() -> T) -> T {
+    let Some(now) = now else {
+        return function();
+    };
+
+    let _guard = FixedNowGuard(FIXED_NOW.with(|fixed_now| fixed_now.replace(Some(now))));
+
+    function()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn test_with_now() {
+        let fixed_now = DateTime::from_timestamp(1609430400, 0).unwrap();
+        let nested_fixed_now = DateTime::from_timestamp(1619859600, 0).unwrap();
+
+        assert!(now() > fixed_now);
+
+        assert_eq!(
+            with_now(Some(fixed_now), || {
+                assert_eq!(now(), fixed_now);
+
+                // Nested fixed nows are restored to the outer one.
+                assert_eq!(with_now(Some(nested_fixed_now), now), nested_fixed_now);
+
+                // Not providing a now retains the outer one.
+                assert_eq!(with_now(None, now), fixed_now);
+
+                now()
+            }),
+            fixed_now,
+        );
+
+        assert!(now() > fixed_now);
+
+        // The fixed now is only for the current thread.
+        with_now(Some(fixed_now), || {
+            assert!(std::thread::spawn(now).join().unwrap() > fixed_now);
+        });
+    }
+}
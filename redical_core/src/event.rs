@@ -1,7 +1,9 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::str::FromStr;
 
-use rrule::{RRuleError, RRuleSet};
+use chrono_tz::Tz;
+
+use rrule::RRuleSet;
 
 use redical_ical::{
     ICalendarComponent,
@@ -27,19 +29,33 @@ use redical_ical::{
         LocationTypeProperty,
         RelatedToProperty,
         ClassProperty,
+        LocationProperty,
         GeoProperty,
         PassiveProperty,
     },
+    values::{
+        date_time::DateTime,
+        recur::Recur,
+        tzid::Tzid,
+    },
 };
 
+use crate::clock;
+
 use crate::event_occurrence_override::EventOccurrenceOverride;
 
+use crate::event_instance::{EventInstance, EventInstanceIterator};
+
 use crate::event_occurrence_iterator::{
+    DstPolicy,
     EventOccurrenceIterator,
     LowerBoundFilterCondition,
+    UpperBoundFilterCondition,
     FilterProperty
 };
 
+use crate::interning::InternedString;
+
 use crate::inverted_index::InvertedEventIndex;
 
 use crate::queries::results::QueryableEntity;
@@ -51,7 +67,7 @@ use crate::utils::KeyValuePair;
 
 #[derive(Default, Debug, Eq, PartialEq, Clone)]
 pub struct ScheduleProperties {
-    pub rrule: Option<RRuleProperty>,
+    pub rrules: Option<HashSet<RRuleProperty>>,
     pub exrule: Option<ExRuleProperty>,
     pub rdates: Option<HashSet<RDateProperty>>,
     pub exdates: Option<HashSet<ExDateProperty>>,
@@ -64,7 +80,7 @@ pub struct ScheduleProperties {
 impl ScheduleProperties {
     pub fn new() -> ScheduleProperties {
         ScheduleProperties {
-            rrule: None,
+            rrules: None,
             exrule: None,
             rdates: None,
             exdates: None,
@@ -75,10 +91,16 @@ impl ScheduleProperties {
         }
     }
 
-    pub fn extract_serialized_rrule_ical_key_value_pair(&self) -> Option<KeyValuePair> {
-        self.rrule
-            .as_ref()
-            .map(|property| property.to_content_line().into())
+    pub fn extract_serialized_rrules_ical_key_value_pairs(&self) -> Option<HashSet<KeyValuePair>> {
+        self.rrules.as_ref().map(|properties| {
+            let mut key_value_pairs = HashSet::new();
+
+            for property in properties {
+                key_value_pairs.insert(property.to_content_line().into());
+            }
+
+            key_value_pairs
+        })
     }
 
     pub fn extract_serialized_exrule_ical_key_value_pair(&self) -> Option<KeyValuePair> {
@@ -131,11 +153,18 @@ impl ScheduleProperties {
 
     pub fn insert(&mut self, property: EventProperty) -> Result<&Self, String> {
         match property {
-            EventProperty::RRule(property) => { self.rrule = Some(property); },
             EventProperty::ExRule(property) => { self.exrule = Some(property); },
             EventProperty::DTStart(property) => { self.dtstart = Some(property); },
             EventProperty::DTEnd(property) => { self.dtend = Some(property); },
 
+            // Multiple RRULE properties are permitted, with the occurrences of each combined.
+            EventProperty::RRule(property) => {
+                match &mut self.rrules {
+                    Some(rrules) => { rrules.insert(property); },
+                    None => { self.rrules = Some(HashSet::from([property])); }
+                }
+            },
+
             EventProperty::RDate(property) => {
                 match &mut self.rdates {
                     Some(rdates) => { rdates.insert(property); },
@@ -162,14 +191,39 @@ impl ScheduleProperties {
         Ok(self)
     }
 
-    pub fn parse_rrule(&self) -> Result<RRuleSet, RRuleError> {
+    // Resolve any local date-time UNTIL of the RRULE/EXRULE recurrence against DTSTART (see
+    // Recur::with_until_resolved_against), so that the rrule crate expands it in the DTSTART
+    // timezone.
+    fn resolve_recur_until(&self, recur: &Recur) -> Result<Recur, String> {
+        let Some(dtstart) = self.dtstart.as_ref() else {
+            return Ok(recur.clone());
+        };
+
+        let dtstart_tz = dtstart.params.tzid.as_ref().map(|tzid| &tzid.0);
+
+        recur.with_until_resolved_against(&dtstart.date_time, dtstart_tz)
+    }
+
+    pub fn parse_rrule(&self) -> Result<RRuleSet, String> {
         let mut ical_parts = vec![];
 
-        if let Some(rrule) = &self.rrule {
-            ical_parts.push(rrule.render_ical());
+        if let Some(rrules) = &self.rrules {
+            for rrule in rrules {
+                let rrule = RRuleProperty {
+                    params: rrule.params.clone(),
+                    value: self.resolve_recur_until(&rrule.value)?,
+                };
+
+                ical_parts.push(rrule.render_ical());
+            }
         }
 
         if let Some(exrule) = &self.exrule {
+            let exrule = ExRuleProperty {
+                params: exrule.params.clone(),
+                value: self.resolve_recur_until(&exrule.value)?,
+            };
+
             ical_parts.push(exrule.render_ical());
         }
 
@@ -201,12 +255,12 @@ impl ScheduleProperties {
             //
             // "RRule parsing error: Missing date generation property. There needs to be at least
             // one `RRULE` or `RDATE` to generate occurrences."
-            if self.rrule.is_none() && (self.rdates.is_none() || self.rdates.as_ref().is_some_and(|rdates| rdates.is_empty())) {
+            if self.rrules.as_ref().is_none_or(|rrules| rrules.is_empty()) && self.rdates.as_ref().is_none_or(|rdates| rdates.is_empty()) {
                 ical_parts.push(RDateProperty::new_from(dtstart).render_ical());
             }
         }
 
-        ical_parts.join("\n").parse::<RRuleSet>()
+        ical_parts.join("\n").parse::<RRuleSet>().map_err(|error| error.to_string())
     }
 
     pub fn get_dtstart_timestamp(&self) -> Option<i64> {
@@ -235,13 +289,88 @@ impl ScheduleProperties {
         }
     }
 
-    pub fn build_parsed_rrule_set(&mut self) -> Result<(), rrule::RRuleError> {
+    pub fn build_parsed_rrule_set(&mut self) -> Result<(), String> {
         let parsed_rrule_set = self.parse_rrule()?;
 
         self.parsed_rrule_set = Some(parsed_rrule_set);
 
         Ok(())
     }
+
+    // Interpret the floating DTSTART, DTEND, RDATE, and EXDATE date-times (specified without a UTC
+    // designator or TZID) in the provided (Calendar default) timezone instead of UTC.
+    //
+    // This needs to be applied before the parsed RRuleSet is built.
+    pub fn apply_default_timezone(&mut self, default_tz: &Tz) -> Result<(), String> {
+        let default_tzid = Tzid(default_tz.to_owned());
+
+        if let Some(dtstart) = self.dtstart.as_mut() {
+            localize_floating_date_times(&mut dtstart.params.tzid, [&dtstart.date_time], &default_tzid)?;
+        }
+
+        if let Some(dtend) = self.dtend.as_mut() {
+            localize_floating_date_times(&mut dtend.params.tzid, [&dtend.date_time], &default_tzid)?;
+        }
+
+        // RDATE and EXDATE properties are hashed by their rendered content, so the sets need to be
+        // rebuilt with the updated properties.
+        if let Some(rdates) = self.rdates.take() {
+            let rdates =
+                rdates
+                    .into_iter()
+                    .map(|mut rdate| {
+                        localize_floating_date_times(&mut rdate.params.tzid, rdate.date_times.iter(), &default_tzid)?;
+
+                        Ok(rdate)
+                    })
+                    .collect::<Result<HashSet<RDateProperty>, String>>()?;
+
+            self.rdates = Some(rdates);
+        }
+
+        if let Some(exdates) = self.exdates.take() {
+            let exdates =
+                exdates
+                    .into_iter()
+                    .map(|mut exdate| {
+                        localize_floating_date_times(&mut exdate.params.tzid, exdate.date_times.iter(), &default_tzid)?;
+
+                        Ok(exdate)
+                    })
+                    .collect::<Result<HashSet<ExDateProperty>, String>>()?;
+
+            self.exdates = Some(exdates);
+        }
+
+        Ok(())
+    }
+}
+
+// Set the TZID of date-time property values which are all floating local date-times (dates and
+// UTC date-times are unaffected) to the provided default TZID, rejecting any which fall within
+// one of it's DST transition gaps.
+pub(crate) fn localize_floating_date_times<'a>(
+    tzid: &mut Option<Tzid>,
+    date_times: impl IntoIterator<Item = &'a DateTime>,
+    default_tzid: &Tzid,
+) -> Result<(), String> {
+    if tzid.is_some() {
+        return Ok(());
+    }
+
+    let date_times: Vec<&DateTime> = date_times.into_iter().collect();
+
+    if date_times.is_empty() || !date_times.iter().all(|date_time| matches!(date_time, DateTime::LocalDateTime(_))) {
+        return Ok(());
+    }
+
+    for date_time in &date_times {
+        default_tzid.validate_with_datetime_value(date_time)?;
+    }
+
+    *tzid = Some(default_tzid.to_owned());
+
+    Ok(())
 }
 
 #[derive(Default, Debug, Eq, PartialEq, Clone)]
@@ -251,6 +380,7 @@ pub struct IndexedProperties {
     pub categories: Option<HashSet<CategoriesProperty>>,
     pub location_type: Option<LocationTypeProperty>,
     pub class: Option<ClassProperty>,
+    pub location: Option<LocationProperty>,
 }
 
 impl IndexedProperties {
@@ -261,6 +391,7 @@ impl IndexedProperties {
             categories: None,
             location_type: None,
             class: None,
+            location: None,
         }
     }
 
@@ -335,12 +466,22 @@ impl IndexedProperties {
             .map(|class_property| class_property.class.to_string())
     }
 
+    pub fn extract_location(&self) -> Option<String> {
+        self.location
+            .as_ref()
+            .and_then(|location_property| location_property.get_normalized_location())
+    }
+
     pub fn insert(&mut self, property: EventProperty) -> Result<&Self, String> {
         match property {
             EventProperty::Class(property) => {
                 self.class = Some(property);
             }
 
+            EventProperty::Location(property) => {
+                self.location = Some(property);
+            }
+
             EventProperty::Geo(property) => {
                 self.geo = Some(property);
             }
@@ -397,6 +538,20 @@ impl PassiveProperties {
         key_value_pairs
     }
 
+    /// Extract all passive X-properties into a HashSet of key/value pairs, keyed by the uppercased
+    /// property name and valued by the property value as is.
+    pub fn extract_x_property_key_value_pairs(&self) -> HashSet<KeyValuePair> {
+        let mut key_value_pairs = HashSet::new();
+
+        for property in &self.properties {
+            if let PassiveProperty::X(content_line) = property {
+                key_value_pairs.insert(KeyValuePair::new(content_line.0.to_uppercase(), content_line.2.to_owned()));
+            }
+        }
+
+        key_value_pairs
+    }
+
     /// Extract all passive properties into a HashMap grouped by the property name.
     pub fn extract_properties_grouped_by_name(&self) -> HashMap<String, Vec<PassiveProperty>> {
         let mut passive_properties: HashMap<String, Vec<PassiveProperty>> = HashMap::new();
@@ -411,6 +566,17 @@ impl PassiveProperties {
         passive_properties
     }
 
+    /// Return the (revision) SEQUENCE passive property value, if present and valid.
+    pub fn get_sequence(&self) -> Option<u64> {
+        self.properties.iter().find_map(|passive_property| {
+            if let PassiveProperty::Sequence(_params, value) = passive_property {
+                value.trim().parse::<u64>().ok()
+            } else {
+                None
+            }
+        })
+    }
+
     pub fn insert(&mut self, property: EventProperty) -> Result<&Self, String> {
         match property {
             EventProperty::UID(_)
@@ -419,7 +585,9 @@ impl PassiveProperties {
             | EventProperty::Geo(_)
             | EventProperty::Categories(_)
             | EventProperty::LocationType(_)
+            | EventProperty::Location(_)
             | EventProperty::RelatedTo(_)
+            | EventProperty::OverrideMode(_)
             | EventProperty::RRule(_)
             | EventProperty::ExRule(_)
             | EventProperty::DTStart(_)
@@ -453,18 +621,20 @@ pub struct Event {
     pub passive_properties: PassiveProperties,
 
     pub overrides: BTreeMap<i64, EventOccurrenceOverride>,
-    pub indexed_categories: Option<InvertedEventIndex<String>>,
-    pub indexed_location_type: Option<InvertedEventIndex<String>>,
+    pub indexed_categories: Option<InvertedEventIndex<InternedString>>,
+    pub indexed_location_type: Option<InvertedEventIndex<InternedString>>,
     pub indexed_related_to: Option<InvertedEventIndex<KeyValuePair>>,
     pub indexed_geo: Option<InvertedEventIndex<GeoPoint>>,
-    pub indexed_class: Option<InvertedEventIndex<String>>,
+    pub indexed_class: Option<InvertedEventIndex<InternedString>>,
+    pub indexed_location: Option<InvertedEventIndex<InternedString>>,
+    pub indexed_x_properties: Option<InvertedEventIndex<KeyValuePair>>,
 }
 
 impl Event {
     pub fn new(uid: String) -> Event {
         Event {
             uid: UIDProperty::from(uid),
-            last_modified: LastModifiedProperty::new_from_now(false),
+            last_modified: LastModifiedProperty::new_from_utc_date_time(clock::now(), false),
 
             schedule_properties: ScheduleProperties::new(),
             indexed_properties: IndexedProperties::new(),
@@ -477,14 +647,15 @@ impl Event {
             indexed_related_to: None,
             indexed_geo: None,
             indexed_class: None,
+            indexed_location: None,
+            indexed_x_properties: None,
         }
     }
 
     pub fn validate(&mut self) -> Result<bool, String> {
         self
             .schedule_properties
-            .build_parsed_rrule_set()
-            .map_err(|error| error.to_string())?;
+            .build_parsed_rrule_set()?;
 
         Ok(true)
     }
@@ -495,6 +666,8 @@ impl Event {
         self.rebuild_indexed_related_to()?;
         self.rebuild_indexed_geo()?;
         self.rebuild_indexed_class()?;
+        self.rebuild_indexed_location()?;
+        self.rebuild_indexed_x_properties()?;
 
         Ok(true)
     }
@@ -511,6 +684,47 @@ impl Event {
         })
     }
 
+    // Build a copy of this Event with every property present on the patch Event replacing all
+    // existing properties of the same name (e.g. all CATEGORIES), retaining the remaining
+    // properties and occurrence overrides as they are.
+    //
+    // The patch LAST-MODIFIED (defaulted to now if not provided) always replaces the existing one.
+    pub fn patch(&self, patch: &Event) -> Result<Event, String> {
+        let patch_content_lines = patch.to_content_line_set();
+
+        let patched_property_names: HashSet<String> =
+            patch_content_lines
+                .iter()
+                .map(get_content_line_property_name)
+                .collect();
+
+        let patched_ical =
+            self.to_content_line_set()
+                .into_iter()
+                .filter(|content_line| !patched_property_names.contains(&get_content_line_property_name(content_line)))
+                .chain(patch_content_lines)
+                .map(|content_line| content_line.render_ical())
+                .collect::<Vec<String>>()
+                .join(" ");
+
+        let mut patched_event = Event::parse_ical(self.uid.uid.to_string().as_str(), patched_ical.as_str())?;
+
+        patched_event.overrides = self.overrides.clone();
+
+        patched_event.validate()?;
+
+        Ok(patched_event)
+    }
+
+    // The (sorted) names of every property (and component, e.g. VALARM) specified on this Event,
+    // excluding those of its occurrence overrides.
+    pub fn get_property_names(&self) -> BTreeSet<String> {
+        self.to_content_line_set()
+            .iter()
+            .map(get_content_line_property_name)
+            .collect()
+    }
+
     pub fn insert(&mut self, property: EventProperty) -> Result<&Self, String> {
         match property {
             EventProperty::UID(property) => {
@@ -529,6 +743,7 @@ impl Event {
             | EventProperty::Geo(_)
             | EventProperty::Categories(_)
             | EventProperty::LocationType(_)
+            | EventProperty::Location(_)
             | EventProperty::RelatedTo(_) => {
                 self.indexed_properties.insert(property)?;
             }
@@ -543,6 +758,12 @@ impl Event {
                 self.schedule_properties.insert(property)?;
             }
 
+            EventProperty::OverrideMode(_) => {
+                return Err(String::from(
+                    "Event does not expect an X-OVERRIDE-MODE property (only event occurrence overrides)",
+                ));
+            }
+
             _ => {
                 self.passive_properties.insert(property)?;
             }
@@ -552,7 +773,7 @@ impl Event {
     }
 
     pub fn rebuild_indexed_categories(&mut self) -> Result<&mut Self, String> {
-        self.indexed_categories = Some(InvertedEventIndex::<String>::new_from_event_categories(
+        self.indexed_categories = Some(InvertedEventIndex::<InternedString>::new_from_event_categories(
             self,
         ));
 
@@ -560,7 +781,7 @@ impl Event {
     }
 
     pub fn rebuild_indexed_location_type(&mut self) -> Result<&mut Self, String> {
-        self.indexed_location_type = Some(InvertedEventIndex::<String>::new_from_event_location_type(
+        self.indexed_location_type = Some(InvertedEventIndex::<InternedString>::new_from_event_location_type(
             self,
         ));
 
@@ -583,7 +804,19 @@ impl Event {
 
     // TODO: Add tests...
     pub fn rebuild_indexed_class(&mut self) -> Result<&mut Self, String> {
-        self.indexed_class = Some(InvertedEventIndex::<String>::new_from_event_class(self));
+        self.indexed_class = Some(InvertedEventIndex::<InternedString>::new_from_event_class(self));
+
+        Ok(self)
+    }
+
+    pub fn rebuild_indexed_location(&mut self) -> Result<&mut Self, String> {
+        self.indexed_location = Some(InvertedEventIndex::<InternedString>::new_from_event_location(self));
+
+        Ok(self)
+    }
+
+    pub fn rebuild_indexed_x_properties(&mut self) -> Result<&mut Self, String> {
+        self.indexed_x_properties = Some(InvertedEventIndex::<KeyValuePair>::new_from_event_x_properties(self));
 
         Ok(self)
     }
@@ -597,6 +830,12 @@ impl Event {
             return Err(String::from("Expected event occurrence override to have dtstart defined."));
         };
 
+        // Remove any existing override being replaced from the indexes first, otherwise the terms
+        // it overrode would linger alongside those of the replacement.
+        if update_indexes && self.overrides.contains_key(&timestamp) {
+            self.remove_occurrence_override(timestamp, true)?;
+        }
+
         self.overrides
             .insert(timestamp, event_occurrence_override.clone());
 
@@ -606,32 +845,29 @@ impl Event {
         }
 
         if let Some(ref mut indexed_categories) = self.indexed_categories {
-            if let Some(overridden_categories) = &event_occurrence_override
-                .indexed_properties
-                .extract_all_category_strings()
-            {
-                indexed_categories.insert_override(timestamp, overridden_categories);
+            if let Some(overridden_categories) = &event_occurrence_override.get_overridden_category_strings() {
+                indexed_categories.insert_override(
+                    timestamp,
+                    &overridden_categories.iter().map(InternedString::from).collect(),
+                );
             }
         } else {
             self.rebuild_indexed_categories()?;
         }
 
         if let Some(ref mut indexed_location_type) = self.indexed_location_type {
-            if let Some(overridden_location_type) = &event_occurrence_override
-                .indexed_properties
-                .extract_all_location_type_strings()
-            {
-                indexed_location_type.insert_override(timestamp, overridden_location_type);
+            if let Some(overridden_location_type) = &event_occurrence_override.get_overridden_location_type_strings() {
+                indexed_location_type.insert_override(
+                    timestamp,
+                    &overridden_location_type.iter().map(InternedString::from).collect(),
+                );
             }
         } else {
             self.rebuild_indexed_location_type()?;
         }
 
         if let Some(ref mut indexed_related_to) = self.indexed_related_to {
-            if let Some(overridden_related_to_set) = &event_occurrence_override
-                .indexed_properties
-                .extract_all_related_to_key_value_pairs()
-            {
+            if let Some(overridden_related_to_set) = &event_occurrence_override.get_overridden_related_to_key_value_pairs() {
                 indexed_related_to.insert_override(timestamp, overridden_related_to_set);
             }
         } else {
@@ -640,30 +876,45 @@ impl Event {
 
         if let Some(ref mut indexed_geo) = self.indexed_geo {
             // Allow events with GEO defined to be overridden to make GEO blank (specific events online only).
-            if event_occurrence_override.indexed_properties.geo.is_some() {
-                if let Some(overridden_geo_point) = &event_occurrence_override.indexed_properties.extract_geo_point() {
-                    // If a non-blank GEO property defined override is present, insert this.
-                    indexed_geo.insert_override(timestamp, &HashSet::from([overridden_geo_point.to_owned()]));
-                } else {
-                    // If a blank GEO property defined override is present, insert the blank.
-                    indexed_geo.insert_override(timestamp, &HashSet::from([]));
-                }
+            if let Some(overridden_geo_set) = &event_occurrence_override.get_overridden_geo_points() {
+                indexed_geo.insert_override(timestamp, overridden_geo_set);
             }
         } else {
             self.rebuild_indexed_geo()?;
         }
 
         if let Some(ref mut indexed_class) = self.indexed_class {
-            if let Some(overridden_class) =
-                &event_occurrence_override.indexed_properties.extract_class()
-            {
-                indexed_class
-                    .insert_override(timestamp, &HashSet::from([overridden_class.clone()]));
+            if let Some(overridden_class_set) = &event_occurrence_override.get_overridden_class_strings() {
+                indexed_class.insert_override(
+                    timestamp,
+                    &overridden_class_set.iter().map(InternedString::from).collect(),
+                );
             }
         } else {
             self.rebuild_indexed_class()?;
         }
 
+        if let Some(ref mut indexed_location) = self.indexed_location {
+            if let Some(overridden_location_set) = &event_occurrence_override.get_overridden_location_strings() {
+                indexed_location.insert_override(
+                    timestamp,
+                    &overridden_location_set.iter().map(InternedString::from).collect(),
+                );
+            }
+        } else {
+            self.rebuild_indexed_location()?;
+        }
+
+        if let Some(ref mut indexed_x_properties) = self.indexed_x_properties {
+            let x_property_set = self.passive_properties.extract_x_property_key_value_pairs();
+
+            if let Some(overridden_x_property_set) = &event_occurrence_override.get_overridden_x_property_key_value_pairs(&x_property_set) {
+                indexed_x_properties.insert_override(timestamp, overridden_x_property_set);
+            }
+        } else {
+            self.rebuild_indexed_x_properties()?;
+        }
+
         Ok(true)
     }
 
@@ -679,7 +930,7 @@ impl Event {
             indexed_categories.remove_override(timestamp);
         } else {
             self.indexed_categories = Some(
-                InvertedEventIndex::<String>::new_from_event_categories(&*self),
+                InvertedEventIndex::<InternedString>::new_from_event_categories(&*self),
             );
         }
 
@@ -687,7 +938,7 @@ impl Event {
             indexed_location_type.remove_override(timestamp);
         } else {
             self.indexed_location_type = Some(
-                InvertedEventIndex::<String>::new_from_event_location_type(&*self),
+                InvertedEventIndex::<InternedString>::new_from_event_location_type(&*self),
             );
         }
 
@@ -709,7 +960,21 @@ impl Event {
             indexed_class.remove_override(timestamp);
         } else {
             self.indexed_class =
-                Some(InvertedEventIndex::<String>::new_from_event_class(&*self));
+                Some(InvertedEventIndex::<InternedString>::new_from_event_class(&*self));
+        }
+
+        if let Some(ref mut indexed_location) = self.indexed_location {
+            indexed_location.remove_override(timestamp);
+        } else {
+            self.indexed_location =
+                Some(InvertedEventIndex::<InternedString>::new_from_event_location(&*self));
+        }
+
+        if let Some(ref mut indexed_x_properties) = self.indexed_x_properties {
+            indexed_x_properties.remove_override(timestamp);
+        } else {
+            self.indexed_x_properties =
+                Some(InvertedEventIndex::<KeyValuePair>::new_from_event_x_properties(&*self));
         }
 
         Ok(override_removed)
@@ -771,6 +1036,159 @@ impl Event {
 
         Ok(result)
     }
+
+    /// Returns whether the event recurs indefinitely (an RRULE without either COUNT or UNTIL).
+    pub fn is_open_ended(&self) -> bool {
+        self.schedule_properties
+            .parsed_rrule_set
+            .as_ref()
+            .is_some_and(|parsed_rrule_set| {
+                parsed_rrule_set
+                    .get_rrule()
+                    .iter()
+                    .any(|rrule| rrule.get_count().is_none() && rrule.get_until().is_none())
+            })
+    }
+
+    /// Returns the DTEND timestamp of the final occurrence of the event, or None if the event
+    /// recurs indefinitely (or has no occurrences).
+    pub fn get_last_occurrence_dtend_timestamp(&self) -> Result<Option<i64>, String> {
+        if self.is_open_ended() {
+            return Ok(None);
+        }
+
+        let event_occurrence_iterator = EventOccurrenceIterator::new(
+            &self.schedule_properties,
+            &self.overrides,
+            None,
+            None,
+            None,
+            None,
+        )?;
+
+        Ok(
+            event_occurrence_iterator
+                .map(|(_dtstart_timestamp, dtend_timestamp, _override)| dtend_timestamp)
+                .max()
+        )
+    }
+
+    /// Returns whether the event schedule (RRULE, RDATE, EXDATE etc.) yields an occurrence
+    /// starting at the provided timestamp.
+    ///
+    /// Overrides are disregarded so that occurrences cancelled via override remain occurrences.
+    pub fn is_occurrence(&self, timestamp: i64) -> Result<bool, String> {
+        let overrides = BTreeMap::new();
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &self.schedule_properties,
+            &overrides,
+            Some(1),
+            Some(LowerBoundFilterCondition::GreaterEqualThan(FilterProperty::DtStart(timestamp))),
+            Some(UpperBoundFilterCondition::LessEqualThan(FilterProperty::DtStart(timestamp))),
+            None,
+        )?;
+
+        Ok(
+            event_occurrence_iterator
+                .next()
+                .is_some_and(|(dtstart_timestamp, _dtend_timestamp, _override)| dtstart_timestamp == timestamp)
+        )
+    }
+
+    /// Returns the (DTSTART, DTEND, override) of the most recent occurrence starting before the
+    /// provided timestamp, expanding the occurrences backwards from it in bounded windows (so
+    /// indefinitely recurring events are supported, without holding every prior occurrence).
+    ///
+    /// Occurrences cancelled via override are disregarded.
+    pub fn get_most_recent_occurrence_before(&self, timestamp: i64) -> Result<Option<(i64, i64, Option<EventOccurrenceOverride>)>, String> {
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &self.schedule_properties,
+            &self.overrides,
+            None,
+            None,
+            Some(UpperBoundFilterCondition::LessThan(FilterProperty::DtStart(timestamp))),
+            None,
+        )?;
+
+        event_occurrence_iterator.try_next_back()
+    }
+
+    /// Adds an ad-hoc occurrence starting at the provided timestamp to the event schedule (as a
+    /// UTC RDATE), rebuilding the parsed RRuleSet.
+    ///
+    /// Returns false (leaving the event unchanged) if the event schedule already yields an
+    /// occurrence at the provided timestamp, and an error if it is excluded (e.g. via EXDATE).
+    pub fn add_occurrence(&mut self, timestamp: i64) -> Result<bool, String> {
+        if self.is_occurrence(timestamp)? {
+            return Ok(false);
+        }
+
+        let mut schedule_properties = self.schedule_properties.clone();
+
+        // Without an RRULE or RDATE, the DTSTART occurrence is only generated implicitly, so it
+        // needs to be retained as an RDATE alongside the new one.
+        if schedule_properties.rrules.as_ref().is_none_or(|rrules| rrules.is_empty()) && schedule_properties.rdates.as_ref().is_none_or(|rdates| rdates.is_empty()) {
+            if let Some(dtstart) = schedule_properties.dtstart.as_ref() {
+                let dtstart_rdate = RDateProperty::new_from(dtstart);
+
+                schedule_properties.insert(EventProperty::RDate(dtstart_rdate))?;
+            }
+        }
+
+        schedule_properties.insert(EventProperty::RDate(RDateProperty::new_from_utc_timestamp(&timestamp)))?;
+        schedule_properties.build_parsed_rrule_set()?;
+
+        let existing_schedule_properties = std::mem::replace(&mut self.schedule_properties, schedule_properties);
+
+        if !self.is_occurrence(timestamp)? {
+            self.schedule_properties = existing_schedule_properties;
+
+            return Err(
+                format!("Event with UID: '{}' excludes an occurrence at: '{}' (e.g. via EXDATE)", self.uid.uid, DateTime::from(timestamp))
+            );
+        }
+
+        Ok(true)
+    }
+
+    /// Returns the instance of the event (with any occurrence override applied) starting at the
+    /// provided timestamp, if the event schedule yields an occurrence at it (resolved as per the
+    /// DST policy).
+    ///
+    /// Occurrences cancelled via override are not returned.
+    pub fn get_instance(&self, timestamp: i64, dst_policy: DstPolicy) -> Result<Option<EventInstance>, String> {
+        let mut event_instance_iterator = EventInstanceIterator::new(
+            self,
+            Some(1),
+            Some(LowerBoundFilterCondition::GreaterEqualThan(FilterProperty::DtStart(timestamp))),
+            Some(UpperBoundFilterCondition::LessEqualThan(FilterProperty::DtStart(timestamp))),
+            None,
+        )?
+        .with_dst_policy(dst_policy);
+
+        Ok(
+            event_instance_iterator
+                .next()
+                .filter(|event_instance| event_instance.dtstart.get_utc_timestamp() == timestamp)
+        )
+    }
+
+    /// Iterate through every instance of the event (with any occurrence overrides applied) in
+    /// chronological order.
+    pub fn instances(&self) -> Result<EventInstanceIterator<'_>, String> {
+        EventInstanceIterator::new(self, None, None, None, None)
+    }
+}
+
+// Components (e.g. PARTICIPANT and VALARM) are rendered as a single BEGIN content line, so are
+// named after the component instead.
+fn get_content_line_property_name(content_line: &ContentLine) -> String {
+    if content_line.0 == "BEGIN" {
+        content_line.2.split_whitespace().next().unwrap_or_default().to_owned()
+    } else {
+        content_line.0.to_owned()
+    }
 }
 
 impl QueryableEntity for Event {
@@ -790,8 +1208,10 @@ impl ICalendarComponent for Event {
         serializable_properties.insert(self.uid.to_content_line_with_context(context));
         serializable_properties.insert(self.last_modified.to_content_line_with_context(context));
 
-        if let Some(rrule_property) = &self.schedule_properties.rrule {
-            serializable_properties.insert(rrule_property.to_content_line_with_context(context));
+        if let Some(rrules_properties) = &self.schedule_properties.rrules {
+            for rrule_property in rrules_properties {
+                serializable_properties.insert(rrule_property.to_content_line_with_context(context));
+            }
         }
 
         if let Some(exrule_property) = &self.schedule_properties.exrule {
@@ -834,6 +1254,10 @@ impl ICalendarComponent for Event {
             serializable_properties.insert(class_property.to_content_line_with_context(context));
         }
 
+        if let Some(location_property) = &self.indexed_properties.location {
+            serializable_properties.insert(location_property.to_content_line_with_context(context));
+        }
+
         if let Some(related_to_properties) = &self.indexed_properties.related_to {
             for related_to_property in related_to_properties {
                 serializable_properties.insert(related_to_property.to_content_line_with_context(context));
@@ -858,9 +1282,10 @@ impl ICalendarComponent for Event {
 mod test {
     use super::*;
 
-    use crate::IndexedConclusion;
+    use crate::{IndexedConclusion, InternedString};
 
     use crate::testing::macros::build_property_from_ical;
+    use crate::testing::utils::{build_event_from_ical, build_event_override_from_ical};
 
     use std::collections::{HashSet, BTreeMap, BTreeSet};
 
@@ -884,7 +1309,7 @@ mod test {
             last_modified: build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
 
             schedule_properties: ScheduleProperties {
-                rrule: None,
+                rrules: None,
                 exrule: None,
                 rdates: None,
                 exdates: None,
@@ -897,6 +1322,7 @@ mod test {
             indexed_properties: IndexedProperties {
                 geo: None,
                 class: None,
+                location: None,
                 related_to: None,
                 location_type: None,
                 categories: Some(HashSet::from([build_property_from_ical!(
@@ -924,11 +1350,13 @@ mod test {
                                 "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO,CATEGORY_THREE,CATEGORY_FOUR"
                             )])),
                             class: None,
+                            location: None,
                         },
                         passive_properties: PassiveProperties::new(),
                         dtstart: None,
                         dtend: None,
                         duration: None,
+                        override_mode: None,
                     },
                 ),
                 // Override 200 has only some event categories (missing CATEGORY_THREE)
@@ -945,11 +1373,13 @@ mod test {
                                 "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO"
                             )])),
                             class: None,
+                            location: None,
                         },
                         passive_properties: PassiveProperties::new(),
                         dtstart: None,
                         dtend: None,
                         duration: None,
+                        override_mode: None,
                     },
                 ),
                 // Override 300 has no overridden categories
@@ -962,6 +1392,7 @@ mod test {
                         dtstart: None,
                         dtend: None,
                         duration: None,
+                        override_mode: None,
                     },
                 ),
                 // Override 400 has removed all categories
@@ -975,11 +1406,13 @@ mod test {
                             location_type: None,
                             categories: Some(HashSet::new()),
                             class: None,
+                            location: None,
                         },
                         passive_properties: PassiveProperties::new(),
                         dtstart: None,
                         dtend: None,
                         duration: None,
+                        override_mode: None,
                     },
                 ),
                 // Override 500 has no base event categories, but does have CATEGORY_FOUR
@@ -996,11 +1429,13 @@ mod test {
                                 "CATEGORIES:CATEGORY_FOUR"
                             )])),
                             class: None,
+                            location: None,
                         },
                         passive_properties: PassiveProperties::new(),
                         dtstart: None,
                         dtend: None,
                         duration: None,
+                        override_mode: None,
                     },
                 ),
             ]),
@@ -1009,29 +1444,31 @@ mod test {
             indexed_related_to: None,
             indexed_geo: None,
             indexed_class: None,
+            indexed_location: None,
+            indexed_x_properties: None,
         };
 
         let mut indexed_categories =
-            InvertedEventIndex::<String>::new_from_event_categories(&event);
+            InvertedEventIndex::<InternedString>::new_from_event_categories(&event);
 
         assert_eq!(
             indexed_categories,
             InvertedEventIndex {
                 terms: HashMap::from([
                     (
-                        String::from("CATEGORY_ONE"),
+                        InternedString::from("CATEGORY_ONE"),
                         IndexedConclusion::Include(Some(HashSet::from([400, 500]))),
                     ),
                     (
-                        String::from("CATEGORY_TWO"),
+                        InternedString::from("CATEGORY_TWO"),
                         IndexedConclusion::Include(Some(HashSet::from([400, 500]))),
                     ),
                     (
-                        String::from("CATEGORY_THREE"),
+                        InternedString::from("CATEGORY_THREE"),
                         IndexedConclusion::Include(Some(HashSet::from([200, 400, 500]))),
                     ),
                     (
-                        String::from("CATEGORY_FOUR"),
+                        InternedString::from("CATEGORY_FOUR"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 500]))),
                     ),
                 ])
@@ -1040,7 +1477,7 @@ mod test {
 
         indexed_categories.insert_override(
             600,
-            &HashSet::from([String::from("CATEGORY_ONE"), String::from("CATEGORY_FIVE")]),
+            &HashSet::from([InternedString::from("CATEGORY_ONE"), InternedString::from("CATEGORY_FIVE")]),
         );
 
         assert_eq!(
@@ -1048,23 +1485,23 @@ mod test {
             InvertedEventIndex {
                 terms: HashMap::from([
                     (
-                        String::from("CATEGORY_ONE"),
+                        InternedString::from("CATEGORY_ONE"),
                         IndexedConclusion::Include(Some(HashSet::from([400, 500]))),
                     ),
                     (
-                        String::from("CATEGORY_TWO"),
+                        InternedString::from("CATEGORY_TWO"),
                         IndexedConclusion::Include(Some(HashSet::from([400, 500, 600]))),
                     ),
                     (
-                        String::from("CATEGORY_THREE"),
+                        InternedString::from("CATEGORY_THREE"),
                         IndexedConclusion::Include(Some(HashSet::from([200, 400, 500, 600]))),
                     ),
                     (
-                        String::from("CATEGORY_FOUR"),
+                        InternedString::from("CATEGORY_FOUR"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 500]))),
                     ),
                     (
-                        String::from("CATEGORY_FIVE"),
+                        InternedString::from("CATEGORY_FIVE"),
                         IndexedConclusion::Exclude(Some(HashSet::from([600]))),
                     ),
                 ])
@@ -1078,23 +1515,23 @@ mod test {
             InvertedEventIndex {
                 terms: HashMap::from([
                     (
-                        String::from("CATEGORY_ONE"),
+                        InternedString::from("CATEGORY_ONE"),
                         IndexedConclusion::Include(Some(HashSet::from([400, 500]))),
                     ),
                     (
-                        String::from("CATEGORY_TWO"),
+                        InternedString::from("CATEGORY_TWO"),
                         IndexedConclusion::Include(Some(HashSet::from([400, 500, 600]))),
                     ),
                     (
-                        String::from("CATEGORY_THREE"),
+                        InternedString::from("CATEGORY_THREE"),
                         IndexedConclusion::Include(Some(HashSet::from([200, 400, 500, 600]))),
                     ),
                     (
-                        String::from("CATEGORY_FOUR"),
+                        InternedString::from("CATEGORY_FOUR"),
                         IndexedConclusion::Exclude(Some(HashSet::from([500]))),
                     ),
                     (
-                        String::from("CATEGORY_FIVE"),
+                        InternedString::from("CATEGORY_FIVE"),
                         IndexedConclusion::Exclude(Some(HashSet::from([600]))),
                     ),
                 ])
@@ -1108,19 +1545,19 @@ mod test {
             InvertedEventIndex {
                 terms: HashMap::from([
                     (
-                        String::from("CATEGORY_ONE"),
+                        InternedString::from("CATEGORY_ONE"),
                         IndexedConclusion::Include(Some(HashSet::from([400]))),
                     ),
                     (
-                        String::from("CATEGORY_TWO"),
+                        InternedString::from("CATEGORY_TWO"),
                         IndexedConclusion::Include(Some(HashSet::from([400, 600]))),
                     ),
                     (
-                        String::from("CATEGORY_THREE"),
+                        InternedString::from("CATEGORY_THREE"),
                         IndexedConclusion::Include(Some(HashSet::from([200, 400, 600]))),
                     ),
                     (
-                        String::from("CATEGORY_FIVE"),
+                        InternedString::from("CATEGORY_FIVE"),
                         IndexedConclusion::Exclude(Some(HashSet::from([600]))),
                     ),
                 ])
@@ -1139,7 +1576,7 @@ mod test {
                 last_modified: build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
 
                 schedule_properties: ScheduleProperties {
-                    rrule: Some(build_property_from_ical!(RRuleProperty, "RRULE:FREQ=WEEKLY;UNTIL=20211231T183000Z;INTERVAL=1;BYDAY=TU,TH")),
+                    rrules: Some(HashSet::from([build_property_from_ical!(RRuleProperty, "RRULE:FREQ=WEEKLY;UNTIL=20211231T183000Z;INTERVAL=1;BYDAY=TU,TH")])),
                     exrule: None,
                     rdates: None,
                     exdates: None,
@@ -1152,6 +1589,7 @@ mod test {
                 indexed_properties: IndexedProperties {
                     geo: None,
                     class: None,
+                    location: None,
                     location_type: None,
                     categories: Some(HashSet::from([build_property_from_ical!(CategoriesProperty, "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO,\"CATEGORY (THREE)\"")])),
                     related_to: None,
@@ -1167,10 +1605,94 @@ mod test {
                 indexed_related_to: None,
                 indexed_geo: None,
                 indexed_class: None,
+                indexed_location: None,
+                indexed_x_properties: None,
             }
         );
     }
 
+    #[test]
+    fn test_parse_ical_with_fixed_now() {
+        let now = chrono::DateTime::from_timestamp(1609430400, 0);
+
+        // A LAST-MODIFIED not provided defaults to the (fixed) now.
+        assert_eq!(
+            clock::with_now(now, || Event::parse_ical("event_UID", "SUMMARY:Summary")).unwrap().last_modified,
+            build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201231T160000Z"),
+        );
+
+        assert_eq!(
+            clock::with_now(now, || Event::parse_ical("event_UID", "SUMMARY:Summary LAST-MODIFIED:20201230T173000Z")).unwrap().last_modified,
+            build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
+        );
+    }
+
+    #[test]
+    fn test_patch() {
+        let mut event = Event::parse_ical(
+            "event_UID",
+            "LAST-MODIFIED:20201230T173000Z DTSTART:20201231T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3 CATEGORIES:CATEGORY_ONE CATEGORIES:CATEGORY_TWO GEO:51.5074;-0.1278 SUMMARY:Summary text",
+        ).unwrap();
+
+        event.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210107T183000Z", "CATEGORIES:CATEGORY_THREE").unwrap(),
+            false,
+        ).unwrap();
+
+        let patch = Event::parse_ical(
+            "event_UID",
+            "LAST-MODIFIED:20210101T090000Z CATEGORIES:CATEGORY_THREE GEO:51.4517446;-1.004574",
+        ).unwrap();
+
+        let patched_event = event.patch(&patch).unwrap();
+
+        assert_eq!(
+            patched_event.to_rendered_content_lines(),
+            vec![
+                String::from("CATEGORIES:CATEGORY_THREE"),
+                String::from("DTSTART:20201231T183000Z"),
+                String::from("GEO:51.4517446;-1.004574"),
+                String::from("LAST-MODIFIED:20210101T090000Z"),
+                String::from("RRULE:COUNT=3;FREQ=WEEKLY;INTERVAL=1"),
+                String::from("SUMMARY:Summary text"),
+                String::from("UID:event_UID"),
+            ],
+        );
+
+        assert_eq!(patched_event.overrides, event.overrides);
+
+        // Patched VALARM components replace all existing VALARM components, leaving any other
+        // components (e.g. PARTICIPANT) as they are.
+        let event = Event::parse_ical(
+            "event_UID",
+            "LAST-MODIFIED:20201230T173000Z DTSTART:20201231T183000Z BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:SPEAKER_ONE END:PARTICIPANT BEGIN:VALARM UID:ALARM_ONE ACTION:DISPLAY TRIGGER:-PT15M END:VALARM",
+        ).unwrap();
+
+        let patch = Event::parse_ical(
+            "event_UID",
+            "LAST-MODIFIED:20210101T090000Z BEGIN:VALARM UID:ALARM_ONE ACTION:DISPLAY TRIGGER:-PT15M ACKNOWLEDGED:20201231T181500Z END:VALARM",
+        ).unwrap();
+
+        assert_eq!(
+            event.patch(&patch).unwrap().to_rendered_content_lines(),
+            vec![
+                String::from("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:SPEAKER_ONE END:PARTICIPANT"),
+                String::from("BEGIN:VALARM UID:ALARM_ONE ACTION:DISPLAY TRIGGER:-PT15M ACKNOWLEDGED:20201231T181500Z END:VALARM"),
+                String::from("DTSTART:20201231T183000Z"),
+                String::from("LAST-MODIFIED:20210101T090000Z"),
+                String::from("UID:event_UID"),
+            ],
+        );
+
+        // Patches of a different event are rejected.
+        let patch = Event::parse_ical("other_event_UID", "CATEGORIES:CATEGORY_THREE").unwrap();
+
+        assert_eq!(
+            event.patch(&patch),
+            Err(String::from("Inserted event UID: other_event_UID does not match existing UID: event_UID")),
+        );
+    }
+
     #[test]
     fn test_build_parsed_rrule_set() {
         let ical: &str = "RRULE:FREQ=WEEKLY;UNTIL=20211231T183000Z;INTERVAL=1;BYDAY=TU,TH DTSTART:16010101T020000 LAST-MODIFIED:20201230T173000Z";
@@ -1184,10 +1706,10 @@ mod test {
                 last_modified: build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
 
                 schedule_properties: ScheduleProperties {
-                    rrule: Some(build_property_from_ical!(
+                    rrules: Some(HashSet::from([build_property_from_ical!(
                         RRuleProperty,
                         "RRULE:FREQ=WEEKLY;UNTIL=20211231T183000Z;INTERVAL=1;BYDAY=TU,TH"
-                    )),
+                    )])),
                     exrule: None,
                     rdates: None,
                     exdates: None,
@@ -1210,6 +1732,8 @@ mod test {
                 indexed_related_to: None,
                 indexed_geo: None,
                 indexed_class: None,
+                indexed_location: None,
+                indexed_x_properties: None,
             }
         );
 
@@ -1229,10 +1753,10 @@ mod test {
                 last_modified: build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
 
                 schedule_properties: ScheduleProperties {
-                    rrule: Some(build_property_from_ical!(
+                    rrules: Some(HashSet::from([build_property_from_ical!(
                         RRuleProperty,
                         "RRULE:FREQ=WEEKLY;UNTIL=20211231T183000Z;INTERVAL=1;BYDAY=TU,TH"
-                    )),
+                    )])),
                     exrule: None,
                     rdates: None,
                     exdates: None,
@@ -1252,6 +1776,8 @@ mod test {
                 indexed_geo: None,
                 indexed_related_to: None,
                 indexed_class: None,
+                indexed_location: None,
+                indexed_x_properties: None,
             }
         );
 
@@ -1276,6 +1802,7 @@ mod test {
                 location_type: None,
                 categories: Some(HashSet::from([build_property_from_ical!(CategoriesProperty, "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO,CATEGORY_THREE")])),
                 class: None,
+                location: None,
             },
             passive_properties: PassiveProperties {
                 properties: BTreeSet::from([build_property_from_ical!(PassiveProperty, "DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\":The Fall'98 Wild Wizards Conference - - Las Vegas\\, NV\\, USA")]),
@@ -1283,6 +1810,7 @@ mod test {
             dtstart: Some(build_property_from_ical!(DTStartProperty, "DTSTART:20210112T183000Z")),
             dtend: None,
             duration: None,
+            override_mode: None,
         };
 
         assert_eq!(
@@ -1299,7 +1827,7 @@ mod test {
                 last_modified: build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
 
                 schedule_properties: ScheduleProperties {
-                    rrule: Some(build_property_from_ical!(RRuleProperty, "RRULE:FREQ=WEEKLY;UNTIL=20210331T183000Z;INTERVAL=1;BYDAY=TU")),
+                    rrules: Some(HashSet::from([build_property_from_ical!(RRuleProperty, "RRULE:FREQ=WEEKLY;UNTIL=20210331T183000Z;INTERVAL=1;BYDAY=TU")])),
                     exrule: None,
                     rdates: None,
                     exdates: None,
@@ -1324,6 +1852,7 @@ mod test {
                                 location_type: None,
                                 categories: Some(HashSet::from([build_property_from_ical!(CategoriesProperty, "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO,CATEGORY_THREE")])),
                                 class: None,
+                                location: None,
                             },
                             passive_properties: PassiveProperties {
                                 properties: BTreeSet::from([build_property_from_ical!(PassiveProperty, "DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\":The Fall'98 Wild Wizards Conference - - Las Vegas\\, NV\\, USA")]),
@@ -1331,6 +1860,7 @@ mod test {
                             dtstart: Some(build_property_from_ical!(DTStartProperty, "DTSTART:20210112T183000Z")),
                             dtend: None,
                             duration: None,
+                            override_mode: None,
                         }
                     )
                 ]),
@@ -1338,15 +1868,15 @@ mod test {
                     InvertedEventIndex {
                         terms: HashMap::from([
                                         (
-                                            String::from("CATEGORY_ONE"),
+                                            InternedString::from("CATEGORY_ONE"),
                                             IndexedConclusion::Exclude(Some(HashSet::from([1610476200]))),
                                         ),
                                         (
-                                            String::from("CATEGORY_TWO"),
+                                            InternedString::from("CATEGORY_TWO"),
                                             IndexedConclusion::Exclude(Some(HashSet::from([1610476200]))),
                                         ),
                                         (
-                                            String::from("CATEGORY_THREE"),
+                                            InternedString::from("CATEGORY_THREE"),
                                             IndexedConclusion::Exclude(Some(HashSet::from([1610476200]))),
                                         ),
                                     ])
@@ -1372,6 +1902,16 @@ mod test {
                         terms: HashMap::from([])
                     }
                 ),
+                indexed_location:    Some(
+                    InvertedEventIndex {
+                        terms: HashMap::from([])
+                    }
+                ),
+                indexed_x_properties: Some(
+                    InvertedEventIndex {
+                        terms: HashMap::from([])
+                    }
+                ),
             },
         );
 
@@ -1388,6 +1928,7 @@ mod test {
                             location_type: None,
                             categories: Some(HashSet::from([build_property_from_ical!(CategoriesProperty, "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO,CATEGORY_THREE")])),
                             class: None,
+                            location: None,
                         },
                         passive_properties: PassiveProperties {
                             properties: BTreeSet::from([build_property_from_ical!(PassiveProperty, "DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\":The Fall'98 Wild Wizards Conference - - Las Vegas\\, NV\\, USA")]),
@@ -1395,6 +1936,7 @@ mod test {
                         dtstart: Some(build_property_from_ical!(DTStartProperty, "DTSTART:20210112T183000Z")),
                         dtend: None,
                         duration: None,
+                        override_mode: None,
                     }
                 )
             )
@@ -1408,10 +1950,10 @@ mod test {
                 last_modified: build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
 
                 schedule_properties: ScheduleProperties {
-                    rrule: Some(build_property_from_ical!(
+                    rrules: Some(HashSet::from([build_property_from_ical!(
                         RRuleProperty,
                         "RRULE:FREQ=WEEKLY;UNTIL=20210331T183000Z;INTERVAL=1;BYDAY=TU"
-                    )),
+                    )])),
                     exrule: None,
                     rdates: None,
                     exdates: None,
@@ -1434,6 +1976,8 @@ mod test {
                 indexed_related_to: Some(InvertedEventIndex::default()),
                 indexed_geo: Some(InvertedEventIndex::default()),
                 indexed_class: Some(InvertedEventIndex::default()),
+                indexed_location: Some(InvertedEventIndex::default()),
+                indexed_x_properties: Some(InvertedEventIndex::default()),
             }
         );
 
@@ -1444,6 +1988,65 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_occurrence_override_replacement() {
+        let mut event = build_event_from_ical(
+            "event_UID",
+            vec![
+                "RRULE:FREQ=WEEKLY;UNTIL=20210331T183000Z;INTERVAL=1;BYDAY=TU",
+                "DTSTART:20201231T183000Z",
+                "CATEGORIES:CATEGORY_ONE",
+                "CLASS:PUBLIC",
+            ],
+        );
+
+        event.override_occurrence(
+            &build_event_override_from_ical("20210112T183000Z", vec!["CATEGORIES:CATEGORY_TWO", "CLASS:PRIVATE"]),
+            true,
+        ).unwrap();
+
+        // Replacing the override with one overriding different terms (and nothing at all).
+        event.override_occurrence(
+            &build_event_override_from_ical("20210112T183000Z", vec!["CATEGORIES:CATEGORY_THREE"]),
+            true,
+        ).unwrap();
+
+        assert_eq!(
+            event.indexed_categories,
+            Some(
+                InvertedEventIndex {
+                    terms: HashMap::from([
+                        (
+                            InternedString::from("CATEGORY_ONE"),
+                            IndexedConclusion::Include(Some(HashSet::from([1610476200]))),
+                        ),
+                        (
+                            InternedString::from("CATEGORY_THREE"),
+                            IndexedConclusion::Exclude(Some(HashSet::from([1610476200]))),
+                        ),
+                    ])
+                }
+            ),
+        );
+
+        assert_eq!(
+            event.indexed_class,
+            Some(
+                InvertedEventIndex {
+                    terms: HashMap::from([
+                        (
+                            InternedString::from("PUBLIC"),
+                            IndexedConclusion::Include(None),
+                        ),
+                    ])
+                }
+            ),
+        );
+
+        assert_eq!(event.indexed_categories, Some(InvertedEventIndex::<InternedString>::new_from_event_categories(&event)));
+        assert_eq!(event.indexed_class, Some(InvertedEventIndex::<InternedString>::new_from_event_class(&event)));
+    }
+
     #[test]
     fn test_related_to() {
         let ical: &str = "RELATED-TO:ParentUID_One RELATED-TO;RELTYPE=PARENT:ParentUID_Two RELATED-TO;RELTYPE=CHILD:ChildUID LAST-MODIFIED:20201230T173000Z";
@@ -1461,7 +2064,7 @@ mod test {
                 last_modified: build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
 
                 schedule_properties: ScheduleProperties {
-                    rrule: None,
+                    rrules: None,
                     exrule: None,
                     rdates: None,
                     exdates: None,
@@ -1474,6 +2077,7 @@ mod test {
                 indexed_properties: IndexedProperties {
                     geo: None,
                     class: None,
+                    location: None,
                     related_to: Some(HashSet::from([
                         build_property_from_ical!(
                             RelatedToProperty,
@@ -1505,6 +2109,8 @@ mod test {
                 indexed_related_to: None,
                 indexed_geo: None,
                 indexed_class: None,
+                indexed_location: None,
+                indexed_x_properties: None,
             }
         );
     }
@@ -1522,11 +2128,13 @@ mod test {
                     "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO,CATEGORY_THREE,CATEGORY_FOUR"
                 )])),
                 class: None,
+                location: None,
             },
             passive_properties: PassiveProperties::new(),
             dtstart: None,
             dtend: None,
             duration: None,
+            override_mode: None,
         }
     }
 
@@ -1543,11 +2151,13 @@ mod test {
                     "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO"
                 )])),
                 class: None,
+                location: None,
             },
             passive_properties: PassiveProperties::new(),
             dtstart: None,
             dtend: None,
             duration: None,
+            override_mode: None,
         }
     }
 
@@ -1560,6 +2170,7 @@ mod test {
             dtstart: None,
             dtend: None,
             duration: None,
+            override_mode: None,
         }
     }
 
@@ -1573,11 +2184,13 @@ mod test {
                 location_type: None,
                 categories: Some(HashSet::new()),
                 class: None,
+                location: None,
             },
             passive_properties: PassiveProperties::new(),
             dtstart: None,
             dtend: None,
             duration: None,
+            override_mode: None,
         }
     }
 
@@ -1594,11 +2207,13 @@ mod test {
                     "CATEGORIES:CATEGORY_FOUR"
                 )])),
                 class: None,
+                location: None,
             },
             passive_properties: PassiveProperties::new(),
             dtstart: None,
             dtend: None,
             duration: None,
+            override_mode: None,
         }
     }
 
@@ -1608,7 +2223,7 @@ mod test {
             last_modified: build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
 
             schedule_properties: ScheduleProperties {
-                rrule: None,
+                rrules: None,
                 exrule: None,
                 rdates: None,
                 exdates: None,
@@ -1621,6 +2236,7 @@ mod test {
             indexed_properties: IndexedProperties {
                 geo: None,
                 class: None,
+                location: None,
                 related_to: None,
                 location_type: None,
                 categories: Some(HashSet::from([build_property_from_ical!(
@@ -1645,6 +2261,8 @@ mod test {
             indexed_related_to: None,
             indexed_geo: None,
             indexed_class: None,
+            indexed_location: None,
+            indexed_x_properties: None,
         }
     }
 
@@ -1691,8 +2309,8 @@ mod test {
             properties: BTreeSet::from([
                 build_property_from_ical!(PassiveProperty, "DESCRIPTION:Description text."),
                 build_property_from_ical!(PassiveProperty, "SUMMARY:Summary text."),
-                build_property_from_ical!(PassiveProperty, "IMAGE:Image one."),
-                build_property_from_ical!(PassiveProperty, "IMAGE:Image two."),
+                build_property_from_ical!(PassiveProperty, "IMAGE:https://example.com/image_one.png"),
+                build_property_from_ical!(PassiveProperty, "IMAGE:https://example.com/image_two.png"),
                 build_property_from_ical!(PassiveProperty, "X-SINGLE-PROP:X single prop text."),
                 build_property_from_ical!(PassiveProperty, "X-MULTI-PROP:X multi prop one text."),
                 build_property_from_ical!(PassiveProperty, "X-MULTI-PROP:X multi prop two text."),
@@ -1717,8 +2335,8 @@ mod test {
                 (
                     String::from("IMAGE"),
                     vec![
-                        build_property_from_ical!(PassiveProperty, "IMAGE:Image one."),
-                        build_property_from_ical!(PassiveProperty, "IMAGE:Image two."),
+                        build_property_from_ical!(PassiveProperty, "IMAGE:https://example.com/image_one.png"),
+                        build_property_from_ical!(PassiveProperty, "IMAGE:https://example.com/image_two.png"),
                     ],
                 ),
                 (
@@ -1738,6 +2356,28 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_passive_properties_get_sequence() {
+        assert_eq!(PassiveProperties::new().get_sequence(), None);
+
+        let passive_properties = PassiveProperties {
+            properties: BTreeSet::from([
+                build_property_from_ical!(PassiveProperty, "DESCRIPTION:Description text."),
+                build_property_from_ical!(PassiveProperty, "SEQUENCE:3"),
+            ])
+        };
+
+        assert_eq!(passive_properties.get_sequence(), Some(3));
+
+        let passive_properties = PassiveProperties {
+            properties: BTreeSet::from([
+                build_property_from_ical!(PassiveProperty, "SEQUENCE:INVALID"),
+            ])
+        };
+
+        assert_eq!(passive_properties.get_sequence(), None);
+    }
+
     #[test]
     fn test_is_last_occurrence_between_validation() {
         let event = build_event();
@@ -1832,4 +2472,280 @@ mod test {
         event.schedule_properties.parsed_rrule_set = Some(rrule_set);
         assert!(!event.is_last_occurrence_between(lower, upper).unwrap());
     }
+
+    #[test]
+    fn test_get_last_occurrence_dtend_timestamp() {
+        let mut event = Event::new(String::from("EVENT_UID"));
+
+        let rrule_set = "DTSTART:20241225T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+        event.schedule_properties.parsed_rrule_set = Some(rrule_set);
+        assert!(event.is_open_ended());
+        assert_eq!(event.get_last_occurrence_dtend_timestamp(), Ok(None));
+
+        let rrule_set = "DTSTART:20241225T090000Z\nRRULE:FREQ=DAILY;COUNT=8".parse().unwrap();
+        event.schedule_properties.parsed_rrule_set = Some(rrule_set);
+        assert!(!event.is_open_ended());
+        assert_eq!(
+            event.get_last_occurrence_dtend_timestamp(),
+            Ok(Some(DateTime::parse_from_rfc2822("1 Jan 2025 09:00:00 +0000").unwrap().timestamp())),
+        );
+
+        let rrule_set = "DTSTART:20241225T090000Z\nRRULE:FREQ=DAILY;UNTIL=20241227T090000Z".parse().unwrap();
+        event.schedule_properties.parsed_rrule_set = Some(rrule_set);
+        assert!(!event.is_open_ended());
+        assert_eq!(
+            event.get_last_occurrence_dtend_timestamp(),
+            Ok(Some(DateTime::parse_from_rfc2822("27 Dec 2024 09:00:00 +0000").unwrap().timestamp())),
+        );
+    }
+
+    #[test]
+    fn test_is_occurrence() {
+        let mut event = Event::parse_ical(
+            "EVENT_UID",
+            "DTSTART:20210104T090000Z RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4;INTERVAL=1 EXDATE:20210111T090000Z",
+        ).unwrap();
+
+        event.validate().unwrap();
+
+        // Mon 4th Jan 2021 09:00 (first occurrence)
+        assert_eq!(event.is_occurrence(1609750800), Ok(true));
+        // Wed 6th Jan 2021 09:00
+        assert_eq!(event.is_occurrence(1609923600), Ok(true));
+        // Wed 6th Jan 2021 09:30 (correct day, wrong time)
+        assert_eq!(event.is_occurrence(1609925400), Ok(false));
+        // Tue 5th Jan 2021 09:00 (not a BYDAY)
+        assert_eq!(event.is_occurrence(1609837200), Ok(false));
+        // Mon 11th Jan 2021 09:00 (excluded by EXDATE)
+        assert_eq!(event.is_occurrence(1610355600), Ok(false));
+        // Wed 13th Jan 2021 09:00 (last occurrence)
+        assert_eq!(event.is_occurrence(1610528400), Ok(true));
+        // Mon 18th Jan 2021 09:00 (after COUNT is exhausted)
+        assert_eq!(event.is_occurrence(1610960400), Ok(false));
+    }
+
+    #[test]
+    fn test_multiple_rrules() {
+        let mut event = Event::parse_ical(
+            "EVENT_UID",
+            "DTSTART:20210104T090000Z RRULE:FREQ=WEEKLY;BYDAY=MO;COUNT=2;INTERVAL=1 RRULE:FREQ=WEEKLY;BYDAY=WE;COUNT=2;INTERVAL=1",
+        ).unwrap();
+
+        event.validate().unwrap();
+
+        assert_eq!(event.schedule_properties.rrules.as_ref().map(HashSet::len), Some(2));
+
+        // Both RRULE properties are retained (instead of the last replacing the first).
+        assert_eq!(
+            event.to_content_line_set_with_context(None)
+                .into_iter()
+                .filter(|content_line| content_line.0 == "RRULE")
+                .map(|content_line| content_line.render_ical())
+                .collect::<Vec<String>>(),
+            vec![
+                String::from("RRULE:BYDAY=MO;COUNT=2;FREQ=WEEKLY;INTERVAL=1"),
+                String::from("RRULE:BYDAY=WE;COUNT=2;FREQ=WEEKLY;INTERVAL=1"),
+            ],
+        );
+
+        // The occurrences of each RRULE are combined.
+        let occurrence_dtstart_timestamps =
+            EventOccurrenceIterator::new(&event.schedule_properties, &event.overrides, None, None, None, None)
+                .unwrap()
+                .map(|(dtstart_timestamp, _dtend_timestamp, _override)| dtstart_timestamp)
+                .collect::<Vec<i64>>();
+
+        assert_eq!(
+            occurrence_dtstart_timestamps,
+            vec![
+                1609750800, // Mon 4th Jan 2021 09:00
+                1609923600, // Wed 6th Jan 2021 09:00
+                1610355600, // Mon 11th Jan 2021 09:00
+                1610528400, // Wed 13th Jan 2021 09:00
+            ],
+        );
+
+        assert!(!event.is_open_ended());
+
+        // Any of the RRULE properties recurring indefinitely leaves the event open ended.
+        let mut event = Event::parse_ical(
+            "EVENT_UID",
+            "DTSTART:20210104T090000Z RRULE:FREQ=WEEKLY;BYDAY=MO;COUNT=2;INTERVAL=1 RRULE:FREQ=WEEKLY;BYDAY=WE;INTERVAL=1",
+        ).unwrap();
+
+        event.validate().unwrap();
+
+        assert!(event.is_open_ended());
+    }
+
+    #[test]
+    fn test_rrule_local_until() {
+        // A local date-time UNTIL is interpreted in the DTSTART timezone (09:00 EST being 14:00 UTC),
+        // so the final occurrence is included.
+        let mut event = Event::parse_ical(
+            "EVENT_UID",
+            "DTSTART;TZID=America/New_York:20210104T090000 RRULE:FREQ=DAILY;INTERVAL=1;UNTIL=20210106T090000",
+        ).unwrap();
+
+        event.validate().unwrap();
+
+        let occurrence_dtstart_timestamps =
+            EventOccurrenceIterator::new(&event.schedule_properties, &event.overrides, None, None, None, None)
+                .unwrap()
+                .map(|(dtstart_timestamp, _dtend_timestamp, _override)| dtstart_timestamp)
+                .collect::<Vec<i64>>();
+
+        assert_eq!(
+            occurrence_dtstart_timestamps,
+            vec![
+                1609768800, // Mon 4th Jan 2021 09:00 EST
+                1609855200, // Tue 5th Jan 2021 09:00 EST
+                1609941600, // Wed 6th Jan 2021 09:00 EST
+            ],
+        );
+
+        // The RRULE is retained as specified.
+        assert_eq!(
+            event.to_content_line_set_with_context(None)
+                .into_iter()
+                .filter(|content_line| content_line.0 == "RRULE")
+                .map(|content_line| content_line.render_ical())
+                .collect::<Vec<String>>(),
+            vec![String::from("RRULE:FREQ=DAILY;INTERVAL=1;UNTIL=20210106T090000")],
+        );
+
+        // A local date-time UNTIL is rejected alongside a UTC DTSTART.
+        let mut event = Event::parse_ical(
+            "EVENT_UID",
+            "DTSTART:20210104T090000Z RRULE:FREQ=DAILY;INTERVAL=1;UNTIL=20210106T090000",
+        ).unwrap();
+
+        assert_eq!(
+            event.validate(),
+            Err(String::from("UNTIL must be specified as a UTC date-time when DTSTART is specified as a UTC date-time")),
+        );
+    }
+
+    #[test]
+    fn test_get_most_recent_occurrence_before() {
+        let mut event = Event::parse_ical(
+            "EVENT_UID",
+            "DTSTART:20210104T090000Z DTEND:20210104T100000Z RRULE:FREQ=WEEKLY;BYDAY=MO,WE;INTERVAL=1",
+        ).unwrap();
+
+        event.validate().unwrap();
+
+        // Before Mon 4th Jan 2021 09:00 (first occurrence)
+        assert_eq!(event.get_most_recent_occurrence_before(1609750800), Ok(None));
+        // Before Wed 6th Jan 2021 09:30 (Wed 6th Jan 2021 09:00)
+        assert_eq!(event.get_most_recent_occurrence_before(1609925400), Ok(Some((1609923600, 1609927200, None))));
+        // Before Wed 6th Jan 2021 09:00 exclusive (Mon 4th Jan 2021 09:00)
+        assert_eq!(event.get_most_recent_occurrence_before(1609923600), Ok(Some((1609750800, 1609754400, None))));
+
+        // Cancelling Mon 4th Jan 2021 09:00 via override leaves no prior occurrence.
+        event.override_occurrence(&build_event_override_from_ical("20210104T090000Z", vec!["STATUS:CANCELLED"]), true).unwrap();
+
+        assert_eq!(event.get_most_recent_occurrence_before(1609923600), Ok(None));
+    }
+
+    #[test]
+    fn test_get_instance() {
+        let mut event = Event::parse_ical(
+            "EVENT_UID",
+            "DTSTART:20210104T090000Z RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4;INTERVAL=1 SUMMARY:Event summary",
+        ).unwrap();
+
+        event.validate().unwrap();
+
+        event.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210106T090000Z", "SUMMARY:Overridden summary LAST-MODIFIED:20210501T090000Z").unwrap(),
+            false,
+        ).unwrap();
+
+        event.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210111T090000Z", "STATUS:CANCELLED LAST-MODIFIED:20210501T090000Z").unwrap(),
+            false,
+        ).unwrap();
+
+        let get_rendered_instance = |timestamp: i64| -> Option<Vec<String>> {
+            event
+                .get_instance(timestamp, DstPolicy::default())
+                .unwrap()
+                .map(|event_instance| event_instance.to_rendered_content_lines())
+        };
+
+        // Mon 4th Jan 2021 09:00 (first occurrence)
+        assert_eq!(
+            get_rendered_instance(1609750800),
+            Some(vec![
+                String::from("DTEND:20210104T090000Z"),
+                String::from("DTSTART:20210104T090000Z"),
+                String::from("DURATION:PT0S"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210104T090000Z"),
+                String::from("SUMMARY:Event summary"),
+                String::from("UID:EVENT_UID"),
+            ]),
+        );
+
+        // Wed 6th Jan 2021 09:00 (overridden)
+        assert_eq!(
+            get_rendered_instance(1609923600),
+            Some(vec![
+                String::from("DTEND:20210106T090000Z"),
+                String::from("DTSTART:20210106T090000Z"),
+                String::from("DURATION:PT0S"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210106T090000Z"),
+                String::from("SUMMARY:Overridden summary"),
+                String::from("UID:EVENT_UID"),
+            ]),
+        );
+
+        // Wed 6th Jan 2021 09:30 (correct day, wrong time)
+        assert_eq!(get_rendered_instance(1609925400), None);
+        // Mon 11th Jan 2021 09:00 (cancelled via override)
+        assert_eq!(get_rendered_instance(1610355600), None);
+        // Mon 18th Jan 2021 09:00 (after COUNT is exhausted)
+        assert_eq!(get_rendered_instance(1610960400), None);
+    }
+
+    #[test]
+    fn test_add_occurrence() {
+        let mut event = Event::parse_ical(
+            "EVENT_UID",
+            "DTSTART:20210104T090000Z RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4;INTERVAL=1 EXDATE:20210111T090000Z",
+        ).unwrap();
+
+        event.validate().unwrap();
+
+        // Tue 5th Jan 2021 09:00 (not a BYDAY)
+        assert_eq!(event.is_occurrence(1609837200), Ok(false));
+        assert_eq!(event.add_occurrence(1609837200), Ok(true));
+        assert_eq!(event.is_occurrence(1609837200), Ok(true));
+
+        assert_eq!(
+            event.schedule_properties.extract_serialized_rdates_ical_key_value_pairs(),
+            Some(HashSet::from([KeyValuePair::new(String::from("RDATE"), String::from(":20210105T090000Z"))])),
+        );
+
+        // Wed 6th Jan 2021 09:00 (already an occurrence)
+        assert_eq!(event.add_occurrence(1609923600), Ok(false));
+
+        // Mon 11th Jan 2021 09:00 (excluded by EXDATE)
+        assert_eq!(
+            event.add_occurrence(1610355600),
+            Err(String::from("Event with UID: 'EVENT_UID' excludes an occurrence at: '20210111T090000Z' (e.g. via EXDATE)")),
+        );
+
+        assert_eq!(event.is_occurrence(1610355600), Ok(false));
+        assert_eq!(event.schedule_properties.rdates.as_ref().map(|rdates| rdates.len()), Some(1));
+
+        // Assert the DTSTART occurrence of a non-recurring event is retained.
+        let mut event = Event::parse_ical("EVENT_UID", "DTSTART:20210104T090000Z").unwrap();
+
+        event.validate().unwrap();
+
+        assert_eq!(event.add_occurrence(1609837200), Ok(true));
+        assert_eq!(event.is_occurrence(1609750800), Ok(true));
+        assert_eq!(event.is_occurrence(1609837200), Ok(true));
+    }
 }
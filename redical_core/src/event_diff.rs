@@ -14,6 +14,7 @@ pub struct EventDiff {
     pub indexed_related_to: Option<UpdatedSetMembers<KeyValuePair>>,
     pub indexed_geo: Option<UpdatedAttribute<GeoPoint>>,
     pub indexed_class: Option<UpdatedAttribute<String>>,
+    pub indexed_location: Option<UpdatedAttribute<String>>,
 
     pub passive_properties: Option<UpdatedSetMembers<KeyValuePair>>,
     pub schedule_properties: Option<SchedulePropertiesDiff>,
@@ -27,6 +28,7 @@ impl EventDiff {
             indexed_related_to: Self::diff_indexed_related_to(original_event, updated_event),
             indexed_geo: Self::diff_indexed_geo(original_event, updated_event),
             indexed_class: Self::diff_indexed_class(original_event, updated_event),
+            indexed_location: Self::diff_indexed_location(original_event, updated_event),
 
             passive_properties: Self::diff_passive_properties(original_event, updated_event),
             schedule_properties: Self::diff_schedule_properties(original_event, updated_event),
@@ -110,6 +112,20 @@ impl EventDiff {
         }
     }
 
+    fn diff_indexed_location(
+        original_event: &Event,
+        updated_event: &Event,
+    ) -> Option<UpdatedAttribute<String>> {
+        let original_location = &original_event.indexed_properties.extract_location();
+        let updated_location = &updated_event.indexed_properties.extract_location();
+
+        if original_location.is_none() && updated_location.is_none() {
+            None
+        } else {
+            Some(UpdatedAttribute::new(original_location, updated_location))
+        }
+    }
+
     fn diff_passive_properties(
         original_event: &Event,
         updated_event: &Event,
@@ -143,7 +159,7 @@ impl EventDiff {
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct SchedulePropertiesDiff {
-    rrule: Option<UpdatedAttribute<KeyValuePair>>,
+    rrule: Option<UpdatedSetMembers<KeyValuePair>>,
     exrule: Option<UpdatedAttribute<KeyValuePair>>,
     rdate: Option<UpdatedSetMembers<KeyValuePair>>,
     exdate: Option<UpdatedSetMembers<KeyValuePair>>,
@@ -157,9 +173,13 @@ impl SchedulePropertiesDiff {
         let original_event_schedule_properties = &original_event.schedule_properties;
         let updated_event_schedule_properties = &updated_event.schedule_properties;
 
-        let rrule = Self::build_updated_attribute(
-            &original_event_schedule_properties.extract_serialized_rrule_ical_key_value_pair(),
-            &updated_event_schedule_properties.extract_serialized_rrule_ical_key_value_pair(),
+        let rrule = Self::build_updated_set_members(
+            original_event_schedule_properties
+                .extract_serialized_rrules_ical_key_value_pairs()
+                .as_ref(),
+            updated_event_schedule_properties
+                .extract_serialized_rrules_ical_key_value_pairs()
+                .as_ref(),
         );
 
         let exrule = Self::build_updated_attribute(
@@ -252,7 +272,7 @@ mod test {
     use std::collections::{BTreeMap, BTreeSet, HashSet};
 
     use redical_ical::properties::{
-        LocationTypeProperty, CategoriesProperty, ClassProperty, DTStartProperty, GeoProperty, RRuleProperty, RelatedToProperty, PassiveProperty, LastModifiedProperty
+        LocationTypeProperty, LocationProperty, CategoriesProperty, ClassProperty, DTStartProperty, GeoProperty, RRuleProperty, RelatedToProperty, PassiveProperty, LastModifiedProperty
     };
 
     use crate::{IndexedProperties, KeyValuePair, PassiveProperties, ScheduleProperties};
@@ -287,6 +307,8 @@ mod test {
 
         let expected_indexed_class = None;
 
+        let expected_indexed_location = None;
+
         let expected_passive_properties = Some(UpdatedSetMembers {
             removed: HashSet::new(),
             maintained: HashSet::new(),
@@ -311,6 +333,7 @@ mod test {
                 indexed_related_to: expected_indexed_related_to,
                 indexed_geo: expected_indexed_geo,
                 indexed_class: expected_indexed_class,
+                indexed_location: expected_indexed_location,
                 passive_properties: expected_passive_properties,
                 schedule_properties: expected_schedule_properties,
             }
@@ -322,10 +345,10 @@ mod test {
             last_modified: build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
 
             schedule_properties: ScheduleProperties {
-                rrule: Some(build_property_from_ical!(
+                rrules: Some(HashSet::from([build_property_from_ical!(
                     RRuleProperty,
                     "RRULE:FREQ=DAILY;UNTIL=20230330T183000Z;INTERVAL=1"
-                )),
+                )])),
                 exrule: None,
                 rdates: None,
                 exdates: None,
@@ -341,6 +364,7 @@ mod test {
             indexed_properties: IndexedProperties {
                 geo: Some(build_property_from_ical!(GeoProperty, "GEO:51.5074;-0.1278")),
                 class: Some(build_property_from_ical!(ClassProperty, "CLASS:PRIVATE")),
+                location: Some(build_property_from_ical!(LocationProperty, "LOCATION:Royal  Albert Hall")),
                 related_to: None,
                 location_type: Some(build_property_from_ical!(LocationTypeProperty, "LOCATION-TYPE:ONLINE")),
                 categories: Some(HashSet::from([build_property_from_ical!(
@@ -359,6 +383,8 @@ mod test {
             indexed_related_to: None,
             indexed_geo: None,
             indexed_class: None,
+            indexed_location: None,
+            indexed_x_properties: None,
         };
 
         assert_eq!(
@@ -389,6 +415,7 @@ mod test {
                     51.5074_f64, -0.1278_f64
                 )))),
                 indexed_class: Some(UpdatedAttribute::Added(String::from("PRIVATE"))),
+                indexed_location: Some(UpdatedAttribute::Added(String::from("Royal Albert Hall"))),
                 passive_properties: Some(UpdatedSetMembers {
                     removed: HashSet::new(),
                     maintained: HashSet::new(),
@@ -398,10 +425,14 @@ mod test {
                     }])
                 }),
                 schedule_properties: Some(SchedulePropertiesDiff {
-                    rrule: Some(UpdatedAttribute::Added(KeyValuePair::new(
-                        String::from("RRULE"),
-                        String::from(":FREQ=DAILY;INTERVAL=1;UNTIL=20230330T183000Z"),
-                    ))),
+                    rrule: Some(UpdatedSetMembers {
+                        removed: HashSet::new(),
+                        maintained: HashSet::new(),
+                        added: HashSet::from([KeyValuePair::new(
+                            String::from("RRULE"),
+                            String::from(":FREQ=DAILY;INTERVAL=1;UNTIL=20230330T183000Z"),
+                        )]),
+                    }),
                     exrule: None,
                     rdate: None,
                     exdate: None,
@@ -421,10 +452,10 @@ mod test {
             last_modified: build_property_from_ical!(LastModifiedProperty, "LAST-MODIFIED:20201230T173000Z"),
 
             schedule_properties: ScheduleProperties {
-                rrule: Some(build_property_from_ical!(
+                rrules: Some(HashSet::from([build_property_from_ical!(
                     RRuleProperty,
                     "RRULE:FREQ=DAILY;UNTIL=20230130T183000Z;INTERVAL=1"
-                )),
+                )])),
                 exrule: None,
                 rdates: None,
                 exdates: None,
@@ -440,6 +471,7 @@ mod test {
             indexed_properties: IndexedProperties {
                 geo: None,
                 class: None,
+                location: None,
                 related_to: Some(HashSet::from([
                     build_property_from_ical!(
                         RelatedToProperty,
@@ -467,6 +499,8 @@ mod test {
             indexed_related_to: None,
             indexed_geo: None,
             indexed_class: None,
+            indexed_location: None,
+            indexed_x_properties: None,
         };
 
         assert_eq!(
@@ -503,6 +537,7 @@ mod test {
                     51.5074_f64, -0.1278_f64
                 )))),
                 indexed_class: Some(UpdatedAttribute::Added(String::from("PRIVATE"))),
+                indexed_location: Some(UpdatedAttribute::Added(String::from("Royal Albert Hall"))),
                 passive_properties: Some(UpdatedSetMembers {
                     removed: HashSet::from([KeyValuePair {
                         key: String::from("DESCRIPTION"),
@@ -515,16 +550,17 @@ mod test {
                     }])
                 }),
                 schedule_properties: Some(SchedulePropertiesDiff {
-                    rrule: Some(UpdatedAttribute::Updated(
-                        KeyValuePair::new(
+                    rrule: Some(UpdatedSetMembers {
+                        removed: HashSet::from([KeyValuePair::new(
                             String::from("RRULE"),
                             String::from(":FREQ=DAILY;INTERVAL=1;UNTIL=20230130T183000Z"),
-                        ),
-                        KeyValuePair::new(
+                        )]),
+                        maintained: HashSet::new(),
+                        added: HashSet::from([KeyValuePair::new(
                             String::from("RRULE"),
                             String::from(":FREQ=DAILY;INTERVAL=1;UNTIL=20230330T183000Z"),
-                        )
-                    )),
+                        )]),
+                    }),
                     exrule: None,
                     rdate: None,
                     exdate: None,
@@ -579,6 +615,7 @@ mod test {
                 }),
                 indexed_geo: None,
                 indexed_class: None,
+                indexed_location: None,
                 passive_properties: Some(UpdatedSetMembers {
                     removed: HashSet::from([KeyValuePair {
                         key: String::from("DESCRIPTION"),
@@ -588,10 +625,14 @@ mod test {
                     added: HashSet::new()
                 }),
                 schedule_properties: Some(SchedulePropertiesDiff {
-                    rrule: Some(UpdatedAttribute::Removed(KeyValuePair::new(
-                        String::from("RRULE"),
-                        String::from(":FREQ=DAILY;INTERVAL=1;UNTIL=20230130T183000Z"),
-                    ))),
+                    rrule: Some(UpdatedSetMembers {
+                        removed: HashSet::from([KeyValuePair::new(
+                            String::from("RRULE"),
+                            String::from(":FREQ=DAILY;INTERVAL=1;UNTIL=20230130T183000Z"),
+                        )]),
+                        maintained: HashSet::new(),
+                        added: HashSet::new(),
+                    }),
                     exrule: None,
                     rdate: None,
                     exdate: None,
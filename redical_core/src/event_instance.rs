@@ -2,14 +2,19 @@ use std::cmp::Ordering;
 
 use std::collections::{BTreeSet, HashSet, HashMap};
 
-use crate::{Event, EventOccurrenceOverride, IndexedConclusion};
+use std::time::{Duration, Instant};
+
+use crate::{Event, EventOccurrenceOverride, ExecutionDeadline, IndexedConclusion};
 
 use crate::event_occurrence_iterator::{
-    EventOccurrenceIterator, LowerBoundFilterCondition, UpperBoundFilterCondition,
+    DstPolicy, DurationFilterCondition, EventOccurrenceIterator, LowerBoundFilterCondition, OccurrenceExpansionLimits,
+    UpperBoundFilterCondition,
 };
 
 use crate::event::{IndexedProperties, PassiveProperties};
 
+use crate::queries::query_profile::QueryProfiler;
+
 use redical_ical::{
     ICalendarComponent,
     RenderingContext,
@@ -21,6 +26,7 @@ use redical_ical::{
         CategoriesProperty,
         LocationTypeProperty,
         ClassProperty,
+        LocationProperty,
         DTEndProperty,
         DTStartProperty,
         DurationProperty,
@@ -63,6 +69,7 @@ impl EventInstance {
             location_type: Self::get_location_type(event, event_occurrence_override),
             related_to: Self::get_related_to(event, event_occurrence_override),
             class: Self::get_class(event, event_occurrence_override),
+            location: Self::get_location(event, event_occurrence_override),
         };
 
         let passive_properties = PassiveProperties {
@@ -116,6 +123,10 @@ impl EventInstance {
                     return None;
                 }
             }
+
+            if event_occurrence_override.is_replacing() {
+                return None;
+            }
         }
 
         event.indexed_properties.geo.to_owned()
@@ -136,6 +147,10 @@ impl EventInstance {
                     .location_type
                     .to_owned();
             }
+
+            if event_occurrence_override.is_replacing() {
+                return None;
+            }
         }
 
         event.indexed_properties.location_type.to_owned()
@@ -156,6 +171,10 @@ impl EventInstance {
                     .categories
                     .to_owned();
             }
+
+            if event_occurrence_override.is_replacing() {
+                return None;
+            }
         }
 
         event.indexed_properties.categories.to_owned()
@@ -176,6 +195,10 @@ impl EventInstance {
                     .related_to
                     .to_owned();
             }
+
+            if event_occurrence_override.is_replacing() {
+                return None;
+            }
         }
 
         event.indexed_properties.related_to.to_owned()
@@ -186,6 +209,8 @@ impl EventInstance {
     //
     // We do this grouped via the property name so that overrides are applied on a property name
     // level only which allows the patching of groups of specific overridden property names.
+    //
+    // Overrides replacing the base event properties (X-OVERRIDE-MODE:REPLACE) inherit none of them.
     fn get_passive_properties(
         event: &Event,
         event_occurrence_override: Option<&EventOccurrenceOverride>,
@@ -193,6 +218,10 @@ impl EventInstance {
         let mut grouped_passive_properties: HashMap<String, Vec<PassiveProperty>> = event.passive_properties.extract_properties_grouped_by_name();
 
         if let Some(event_occurrence_override) = event_occurrence_override {
+            if event_occurrence_override.is_replacing() {
+                grouped_passive_properties.clear();
+            }
+
             for (property_name, grouped_properties) in event_occurrence_override.passive_properties.extract_properties_grouped_by_name() {
                 grouped_passive_properties.insert(property_name, grouped_properties);
             }
@@ -212,11 +241,38 @@ impl EventInstance {
                     .class
                     .to_owned();
             }
+
+            if event_occurrence_override.is_replacing() {
+                return None;
+            }
         }
 
         event.indexed_properties.class.to_owned()
     }
 
+    // Allow events with LOCATION defined to be overridden to make LOCATION blank (specific events
+    // online only), via a blank LOCATION property.
+    fn get_location(
+        event: &Event,
+        event_occurrence_override: Option<&EventOccurrenceOverride>,
+    ) -> Option<LocationProperty> {
+        if let Some(event_occurrence_override) = event_occurrence_override {
+            if let Some(overridden_location_property) = event_occurrence_override.indexed_properties.location.as_ref() {
+                if overridden_location_property.get_normalized_location().is_some() {
+                    return Some(overridden_location_property.to_owned());
+                } else {
+                    return None;
+                }
+            }
+
+            if event_occurrence_override.is_replacing() {
+                return None;
+            }
+        }
+
+        event.indexed_properties.location.to_owned()
+    }
+
     // Copy the contents of the DTStartProperty into RecurrenceIDProperty as it serves
     // essentially the same purpose.
     //
@@ -232,6 +288,10 @@ impl QueryableEntity for EventInstance {
         self.uid.uid.to_string()
     }
 
+    fn get_recurrence_id_timestamp(&self) -> Option<i64> {
+        Some(self.dtstart.get_utc_timestamp())
+    }
+
     fn build_result_ordering(&self, ordering_condition: &OrderingCondition) -> QueryResultOrdering {
         ordering_condition.build_result_ordering_for_event_instance(self)
     }
@@ -258,6 +318,10 @@ impl ICalendarComponent for EventInstance {
             serializable_properties.insert(class_property.to_content_line_with_context(context));
         }
 
+        if let Some(location_property) = &self.indexed_properties.location {
+            serializable_properties.insert(location_property.to_content_line_with_context(context));
+        }
+
         if let Some(related_to_properties) = &self.indexed_properties.related_to {
             for related_to_property in related_to_properties {
                 serializable_properties.insert(related_to_property.to_content_line_with_context(context));
@@ -289,16 +353,14 @@ impl PartialOrd for EventInstance {
     }
 }
 
+// Event instances sharing the same DTSTART are ordered by UID (as with the query results), so
+// that they are merged in the same order as they are returned by queries.
 impl Ord for EventInstance {
     fn cmp(&self, other: &Self) -> Ordering {
-        let dtstart_timestamp_comparison =
-            self.dtstart.get_utc_timestamp().cmp(&other.dtstart.get_utc_timestamp());
-
-        if dtstart_timestamp_comparison.is_eq() {
-            self.dtend.get_utc_timestamp().cmp(&other.dtend.get_utc_timestamp())
-        } else {
-            dtstart_timestamp_comparison
-        }
+        self.dtstart.get_utc_timestamp()
+            .cmp(&other.dtstart.get_utc_timestamp())
+            .then_with(|| self.uid.uid.0.cmp(&other.uid.uid.0))
+            .then_with(|| self.dtend.get_utc_timestamp().cmp(&other.dtend.get_utc_timestamp()))
     }
 }
 
@@ -306,6 +368,9 @@ impl Ord for EventInstance {
 pub struct EventInstanceIterator<'a> {
     event: &'a Event,
     internal_iter: EventOccurrenceIterator<'a>,
+    occurrence_expansion_limits: Option<OccurrenceExpansionLimits>,
+    query_profiler: Option<QueryProfiler>,
+    expansion_duration: Duration,
 }
 
 impl<'a> EventInstanceIterator<'a> {
@@ -328,8 +393,144 @@ impl<'a> EventInstanceIterator<'a> {
         Ok(EventInstanceIterator {
             event,
             internal_iter,
+            occurrence_expansion_limits: None,
+            query_profiler: None,
+            expansion_duration: Duration::ZERO,
         })
     }
+
+    /// Only yield event instances with an (overridden) duration satisfying all the provided
+    /// duration filter conditions.
+    pub fn with_duration_filter_conditions(mut self, filter_durations: Vec<DurationFilterCondition>) -> Self {
+        self.internal_iter = self.internal_iter.with_duration_filter_conditions(filter_durations);
+
+        self
+    }
+
+    /// Stop extrapolating event instances once the execution deadline has been exceeded.
+    pub fn with_execution_deadline(mut self, execution_deadline: ExecutionDeadline) -> Self {
+        self.internal_iter = self.internal_iter.with_execution_deadline(execution_deadline);
+
+        self
+    }
+
+    /// Whether the extrapolation of event instances was cut short by the execution deadline.
+    pub fn is_execution_deadline_reached(&self) -> bool {
+        self.internal_iter.is_execution_deadline_reached()
+    }
+
+    /// Stop extrapolating event instances once either of the occurrence expansion limits are
+    /// exceeded, recording the event if its expansion exceeds either slow expansion threshold.
+    pub fn with_occurrence_expansion_limits(mut self, occurrence_expansion_limits: OccurrenceExpansionLimits) -> Self {
+        if occurrence_expansion_limits.has_slow_expansion_thresholds() {
+            self.occurrence_expansion_limits = Some(occurrence_expansion_limits.clone());
+        }
+
+        self.internal_iter = self.internal_iter.with_occurrence_expansion_limits(occurrence_expansion_limits);
+
+        self
+    }
+
+    /// Resolve event instances landing on a local time skipped or repeated by a DST transition as
+    /// specified by the policy.
+    pub fn with_dst_policy(mut self, dst_policy: DstPolicy) -> Self {
+        self.internal_iter = self.internal_iter.with_dst_policy(dst_policy);
+
+        self
+    }
+
+    /// Skip the event instances starting before the seek timestamp without counting them towards
+    /// the occurrence expansion limits, expanding from the seeked RRULE set if provided (see
+    /// `build_seeked_rrule_set`).
+    pub fn with_seek(mut self, seek_timestamp: i64, seeked_rrule_set: Option<&'a rrule::RRuleSet>) -> Self {
+        self.internal_iter = self.internal_iter.with_seek(seek_timestamp, seeked_rrule_set);
+
+        self
+    }
+
+    /// Record the expansion of the event (and the application of it's overrides) against the
+    /// query profile.
+    pub fn with_query_profiler(mut self, query_profiler: QueryProfiler) -> Self {
+        self.query_profiler = Some(query_profiler);
+
+        self
+    }
+
+    // Time the expansion of the next occurrence(s) (from either end) only when tracking slow
+    // expansions or profiling, recording the accumulated expansion of the event against the slow
+    // expansion thresholds.
+    fn expand_occurrences<T>(&mut self, expand: impl FnOnce(&mut EventOccurrenceIterator<'a>) -> T) -> T {
+        if self.occurrence_expansion_limits.is_none() && self.query_profiler.is_none() {
+            return expand(&mut self.internal_iter);
+        }
+
+        let previous_expanded_count = self.internal_iter.get_expanded_count();
+
+        let started_at = Instant::now();
+
+        let expanded = expand(&mut self.internal_iter);
+
+        let duration = started_at.elapsed();
+
+        self.expansion_duration += duration;
+
+        let event_uid = self.event.uid.uid.0.as_str();
+        let expanded_count = self.internal_iter.get_expanded_count();
+
+        if let Some(occurrence_expansion_limits) = &self.occurrence_expansion_limits {
+            occurrence_expansion_limits.record_expansion(event_uid, expanded_count, self.expansion_duration);
+        }
+
+        if let Some(query_profiler) = &self.query_profiler {
+            query_profiler.record_event_expansion(
+                event_uid,
+                expanded_count.saturating_sub(previous_expanded_count),
+                duration,
+            );
+        }
+
+        expanded
+    }
+
+    /// Returns the next event instance from the back (see `EventOccurrenceIterator::try_next_back`
+    /// for how far the occurrences are expanded, and when iterating backwards is an error).
+    pub fn try_next_back(&mut self) -> Result<Option<EventInstance>, String> {
+        let Some((dtstart_timestamp, _dtend_timestamp, event_occurrence_override)) = self.expand_occurrences(EventOccurrenceIterator::try_next_back)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(self.build_event_instance(dtstart_timestamp, event_occurrence_override)))
+    }
+
+    /// Consumes the iterator, yielding the event instances in reverse order (see `try_next_back`).
+    pub fn try_rev(mut self) -> Result<impl Iterator<Item = EventInstance> + 'a, String> {
+        self.expand_occurrences(EventOccurrenceIterator::start_back_expansion)?;
+
+        // Only starting the backwards expansion is fallible, which has already succeeded.
+        Ok(std::iter::from_fn(move || self.try_next_back().ok().flatten()))
+    }
+
+    fn build_event_instance(&self, dtstart_timestamp: i64, event_occurrence_override: Option<EventOccurrenceOverride>) -> EventInstance {
+        let Some(query_profiler) = &self.query_profiler else {
+            return EventInstance::new(
+                &dtstart_timestamp,
+                self.event,
+                event_occurrence_override.as_ref(),
+            );
+        };
+
+        let started_at = Instant::now();
+
+        let event_instance = EventInstance::new(
+            &dtstart_timestamp,
+            self.event,
+            event_occurrence_override.as_ref(),
+        );
+
+        query_profiler.record_override_application(self.event.uid.uid.0.as_str(), started_at.elapsed());
+
+        event_instance
+    }
 }
 
 impl Iterator for EventInstanceIterator<'_> {
@@ -338,15 +539,9 @@ impl Iterator for EventInstanceIterator<'_> {
     fn next(&mut self) -> Option<Self::Item> {
         // Filter occurrence index iterator timestamps according to IndexedConclusion if
         // present, else include all.
-        self.internal_iter.next().map(
-            |(dtstart_timestamp, _dtend_timestamp, event_occurrence_override)| {
-                EventInstance::new(
-                    &dtstart_timestamp,
-                    self.event,
-                    event_occurrence_override.as_ref(),
-                )
-            },
-        )
+        let (dtstart_timestamp, _dtend_timestamp, event_occurrence_override) = self.expand_occurrences(Iterator::next)?;
+
+        Some(self.build_event_instance(dtstart_timestamp, event_occurrence_override))
     }
 }
 
@@ -361,6 +556,8 @@ mod test {
     use std::collections::{BTreeSet, HashMap, HashSet};
 
     use crate::IndexedConclusion;
+    use crate::event_occurrence_iterator::FilterProperty;
+    use crate::interning::InternedString;
 
     use crate::testing::utils::{build_event_and_overrides_from_ical, build_event_from_ical};
     use crate::testing::macros::build_property_from_ical;
@@ -400,6 +597,7 @@ mod test {
                 duration: build_property_from_ical!(DurationProperty, "DURATION:PT1M"),
                 indexed_properties: IndexedProperties {
                     class: Some(build_property_from_ical!(ClassProperty, "CLASS:PRIVATE")),
+                    location: Some(build_property_from_ical!(LocationProperty, "LOCATION:Event address text.")),
                     geo: Some(build_property_from_ical!(
                         GeoProperty,
                         "GEO:48.85299;2.36885"
@@ -442,7 +640,6 @@ mod test {
                 passive_properties: PassiveProperties {
                     properties: BTreeSet::from([
                         build_property_from_ical!(PassiveProperty, "DESCRIPTION:Event description text."),
-                        build_property_from_ical!(PassiveProperty, "LOCATION:Event address text."),
                     ])
                 },
             }
@@ -525,6 +722,7 @@ mod test {
                 duration: build_property_from_ical!(DurationProperty, "DURATION:PT1M"),
                 indexed_properties: IndexedProperties {
                     class: None,
+                    location: Some(build_property_from_ical!(LocationProperty, "LOCATION:Overridden Event address text.")),
                     geo: None,
                     location_type: None,
                     categories: Some(HashSet::from([build_property_from_ical!(
@@ -553,7 +751,6 @@ mod test {
                 passive_properties: PassiveProperties {
                     properties: BTreeSet::from([
                         build_property_from_ical!(PassiveProperty, "DESCRIPTION:Event description text."),
-                        build_property_from_ical!(PassiveProperty, "LOCATION:Overridden Event address text."),
                     ])
                 },
             }
@@ -578,6 +775,279 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_event_instance_with_rfc7986_properties_override() {
+        let event = build_event_and_overrides_from_ical(
+            "event_UID",
+            vec![
+                "DTSTART:20201231T183000Z",
+                "DTEND:20201231T190000Z",
+                "RRULE:FREQ=DAILY;COUNT=2;INTERVAL=1",
+                "COLOR:turquoise",
+                "IMAGE;DISPLAY=BADGE;VALUE=URI:https://example.com/images/badge.png",
+                "CONFERENCE;VALUE=URI;FEATURE=AUDIO,VIDEO:https://meet.example.com/abc",
+            ],
+            vec![
+                (
+                    "20210101T183000Z",
+                    vec![
+                        "COLOR:crimson",
+                        "CONFERENCE;FEATURE=PHONE;LABEL=Dial-in:tel:+1-412-555-0123",
+                    ]
+                ),
+            ],
+        );
+
+        assert_eq!(
+            EventInstance::new(&1609439400, &event, None).to_rendered_content_lines(),
+            vec![
+                String::from("COLOR:turquoise"),
+                String::from("CONFERENCE;FEATURE=AUDIO,VIDEO;VALUE=URI:https://meet.example.com/abc"),
+                String::from("DTEND:20201231T190000Z"),
+                String::from("DTSTART:20201231T183000Z"),
+                String::from("DURATION:PT30M"),
+                String::from("IMAGE;DISPLAY=BADGE;VALUE=URI:https://example.com/images/badge.png"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20201231T183000Z"),
+                String::from("UID:event_UID"),
+            ]
+        );
+
+        let Some(event_occurrence_override) = event.overrides.get(&1609525800) else {
+            panic!("Expected event to have an occurrence...");
+        };
+
+        // The overridden COLOR and CONFERENCE replace those of the base event, whilst the IMAGE is
+        // carried over.
+        assert_eq!(
+            EventInstance::new(&1609525800, &event, Some(event_occurrence_override)).to_rendered_content_lines(),
+            vec![
+                String::from("COLOR:crimson"),
+                String::from("CONFERENCE;FEATURE=PHONE;LABEL=Dial-in;VALUE=URI:tel:+1-412-555-0123"),
+                String::from("DTEND:20210101T190000Z"),
+                String::from("DTSTART:20210101T183000Z"),
+                String::from("DURATION:PT30M"),
+                String::from("IMAGE;DISPLAY=BADGE;VALUE=URI:https://example.com/images/badge.png"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210101T183000Z"),
+                String::from("UID:event_UID"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_instance_with_rfc9073_properties_override() {
+        let event = build_event_and_overrides_from_ical(
+            "event_UID",
+            vec![
+                "DTSTART:20201231T183000Z",
+                "DTEND:20201231T190000Z",
+                "RRULE:FREQ=DAILY;COUNT=2;INTERVAL=1",
+                "STRUCTURED-DATA;VALUE=URI;FMTTYPE=application/ld+json:https://example.com/event.jsonld",
+                "BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:SPEAKER_ONE END:PARTICIPANT",
+            ],
+            vec![
+                (
+                    "20210101T183000Z",
+                    vec![
+                        "BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:SPEAKER_TWO END:PARTICIPANT",
+                    ]
+                ),
+            ],
+        );
+
+        assert_eq!(
+            EventInstance::new(&1609439400, &event, None).to_rendered_content_lines(),
+            vec![
+                String::from("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:SPEAKER_ONE END:PARTICIPANT"),
+                String::from("DTEND:20201231T190000Z"),
+                String::from("DTSTART:20201231T183000Z"),
+                String::from("DURATION:PT30M"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20201231T183000Z"),
+                String::from("STRUCTURED-DATA;FMTTYPE=application/ld+json;VALUE=URI:https://example.com/event.jsonld"),
+                String::from("UID:event_UID"),
+            ]
+        );
+
+        let Some(event_occurrence_override) = event.overrides.get(&1609525800) else {
+            panic!("Expected event to have an occurrence...");
+        };
+
+        // The overridden PARTICIPANT components replace those of the base event, whilst the
+        // STRUCTURED-DATA is carried over.
+        assert_eq!(
+            EventInstance::new(&1609525800, &event, Some(event_occurrence_override)).to_rendered_content_lines(),
+            vec![
+                String::from("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:SPEAKER_TWO END:PARTICIPANT"),
+                String::from("DTEND:20210101T190000Z"),
+                String::from("DTSTART:20210101T183000Z"),
+                String::from("DURATION:PT30M"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210101T183000Z"),
+                String::from("STRUCTURED-DATA;FMTTYPE=application/ld+json;VALUE=URI:https://example.com/event.jsonld"),
+                String::from("UID:event_UID"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_instance_with_dtend_and_duration_override() {
+        let event = build_event_and_overrides_from_ical(
+            "event_UID",
+            vec![
+                "DTSTART:20201231T183000Z",
+                "DTEND:20201231T190000Z", // Ends 30 minutes after it starts.
+                "RRULE:FREQ=DAILY;COUNT=3;INTERVAL=1",
+            ],
+            vec![
+                (
+                    "20210101T183000Z",
+                    vec![
+                        "DTEND:20210101T200000Z",
+                    ]
+                ),
+                (
+                    "20210102T183000Z",
+                    vec![
+                        "DURATION:PT15M",
+                    ]
+                ),
+            ],
+        );
+
+        let event_instances: Vec<Vec<String>> =
+            EventInstanceIterator::new(&event, None, None, None, None)
+                .unwrap()
+                .map(|event_instance| event_instance.to_rendered_content_lines())
+                .collect();
+
+        assert_eq!(
+            event_instances,
+            vec![
+                vec![
+                    String::from("DTEND:20201231T190000Z"),
+                    String::from("DTSTART:20201231T183000Z"),
+                    String::from("DURATION:PT30M"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20201231T183000Z"),
+                    String::from("UID:event_UID"),
+                ],
+                vec![
+                    String::from("DTEND:20210101T200000Z"),
+                    String::from("DTSTART:20210101T183000Z"),
+                    String::from("DURATION:PT1H30M"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210101T183000Z"),
+                    String::from("UID:event_UID"),
+                ],
+                vec![
+                    String::from("DTEND:20210102T184500Z"),
+                    String::from("DTSTART:20210102T183000Z"),
+                    String::from("DURATION:PT15M"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210102T183000Z"),
+                    String::from("UID:event_UID"),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_instance_with_replacing_override() {
+        let event = build_event_and_overrides_from_ical(
+            "event_UID",
+            vec![
+                "DTSTART:20201231T183000Z",
+                "DTEND:20201231T190000Z",
+                "RRULE:FREQ=DAILY;COUNT=3;INTERVAL=1",
+                "CATEGORIES:MUSIC,SPORT",
+                "CLASS:PUBLIC",
+                "SUMMARY:Base summary",
+                "LOCATION:Base location",
+            ],
+            vec![
+                (
+                    "20210101T183000Z",
+                    vec![
+                        "X-OVERRIDE-MODE:REPLACE",
+                        "CATEGORIES:SPORT",
+                        "SUMMARY:Replaced summary",
+                    ]
+                ),
+                (
+                    "20210102T183000Z",
+                    vec![
+                        "X-OVERRIDE-MODE:MERGE",
+                        "CATEGORIES:SPORT",
+                        "SUMMARY:Merged summary",
+                    ]
+                ),
+            ],
+        );
+
+        let event_instances: Vec<Vec<String>> =
+            EventInstanceIterator::new(&event, None, None, None, None)
+                .unwrap()
+                .map(|event_instance| event_instance.to_rendered_content_lines())
+                .collect();
+
+        assert_eq!(
+            event_instances,
+            vec![
+                vec![
+                    String::from("CATEGORIES:MUSIC,SPORT"),
+                    String::from("CLASS:PUBLIC"),
+                    String::from("DTEND:20201231T190000Z"),
+                    String::from("DTSTART:20201231T183000Z"),
+                    String::from("DURATION:PT30M"),
+                    String::from("LOCATION:Base location"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20201231T183000Z"),
+                    String::from("SUMMARY:Base summary"),
+                    String::from("UID:event_UID"),
+                ],
+                // Only the properties defined on the replacing override are present.
+                vec![
+                    String::from("CATEGORIES:SPORT"),
+                    String::from("DTEND:20210101T190000Z"),
+                    String::from("DTSTART:20210101T183000Z"),
+                    String::from("DURATION:PT30M"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210101T183000Z"),
+                    String::from("SUMMARY:Replaced summary"),
+                    String::from("UID:event_UID"),
+                ],
+                vec![
+                    String::from("CATEGORIES:SPORT"),
+                    String::from("CLASS:PUBLIC"),
+                    String::from("DTEND:20210102T190000Z"),
+                    String::from("DTSTART:20210102T183000Z"),
+                    String::from("DURATION:PT30M"),
+                    String::from("LOCATION:Base location"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210102T183000Z"),
+                    String::from("SUMMARY:Merged summary"),
+                    String::from("UID:event_UID"),
+                ],
+            ]
+        );
+
+        // The base event CLASS and LOCATION are excluded from the indexes for the replaced
+        // occurrence only.
+        assert_eq!(
+            event.indexed_class.as_ref().unwrap().terms,
+            HashMap::from([
+                (InternedString::from("PUBLIC"), IndexedConclusion::Include(Some(HashSet::from([1609525800])))),
+            ]),
+        );
+
+        assert_eq!(
+            event.indexed_location.as_ref().unwrap().terms,
+            HashMap::from([
+                (InternedString::from("Base location"), IndexedConclusion::Include(Some(HashSet::from([1609525800])))),
+            ]),
+        );
+
+        assert_eq!(
+            event.indexed_categories.as_ref().unwrap().terms,
+            HashMap::from([
+                (InternedString::from("MUSIC"), IndexedConclusion::Include(Some(HashSet::from([1609525800, 1609612200])))),
+                (InternedString::from("SPORT"), IndexedConclusion::Include(None)),
+            ]),
+        );
+    }
+
     #[test]
     fn test_event_instance_iterator() {
         let event = build_event_and_overrides_from_ical(
@@ -852,4 +1322,99 @@ mod test {
 
         assert_eq!(event_instance_iterator.next(), None);
     }
+
+    #[test]
+    fn test_event_instance_iterator_slow_expansion_thresholds() {
+        let event = build_event_from_ical(
+            "event_UID",
+            vec![
+                "DTSTART:20201231T183000Z",
+                "RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=5",
+            ],
+        );
+
+        let occurrence_expansion_limits =
+            OccurrenceExpansionLimits::new(None, None)
+                .with_slow_expansion_thresholds(None, Some(3));
+
+        let mut event_instance_iterator =
+            EventInstanceIterator::new(&event, None, None, None, None)
+                .unwrap()
+                .with_occurrence_expansion_limits(occurrence_expansion_limits.clone());
+
+        event_instance_iterator.next();
+        event_instance_iterator.next();
+
+        // Below the threshold.
+        assert_eq!(occurrence_expansion_limits.get_slow_expansions(), vec![]);
+
+        event_instance_iterator.next();
+        event_instance_iterator.next();
+
+        assert_eq!(
+            occurrence_expansion_limits
+                .get_slow_expansions()
+                .into_iter()
+                .map(|slow_expansion| (slow_expansion.event_uid, slow_expansion.expanded_count))
+                .collect::<Vec<(String, usize)>>(),
+            vec![(String::from("event_UID"), 4)],
+        );
+
+        // Without any slow expansion thresholds, nothing is recorded.
+        let occurrence_expansion_limits = OccurrenceExpansionLimits::new(None, None);
+
+        let event_instance_count =
+            EventInstanceIterator::new(&event, None, None, None, None)
+                .unwrap()
+                .with_occurrence_expansion_limits(occurrence_expansion_limits.clone())
+                .count();
+
+        assert_eq!(event_instance_count, 5);
+        assert_eq!(occurrence_expansion_limits.get_slow_expansions(), vec![]);
+    }
+
+    #[test]
+    fn test_event_instance_iterator_reversed() {
+        let event = build_event_from_ical(
+            "event_UID",
+            vec![
+                "DTSTART:20201231T183000Z",
+                "RRULE:FREQ=WEEKLY;INTERVAL=1",
+            ],
+        );
+
+        let reversed_dtstart_timestamps = |event_instance_iterator: EventInstanceIterator| -> Result<Vec<i64>, String> {
+            Ok(
+                event_instance_iterator
+                    .try_rev()?
+                    .map(|event_instance| event_instance.dtstart.get_utc_timestamp())
+                    .collect()
+            )
+        };
+
+        // Indefinitely recurring without an upper bound.
+        assert_eq!(
+            reversed_dtstart_timestamps(EventInstanceIterator::new(&event, None, None, None, None).unwrap()),
+            Err(String::from("Cannot iterate the occurrences of an indefinitely recurring event in reverse without an upper bound")),
+        );
+
+        // Before Thu 21st Jan 2021 18:30
+        let upper_bound = Some(UpperBoundFilterCondition::LessThan(FilterProperty::DtStart(1611253800)));
+
+        assert_eq!(
+            reversed_dtstart_timestamps(EventInstanceIterator::new(&event, None, None, upper_bound.clone(), None).unwrap()),
+            Ok(vec![1610649000, 1610044200, 1609439400]),
+        );
+
+        let mut event_instance_iterator = EventInstanceIterator::new(&event, None, None, upper_bound, None).unwrap();
+
+        assert_eq!(
+            event_instance_iterator.try_next_back().map(|event_instance| event_instance.map(|event_instance| event_instance.dtstart.get_utc_timestamp())),
+            Ok(Some(1610649000)),
+        );
+
+        assert_eq!(event_instance_iterator.next().map(|event_instance| event_instance.dtstart.get_utc_timestamp()), Some(1609439400));
+        assert_eq!(event_instance_iterator.next().map(|event_instance| event_instance.dtstart.get_utc_timestamp()), Some(1610044200));
+        assert_eq!(event_instance_iterator.try_next_back().map(|event_instance| event_instance.is_some()), Ok(false));
+    }
 }
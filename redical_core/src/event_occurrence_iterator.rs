@@ -1,5 +1,10 @@
-use crate::{EventOccurrenceOverride, IndexedConclusion, ScheduleProperties};
-use std::collections::BTreeMap;
+use crate::{EventOccurrenceOverride, ExecutionDeadline, IndexedConclusion, ScheduleProperties};
+use chrono::{Datelike, LocalResult, Months, NaiveTime, TimeZone, Timelike};
+use redical_ical::values::dst_policy::DstPolicyValue;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub enum FilterProperty {
@@ -55,6 +60,347 @@ impl UpperBoundFilterCondition {
     }
 }
 
+#[derive(Debug, Clone)]
+pub enum DurationFilterCondition {
+    GreaterThan(i64),
+    GreaterEqualThan(i64),
+    LessThan(i64),
+    LessEqualThan(i64),
+    Equal(i64),
+}
+
+impl DurationFilterCondition {
+    pub fn is_satisfied_by(&self, duration: &i64) -> bool {
+        match self {
+            DurationFilterCondition::GreaterThan(comparison) => duration > comparison,
+            DurationFilterCondition::GreaterEqualThan(comparison) => duration >= comparison,
+            DurationFilterCondition::LessThan(comparison) => duration < comparison,
+            DurationFilterCondition::LessEqualThan(comparison) => duration <= comparison,
+            DurationFilterCondition::Equal(comparison) => duration == comparison,
+        }
+    }
+}
+
+/// How recurring occurrences landing on a local time skipped or repeated by a DST transition
+/// are resolved.
+///
+/// * `Skip` - occurrences within a skipped local hour are dropped.
+/// * `ShiftForward` - occurrences within a skipped local hour are shifted forward by the length
+///   of the transition, and occurrences within a repeated local hour resolve to the first instant.
+/// * `BothInstants` - as `ShiftForward`, but occurrences within a repeated local hour are yielded
+///   at both instants.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum DstPolicy {
+    Skip,
+    #[default]
+    ShiftForward,
+    BothInstants,
+}
+
+impl From<&DstPolicyValue> for DstPolicy {
+    fn from(dst_policy_value: &DstPolicyValue) -> Self {
+        match dst_policy_value {
+            DstPolicyValue::Skip => DstPolicy::Skip,
+            DstPolicyValue::ShiftForward => DstPolicy::ShiftForward,
+            DstPolicyValue::BothInstants => DstPolicy::BothInstants,
+        }
+    }
+}
+
+impl From<DstPolicyValue> for DstPolicy {
+    fn from(dst_policy_value: DstPolicyValue) -> Self {
+        DstPolicy::from(&dst_policy_value)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum DstTransition {
+    None,
+    Skipped,
+    Repeated(chrono::DateTime<rrule::Tz>),
+}
+
+// The local times of day (in the DTSTART timezone) recurred at by each daily (or less frequent)
+// RRULE. Only these are displaced by DST transitions, sub-daily RRULEs are not anchored to a
+// local time of day.
+fn get_recurring_local_times(parsed_rrule_set: &rrule::RRuleSet) -> Vec<NaiveTime> {
+    let dtstart = parsed_rrule_set.get_dt_start();
+
+    let or_dtstart_value = |values: &[u8], dtstart_value: u32| -> Vec<u32> {
+        if values.is_empty() {
+            vec![dtstart_value]
+        } else {
+            values.iter().map(|value| *value as u32).collect()
+        }
+    };
+
+    let mut local_times = BTreeSet::new();
+
+    for rrule in parsed_rrule_set.get_rrule() {
+        if matches!(rrule.get_freq(), rrule::Frequency::Hourly | rrule::Frequency::Minutely | rrule::Frequency::Secondly) {
+            continue;
+        }
+
+        for hour in or_dtstart_value(rrule.get_by_hour(), dtstart.hour()) {
+            for minute in or_dtstart_value(rrule.get_by_minute(), dtstart.minute()) {
+                for second in or_dtstart_value(rrule.get_by_second(), dtstart.second()) {
+                    if let Some(local_time) = NaiveTime::from_hms_opt(hour, minute, second) {
+                        local_times.insert(local_time);
+                    }
+                }
+            }
+        }
+    }
+
+    local_times.into_iter().collect()
+}
+
+/// An RRULE set expanding the same occurrences as the parsed RRULE set from (shortly before) the
+/// seek timestamp onwards, with it's DTSTART shifted forwards by a whole number of recurrence
+/// periods (in the DTSTART timezone), so that seeking to a distant timestamp does not expand every
+/// preceding occurrence.
+///
+/// Only a single RRULE without a COUNT (which counts occurrences from the original DTSTART) and
+/// without any EXRULE can be seeked, otherwise (or if the timestamp is not at least a couple of
+/// periods beyond the DTSTART) None is returned.
+pub fn build_seeked_rrule_set(parsed_rrule_set: &rrule::RRuleSet, seek_timestamp: i64) -> Option<rrule::RRuleSet> {
+    let [rrule] = parsed_rrule_set.get_rrule().as_slice() else {
+        return None;
+    };
+
+    if rrule.get_count().is_some() || !parsed_rrule_set.get_exrule().is_empty() {
+        return None;
+    }
+
+    let dtstart = parsed_rrule_set.get_dt_start();
+    let timezone = dtstart.timezone();
+
+    let dtstart_local = dtstart.naive_local();
+
+    // The rrule crate rolls the day of the month it counts periods from over into the following
+    // month when it does not exist (e.g. the 31st of a shorter month), drifting the periods expanded
+    // thereafter, which is only reproducible from a shifted DTSTART on a day every month has.
+    if matches!(rrule.get_freq(), rrule::Frequency::Yearly | rrule::Frequency::Monthly) && dtstart_local.day() > 28 {
+        return None;
+    }
+    let seek_local = chrono::DateTime::from_timestamp(seek_timestamp, 0)?.with_timezone(&timezone).naive_local();
+
+    let interval = rrule.get_interval().max(1) as i64;
+
+    let elapsed = seek_local.signed_duration_since(dtstart_local);
+    let elapsed_months = (seek_local.year() as i64 - dtstart_local.year() as i64) * 12 + (seek_local.month() as i64 - dtstart_local.month() as i64);
+
+    let (elapsed_units, units_per_period) =
+        match rrule.get_freq() {
+            rrule::Frequency::Yearly => (elapsed_months, 12 * interval),
+            rrule::Frequency::Monthly => (elapsed_months, interval),
+            rrule::Frequency::Weekly => (elapsed.num_weeks(), interval),
+            rrule::Frequency::Daily => (elapsed.num_days(), interval),
+            rrule::Frequency::Hourly => (elapsed.num_hours(), interval),
+            rrule::Frequency::Minutely => (elapsed.num_minutes(), interval),
+            rrule::Frequency::Secondly => (elapsed.num_seconds(), interval),
+        };
+
+    // Stop a period short, so that no occurrence within the period of the seek timestamp (but
+    // before the position of the shifted DTSTART within it) is missed.
+    let shifted_periods = (elapsed_units / units_per_period).saturating_sub(1);
+
+    if shifted_periods < 1 {
+        return None;
+    }
+
+    let shifted_units = shifted_periods.checked_mul(units_per_period)?;
+
+    let shifted_dtstart_local =
+        match rrule.get_freq() {
+            rrule::Frequency::Yearly | rrule::Frequency::Monthly => {
+                dtstart_local.checked_add_months(Months::new(u32::try_from(shifted_units).ok()?))?
+            },
+
+            rrule::Frequency::Weekly => dtstart_local.checked_add_signed(chrono::Duration::try_weeks(shifted_units)?)?,
+            rrule::Frequency::Daily => dtstart_local.checked_add_signed(chrono::Duration::try_days(shifted_units)?)?,
+            rrule::Frequency::Hourly => dtstart_local.checked_add_signed(chrono::Duration::try_hours(shifted_units)?)?,
+            rrule::Frequency::Minutely => dtstart_local.checked_add_signed(chrono::Duration::try_minutes(shifted_units)?)?,
+            rrule::Frequency::Secondly => dtstart_local.checked_add_signed(chrono::Duration::try_seconds(shifted_units)?)?,
+        };
+
+    // The shifted DTSTART falling within a DST transition gap is not seeked.
+    let shifted_dtstart = timezone.from_local_datetime(&shifted_dtstart_local).earliest()?;
+
+    // The RRULE has already been validated against the original DTSTART, so all the BYXXX parts
+    // defaulted from it (e.g. the BYMONTHDAY of a MONTHLY RRULE) are retained.
+    Some(
+        rrule::RRuleSet::new(shifted_dtstart)
+            .rrule(rrule.clone())
+            .set_rdates(parsed_rrule_set.get_rdate().clone())
+            .set_exdates(parsed_rrule_set.get_exdate().clone())
+    )
+}
+
+// Determine whether the expanded occurrence was intended for a local time skipped or repeated by
+// a DST transition.
+//
+// Occurrences intended for a skipped local time are expanded as the elapsed time since local
+// midnight (i.e. shifted forward by the length of the transition), and occurrences intended for a
+// repeated local time are expanded as the earliest instant.
+fn classify_dst_transition(dtstart: &chrono::DateTime<rrule::Tz>, local_times: &[NaiveTime]) -> DstTransition {
+    let tz = dtstart.timezone();
+    let local_dtstart = dtstart.naive_local();
+
+    if local_times.contains(&local_dtstart.time()) {
+        if let LocalResult::Ambiguous(earliest, latest) = tz.from_local_datetime(&local_dtstart) {
+            if &earliest == dtstart {
+                return DstTransition::Repeated(latest);
+            }
+        }
+    }
+
+    let local_date = local_dtstart.date();
+
+    let Some(local_midnight) = local_date.and_hms_opt(0, 0, 0).and_then(|local_midnight| tz.from_local_datetime(&local_midnight).single()) else {
+        return DstTransition::None;
+    };
+
+    let is_skipped = local_times.iter().any(|local_time| {
+        matches!(tz.from_local_datetime(&local_date.and_time(*local_time)), LocalResult::None)
+            && local_midnight + chrono::Duration::try_seconds(local_time.num_seconds_from_midnight() as i64).expect("seconds from midnight cannot overflow") == *dtstart
+    });
+
+    if is_skipped {
+        DstTransition::Skipped
+    } else {
+        DstTransition::None
+    }
+}
+
+/// Bounds the expansion of (potentially non-terminating) recurring event occurrences, either by
+/// the max number of occurrences expanded per event, or by a horizon timestamp beyond which no
+/// occurrences are expanded.
+///
+/// Reaching either bound is recorded (and shared between clones) so that the caller can surface
+/// that the results are potentially partial.
+///
+/// Events whose expansion exceeds either of the (optional) slow expansion thresholds are likewise
+/// recorded, so that the caller can surface the specific events responsible for slow queries.
+///
+/// The (optional) safety horizon is a hard bound on how far beyond it's DTSTART the occurrences of
+/// each event are expanded, protecting against pathological recurrences (e.g. an unbounded RRULE
+/// queried with an extreme `X-UNTIL`). Reaching it is recorded separately so that the caller can
+/// surface it distinctly.
+#[derive(Debug, Default, Clone)]
+pub struct OccurrenceExpansionLimits {
+    pub max_occurrences: Option<usize>,
+    pub horizon_timestamp: Option<i64>,
+    pub safety_horizon_years: Option<i64>,
+    pub slow_expansion_duration: Option<Duration>,
+    pub slow_expansion_count: Option<usize>,
+    is_reached: Arc<AtomicBool>,
+    is_safety_horizon_reached: Arc<AtomicBool>,
+    slow_expansions: Arc<Mutex<BTreeMap<String, SlowExpansion>>>,
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+// The number of occurrences initially expanded at a time when iterating backwards, with each
+// successive window of occurrences expanded being twice the span of the last.
+const BACK_WINDOW_OCCURRENCES: i64 = 64;
+
+// The execution deadline is only checked once every this many expanded occurrences, as reading the
+// clock for each one is comparatively expensive when expanding cheap (e.g. MINUTELY) recurrences.
+const EXECUTION_DEADLINE_CHECK_INTERVAL: usize = 64;
+
+/// The occurrence expansion of a specific event exceeding either of the slow expansion
+/// thresholds, with the number of occurrences expanded and the time spent expanding them.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SlowExpansion {
+    pub event_uid: String,
+    pub expanded_count: usize,
+    pub duration: Duration,
+}
+
+impl OccurrenceExpansionLimits {
+    pub fn new(max_occurrences: Option<usize>, horizon_timestamp: Option<i64>) -> Self {
+        OccurrenceExpansionLimits {
+            max_occurrences,
+            horizon_timestamp,
+            safety_horizon_years: None,
+            slow_expansion_duration: None,
+            slow_expansion_count: None,
+            is_reached: Arc::new(AtomicBool::new(false)),
+            is_safety_horizon_reached: Arc::new(AtomicBool::new(false)),
+            slow_expansions: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Record the events whose occurrence expansion takes at least the duration, or expands at
+    /// least the count of occurrences.
+    pub fn with_slow_expansion_thresholds(mut self, slow_expansion_duration: Option<Duration>, slow_expansion_count: Option<usize>) -> Self {
+        self.slow_expansion_duration = slow_expansion_duration;
+        self.slow_expansion_count = slow_expansion_count;
+
+        self
+    }
+
+    /// Stop expanding the occurrences of each event beyond the number of years after it's DTSTART.
+    pub fn with_safety_horizon_years(mut self, safety_horizon_years: Option<i64>) -> Self {
+        self.safety_horizon_years = safety_horizon_years;
+
+        self
+    }
+
+    pub fn is_reached(&self) -> bool {
+        self.is_reached.load(Ordering::Relaxed)
+    }
+
+    /// Whether the expansion of any event was cut short by the safety horizon (also flagging the
+    /// limits as reached).
+    pub fn is_safety_horizon_reached(&self) -> bool {
+        self.is_safety_horizon_reached.load(Ordering::Relaxed)
+    }
+
+    pub fn has_slow_expansion_thresholds(&self) -> bool {
+        self.slow_expansion_duration.is_some() || self.slow_expansion_count.is_some()
+    }
+
+    /// The recorded slow expansions, ordered by event UID.
+    pub fn get_slow_expansions(&self) -> Vec<SlowExpansion> {
+        self.slow_expansions.lock().unwrap().values().cloned().collect()
+    }
+
+    // Record (or update) the expansion of the event if it exceeds either slow expansion threshold.
+    pub(crate) fn record_expansion(&self, event_uid: &str, expanded_count: usize, duration: Duration) {
+        let is_slow =
+            self.slow_expansion_duration.is_some_and(|slow_expansion_duration| duration >= slow_expansion_duration)
+                || self.slow_expansion_count.is_some_and(|slow_expansion_count| expanded_count >= slow_expansion_count);
+
+        if !is_slow {
+            return;
+        }
+
+        self.slow_expansions.lock().unwrap().insert(
+            event_uid.to_owned(),
+            SlowExpansion {
+                event_uid: event_uid.to_owned(),
+                expanded_count,
+                duration,
+            },
+        );
+    }
+
+    fn is_exceeded_by(&self, expanded_count: usize, dtstart_timestamp: i64) -> bool {
+        self.max_occurrences.is_some_and(|max_occurrences| expanded_count >= max_occurrences)
+            || self.horizon_timestamp.is_some_and(|horizon_timestamp| dtstart_timestamp > horizon_timestamp)
+    }
+
+    fn flag_reached(&self) {
+        self.is_reached.store(true, Ordering::Relaxed);
+    }
+
+    fn flag_safety_horizon_reached(&self) {
+        self.is_safety_horizon_reached.store(true, Ordering::Relaxed);
+    }
+}
+
 #[derive(Debug)]
 pub struct EventOccurrenceIterator<'a> {
     event_occurrence_overrides: BTreeMap<i64, EventOccurrenceOverride>,
@@ -62,11 +408,29 @@ pub struct EventOccurrenceIterator<'a> {
     base_duration: i64,
     limit: Option<usize>,
     count: usize,
+    expanded_count: usize,
     is_ended: bool,
     filter_from: Option<LowerBoundFilterCondition>,
     filter_until: Option<UpperBoundFilterCondition>,
+    filter_durations: Vec<DurationFilterCondition>,
     filtering_indexed_conclusion: Option<IndexedConclusion>,
     internal_min_max_bounds: Option<(i64, i64)>,
+    execution_deadline: ExecutionDeadline,
+    is_execution_deadline_reached: bool,
+    occurrence_expansion_limits: OccurrenceExpansionLimits,
+    safety_horizon_timestamp: Option<i64>,
+    seek_timestamp: Option<i64>,
+    seek_skipped_count: usize,
+    parsed_rrule_set: Option<&'a rrule::RRuleSet>,
+    dst_policy: DstPolicy,
+    dst_local_times: Vec<NaiveTime>,
+    pending_dtstarts: BTreeSet<chrono::DateTime<rrule::Tz>>,
+    peeked_dtstart: Option<chrono::DateTime<rrule::Tz>>,
+    dtstart_window: Option<(i64, i64)>,
+    last_dtstart_timestamp: Option<i64>,
+    back_window_from: Option<i64>,
+    back_window_span: i64,
+    back_buffer: VecDeque<(i64, i64, Option<EventOccurrenceOverride>)>,
 }
 
 impl<'a> EventOccurrenceIterator<'a> {
@@ -105,14 +469,217 @@ impl<'a> EventOccurrenceIterator<'a> {
             base_duration,
             limit,
             count,
+            expanded_count: 0,
             is_ended,
             filter_from,
             filter_until,
+            filter_durations: Vec::new(),
             filtering_indexed_conclusion,
             internal_min_max_bounds,
+            execution_deadline: ExecutionDeadline::default(),
+            is_execution_deadline_reached: false,
+            occurrence_expansion_limits: OccurrenceExpansionLimits::default(),
+            safety_horizon_timestamp: None,
+            seek_timestamp: None,
+            seek_skipped_count: 0,
+            parsed_rrule_set: schedule_properties.parsed_rrule_set.as_ref(),
+            dst_policy: DstPolicy::default(),
+            dst_local_times: Vec::new(),
+            pending_dtstarts: BTreeSet::new(),
+            peeked_dtstart: None,
+            dtstart_window: None,
+            last_dtstart_timestamp: None,
+            back_window_from: None,
+            back_window_span: 0,
+            back_buffer: VecDeque::new(),
         })
     }
 
+    /// Only yield occurrences with an (overridden) duration satisfying all the provided duration
+    /// filter conditions.
+    pub fn with_duration_filter_conditions(mut self, filter_durations: Vec<DurationFilterCondition>) -> Self {
+        self.filter_durations = filter_durations;
+
+        self
+    }
+
+    /// Stop expanding occurrences once the execution deadline has been exceeded (checked every
+    /// `EXECUTION_DEADLINE_CHECK_INTERVAL` occurrences expanded), it is left to the caller to
+    /// check the deadline (or `is_execution_deadline_reached`) and surface the timeout.
+    pub fn with_execution_deadline(mut self, execution_deadline: ExecutionDeadline) -> Self {
+        self.execution_deadline = execution_deadline;
+
+        self
+    }
+
+    /// Stop expanding occurrences once either of the occurrence expansion limits are exceeded,
+    /// flagging them as reached if there were occurrences within the filtered bounds remaining.
+    pub fn with_occurrence_expansion_limits(mut self, occurrence_expansion_limits: OccurrenceExpansionLimits) -> Self {
+        // The safety horizon falls on the same date (and time) the number of years after the
+        // DTSTART in it's timezone, or is unbounded if that is beyond the supported date range.
+        self.safety_horizon_timestamp =
+            occurrence_expansion_limits
+                .safety_horizon_years
+                .zip(self.parsed_rrule_set)
+                .map(|(safety_horizon_years, parsed_rrule_set)| {
+                    let safety_horizon_months = safety_horizon_years.saturating_mul(12).clamp(0, u32::MAX as i64) as u32;
+
+                    parsed_rrule_set
+                        .get_dt_start()
+                        .checked_add_months(Months::new(safety_horizon_months))
+                        .map_or(i64::MAX, |safety_horizon| safety_horizon.timestamp())
+                });
+
+        self.occurrence_expansion_limits = occurrence_expansion_limits;
+
+        self
+    }
+
+    /// Resolve occurrences landing on a local time skipped or repeated by a DST transition as
+    /// specified by the policy (by default they are shifted forward).
+    pub fn with_dst_policy(mut self, dst_policy: DstPolicy) -> Self {
+        self.dst_policy = dst_policy;
+
+        // The default policy is how the occurrences are expanded already, so there is nothing to
+        // detect.
+        self.dst_local_times =
+            match (dst_policy, self.parsed_rrule_set) {
+                (DstPolicy::ShiftForward, _) | (_, None) => Vec::new(),
+                (_, Some(parsed_rrule_set)) => get_recurring_local_times(parsed_rrule_set),
+            };
+
+        self
+    }
+
+    /// Skip the occurrences starting before the seek timestamp without counting them as expanded
+    /// (so that they do not count towards the max occurrences expanded), expanding the recurrence
+    /// from the seeked RRULE set (see `build_seeked_rrule_set`) if provided.
+    pub fn with_seek(mut self, seek_timestamp: i64, seeked_rrule_set: Option<&'a rrule::RRuleSet>) -> Self {
+        self.seek_timestamp = Some(seek_timestamp);
+
+        if let Some(seeked_rrule_set) = seeked_rrule_set {
+            self.rrule_set_iter = Some(seeked_rrule_set.into_iter());
+        }
+
+        self
+    }
+
+    /// The number of occurrences expanded so far.
+    pub fn get_expanded_count(&self) -> usize {
+        self.expanded_count
+    }
+
+    /// Whether the expansion of occurrences was cut short by the execution deadline.
+    pub fn is_execution_deadline_reached(&self) -> bool {
+        self.is_execution_deadline_reached
+    }
+
+    // The exclusive upper bound of the DTSTART of any occurrence yielded, derived from the filtered
+    // upper bound, the occurrence expansion limits, or the end of the recurrence itself (when each
+    // RRULE has an UNTIL), if any.
+    fn get_dtstart_upper_bound(&self) -> Option<i64> {
+        let filtered_upper_bound =
+            self.filter_until
+                .as_ref()
+                .map(|filter_until| {
+                    match filter_until {
+                        UpperBoundFilterCondition::LessThan(FilterProperty::DtStart(comparison))
+                        | UpperBoundFilterCondition::LessThan(FilterProperty::DtEnd(comparison)) => *comparison,
+
+                        UpperBoundFilterCondition::LessEqualThan(FilterProperty::DtStart(comparison))
+                        | UpperBoundFilterCondition::LessEqualThan(FilterProperty::DtEnd(comparison)) => comparison.saturating_add(1),
+                    }
+                });
+
+        let recurrence_upper_bound =
+            self.parsed_rrule_set
+                .filter(|parsed_rrule_set| parsed_rrule_set.get_rrule().iter().all(|rrule| rrule.get_until().is_some()))
+                .and_then(|parsed_rrule_set| {
+                    parsed_rrule_set
+                        .get_rrule()
+                        .iter()
+                        .filter_map(|rrule| rrule.get_until().map(|until| until.timestamp()))
+                        .chain(parsed_rrule_set.get_rdate().iter().map(|rdate| rdate.timestamp()))
+                        .chain(std::iter::once(parsed_rrule_set.get_dt_start().timestamp()))
+                        .max()
+                })
+                .map(|last_timestamp| last_timestamp.saturating_add(1));
+
+        [
+            filtered_upper_bound,
+            self.internal_min_max_bounds.map(|(_min, max)| max.saturating_add(1)),
+            self.safety_horizon_timestamp.map(|safety_horizon_timestamp| safety_horizon_timestamp.saturating_add(1)),
+            self.occurrence_expansion_limits.horizon_timestamp.map(|horizon_timestamp| horizon_timestamp.saturating_add(1)),
+            recurrence_upper_bound,
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    // The inclusive lower bound of the DTSTART of any occurrence yet to be yielded, derived from
+    // the start of the recurrence, the filtered lower bound, and the occurrences already yielded
+    // from the front.
+    fn get_dtstart_lower_bound(&self) -> i64 {
+        let filtered_lower_bound =
+            match self.filter_from {
+                Some(LowerBoundFilterCondition::GreaterThan(FilterProperty::DtStart(comparison))) => Some(comparison.saturating_add(1)),
+                Some(LowerBoundFilterCondition::GreaterEqualThan(FilterProperty::DtStart(comparison))) => Some(comparison),
+
+                // Long enough occurrences starting any time before still end after it.
+                _ => None,
+            };
+
+        let recurrence_lower_bound =
+            self.parsed_rrule_set
+                .and_then(|parsed_rrule_set| {
+                    parsed_rrule_set
+                        .get_rdate()
+                        .iter()
+                        .map(|rdate| rdate.timestamp())
+                        .chain(std::iter::once(parsed_rrule_set.get_dt_start().timestamp()))
+                        .min()
+                });
+
+        [
+            recurrence_lower_bound,
+            filtered_lower_bound,
+            self.internal_min_max_bounds.map(|(min, _max)| min),
+            self.last_dtstart_timestamp.map(|last_dtstart_timestamp| last_dtstart_timestamp.saturating_add(1)),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(i64::MAX)
+    }
+
+    // The span of the first window of occurrences expanded when iterating backwards, enough for
+    // `BACK_WINDOW_OCCURRENCES` occurrences of the most frequent RRULE (or days for RDATEs only).
+    fn get_initial_back_window_span(&self) -> i64 {
+        let frequency_seconds = |frequency: &rrule::Frequency| -> i64 {
+            match frequency {
+                rrule::Frequency::Yearly => 366 * SECONDS_PER_DAY,
+                rrule::Frequency::Monthly => 31 * SECONDS_PER_DAY,
+                rrule::Frequency::Weekly => 7 * SECONDS_PER_DAY,
+                rrule::Frequency::Daily => SECONDS_PER_DAY,
+                rrule::Frequency::Hourly => 3600,
+                rrule::Frequency::Minutely => 60,
+                rrule::Frequency::Secondly => 1,
+            }
+        };
+
+        self.parsed_rrule_set
+            .and_then(|parsed_rrule_set| {
+                parsed_rrule_set
+                    .get_rrule()
+                    .iter()
+                    .map(|rrule| frequency_seconds(&rrule.get_freq()).saturating_mul(rrule.get_interval().max(1) as i64))
+                    .min()
+            })
+            .unwrap_or(SECONDS_PER_DAY)
+            .saturating_mul(BACK_WINDOW_OCCURRENCES)
+    }
+
     fn is_within_limit(&self) -> bool {
         self.limit.is_none() || matches!(self.limit, Some(limit) if limit > self.count)
     }
@@ -282,29 +849,128 @@ impl<'a> EventOccurrenceIterator<'a> {
             }
         }
 
+        if self
+            .filter_durations
+            .iter()
+            .any(|filter_duration| !filter_duration.is_satisfied_by(duration))
+        {
+            return true;
+        }
+
         false
     }
 
     fn rrule_set_iter_next(&mut self) -> Option<chrono::DateTime<rrule::Tz>> {
-        match &mut self.rrule_set_iter {
-            Some(rrule_set_iter) => rrule_set_iter.next(),
-            None => None,
+        loop {
+            let next_dtstart =
+                match self.peeked_dtstart.take() {
+                    Some(peeked_dtstart) => Some(peeked_dtstart),
+                    None => self.rrule_set_iter.as_mut().and_then(|rrule_set_iter| rrule_set_iter.next()),
+                };
+
+            // The later instants of repeated local times are yielded in order, so are held back
+            // until the next expanded occurrence is later still.
+            if let Some(pending_dtstart) = self.pending_dtstarts.first().cloned() {
+                match &next_dtstart {
+                    Some(next_dtstart) if next_dtstart < &pending_dtstart => {},
+
+                    Some(next_dtstart) if next_dtstart == &pending_dtstart => {
+                        self.pending_dtstarts.remove(&pending_dtstart);
+                    },
+
+                    _ => {
+                        self.peeked_dtstart = next_dtstart;
+
+                        return self.pending_dtstarts.pop_first();
+                    },
+                }
+            }
+
+            let next_dtstart = next_dtstart?;
+
+            if self.dst_local_times.is_empty() {
+                return Some(next_dtstart);
+            }
+
+            match classify_dst_transition(&next_dtstart, &self.dst_local_times) {
+                DstTransition::Skipped if self.dst_policy == DstPolicy::Skip => {
+                    continue;
+                },
+
+                DstTransition::Repeated(latest) if self.dst_policy == DstPolicy::BothInstants => {
+                    self.pending_dtstarts.insert(latest);
+
+                    return Some(next_dtstart);
+                },
+
+                _ => {
+                    return Some(next_dtstart);
+                },
+            }
         }
     }
 }
 
-impl Iterator for EventOccurrenceIterator<'_> {
-    type Item = (i64, i64, Option<EventOccurrenceOverride>);
-    fn next(&mut self) -> Option<Self::Item> {
+impl EventOccurrenceIterator<'_> {
+    fn next_forward(&mut self) -> Option<(i64, i64, Option<EventOccurrenceOverride>)> {
         if self.is_ended {
             return None;
         }
 
         while self.is_within_limit() {
+            if (self.expanded_count + self.seek_skipped_count).is_multiple_of(EXECUTION_DEADLINE_CHECK_INTERVAL) && self.execution_deadline.is_exceeded() {
+                self.is_execution_deadline_reached = true;
+                self.is_ended = true;
+
+                break;
+            }
+
             if let Some(dtstart) = self.rrule_set_iter_next() {
                 let dtstart_timestamp = dtstart.timestamp();
                 let mut duration = self.base_duration;
 
+                let is_beyond_safety_horizon =
+                    self.safety_horizon_timestamp
+                        .is_some_and(|safety_horizon_timestamp| dtstart_timestamp > safety_horizon_timestamp);
+
+                if is_beyond_safety_horizon || self.occurrence_expansion_limits.is_exceeded_by(self.expanded_count, dtstart_timestamp) {
+                    // Occurrences beyond the filtered upper bound would not have been returned
+                    // anyway, so the results are only partial if the end has not been reached.
+                    if !self.has_reached_the_end(&dtstart_timestamp) {
+                        self.occurrence_expansion_limits.flag_reached();
+
+                        if is_beyond_safety_horizon {
+                            self.occurrence_expansion_limits.flag_safety_horizon_reached();
+                        }
+                    }
+
+                    self.is_ended = true;
+
+                    break;
+                }
+
+                if let Some((window_from, window_until)) = self.dtstart_window {
+                    if dtstart_timestamp >= window_until {
+                        self.is_ended = true;
+
+                        break;
+                    }
+
+                    if dtstart_timestamp < window_from {
+                        self.expanded_count += 1;
+
+                        continue;
+                    }
+                }
+
+                if self.seek_timestamp.is_some_and(|seek_timestamp| dtstart_timestamp < seek_timestamp) {
+                    self.seek_skipped_count += 1;
+
+                    continue;
+                }
+
+                self.expanded_count += 1;
+
                 if self
                     .is_excluded_by_pre_override_enrichment_filters(&dtstart_timestamp, &duration)
                 {
@@ -321,6 +987,12 @@ impl Iterator for EventOccurrenceIterator<'_> {
                     self.event_occurrence_overrides.get(&dtstart_timestamp);
 
                 if let Some(event_occurrenece_override) = event_occurrenece_override {
+                    // Occurrences cancelled via override (STATUS:CANCELLED) are skipped as if
+                    // excluded by EXDATE.
+                    if event_occurrenece_override.is_cancelled() {
+                        continue;
+                    }
+
                     duration = match event_occurrenece_override.get_duration_in_seconds() {
                         Some(duration) => duration,
                         _ => self.base_duration,
@@ -357,6 +1029,195 @@ impl Iterator for EventOccurrenceIterator<'_> {
     }
 }
 
+impl<'a> EventOccurrenceIterator<'a> {
+    /// Returns the next occurrence from the back (the latest occurrence within the upper bound not
+    /// yet yielded from either end).
+    ///
+    /// The occurrences are expanded backwards from the upper bound of the iterator (filtered
+    /// DTSTART/DTEND, occurrence expansion limits, or the UNTIL of the recurrence) in windows of
+    /// successively doubling span, so only a bounded number are held at once. Iterators bounded by
+    /// a count instead (limit, max occurrences, or the COUNT of the recurrence) have no known upper
+    /// bound to start from, so the (bounded number of) occurrences are expanded forwards once.
+    ///
+    /// Indefinitely recurring events without any such bound have no final occurrence, so iterating
+    /// them backwards is an error.
+    pub fn try_next_back(&mut self) -> Result<Option<(i64, i64, Option<EventOccurrenceOverride>)>, String> {
+        self.start_back_expansion()?;
+
+        Ok(self.next_back_occurrence())
+    }
+
+    /// Consumes the iterator, yielding the occurrences in reverse order (see `try_next_back`).
+    pub fn try_rev(mut self) -> Result<impl Iterator<Item = (i64, i64, Option<EventOccurrenceOverride>)> + 'a, String> {
+        self.start_back_expansion()?;
+
+        Ok(std::iter::from_fn(move || self.next_back_occurrence()))
+    }
+
+    pub(crate) fn start_back_expansion(&mut self) -> Result<(), String> {
+        if self.back_window_from.is_some() {
+            return Ok(());
+        }
+
+        let is_count_bounded =
+            self.limit.is_some()
+                || self.occurrence_expansion_limits.max_occurrences.is_some()
+                || self.parsed_rrule_set.is_none_or(|parsed_rrule_set| {
+                    parsed_rrule_set.get_rrule().iter().all(|rrule| rrule.get_count().is_some() || rrule.get_until().is_some())
+                });
+
+        // The first `limit` occurrences (or the first `max_occurrences` expanded) are only known
+        // by expanding forwards, so the upper bound is disregarded.
+        let dtstart_upper_bound =
+            if self.limit.is_some() || self.occurrence_expansion_limits.max_occurrences.is_some() {
+                None
+            } else {
+                self.get_dtstart_upper_bound()
+            };
+
+        match dtstart_upper_bound {
+            Some(dtstart_upper_bound) => {
+                self.back_window_from = Some(dtstart_upper_bound);
+                self.back_window_span = self.get_initial_back_window_span();
+            },
+
+            None if is_count_bounded => {
+                while let Some(occurrence) = self.next_forward() {
+                    self.back_buffer.push_back(occurrence);
+                }
+
+                self.back_window_from = Some(i64::MIN);
+            },
+
+            None => {
+                return Err(String::from("Cannot iterate the occurrences of an indefinitely recurring event in reverse without an upper bound"));
+            },
+        }
+
+        Ok(())
+    }
+
+    fn next_back_occurrence(&mut self) -> Option<(i64, i64, Option<EventOccurrenceOverride>)> {
+        loop {
+            if let Some(occurrence) = self.back_buffer.pop_back() {
+                return Some(occurrence);
+            }
+
+            if !self.expand_previous_back_window() {
+                return None;
+            }
+        }
+    }
+
+    // Expand the window of occurrences preceding those already expanded backwards (but following
+    // those already yielded from the front) into the back buffer, returning whether there was one.
+    fn expand_previous_back_window(&mut self) -> bool {
+        let Some(window_until) = self.back_window_from else {
+            return false;
+        };
+
+        let dtstart_lower_bound = self.get_dtstart_lower_bound();
+
+        if self.is_execution_deadline_reached || window_until <= dtstart_lower_bound {
+            return false;
+        }
+
+        let window_from = window_until.saturating_sub(self.back_window_span).max(dtstart_lower_bound);
+
+        let mut window_iterator = self.build_window_iterator(window_from, window_until);
+        let window_occurrences = window_iterator.by_ref().collect::<VecDeque<_>>();
+
+        self.expanded_count += window_iterator.expanded_count;
+
+        // Only part of the window was expanded, so it's latest occurrences are unknown.
+        if window_iterator.is_execution_deadline_reached {
+            self.is_execution_deadline_reached = true;
+
+            return false;
+        }
+
+        self.back_buffer = window_occurrences;
+        self.back_window_from = Some(window_from);
+        self.back_window_span = self.back_window_span.saturating_mul(2);
+
+        true
+    }
+
+    // An iterator of the occurrences starting within the window (as filtered by this one), expanded
+    // afresh from the start of the recurrence.
+    fn build_window_iterator(&self, window_from: i64, window_until: i64) -> EventOccurrenceIterator<'a> {
+        let event_occurrence_overrides =
+            self.event_occurrence_overrides
+                .range(window_from..window_until)
+                .map(|(timestamp, event_occurrence_override)| (*timestamp, event_occurrence_override.clone()))
+                .collect();
+
+        EventOccurrenceIterator {
+            event_occurrence_overrides,
+            rrule_set_iter: self.parsed_rrule_set.map(|parsed_rrule_set| parsed_rrule_set.into_iter()),
+            base_duration: self.base_duration,
+            limit: None,
+            count: 0,
+            expanded_count: 0,
+            is_ended: false,
+            filter_from: self.filter_from.clone(),
+            filter_until: self.filter_until.clone(),
+            filter_durations: self.filter_durations.clone(),
+            filtering_indexed_conclusion: self.filtering_indexed_conclusion.clone(),
+            internal_min_max_bounds: self.internal_min_max_bounds,
+            execution_deadline: self.execution_deadline,
+            is_execution_deadline_reached: false,
+            occurrence_expansion_limits: self.occurrence_expansion_limits.clone(),
+            safety_horizon_timestamp: self.safety_horizon_timestamp,
+            seek_timestamp: self.seek_timestamp,
+            seek_skipped_count: 0,
+            parsed_rrule_set: self.parsed_rrule_set,
+            dst_policy: self.dst_policy,
+            dst_local_times: self.dst_local_times.clone(),
+            pending_dtstarts: BTreeSet::new(),
+            peeked_dtstart: None,
+            dtstart_window: Some((window_from, window_until)),
+            last_dtstart_timestamp: None,
+            back_window_from: None,
+            back_window_span: 0,
+            back_buffer: VecDeque::new(),
+        }
+    }
+}
+
+impl Iterator for EventOccurrenceIterator<'_> {
+    type Item = (i64, i64, Option<EventOccurrenceOverride>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Once iterating backwards, the occurrences from the earliest window expanded onwards are
+        // yielded from the back buffer instead.
+        let next_occurrence =
+            match self.back_window_from {
+                None => self.next_forward(),
+
+                Some(back_window_from) => {
+                    let next_occurrence = if self.is_ended { None } else { self.next_forward() };
+
+                    match next_occurrence {
+                        Some(next_occurrence) if next_occurrence.0 < back_window_from => Some(next_occurrence),
+
+                        _ => {
+                            self.is_ended = true;
+
+                            self.back_buffer.pop_front()
+                        },
+                    }
+                },
+            };
+
+        if let Some((dtstart_timestamp, _dtend_timestamp, _event_occurrence_override)) = &next_occurrence {
+            self.last_dtstart_timestamp = Some(*dtstart_timestamp);
+        }
+
+        next_occurrence
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -377,10 +1238,10 @@ mod test {
 
     fn build_schedule_properties() -> ScheduleProperties {
         let mut schedule_properties = ScheduleProperties {
-            rrule: Some(build_property_from_ical!(
+            rrules: Some(HashSet::from([build_property_from_ical!(
                 RRuleProperty,
                 "RRULE:FREQ=SECONDLY;COUNT=10;INTERVAL=100"
-            )),
+            )])),
             exrule: None,
             rdates: None,
             exdates: None,
@@ -407,6 +1268,7 @@ mod test {
             indexed_properties: IndexedProperties {
                 geo: None,
                 class: None,
+                location: None,
                 location_type: None,
                 categories: None,
                 related_to: None,
@@ -418,6 +1280,7 @@ mod test {
                 "DTSTART:19700101T000500Z"
             )),
             dtend: None,
+            override_mode: None,
         }
     }
 
@@ -427,6 +1290,7 @@ mod test {
             indexed_properties: IndexedProperties {
                 geo: None,
                 class: None,
+                location: None,
                 location_type: None,
                 categories: None,
                 related_to: None,
@@ -441,6 +1305,7 @@ mod test {
                 DTEndProperty,
                 "DTEND:19700101T000830Z"
             )),
+            override_mode: None,
         }
     }
 
@@ -450,6 +1315,7 @@ mod test {
             indexed_properties: IndexedProperties {
                 geo: None,
                 class: None,
+                location: None,
                 location_type: None,
                 categories: None,
                 related_to: None,
@@ -461,6 +1327,7 @@ mod test {
                 "DTSTART:19700101T001140Z"
             )),
             dtend: None,
+            override_mode: None,
         }
     }
 
@@ -470,6 +1337,7 @@ mod test {
             indexed_properties: IndexedProperties {
                 geo: None,
                 class: None,
+                location: None,
                 location_type: None,
                 categories: None,
                 related_to: None,
@@ -484,6 +1352,7 @@ mod test {
                 DTEndProperty,
                 "DTEND:19700101T001515Z"
             )),
+            override_mode: None,
         }
     }
 
@@ -591,18 +1460,547 @@ mod test {
     }
 
     #[test]
-    fn test_event_occurrence_iterator_filters_gt_dtstart() {
-        // Test filters -- greater equal than - DtStart
+    fn test_event_occurrence_iterator_reversed() {
         let schedule_properties = build_schedule_properties();
         let event_occurrence_overrides = build_event_occurrence_overrides();
 
-        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+        let event_occurrence_iterator = EventOccurrenceIterator::new(
             &schedule_properties,
             &event_occurrence_overrides,
             None,
-            Some(LowerBoundFilterCondition::GreaterEqualThan(
-                FilterProperty::DtStart(800),
-            )),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            event_occurrence_iterator
+                .try_rev()
+                .unwrap()
+                .map(|(dtstart_timestamp, dtend_timestamp, _event_occurrence_override)| (dtstart_timestamp, dtend_timestamp))
+                .collect::<Vec<(i64, i64)>>(),
+            vec![
+                (900, 915),
+                (800, 805),
+                (700, 705),
+                (600, 605),
+                (500, 510),
+                (400, 405),
+                (300, 305),
+                (200, 205),
+                (100, 105),
+                (0, 5),
+            ],
+        );
+
+        // Iterating from both ends (within the filtered upper bound) meets in the middle.
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            Some(UpperBoundFilterCondition::LessThan(FilterProperty::DtStart(500))),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            event_occurrence_iterator.try_next_back(),
+            Ok(Some((400, 405, None)))
+        );
+
+        assert_eq!(event_occurrence_iterator.next(), Some((0, 5, None)));
+        assert_eq!(event_occurrence_iterator.next(), Some((100, 105, None)));
+
+        assert_eq!(
+            event_occurrence_iterator.try_next_back(),
+            Ok(Some((300, 305, Some(build_event_occurrence_override_300()))))
+        );
+
+        assert_eq!(event_occurrence_iterator.try_next_back(), Ok(Some((200, 205, None))));
+        assert_eq!(event_occurrence_iterator.try_next_back(), Ok(None));
+        assert_eq!(event_occurrence_iterator.next(), None);
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_reversed_indefinite_recurrence() {
+        let mut schedule_properties = build_schedule_properties();
+
+        schedule_properties.rrules = Some(HashSet::from([build_property_from_ical!(RRuleProperty, "RRULE:FREQ=SECONDLY;INTERVAL=100")]));
+
+        assert!(schedule_properties.build_parsed_rrule_set().is_ok());
+
+        let event_occurrence_overrides = BTreeMap::new();
+
+        // Nothing bounds the expansion, so there is no final occurrence to start from.
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            event_occurrence_iterator.try_next_back(),
+            Err(String::from("Cannot iterate the occurrences of an indefinitely recurring event in reverse without an upper bound")),
+        );
+
+        // Iterating forwards is unaffected.
+        assert_eq!(event_occurrence_iterator.next(), Some((0, 5, None)));
+
+        assert!(
+            EventOccurrenceIterator::new(&schedule_properties, &event_occurrence_overrides, None, None, None, None)
+                .unwrap()
+                .try_rev()
+                .is_err()
+        );
+
+        // The most recent occurrence starting before the filtered upper bound.
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            Some(UpperBoundFilterCondition::LessThan(FilterProperty::DtStart(100_050))),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(event_occurrence_iterator.try_next_back(), Ok(Some((100_000, 100_005, None))));
+        assert_eq!(event_occurrence_iterator.try_next_back(), Ok(Some((99_900, 99_905, None))));
+
+        // Only the first window of 64 occurrences (64 * 100 seconds) is held, rather than all
+        // 1001 preceding the filtered upper bound.
+        assert_eq!(event_occurrence_iterator.back_window_from, Some(100_050 - 6_400));
+        assert_eq!(event_occurrence_iterator.back_buffer.len(), 62);
+
+        // Iterating from both ends across successive windows meets in the middle.
+        assert_eq!(event_occurrence_iterator.next(), Some((0, 5, None)));
+
+        let mut remaining_dtstart_timestamps = Vec::new();
+
+        while let Some((dtstart_timestamp, _dtend_timestamp, _event_occurrence_override)) = event_occurrence_iterator.try_next_back().unwrap() {
+            remaining_dtstart_timestamps.push(dtstart_timestamp);
+        }
+
+        assert_eq!(remaining_dtstart_timestamps, (1..=998).rev().map(|index| index * 100).collect::<Vec<i64>>());
+        assert_eq!(event_occurrence_iterator.next(), None);
+
+        // Bounded by the occurrence expansion limits instead.
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_occurrence_expansion_limits(OccurrenceExpansionLimits::new(Some(3), None));
+
+        assert_eq!(event_occurrence_iterator.try_next_back(), Ok(Some((200, 205, None))));
+        assert_eq!(event_occurrence_iterator.next(), Some((0, 5, None)));
+        assert_eq!(event_occurrence_iterator.try_next_back(), Ok(Some((100, 105, None))));
+        assert_eq!(event_occurrence_iterator.next(), None);
+
+        // The horizon timestamp is an upper bound to expand backwards from, flagged as reached.
+        let occurrence_expansion_limits = OccurrenceExpansionLimits::new(None, Some(1_000));
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_occurrence_expansion_limits(occurrence_expansion_limits.clone());
+
+        assert_eq!(event_occurrence_iterator.try_next_back(), Ok(Some((1_000, 1_005, None))));
+        assert!(occurrence_expansion_limits.is_reached());
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_with_exceeded_execution_deadline() {
+        let schedule_properties = build_schedule_properties();
+        let event_occurrence_overrides = build_event_occurrence_overrides();
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_execution_deadline(ExecutionDeadline::new(std::time::Duration::ZERO));
+
+        assert_eq!(event_occurrence_iterator.next(), None);
+        assert!(event_occurrence_iterator.is_execution_deadline_reached());
+
+        // Exhausting the occurrences within the deadline does not flag it as reached.
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_execution_deadline(ExecutionDeadline::new(std::time::Duration::from_secs(60)));
+
+        assert!(event_occurrence_iterator.by_ref().count() > 0);
+        assert!(!event_occurrence_iterator.is_execution_deadline_reached());
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_execution_deadline_check_interval() {
+        let mut schedule_properties = build_schedule_properties();
+
+        schedule_properties.rrules = Some(HashSet::from([build_property_from_ical!(RRuleProperty, "RRULE:FREQ=SECONDLY;INTERVAL=100")]));
+
+        assert!(schedule_properties.build_parsed_rrule_set().is_ok());
+
+        let event_occurrence_overrides = BTreeMap::new();
+
+        let execution_deadline = ExecutionDeadline::new(std::time::Duration::from_millis(20));
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_execution_deadline(execution_deadline);
+
+        // The first occurrence is expanded within the deadline, then the indefinite recurrence is
+        // only cut short at the next check once the deadline has been exceeded.
+        assert!(event_occurrence_iterator.next().is_some());
+
+        std::thread::sleep(std::time::Duration::from_millis(25));
+
+        assert_eq!(event_occurrence_iterator.by_ref().count(), EXECUTION_DEADLINE_CHECK_INTERVAL - 1);
+        assert!(event_occurrence_iterator.is_execution_deadline_reached());
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_with_occurrence_expansion_limits() {
+        let schedule_properties = build_schedule_properties();
+        let event_occurrence_overrides = build_event_occurrence_overrides();
+
+        let occurrence_expansion_limits = OccurrenceExpansionLimits::new(Some(2), None);
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_occurrence_expansion_limits(occurrence_expansion_limits.clone());
+
+        assert_eq!(event_occurrence_iterator.next(), Some((0, 5, None)));
+        assert_eq!(event_occurrence_iterator.next(), Some((100, 105, None)));
+        assert_eq!(event_occurrence_iterator.next(), None);
+
+        assert_eq!(occurrence_expansion_limits.is_reached(), true);
+
+        let occurrence_expansion_limits = OccurrenceExpansionLimits::new(None, Some(100));
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_occurrence_expansion_limits(occurrence_expansion_limits.clone());
+
+        assert_eq!(event_occurrence_iterator.next(), Some((0, 5, None)));
+        assert_eq!(event_occurrence_iterator.next(), Some((100, 105, None)));
+        assert_eq!(event_occurrence_iterator.next(), None);
+
+        assert_eq!(occurrence_expansion_limits.is_reached(), true);
+
+        // Reaching the horizon beyond the filtered upper bound does not flag the limits as reached.
+        let occurrence_expansion_limits = OccurrenceExpansionLimits::new(None, Some(100));
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            Some(UpperBoundFilterCondition::LessThan(FilterProperty::DtStart(150))),
+            None,
+        )
+        .unwrap()
+        .with_occurrence_expansion_limits(occurrence_expansion_limits.clone());
+
+        assert_eq!(event_occurrence_iterator.next(), Some((0, 5, None)));
+        assert_eq!(event_occurrence_iterator.next(), Some((100, 105, None)));
+        assert_eq!(event_occurrence_iterator.next(), None);
+
+        assert_eq!(occurrence_expansion_limits.is_reached(), false);
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_with_safety_horizon() {
+        let mut event = crate::Event::parse_ical("EVENT_UID", "DTSTART:20000101T000000Z RRULE:FREQ=YEARLY;INTERVAL=1").unwrap();
+
+        event.validate().unwrap();
+
+        let expand_dtstart_timestamps = |occurrence_expansion_limits: &OccurrenceExpansionLimits, filter_until: i64| -> Vec<i64> {
+            EventOccurrenceIterator::new(
+                &event.schedule_properties,
+                &event.overrides,
+                None,
+                None,
+                Some(UpperBoundFilterCondition::LessThan(FilterProperty::DtStart(filter_until))),
+                None,
+            )
+            .unwrap()
+            .with_occurrence_expansion_limits(occurrence_expansion_limits.clone())
+            .map(|(dtstart_timestamp, _dtend_timestamp, _event_occurrence_override)| dtstart_timestamp)
+            .collect()
+        };
+
+        // An unbounded RRULE queried with an extreme upper bound stops at the safety horizon.
+        let occurrence_expansion_limits = OccurrenceExpansionLimits::new(None, None).with_safety_horizon_years(Some(10));
+
+        let dtstart_timestamps = expand_dtstart_timestamps(&occurrence_expansion_limits, i64::MAX);
+
+        assert_eq!(dtstart_timestamps.len(), 11);
+        assert_eq!(dtstart_timestamps.first(), Some(&946684800)); // 2000-01-01T00:00:00Z
+        assert_eq!(dtstart_timestamps.last(), Some(&1262304000)); // 2010-01-01T00:00:00Z
+
+        assert_eq!(occurrence_expansion_limits.is_reached(), true);
+        assert_eq!(occurrence_expansion_limits.is_safety_horizon_reached(), true);
+
+        // Reaching the safety horizon beyond the filtered upper bound does not flag it as reached.
+        let occurrence_expansion_limits = OccurrenceExpansionLimits::new(None, None).with_safety_horizon_years(Some(10));
+
+        let dtstart_timestamps = expand_dtstart_timestamps(&occurrence_expansion_limits, 1104537600); // 2005-01-01T00:00:00Z
+
+        assert_eq!(dtstart_timestamps.len(), 5);
+
+        assert_eq!(occurrence_expansion_limits.is_reached(), false);
+        assert_eq!(occurrence_expansion_limits.is_safety_horizon_reached(), false);
+
+        // Other occurrence expansion limits are not flagged as reaching the safety horizon.
+        let occurrence_expansion_limits = OccurrenceExpansionLimits::new(Some(3), None).with_safety_horizon_years(Some(10));
+
+        assert_eq!(expand_dtstart_timestamps(&occurrence_expansion_limits, i64::MAX).len(), 3);
+
+        assert_eq!(occurrence_expansion_limits.is_reached(), true);
+        assert_eq!(occurrence_expansion_limits.is_safety_horizon_reached(), false);
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_with_seek() {
+        let anchor_timestamp = 1735732800; // 2025-01-01T12:00:00Z
+
+        let expand_dtstart_timestamps = |event: &crate::Event, is_seeked: bool, occurrence_expansion_limits: &OccurrenceExpansionLimits| -> (Vec<i64>, usize) {
+            let seeked_rrule_set =
+                event.schedule_properties
+                    .parsed_rrule_set
+                    .as_ref()
+                    .and_then(|parsed_rrule_set| build_seeked_rrule_set(parsed_rrule_set, anchor_timestamp))
+                    .filter(|_| is_seeked);
+
+            let mut event_occurrence_iterator =
+                EventOccurrenceIterator::new(
+                    &event.schedule_properties,
+                    &event.overrides,
+                    Some(10),
+                    Some(LowerBoundFilterCondition::GreaterEqualThan(FilterProperty::DtStart(anchor_timestamp))),
+                    None,
+                    None,
+                )
+                .unwrap()
+                .with_occurrence_expansion_limits(occurrence_expansion_limits.clone())
+                .with_seek(anchor_timestamp, seeked_rrule_set.as_ref());
+
+            let dtstart_timestamps =
+                event_occurrence_iterator
+                    .by_ref()
+                    .map(|(dtstart_timestamp, _dtend_timestamp, _event_occurrence_override)| dtstart_timestamp)
+                    .collect();
+
+            (dtstart_timestamps, event_occurrence_iterator.get_expanded_count())
+        };
+
+        for (ical, is_seekable) in [
+            ("DTSTART;TZID=Europe/London:19900115T093000 RRULE:FREQ=MONTHLY;INTERVAL=1", true),
+            ("DTSTART;TZID=Europe/London:19900115T093000 RRULE:FREQ=MONTHLY;BYDAY=-1FR;INTERVAL=5", true),
+            ("DTSTART;TZID=Europe/London:19900131T093000 RRULE:FREQ=MONTHLY;BYDAY=-1FR;INTERVAL=5", false),
+            ("DTSTART;TZID=Europe/London:19900101T093000 RRULE:FREQ=WEEKLY;BYDAY=MO,WE;INTERVAL=3", true),
+            ("DTSTART;TZID=Europe/London:19900101T013000 RRULE:FREQ=DAILY;INTERVAL=7 EXDATE;TZID=Europe/London:20250107T013000", true),
+            ("DTSTART;TZID=Europe/London:19960301T180000 RRULE:FREQ=YEARLY;INTERVAL=2", true),
+            ("DTSTART;TZID=Europe/London:19960229T180000 RRULE:FREQ=YEARLY;INTERVAL=1", false),
+            ("DTSTART:20150101T000000Z RRULE:FREQ=HOURLY;INTERVAL=5", true),
+            ("DTSTART:20240101T000000Z RRULE:FREQ=MINUTELY;INTERVAL=7;UNTIL=20250101T130000Z", true),
+            ("DTSTART:19900101T000000Z RRULE:FREQ=DAILY;INTERVAL=1;COUNT=20000", false),
+            ("DTSTART:20241231T000000Z RRULE:FREQ=DAILY;INTERVAL=1", false),
+        ] {
+            let mut event = crate::Event::parse_ical("EVENT_UID", ical).unwrap();
+
+            event.validate().unwrap();
+
+            assert_eq!(
+                build_seeked_rrule_set(event.schedule_properties.parsed_rrule_set.as_ref().unwrap(), anchor_timestamp).is_some(),
+                is_seekable,
+                "ical: {ical}",
+            );
+
+            let (seeked_dtstart_timestamps, seeked_expanded_count) = expand_dtstart_timestamps(&event, true, &OccurrenceExpansionLimits::default());
+            let (dtstart_timestamps, expanded_count) = expand_dtstart_timestamps(&event, false, &OccurrenceExpansionLimits::default());
+
+            // Seeking yields the same occurrences as expanding from the DTSTART.
+            assert_eq!(seeked_dtstart_timestamps, dtstart_timestamps, "ical: {ical}");
+            assert!(!dtstart_timestamps.is_empty(), "ical: {ical}");
+
+            // The occurrences skipped before the anchor are not counted as expanded.
+            assert_eq!(seeked_expanded_count, dtstart_timestamps.len(), "ical: {ical}");
+            assert_eq!(expanded_count, dtstart_timestamps.len(), "ical: {ical}");
+        }
+
+        // The occurrences skipped before the anchor do not count towards the max occurrences expanded.
+        let mut event = crate::Event::parse_ical("EVENT_UID", "DTSTART:19900101T000000Z RRULE:FREQ=DAILY;INTERVAL=1;COUNT=20000").unwrap();
+
+        event.validate().unwrap();
+
+        let occurrence_expansion_limits = OccurrenceExpansionLimits::new(Some(3), None);
+
+        assert_eq!(
+            expand_dtstart_timestamps(&event, false, &occurrence_expansion_limits),
+            (
+                vec![
+                    1735776000, // 2025-01-02T00:00:00Z
+                    1735862400, // 2025-01-03T00:00:00Z
+                    1735948800, // 2025-01-04T00:00:00Z
+                ],
+                3,
+            ),
+        );
+
+        assert_eq!(occurrence_expansion_limits.is_reached(), true);
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_filters_duration() {
+        let schedule_properties = build_schedule_properties();
+        let event_occurrence_overrides = build_event_occurrence_overrides();
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_duration_filter_conditions(vec![DurationFilterCondition::GreaterEqualThan(10)]);
+
+        assert_eq!(
+            event_occurrence_iterator.next(),
+            Some((500, 510, Some(build_event_occurrence_override_500())))
+        );
+
+        assert_eq!(
+            event_occurrence_iterator.next(),
+            Some((900, 915, Some(build_event_occurrence_override_900())))
+        );
+
+        assert_eq!(event_occurrence_iterator.next(), None);
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            Some(2),
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_duration_filter_conditions(vec![
+            DurationFilterCondition::GreaterThan(5),
+            DurationFilterCondition::LessEqualThan(10),
+        ]);
+
+        assert_eq!(
+            event_occurrence_iterator.next(),
+            Some((500, 510, Some(build_event_occurrence_override_500())))
+        );
+
+        assert_eq!(event_occurrence_iterator.next(), None);
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_cancelled_override() {
+        let schedule_properties = build_schedule_properties();
+        let mut event_occurrence_overrides = build_event_occurrence_overrides();
+
+        event_occurrence_overrides.insert(
+            400,
+            EventOccurrenceOverride::parse_ical("19700101T000640Z", "STATUS:CANCELLED").unwrap(),
+        );
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            Some(5),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(event_occurrence_iterator.next(), Some((0, 5, None)));
+        assert_eq!(event_occurrence_iterator.next(), Some((100, 105, None)));
+        assert_eq!(event_occurrence_iterator.next(), Some((200, 205, None)));
+
+        assert_eq!(
+            event_occurrence_iterator.next(),
+            Some((300, 305, Some(build_event_occurrence_override_300())))
+        );
+
+        // Occurrence at 400 is cancelled so is skipped (without counting towards the limit).
+        assert_eq!(
+            event_occurrence_iterator.next(),
+            Some((500, 510, Some(build_event_occurrence_override_500())))
+        );
+
+        assert_eq!(event_occurrence_iterator.next(), None);
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_filters_gt_dtstart() {
+        // Test filters -- greater equal than - DtStart
+        let schedule_properties = build_schedule_properties();
+        let event_occurrence_overrides = build_event_occurrence_overrides();
+
+        let mut event_occurrence_iterator = EventOccurrenceIterator::new(
+            &schedule_properties,
+            &event_occurrence_overrides,
+            None,
+            Some(LowerBoundFilterCondition::GreaterEqualThan(
+                FilterProperty::DtStart(800),
+            )),
             None,
             None,
         )
@@ -799,10 +2197,10 @@ mod test {
     fn test_event_occurrence_iterator_handle_expensive_runaway_indexed_conclusion_exclude_exceptions(
     ) {
         let mut schedule_properties = ScheduleProperties {
-            rrule: Some(build_property_from_ical!(
+            rrules: Some(HashSet::from([build_property_from_ical!(
                 RRuleProperty,
                 "RRULE:FREQ=SECONDLY;INTERVAL=100"
-            )),
+            )])),
             exrule: None,
             rdates: None,
             exdates: None,
@@ -856,4 +2254,146 @@ mod test {
             }
         }
     }
+
+    fn expand_dtstart_timestamps(ical: &str, dst_policy: DstPolicy) -> Vec<i64> {
+        let mut event = crate::Event::parse_ical("EVENT_UID", ical).unwrap();
+
+        event.validate().unwrap();
+
+        EventOccurrenceIterator::new(
+            &event.schedule_properties,
+            &event.overrides,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+        .with_dst_policy(dst_policy)
+        .map(|(dtstart_timestamp, _dtend_timestamp, _event_occurrence_override)| dtstart_timestamp)
+        .collect()
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_dst_policy_europe_london() {
+        // Clocks go forward at 01:00 on 2021-03-28, skipping 01:30.
+        let skipped_ical = "DTSTART;TZID=Europe/London:20210326T013000 RRULE:FREQ=DAILY;INTERVAL=1;COUNT=4";
+
+        assert_eq!(
+            expand_dtstart_timestamps(skipped_ical, DstPolicy::ShiftForward),
+            vec![
+                1616722200, // 2021-03-26T01:30:00Z (01:30 GMT)
+                1616808600, // 2021-03-27T01:30:00Z (01:30 GMT)
+                1616895000, // 2021-03-28T01:30:00Z (02:30 BST)
+                1616977800, // 2021-03-29T00:30:00Z (01:30 BST)
+            ],
+        );
+
+        assert_eq!(
+            expand_dtstart_timestamps(skipped_ical, DstPolicy::Skip),
+            vec![1616722200, 1616808600, 1616977800],
+        );
+
+        assert_eq!(
+            expand_dtstart_timestamps(skipped_ical, DstPolicy::BothInstants),
+            vec![1616722200, 1616808600, 1616895000, 1616977800],
+        );
+
+        // Clocks go back at 02:00 on 2021-10-31, repeating 01:30.
+        let repeated_ical = "DTSTART;TZID=Europe/London:20211030T013000 RRULE:FREQ=DAILY;INTERVAL=1;COUNT=3";
+
+        assert_eq!(
+            expand_dtstart_timestamps(repeated_ical, DstPolicy::ShiftForward),
+            vec![
+                1635553800, // 2021-10-30T00:30:00Z (01:30 BST)
+                1635640200, // 2021-10-31T00:30:00Z (01:30 BST)
+                1635730200, // 2021-11-01T01:30:00Z (01:30 GMT)
+            ],
+        );
+
+        assert_eq!(
+            expand_dtstart_timestamps(repeated_ical, DstPolicy::Skip),
+            vec![1635553800, 1635640200, 1635730200],
+        );
+
+        assert_eq!(
+            expand_dtstart_timestamps(repeated_ical, DstPolicy::BothInstants),
+            vec![
+                1635553800, // 2021-10-30T00:30:00Z (01:30 BST)
+                1635640200, // 2021-10-31T00:30:00Z (01:30 BST)
+                1635643800, // 2021-10-31T01:30:00Z (01:30 GMT)
+                1635730200, // 2021-11-01T01:30:00Z (01:30 GMT)
+            ],
+        );
+
+        // Assert the later instants are yielded in order amongst the other repeated local times.
+        assert_eq!(
+            expand_dtstart_timestamps(
+                "DTSTART;TZID=Europe/London:20211030T010000 RRULE:FREQ=DAILY;INTERVAL=1;BYMINUTE=0,30;COUNT=4",
+                DstPolicy::BothInstants,
+            ),
+            vec![
+                1635552000, // 2021-10-30T00:00:00Z (01:00 BST)
+                1635553800, // 2021-10-30T00:30:00Z (01:30 BST)
+                1635638400, // 2021-10-31T00:00:00Z (01:00 BST)
+                1635640200, // 2021-10-31T00:30:00Z (01:30 BST)
+                1635642000, // 2021-10-31T01:00:00Z (01:00 GMT)
+                1635643800, // 2021-10-31T01:30:00Z (01:30 GMT)
+            ],
+        );
+
+        // Assert sub-daily recurrences are not affected, as they are not anchored to a local time.
+        assert_eq!(
+            expand_dtstart_timestamps(
+                "DTSTART;TZID=Europe/London:20211031T003000 RRULE:FREQ=HOURLY;INTERVAL=1;COUNT=3",
+                DstPolicy::BothInstants,
+            ),
+            expand_dtstart_timestamps(
+                "DTSTART;TZID=Europe/London:20211031T003000 RRULE:FREQ=HOURLY;INTERVAL=1;COUNT=3",
+                DstPolicy::ShiftForward,
+            ),
+        );
+    }
+
+    #[test]
+    fn test_event_occurrence_iterator_dst_policy_america_new_york() {
+        // Clocks go forward at 02:00 on 2021-03-14, skipping 02:30.
+        let skipped_ical = "DTSTART;TZID=America/New_York:20210313T023000 RRULE:FREQ=DAILY;INTERVAL=1;COUNT=3";
+
+        assert_eq!(
+            expand_dtstart_timestamps(skipped_ical, DstPolicy::ShiftForward),
+            vec![
+                1615620600, // 2021-03-13T07:30:00Z (02:30 EST)
+                1615707000, // 2021-03-14T07:30:00Z (03:30 EDT)
+                1615789800, // 2021-03-15T06:30:00Z (02:30 EDT)
+            ],
+        );
+
+        assert_eq!(
+            expand_dtstart_timestamps(skipped_ical, DstPolicy::Skip),
+            vec![1615620600, 1615789800],
+        );
+
+        // Clocks go back at 02:00 on 2021-11-07, repeating 01:30.
+        let repeated_ical = "DTSTART;TZID=America/New_York:20211106T013000 RRULE:FREQ=DAILY;INTERVAL=1;COUNT=3";
+
+        assert_eq!(
+            expand_dtstart_timestamps(repeated_ical, DstPolicy::ShiftForward),
+            vec![
+                1636176600, // 2021-11-06T05:30:00Z (01:30 EDT)
+                1636263000, // 2021-11-07T05:30:00Z (01:30 EDT)
+                1636353000, // 2021-11-08T06:30:00Z (01:30 EST)
+            ],
+        );
+
+        assert_eq!(
+            expand_dtstart_timestamps(repeated_ical, DstPolicy::BothInstants),
+            vec![
+                1636176600, // 2021-11-06T05:30:00Z (01:30 EDT)
+                1636263000, // 2021-11-07T05:30:00Z (01:30 EDT)
+                1636266600, // 2021-11-07T06:30:00Z (01:30 EST)
+                1636353000, // 2021-11-08T06:30:00Z (01:30 EST)
+            ],
+        );
+    }
 }
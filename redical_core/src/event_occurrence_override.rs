@@ -1,8 +1,11 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashSet};
 
 use std::str::FromStr;
 
-use crate::event::{IndexedProperties, PassiveProperties};
+use chrono_tz::Tz;
+
+use crate::clock;
+use crate::event::{IndexedProperties, PassiveProperties, localize_floating_date_times};
 
 use redical_ical::{
     ICalendarComponent,
@@ -17,10 +20,17 @@ use redical_ical::{
         DTStartProperty,
         DTEndProperty,
         DurationProperty,
+        PassiveProperty,
+        XOverrideModeProperty,
     },
 };
 
 use redical_ical::values::date_time::DateTime as ICalDateTime;
+use redical_ical::values::tzid::Tzid;
+use redical_ical::values::override_mode::OverrideModeValue;
+
+use crate::geo_index::GeoPoint;
+use crate::utils::KeyValuePair;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct EventOccurrenceOverride {
@@ -32,17 +42,20 @@ pub struct EventOccurrenceOverride {
     pub dtstart: Option<DTStartProperty>,
     pub dtend: Option<DTEndProperty>,
     pub duration: Option<DurationProperty>,
+
+    pub override_mode: Option<XOverrideModeProperty>,
 }
 
 impl Default for EventOccurrenceOverride {
     fn default() -> Self {
         EventOccurrenceOverride {
-            last_modified: LastModifiedProperty::new_from_now(false),
+            last_modified: LastModifiedProperty::new_from_utc_date_time(clock::now(), false),
             indexed_properties: IndexedProperties::new(),
             passive_properties: PassiveProperties::new(),
             dtstart: None,
             dtend: None,
             duration: None,
+            override_mode: None,
         }
     }
 }
@@ -74,6 +87,113 @@ impl EventOccurrenceOverride {
         }
     }
 
+    /// Returns whether this override cancels the occurrence it overrides (STATUS:CANCELLED), in
+    /// which case the occurrence is omitted from the extrapolated event instances (like EXDATE).
+    pub fn is_cancelled(&self) -> bool {
+        self.passive_properties
+            .properties
+            .iter()
+            .any(|passive_property| {
+                matches!(passive_property, PassiveProperty::Status(_, value) if value.eq_ignore_ascii_case("CANCELLED"))
+            })
+    }
+
+    /// Returns whether the properties of this override replace all those of the base event
+    /// (X-OVERRIDE-MODE:REPLACE), rather than only those of the same name (MERGE - the default).
+    pub fn is_replacing(&self) -> bool {
+        self.override_mode
+            .as_ref()
+            .is_some_and(|override_mode| override_mode.override_mode == OverrideModeValue::Replace)
+    }
+
+    // The overridden indexed terms of the occurrence, where None represents the base event terms
+    // being inherited, and an empty set represents the base event terms being blanked out.
+    //
+    // When replacing the base event properties, any not defined on the override are blanked out.
+    pub fn get_overridden_category_strings(&self) -> Option<HashSet<String>> {
+        self.indexed_properties
+            .extract_all_category_strings()
+            .or_else(|| self.is_replacing().then(HashSet::new))
+    }
+
+    pub fn get_overridden_location_type_strings(&self) -> Option<HashSet<String>> {
+        self.indexed_properties
+            .extract_all_location_type_strings()
+            .or_else(|| self.is_replacing().then(HashSet::new))
+    }
+
+    pub fn get_overridden_related_to_key_value_pairs(&self) -> Option<HashSet<KeyValuePair>> {
+        self.indexed_properties
+            .extract_all_related_to_key_value_pairs()
+            .or_else(|| self.is_replacing().then(HashSet::new))
+    }
+
+    // Overridden passive X-properties only replace the event X-properties of the same name, the
+    // remaining event X-properties still apply to the occurrence (unless it is replacing).
+    pub fn get_overridden_x_property_key_value_pairs(&self, event_x_property_set: &HashSet<KeyValuePair>) -> Option<HashSet<KeyValuePair>> {
+        let overridden_x_property_set = self.passive_properties.extract_x_property_key_value_pairs();
+
+        if self.is_replacing() {
+            return Some(overridden_x_property_set);
+        }
+
+        if overridden_x_property_set.is_empty() {
+            return None;
+        }
+
+        let overridden_names: HashSet<&String> =
+            overridden_x_property_set
+                .iter()
+                .map(|key_value_pair| &key_value_pair.key)
+                .collect();
+
+        Some(
+            event_x_property_set
+                .iter()
+                .filter(|key_value_pair| !overridden_names.contains(&key_value_pair.key))
+                .chain(overridden_x_property_set.iter())
+                .cloned()
+                .collect()
+        )
+    }
+
+    pub fn get_overridden_class_strings(&self) -> Option<HashSet<String>> {
+        self.indexed_properties
+            .extract_class()
+            .map(|class| HashSet::from([class]))
+            .or_else(|| self.is_replacing().then(HashSet::new))
+    }
+
+    // Allow events with LOCATION defined to be overridden to make LOCATION blank (specific events
+    // online only), via a blank LOCATION property.
+    pub fn get_overridden_location_strings(&self) -> Option<HashSet<String>> {
+        if self.indexed_properties.location.is_none() {
+            return self.is_replacing().then(HashSet::new);
+        }
+
+        Some(
+            self.indexed_properties
+                .extract_location()
+                .into_iter()
+                .collect()
+        )
+    }
+
+    // Allow events with GEO defined to be overridden to make GEO blank (specific events online
+    // only), via a blank GEO property.
+    pub fn get_overridden_geo_points(&self) -> Option<HashSet<GeoPoint>> {
+        if self.indexed_properties.geo.is_none() {
+            return self.is_replacing().then(HashSet::new);
+        }
+
+        Some(
+            self.indexed_properties
+                .extract_geo_point()
+                .into_iter()
+                .collect()
+        )
+    }
+
     pub fn parse_ical(dtstart_date_string: &str, input: &str) -> Result<EventOccurrenceOverride, String> {
         EventProperties::from_str(input).and_then(|EventProperties(parsed_properties)| {
             let mut new_override = EventOccurrenceOverride::default();
@@ -96,6 +216,22 @@ impl EventOccurrenceOverride {
         })
     }
 
+    // Interpret a floating DTEND (specified without a UTC designator or TZID) in the provided
+    // (Calendar default) timezone instead of UTC, re-validating the override.
+    //
+    // The DTSTART is always derived from the (UTC) occurrence date string so is unaffected.
+    pub fn apply_default_timezone(&mut self, default_tz: &Tz) -> Result<(), String> {
+        let default_tzid = Tzid(default_tz.to_owned());
+
+        if let Some(dtend) = self.dtend.as_mut() {
+            localize_floating_date_times(&mut dtend.params.tzid, [&dtend.date_time], &default_tzid)?;
+        }
+
+        self.validate()?;
+
+        Ok(())
+    }
+
     pub fn validate(&self) -> Result<bool, String> {
         if self.dtstart.is_none() {
             return Err(
@@ -103,6 +239,18 @@ impl EventOccurrenceOverride {
             );
         }
 
+        if self.dtend.is_some() && self.duration.is_some() {
+            return Err(
+                String::from("Event occurrence override invalid, expected either DTEND or DURATION to be defined, not both.")
+            );
+        }
+
+        if self.get_duration_in_seconds().is_some_and(|duration| duration < 0) {
+            return Err(
+                String::from("Event occurrence override invalid, expected DTEND to not be before DTSTART.")
+            );
+        }
+
         Ok(true)
     }
 
@@ -116,6 +264,7 @@ impl EventOccurrenceOverride {
             | EventProperty::Geo(_)
             | EventProperty::Categories(_)
             | EventProperty::LocationType(_)
+            | EventProperty::Location(_)
             | EventProperty::RelatedTo(_) => {
                 self.indexed_properties.insert(property)?;
             }
@@ -156,6 +305,10 @@ impl EventOccurrenceOverride {
                 self.duration = Some(duration_property);
             }
 
+            EventProperty::OverrideMode(override_mode_property) => {
+                self.override_mode = Some(override_mode_property);
+            }
+
             _ => {
                 self.passive_properties.insert(property)?;
             }
@@ -183,6 +336,10 @@ impl ICalendarComponent for EventOccurrenceOverride {
             serializable_properties.insert(duration_property.to_content_line_with_context(context));
         }
 
+        if let Some(override_mode_property) = &self.override_mode {
+            serializable_properties.insert(override_mode_property.to_content_line_with_context(context));
+        }
+
         if let Some(geo_property) = &self.indexed_properties.geo {
             serializable_properties.insert(geo_property.to_content_line_with_context(context));
         }
@@ -195,6 +352,10 @@ impl ICalendarComponent for EventOccurrenceOverride {
             serializable_properties.insert(class_property.to_content_line_with_context(context));
         }
 
+        if let Some(location_property) = &self.indexed_properties.location {
+            serializable_properties.insert(location_property.to_content_line_with_context(context));
+        }
+
         if let Some(related_to_properties) = &self.indexed_properties.related_to {
             for related_to_property in related_to_properties {
                 serializable_properties.insert(related_to_property.to_content_line_with_context(context));
@@ -254,6 +415,7 @@ mod test {
                     indexed_properties: IndexedProperties {
                         geo: None,
                         class: None,
+                        location: None,
                         location_type: None,
                         categories: None,
                         related_to: None,
@@ -266,6 +428,7 @@ mod test {
                     duration: None,
                     dtstart: Some(build_property_from_ical!(DTStartProperty, "DTSTART:19700101T000500Z")),
                     dtend: None,
+                    override_mode: None,
                 }
             )
         );
@@ -279,6 +442,7 @@ mod test {
                 indexed_properties: IndexedProperties {
                     geo: None,
                     class: Some(build_property_from_ical!(ClassProperty, "CLASS:PRIVATE")),
+                    location: None,
                     location_type: Some(build_property_from_ical!(LocationTypeProperty, "LOCATION-TYPE:HOTEL")),
                     categories: Some(HashSet::from([build_property_from_ical!(CategoriesProperty, "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO,\"CATEGORY (THREE)\"")])),
                     related_to: None,
@@ -291,7 +455,75 @@ mod test {
                 duration: None,
                 dtstart: Some(build_property_from_ical!(DTStartProperty, "DTSTART:19700101T000500Z")),
                 dtend: None,
+                override_mode: None,
             }
         );
     }
+
+    #[test]
+    fn test_parse_ical_dtend_and_duration() {
+        assert_eq!(
+            EventOccurrenceOverride::parse_ical("20210104T170000Z", "DTEND:20210104T190000Z").map(|event_occurrence_override| event_occurrence_override.get_duration_in_seconds()),
+            Ok(Some(7200)),
+        );
+
+        assert_eq!(
+            EventOccurrenceOverride::parse_ical("20210104T170000Z", "DTEND;TZID=Europe/London:20210104T180000").map(|event_occurrence_override| event_occurrence_override.get_duration_in_seconds()),
+            Ok(Some(3600)),
+        );
+
+        assert_eq!(
+            EventOccurrenceOverride::parse_ical("20210104T170000Z", "DURATION:PT45M").map(|event_occurrence_override| event_occurrence_override.get_duration_in_seconds()),
+            Ok(Some(2700)),
+        );
+
+        assert_eq!(
+            EventOccurrenceOverride::parse_ical("20210104T170000Z", "DTEND:20210104T160000Z"),
+            Err(String::from("Event occurrence override invalid, expected DTEND to not be before DTSTART.")),
+        );
+
+        assert_eq!(
+            EventOccurrenceOverride::parse_ical("20210104T170000Z", "DTEND:20210104T190000Z DURATION:PT1H"),
+            Err(String::from("Event occurrence override invalid, expected either DTEND or DURATION to be defined, not both.")),
+        );
+    }
+
+    #[test]
+    fn test_is_cancelled() {
+        assert!(!EventOccurrenceOverride::parse_ical("19700101T000500Z", "SUMMARY:Not cancelled").unwrap().is_cancelled());
+        assert!(!EventOccurrenceOverride::parse_ical("19700101T000500Z", "STATUS:CONFIRMED").unwrap().is_cancelled());
+        assert!(EventOccurrenceOverride::parse_ical("19700101T000500Z", "STATUS:CANCELLED").unwrap().is_cancelled());
+    }
+
+    #[test]
+    fn test_override_mode() {
+        let merging_override = EventOccurrenceOverride::parse_ical("19700101T000500Z", "CLASS:PRIVATE").unwrap();
+
+        assert!(!merging_override.is_replacing());
+        assert_eq!(merging_override.get_overridden_class_strings(), Some(HashSet::from([String::from("PRIVATE")])));
+        assert_eq!(merging_override.get_overridden_category_strings(), None);
+        assert_eq!(merging_override.get_overridden_geo_points(), None);
+
+        let replacing_override = EventOccurrenceOverride::parse_ical("19700101T000500Z", "X-OVERRIDE-MODE:REPLACE CLASS:PRIVATE").unwrap();
+
+        assert!(replacing_override.is_replacing());
+        assert_eq!(replacing_override.get_overridden_class_strings(), Some(HashSet::from([String::from("PRIVATE")])));
+        assert_eq!(replacing_override.get_overridden_category_strings(), Some(HashSet::new()));
+        assert_eq!(replacing_override.get_overridden_geo_points(), Some(HashSet::new()));
+
+        assert_eq!(
+            replacing_override
+                .to_rendered_content_lines()
+                .into_iter()
+                .filter(|content_line| !content_line.starts_with("LAST-MODIFIED"))
+                .collect::<Vec<String>>(),
+            vec![
+                String::from("CLASS:PRIVATE"),
+                String::from("DTSTART:19700101T000500Z"),
+                String::from("X-OVERRIDE-MODE:REPLACE"),
+            ],
+        );
+
+        assert!(EventOccurrenceOverride::parse_ical("19700101T000500Z", "X-OVERRIDE-MODE:UNION").is_err());
+    }
 }
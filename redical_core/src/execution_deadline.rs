@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+
+/// A deadline cooperatively checked throughout query execution (where conditional evaluation and
+/// occurrence expansion) so that an expensive query is aborted instead of running indefinitely.
+///
+/// The default deadline is unbounded and will never be exceeded.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ExecutionDeadline(Option<Instant>);
+
+impl ExecutionDeadline {
+    pub fn new(timeout: Duration) -> Self {
+        ExecutionDeadline(Instant::now().checked_add(timeout))
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        self.0.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_exceeded() {
+            return Err(String::from("query exceeded timeout"));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn test_execution_deadline() {
+        assert_eq!(ExecutionDeadline::default().is_exceeded(), false);
+        assert_eq!(ExecutionDeadline::default().check(), Ok(()));
+
+        assert_eq!(ExecutionDeadline::new(Duration::from_secs(60)).is_exceeded(), false);
+        assert_eq!(ExecutionDeadline::new(Duration::from_secs(60)).check(), Ok(()));
+
+        assert_eq!(ExecutionDeadline::new(Duration::ZERO).is_exceeded(), true);
+        assert_eq!(
+            ExecutionDeadline::new(Duration::ZERO).check(),
+            Err(String::from("query exceeded timeout")),
+        );
+    }
+}
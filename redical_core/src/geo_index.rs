@@ -1,12 +1,13 @@
 use geo::{HaversineDistance, Point};
-use rstar::{PointDistance, RTree, RTreeObject};
+use rstar::{PointDistance, RTreeObject};
 use std::cmp::Ordering;
-
-use rstar::primitives::GeomWithData;
+use std::collections::{BTreeMap, BTreeSet};
 
 use std::hash::{Hash, Hasher};
 
-use crate::{IndexedConclusion, InvertedCalendarIndexTerm};
+use serde::{Deserialize, Serialize};
+
+use crate::{IndexedConclusion, InternedString, InvertedCalendarIndexTerm};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum GeoDistance {
@@ -144,7 +145,7 @@ impl Ord for GeoDistance {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GeoPoint {
     pub lat: f64,
     pub long: f64,
@@ -285,39 +286,144 @@ impl PartialEq for GeoPoint {
 
 impl Eq for GeoPoint {}
 
+// The mean radius of the Earth (in meters) used by the haversine distance calculations.
+const EARTH_RADIUS_METERS: f64 = 6_371_008.8_f64;
+
+// The geohash precision of the indexed points (accurate to 37.2mm × 18.6mm), see `GeoPoint::geohash`.
+const MAX_GEOHASH_PRECISION: usize = 12;
+
+// The (longitude, latitude) dimensions in degrees of each geohash cell of the given precision (each
+// character encodes 5 bits, alternately halving the longitude and latitude ranges).
+fn geohash_cell_dimensions(precision: usize) -> (f64, f64) {
+    let bits = precision as i32 * 5;
+
+    let long_bits = (bits + 1) / 2;
+    let lat_bits = bits / 2;
+
+    (360_f64 / 2_f64.powi(long_bits), 180_f64 / 2_f64.powi(lat_bits))
+}
+
 // Multi layer inverted index (for multiple events) - indexed term - event - include/exclude
-//#[derive(Debug, PartialEq, Clone)]
-#[derive(Debug, Clone)]
+//
+// Each indexed point is keyed by its geohash, so that (as geohashes are hierarchical, each cell
+// sharing the prefix of it's parent cell) all the points within a cell of any precision are
+// contiguous and can be found via a range scan rather than checking every indexed point.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct GeoSpatialCalendarIndex {
-    pub coords: RTree<GeomWithData<GeoPoint, InvertedCalendarIndexTerm>>,
+    pub cells: BTreeMap<String, (GeoPoint, InvertedCalendarIndexTerm)>,
 }
 
-impl PartialEq for GeoSpatialCalendarIndex {
-    fn eq(&self, other: &GeoSpatialCalendarIndex) -> bool {
-        let self_iter = self.coords.into_iter();
-        let other_iter = other.coords.into_iter();
+impl GeoSpatialCalendarIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        for (self_element, other_element) in self_iter.zip(other_iter) {
-            if self_element != other_element {
-                return false;
-            }
-        }
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
 
-        true
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&GeoPoint, &InvertedCalendarIndexTerm)> {
+        self.cells.values().map(|(geo_point, indexed_term)| (geo_point, indexed_term))
     }
-}
 
-impl Default for GeoSpatialCalendarIndex {
-    fn default() -> Self {
-        GeoSpatialCalendarIndex {
-            coords: RTree::new(),
+    // The geohash prefixes of the cells covering the bounding box of all the points within the
+    // given distance, using the finest precision that covers it with at most 3x3 cells (or None
+    // if the distance is too large or covers a pole, requiring every point to be checked).
+    fn covering_geohash_prefixes(long_lat: &GeoPoint, distance_meters: f64) -> Option<BTreeSet<String>> {
+        // The angular distance with a small margin to guard against floating point error.
+        let angular_distance = (distance_meters / EARTH_RADIUS_METERS) * 1.0001_f64 + 1e-9_f64;
+
+        let lat_delta = angular_distance.to_degrees();
+
+        if long_lat.lat + lat_delta >= 90_f64 || long_lat.lat - lat_delta <= -90_f64 {
+            return None;
         }
+
+        // The widest longitude span of a spherical cap not covering a pole (at the latitude where
+        // the cap boundary is tangent to a meridian).
+        let long_delta = (angular_distance.sin() / long_lat.lat.to_radians().cos()).min(1_f64).asin().to_degrees();
+
+        let precision =
+            (1..=MAX_GEOHASH_PRECISION)
+                .rev()
+                .find(|precision| {
+                    let (cell_long_span, cell_lat_span) = geohash_cell_dimensions(*precision);
+
+                    cell_long_span >= long_delta && cell_lat_span >= lat_delta
+                })?;
+
+        let (cell_long_span, cell_lat_span) = geohash_cell_dimensions(precision);
+
+        // Sample the bounding box at intervals of the cell dimensions (including the far edges),
+        // so that every cell it intersects contains at least one sampled point.
+        let sample_range = |min: f64, max: f64, step: f64| -> Vec<f64> {
+            let mut samples: Vec<f64> =
+                (0..)
+                    .map(|index| min + (index as f64 * step))
+                    .take_while(|sample| *sample < max)
+                    .collect();
+
+            samples.push(max);
+
+            samples
+        };
+
+        let lat_samples = sample_range(long_lat.lat - lat_delta, long_lat.lat + lat_delta, cell_lat_span);
+        let long_samples = sample_range(long_lat.long - long_delta, long_lat.long + long_delta, cell_long_span);
+
+        let mut geohash_prefixes = BTreeSet::new();
+
+        for lat in &lat_samples {
+            for long in &long_samples {
+                // Wrap longitudes crossing the antimeridian back around into -180..180.
+                let long = (long + 180_f64).rem_euclid(360_f64) - 180_f64;
+
+                let geohash_prefix = geohash::encode(geohash::Coord { x: long, y: *lat }, precision).ok()?;
+
+                geohash_prefixes.insert(geohash_prefix);
+            }
+        }
+
+        Some(geohash_prefixes)
     }
-}
 
-impl GeoSpatialCalendarIndex {
-    pub fn new() -> Self {
-        Self::default()
+    // Each indexed point (with its distance in meters) within the given distance, only scanning
+    // the cells covering it where possible.
+    fn points_within_distance<'a>(&'a self, long_lat: &GeoPoint, distance_meters: f64) -> Vec<(&'a GeoPoint, &'a InvertedCalendarIndexTerm, f64)> {
+        let with_distance = |(geo_point, indexed_term): &'a (GeoPoint, InvertedCalendarIndexTerm)| {
+            (geo_point, indexed_term, geo_point.haversine_distance(long_lat))
+        };
+
+        let is_within_distance = |(_geo_point, _indexed_term, distance): &(&GeoPoint, &InvertedCalendarIndexTerm, f64)| {
+            *distance <= distance_meters
+        };
+
+        match Self::covering_geohash_prefixes(long_lat, distance_meters) {
+            Some(geohash_prefixes) => {
+                geohash_prefixes
+                    .iter()
+                    .flat_map(|geohash_prefix| {
+                        self.cells
+                            .range(geohash_prefix.to_owned()..)
+                            .take_while(move |(geohash, _cell)| geohash.starts_with(geohash_prefix.as_str()))
+                            .map(move |(_geohash, cell)| with_distance(cell))
+                    })
+                    .filter(is_within_distance)
+                    .collect()
+            },
+
+            None => {
+                self.cells
+                    .values()
+                    .map(with_distance)
+                    .filter(is_within_distance)
+                    .collect()
+            },
+        }
     }
 
     pub fn locate_within_distance(
@@ -327,19 +433,36 @@ impl GeoSpatialCalendarIndex {
     ) -> InvertedCalendarIndexTerm {
         let mut result_inverted_index_term = InvertedCalendarIndexTerm::new();
 
-        for indexed_coord in self
-            .coords
-            .locate_within_distance(long_lat.to_point(), distance.to_meters_float())
-        {
+        for (_geo_point, indexed_term, _distance) in self.points_within_distance(long_lat, distance.to_meters_float()) {
             result_inverted_index_term = InvertedCalendarIndexTerm::merge_or(
                 &result_inverted_index_term,
-                &indexed_coord.data,
+                indexed_term,
             );
         }
 
         result_inverted_index_term
     }
 
+    /// The conclusion indexed for the given event at the given point (if any).
+    pub fn get_indexed_conclusion(&self, event_uid: &str, long_lat: &GeoPoint) -> Option<&IndexedConclusion> {
+        let geohash = long_lat.geohash().ok()?;
+
+        self.cells
+            .get(&geohash)
+            .and_then(|(_geo_point, indexed_term)| indexed_term.events.get(event_uid))
+    }
+
+    /// Iterate over each indexed point ordered by its distance from the given point (nearest
+    /// first), searching progressively further afield only as required.
+    pub fn nearest_neighbor_iter<'a>(&'a self, long_lat: &GeoPoint) -> NearestGeoPointIterator<'a> {
+        NearestGeoPointIterator {
+            geo_spatial_calendar_index: self,
+            long_lat: long_lat.clone(),
+            searched_distance_meters: None,
+            buffered_points: Vec::new(),
+        }
+    }
+
     /// As there may be other events in the calendar outside those indexed here, a vector of
     /// all the event uids contained in the calendar must be passed so that they can be referenced
     /// in the negated event set, as by design they will not match the given term.
@@ -375,30 +498,35 @@ impl GeoSpatialCalendarIndex {
 
     pub fn insert(
         &mut self,
-        event_uid: String,
+        event_uid: impl Into<InternedString>,
         long_lat: &GeoPoint,
         indexed_conclusion: &IndexedConclusion,
     ) -> Result<&mut Self, String> {
-        match self.coords.locate_at_point_mut(&long_lat.to_point()) {
-            Some(existing_result) => {
+        let event_uid: InternedString = event_uid.into();
+
+        let geohash = long_lat.geohash()?;
+
+        match self.cells.get_mut(&geohash) {
+            Some((_geo_point, existing_result)) => {
                 match indexed_conclusion {
                     IndexedConclusion::Include(exceptions) => existing_result
-                        .data
                         .insert_included_event(event_uid.clone(), exceptions.clone()),
                     IndexedConclusion::Exclude(exceptions) => existing_result
-                        .data
                         .insert_excluded_event(event_uid.clone(), exceptions.clone()),
                 };
             }
 
             None => {
-                self.coords.insert(GeomWithData::new(
-                    long_lat.clone(),
-                    InvertedCalendarIndexTerm::new_with_event(
-                        event_uid.clone(),
-                        indexed_conclusion.clone(),
+                self.cells.insert(
+                    geohash,
+                    (
+                        long_lat.clone(),
+                        InvertedCalendarIndexTerm::new_with_event(
+                            event_uid.clone(),
+                            indexed_conclusion.clone(),
+                        ),
                     ),
-                ));
+                );
             }
         }
 
@@ -406,13 +534,14 @@ impl GeoSpatialCalendarIndex {
     }
 
     pub fn remove(&mut self, event_uid: String, long_lat: &GeoPoint) -> Result<&mut Self, String> {
-        if let Some(existing_result) = self.coords.locate_at_point_mut(&long_lat.to_point()) {
+        let geohash = long_lat.geohash()?;
+
+        if let Some((_geo_point, existing_result)) = self.cells.get_mut(&geohash) {
             if existing_result
-                .data
                 .remove_event(event_uid)
                 .is_ok_and(|inverted_calendar_index_term| inverted_calendar_index_term.is_empty())
             {
-                self.coords.remove_at_point(&long_lat.to_point());
+                self.cells.remove(&geohash);
             }
         }
 
@@ -420,12 +549,75 @@ impl GeoSpatialCalendarIndex {
     }
 }
 
+// The distance searched first by `NearestGeoPointIterator`, doubling each time it is exhausted.
+const INITIAL_NEAREST_SEARCH_DISTANCE_METERS: f64 = 1_000_f64;
+
+// Half the circumference of the Earth, beyond which there are no further points to search.
+const MAX_SEARCH_DISTANCE_METERS: f64 = std::f64::consts::PI * EARTH_RADIUS_METERS;
+
+/// Lazily iterates the indexed points of a `GeoSpatialCalendarIndex` ordered by their distance
+/// from a given point, yielding each point with its indexed term and distance (in meters).
+///
+/// Rather than ordering every indexed point up front, each successive ring of points beyond those
+/// already yielded is searched (doubling the distance each time), so consumers only interested in
+/// the nearest points avoid the cost of ordering the rest.
+pub struct NearestGeoPointIterator<'a> {
+    geo_spatial_calendar_index: &'a GeoSpatialCalendarIndex,
+    long_lat: GeoPoint,
+    searched_distance_meters: Option<f64>,
+    // Ordered furthest first so that the nearest can be popped off the end.
+    buffered_points: Vec<(&'a GeoPoint, &'a InvertedCalendarIndexTerm, f64)>,
+}
+
+impl<'a> Iterator for NearestGeoPointIterator<'a> {
+    type Item = (&'a GeoPoint, &'a InvertedCalendarIndexTerm, f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffered_points.is_empty() {
+            let search_distance_meters =
+                match self.searched_distance_meters {
+                    None => INITIAL_NEAREST_SEARCH_DISTANCE_METERS,
+
+                    Some(searched_distance_meters) if searched_distance_meters >= MAX_SEARCH_DISTANCE_METERS => {
+                        return None;
+                    },
+
+                    Some(searched_distance_meters) => searched_distance_meters * 2_f64,
+                };
+
+            let previous_searched_distance_meters = self.searched_distance_meters;
+
+            let mut ring_points: Vec<(&'a GeoPoint, &'a InvertedCalendarIndexTerm, f64)> =
+                self.geo_spatial_calendar_index
+                    .points_within_distance(&self.long_lat, search_distance_meters)
+                    .into_iter()
+                    .filter(|(_geo_point, _indexed_term, distance)| {
+                        previous_searched_distance_meters.is_none_or(|previous_searched_distance_meters| *distance > previous_searched_distance_meters)
+                    })
+                    .collect();
+
+            ring_points.sort_by(|(geo_point_a, _, distance_a), (geo_point_b, _, distance_b)| {
+                distance_b
+                    .total_cmp(distance_a)
+                    .then_with(|| geo_point_b.geohash().cmp(&geo_point_a.geohash()))
+            });
+
+            self.buffered_points = ring_points;
+            self.searched_distance_meters = Some(search_distance_meters);
+        }
+
+        self.buffered_points.pop()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     use std::collections::{HashMap, HashSet};
 
+    use rstar::RTree;
+
     use pretty_assertions_sorted::{assert_eq, assert_eq_sorted};
 
     const RANDOM: GeoPoint = GeoPoint { lat: 51.7854972_f64, long: -1.4701705_f64 };
@@ -435,6 +627,13 @@ mod test {
     const LONDON: GeoPoint = GeoPoint { lat: 51.5074_f64, long: -0.1278_f64 };
     const OXFORD: GeoPoint = GeoPoint { lat: 51.8773_f64, long: -1.2475878_f64 };
 
+    fn build_cells(cells: Vec<(GeoPoint, InvertedCalendarIndexTerm)>) -> BTreeMap<String, (GeoPoint, InvertedCalendarIndexTerm)> {
+        cells
+            .into_iter()
+            .map(|(geo_point, indexed_term)| (geo_point.geohash().unwrap(), (geo_point, indexed_term)))
+            .collect()
+    }
+
     fn example_geo_index() -> GeoSpatialCalendarIndex {
         let mut geo_spatial_calendar_index = GeoSpatialCalendarIndex::new();
 
@@ -536,7 +735,7 @@ mod test {
         assert_eq!(
             geo_spatial_calendar_index,
             GeoSpatialCalendarIndex {
-                coords: RTree::new(),
+                cells: BTreeMap::new(),
             }
         );
 
@@ -551,11 +750,11 @@ mod test {
         assert_eq!(
             geo_spatial_calendar_index,
             GeoSpatialCalendarIndex {
-                coords: RTree::bulk_load(vec![GeomWithData::new(
+                cells: build_cells(vec![(
                     LONDON.clone(),
                     InvertedCalendarIndexTerm {
                         events: HashMap::from([(
-                            String::from("london_event_uid_one"),
+                            InternedString::from("london_event_uid_one"),
                             IndexedConclusion::Include(None)
                         )])
                     }
@@ -574,16 +773,16 @@ mod test {
         assert_eq!(
             geo_spatial_calendar_index,
             GeoSpatialCalendarIndex {
-                coords: RTree::bulk_load(vec![GeomWithData::new(
+                cells: build_cells(vec![(
                     LONDON.clone(),
                     InvertedCalendarIndexTerm {
                         events: HashMap::from([
                             (
-                                String::from("london_event_uid_one"),
+                                InternedString::from("london_event_uid_one"),
                                 IndexedConclusion::Include(None)
                             ),
                             (
-                                String::from("london_event_uid_two"),
+                                InternedString::from("london_event_uid_two"),
                                 IndexedConclusion::Exclude(Some(HashSet::from([100])))
                             ),
                         ])
@@ -603,27 +802,27 @@ mod test {
         assert_eq!(
             geo_spatial_calendar_index,
             GeoSpatialCalendarIndex {
-                coords: RTree::bulk_load(vec![
-                    GeomWithData::new(
+                cells: build_cells(vec![
+                    (
                         LONDON.clone(),
                         InvertedCalendarIndexTerm {
                             events: HashMap::from([
                                 (
-                                    String::from("london_event_uid_one"),
+                                    InternedString::from("london_event_uid_one"),
                                     IndexedConclusion::Include(None)
                                 ),
                                 (
-                                    String::from("london_event_uid_two"),
+                                    InternedString::from("london_event_uid_two"),
                                     IndexedConclusion::Exclude(Some(HashSet::from([100])))
                                 ),
                             ])
                         }
                     ),
-                    GeomWithData::new(
+                    (
                         OXFORD.clone(),
                         InvertedCalendarIndexTerm {
                             events: HashMap::from([(
-                                String::from("oxford_event_uid"),
+                                InternedString::from("oxford_event_uid"),
                                 IndexedConclusion::Include(Some(HashSet::from([100])))
                             ),])
                         }
@@ -639,16 +838,16 @@ mod test {
         assert_eq!(
             geo_spatial_calendar_index,
             GeoSpatialCalendarIndex {
-                coords: RTree::bulk_load(vec![GeomWithData::new(
+                cells: build_cells(vec![(
                     LONDON.clone(),
                     InvertedCalendarIndexTerm {
                         events: HashMap::from([
                             (
-                                String::from("london_event_uid_one"),
+                                InternedString::from("london_event_uid_one"),
                                 IndexedConclusion::Include(None)
                             ),
                             (
-                                String::from("london_event_uid_two"),
+                                InternedString::from("london_event_uid_two"),
                                 IndexedConclusion::Exclude(Some(HashSet::from([100])))
                             ),
                         ])
@@ -664,11 +863,11 @@ mod test {
         assert_eq!(
             geo_spatial_calendar_index,
             GeoSpatialCalendarIndex {
-                coords: RTree::bulk_load(vec![GeomWithData::new(
+                cells: build_cells(vec![(
                     LONDON.clone(),
                     InvertedCalendarIndexTerm {
                         events: HashMap::from([(
-                            String::from("london_event_uid_two"),
+                            InternedString::from("london_event_uid_two"),
                             IndexedConclusion::Exclude(Some(HashSet::from([100])))
                         ),])
                     }
@@ -683,11 +882,11 @@ mod test {
         assert_eq!(
             geo_spatial_calendar_index,
             GeoSpatialCalendarIndex {
-                coords: RTree::bulk_load(vec![GeomWithData::new(
+                cells: build_cells(vec![(
                     LONDON.clone(),
                     InvertedCalendarIndexTerm {
                         events: HashMap::from([(
-                            String::from("london_event_uid_two"),
+                            InternedString::from("london_event_uid_two"),
                             IndexedConclusion::Exclude(Some(HashSet::from([100])))
                         ),])
                     }
@@ -702,7 +901,7 @@ mod test {
         assert_eq!(
             geo_spatial_calendar_index,
             GeoSpatialCalendarIndex {
-                coords: RTree::bulk_load(vec![])
+                cells: build_cells(vec![])
             }
         );
     }
@@ -717,11 +916,11 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("oxford_event_one_uid"),
+                        InternedString::from("oxford_event_one_uid"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("oxford_event_two_uid"),
+                        InternedString::from("oxford_event_two_uid"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                     ),
                 ])
@@ -734,31 +933,31 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("churchdown_event_uid"),
+                        InternedString::from("churchdown_event_uid"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("oxford_event_one_uid"),
+                        InternedString::from("oxford_event_one_uid"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("oxford_event_two_uid"),
+                        InternedString::from("oxford_event_two_uid"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                     ),
                     (
-                        String::from("random_and_churchdown_event_uid"),
+                        InternedString::from("random_and_churchdown_event_uid"),
                         IndexedConclusion::Include(Some(HashSet::from([100])))
                     ),
                     (
-                        String::from("random_event_uid"),
+                        InternedString::from("random_event_uid"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("random_plus_offset_and_london_event_uid"),
+                        InternedString::from("random_plus_offset_and_london_event_uid"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100])))
                     ),
                     (
-                        String::from("random_plus_offset_event_uid"),
+                        InternedString::from("random_plus_offset_event_uid"),
                         IndexedConclusion::Include(None)
                     ),
                 ])
@@ -771,35 +970,35 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("churchdown_event_uid"),
+                        InternedString::from("churchdown_event_uid"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("oxford_event_one_uid"),
+                        InternedString::from("oxford_event_one_uid"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("oxford_event_two_uid"),
+                        InternedString::from("oxford_event_two_uid"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                     ),
                     (
-                        String::from("random_and_churchdown_event_uid"),
+                        InternedString::from("random_and_churchdown_event_uid"),
                         IndexedConclusion::Include(Some(HashSet::from([100])))
                     ),
                     (
-                        String::from("random_event_uid"),
+                        InternedString::from("random_event_uid"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("random_plus_offset_and_london_event_uid"),
+                        InternedString::from("random_plus_offset_and_london_event_uid"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("random_plus_offset_event_uid"),
+                        InternedString::from("random_plus_offset_event_uid"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("london_event_uid"),
+                        InternedString::from("london_event_uid"),
                         IndexedConclusion::Include(None)
                     ),
                 ])
@@ -838,43 +1037,43 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("churchdown_event_uid"),
+                        InternedString::from("churchdown_event_uid"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("london_event_uid"),
+                        InternedString::from("london_event_uid"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("new_york_city_event_uid"),
+                        InternedString::from("new_york_city_event_uid"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("random_event_uid"),
+                        InternedString::from("random_event_uid"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("random_and_churchdown_event_uid"),
+                        InternedString::from("random_and_churchdown_event_uid"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("random_plus_offset_event_uid"),
+                        InternedString::from("random_plus_offset_event_uid"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("random_plus_offset_and_london_event_uid"),
+                        InternedString::from("random_plus_offset_and_london_event_uid"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("oxford_event_two_uid"),
+                        InternedString::from("oxford_event_two_uid"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                     ),
                     (
-                        String::from("unknown_location_event_uid_1"),
+                        InternedString::from("unknown_location_event_uid_1"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("unknown_location_event_uid_2"),
+                        InternedString::from("unknown_location_event_uid_2"),
                         IndexedConclusion::Include(None),
                     ),
                 ])
@@ -890,31 +1089,31 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("london_event_uid"),
+                        InternedString::from("london_event_uid"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("new_york_city_event_uid"),
+                        InternedString::from("new_york_city_event_uid"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("oxford_event_two_uid"),
+                        InternedString::from("oxford_event_two_uid"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                     ),
                     (
-                        String::from("random_and_churchdown_event_uid"),
+                        InternedString::from("random_and_churchdown_event_uid"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100])))
                     ),
                     (
-                        String::from("random_plus_offset_and_london_event_uid"),
+                        InternedString::from("random_plus_offset_and_london_event_uid"),
                         IndexedConclusion::Include(Some(HashSet::from([100])))
                     ),
                     (
-                        String::from("unknown_location_event_uid_1"),
+                        InternedString::from("unknown_location_event_uid_1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("unknown_location_event_uid_2"),
+                        InternedString::from("unknown_location_event_uid_2"),
                         IndexedConclusion::Include(None)
                     ),
                 ])
@@ -930,23 +1129,23 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("new_york_city_event_uid"),
+                        InternedString::from("new_york_city_event_uid"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("oxford_event_two_uid"),
+                        InternedString::from("oxford_event_two_uid"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                     ),
                     (
-                        String::from("random_and_churchdown_event_uid"),
+                        InternedString::from("random_and_churchdown_event_uid"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100])))
                     ),
                     (
-                        String::from("unknown_location_event_uid_1"),
+                        InternedString::from("unknown_location_event_uid_1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("unknown_location_event_uid_2"),
+                        InternedString::from("unknown_location_event_uid_2"),
                         IndexedConclusion::Include(None)
                     ),
                 ])
@@ -954,6 +1153,115 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_geohash_cell_dimensions() {
+        assert_eq!(geohash_cell_dimensions(1), (45_f64, 45_f64));
+        assert_eq!(geohash_cell_dimensions(2), (11.25_f64, 5.625_f64));
+        assert_eq!(geohash_cell_dimensions(3), (1.40625_f64, 1.40625_f64));
+    }
+
+    #[test]
+    fn test_geo_spatial_calendar_index_covering_geohash_prefixes() {
+        // The finest precision covering the distance with at most 3x3 cells.
+        let geohash_prefixes = GeoSpatialCalendarIndex::covering_geohash_prefixes(&OXFORD, 87_500_f64).unwrap();
+
+        assert!(geohash_prefixes.len() <= 9);
+        assert!(geohash_prefixes.iter().all(|geohash_prefix| geohash_prefix.len() == 3));
+        assert!(geohash_prefixes.contains(&OXFORD.geohash().unwrap()[..3]));
+        assert!(geohash_prefixes.contains(&LONDON.geohash().unwrap()[..3]));
+
+        // Cells either side of the antimeridian are covered.
+        let geohash_prefixes = GeoSpatialCalendarIndex::covering_geohash_prefixes(&GeoPoint::new(0_f64, 179.99_f64), 5_000_f64).unwrap();
+
+        assert!(geohash_prefixes.iter().any(|geohash_prefix| GeoPoint::new(0_f64, -179.99_f64).geohash().unwrap().starts_with(geohash_prefix.as_str())));
+
+        // Distances covering a pole (or too large to be covered by 3x3 cells) check every point.
+        assert_eq!(GeoSpatialCalendarIndex::covering_geohash_prefixes(&GeoPoint::new(89.9_f64, 0_f64), 50_000_f64), None);
+        assert_eq!(GeoSpatialCalendarIndex::covering_geohash_prefixes(&OXFORD, 10_000_000_f64), None);
+    }
+
+    #[test]
+    fn test_geo_spatial_calendar_index_locate_within_distance_across_cells() {
+        let mut geo_spatial_calendar_index = GeoSpatialCalendarIndex::new();
+
+        // A grid of points spanning the antimeridian and the north pole, every 0.5 degrees.
+        let geo_points: Vec<GeoPoint> =
+            (-20_i32..=20)
+                .flat_map(|lat_index| {
+                    (-20..=20).flat_map(move |long_index| {
+                        // Longitudes beyond the antimeridian are wrapped back around to -180.
+                        let antimeridian_long = 180_f64 + (long_index as f64 * 0.5_f64);
+                        let antimeridian_long = if antimeridian_long > 180_f64 { antimeridian_long - 360_f64 } else { antimeridian_long };
+
+                        [
+                            GeoPoint::new(lat_index as f64 * 0.5_f64, antimeridian_long),
+                            GeoPoint::new(80_f64 + (lat_index.abs() as f64 * 0.5_f64), long_index as f64 * 9_f64),
+                        ]
+                    })
+                })
+                .filter(|geo_point| geo_point.validate().is_ok())
+                .collect();
+
+        for (index, geo_point) in geo_points.iter().enumerate() {
+            geo_spatial_calendar_index.insert(format!("EVENT_{index}"), geo_point, &IndexedConclusion::Include(None)).unwrap();
+        }
+
+        // Assert the indexed cells return exactly the points found by checking every point.
+        for (long_lat, distance) in [
+            (GeoPoint::new(0_f64, 180_f64), GeoDistance::new_from_kilometers_float(150_f64)),
+            (GeoPoint::new(0_f64, -179.75_f64), GeoDistance::new_from_kilometers_float(60_f64)),
+            (GeoPoint::new(5_f64, 175_f64), GeoDistance::new_from_kilometers_float(1_f64)),
+            (GeoPoint::new(5_f64, 175_f64), GeoDistance::new_from_miles_float(400_f64)),
+            (GeoPoint::new(89_f64, 45_f64), GeoDistance::new_from_kilometers_float(500_f64)),
+            (GeoPoint::new(85_f64, 0_f64), GeoDistance::new_from_kilometers_float(50_f64)),
+        ] {
+            let expected_event_uids: HashSet<String> =
+                geo_points
+                    .iter()
+                    .enumerate()
+                    .filter(|(_index, geo_point)| geo_point.haversine_distance(&long_lat) <= distance.to_meters_float())
+                    .map(|(index, _geo_point)| format!("EVENT_{index}"))
+                    .collect();
+
+            let located_event_uids: HashSet<String> =
+                geo_spatial_calendar_index
+                    .locate_within_distance(&long_lat, &distance)
+                    .events
+                    .keys()
+                    .map(|event_uid| event_uid.to_string())
+                    .collect();
+
+            assert_eq_sorted!(located_event_uids, expected_event_uids);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
+    fn test_geo_spatial_calendar_index_nearest_neighbor_iter() {
+        let geo_spatial_calendar_index = example_geo_index();
+
+        let nearest_points: Vec<(GeoPoint, f32)> =
+            geo_spatial_calendar_index
+                .nearest_neighbor_iter(&OXFORD)
+                .map(|(geo_point, _indexed_term, distance)| (geo_point.to_owned(), distance as f32))
+                .collect();
+
+        // Cast all f64 distances to f32 so tests pass under both MacOS and Linux.
+        assert_eq!(
+            nearest_points,
+            vec![
+                (OXFORD, 0_f32),
+                (RANDOM, 18388.597009683246_f32),
+                (RANDOM_PLUS_OFFSET, 18402.23696221235_f32),
+                (CHURCHDOWN, 63223.39709694925_f32),
+                (LONDON, 87458.64969073102_f32),
+                (NEW_YORK_CITY, 5484158.985172745_f32),
+            ],
+        );
+
+        assert_eq!(GeoSpatialCalendarIndex::new().nearest_neighbor_iter(&OXFORD).next(), None);
+    }
+
     #[test]
     #[allow(clippy::excessive_precision)]
     fn test_geo_distance_rtree() {
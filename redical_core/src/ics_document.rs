@@ -0,0 +1,562 @@
+use std::str::FromStr;
+
+use crate::calendar::Calendar;
+use crate::event::Event;
+use crate::event_occurrence_override::EventOccurrenceOverride;
+
+use redical_ical::{ICalendarComponent, ICalendarEntity};
+use redical_ical::content_line::{fold_content_line, unfold_content_lines};
+use redical_ical::properties::{DurationProperty, ICalendarDateTimeProperty, RecurrenceIDProperty};
+use redical_ical::properties::event::{ParticipantComponent, AlarmComponent};
+use redical_ical::values::date_time::DateTime;
+
+pub const ICS_DOCUMENT_PRODID: &str = "-//RediCal//RediCal//EN";
+
+// The components nested within VEVENTs which are retained along with the VEVENT properties.
+const EVENT_SUBCOMPONENT_NAMES: [&str; 2] = ["PARTICIPANT", "VALARM"];
+
+/// A single VEVENT component extracted from an iCalendar (VCALENDAR) document.
+///
+/// Components with a RECURRENCE-ID property represent an override of a specific occurrence of
+/// the event sharing the same UID, all others represent the base event itself.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ICSEventComponent {
+    pub uid: String,
+    pub recurrence_id: Option<RecurrenceIDProperty>,
+    pub ical: String,
+}
+
+impl ICSEventComponent {
+    pub fn get_recurrence_id_timestamp(&self) -> Option<i64> {
+        self.recurrence_id
+            .as_ref()
+            .map(|recurrence_id| recurrence_id.get_utc_timestamp())
+    }
+
+    // The UTC date string of the overridden occurrence (as expected by
+    // `EventOccurrenceOverride::parse_ical`).
+    pub fn get_recurrence_id_date_string(&self) -> Option<String> {
+        self.get_recurrence_id_timestamp()
+            .map(|timestamp| DateTime::from(timestamp).render_ical())
+    }
+
+    pub fn is_override(&self) -> bool {
+        self.recurrence_id.is_some()
+    }
+
+    pub fn parse_event(&self) -> Result<Event, String> {
+        if self.is_override() {
+            return Err(format!("Event with UID: '{}' is an occurrence override, not an event", self.uid));
+        }
+
+        let mut event = Event::parse_ical(self.uid.as_str(), self.ical.as_str())?;
+
+        event.validate()?;
+
+        Ok(event)
+    }
+
+    pub fn parse_event_occurrence_override(&self) -> Result<EventOccurrenceOverride, String> {
+        let Some(recurrence_id_date_string) = self.get_recurrence_id_date_string() else {
+            return Err(format!("Event with UID: '{}' is an event, not an occurrence override", self.uid));
+        };
+
+        EventOccurrenceOverride::parse_ical(recurrence_id_date_string.as_str(), self.ical.as_str())
+    }
+}
+
+fn get_property_name(content_line: &str) -> String {
+    content_line
+        .split([';', ':'])
+        .next()
+        .unwrap_or("")
+        .to_ascii_uppercase()
+}
+
+fn get_property_value(content_line: &str) -> &str {
+    content_line
+        .split_once(':')
+        .map(|(_, value)| value)
+        .unwrap_or("")
+}
+
+/// Split an iCalendar (VCALENDAR) document into its VEVENT components.
+///
+/// PARTICIPANT and VALARM components nested directly within VEVENTs are retained along with the
+/// VEVENT properties, whereas any other components (e.g. VTIMEZONE, VTODO), calendar level
+/// properties (e.g. PRODID, VERSION), and components nested within VEVENTs (e.g. VLOCATION) are
+/// ignored.
+pub fn parse_ics_document(input: &str) -> Result<Vec<ICSEventComponent>, String> {
+    let unfolded_input = unfold_content_lines(input);
+
+    let mut content_lines =
+        unfolded_input
+            .lines()
+            .map(|content_line| content_line.trim_end_matches('\r'))
+            .filter(|content_line| !content_line.trim().is_empty());
+
+    if !content_lines.next().is_some_and(|content_line| content_line.eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err(String::from("Expected iCalendar document to begin with BEGIN:VCALENDAR"));
+    }
+
+    let mut ics_event_components = Vec::new();
+
+    // The depth of the components we are currently nested within (excluding the VCALENDAR).
+    let mut component_depth: usize = 0;
+    let mut is_vcalendar_ended = false;
+    let mut is_within_subcomponent = false;
+
+    let mut current_event_content_lines: Option<Vec<&str>> = None;
+
+    for content_line in content_lines {
+        if is_vcalendar_ended {
+            return Err(String::from("Unexpected content after END:VCALENDAR"));
+        }
+
+        let property_name = get_property_name(content_line);
+
+        match property_name.as_str() {
+            "BEGIN" => {
+                if component_depth == 0 && get_property_value(content_line).eq_ignore_ascii_case("VEVENT") {
+                    current_event_content_lines = Some(Vec::new());
+                } else if component_depth == 1 && is_event_subcomponent_name(get_property_value(content_line)) {
+                    if let Some(event_content_lines) = current_event_content_lines.as_mut() {
+                        event_content_lines.push(content_line);
+
+                        is_within_subcomponent = true;
+                    }
+                }
+
+                component_depth += 1;
+            },
+
+            "END" if component_depth == 0 => {
+                if !get_property_value(content_line).eq_ignore_ascii_case("VCALENDAR") {
+                    return Err(format!("Unexpected {content_line} outside of component"));
+                }
+
+                is_vcalendar_ended = true;
+            },
+
+            "END" => {
+                component_depth -= 1;
+
+                if component_depth == 1 && is_within_subcomponent {
+                    if let Some(event_content_lines) = current_event_content_lines.as_mut() {
+                        event_content_lines.push(content_line);
+                    }
+
+                    is_within_subcomponent = false;
+                }
+
+                if component_depth == 0 {
+                    if let Some(event_content_lines) = current_event_content_lines.take() {
+                        ics_event_components.push(build_ics_event_component(event_content_lines)?);
+                    }
+                }
+            },
+
+            // Only collect the properties belonging directly to the VEVENT (or to a retained
+            // component within it).
+            _ if component_depth == 1 || (component_depth == 2 && is_within_subcomponent) => {
+                if let Some(event_content_lines) = current_event_content_lines.as_mut() {
+                    event_content_lines.push(content_line);
+                }
+            },
+
+            _ => {},
+        }
+    }
+
+    if !is_vcalendar_ended {
+        return Err(String::from("Expected iCalendar document to end with END:VCALENDAR"));
+    }
+
+    Ok(ics_event_components)
+}
+
+fn is_event_subcomponent_name(component_name: &str) -> bool {
+    EVENT_SUBCOMPONENT_NAMES
+        .iter()
+        .any(|subcomponent_name| subcomponent_name.eq_ignore_ascii_case(component_name))
+}
+
+fn build_ics_event_component(event_content_lines: Vec<&str>) -> Result<ICSEventComponent, String> {
+    let mut uid = None;
+    let mut recurrence_id = None;
+    let mut ical_content_lines = Vec::new();
+    let mut is_within_subcomponent = false;
+
+    for content_line in event_content_lines {
+        match get_property_name(content_line).as_str() {
+            // The UID of a PARTICIPANT (or VALARM) is it's own, rather than that of the VEVENT.
+            "BEGIN" => {
+                is_within_subcomponent = true;
+
+                ical_content_lines.push(content_line);
+            },
+
+            "END" => {
+                is_within_subcomponent = false;
+
+                ical_content_lines.push(content_line);
+            },
+
+            "UID" if !is_within_subcomponent => {
+                uid = Some(get_property_value(content_line).to_string());
+            },
+
+            "RECURRENCE-ID" if !is_within_subcomponent => {
+                recurrence_id = Some(
+                    RecurrenceIDProperty::from_str(content_line)
+                        .map_err(|error| format!("Invalid RECURRENCE-ID: {error}"))?
+                );
+            },
+
+            _ => {
+                ical_content_lines.push(content_line);
+            },
+        }
+    }
+
+    let Some(uid) = uid.filter(|uid| !uid.is_empty()) else {
+        return Err(String::from("Expected VEVENT to have a UID defined"));
+    };
+
+    Ok(
+        ICSEventComponent {
+            uid,
+            recurrence_id,
+            ical: ical_content_lines.join("\n"),
+        }
+    )
+}
+
+fn push_content_line(ics_document: &mut String, content_line: &str) {
+    ics_document.push_str(fold_content_line(content_line).as_str());
+    ics_document.push_str("\r\n");
+}
+
+// PARTICIPANT and VALARM components are stored (and rendered) as a single content line spanning
+// all of it's content lines, so these are split back out into their own (individually folded)
+// content lines.
+fn push_event_content_line(ics_document: &mut String, content_line: &str) {
+    let subcomponent_content_lines =
+        if content_line.starts_with("BEGIN:PARTICIPANT") {
+            ParticipantComponent::from_str(content_line).ok().map(|participant| participant.to_content_lines())
+        } else if content_line.starts_with("BEGIN:VALARM") {
+            AlarmComponent::from_str(content_line).ok().map(|alarm| alarm.to_content_lines())
+        } else {
+            None
+        };
+
+    let Some(subcomponent_content_lines) = subcomponent_content_lines else {
+        push_content_line(ics_document, content_line);
+
+        return;
+    };
+
+    for subcomponent_content_line in subcomponent_content_lines {
+        push_content_line(ics_document, subcomponent_content_line.render_ical().as_str());
+    }
+}
+
+/// Render the Calendar along with the provided events (and their overrides) as a single
+/// well-formed iCalendar (VCALENDAR) document with folded lines and CRLF line breaks.
+///
+/// Each event occurrence override is rendered as its own VEVENT with a RECURRENCE-ID property,
+/// inheriting the duration of the base event unless it overrides DTEND or DURATION itself.
+pub fn render_ics_document<'a>(calendar: &Calendar, events: impl IntoIterator<Item = &'a Event>) -> String {
+    let mut ics_document = String::new();
+
+    push_content_line(&mut ics_document, "BEGIN:VCALENDAR");
+    push_content_line(&mut ics_document, "VERSION:2.0");
+    push_content_line(&mut ics_document, format!("PRODID:{ICS_DOCUMENT_PRODID}").as_str());
+
+    // The VERSION and PRODID are always those of the exported document, rather than any set on the
+    // Calendar.
+    for content_line in calendar.to_content_line_set() {
+        if content_line.0 == "VERSION" || content_line.0 == "PRODID" {
+            continue;
+        }
+
+        push_content_line(&mut ics_document, content_line.render_ical().as_str());
+    }
+
+    for event in events {
+        push_content_line(&mut ics_document, "BEGIN:VEVENT");
+
+        for content_line in event.to_rendered_content_lines() {
+            push_event_content_line(&mut ics_document, content_line.as_str());
+        }
+
+        push_content_line(&mut ics_document, "END:VEVENT");
+
+        let event_duration_in_seconds = event.schedule_properties.get_duration_in_seconds();
+
+        for (timestamp, event_occurrence_override) in &event.overrides {
+            push_content_line(&mut ics_document, "BEGIN:VEVENT");
+
+            push_content_line(&mut ics_document, event.uid.render_ical().as_str());
+            push_content_line(&mut ics_document, RecurrenceIDProperty::new_from_utc_timestamp(timestamp).render_ical().as_str());
+
+            for content_line in event_occurrence_override.to_rendered_content_lines() {
+                push_event_content_line(&mut ics_document, content_line.as_str());
+            }
+
+            if event_occurrence_override.dtend.is_none() && event_occurrence_override.duration.is_none() {
+                if let Some(event_duration_in_seconds) = event_duration_in_seconds {
+                    push_content_line(&mut ics_document, DurationProperty::new_from_seconds(&event_duration_in_seconds).render_ical().as_str());
+                }
+            }
+
+            push_content_line(&mut ics_document, "END:VEVENT");
+        }
+    }
+
+    push_content_line(&mut ics_document, "END:VCALENDAR");
+
+    ics_document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    const ICS_DOCUMENT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//hacksw/handcal//NONSGML v1.0//EN\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/London\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:EVENT_ONE\r
+DTSTART:20210105T183000Z\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3\r
+SUMMARY:A long summary which has been folded acro\r
+ ss two lines\r
+CATEGORIES:CATEGORY_ONE\r
+BEGIN:VALARM\r
+UID:ALARM_ONE\r
+ACTION:DISPLAY\r
+TRIGGER:-PT15M\r
+ACKNOWLEDGED:20210105T181500Z\r
+BEGIN:VLOCATION\r
+UID:LOCATION_ONE\r
+END:VLOCATION\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:EVENT_ONE\r
+RECURRENCE-ID;TZID=Europe/London:20210112T183000\r
+SUMMARY:Overridden summary\r
+CATEGORIES:CATEGORY_TWO\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_parse_ics_document() {
+        let ics_event_components = parse_ics_document(ICS_DOCUMENT).unwrap();
+
+        assert_eq!(ics_event_components.len(), 2);
+
+        assert_eq!(
+            ics_event_components[0],
+            ICSEventComponent {
+                uid: String::from("EVENT_ONE"),
+                recurrence_id: None,
+                ical: String::from("DTSTART:20210105T183000Z\nDURATION:PT1H\nRRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3\nSUMMARY:A long summary which has been folded across two lines\nCATEGORIES:CATEGORY_ONE\nBEGIN:VALARM\nUID:ALARM_ONE\nACTION:DISPLAY\nTRIGGER:-PT15M\nACKNOWLEDGED:20210105T181500Z\nEND:VALARM"),
+            },
+        );
+
+        assert!(!ics_event_components[0].is_override());
+        assert!(ics_event_components[1].is_override());
+
+        assert_eq!(ics_event_components[1].uid, String::from("EVENT_ONE"));
+        assert_eq!(ics_event_components[1].ical, String::from("SUMMARY:Overridden summary\nCATEGORIES:CATEGORY_TWO"));
+        assert_eq!(ics_event_components[1].get_recurrence_id_date_string(), Some(String::from("20210112T183000Z")));
+
+        let event = ics_event_components[0].parse_event().unwrap();
+
+        assert_eq!(event.uid.uid.to_string(), String::from("EVENT_ONE"));
+        assert_eq!(event.schedule_properties.get_duration_in_seconds(), Some(3600));
+
+        // The VALARM (along with it's own UID) is retained, and split back out when rendered.
+        assert!(
+            event
+                .to_rendered_content_lines()
+                .contains(&String::from("BEGIN:VALARM UID:ALARM_ONE ACTION:DISPLAY TRIGGER:-PT15M ACKNOWLEDGED:20210105T181500Z END:VALARM"))
+        );
+
+        assert!(
+            render_ics_document(&Calendar::new(String::from("CALENDAR_UID")), [&event])
+                .contains("\r\nBEGIN:VALARM\r\nUID:ALARM_ONE\r\nACTION:DISPLAY\r\nTRIGGER:-PT15M\r\nACKNOWLEDGED:20210105T181500Z\r\nEND:VALARM\r\n")
+        );
+
+        let event_occurrence_override = ics_event_components[1].parse_event_occurrence_override().unwrap();
+
+        assert_eq!(
+            event_occurrence_override.get_dtstart_timestamp(),
+            ics_event_components[1].get_recurrence_id_timestamp(),
+        );
+
+        assert!(ics_event_components[0].parse_event_occurrence_override().is_err());
+        assert!(ics_event_components[1].parse_event().is_err());
+    }
+
+    #[test]
+    fn test_parse_and_render_ics_document_with_participants() {
+        let ics_event_components = parse_ics_document(
+            [
+                "BEGIN:VCALENDAR",
+                "BEGIN:VEVENT",
+                "UID:EVENT_ONE",
+                "DTSTART:20210105T183000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "BEGIN:PARTICIPANT",
+                "PARTICIPANT-TYPE:SPEAKER",
+                "UID:SPEAKER_ONE",
+                "STRUCTURED-DATA;FMTTYPE=application/ld+json;VALUE=URI:https://example.com/",
+                " speakers/jane-doe.jsonld",
+                "BEGIN:VLOCATION",
+                "UID:LOCATION_ONE",
+                "END:VLOCATION",
+                "END:PARTICIPANT",
+                "SUMMARY:Keynote",
+                "END:VEVENT",
+                "END:VCALENDAR",
+            ].join("\r\n").as_str()
+        ).unwrap();
+
+        // The UID of the PARTICIPANT is retained within it, and the nested VLOCATION is ignored.
+        assert_eq!(
+            ics_event_components,
+            vec![
+                ICSEventComponent {
+                    uid: String::from("EVENT_ONE"),
+                    recurrence_id: None,
+                    ical: String::from("DTSTART:20210105T183000Z\nLAST-MODIFIED:20210501T090000Z\nBEGIN:PARTICIPANT\nPARTICIPANT-TYPE:SPEAKER\nUID:SPEAKER_ONE\nSTRUCTURED-DATA;FMTTYPE=application/ld+json;VALUE=URI:https://example.com/speakers/jane-doe.jsonld\nEND:PARTICIPANT\nSUMMARY:Keynote"),
+                },
+            ],
+        );
+
+        let event = ics_event_components[0].parse_event().unwrap();
+
+        let calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        assert_eq!(
+            render_ics_document(&calendar, [&event]),
+            [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//RediCal//RediCal//EN",
+                "UID:CALENDAR_UID",
+                "BEGIN:VEVENT",
+                "BEGIN:PARTICIPANT",
+                "PARTICIPANT-TYPE:SPEAKER",
+                "UID:SPEAKER_ONE",
+                "STRUCTURED-DATA;FMTTYPE=application/ld+json;VALUE=URI:https://example.com/s",
+                " peakers/jane-doe.jsonld",
+                "END:PARTICIPANT",
+                "DTSTART:20210105T183000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "SUMMARY:Keynote",
+                "UID:EVENT_ONE",
+                "END:VEVENT",
+                "END:VCALENDAR",
+                "",
+            ].join("\r\n"),
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_ics_document() {
+        assert_eq!(
+            parse_ics_document("BEGIN:VEVENT\nUID:EVENT_ONE\nEND:VEVENT"),
+            Err(String::from("Expected iCalendar document to begin with BEGIN:VCALENDAR")),
+        );
+
+        assert_eq!(
+            parse_ics_document("BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:EVENT_ONE\nEND:VEVENT"),
+            Err(String::from("Expected iCalendar document to end with END:VCALENDAR")),
+        );
+
+        assert_eq!(
+            parse_ics_document("BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:20210105T183000Z\nEND:VEVENT\nEND:VCALENDAR"),
+            Err(String::from("Expected VEVENT to have a UID defined")),
+        );
+
+        assert_eq!(
+            parse_ics_document("BEGIN:VCALENDAR\nEND:VCALENDAR\nBEGIN:VEVENT"),
+            Err(String::from("Unexpected content after END:VCALENDAR")),
+        );
+
+        assert!(
+            parse_ics_document("BEGIN:VCALENDAR\nBEGIN:VEVENT\nUID:EVENT_ONE\nRECURRENCE-ID:INVALID\nEND:VEVENT\nEND:VCALENDAR").is_err()
+        );
+
+        assert_eq!(parse_ics_document("BEGIN:VCALENDAR\nEND:VCALENDAR"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_render_ics_document() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        let mut event = Event::parse_ical(
+            "EVENT_ONE",
+            "DTSTART:20210105T183000Z DTEND:20210105T190000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3 LAST-MODIFIED:20210501T090000Z DESCRIPTION:A very long description which will need to be folded across multiple lines of the rendered document",
+        ).unwrap();
+
+        event.validate().unwrap();
+
+        event.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210112T183000Z", "LAST-MODIFIED:20210501T090000Z SUMMARY:Overridden summary").unwrap(),
+            false,
+        ).unwrap();
+
+        calendar.insert_event(event);
+
+        let ics_document = render_ics_document(&calendar, calendar.events.values().map(|event| event.as_ref()));
+
+        assert_eq!(
+            ics_document,
+            [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//RediCal//RediCal//EN",
+                "UID:CALENDAR_UID",
+                "BEGIN:VEVENT",
+                "DESCRIPTION:A very long description which will need to be folded across mul",
+                " tiple lines of the rendered document",
+                "DTEND:20210105T190000Z",
+                "DTSTART:20210105T183000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "RRULE:COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "UID:EVENT_ONE",
+                "END:VEVENT",
+                "BEGIN:VEVENT",
+                "UID:EVENT_ONE",
+                "RECURRENCE-ID;VALUE=DATE-TIME:20210112T183000Z",
+                "DTSTART:20210112T183000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "SUMMARY:Overridden summary",
+                "DURATION:PT30M",
+                "END:VEVENT",
+                "END:VCALENDAR",
+                "",
+            ].join("\r\n"),
+        );
+
+        // Assert the rendered document can be imported again.
+        let ics_event_components = parse_ics_document(ics_document.as_str()).unwrap();
+
+        assert_eq!(ics_event_components.len(), 2);
+        assert_eq!(ics_event_components[1].get_recurrence_id_date_string(), Some(String::from("20210112T183000Z")));
+        assert!(ics_event_components[0].parse_event().is_ok());
+        assert!(ics_event_components[1].parse_event_occurrence_override().is_ok());
+    }
+}
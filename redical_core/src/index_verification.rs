@@ -0,0 +1,467 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::{
+    Calendar, Event, EventTimeBuckets, GeoPoint, IndexedConclusion, InternedString,
+    InvertedCalendarIndex, InvertedCalendarIndexTerm, InvertedEventIndex, KeyValuePair,
+};
+
+/// How the live Calendar index differs from the index terms recomputed from the event.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum IndexDiscrepancyKind {
+    // The recomputed term is absent from the live index.
+    Missing,
+
+    // The live index holds a term which is not recomputed from the event (or the event no longer
+    // exists on the Calendar).
+    Unexpected,
+
+    // The live index holds the term, but with a different include/exclude conclusion (or
+    // different time buckets).
+    Mismatched,
+}
+
+impl std::fmt::Display for IndexDiscrepancyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndexDiscrepancyKind::Missing => write!(f, "MISSING"),
+            IndexDiscrepancyKind::Unexpected => write!(f, "UNEXPECTED"),
+            IndexDiscrepancyKind::Mismatched => write!(f, "MISMATCHED"),
+        }
+    }
+}
+
+/// A single event index term which is inconsistent between the live Calendar index and the index
+/// terms recomputed from the event.
+///
+/// The term is absent for the time bucket index, as the buckets of an event are compared as a
+/// whole.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct IndexDiscrepancy {
+    pub event_uid: String,
+    pub index: String,
+    pub term: Option<String>,
+    pub kind: IndexDiscrepancyKind,
+}
+
+impl IndexDiscrepancy {
+    pub fn new(event_uid: &str, index: &str, term: Option<String>, kind: IndexDiscrepancyKind) -> Self {
+        IndexDiscrepancy {
+            event_uid: event_uid.to_owned(),
+            index: index.to_owned(),
+            term,
+            kind,
+        }
+    }
+}
+
+/// The outcome of verifying the Calendar indexes against the index terms recomputed from each
+/// verified event.
+#[derive(Debug, PartialEq, Clone)]
+pub struct IndexVerification {
+    pub verified_event_count: usize,
+    pub discrepancies: Vec<IndexDiscrepancy>,
+}
+
+impl IndexVerification {
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+// The live index terms (and conclusions) held for each event.
+type LiveEventTerms<K> = HashMap<String, HashMap<K, IndexedConclusion>>;
+
+impl Calendar {
+    /// Recompute the index terms of the provided events (or every event if None) and compare them
+    /// to the live Calendar indexes, returning each discrepancy found.
+    ///
+    /// Live index entries referencing events no longer stored on the Calendar are always reported
+    /// (regardless of the events verified), as they are found whilst scanning the live indexes.
+    pub fn verify_indexes(&self, event_uids: Option<&HashSet<String>>) -> Result<IndexVerification, String> {
+        if !self.indexes_active {
+            return Err(String::from("Calendar indexes are disabled"));
+        }
+
+        let is_verified = |event_uid: &str| {
+            if !self.events.contains_key(event_uid) {
+                return true;
+            }
+
+            match event_uids {
+                Some(event_uids) => event_uids.contains(event_uid),
+                None => true,
+            }
+        };
+
+        let live_categories = Self::collect_live_event_terms(&self.indexed_categories, is_verified);
+        let live_location_type = Self::collect_live_event_terms(&self.indexed_location_type, is_verified);
+        let live_related_to = Self::collect_live_event_terms(&self.indexed_related_to, is_verified);
+        let live_class = Self::collect_live_event_terms(&self.indexed_class, is_verified);
+        let live_location = Self::collect_live_event_terms(&self.indexed_location, is_verified);
+        let live_x_properties = Self::collect_live_event_terms(&self.indexed_x_properties, is_verified);
+
+        let mut live_geo: LiveEventTerms<GeoPoint> = HashMap::new();
+
+        for (geo_point, indexed_term) in self.indexed_geo.iter() {
+            Self::collect_live_index_term_events(&mut live_geo, geo_point, indexed_term, is_verified);
+        }
+
+        let live_time_buckets = self.collect_live_event_time_buckets(is_verified);
+
+        let mut discrepancies = Vec::new();
+        let mut verified_event_count = 0;
+
+        for (event_uid, event) in &self.events {
+            if !is_verified(event_uid.as_str()) {
+                continue;
+            }
+
+            verified_event_count += 1;
+
+            Self::diff_event_terms(
+                &mut discrepancies,
+                event_uid,
+                "CATEGORIES",
+                &InvertedEventIndex::<InternedString>::new_from_event_categories(event),
+                live_categories.get(event_uid),
+                |category| category.to_string(),
+            );
+
+            Self::diff_event_terms(
+                &mut discrepancies,
+                event_uid,
+                "LOCATION-TYPE",
+                &InvertedEventIndex::<InternedString>::new_from_event_location_type(event),
+                live_location_type.get(event_uid),
+                |location_type| location_type.to_string(),
+            );
+
+            Self::diff_event_terms(
+                &mut discrepancies,
+                event_uid,
+                "RELATED-TO",
+                &InvertedEventIndex::<KeyValuePair>::new_from_event_related_to(event),
+                live_related_to.get(event_uid),
+                |reltype_uids| format!("{};{}", reltype_uids.key, reltype_uids.value),
+            );
+
+            Self::diff_event_terms(
+                &mut discrepancies,
+                event_uid,
+                "GEO",
+                &InvertedEventIndex::<GeoPoint>::new_from_event_geo(event),
+                live_geo.get(event_uid),
+                |long_lat| long_lat.to_string(),
+            );
+
+            Self::diff_event_terms(
+                &mut discrepancies,
+                event_uid,
+                "CLASS",
+                &InvertedEventIndex::<InternedString>::new_from_event_class(event),
+                live_class.get(event_uid),
+                |class| class.to_string(),
+            );
+
+            Self::diff_event_terms(
+                &mut discrepancies,
+                event_uid,
+                "LOCATION",
+                &InvertedEventIndex::<InternedString>::new_from_event_location(event),
+                live_location.get(event_uid),
+                |location| location.to_string(),
+            );
+
+            // Only the X-properties declared by the Calendar are expected to be indexed.
+            let mut expected_x_properties = InvertedEventIndex::<KeyValuePair>::new_from_event_x_properties(event);
+
+            expected_x_properties.terms.retain(|name_value, _| self.is_x_property_indexed(&name_value.key));
+
+            Self::diff_event_terms(
+                &mut discrepancies,
+                event_uid,
+                "X-PROP",
+                &expected_x_properties,
+                live_x_properties.get(event_uid),
+                |name_value| format!("{};{}", name_value.key, name_value.value),
+            );
+
+            Self::diff_event_time_buckets(&mut discrepancies, event, live_time_buckets.get(event_uid))?;
+        }
+
+        // Report every live index entry of events no longer stored on the Calendar.
+        Self::report_orphaned_event_terms(&mut discrepancies, &self.events, "CATEGORIES", &live_categories, |category| category.to_string());
+        Self::report_orphaned_event_terms(&mut discrepancies, &self.events, "LOCATION-TYPE", &live_location_type, |location_type| location_type.to_string());
+        Self::report_orphaned_event_terms(&mut discrepancies, &self.events, "RELATED-TO", &live_related_to, |reltype_uids| format!("{};{}", reltype_uids.key, reltype_uids.value));
+        Self::report_orphaned_event_terms(&mut discrepancies, &self.events, "GEO", &live_geo, |long_lat| long_lat.to_string());
+        Self::report_orphaned_event_terms(&mut discrepancies, &self.events, "CLASS", &live_class, |class| class.to_string());
+        Self::report_orphaned_event_terms(&mut discrepancies, &self.events, "LOCATION", &live_location, |location| location.to_string());
+        Self::report_orphaned_event_terms(&mut discrepancies, &self.events, "X-PROP", &live_x_properties, |name_value| format!("{};{}", name_value.key, name_value.value));
+
+        for event_uid in live_time_buckets.keys() {
+            if !self.events.contains_key(event_uid) {
+                discrepancies.push(IndexDiscrepancy::new(event_uid, "TIME-BUCKETS", None, IndexDiscrepancyKind::Unexpected));
+            }
+        }
+
+        discrepancies.sort();
+
+        Ok(IndexVerification {
+            verified_event_count,
+            discrepancies,
+        })
+    }
+
+    fn collect_live_event_terms<K>(
+        inverted_calendar_index: &InvertedCalendarIndex<K>,
+        is_verified: impl Fn(&str) -> bool,
+    ) -> LiveEventTerms<K>
+    where
+        K: Hash + Clone + Ord,
+    {
+        let mut live_event_terms = HashMap::new();
+
+        for (term, inverted_calendar_index_term) in &inverted_calendar_index.terms {
+            Self::collect_live_index_term_events(&mut live_event_terms, term, inverted_calendar_index_term, &is_verified);
+        }
+
+        live_event_terms
+    }
+
+    fn collect_live_index_term_events<K>(
+        live_event_terms: &mut LiveEventTerms<K>,
+        term: &K,
+        inverted_calendar_index_term: &InvertedCalendarIndexTerm,
+        is_verified: impl Fn(&str) -> bool,
+    ) where
+        K: Hash + Clone + Eq,
+    {
+        for (event_uid, indexed_conclusion) in &inverted_calendar_index_term.events {
+            if !is_verified(event_uid.as_str()) {
+                continue;
+            }
+
+            live_event_terms
+                .entry(event_uid.to_string())
+                .or_default()
+                .insert(term.to_owned(), indexed_conclusion.to_owned());
+        }
+    }
+
+    // Rebuild the time buckets of each event from the bucket lookups used when querying, rather
+    // than the per-event bookkeeping used to maintain them.
+    fn collect_live_event_time_buckets(&self, is_verified: impl Fn(&str) -> bool) -> HashMap<String, EventTimeBuckets> {
+        let mut live_event_time_buckets: HashMap<String, EventTimeBuckets> = HashMap::new();
+
+        for (bucket, event_uids) in &self.indexed_time_buckets.buckets {
+            for event_uid in event_uids.iter().filter(|event_uid| is_verified(event_uid.as_str())) {
+                live_event_time_buckets.entry(event_uid.to_owned()).or_default().buckets.insert(*bucket);
+            }
+        }
+
+        for (open_ended_from, event_uids) in &self.indexed_time_buckets.open_ended {
+            for event_uid in event_uids.iter().filter(|event_uid| is_verified(event_uid.as_str())) {
+                live_event_time_buckets.entry(event_uid.to_owned()).or_default().open_ended_from = Some(*open_ended_from);
+            }
+        }
+
+        for (ends_at, event_uids) in &self.indexed_time_buckets.ends {
+            for event_uid in event_uids.iter().filter(|event_uid| is_verified(event_uid.as_str())) {
+                live_event_time_buckets.entry(event_uid.to_owned()).or_default().ends_at = Some(*ends_at);
+            }
+        }
+
+        live_event_time_buckets
+    }
+
+    fn diff_event_terms<K>(
+        discrepancies: &mut Vec<IndexDiscrepancy>,
+        event_uid: &str,
+        index: &str,
+        expected_event_index: &InvertedEventIndex<K>,
+        live_event_terms: Option<&HashMap<K, IndexedConclusion>>,
+        render_term: impl Fn(&K) -> String,
+    ) where
+        K: Hash + Clone + Eq,
+    {
+        let empty_live_event_terms = HashMap::new();
+        let live_event_terms = live_event_terms.unwrap_or(&empty_live_event_terms);
+
+        for (term, expected_indexed_conclusion) in &expected_event_index.terms {
+            let kind = match live_event_terms.get(term) {
+                None => IndexDiscrepancyKind::Missing,
+                Some(live_indexed_conclusion) if live_indexed_conclusion != expected_indexed_conclusion => IndexDiscrepancyKind::Mismatched,
+                Some(_) => continue,
+            };
+
+            discrepancies.push(IndexDiscrepancy::new(event_uid, index, Some(render_term(term)), kind));
+        }
+
+        for term in live_event_terms.keys() {
+            if !expected_event_index.terms.contains_key(term) {
+                discrepancies.push(IndexDiscrepancy::new(event_uid, index, Some(render_term(term)), IndexDiscrepancyKind::Unexpected));
+            }
+        }
+    }
+
+    fn diff_event_time_buckets(
+        discrepancies: &mut Vec<IndexDiscrepancy>,
+        event: &Event,
+        live_event_time_buckets: Option<&EventTimeBuckets>,
+    ) -> Result<(), String> {
+        let event_uid = event.uid.uid.to_string();
+        let expected_event_time_buckets = EventTimeBuckets::new_from_event(event)?;
+
+        // Events without any occurrences occupy no buckets, so are absent from the bucket lookups.
+        let is_expected_empty =
+            expected_event_time_buckets.buckets.is_empty() && expected_event_time_buckets.open_ended_from.is_none();
+
+        let kind = match live_event_time_buckets {
+            None if is_expected_empty => return Ok(()),
+            None => IndexDiscrepancyKind::Missing,
+            Some(live_event_time_buckets) if *live_event_time_buckets != expected_event_time_buckets => IndexDiscrepancyKind::Mismatched,
+            Some(_) => return Ok(()),
+        };
+
+        discrepancies.push(IndexDiscrepancy::new(&event_uid, "TIME-BUCKETS", None, kind));
+
+        Ok(())
+    }
+
+    fn report_orphaned_event_terms<K, V>(
+        discrepancies: &mut Vec<IndexDiscrepancy>,
+        events: &BTreeMap<String, V>,
+        index: &str,
+        live_event_terms: &LiveEventTerms<K>,
+        render_term: impl Fn(&K) -> String,
+    ) where
+        K: Hash + Clone + Eq,
+    {
+        for (event_uid, live_terms) in live_event_terms {
+            if events.contains_key(event_uid) {
+                continue;
+            }
+
+            let terms: BTreeSet<String> = live_terms.keys().map(&render_term).collect();
+
+            for term in terms {
+                discrepancies.push(IndexDiscrepancy::new(event_uid, index, Some(term), IndexDiscrepancyKind::Unexpected));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use crate::testing::utils::build_event_from_ical;
+
+    fn build_calendar() -> Calendar {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_ONE", vec!["DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3 CATEGORIES:CATEGORY_ONE,CATEGORY_TWO CLASS:PUBLIC GEO:51.5074;-0.1278"])
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_TWO", vec!["DTSTART:20210105T183000Z CATEGORIES:CATEGORY_ONE"])
+        ).unwrap();
+
+        calendar
+    }
+
+    #[test]
+    fn test_verify_indexes_consistent() {
+        let calendar = build_calendar();
+
+        assert_eq!(
+            calendar.verify_indexes(None),
+            Ok(IndexVerification {
+                verified_event_count: 2,
+                discrepancies: vec![],
+            }),
+        );
+
+        assert_eq!(
+            calendar.verify_indexes(Some(&HashSet::from([String::from("EVENT_TWO")]))),
+            Ok(IndexVerification {
+                verified_event_count: 1,
+                discrepancies: vec![],
+            }),
+        );
+
+        let mut calendar = calendar;
+
+        calendar.disable_indexes();
+
+        assert_eq!(calendar.verify_indexes(None), Err(String::from("Calendar indexes are disabled")));
+    }
+
+    #[test]
+    fn test_verify_indexes_discrepancies() {
+        let mut calendar = build_calendar();
+
+        // Missing term.
+        calendar.indexed_categories.remove(String::from("EVENT_ONE"), String::from("CATEGORY_TWO")).unwrap();
+
+        // Unexpected term.
+        calendar.indexed_class.insert(
+            String::from("EVENT_TWO"),
+            String::from("PRIVATE"),
+            &IndexedConclusion::Include(None),
+        ).unwrap();
+
+        // Mismatched term conclusion.
+        calendar.indexed_geo.insert(
+            String::from("EVENT_ONE"),
+            &GeoPoint::new(51.5074, -0.1278),
+            &IndexedConclusion::Exclude(None),
+        ).unwrap();
+
+        // Orphaned term of an event no longer on the Calendar.
+        calendar.indexed_location_type.insert(
+            String::from("EVENT_REMOVED"),
+            String::from("ONLINE"),
+            &IndexedConclusion::Include(None),
+        ).unwrap();
+
+        // Missing time buckets.
+        calendar.indexed_time_buckets.remove(&String::from("EVENT_TWO"));
+
+        assert_eq!(
+            calendar.verify_indexes(None),
+            Ok(IndexVerification {
+                verified_event_count: 2,
+                discrepancies: vec![
+                    IndexDiscrepancy::new("EVENT_ONE", "CATEGORIES", Some(String::from("CATEGORY_TWO")), IndexDiscrepancyKind::Missing),
+                    IndexDiscrepancy::new("EVENT_ONE", "GEO", Some(String::from("51.5074;-0.1278")), IndexDiscrepancyKind::Mismatched),
+                    IndexDiscrepancy::new("EVENT_REMOVED", "LOCATION-TYPE", Some(String::from("ONLINE")), IndexDiscrepancyKind::Unexpected),
+                    IndexDiscrepancy::new("EVENT_TWO", "CLASS", Some(String::from("PRIVATE")), IndexDiscrepancyKind::Unexpected),
+                    IndexDiscrepancy::new("EVENT_TWO", "TIME-BUCKETS", None, IndexDiscrepancyKind::Missing),
+                ],
+            }),
+        );
+
+        // Only the discrepancies of the verified events (and orphaned terms) are reported.
+        assert_eq!(
+            calendar.verify_indexes(Some(&HashSet::from([String::from("EVENT_TWO")]))),
+            Ok(IndexVerification {
+                verified_event_count: 1,
+                discrepancies: vec![
+                    IndexDiscrepancy::new("EVENT_REMOVED", "LOCATION-TYPE", Some(String::from("ONLINE")), IndexDiscrepancyKind::Unexpected),
+                    IndexDiscrepancy::new("EVENT_TWO", "CLASS", Some(String::from("PRIVATE")), IndexDiscrepancyKind::Unexpected),
+                    IndexDiscrepancy::new("EVENT_TWO", "TIME-BUCKETS", None, IndexDiscrepancyKind::Missing),
+                ],
+            }),
+        );
+
+        // Rebuilding the indexes restores consistency.
+        calendar.rebuild_indexes().unwrap();
+
+        assert_eq!(calendar.verify_indexes(None).unwrap().is_consistent(), true);
+    }
+}
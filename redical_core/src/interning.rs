@@ -0,0 +1,219 @@
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+lazy_static::lazy_static! {
+    static ref INTERNED_STRING_TABLE: Mutex<InternedStringTable> = Mutex::new(InternedStringTable::default());
+}
+
+// The table is purged of strings no longer referenced by any index once it has grown to this
+// many strings (or twice the amount remaining after the previous purge, whichever is greater).
+const MIN_PURGE_THRESHOLD: usize = 1_024;
+
+// The table of every currently interned string, shared across all Calendars so that the same
+// term (e.g. a category) indexed by many Calendars is only allocated once.
+#[derive(Debug, Default)]
+struct InternedStringTable {
+    strings: HashSet<Arc<str>>,
+    purge_threshold: usize,
+}
+
+impl InternedStringTable {
+    fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(interned_string) = self.strings.get(value) {
+            return interned_string.clone();
+        }
+
+        if self.strings.len() >= self.purge_threshold.max(MIN_PURGE_THRESHOLD) {
+            self.purge();
+        }
+
+        let interned_string: Arc<str> = Arc::from(value);
+
+        self.strings.insert(interned_string.clone());
+
+        interned_string
+    }
+
+    // Drop the strings only referenced by the table itself (i.e. removed from every index), with
+    // the next purge deferred until the table has doubled in size so that it's cost is amortised.
+    fn purge(&mut self) {
+        self.strings.retain(|interned_string| Arc::strong_count(interned_string) > 1);
+
+        self.purge_threshold = self.strings.len().saturating_mul(2);
+    }
+}
+
+/// An immutable string shared by reference across every index map it is present in (rather than
+/// duplicated as a `String` in each).
+///
+/// Event UIDs and indexed terms (categories, location types, locations, classes) are repeated
+/// across many of the Calendar index maps, so are interned to reduce the memory footprint of
+/// large Calendars.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InternedString(Arc<str>);
+
+impl InternedString {
+    pub fn new(value: &str) -> Self {
+        InternedString(INTERNED_STRING_TABLE.lock().unwrap().intern(value))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for InternedString {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for InternedString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for InternedString {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for InternedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Debug for InternedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl From<&str> for InternedString {
+    fn from(value: &str) -> Self {
+        InternedString::new(value)
+    }
+}
+
+impl From<&String> for InternedString {
+    fn from(value: &String) -> Self {
+        InternedString::new(value.as_str())
+    }
+}
+
+impl From<String> for InternedString {
+    fn from(value: String) -> Self {
+        InternedString::new(value.as_str())
+    }
+}
+
+impl From<InternedString> for String {
+    fn from(value: InternedString) -> Self {
+        value.0.to_string()
+    }
+}
+
+impl PartialEq<str> for InternedString {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for InternedString {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for InternedString {
+    fn eq(&self, other: &String) -> bool {
+        &*self.0 == other.as_str()
+    }
+}
+
+impl Serialize for InternedString {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for InternedString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(InternedString::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_interned_string() {
+        let interned_string = InternedString::from("CATEGORY_ONE");
+
+        assert_eq!(interned_string.as_str(), "CATEGORY_ONE");
+        assert_eq!(interned_string.to_string(), String::from("CATEGORY_ONE"));
+        assert_eq!(format!("{interned_string:?}"), String::from("\"CATEGORY_ONE\""));
+        assert_eq!(interned_string, String::from("CATEGORY_ONE"));
+        assert_eq!(interned_string, "CATEGORY_ONE");
+
+        // Assert the same string is shared rather than allocated again.
+        assert!(Arc::ptr_eq(&interned_string.0, &InternedString::from(String::from("CATEGORY_ONE")).0));
+
+        // Assert maps keyed by interned strings can be queried by &str.
+        let interned_string_map = HashMap::from([(interned_string.clone(), 1)]);
+
+        assert_eq!(interned_string_map.get("CATEGORY_ONE"), Some(&1));
+        assert_eq!(interned_string_map.get("CATEGORY_TWO"), None);
+
+        assert_eq!(
+            bincode::deserialize::<InternedString>(&bincode::serialize(&interned_string).unwrap()).unwrap(),
+            interned_string,
+        );
+    }
+
+    #[test]
+    fn test_interned_string_table() {
+        let mut interned_string_table = InternedStringTable::default();
+
+        let retained_strings: Vec<Arc<str>> =
+            (0..MIN_PURGE_THRESHOLD)
+                .map(|index| interned_string_table.intern(format!("TERM_{index}").as_str()))
+                .filter(|interned_string| !interned_string.ends_with('1'))
+                .collect();
+
+        assert_eq!(interned_string_table.strings.len(), MIN_PURGE_THRESHOLD);
+
+        // Assert interning a string already present does not trigger a purge.
+        assert!(Arc::ptr_eq(&interned_string_table.intern("TERM_0"), &retained_strings[0]));
+        assert_eq!(interned_string_table.strings.len(), MIN_PURGE_THRESHOLD);
+
+        // Assert the strings no longer referenced are purged upon reaching the threshold.
+        let _new_string = interned_string_table.intern("NEW_TERM");
+
+        assert_eq!(interned_string_table.strings.len(), retained_strings.len() + 1);
+        assert_eq!(interned_string_table.purge_threshold, retained_strings.len() * 2);
+        assert!(!interned_string_table.strings.contains("TERM_1"));
+        assert!(interned_string_table.strings.contains("TERM_2"));
+    }
+}
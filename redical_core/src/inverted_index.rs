@@ -1,15 +1,25 @@
-use std::collections::{HashMap, HashSet};
+use std::borrow::{Borrow, Cow};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::Bound;
+
+use regex::Regex;
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use serde::{Deserialize, Serialize};
 
 use crate::event::Event;
 use crate::geo_index::GeoPoint;
+use crate::interning::InternedString;
 
 use crate::utils::{KeyValuePair, UpdatedHashMapMembers, UpdatedSetMembers};
 
 use redical_ical::properties::ICalendarGeoProperty;
 
-#[derive(Default, Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, Clone)]
 pub struct InvertedCalendarIndexTerm {
-    pub events: HashMap<String, IndexedConclusion>,
+    pub events: HashMap<InternedString, IndexedConclusion>,
 }
 
 impl InvertedCalendarIndexTerm {
@@ -17,7 +27,7 @@ impl InvertedCalendarIndexTerm {
         Self::default()
     }
 
-    pub fn new_with_event(event_uid: String, indexed_conclusion: IndexedConclusion) -> Self {
+    pub fn new_with_event(event_uid: impl Into<InternedString>, indexed_conclusion: IndexedConclusion) -> Self {
         Self::new_with_events(
             vec![
                 (event_uid, indexed_conclusion),
@@ -25,7 +35,10 @@ impl InvertedCalendarIndexTerm {
         )
     }
 
-    pub fn new_with_events(event_uid_index_conclusion_pairs: Vec<(String, IndexedConclusion)>) -> Self {
+    pub fn new_with_events<E>(event_uid_index_conclusion_pairs: Vec<(E, IndexedConclusion)>) -> Self
+    where
+        E: Into<InternedString>,
+    {
         let mut inverted_calendar_index_term = Self::new();
 
         for (event_uid, indexed_conclusion) in event_uid_index_conclusion_pairs {
@@ -49,7 +62,7 @@ impl InvertedCalendarIndexTerm {
         let events_a = &inverted_index_term_a.events;
         let events_b = &inverted_index_term_b.events;
 
-        let mut compound_events = HashMap::<String, IndexedConclusion>::new();
+        let mut compound_events = HashMap::<InternedString, IndexedConclusion>::new();
 
         // TODO:
         //   * Iterate on the smallest events HashMap for efficiency
@@ -76,14 +89,14 @@ impl InvertedCalendarIndexTerm {
         let events_a = &inverted_index_term_a.events;
         let events_b = &inverted_index_term_b.events;
 
-        let mut compound_events = HashMap::<String, IndexedConclusion>::new();
+        let mut compound_events = HashMap::<InternedString, IndexedConclusion>::new();
 
         // TODO:
         //   * clone()/borrowing etc
         //   * refine this logic to be more concise/readable...
 
-        let events_a_uids = HashSet::<String>::from_iter(events_a.keys().cloned());
-        let events_b_uids = HashSet::<String>::from_iter(events_b.keys().cloned());
+        let events_a_uids = HashSet::<InternedString>::from_iter(events_a.keys().cloned());
+        let events_b_uids = HashSet::<InternedString>::from_iter(events_b.keys().cloned());
 
         let uid_key_diff = UpdatedSetMembers::new(Some(&events_a_uids), Some(&events_b_uids));
 
@@ -126,6 +139,37 @@ impl InvertedCalendarIndexTerm {
         }
     }
 
+    /// Returns a virtual indexed event set of the provided events which do not match the given
+    /// (optional) matching event set (NOT).
+    ///
+    /// The negated virtual index is formed by building an index of full inclusions of all the
+    /// provided events and then merging in the inverse of the matching event set.
+    pub fn new_negated(matching_event_set: Option<&InvertedCalendarIndexTerm>, event_uids: &[String]) -> Self {
+        // Create an empty event set
+        let mut negated_event_set = InvertedCalendarIndexTerm::new();
+
+        // Initially index all events as Included
+        for event_uid in event_uids.iter() {
+            negated_event_set.insert_included_event(event_uid.to_owned(), None);
+        }
+
+        // Merge the inverse of the matching event set if present
+        if let Some(matching_event_set) = matching_event_set {
+            let not_matching_event_set = matching_event_set.inverse();
+
+            for (event_uid, indexed_conclusion) in not_matching_event_set.events {
+                // Remove Exclude(None) results or merge into the virtual index.
+                if indexed_conclusion.is_empty_exclude() {
+                    negated_event_set.events.remove(&event_uid);
+                } else {
+                    negated_event_set.events.insert(event_uid, indexed_conclusion);
+                }
+            }
+        }
+
+        negated_event_set
+    }
+
     pub fn inverse(&self) -> Self {
         let inverted_events = self.events.iter()
             .map(|(uid, indexed_conclusion)| (uid.clone(), indexed_conclusion.negate()))
@@ -135,7 +179,7 @@ impl InvertedCalendarIndexTerm {
     }
 
     pub fn include_event_occurrence(&self, event_uid: String, occurrence: i64) -> bool {
-        match self.events.get(&event_uid) {
+        match self.events.get(event_uid.as_str()) {
             Some(indexed_conclusion) => indexed_conclusion.include_event_occurrence(occurrence),
             None => false,
         }
@@ -143,24 +187,24 @@ impl InvertedCalendarIndexTerm {
 
     pub fn insert_included_event(
         &mut self,
-        event_uid: String,
+        event_uid: impl Into<InternedString>,
         exceptions: Option<HashSet<i64>>,
     ) -> Option<IndexedConclusion> {
         self.events
-            .insert(event_uid, IndexedConclusion::Include(exceptions))
+            .insert(event_uid.into(), IndexedConclusion::Include(exceptions))
     }
 
     pub fn insert_excluded_event(
         &mut self,
-        event_uid: String,
+        event_uid: impl Into<InternedString>,
         exceptions: Option<HashSet<i64>>,
     ) -> Option<IndexedConclusion> {
         self.events
-            .insert(event_uid, IndexedConclusion::Exclude(exceptions))
+            .insert(event_uid.into(), IndexedConclusion::Exclude(exceptions))
     }
 
     pub fn remove_event(&mut self, event_uid: String) -> Result<&mut Self, String> {
-        self.events.remove_entry(&event_uid);
+        self.events.remove_entry(event_uid.as_str());
 
         Ok(self)
     }
@@ -174,7 +218,7 @@ impl InvertedCalendarIndexTerm {
         event_uid: String,
         exception: i64,
     ) -> Result<&mut IndexedConclusion, String> {
-        match self.events.get_mut(&event_uid) {
+        match self.events.get_mut(event_uid.as_str()) {
             Some(indexed_conclusion) => {
                 indexed_conclusion.insert_exception(exception);
 
@@ -191,7 +235,7 @@ impl InvertedCalendarIndexTerm {
         event_uid: String,
         exception: i64,
     ) -> Result<&mut IndexedConclusion, String> {
-        match self.events.get_mut(&event_uid) {
+        match self.events.get_mut(event_uid.as_str()) {
             Some(indexed_conclusion) => {
                 indexed_conclusion.remove_exception(exception);
 
@@ -206,11 +250,13 @@ impl InvertedCalendarIndexTerm {
 
 // TODO: Make more generic as this is used into the geo index
 // Single layer inverted index (for one event) - indexed term - include/exclude
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(bound(serialize = "K: Serialize", deserialize = "K: Deserialize<'de>"))]
 pub struct InvertedEventIndex<K>
 where
     K: std::hash::Hash + Clone + std::cmp::Eq,
 {
+    #[serde(with = "crate::serialization::map_as_pairs")]
     pub terms: HashMap<K, IndexedConclusion>,
 }
 
@@ -229,7 +275,7 @@ impl<K> InvertedEventIndex<K>
 where
     K: std::hash::Hash + Clone + std::cmp::Eq,
 {
-    pub fn new_from_event_categories(event: &Event) -> InvertedEventIndex<String> {
+    pub fn new_from_event_categories(event: &Event) -> InvertedEventIndex<InternedString> {
         let mut indexed_categories = InvertedEventIndex {
             terms: HashMap::new(),
         };
@@ -237,40 +283,40 @@ where
         if let Some(categories_properties) = event.indexed_properties.categories.as_ref() {
             for categories_property in categories_properties {
                 for category in &categories_property.categories {
-                    indexed_categories.insert(&category.to_string());
+                    indexed_categories.insert(&InternedString::from(category.to_string()));
                 }
             }
         }
 
         for (timestamp, event_override) in event.overrides.iter() {
-            if let Some(override_categories_set) = &event_override
-                .indexed_properties
-                .extract_all_category_strings()
-            {
-                indexed_categories.insert_override(timestamp.to_owned(), override_categories_set);
+            if let Some(override_categories_set) = &event_override.get_overridden_category_strings() {
+                indexed_categories.insert_override(
+                    timestamp.to_owned(),
+                    &override_categories_set.iter().map(InternedString::from).collect(),
+                );
             }
         }
 
         indexed_categories
     }
 
-    pub fn new_from_event_location_type(event: &Event) -> InvertedEventIndex<String> {
+    pub fn new_from_event_location_type(event: &Event) -> InvertedEventIndex<InternedString> {
         let mut indexed_location_type = InvertedEventIndex {
             terms: HashMap::new(),
         };
 
         if let Some(location_type_property) = event.indexed_properties.location_type.as_ref() {
             for location_type in &location_type_property.types {
-                indexed_location_type.insert(&location_type.to_string());
+                indexed_location_type.insert(&InternedString::from(location_type.to_string()));
             }
         }
 
         for (timestamp, event_override) in event.overrides.iter() {
-            if let Some(override_location_type_set) = &event_override
-                .indexed_properties
-                .extract_all_location_type_strings()
-            {
-                indexed_location_type.insert_override(timestamp.to_owned(), override_location_type_set);
+            if let Some(override_location_type_set) = &event_override.get_overridden_location_type_strings() {
+                indexed_location_type.insert_override(
+                    timestamp.to_owned(),
+                    &override_location_type_set.iter().map(InternedString::from).collect(),
+                );
             }
         }
 
@@ -289,10 +335,7 @@ where
         }
 
         for (timestamp, event_override) in event.overrides.iter() {
-            if let Some(override_related_to_set) = &event_override
-                .indexed_properties
-                .extract_all_related_to_key_value_pairs()
-            {
+            if let Some(override_related_to_set) = &event_override.get_overridden_related_to_key_value_pairs() {
                 indexed_related_to.insert_override(timestamp.to_owned(), override_related_to_set);
             }
         }
@@ -300,6 +343,26 @@ where
         indexed_related_to
     }
 
+    pub fn new_from_event_x_properties(event: &Event) -> InvertedEventIndex<KeyValuePair> {
+        let mut indexed_x_properties = InvertedEventIndex {
+            terms: HashMap::new(),
+        };
+
+        let x_property_set = event.passive_properties.extract_x_property_key_value_pairs();
+
+        for x_property in x_property_set.iter() {
+            indexed_x_properties.insert(x_property);
+        }
+
+        for (timestamp, event_override) in event.overrides.iter() {
+            if let Some(override_x_property_set) = &event_override.get_overridden_x_property_key_value_pairs(&x_property_set) {
+                indexed_x_properties.insert_override(timestamp.to_owned(), override_x_property_set);
+            }
+        }
+
+        indexed_x_properties
+    }
+
     // TODO: Add tests...
     pub fn new_from_event_geo(event: &Event) -> InvertedEventIndex<GeoPoint> {
         let mut indexed_geo = InvertedEventIndex {
@@ -313,19 +376,9 @@ where
         }
 
         for (timestamp, event_override) in event.overrides.iter() {
-            if event_override.indexed_properties.geo.is_none() {
-                continue;
-            };
-
-            let timestamp = timestamp.to_owned();
-
             // Allow events with GEO defined to be overridden to make GEO blank (specific events online only).
-            if let Some(overridden_geo_point) = &event_override.indexed_properties.extract_geo_point() {
-                // If a non-blank GEO property defined override is present, insert this.
-                indexed_geo.insert_override(timestamp, &HashSet::from([overridden_geo_point.to_owned()]));
-            } else {
-                // If a blank GEO property defined override is present, insert the blank.
-                indexed_geo.insert_override(timestamp, &HashSet::from([]));
+            if let Some(override_geo_set) = &event_override.get_overridden_geo_points() {
+                indexed_geo.insert_override(timestamp.to_owned(), override_geo_set);
             }
         }
 
@@ -333,20 +386,20 @@ where
     }
 
     // TODO: Add tests...
-    pub fn new_from_event_class(event: &Event) -> InvertedEventIndex<String> {
+    pub fn new_from_event_class(event: &Event) -> InvertedEventIndex<InternedString> {
         let mut indexed_class = InvertedEventIndex {
             terms: HashMap::new(),
         };
 
         if let Some(class_property) = event.indexed_properties.class.as_ref() {
-            indexed_class.insert(&class_property.class.to_string());
+            indexed_class.insert(&InternedString::from(class_property.class.to_string()));
         }
 
         for (timestamp, event_override) in event.overrides.iter() {
-            if let Some(overridden_class) = &event_override.indexed_properties.extract_class() {
+            if let Some(override_class_set) = &event_override.get_overridden_class_strings() {
                 indexed_class.insert_override(
                     timestamp.to_owned(),
-                    &HashSet::from([overridden_class.to_string()]),
+                    &override_class_set.iter().map(InternedString::from).collect(),
                 );
             }
         }
@@ -354,6 +407,27 @@ where
         indexed_class
     }
 
+    pub fn new_from_event_location(event: &Event) -> InvertedEventIndex<InternedString> {
+        let mut indexed_location = InvertedEventIndex {
+            terms: HashMap::new(),
+        };
+
+        if let Some(location) = event.indexed_properties.extract_location() {
+            indexed_location.insert(&InternedString::from(location));
+        }
+
+        for (timestamp, event_override) in event.overrides.iter() {
+            if let Some(override_location_set) = &event_override.get_overridden_location_strings() {
+                indexed_location.insert_override(
+                    timestamp.to_owned(),
+                    &override_location_set.iter().map(InternedString::from).collect(),
+                );
+            }
+        }
+
+        indexed_location
+    }
+
     pub fn diff_indexed_terms(
         original: Option<&InvertedEventIndex<K>>,
         updated: Option<&InvertedEventIndex<K>>,
@@ -432,18 +506,115 @@ where
     }
 }
 
+/// How the index terms are normalised (on top of being case folded) before they are grouped
+/// together, so that terms differing only by their casing, surrounding whitespace, or diacritics
+/// can be matched without scanning every indexed term (see `X-TERM-NORMALIZATION`).
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TermNormalization {
+    pub case_fold: bool,
+    pub trim: bool,
+    pub strip_diacritics: bool,
+}
+
+impl TermNormalization {
+    pub fn new(case_fold: bool, trim: bool, strip_diacritics: bool) -> Self {
+        TermNormalization {
+            case_fold,
+            trim,
+            strip_diacritics,
+        }
+    }
+
+    /// Whether terms are only matched exactly as they are.
+    pub fn is_exact(&self) -> bool {
+        !(self.case_fold || self.trim || self.strip_diacritics)
+    }
+
+    pub fn with_case_fold(&self, case_fold: bool) -> Self {
+        TermNormalization {
+            case_fold: self.case_fold || case_fold,
+            ..*self
+        }
+    }
+
+    pub fn normalize<'a>(&self, term: &'a str) -> Cow<'a, str> {
+        let mut normalized_term = Cow::Borrowed(term);
+
+        if self.trim {
+            normalized_term = match normalized_term {
+                Cow::Borrowed(term) => Cow::Borrowed(term.trim()),
+                Cow::Owned(term) => Cow::Owned(term.trim().to_owned()),
+            };
+        }
+
+        if self.strip_diacritics && !normalized_term.is_ascii() {
+            normalized_term = Cow::Owned(
+                normalized_term
+                    .nfd()
+                    .filter(|char| !is_combining_mark(*char))
+                    .nfc()
+                    .collect()
+            );
+        }
+
+        if self.case_fold {
+            normalized_term = Cow::Owned(normalized_term.to_lowercase());
+        }
+
+        normalized_term
+    }
+}
+
+/// Index terms which can be matched once normalised (see `TermNormalization`), if they can be.
+pub trait NormalizableTerm {
+    fn normalizable_term(&self) -> Option<&str>;
+}
+
+impl NormalizableTerm for InternedString {
+    fn normalizable_term(&self) -> Option<&str> {
+        Some(self.as_str())
+    }
+}
+
+impl NormalizableTerm for String {
+    fn normalizable_term(&self) -> Option<&str> {
+        Some(self.as_str())
+    }
+}
+
+// RELATED-TO terms are always matched exactly.
+impl NormalizableTerm for KeyValuePair {
+    fn normalizable_term(&self) -> Option<&str> {
+        None
+    }
+}
+
 // Multi layer inverted index (for multiple events) - indexed term - event - include/exclude
-#[derive(Debug, PartialEq, Clone)]
+//
+// The terms are kept ordered so that they can be range scanned (e.g. for prefix matching).
+//
+// Alongside it, a normalised variant (normalised term - indexed terms) is maintained so that
+// terms can be matched case-insensitively (and normalised as configured) without scanning every
+// indexed term. The normalised terms are always case folded, so that case-insensitive matching
+// can be toggled without rebuilding the index, whereas changing the other normalisations requires
+// the index to be rebuilt.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(bound(serialize = "K: Serialize", deserialize = "K: Deserialize<'de>"))]
 pub struct InvertedCalendarIndex<K>
 where
-    K: std::hash::Hash + Clone + Eq,
+    K: std::hash::Hash + Clone + Ord,
 {
-    pub terms: HashMap<K, InvertedCalendarIndexTerm>,
+    #[serde(with = "crate::serialization::map_as_pairs")]
+    pub terms: BTreeMap<K, InvertedCalendarIndexTerm>,
+
+    pub normalized_terms: HashMap<String, BTreeSet<K>>,
+
+    pub normalization: TermNormalization,
 }
 
 impl<K> Default for InvertedCalendarIndex<K>
 where
-    K: std::hash::Hash + Clone + Eq,
+    K: std::hash::Hash + Clone + Ord,
 {
     fn default() -> Self {
         Self::new()
@@ -452,20 +623,46 @@ where
 
 impl<K> InvertedCalendarIndex<K>
 where
-    K: std::hash::Hash + Clone + Eq,
+    K: std::hash::Hash + Clone + Ord,
 {
     pub fn new() -> Self {
+        Self::new_with_normalization(TermNormalization::default())
+    }
+
+    pub fn new_with_normalization(normalization: TermNormalization) -> Self {
         InvertedCalendarIndex {
-            terms: HashMap::new(),
+            terms: BTreeMap::new(),
+            normalized_terms: HashMap::new(),
+            normalization,
         }
     }
 
+    // The key the term is grouped under within the normalised terms.
+    fn get_normalized_key(&self, term: &str) -> String {
+        self.normalization.with_case_fold(true).normalize(term).into_owned()
+    }
+
     pub fn insert(
         &mut self,
-        event_uid: String,
-        term: K,
+        event_uid: impl Into<InternedString>,
+        term: impl Into<K>,
         indexed_conclusion: &IndexedConclusion,
-    ) -> Result<&mut Self, String> {
+    ) -> Result<&mut Self, String>
+    where
+        K: NormalizableTerm,
+    {
+        let event_uid: InternedString = event_uid.into();
+        let term: K = term.into();
+
+        if let Some(normalizable_term) = term.normalizable_term() {
+            let normalized_key = self.get_normalized_key(normalizable_term);
+
+            self.normalized_terms
+                .entry(normalized_key)
+                .or_default()
+                .insert(term.clone());
+        }
+
         self.terms
             .entry(term)
             .and_modify(|term_events| {
@@ -486,9 +683,9 @@ where
         Ok(self)
     }
 
-    pub fn remove(&mut self, event_uid: String, term: K) -> Result<&mut Self, String> {
+    pub fn remove(&mut self, event_uid: String, term: impl Into<K>) -> Result<&mut Self, String> {
         self.terms
-            .entry(term)
+            .entry(term.into())
             .and_modify(|inverted_calendar_index_term| {
                 let _ = inverted_calendar_index_term.remove_event(event_uid);
             });
@@ -497,10 +694,89 @@ where
     }
 
     /// Returns an indexed Event set that matches the given term.
-    pub fn get_term(&self, term: &K) -> Option<&InvertedCalendarIndexTerm> {
+    pub fn get_term<Q>(&self, term: &Q) -> Option<&InvertedCalendarIndexTerm>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         self.terms.get(term)
     }
 
+    /// Returns the indexed Event sets of all the terms starting with the given prefix (ordered by
+    /// term), range scanning from the prefix rather than iterating over every term.
+    pub fn get_prefixed_terms<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a K, &'a InvertedCalendarIndexTerm)>
+    where
+        K: Borrow<str>,
+    {
+        self.terms
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(move |(term, _inverted_calendar_index_term)| Borrow::<str>::borrow(*term).starts_with(prefix))
+    }
+
+    /// Returns the indexed Event sets of all the terms matching the given (compiled query)
+    /// regular expression, which unlike prefixes cannot be range scanned so every term is tested.
+    pub fn get_regex_matching_terms<'a>(&'a self, regex: &'a Regex) -> impl Iterator<Item = (&'a K, &'a InvertedCalendarIndexTerm)>
+    where
+        K: Borrow<str>,
+    {
+        self.terms
+            .iter()
+            .filter(move |(term, _inverted_calendar_index_term)| regex.is_match(Borrow::<str>::borrow(*term)))
+    }
+
+    /// Returns an indexed Event set that matches the given term, either exactly or once
+    /// normalised, i.e. case-insensitively and/or as the index was built (merging the indexed
+    /// Event sets of every variant of the term indexed, e.g. "Sport", "SPORT", and " sport ").
+    pub fn get_matching_term(&self, term: &str, case_insensitive: bool) -> Option<Cow<'_, InvertedCalendarIndexTerm>>
+    where
+        K: Borrow<str>,
+    {
+        // The normalised terms are always case folded, so whether they are matched case
+        // insensitively is determined by the caller rather than when the index was built.
+        let normalization = TermNormalization {
+            case_fold: case_insensitive,
+            ..self.normalization
+        };
+
+        if normalization.is_exact() {
+            return self.terms.get(term).map(Cow::Borrowed);
+        }
+
+        let indexed_terms = self.normalized_terms.get(&self.get_normalized_key(term))?;
+
+        let normalized_term = normalization.normalize(term);
+
+        let matching_term =
+            indexed_terms
+                .iter()
+                // When matched case-sensitively only the indexed terms sharing the same casing
+                // match.
+                .filter(|indexed_term| normalization.normalize(Borrow::<str>::borrow(*indexed_term)) == normalized_term)
+                .filter_map(|indexed_term| self.terms.get::<K>(indexed_term))
+                .fold(InvertedCalendarIndexTerm::new(), |matching_term, inverted_calendar_index_term| {
+                    InvertedCalendarIndexTerm::merge_or(&matching_term, inverted_calendar_index_term)
+                });
+
+        Some(Cow::Owned(matching_term))
+    }
+
+    /// Returns a virtual indexed event set of events where the given term does not match (NOT),
+    /// either exactly or once normalised (see `get_matching_term` and `get_not_term`).
+    pub fn get_not_matching_term(
+        &self,
+        term: &str,
+        case_insensitive: bool,
+        calendar_event_uids: &[String]
+    ) -> InvertedCalendarIndexTerm
+    where
+        K: Borrow<str>,
+    {
+        InvertedCalendarIndexTerm::new_negated(
+            self.get_matching_term(term, case_insensitive).as_deref(),
+            calendar_event_uids,
+        )
+    }
+
     /// Returns a virtual indexed event set of events where the given term does not match (NOT).
     /// As there may be other events in the calendar outside those indexed here, a vector of
     /// all the event uids contained in the calendar must be passed so that they can be referenced
@@ -510,38 +786,20 @@ where
     /// in the calendar and then merging in the inverse of the event set of the given term.
     ///
     /// As this is a virtual index, ownership is transferred to the callsite.
-    pub fn get_not_term(
+    pub fn get_not_term<Q>(
         &self,
-        term: &K,
+        term: &Q,
         calendar_event_uids: &[String]
-    ) -> InvertedCalendarIndexTerm {
-        // Create an empty event set
-        let mut negated_event_set = InvertedCalendarIndexTerm::new();
-
-        // Initially index all events as Included
-        for event_uid in calendar_event_uids.iter() {
-            negated_event_set.insert_included_event(event_uid.to_owned(), None);
-        }
-
-        // Merge the inverse of the matching event set if present
-        if let Some(matching_term_event_set) = self.get_term(term) {
-            let not_matching_term_event_set = matching_term_event_set.inverse();
-
-            for (event_uid, indexed_conclusion) in not_matching_term_event_set.events {
-                // Remove Exclude(None) results or merge into the virtual index.
-                if indexed_conclusion.is_empty_exclude() {
-                    negated_event_set.events.remove(&event_uid);
-                } else {
-                    negated_event_set.events.insert(event_uid, indexed_conclusion);
-                }
-            }
-        }
-
-        negated_event_set
+    ) -> InvertedCalendarIndexTerm
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        InvertedCalendarIndexTerm::new_negated(self.get_term(term), calendar_event_uids)
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub enum IndexedConclusion {
     Include(Option<HashSet<i64>>),
     Exclude(Option<HashSet<i64>>),
@@ -930,21 +1188,21 @@ mod test {
 
     fn example_calendar_index() -> InvertedCalendarIndex<String> {
         InvertedCalendarIndex {
-            terms: HashMap::from([
+            terms: BTreeMap::from([
                 (
                     String::from("ONLINE"),
                     InvertedCalendarIndexTerm {
                         events: HashMap::from([
                             (
-                                String::from("Always online"),
+                                InternedString::from("Always online"),
                                 IndexedConclusion::Include(None)
                             ),
                             (
-                                String::from("Mostly online"),
+                                InternedString::from("Mostly online"),
                                 IndexedConclusion::Include(Some([100].into()))
                             ),
                             (
-                                String::from("Mostly in person"),
+                                InternedString::from("Mostly in person"),
                                 IndexedConclusion::Exclude(Some([100].into()))
                             ),
                         ])
@@ -955,21 +1213,26 @@ mod test {
                     InvertedCalendarIndexTerm {
                         events: HashMap::from([
                             (
-                                String::from("Always in person"),
+                                InternedString::from("Always in person"),
                                 IndexedConclusion::Include(None)
                             ),
                             (
-                                String::from("Mostly in person"),
+                                InternedString::from("Mostly in person"),
                                 IndexedConclusion::Include(Some([100].into()))
                             ),
                             (
-                                String::from("Mostly online"),
+                                InternedString::from("Mostly online"),
                                 IndexedConclusion::Exclude(Some([100].into()))
                             ),
                         ])
                     }
                 )
-            ])
+            ]),
+            normalized_terms: HashMap::from([
+                (String::from("online"), BTreeSet::from([String::from("ONLINE")])),
+                (String::from("in-person"), BTreeSet::from([String::from("IN-PERSON")])),
+            ]),
+            normalization: TermNormalization::default(),
         }
     }
 
@@ -984,15 +1247,15 @@ mod test {
                 &InvertedCalendarIndexTerm {
                     events: HashMap::from([
                         (
-                            String::from("Always online"),
+                            InternedString::from("Always online"),
                             IndexedConclusion::Include(None)
                         ),
                         (
-                            String::from("Mostly online"),
+                            InternedString::from("Mostly online"),
                             IndexedConclusion::Include(Some([100].into()))
                         ),
                         (
-                            String::from("Mostly in person"),
+                            InternedString::from("Mostly in person"),
                             IndexedConclusion::Exclude(Some([100].into()))
                         ),
                     ])
@@ -1004,6 +1267,42 @@ mod test {
         assert_eq!(index.get_term(&String::from("FOOBAR")), None);
     }
 
+    #[test]
+    fn test_inverted_calendar_index_get_prefixed_terms() {
+        let mut index = example_calendar_index();
+
+        index.insert("Online only", "ONLINE-ONLY", &IndexedConclusion::Include(None)).unwrap();
+
+        let prefixed_terms = |prefix| {
+            index
+                .get_prefixed_terms(prefix)
+                .map(|(term, inverted_calendar_index_term)| (term.to_owned(), inverted_calendar_index_term.events.len()))
+                .collect::<Vec<(String, usize)>>()
+        };
+
+        assert_eq!(
+            prefixed_terms("ONLINE"),
+            vec![
+                (String::from("ONLINE"), 3),
+                (String::from("ONLINE-ONLY"), 1),
+            ],
+        );
+
+        assert_eq!(prefixed_terms("ONLINE-"), vec![(String::from("ONLINE-ONLY"), 1)]);
+        assert_eq!(prefixed_terms("IN"), vec![(String::from("IN-PERSON"), 3)]);
+        assert_eq!(prefixed_terms("ONLINE-ONLY-"), vec![]);
+        assert_eq!(prefixed_terms("FOOBAR"), vec![]);
+
+        assert_eq!(
+            prefixed_terms(""),
+            vec![
+                (String::from("IN-PERSON"), 3),
+                (String::from("ONLINE"), 3),
+                (String::from("ONLINE-ONLY"), 1),
+            ],
+        );
+    }
+
     #[test]
     fn test_inverted_calendar_index_get_not_term() {
         let index = example_calendar_index();
@@ -1028,23 +1327,23 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("Always in person"),
+                        InternedString::from("Always in person"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("Mostly online"),
+                        InternedString::from("Mostly online"),
                         IndexedConclusion::Exclude(Some([100].into())),
                     ),
                     (
-                        String::from("Mostly in person"),
+                        InternedString::from("Mostly in person"),
                         IndexedConclusion::Include(Some([100].into())),
                     ),
                     (
-                        String::from("Not specified 1"),
+                        InternedString::from("Not specified 1"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("Not specified 2"),
+                        InternedString::from("Not specified 2"),
                         IndexedConclusion::Include(None),
                     ),
                 ])
@@ -1060,27 +1359,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("Always online"),
+                        InternedString::from("Always online"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("Always in person"),
+                        InternedString::from("Always in person"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("Mostly online"),
+                        InternedString::from("Mostly online"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("Mostly in person"),
+                        InternedString::from("Mostly in person"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("Not specified 1"),
+                        InternedString::from("Not specified 1"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("Not specified 2"),
+                        InternedString::from("Not specified 2"),
                         IndexedConclusion::Include(None),
                     ),
                 ])
@@ -1088,6 +1387,132 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_inverted_calendar_index_get_matching_term() {
+        let mut index = example_calendar_index();
+
+        index.insert("Online only", "Online", &IndexedConclusion::Include(None)).unwrap();
+        index.insert("Mostly in person", "online", &IndexedConclusion::Include(None)).unwrap();
+
+        assert_eq!(
+            index.normalized_terms.get("online"),
+            Some(&BTreeSet::from([String::from("ONLINE"), String::from("Online"), String::from("online")])),
+        );
+
+        // Matching exactly only returns the term event set with the exact same casing.
+        assert_eq!(
+            index.get_matching_term("Online", false),
+            Some(
+                Cow::Borrowed(
+                    &InvertedCalendarIndexTerm {
+                        events: HashMap::from([
+                            (InternedString::from("Online only"), IndexedConclusion::Include(None)),
+                        ])
+                    }
+                )
+            )
+        );
+
+        // Matching case-insensitively merges the term event sets of every casing (OR).
+        assert_eq!(
+            index.get_matching_term("oNlInE", true).map(Cow::into_owned),
+            Some(
+                InvertedCalendarIndexTerm {
+                    events: HashMap::from([
+                        (InternedString::from("Always online"), IndexedConclusion::Include(None)),
+                        (InternedString::from("Mostly online"), IndexedConclusion::Include(Some([100].into()))),
+                        (InternedString::from("Mostly in person"), IndexedConclusion::Include(None)),
+                        (InternedString::from("Online only"), IndexedConclusion::Include(None)),
+                    ])
+                }
+            )
+        );
+
+        assert_eq!(index.get_matching_term("oNlInE", false), None);
+        assert_eq!(index.get_matching_term("FOOBAR", true), None);
+
+        let calendar_event_uids = vec![
+            String::from("Always online"),
+            String::from("Always in person"),
+            String::from("Mostly online"),
+            String::from("Mostly in person"),
+            String::from("Online only"),
+        ];
+
+        assert_eq!(
+            index.get_not_matching_term("oNlInE", true, &calendar_event_uids),
+            InvertedCalendarIndexTerm {
+                events: HashMap::from([
+                    (InternedString::from("Always in person"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly online"), IndexedConclusion::Exclude(Some([100].into()))),
+                ])
+            }
+        );
+    }
+
+    #[test]
+    fn test_term_normalization() {
+        assert!(TermNormalization::default().is_exact());
+        assert_eq!(TermNormalization::default().normalize(" Café "), Cow::Borrowed(" Café "));
+
+        assert_eq!(TermNormalization::new(true, false, false).normalize(" Café "), Cow::Borrowed(" café "));
+        assert_eq!(TermNormalization::new(false, true, false).normalize(" Café "), Cow::Borrowed("Café"));
+        assert_eq!(TermNormalization::new(false, false, true).normalize(" Café "), Cow::Borrowed(" Cafe "));
+        assert_eq!(TermNormalization::new(true, true, true).normalize(" Crème Brûlée\t"), Cow::Borrowed("creme brulee"));
+
+        // Characters without diacritics are left as they are.
+        assert_eq!(TermNormalization::new(false, false, true).normalize("Straße ØRESUND"), Cow::Borrowed("Straße ØRESUND"));
+
+        assert_eq!(
+            TermNormalization::new(false, true, false).with_case_fold(true),
+            TermNormalization::new(true, true, false),
+        );
+    }
+
+    #[test]
+    fn test_inverted_calendar_index_get_normalized_matching_term() {
+        let mut index: InvertedCalendarIndex<String> =
+            InvertedCalendarIndex::new_with_normalization(TermNormalization::new(false, true, true));
+
+        index.insert("Event one", "Café", &IndexedConclusion::Include(None)).unwrap();
+        index.insert("Event two", " Cafe ", &IndexedConclusion::Include(Some([100].into()))).unwrap();
+        index.insert("Event three", "CAFE", &IndexedConclusion::Include(None)).unwrap();
+
+        assert_eq!(
+            index.normalized_terms.get("cafe"),
+            Some(&BTreeSet::from([String::from(" Cafe "), String::from("CAFE"), String::from("Café")])),
+        );
+
+        // Matching case-sensitively only merges the term event sets sharing the same casing once
+        // normalised.
+        assert_eq!(
+            index.get_matching_term("Cafe", false).map(Cow::into_owned),
+            Some(
+                InvertedCalendarIndexTerm {
+                    events: HashMap::from([
+                        (InternedString::from("Event one"), IndexedConclusion::Include(None)),
+                        (InternedString::from("Event two"), IndexedConclusion::Include(Some([100].into()))),
+                    ])
+                }
+            )
+        );
+
+        assert_eq!(
+            index.get_matching_term(" cafè", true).map(Cow::into_owned),
+            Some(
+                InvertedCalendarIndexTerm {
+                    events: HashMap::from([
+                        (InternedString::from("Event one"), IndexedConclusion::Include(None)),
+                        (InternedString::from("Event two"), IndexedConclusion::Include(Some([100].into()))),
+                        (InternedString::from("Event three"), IndexedConclusion::Include(None)),
+                    ])
+                }
+            )
+        );
+
+        assert_eq!(index.get_matching_term("Tea", true), None);
+    }
+
     #[test]
     fn test_inverted_index_term_merge_and() {
         assert_eq_sorted!(
@@ -1095,63 +1520,63 @@ mod test {
                 &InvertedCalendarIndexTerm {
                     events: HashMap::from([
                         (
-                            String::from("event_one"),
+                            InternedString::from("event_one"),
                             IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_two"),
+                            InternedString::from("event_two"),
                             IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_three"),
+                            InternedString::from("event_three"),
                             IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_four"),
+                            InternedString::from("event_four"),
                             IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_five"),
+                            InternedString::from("event_five"),
                             IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_six"),
+                            InternedString::from("event_six"),
                             IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_seven"),
+                            InternedString::from("event_seven"),
                             IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_eight"),
+                            InternedString::from("event_eight"),
                             IndexedConclusion::Exclude(None)
                         ),
-                        (String::from("event_nine"), IndexedConclusion::Exclude(None)),
+                        (InternedString::from("event_nine"), IndexedConclusion::Exclude(None)),
                     ])
                 },
                 &InvertedCalendarIndexTerm {
                     events: HashMap::from([
                         (
-                            String::from("event_one"),
+                            InternedString::from("event_one"),
                             IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_two"),
+                            InternedString::from("event_two"),
                             IndexedConclusion::Include(Some(HashSet::from([200, 300])))
                         ),
                         (
-                            String::from("event_three"),
+                            InternedString::from("event_three"),
                             IndexedConclusion::Exclude(Some(HashSet::from([200, 300])))
                         ),
-                        (String::from("event_four"), IndexedConclusion::Exclude(None)),
-                        (String::from("event_five"), IndexedConclusion::Include(None)),
-                        (String::from("event_six"), IndexedConclusion::Exclude(None)),
+                        (InternedString::from("event_four"), IndexedConclusion::Exclude(None)),
+                        (InternedString::from("event_five"), IndexedConclusion::Include(None)),
+                        (InternedString::from("event_six"), IndexedConclusion::Exclude(None)),
                         (
-                            String::from("event_seven"),
+                            InternedString::from("event_seven"),
                             IndexedConclusion::Include(None)
                         ),
                         (
-                            String::from("event_eight"),
+                            InternedString::from("event_eight"),
                             IndexedConclusion::Include(None)
                         ),
                     ]),
@@ -1159,27 +1584,27 @@ mod test {
             ),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("event_one"), IndexedConclusion::Exclude(None)),
+                    (InternedString::from("event_one"), IndexedConclusion::Exclude(None)),
                     (
-                        String::from("event_two"),
+                        InternedString::from("event_two"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200, 300])))
                     ),
                     (
-                        String::from("event_three"),
+                        InternedString::from("event_three"),
                         IndexedConclusion::Exclude(Some(HashSet::from([200])))
                     ),
-                    (String::from("event_four"), IndexedConclusion::Exclude(None)),
+                    (InternedString::from("event_four"), IndexedConclusion::Exclude(None)),
                     (
-                        String::from("event_five"),
+                        InternedString::from("event_five"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                     ),
-                    (String::from("event_six"), IndexedConclusion::Exclude(None)),
+                    (InternedString::from("event_six"), IndexedConclusion::Exclude(None)),
                     (
-                        String::from("event_seven"),
+                        InternedString::from("event_seven"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                     ),
                     (
-                        String::from("event_eight"),
+                        InternedString::from("event_eight"),
                         IndexedConclusion::Exclude(None)
                     ),
                 ]),
@@ -1194,67 +1619,67 @@ mod test {
                 &InvertedCalendarIndexTerm {
                     events: HashMap::from([
                         (
-                            String::from("event_one"),
+                            InternedString::from("event_one"),
                             IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_two"),
+                            InternedString::from("event_two"),
                             IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_three"),
+                            InternedString::from("event_three"),
                             IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_four"),
+                            InternedString::from("event_four"),
                             IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_five"),
+                            InternedString::from("event_five"),
                             IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_six"),
+                            InternedString::from("event_six"),
                             IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_seven"),
+                            InternedString::from("event_seven"),
                             IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_eight"),
+                            InternedString::from("event_eight"),
                             IndexedConclusion::Exclude(None)
                         ),
-                        (String::from("event_nine"), IndexedConclusion::Exclude(None)),
+                        (InternedString::from("event_nine"), IndexedConclusion::Exclude(None)),
                     ])
                 },
                 &InvertedCalendarIndexTerm {
                     events: HashMap::from([
                         (
-                            String::from("event_one"),
+                            InternedString::from("event_one"),
                             IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                         ),
                         (
-                            String::from("event_two"),
+                            InternedString::from("event_two"),
                             IndexedConclusion::Include(Some(HashSet::from([200, 300])))
                         ),
                         (
-                            String::from("event_three"),
+                            InternedString::from("event_three"),
                             IndexedConclusion::Exclude(Some(HashSet::from([200, 300])))
                         ),
-                        (String::from("event_four"), IndexedConclusion::Exclude(None)),
-                        (String::from("event_five"), IndexedConclusion::Include(None)),
-                        (String::from("event_six"), IndexedConclusion::Exclude(None)),
+                        (InternedString::from("event_four"), IndexedConclusion::Exclude(None)),
+                        (InternedString::from("event_five"), IndexedConclusion::Include(None)),
+                        (InternedString::from("event_six"), IndexedConclusion::Exclude(None)),
                         (
-                            String::from("event_seven"),
+                            InternedString::from("event_seven"),
                             IndexedConclusion::Include(None)
                         ),
                         (
-                            String::from("event_eight"),
+                            InternedString::from("event_eight"),
                             IndexedConclusion::Include(None)
                         ),
                         (
-                            String::from("event_ten"),
+                            InternedString::from("event_ten"),
                             IndexedConclusion::Exclude(Some(HashSet::from([200])))
                         ),
                     ]),
@@ -1262,34 +1687,34 @@ mod test {
             ),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("event_one"), IndexedConclusion::Include(None)),
+                    (InternedString::from("event_one"), IndexedConclusion::Include(None)),
                     (
-                        String::from("event_two"),
+                        InternedString::from("event_two"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 300])))
                     ),
                     (
-                        String::from("event_three"),
+                        InternedString::from("event_three"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200, 300])))
                     ),
                     (
-                        String::from("event_four"),
+                        InternedString::from("event_four"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200])))
                     ),
-                    (String::from("event_five"), IndexedConclusion::Include(None)),
+                    (InternedString::from("event_five"), IndexedConclusion::Include(None)),
                     (
-                        String::from("event_six"),
+                        InternedString::from("event_six"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200])))
                     ),
                     (
-                        String::from("event_seven"),
+                        InternedString::from("event_seven"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("event_eight"),
+                        InternedString::from("event_eight"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("event_ten"),
+                        InternedString::from("event_ten"),
                         IndexedConclusion::Exclude(Some(HashSet::from([200])))
                     ),
                 ]),
@@ -1300,10 +1725,10 @@ mod test {
     #[test]
     fn test_inverted_index_term_inverse() {
         let events = vec![
-            (String::from("Event one"), IndexedConclusion::Include(None)),
-            (String::from("Event two"), IndexedConclusion::Include(Some(HashSet::from([100])))),
-            (String::from("Event three"), IndexedConclusion::Exclude(None)),
-            (String::from("Event four"), IndexedConclusion::Exclude(Some(HashSet::from([100])))),
+            (InternedString::from("Event one"), IndexedConclusion::Include(None)),
+            (InternedString::from("Event two"), IndexedConclusion::Include(Some(HashSet::from([100])))),
+            (InternedString::from("Event three"), IndexedConclusion::Exclude(None)),
+            (InternedString::from("Event four"), IndexedConclusion::Exclude(Some(HashSet::from([100])))),
         ];
 
         let inverted_index_term = InvertedCalendarIndexTerm::new_with_events(events);
@@ -1313,10 +1738,10 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from(
                     [
-                        (String::from("Event one"), IndexedConclusion::Exclude(None)),
-                        (String::from("Event two"), IndexedConclusion::Exclude(Some(HashSet::from([100])))),
-                        (String::from("Event three"), IndexedConclusion::Include(None)),
-                        (String::from("Event four"), IndexedConclusion::Include(Some(HashSet::from([100])))),
+                        (InternedString::from("Event one"), IndexedConclusion::Exclude(None)),
+                        (InternedString::from("Event two"), IndexedConclusion::Exclude(Some(HashSet::from([100])))),
+                        (InternedString::from("Event three"), IndexedConclusion::Include(None)),
+                        (InternedString::from("Event four"), IndexedConclusion::Include(Some(HashSet::from([100])))),
                     ]
                 )
             }
@@ -0,0 +1,151 @@
+use std::collections::BTreeSet;
+
+use redical_ical::{ICalendarComponent, RenderingContext};
+use redical_ical::content_line::{ContentLine, ContentLineParams};
+
+// Escape a string to be rendered as a JSON string literal (RFC-8259 7).
+fn render_json_string(input: &str) -> String {
+    let mut output = String::with_capacity(input.len() + 2);
+
+    output.push('"');
+
+    for character in input.chars() {
+        match character {
+            '"'  => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+
+            character if character.is_control() => {
+                output.push_str(format!("\\u{:04x}", character as u32).as_str());
+            },
+
+            character => output.push(character),
+        }
+    }
+
+    output.push('"');
+
+    output
+}
+
+fn render_json_params(params: &ContentLineParams) -> String {
+    let rendered_params: Vec<String> =
+        params.0
+            .iter()
+            .map(|param| format!("{}:{}", render_json_string(&param.0), render_json_string(&param.1)))
+            .collect();
+
+    format!("{{{}}}", rendered_params.join(","))
+}
+
+// Properties without params are rendered as just their value, those with params are rendered as
+// an object with their params nested within it.
+fn render_json_property_value(content_line: &ContentLine) -> String {
+    if content_line.1.is_empty() {
+        return render_json_string(&content_line.2);
+    }
+
+    format!(
+        "{{\"params\":{},\"value\":{}}}",
+        render_json_params(&content_line.1),
+        render_json_string(&content_line.2),
+    )
+}
+
+/// Render an iCalendar component as a JSON object of each property name to its value.
+///
+/// Properties with params (e.g. `DTSTART;TZID=Europe/London:20210105T170000`) are rendered as an
+/// object with the params nested (e.g. `{"params":{"TZID":"Europe/London"},"value":"20210105T170000"}`)
+/// and properties occurring multiple times (e.g. `RELATED-TO`) are rendered as an array of each
+/// value.
+pub fn render_json_object<I: ICalendarComponent>(component: &I, rendering_context: Option<&RenderingContext>) -> String {
+    render_json_object_from_content_lines(component.to_content_line_set_with_context(rendering_context))
+}
+
+/// Render a set of content lines as a JSON object of each property name to its value (see
+/// `render_json_object`).
+pub fn render_json_object_from_content_lines(content_lines: BTreeSet<ContentLine>) -> String {
+    // Group the (already sorted) content lines by property name, retaining their order.
+    let mut grouped_content_lines: Vec<(String, Vec<ContentLine>)> = Vec::new();
+
+    for content_line in content_lines {
+        match grouped_content_lines.last_mut() {
+            Some((name, content_lines)) if name == &content_line.0 => {
+                content_lines.push(content_line);
+            },
+
+            _ => {
+                grouped_content_lines.push((content_line.0.to_owned(), vec![content_line]));
+            },
+        }
+    }
+
+    let rendered_properties: Vec<String> =
+        grouped_content_lines
+            .iter()
+            .map(|(name, content_lines)| {
+                let rendered_value =
+                    if let [content_line] = content_lines.as_slice() {
+                        render_json_property_value(content_line)
+                    } else {
+                        let rendered_values: Vec<String> =
+                            content_lines
+                                .iter()
+                                .map(render_json_property_value)
+                                .collect();
+
+                        format!("[{}]", rendered_values.join(","))
+                    };
+
+                format!("{}:{}", render_json_string(name), rendered_value)
+            })
+            .collect();
+
+    format!("{{{}}}", rendered_properties.join(","))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::Event;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn test_render_json_string() {
+        assert_eq!(render_json_string(""), String::from("\"\""));
+        assert_eq!(render_json_string("Event summary"), String::from("\"Event summary\""));
+        assert_eq!(
+            render_json_string("Quoted \"summary\" with \\ and\nnew line\u{1}"),
+            String::from("\"Quoted \\\"summary\\\" with \\\\ and\\nnew line\\u0001\""),
+        );
+    }
+
+    #[test]
+    fn test_render_json_object() {
+        let event =
+            Event::parse_ical(
+                "EVENT_UID",
+                "DTSTART;TZID=Europe/London:20210105T170000 LAST-MODIFIED:20210501T090000Z RELATED-TO;RELTYPE=PARENT:PARENT_UID RELATED-TO;RELTYPE=CHILD:CHILD_UID SUMMARY:Event \"summary\"",
+            ).unwrap();
+
+        assert_eq!(
+            render_json_object(&event, None),
+            [
+                "{",
+                "\"DTSTART\":{\"params\":{\"TZID\":\"Europe/London\"},\"value\":\"20210105T170000\"},",
+                "\"LAST-MODIFIED\":\"20210501T090000Z\",",
+                "\"RELATED-TO\":[",
+                "{\"params\":{\"RELTYPE\":\"CHILD\"},\"value\":\"CHILD_UID\"},",
+                "{\"params\":{\"RELTYPE\":\"PARENT\"},\"value\":\"PARENT_UID\"}",
+                "],",
+                "\"SUMMARY\":\"Event \\\"summary\\\"\",",
+                "\"UID\":\"EVENT_UID\"",
+                "}",
+            ].concat(),
+        );
+    }
+}
@@ -1,24 +1,40 @@
+mod builders;
 mod calendar;
 mod event;
 mod event_diff;
 mod event_instance;
 mod event_occurrence_iterator;
 mod event_occurrence_override;
+mod execution_deadline;
 mod geo_index;
+mod ics_document;
+mod index_verification;
+mod interning;
 mod inverted_index;
+mod json_object;
+mod serialization;
+mod time_bucket_index;
 mod utils;
 
 #[cfg(test)]
 mod testing;
 
+pub use builders::*;
 pub use calendar::*;
 pub use event::*;
 pub use event_diff::*;
 pub use event_instance::*;
 pub use event_occurrence_iterator::*;
 pub use event_occurrence_override::*;
+pub use execution_deadline::*;
 pub use geo_index::*;
+pub use ics_document::*;
+pub use index_verification::*;
+pub use interning::*;
 pub use inverted_index::*;
+pub use json_object::*;
+pub use time_bucket_index::*;
 pub use utils::*;
 
+pub mod clock;
 pub mod queries;
@@ -1,20 +1,28 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::str::FromStr;
+use std::time::Instant;
 
 use chrono_tz::Tz;
 
+use regex::Regex;
+
 use crate::{
-    Calendar, Event, EventInstance, EventInstanceIterator, IndexedConclusion,
+    Calendar, DstPolicy, Event, EventInstance, EventInstanceIterator, ExecutionDeadline, IndexedConclusion,
+    OccurrenceExpansionLimits,
     InvertedCalendarIndexTerm, LowerBoundFilterCondition, UpperBoundFilterCondition,
-    KeyValuePair, GeoDistance, GeoPoint,
+    DurationFilterCondition, KeyValuePair, GeoDistance, GeoPoint,
 };
 
 use crate::queries::indexed_property_filters::WhereConditional;
 use crate::queries::query::{Query, QueryIndexAccessor};
+use crate::queries::query_explain::{QueryExplanation, WhereConditionalExplanation};
+use crate::queries::query_profile::QueryProfiler;
 use crate::queries::query_parser::parse_query_string;
 use crate::queries::results::QueryResults;
-use crate::queries::results_ordering::OrderingCondition;
+use crate::queries::results_ordering::{OrderingCondition, QueryResultOrdering};
 use crate::queries::results_range_bounds::{
-    LowerBoundRangeCondition, UpperBoundRangeCondition,
+    DurationRangeCondition, LowerBoundRangeCondition, UpperBoundRangeCondition,
 };
 
 use redical_ical::properties::ICalendarDateTimeProperty;
@@ -30,6 +38,7 @@ use crate::MergedIterator;
 pub struct EventInstanceQueryIndexAccessor<'cal> {
     calendar: &'cal Calendar,
     event_uids: Vec<String>,
+    case_insensitive: bool,
 }
 
 impl<'cal> QueryIndexAccessor<'cal> for EventInstanceQueryIndexAccessor<'cal> {
@@ -39,31 +48,84 @@ impl<'cal> QueryIndexAccessor<'cal> for EventInstanceQueryIndexAccessor<'cal> {
         EventInstanceQueryIndexAccessor {
             calendar,
             event_uids,
+            case_insensitive: calendar.is_term_matching_case_insensitive(),
         }
     }
 
-    // For UID, we just return an "include all" consensus for that event UID.
+    fn is_term_matching_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    // For UID, we just return an "include all" consensus for each matching event UID within the
+    // calendar, otherwise an empty term (consistent with the other indexes).
     fn search_uid_index(&self, uid: &str) -> InvertedCalendarIndexTerm {
-        InvertedCalendarIndexTerm::new_with_event(
-            uid.to_owned(),
-            IndexedConclusion::Include(None),
+        InvertedCalendarIndexTerm::new_with_events(
+            self.calendar
+                .get_matching_event_uids(uid)
+                .into_iter()
+                .map(|event_uid| (event_uid.to_owned(), IndexedConclusion::Include(None)))
+                .collect()
+        )
+    }
+
+    // Matches any event UID starting with the prefix (range scanned over the ordered event UIDs).
+    fn search_uid_prefix_index(&self, uid_prefix: &str) -> InvertedCalendarIndexTerm {
+        InvertedCalendarIndexTerm::new_with_events(
+            self.calendar
+                .get_prefixed_event_uids(uid_prefix)
+                .into_iter()
+                .map(|event_uid| (event_uid.to_owned(), IndexedConclusion::Include(None)))
+                .collect()
+        )
+    }
+
+    // Matches any event UID matching the regular expression (tested against every event UID).
+    fn search_uid_regex_index(&self, uid_regex: &Regex) -> InvertedCalendarIndexTerm {
+        InvertedCalendarIndexTerm::new_with_events(
+            self.calendar
+                .get_regex_matching_event_uids(uid_regex)
+                .into_iter()
+                .map(|event_uid| (event_uid.to_owned(), IndexedConclusion::Include(None)))
+                .collect()
         )
     }
 
     fn search_location_type_index(&self, location_type: &str) -> InvertedCalendarIndexTerm {
         self.calendar
             .indexed_location_type
-            .get_term(&location_type.to_string())
-            .unwrap_or(&InvertedCalendarIndexTerm::new())
-            .to_owned()
+            .get_matching_term(location_type, self.case_insensitive)
+            .map(Cow::into_owned)
+            .unwrap_or_default()
     }
 
     fn search_categories_index(&self, category: &str) -> InvertedCalendarIndexTerm {
         self.calendar
             .indexed_categories
-            .get_term(&category.to_string())
-            .unwrap_or(&InvertedCalendarIndexTerm::new())
-            .to_owned()
+            .get_matching_term(category, self.case_insensitive)
+            .map(Cow::into_owned)
+            .unwrap_or_default()
+    }
+
+    // Matches any category term starting with the prefix (range scanned over the ordered index
+    // terms).
+    fn search_categories_prefix_index(&self, category_prefix: &str) -> InvertedCalendarIndexTerm {
+        self.calendar
+            .indexed_categories
+            .get_prefixed_terms(category_prefix)
+            .fold(InvertedCalendarIndexTerm::new(), |matches, (_category, inverted_calendar_index_term)| {
+                InvertedCalendarIndexTerm::merge_or(&matches, inverted_calendar_index_term)
+            })
+    }
+
+    // Matches any category term matching the regular expression (tested against every indexed
+    // term).
+    fn search_categories_regex_index(&self, category_regex: &Regex) -> InvertedCalendarIndexTerm {
+        self.calendar
+            .indexed_categories
+            .get_regex_matching_terms(category_regex)
+            .fold(InvertedCalendarIndexTerm::new(), |matches, (_category, inverted_calendar_index_term)| {
+                InvertedCalendarIndexTerm::merge_or(&matches, inverted_calendar_index_term)
+            })
     }
 
     fn search_related_to_index(&self, reltype_uids: &KeyValuePair) -> InvertedCalendarIndexTerm {
@@ -80,10 +142,42 @@ impl<'cal> QueryIndexAccessor<'cal> for EventInstanceQueryIndexAccessor<'cal> {
             .locate_within_distance(long_lat, distance)
     }
 
+    fn search_geo_index_within_candidates(&self, distance: &GeoDistance, long_lat: &GeoPoint, candidates: &InvertedCalendarIndexTerm) -> InvertedCalendarIndexTerm {
+        self.calendar
+            .locate_candidates_within_distance(long_lat, distance, candidates)
+    }
+
     fn search_class_index(&self, class: &str) -> InvertedCalendarIndexTerm {
         self.calendar
             .indexed_class
-            .get_term(&class.to_string())
+            .get_matching_term(class, self.case_insensitive)
+            .map(Cow::into_owned)
+            .unwrap_or_default()
+    }
+
+    // Matches any class term matching the regular expression (tested against every indexed
+    // term).
+    fn search_class_regex_index(&self, class_regex: &Regex) -> InvertedCalendarIndexTerm {
+        self.calendar
+            .indexed_class
+            .get_regex_matching_terms(class_regex)
+            .fold(InvertedCalendarIndexTerm::new(), |matches, (_class, inverted_calendar_index_term)| {
+                InvertedCalendarIndexTerm::merge_or(&matches, inverted_calendar_index_term)
+            })
+    }
+
+    fn search_location_index(&self, location: &str) -> InvertedCalendarIndexTerm {
+        self.calendar
+            .indexed_location
+            .get_matching_term(location, self.case_insensitive)
+            .map(Cow::into_owned)
+            .unwrap_or_default()
+    }
+
+    fn search_x_property_index(&self, name_value: &KeyValuePair) -> InvertedCalendarIndexTerm {
+        self.calendar
+            .indexed_x_properties
+            .get_term(name_value)
             .unwrap_or(&InvertedCalendarIndexTerm::new())
             .to_owned()
     }
@@ -91,9 +185,43 @@ impl<'cal> QueryIndexAccessor<'cal> for EventInstanceQueryIndexAccessor<'cal> {
     fn search_not_uid_index(&self, uid: &str) -> InvertedCalendarIndexTerm {
         let mut inverse_matches = InvertedCalendarIndexTerm::new();
 
+        let matching_event_uids = self.calendar.get_matching_event_uids(uid);
+
+        let included_event_uids_iter = self.event_uids
+            .iter()
+            .filter(|event_uid| !matching_event_uids.contains(event_uid));
+
+        for event_uid in included_event_uids_iter {
+            inverse_matches.insert_included_event(event_uid.to_owned(), None);
+        }
+
+        inverse_matches
+    }
+
+    fn search_not_uid_prefix_index(&self, uid_prefix: &str) -> InvertedCalendarIndexTerm {
+        let mut inverse_matches = InvertedCalendarIndexTerm::new();
+
+        let matching_event_uids = self.calendar.get_prefixed_event_uids(uid_prefix);
+
         let included_event_uids_iter = self.event_uids
             .iter()
-            .filter(|event_uid| *event_uid != uid);
+            .filter(|event_uid| !matching_event_uids.contains(event_uid));
+
+        for event_uid in included_event_uids_iter {
+            inverse_matches.insert_included_event(event_uid.to_owned(), None);
+        }
+
+        inverse_matches
+    }
+
+    fn search_not_uid_regex_index(&self, uid_regex: &Regex) -> InvertedCalendarIndexTerm {
+        let mut inverse_matches = InvertedCalendarIndexTerm::new();
+
+        let matching_event_uids = self.calendar.get_regex_matching_event_uids(uid_regex);
+
+        let included_event_uids_iter = self.event_uids
+            .iter()
+            .filter(|event_uid| !matching_event_uids.contains(event_uid));
 
         for event_uid in included_event_uids_iter {
             inverse_matches.insert_included_event(event_uid.to_owned(), None);
@@ -103,15 +231,17 @@ impl<'cal> QueryIndexAccessor<'cal> for EventInstanceQueryIndexAccessor<'cal> {
     }
 
     fn search_not_location_type_index(&self, location_type: &str) -> InvertedCalendarIndexTerm {
-        self.calendar.indexed_location_type.get_not_term(
-            &location_type.to_string(),
+        self.calendar.indexed_location_type.get_not_matching_term(
+            location_type,
+            self.case_insensitive,
             &self.event_uids,
         )
     }
 
     fn search_not_categories_index(&self, category: &str) -> InvertedCalendarIndexTerm {
-        self.calendar.indexed_categories.get_not_term(
-            &category.to_string(),
+        self.calendar.indexed_categories.get_not_matching_term(
+            category,
+            self.case_insensitive,
             &self.event_uids,
         )
     }
@@ -132,8 +262,24 @@ impl<'cal> QueryIndexAccessor<'cal> for EventInstanceQueryIndexAccessor<'cal> {
     }
 
     fn search_not_class_index(&self, class: &str) -> InvertedCalendarIndexTerm {
-        self.calendar.indexed_class.get_not_term(
-            &class.to_string(),
+        self.calendar.indexed_class.get_not_matching_term(
+            class,
+            self.case_insensitive,
+            &self.event_uids,
+        )
+    }
+
+    fn search_not_location_index(&self, location: &str) -> InvertedCalendarIndexTerm {
+        self.calendar.indexed_location.get_not_matching_term(
+            location,
+            self.case_insensitive,
+            &self.event_uids,
+        )
+    }
+
+    fn search_not_x_property_index(&self, name_value: &KeyValuePair) -> InvertedCalendarIndexTerm {
+        self.calendar.indexed_x_properties.get_not_term(
+            name_value,
             &self.event_uids,
         )
     }
@@ -148,10 +294,18 @@ pub struct EventInstanceQuery {
     pub ordering_condition: OrderingCondition,
     pub lower_bound_range_condition: Option<LowerBoundRangeCondition>,
     pub upper_bound_range_condition: Option<UpperBoundRangeCondition>,
+    pub duration_range_conditions: Vec<DurationRangeCondition>,
     pub in_timezone: Tz,
     pub distinct_uids: bool,
+    pub group_by_uid: bool,
     pub offset: usize,
     pub limit: usize,
+    pub limit_per_event: Option<usize>,
+    pub dst_policy: Option<DstPolicy>,
+    pub return_properties: Option<Vec<String>>,
+    pub count_only: bool,
+    pub merge_intervals: bool,
+    pub warnings: Vec<String>,
 }
 
 impl FromStr for EventInstanceQuery {
@@ -165,19 +319,231 @@ impl FromStr for EventInstanceQuery {
 }
 
 impl Query<EventInstance> for EventInstanceQuery {
-    fn execute(&mut self, calendar: &Calendar) -> Result<QueryResults<EventInstance>, String> {
+    fn execute_with_deadline(&mut self, calendar: &Calendar, execution_deadline: &ExecutionDeadline) -> Result<QueryResults<EventInstance>, String> {
+        self.execute_with_occurrence_expansion_limits(
+            calendar,
+            execution_deadline,
+            &OccurrenceExpansionLimits::default(),
+        )
+    }
+
+    fn set_where_conditional(&mut self, where_conditional: Option<WhereConditional>) {
+        self.where_conditional = where_conditional;
+    }
+
+    fn get_where_conditional(&self) -> &Option<WhereConditional> {
+        &self.where_conditional
+    }
+
+    fn set_ordering_condition(&mut self, ordering_condition: OrderingCondition) {
+        self.ordering_condition = ordering_condition;
+    }
+
+    fn set_lower_bound_range_condition(&mut self, lower_bound_range_condition: Option<LowerBoundRangeCondition>) {
+        self.lower_bound_range_condition = lower_bound_range_condition;
+    }
+
+    fn set_upper_bound_range_condition(&mut self, upper_bound_range_condition: Option<UpperBoundRangeCondition>) {
+        self.upper_bound_range_condition = upper_bound_range_condition;
+    }
+
+    fn insert_duration_range_condition(&mut self, duration_range_condition: DurationRangeCondition) {
+        self.duration_range_conditions.push(duration_range_condition);
+    }
+
+    fn set_in_timezone(&mut self, in_timezone: Tz) {
+        self.in_timezone = in_timezone;
+    }
+
+    fn set_distinct_uids(&mut self, distinct_uids: bool) {
+        self.distinct_uids = distinct_uids;
+    }
+
+    fn set_group_by_uid(&mut self, group_by_uid: bool) {
+        self.group_by_uid = group_by_uid;
+    }
+
+    fn set_offset(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+
+    fn get_offset(&self) -> usize {
+        self.offset
+    }
+
+    fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+
+    fn get_limit(&self) -> usize {
+        self.limit
+    }
+
+    fn set_limit_per_event(&mut self, limit_per_event: Option<usize>) {
+        self.limit_per_event = limit_per_event;
+    }
+
+    fn set_dst_policy(&mut self, dst_policy: Option<DstPolicy>) {
+        self.dst_policy = dst_policy;
+    }
+
+    fn set_return_properties(&mut self, return_properties: Option<Vec<String>>) {
+        self.return_properties = return_properties;
+    }
+
+    fn get_return_properties(&self) -> &Option<Vec<String>> {
+        &self.return_properties
+    }
+
+    fn set_count_only(&mut self, count_only: bool) {
+        self.count_only = count_only;
+    }
+
+    fn set_merge_intervals(&mut self, merge_intervals: bool) {
+        self.merge_intervals = merge_intervals;
+    }
+
+    fn set_warnings(&mut self, warnings: Vec<String>) {
+        self.warnings = warnings;
+    }
+
+    fn get_warnings(&self) -> &Vec<String> {
+        &self.warnings
+    }
+}
+
+impl EventInstanceQuery {
+    /// Executes the query with the expansion of each event's occurrences bounded by the provided
+    /// limits, `OccurrenceExpansionLimits::is_reached` then determines if the results are partial.
+    pub fn execute_with_occurrence_expansion_limits(
+        &mut self,
+        calendar: &Calendar,
+        execution_deadline: &ExecutionDeadline,
+        occurrence_expansion_limits: &OccurrenceExpansionLimits,
+    ) -> Result<QueryResults<EventInstance>, String> {
+        self.execute_with_optional_query_profiler(calendar, execution_deadline, occurrence_expansion_limits, None)
+    }
+
+    /// Executes the query (as with `execute_with_occurrence_expansion_limits`) while recording
+    /// the time spent within each phase of the execution, and the expansion of each event, against
+    /// the provided query profiler.
+    pub fn execute_with_query_profiler(
+        &mut self,
+        calendar: &Calendar,
+        execution_deadline: &ExecutionDeadline,
+        occurrence_expansion_limits: &OccurrenceExpansionLimits,
+        query_profiler: &QueryProfiler,
+    ) -> Result<QueryResults<EventInstance>, String> {
+        let started_at = Instant::now();
+
+        let query_results = self.execute_with_optional_query_profiler(
+            calendar,
+            execution_deadline,
+            occurrence_expansion_limits,
+            Some(query_profiler),
+        );
+
+        query_profiler.record_execution(started_at.elapsed());
+
+        query_results
+    }
+
+    /// Counts the event instances matching the query (disregarding the offset and limit) up to
+    /// one beyond the provided max count (as with `Query::count_with_deadline`), without
+    /// collecting, ordering, or rendering them.
+    pub fn count_with_occurrence_expansion_limits(
+        &self,
+        calendar: &Calendar,
+        max_count: Option<usize>,
+        execution_deadline: &ExecutionDeadline,
+        occurrence_expansion_limits: &OccurrenceExpansionLimits,
+    ) -> Result<usize, String> {
+        // Only the event instances of events with a geographical location are included when
+        // ordered by geographical distance first, so these are counted by executing the query
+        // instead.
+        if matches!(self.ordering_condition, OrderingCondition::GeoDistDtStart(_)) {
+            let query_results =
+                self.build_count_query(max_count)
+                    .execute_with_occurrence_expansion_limits(calendar, execution_deadline, occurrence_expansion_limits)?;
+
+            return Ok(query_results.len());
+        }
+
+        let mut count_query = self.clone();
+
+        // Queries not specifying a DST policy fall back to the Calendar default (if declared).
+        count_query.dst_policy = count_query.dst_policy.or_else(|| calendar.get_default_dst_policy());
+
         let query_index_accessor = EventInstanceQueryIndexAccessor::new(calendar);
 
+        let where_conditional_result = if let Some(where_conditional) = &mut count_query.where_conditional
+        {
+            Some(where_conditional.execute(&query_index_accessor, execution_deadline)?)
+        } else {
+            None
+        };
+
+        let mut merged_iterator: MergedIterator<EventInstance, EventInstanceIterator> =
+            MergedIterator::new();
+
+        count_query.populate_merged_iterator_for_dtstart_ordering(
+            calendar,
+            &mut merged_iterator,
+            &where_conditional_result,
+            execution_deadline,
+            occurrence_expansion_limits,
+            None,
+        )?;
+
+        let count =
+            merged_iterator
+                .take(max_count.map_or(usize::MAX, |max_count| max_count.saturating_add(1)))
+                .count();
+
+        // The event instance iterators stop expanding occurrences once the execution deadline is
+        // exceeded, so check it here to avoid returning a truncated count.
+        execution_deadline.check()?;
+
+        Ok(count)
+    }
+
+    fn execute_with_optional_query_profiler(
+        &mut self,
+        calendar: &Calendar,
+        execution_deadline: &ExecutionDeadline,
+        occurrence_expansion_limits: &OccurrenceExpansionLimits,
+        query_profiler: Option<&QueryProfiler>,
+    ) -> Result<QueryResults<EventInstance>, String> {
+        // Queries not specifying a DST policy fall back to the Calendar default (if declared).
+        self.dst_policy = self.dst_policy.or_else(|| calendar.get_default_dst_policy());
+
+        let query_index_accessor = EventInstanceQueryIndexAccessor::new(calendar);
+
+        let started_at = Instant::now();
+
         let where_conditional_result = if let Some(where_conditional) = &mut self.where_conditional
         {
-            Some(where_conditional.execute(&query_index_accessor)?)
+            Some(where_conditional.execute(&query_index_accessor, execution_deadline)?)
         } else {
             None
         };
 
+        if let Some(query_profiler) = query_profiler {
+            query_profiler.record_index_filtering(started_at.elapsed());
+        }
+
+        // Results ordered by relevance score (or by geographical distance within a shared
+        // dtstart) can only be offset once they have been collected and sorted, so the offset is
+        // applied after execution instead.
+        let offset = if matches!(self.ordering_condition, OrderingCondition::Score(_) | OrderingCondition::DtStartGeoDist(_)) {
+            0
+        } else {
+            self.offset
+        };
+
         let mut query_results = QueryResults::new(
-            self.ordering_condition.clone(),
-            self.offset,
+            self.ordering_condition.bind_where_conditional(&self.where_conditional),
+            offset,
             self.distinct_uids,
         );
 
@@ -187,6 +553,9 @@ impl Query<EventInstance> for EventInstanceQuery {
                     calendar,
                     &mut query_results,
                     &where_conditional_result,
+                    execution_deadline,
+                    occurrence_expansion_limits,
+                    query_profiler,
                 )?;
             }
 
@@ -195,6 +564,9 @@ impl Query<EventInstance> for EventInstanceQuery {
                     calendar,
                     &mut query_results,
                     &where_conditional_result,
+                    execution_deadline,
+                    occurrence_expansion_limits,
+                    query_profiler,
                 )?;
             }
 
@@ -204,6 +576,20 @@ impl Query<EventInstance> for EventInstanceQuery {
                     calendar,
                     &mut query_results,
                     &where_conditional_result,
+                    execution_deadline,
+                    occurrence_expansion_limits,
+                    query_profiler,
+                )?;
+            }
+
+            OrderingCondition::Score(_) => {
+                self.execute_for_score_ordering(
+                    calendar,
+                    &mut query_results,
+                    &where_conditional_result,
+                    execution_deadline,
+                    occurrence_expansion_limits,
+                    query_profiler,
                 )?;
             }
         }
@@ -211,44 +597,35 @@ impl Query<EventInstance> for EventInstanceQuery {
         Ok(query_results)
     }
 
-    fn set_where_conditional(&mut self, where_conditional: Option<WhereConditional>) {
-        self.where_conditional = where_conditional;
-    }
-
-    fn get_where_conditional(&self) -> &Option<WhereConditional> {
-        &self.where_conditional
-    }
-
-    fn set_ordering_condition(&mut self, ordering_condition: OrderingCondition) {
-        self.ordering_condition = ordering_condition;
-    }
-
-    fn set_lower_bound_range_condition(&mut self, lower_bound_range_condition: Option<LowerBoundRangeCondition>) {
-        self.lower_bound_range_condition = lower_bound_range_condition;
-    }
-
-    fn set_upper_bound_range_condition(&mut self, upper_bound_range_condition: Option<UpperBoundRangeCondition>) {
-        self.upper_bound_range_condition = upper_bound_range_condition;
-    }
-
-    fn set_in_timezone(&mut self, in_timezone: Tz) {
-        self.in_timezone = in_timezone;
-    }
+    /// Plans the query against the calendar without expanding or ordering any event instances,
+    /// describing the resolved where conditional tree, the candidate events, and how the results
+    /// would be ordered and offset.
+    pub fn explain(&self, calendar: &Calendar, execution_deadline: &ExecutionDeadline) -> Result<QueryExplanation, String> {
+        let query_index_accessor = EventInstanceQueryIndexAccessor::new(calendar);
 
-    fn set_distinct_uids(&mut self, distinct_uids: bool) {
-        self.distinct_uids = distinct_uids;
-    }
+        let where_conditional = if let Some(where_conditional) = &self.where_conditional {
+            Some(WhereConditionalExplanation::build(where_conditional, &query_index_accessor, execution_deadline)?)
+        } else {
+            None
+        };
 
-    fn set_offset(&mut self, offset: usize) {
-        self.offset = offset;
-    }
+        let time_bucketed_event_uids = self.get_time_bucketed_event_uids(
+            calendar,
+            &self.get_lower_bound_filter_condition(),
+            &self.get_upper_bound_filter_condition(),
+        );
 
-    fn set_limit(&mut self, limit: usize) {
-        self.limit = limit;
+        Ok(QueryExplanation::new(
+            where_conditional,
+            calendar.events.keys().collect(),
+            time_bucketed_event_uids,
+            &self.ordering_condition,
+            self.offset,
+            self.limit,
+            self.distinct_uids,
+        ))
     }
-}
 
-impl EventInstanceQuery {
     fn get_lower_bound_filter_condition(&self) -> Option<LowerBoundFilterCondition> {
         self.lower_bound_range_condition
             .to_owned()
@@ -261,19 +638,72 @@ impl EventInstanceQuery {
             .map(UpperBoundFilterCondition::from)
     }
 
+    fn get_duration_filter_conditions(&self) -> Vec<DurationFilterCondition> {
+        self.duration_range_conditions
+            .iter()
+            .cloned()
+            .map(DurationFilterCondition::from)
+            .collect()
+    }
+
+    // Pre-filter the events to only those with occurrences potentially within the queried time
+    // window (if specified) using the calendar time bucket index, to avoid needlessly expanding
+    // those that fall entirely outside of it.
+    fn get_time_bucketed_event_uids(
+        &self,
+        calendar: &Calendar,
+        lower_bound_filter_condition: &Option<LowerBoundFilterCondition>,
+        upper_bound_filter_condition: &Option<UpperBoundFilterCondition>,
+    ) -> Option<HashSet<String>> {
+        if !calendar.indexes_active {
+            return None;
+        }
+
+        calendar
+            .indexed_time_buckets
+            .get_event_uids_within(lower_bound_filter_condition, upper_bound_filter_condition)
+    }
+
+    fn is_excluded_by_time_buckets(time_bucketed_event_uids: &Option<HashSet<String>>, event_uid: &str) -> bool {
+        time_bucketed_event_uids
+            .as_ref()
+            .is_some_and(|time_bucketed_event_uids| !time_bucketed_event_uids.contains(event_uid))
+    }
+
     fn populate_merged_iterator_for_dtstart_ordering<'iter, 'cal: 'iter>(
         &self,
         calendar: &'cal Calendar,
         merged_iterator: &'iter mut MergedIterator<EventInstance, EventInstanceIterator<'cal>>,
         where_conditional_result: &Option<InvertedCalendarIndexTerm>,
+        execution_deadline: &ExecutionDeadline,
+        occurrence_expansion_limits: &OccurrenceExpansionLimits,
+        query_profiler: Option<&QueryProfiler>,
     ) -> Result<(), String> {
         let lower_bound_filter_condition = self.get_lower_bound_filter_condition();
         let upper_bound_filter_condition = self.get_upper_bound_filter_condition();
 
+        let started_at = Instant::now();
+
+        let time_bucketed_event_uids = self.get_time_bucketed_event_uids(
+            calendar,
+            &lower_bound_filter_condition,
+            &upper_bound_filter_condition,
+        );
+
+        if let Some(query_profiler) = query_profiler {
+            query_profiler.record_index_filtering(started_at.elapsed());
+        }
+
         match where_conditional_result {
             Some(inverted_calendar_index_term) => {
                 for (event_uid, indexed_conclusion) in &inverted_calendar_index_term.events {
-                    let Some(event) = calendar.events.get(event_uid) else {
+                    execution_deadline.check()?;
+
+                    if Self::is_excluded_by_time_buckets(&time_bucketed_event_uids, event_uid) {
+                        continue;
+                    }
+
+                    let Some(event) = calendar.events.get(event_uid.as_str()) else {
                         // TODO: handle missing indexed event...
 
                         continue;
@@ -285,18 +715,30 @@ impl EventInstanceQuery {
                         &lower_bound_filter_condition,
                         &upper_bound_filter_condition,
                         &Some(indexed_conclusion.clone()),
+                        execution_deadline,
+                        occurrence_expansion_limits,
+                        query_profiler,
                     )?;
                 }
             }
 
             None => {
-                for event in calendar.events.values() {
+                for (event_uid, event) in &calendar.events {
+                    execution_deadline.check()?;
+
+                    if Self::is_excluded_by_time_buckets(&time_bucketed_event_uids, event_uid) {
+                        continue;
+                    }
+
                     self.add_event_to_merged_iterator(
                         event,
                         merged_iterator,
                         &lower_bound_filter_condition,
                         &upper_bound_filter_condition,
                         &None,
+                        execution_deadline,
+                        occurrence_expansion_limits,
+                        query_profiler,
                     )?;
                 }
             }
@@ -310,6 +752,9 @@ impl EventInstanceQuery {
         calendar: &Calendar,
         query_results: &mut QueryResults<EventInstance>,
         where_conditional_result: &Option<InvertedCalendarIndexTerm>,
+        execution_deadline: &ExecutionDeadline,
+        occurrence_expansion_limits: &OccurrenceExpansionLimits,
+        query_profiler: Option<&QueryProfiler>,
     ) -> Result<(), String> {
         let mut merged_iterator: MergedIterator<EventInstance, EventInstanceIterator> =
             MergedIterator::new();
@@ -318,6 +763,9 @@ impl EventInstanceQuery {
             calendar,
             &mut merged_iterator,
             where_conditional_result,
+            execution_deadline,
+            occurrence_expansion_limits,
+            query_profiler,
         )?;
 
         for (_, event_instance) in merged_iterator {
@@ -328,6 +776,10 @@ impl EventInstanceQuery {
             query_results.push(event_instance);
         }
 
+        // The event instance iterators stop expanding occurrences once the execution deadline is
+        // exceeded, so check it here to avoid returning truncated results.
+        execution_deadline.check()?;
+
         Ok(())
     }
 
@@ -336,6 +788,9 @@ impl EventInstanceQuery {
         calendar: &Calendar,
         query_results: &mut QueryResults<EventInstance>,
         where_conditional_result: &Option<InvertedCalendarIndexTerm>,
+        execution_deadline: &ExecutionDeadline,
+        occurrence_expansion_limits: &OccurrenceExpansionLimits,
+        query_profiler: Option<&QueryProfiler>,
     ) -> Result<(), String> {
         let mut merged_iterator: MergedIterator<EventInstance, EventInstanceIterator> =
             MergedIterator::new();
@@ -344,6 +799,9 @@ impl EventInstanceQuery {
             calendar,
             &mut merged_iterator,
             where_conditional_result,
+            execution_deadline,
+            occurrence_expansion_limits,
+            query_profiler,
         )?;
 
         // This is functionally similar to the DtStart ordering, except we need to include all the
@@ -355,7 +813,10 @@ impl EventInstanceQuery {
         // than those pulled in earlier.
         //
         // We can enforce the result limit after this has finished, as the result set will sort
-        // itself.
+        // itself. The offset is likewise only applied once sorted, so that a group straddling a
+        // page boundary is split consistently between the pages either side of it.
+        let result_limit = self.offset + self.limit;
+
         let mut previous_dtstart_timestamp = None;
 
         for (_, event_instance) in merged_iterator {
@@ -364,7 +825,7 @@ impl EventInstanceQuery {
                     dtstart_timestamp != event_instance.dtstart.get_utc_timestamp()
                 });
 
-            if is_unique_dtstart_timestamp && query_results.len() >= self.limit {
+            if is_unique_dtstart_timestamp && query_results.len() >= result_limit {
                 break;
             }
 
@@ -373,39 +834,63 @@ impl EventInstanceQuery {
             query_results.push(event_instance);
         }
 
-        query_results.truncate(self.limit);
+        execution_deadline.check()?;
+
+        query_results.truncate(result_limit);
+        query_results.skip(self.offset);
 
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn execute_for_geo_dist_dtstart_ordering(
         &self,
         geo_point: &GeoPoint,
         calendar: &Calendar,
         query_results: &mut QueryResults<EventInstance>,
         where_conditional_result: &Option<InvertedCalendarIndexTerm>,
+        execution_deadline: &ExecutionDeadline,
+        occurrence_expansion_limits: &OccurrenceExpansionLimits,
+        query_profiler: Option<&QueryProfiler>,
     ) -> Result<(), String> {
         let lower_bound_filter_condition = self.get_lower_bound_filter_condition();
         let upper_bound_filter_condition = self.get_upper_bound_filter_condition();
 
-        for (point, _distance) in calendar
+        let started_at = Instant::now();
+
+        let time_bucketed_event_uids = self.get_time_bucketed_event_uids(
+            calendar,
+            &lower_bound_filter_condition,
+            &upper_bound_filter_condition,
+        );
+
+        if let Some(query_profiler) = query_profiler {
+            query_profiler.record_index_filtering(started_at.elapsed());
+        }
+
+        for (_point, indexed_term, _distance) in calendar
             .indexed_geo
-            .coords
-            .nearest_neighbor_iter_with_distance_2(&geo_point.to_point())
+            .nearest_neighbor_iter(geo_point)
         {
+            execution_deadline.check()?;
+
             let mut merged_iterator: MergedIterator<EventInstance, EventInstanceIterator> =
                 MergedIterator::new();
 
             let current_inverted_index_calendar_term = match where_conditional_result {
                 Some(inverted_calendar_index_term) => {
-                    InvertedCalendarIndexTerm::merge_and(&point.data, inverted_calendar_index_term)
+                    InvertedCalendarIndexTerm::merge_and(indexed_term, inverted_calendar_index_term)
                 }
 
-                None => point.data.to_owned(),
+                None => indexed_term.to_owned(),
             };
 
             for (event_uid, indexed_conclusion) in &current_inverted_index_calendar_term.events {
-                let Some(event) = calendar.events.get(event_uid) else {
+                if Self::is_excluded_by_time_buckets(&time_bucketed_event_uids, event_uid) {
+                    continue;
+                }
+
+                let Some(event) = calendar.events.get(event_uid.as_str()) else {
                     // TODO: handle missing indexed event...
 
                     continue;
@@ -417,6 +902,9 @@ impl EventInstanceQuery {
                     &lower_bound_filter_condition,
                     &upper_bound_filter_condition,
                     &Some(indexed_conclusion.clone()),
+                    execution_deadline,
+                    occurrence_expansion_limits,
+                    query_profiler,
                 )?;
             }
 
@@ -429,11 +917,76 @@ impl EventInstanceQuery {
                 //       wastefully re-calculating it.
                 query_results.push(event_instance);
             }
+
+            execution_deadline.check()?;
+        }
+
+        Ok(())
+    }
+
+    fn execute_for_score_ordering(
+        &self,
+        calendar: &Calendar,
+        query_results: &mut QueryResults<EventInstance>,
+        where_conditional_result: &Option<InvertedCalendarIndexTerm>,
+        execution_deadline: &ExecutionDeadline,
+        occurrence_expansion_limits: &OccurrenceExpansionLimits,
+        query_profiler: Option<&QueryProfiler>,
+    ) -> Result<(), String> {
+        // Every EventInstance has to be scored (see below), which is unbounded for recurring
+        // events without an upper bound to stop expanding their occurrences at.
+        if self.upper_bound_range_condition.is_none() {
+            return Err(String::from("X-ORDER-BY:SCORE requires an X-UNTIL upper bound"));
+        }
+
+        let mut merged_iterator: MergedIterator<EventInstance, EventInstanceIterator> =
+            MergedIterator::new();
+
+        self.populate_merged_iterator_for_dtstart_ordering(
+            calendar,
+            &mut merged_iterator,
+            where_conditional_result,
+            execution_deadline,
+            occurrence_expansion_limits,
+            query_profiler,
+        )?;
+
+        // Every EventInstance could potentially score higher than those already collected, so
+        // unlike the DtStart ordering we cannot stop as soon as the limit is reached. We can
+        // however stop once all the collected results have the highest achievable score, as any
+        // subsequent EventInstances (in DtStart order) would then be ordered after them anyway.
+        let max_score = self.where_conditional
+            .as_ref()
+            .map(|where_conditional| where_conditional.max_score())
+            .unwrap_or_default();
+
+        let result_limit = self.offset + self.limit;
+
+        for (_, event_instance) in merged_iterator {
+            query_results.push(event_instance);
+            query_results.truncate(result_limit);
+
+            let has_reached_max_score =
+                query_results.results.last().is_some_and(|query_result| {
+                    matches!(
+                        query_result.result_ordering,
+                        QueryResultOrdering::Score(score, _) if score >= max_score
+                    )
+                });
+
+            if query_results.len() >= result_limit && has_reached_max_score {
+                break;
+            }
         }
 
+        execution_deadline.check()?;
+
+        query_results.skip(self.offset);
+
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn add_event_to_merged_iterator<'iter, 'evt: 'iter>(
         &self,
         event: &'evt Event,
@@ -441,8 +994,13 @@ impl EventInstanceQuery {
         lower_bound_filter_condition: &Option<LowerBoundFilterCondition>,
         upper_bound_filter_condition: &Option<UpperBoundFilterCondition>,
         filtering_indexed_conclusion: &Option<IndexedConclusion>,
+        execution_deadline: &ExecutionDeadline,
+        occurrence_expansion_limits: &OccurrenceExpansionLimits,
+        query_profiler: Option<&QueryProfiler>,
     ) -> Result<(), String> {
-        let limit = if self.distinct_uids { Some(1) } else { None };
+        // Each event contributes at most one instance if only distinct UIDs are to be returned,
+        // otherwise at most the limit per event (if any).
+        let limit = if self.distinct_uids { Some(1) } else { self.limit_per_event };
 
         let event_uid: String = event.uid.uid.to_string();
 
@@ -452,7 +1010,16 @@ impl EventInstanceQuery {
             lower_bound_filter_condition.clone(),
             upper_bound_filter_condition.clone(),
             filtering_indexed_conclusion.clone(),
-        )?;
+        )?
+        .with_duration_filter_conditions(self.get_duration_filter_conditions())
+        .with_execution_deadline(*execution_deadline)
+        .with_occurrence_expansion_limits(occurrence_expansion_limits.clone())
+        .with_dst_policy(self.dst_policy.unwrap_or_default());
+
+        let event_instance_iterator = match query_profiler {
+            Some(query_profiler) => event_instance_iterator.with_query_profiler(query_profiler.clone()),
+            None => event_instance_iterator,
+        };
 
         if let Err(error) = merged_iterator.add_iter(event_uid, event_instance_iterator) {
             Err(error)
@@ -469,10 +1036,18 @@ impl Default for EventInstanceQuery {
             ordering_condition: OrderingCondition::DtStart,
             lower_bound_range_condition: None,
             upper_bound_range_condition: None,
+            duration_range_conditions: Vec::new(),
             in_timezone: Tz::UTC,
             distinct_uids: false,
+            group_by_uid: false,
             offset: 0,
             limit: 50,
+            limit_per_event: None,
+            dst_policy: None,
+            return_properties: None,
+            count_only: false,
+            merge_intervals: false,
+            warnings: Vec::new(),
         }
     }
 }
@@ -489,11 +1064,17 @@ mod test {
         LowerBoundRangeCondition, RangeConditionProperty, UpperBoundRangeCondition,
     };
 
-    use crate::{GeoPoint, KeyValuePair};
+    use crate::queries::results::QueryableEntity;
+
+    use crate::testing::utils::build_event_from_ical;
+
+    use crate::{EventOccurrenceOverride, GeoPoint, InternedString, KeyValuePair};
     use pretty_assertions_sorted::{assert_eq, assert_eq_sorted};
 
     use std::collections::{HashSet, HashMap};
 
+    use redical_ical::{ICalendarComponent, ICalendarEntity};
+
     #[test]
     fn test_uid_index_retrieval() {
         let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
@@ -514,19 +1095,16 @@ mod test {
             accessor.search_uid_index("EVENT_ONE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("EVENT_ONE"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_ONE"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
 
-        // TODO: Shouldn't this return an empty event set?
         // Positive matching: term does not exist
         assert_eq!(
             accessor.search_uid_index("EVENT_FOUR"),
             InvertedCalendarIndexTerm {
-                events: HashMap::from([
-                    (String::from("EVENT_FOUR"), IndexedConclusion::Include(None)),
-                ]),
+                events: HashMap::new(),
             }
         );
 
@@ -535,8 +1113,8 @@ mod test {
             accessor.search_not_uid_index("EVENT_ONE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("EVENT_TWO"), IndexedConclusion::Include(None)),
-                    (String::from("EVENT_THREE"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_TWO"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_THREE"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -546,9 +1124,9 @@ mod test {
             accessor.search_not_uid_index("EVENT_FOUR"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("EVENT_ONE"), IndexedConclusion::Include(None)),
-                    (String::from("EVENT_TWO"), IndexedConclusion::Include(None)),
-                    (String::from("EVENT_THREE"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_ONE"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_TWO"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_THREE"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -562,17 +1140,17 @@ mod test {
             (
                 String::from("ONLINE"),
                 [
-                    (String::from("All online"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly online"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly in person"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All online"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly online"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly in person"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
             (
                 String::from("IN-PERSON"),
                 [
-                    (String::from("All in person"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in person"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly online"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All in person"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in person"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly online"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             )
         ];
@@ -597,16 +1175,16 @@ mod test {
             String::from("Other event 2"),
         ];
 
-        let accessor = EventInstanceQueryIndexAccessor { calendar: &calendar, event_uids };
+        let accessor = EventInstanceQueryIndexAccessor { calendar: &calendar, event_uids, case_insensitive: false };
 
         // Positive matching: term exists
         assert_eq!(
             accessor.search_location_type_index("ONLINE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All online"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly online"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly in person"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All online"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly online"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly in person"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]),
             }
         );
@@ -624,11 +1202,11 @@ mod test {
             accessor.search_not_location_type_index("ONLINE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("Mostly online"), IndexedConclusion::Exclude(Some([100].into()))),
-                    (String::from("All in person"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in person"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly online"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All in person"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in person"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ])
             }
         );
@@ -638,12 +1216,12 @@ mod test {
             accessor.search_not_categories_index("FOOBAR"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All online"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly online"), IndexedConclusion::Include(None)),
-                    (String::from("All in person"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in person"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All online"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly online"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All in person"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in person"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -657,17 +1235,17 @@ mod test {
             (
                 String::from("Adults"),
                 [
-                    (String::from("All adults"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly adults"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly kids"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All adults"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly adults"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly kids"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
             (
                 String::from("Kids"),
                 [
-                    (String::from("All kids"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly kids"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly adults"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All kids"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly kids"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly adults"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
         ];
@@ -692,16 +1270,16 @@ mod test {
             String::from("Other event 2"),
         ];
 
-        let accessor = EventInstanceQueryIndexAccessor { calendar: &calendar, event_uids };
+        let accessor = EventInstanceQueryIndexAccessor { calendar: &calendar, event_uids, case_insensitive: false };
 
         // Positive matching: term exists
         assert_eq!(
             accessor.search_categories_index("Kids"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All kids"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly kids"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly adults"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All kids"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly kids"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly adults"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]),
             }
         );
@@ -719,11 +1297,11 @@ mod test {
             accessor.search_not_categories_index("Kids"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("Mostly kids"), IndexedConclusion::Exclude(Some([100].into()))),
-                    (String::from("All adults"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly adults"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly kids"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All adults"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly adults"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ])
             }
         );
@@ -733,12 +1311,12 @@ mod test {
             accessor.search_not_categories_index("FOOBAR"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All adults"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly adults"), IndexedConclusion::Include(None)),
-                    (String::from("All kids"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly kids"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All adults"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly adults"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All kids"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly kids"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -755,9 +1333,9 @@ mod test {
                     String::from("account-1"),
                 ),
                 [
-                    (String::from("All account-1"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-1"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly account-2"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All account-1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-1"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly account-2"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
             (
@@ -766,9 +1344,9 @@ mod test {
                     String::from("account-2"),
                 ),
                 [
-                    (String::from("All account-2"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-2"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly account-1"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All account-2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-2"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly account-1"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
         ];
@@ -793,7 +1371,7 @@ mod test {
             String::from("Other event 2"),
         ];
 
-        let accessor = EventInstanceQueryIndexAccessor { calendar: &calendar, event_uids };
+        let accessor = EventInstanceQueryIndexAccessor { calendar: &calendar, event_uids, case_insensitive: false };
 
         // Positive matching: term exists
         assert_eq!(
@@ -805,9 +1383,9 @@ mod test {
             ),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All account-1"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-1"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly account-2"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All account-1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-1"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly account-2"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]),
             }
         );
@@ -835,11 +1413,11 @@ mod test {
             ),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("Mostly account-1"), IndexedConclusion::Exclude(Some([100].into()))),
-                    (String::from("All account-2"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-2"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-1"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All account-2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-2"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ])
             }
         );
@@ -854,12 +1432,12 @@ mod test {
             ),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All account-1"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-1"), IndexedConclusion::Include(None)),
-                    (String::from("All account-2"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-2"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All account-1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All account-2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -877,17 +1455,17 @@ mod test {
             (
                 LONDON,
                 [
-                    (String::from("All in London"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in London"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly in Oxford"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All in London"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in London"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly in Oxford"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
             (
                 OXFORD,
                 [
-                    (String::from("All in Oxford"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in Oxford"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly in London"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All in Oxford"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in Oxford"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly in London"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
         ];
@@ -912,7 +1490,7 @@ mod test {
             String::from("Other event 2"),
         ];
 
-        let accessor = EventInstanceQueryIndexAccessor { calendar: &calendar, event_uids };
+        let accessor = EventInstanceQueryIndexAccessor { calendar: &calendar, event_uids, case_insensitive: false };
 
         let search_distance = GeoDistance::new_from_miles_float(10.0_f64);
 
@@ -921,9 +1499,9 @@ mod test {
             accessor.search_geo_index(&search_distance, &OXFORD),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All in Oxford"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in Oxford"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly in London"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All in Oxford"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in Oxford"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly in London"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]),
             }
         );
@@ -941,11 +1519,11 @@ mod test {
             accessor.search_not_geo_index(&search_distance, &OXFORD),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("Mostly in Oxford"), IndexedConclusion::Exclude(Some([100].into()))),
-                    (String::from("All in London"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in London"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in Oxford"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All in London"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in London"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ])
             }
         );
@@ -955,12 +1533,12 @@ mod test {
             accessor.search_not_geo_index(&search_distance, &NEW_YORK_CITY),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All in London"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in London"), IndexedConclusion::Include(None)),
-                    (String::from("All in Oxford"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in Oxford"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All in London"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in London"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All in Oxford"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in Oxford"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -974,17 +1552,17 @@ mod test {
             (
                 String::from("PUBLIC"),
                 [
-                    (String::from("All public"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly public"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly private"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All public"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly public"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly private"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
             (
                 String::from("PRIVATE"),
                 [
-                    (String::from("All private"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly private"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly public"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly private"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly public"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
         ];
@@ -1009,16 +1587,16 @@ mod test {
             String::from("Other event 2"),
         ];
 
-        let accessor = EventInstanceQueryIndexAccessor { calendar: &calendar, event_uids };
+        let accessor = EventInstanceQueryIndexAccessor { calendar: &calendar, event_uids, case_insensitive: false };
 
         // Positive matching: term exists
         assert_eq!(
             accessor.search_class_index("PRIVATE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All private"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly private"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly public"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly private"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly public"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]),
             }
         );
@@ -1036,11 +1614,11 @@ mod test {
             accessor.search_not_class_index("PUBLIC"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("Mostly public"), IndexedConclusion::Exclude(Some([100].into()))),
-                    (String::from("All private"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly private"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly public"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly private"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ])
             }
         );
@@ -1050,12 +1628,12 @@ mod test {
             accessor.search_not_class_index("FOOBAR"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All public"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly public"), IndexedConclusion::Include(None)),
-                    (String::from("All private"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly private"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All public"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly public"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -1132,9 +1710,10 @@ mod test {
 
         let event_instance_query_index_accessor = EventInstanceQueryIndexAccessor::new(&calendar);
 
+        // Test UID not present within the calendar.
         assert_eq_sorted!(
             event_instance_query_index_accessor.search_uid_index("UID"),
-            InvertedCalendarIndexTerm::new_with_event(String::from("UID"), IndexedConclusion::Include(None)),
+            InvertedCalendarIndexTerm::new(),
         );
 
         // Test existing calendar categories index entry.
@@ -1142,10 +1721,10 @@ mod test {
             event_instance_query_index_accessor.search_categories_index("CATEGORY"),
             InvertedCalendarIndexTerm::new_with_events(
                 vec![
-                    (String::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
-                    (String::from("FULLY_EXCLUDED_EVENT_UID"),     IndexedConclusion::Exclude(None)),
-                    (String::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
-                    (String::from("PARTIALLY_EXCLUDED_EVENT_UID"), IndexedConclusion::Exclude(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
+                    (InternedString::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
+                    (InternedString::from("FULLY_EXCLUDED_EVENT_UID"),     IndexedConclusion::Exclude(None)),
+                    (InternedString::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
+                    (InternedString::from("PARTIALLY_EXCLUDED_EVENT_UID"), IndexedConclusion::Exclude(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
                 ]
             ),
         );
@@ -1161,10 +1740,10 @@ mod test {
             event_instance_query_index_accessor.search_location_type_index("ONLINE"),
             InvertedCalendarIndexTerm::new_with_events(
                 vec![
-                    (String::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
-                    (String::from("FULLY_EXCLUDED_EVENT_UID"),     IndexedConclusion::Exclude(None)),
-                    (String::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
-                    (String::from("PARTIALLY_EXCLUDED_EVENT_UID"), IndexedConclusion::Exclude(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
+                    (InternedString::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
+                    (InternedString::from("FULLY_EXCLUDED_EVENT_UID"),     IndexedConclusion::Exclude(None)),
+                    (InternedString::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
+                    (InternedString::from("PARTIALLY_EXCLUDED_EVENT_UID"), IndexedConclusion::Exclude(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
                 ]
             ),
         );
@@ -1182,10 +1761,10 @@ mod test {
             ),
             InvertedCalendarIndexTerm::new_with_events(
                 vec![
-                    (String::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
-                    (String::from("FULLY_EXCLUDED_EVENT_UID"),     IndexedConclusion::Exclude(None)),
-                    (String::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
-                    (String::from("PARTIALLY_EXCLUDED_EVENT_UID"), IndexedConclusion::Exclude(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
+                    (InternedString::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
+                    (InternedString::from("FULLY_EXCLUDED_EVENT_UID"),     IndexedConclusion::Exclude(None)),
+                    (InternedString::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
+                    (InternedString::from("PARTIALLY_EXCLUDED_EVENT_UID"), IndexedConclusion::Exclude(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
                 ]
             ),
         );
@@ -1206,9 +1785,9 @@ mod test {
             ),
             InvertedCalendarIndexTerm::new_with_events(
                 vec![
-                    (String::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
-                    (String::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
-                    (String::from("PARTIALLY_EXCLUDED_EVENT_UID"), IndexedConclusion::Exclude(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
+                    (InternedString::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
+                    (InternedString::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
+                    (InternedString::from("PARTIALLY_EXCLUDED_EVENT_UID"), IndexedConclusion::Exclude(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
                 ]
             ),
         );
@@ -1227,10 +1806,10 @@ mod test {
             event_instance_query_index_accessor.search_class_index("PUBLIC"),
             InvertedCalendarIndexTerm::new_with_events(
                 vec![
-                    (String::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
-                    (String::from("FULLY_EXCLUDED_EVENT_UID"),     IndexedConclusion::Exclude(None)),
-                    (String::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
-                    (String::from("PARTIALLY_EXCLUDED_EVENT_UID"), IndexedConclusion::Exclude(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
+                    (InternedString::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
+                    (InternedString::from("FULLY_EXCLUDED_EVENT_UID"),     IndexedConclusion::Exclude(None)),
+                    (InternedString::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
+                    (InternedString::from("PARTIALLY_EXCLUDED_EVENT_UID"), IndexedConclusion::Exclude(Some(HashSet::from([100_i64, 200_i64, 300_i64])))),
                 ]
             ),
         );
@@ -1302,14 +1881,353 @@ mod test {
 
                 lower_bound_range_condition: Some(LowerBoundRangeCondition::GreaterThan(RangeConditionProperty::DtStart(875779200))),
                 upper_bound_range_condition: Some(UpperBoundRangeCondition::LessEqualThan(RangeConditionProperty::DtStart(878461200))),
+                duration_range_conditions: Vec::new(),
 
                 in_timezone: chrono_tz::Tz::Europe__Vilnius,
 
                 distinct_uids: false,
+                group_by_uid: false,
 
                 offset: 0,
                 limit: 50,
+                limit_per_event: None,
+                dst_policy: None,
+                return_properties: None,
+                count_only: false,
+                merge_intervals: false,
+                warnings: Vec::new(),
             })
         );
     }
+
+    #[test]
+    fn test_paginated_execution_tie_break() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        // Events sharing the same DTSTART (and geographical distance), inserted out of UID order.
+        for (event_uid, geo) in [
+            ("EVENT_D", "51.5074;-0.1278"),
+            ("EVENT_B", "51.5074;-0.1278"),
+            ("EVENT_C", "51.5074;-0.1278"),
+            ("EVENT_A", "51.5074;-0.1278"),
+            ("EVENT_E", "51.4517446;-1.004574"),
+        ] {
+            calendar.insert_event_and_reindex(
+                build_event_from_ical(event_uid, vec![format!("DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=2 GEO:{geo}").as_str()])
+            ).unwrap();
+        }
+
+        for ordering in ["DTSTART", "DTSTART-GEO-DIST;51.5074;-0.1278", "GEO-DIST-DTSTART;51.5074;-0.1278"] {
+            let query_page = |offset: usize| -> Vec<(String, i64)> {
+                EventInstanceQuery::from_str(format!("X-ORDER-BY:{ordering} X-OFFSET:{offset} X-LIMIT:3").as_str())
+                    .unwrap()
+                    .execute(&calendar)
+                    .unwrap()
+                    .results
+                    .into_iter()
+                    .map(|query_result| (query_result.result.get_uid(), query_result.result.get_recurrence_id_timestamp().unwrap()))
+                    .collect()
+            };
+
+            let all_results: Vec<(String, i64)> =
+                EventInstanceQuery::from_str(format!("X-ORDER-BY:{ordering} X-LIMIT:10").as_str())
+                    .unwrap()
+                    .execute(&calendar)
+                    .unwrap()
+                    .results
+                    .into_iter()
+                    .map(|query_result| (query_result.result.get_uid(), query_result.result.get_recurrence_id_timestamp().unwrap()))
+                    .collect();
+
+            assert_eq!(all_results.len(), 10);
+
+            // Paging through the results returns each exactly once, in the same order as they
+            // are returned unpaginated.
+            let paginated_results: Vec<(String, i64)> =
+                [0, 3, 6, 9]
+                    .into_iter()
+                    .flat_map(query_page)
+                    .collect();
+
+            assert_eq!(paginated_results, all_results, "ordering: {ordering}");
+        }
+
+        // Event instances sharing the same DTSTART and distance are ordered by UID.
+        assert_eq!(
+            EventInstanceQuery::from_str("X-ORDER-BY:DTSTART-GEO-DIST;51.5074;-0.1278 X-OFFSET:2 X-LIMIT:3")
+                .unwrap()
+                .execute(&calendar)
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|query_result| query_result.result.get_uid())
+                .collect::<Vec<String>>(),
+            vec![
+                String::from("EVENT_C"),
+                String::from("EVENT_D"),
+                String::from("EVENT_E"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_score_ordering_requires_upper_bound() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_ART", vec!["DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1 CATEGORIES:ART"])
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_ART_SPORT", vec!["DTSTART:20210106T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1 CATEGORIES:ART,SPORT"])
+        ).unwrap();
+
+        assert_eq!(
+            EventInstanceQuery::from_str("(X-CATEGORIES:ART OR X-CATEGORIES:SPORT) X-ORDER-BY:SCORE X-LIMIT:2")
+                .unwrap()
+                .execute(&calendar)
+                .map(|query_results| query_results.len()),
+            Err(String::from("X-ORDER-BY:SCORE requires an X-UNTIL upper bound")),
+        );
+
+        assert_eq!(
+            EventInstanceQuery::from_str("(X-CATEGORIES:ART OR X-CATEGORIES:SPORT) X-UNTIL:20210120T000000Z X-ORDER-BY:SCORE X-LIMIT:2")
+                .unwrap()
+                .execute(&calendar)
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|query_result| (query_result.result.get_uid(), query_result.result.get_recurrence_id_timestamp().unwrap()))
+                .collect::<Vec<(String, i64)>>(),
+            vec![
+                (String::from("EVENT_ART_SPORT"), 1609957800),
+                (String::from("EVENT_ART_SPORT"), 1610562600),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_execute_with_query_profiler() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_ONE", vec!["DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3 CATEGORIES:CATEGORY_ONE"])
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_TWO", vec!["DTSTART:20210105T183000Z CATEGORIES:CATEGORY_TWO"])
+        ).unwrap();
+
+        let query_profiler = QueryProfiler::new();
+
+        let query_results =
+            EventInstanceQuery::from_str("X-CATEGORIES:CATEGORY_ONE X-LIMIT:10")
+                .unwrap()
+                .execute_with_query_profiler(
+                    &calendar,
+                    &ExecutionDeadline::default(),
+                    &OccurrenceExpansionLimits::default(),
+                    &query_profiler,
+                )
+                .unwrap();
+
+        assert_eq!(query_results.len(), 3);
+
+        let query_profile = query_profiler.get_profile();
+
+        // Only the events matching the where conditional are expanded.
+        assert_eq!(
+            query_profile
+                .event_expansions
+                .iter()
+                .map(|(event_uid, event_expansion_profile)| {
+                    (
+                        event_uid.to_owned(),
+                        event_expansion_profile.expanded_count,
+                        event_expansion_profile.instance_count,
+                    )
+                })
+                .collect::<Vec<(String, usize, usize)>>(),
+            vec![(String::from("EVENT_ONE"), 3, 3)],
+        );
+
+        assert!(query_profile.execution_duration >= query_profile.index_filtering_duration + query_profile.expansion_duration);
+    }
+
+    #[test]
+    fn test_build_count_query() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_ONE", vec!["DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=5 CATEGORIES:CATEGORY_ONE"])
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_TWO", vec!["DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1 CATEGORIES:CATEGORY_TWO"])
+        ).unwrap();
+
+        let query = EventInstanceQuery::from_str("X-CATEGORIES:CATEGORY_ONE X-OFFSET:1 X-LIMIT:2").unwrap();
+
+        // The offset and limit are disregarded when counting all the matching results.
+        assert_eq!(query.build_count_query(None).execute(&calendar).unwrap().len(), 5);
+        assert_eq!(query.build_count_query(Some(10)).execute(&calendar).unwrap().len(), 5);
+
+        // The count exceeds the max count (by one) when capped.
+        assert_eq!(query.build_count_query(Some(3)).execute(&calendar).unwrap().len(), 4);
+
+        // Counting the matches of an infinitely recurring event is bounded by the max count.
+        let query = EventInstanceQuery::from_str("X-CATEGORIES:CATEGORY_TWO X-LIMIT:2").unwrap();
+
+        assert_eq!(query.build_count_query(Some(100)).execute(&calendar).unwrap().len(), 101);
+
+        // The original query is left unchanged.
+        assert_eq!((query.offset, query.limit), (0, 2));
+    }
+
+    #[test]
+    fn test_limit_per_event() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_DAILY", vec!["DTSTART:20210105T090000Z RRULE:FREQ=DAILY;INTERVAL=1"])
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_WEEKLY", vec!["DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3"])
+        ).unwrap();
+
+        let query_results = |query_string: &str| -> Vec<(String, i64)> {
+            EventInstanceQuery::from_str(query_string)
+                .unwrap()
+                .execute(&calendar)
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|query_result| (query_result.result.get_uid(), query_result.result.get_recurrence_id_timestamp().unwrap()))
+                .collect()
+        };
+
+        // Without a limit per event, the daily event crowds out the weekly event.
+        assert_eq!(
+            query_results("X-LIMIT:4"),
+            vec![
+                (String::from("EVENT_DAILY"), 1609837200),
+                (String::from("EVENT_WEEKLY"), 1609871400),
+                (String::from("EVENT_DAILY"), 1609923600),
+                (String::from("EVENT_DAILY"), 1610010000),
+            ],
+        );
+
+        assert_eq!(
+            query_results("X-LIMIT-PER-EVENT:2 X-LIMIT:10"),
+            vec![
+                (String::from("EVENT_DAILY"), 1609837200),
+                (String::from("EVENT_WEEKLY"), 1609871400),
+                (String::from("EVENT_DAILY"), 1609923600),
+                (String::from("EVENT_WEEKLY"), 1610476200),
+            ],
+        );
+
+        // The limit per event applies before the offset.
+        assert_eq!(
+            query_results("X-LIMIT-PER-EVENT:2 X-OFFSET:3 X-LIMIT:10"),
+            vec![
+                (String::from("EVENT_WEEKLY"), 1610476200),
+            ],
+        );
+
+        // X-DISTINCT:UID takes precedence (a single instance per event).
+        assert_eq!(
+            query_results("X-LIMIT-PER-EVENT:2 X-DISTINCT:UID X-LIMIT:10"),
+            vec![
+                (String::from("EVENT_DAILY"), 1609837200),
+                (String::from("EVENT_WEEKLY"), 1609871400),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_return_properties() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_ONE", vec!["DTSTART:20210105T090000Z SUMMARY:Event one CATEGORIES:CATEGORY_ONE"])
+        ).unwrap();
+
+        let query_result_properties = |query_string: &str| -> Vec<Vec<String>> {
+            let mut query = EventInstanceQuery::from_str(query_string).unwrap();
+
+            query
+                .execute(&calendar)
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|query_result| {
+                    query
+                        .project_content_lines(query_result.result.to_content_line_set())
+                        .into_iter()
+                        .map(|content_line| content_line.render_ical())
+                        .collect()
+                })
+                .collect()
+        };
+
+        // All properties are rendered unless restricted.
+        assert!(query_result_properties("X-LIMIT:1")[0].contains(&String::from("CATEGORIES:CATEGORY_ONE")));
+
+        // Property names are matched case-insensitively, and those absent are omitted.
+        assert_eq!(
+            query_result_properties("X-RETURN:uid,DTSTART,SUMMARY,LOCATION X-LIMIT:1"),
+            vec![
+                vec![
+                    String::from("DTSTART:20210105T090000Z"),
+                    String::from("SUMMARY:Event one"),
+                    String::from("UID:EVENT_ONE"),
+                ],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_count_with_occurrence_expansion_limits() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        let mut event_daily = build_event_from_ical("EVENT_DAILY", vec!["DTSTART:20210105T090000Z RRULE:FREQ=DAILY;INTERVAL=1;COUNT=5 CATEGORIES:CATEGORY_ONE"]);
+
+        // Overridden occurrences are counted with consideration to their overridden properties.
+        event_daily.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210106T090000Z", "CATEGORIES:CATEGORY_TWO").unwrap(),
+            false,
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(event_daily).unwrap();
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_WEEKLY", vec!["DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3 CATEGORIES:CATEGORY_ONE"])
+        ).unwrap();
+
+        let count = |query_string: &str, max_count: Option<usize>| -> usize {
+            let query = EventInstanceQuery::from_str(query_string).unwrap();
+
+            assert!(query.count_only);
+
+            query.count_with_occurrence_expansion_limits(
+                &calendar,
+                max_count,
+                &ExecutionDeadline::default(),
+                &OccurrenceExpansionLimits::default(),
+            ).unwrap()
+        };
+
+        // The offset and limit are disregarded.
+        assert_eq!(count("X-COUNT:TRUE X-OFFSET:2 X-LIMIT:1", None), 8);
+        assert_eq!(count("X-COUNT:TRUE X-CATEGORIES:CATEGORY_ONE", None), 7);
+        assert_eq!(count("X-COUNT:TRUE X-CATEGORIES:CATEGORY_TWO", None), 1);
+        assert_eq!(count("X-COUNT:TRUE X-FROM;PROP=DTSTART;OP=GT:20210107T000000Z", None), 5);
+        assert_eq!(count("X-COUNT:TRUE X-DISTINCT:UID", None), 2);
+        assert_eq!(count("X-COUNT:TRUE X-LIMIT-PER-EVENT:2", None), 4);
+
+        // Counted up to one beyond the max count.
+        assert_eq!(count("X-COUNT:TRUE", Some(3)), 4);
+    }
 }
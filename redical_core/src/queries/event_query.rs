@@ -2,19 +2,22 @@ use std::str::FromStr;
 
 use chrono_tz::Tz;
 
+use regex::Regex;
+
 use crate::{
-    Calendar, Event, IndexedConclusion, InvertedCalendarIndexTerm,
-    LowerBoundFilterCondition, UpperBoundFilterCondition, KeyValuePair,
-    GeoDistance, GeoPoint, FilterProperty,
+    Calendar, DstPolicy, Event, ExecutionDeadline, IndexedConclusion, InvertedCalendarIndexTerm,
+    LowerBoundFilterCondition, UpperBoundFilterCondition, DurationFilterCondition,
+    KeyValuePair, GeoDistance, GeoPoint, FilterProperty,
 };
 
 use crate::queries::indexed_property_filters::WhereConditional;
 use crate::queries::query::{Query, QueryIndexAccessor};
+use crate::queries::query_explain::{QueryExplanation, WhereConditionalExplanation};
 use crate::queries::query_parser::parse_query_string;
 use crate::queries::results::QueryResults;
 use crate::queries::results_ordering::OrderingCondition;
 use crate::queries::results_range_bounds::{
-    LowerBoundRangeCondition, UpperBoundRangeCondition,
+    DurationRangeCondition, LowerBoundRangeCondition, UpperBoundRangeCondition,
 };
 
 /// This struct implements the `QueryIndexAccessor` trait and it's purpose is to specify the index
@@ -28,6 +31,7 @@ use crate::queries::results_range_bounds::{
 pub struct EventQueryIndexAccessor<'cal> {
     calendar: &'cal Calendar,
     event_uids: Vec<String>,
+    case_insensitive: bool,
 }
 
 impl EventQueryIndexAccessor<'_> {
@@ -57,29 +61,88 @@ impl<'cal> QueryIndexAccessor<'cal> for EventQueryIndexAccessor<'cal> {
         EventQueryIndexAccessor {
             calendar,
             event_uids,
+            case_insensitive: calendar.is_term_matching_case_insensitive(),
         }
     }
 
-    // For UID, we just return an "include all" consensus for that event UID.
+    fn is_term_matching_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    // For UID, we just return an "include all" consensus for each matching event UID within the
+    // calendar, otherwise an empty term (consistent with the other indexes).
     fn search_uid_index(&self, uid: &str) -> InvertedCalendarIndexTerm {
-        InvertedCalendarIndexTerm::new_with_event(
-            uid.to_owned(),
-            IndexedConclusion::Include(None),
+        InvertedCalendarIndexTerm::new_with_events(
+            self.calendar
+                .get_matching_event_uids(uid)
+                .into_iter()
+                .map(|event_uid| (event_uid.to_owned(), IndexedConclusion::Include(None)))
+                .collect()
+        )
+    }
+
+    // Matches any event UID starting with the prefix (range scanned over the ordered event UIDs).
+    fn search_uid_prefix_index(&self, uid_prefix: &str) -> InvertedCalendarIndexTerm {
+        InvertedCalendarIndexTerm::new_with_events(
+            self.calendar
+                .get_prefixed_event_uids(uid_prefix)
+                .into_iter()
+                .map(|event_uid| (event_uid.to_owned(), IndexedConclusion::Include(None)))
+                .collect()
+        )
+    }
+
+    // Matches any event UID matching the regular expression (tested against every event UID).
+    fn search_uid_regex_index(&self, uid_regex: &Regex) -> InvertedCalendarIndexTerm {
+        InvertedCalendarIndexTerm::new_with_events(
+            self.calendar
+                .get_regex_matching_event_uids(uid_regex)
+                .into_iter()
+                .map(|event_uid| (event_uid.to_owned(), IndexedConclusion::Include(None)))
+                .collect()
         )
     }
 
     fn search_location_type_index(&self, location_type: &str) -> InvertedCalendarIndexTerm {
         Self::included_conclusions_or_nothing(
-            self.calendar.indexed_location_type.get_term(&location_type.to_string())
+            self.calendar.indexed_location_type.get_matching_term(location_type, self.case_insensitive).as_deref()
         )
     }
 
     fn search_categories_index(&self, category: &str) -> InvertedCalendarIndexTerm {
         Self::included_conclusions_or_nothing(
-            self.calendar.indexed_categories.get_term(&category.to_string())
+            self.calendar.indexed_categories.get_matching_term(category, self.case_insensitive).as_deref()
         )
     }
 
+    // Matches any category term starting with the prefix (range scanned over the ordered index
+    // terms).
+    fn search_categories_prefix_index(&self, category_prefix: &str) -> InvertedCalendarIndexTerm {
+        self.calendar
+            .indexed_categories
+            .get_prefixed_terms(category_prefix)
+            .fold(InvertedCalendarIndexTerm::new(), |matches, (_category, inverted_calendar_index_term)| {
+                InvertedCalendarIndexTerm::merge_or(
+                    &matches,
+                    &Self::included_conclusions_or_nothing(Some(inverted_calendar_index_term)),
+                )
+            })
+    }
+
+    // Matches any category term matching the regular expression (tested against every indexed
+    // term).
+    fn search_categories_regex_index(&self, category_regex: &Regex) -> InvertedCalendarIndexTerm {
+        self.calendar
+            .indexed_categories
+            .get_regex_matching_terms(category_regex)
+            .fold(InvertedCalendarIndexTerm::new(), |matches, (_category, inverted_calendar_index_term)| {
+                InvertedCalendarIndexTerm::merge_or(
+                    &matches,
+                    &Self::included_conclusions_or_nothing(Some(inverted_calendar_index_term)),
+                )
+            })
+    }
+
     fn search_related_to_index(&self, reltype_uids: &KeyValuePair) -> InvertedCalendarIndexTerm {
         Self::included_conclusions_or_nothing(
             self.calendar.indexed_related_to.get_term(reltype_uids)
@@ -94,18 +157,86 @@ impl<'cal> QueryIndexAccessor<'cal> for EventQueryIndexAccessor<'cal> {
         )
     }
 
+    fn search_geo_index_within_candidates(&self, distance: &GeoDistance, long_lat: &GeoPoint, candidates: &InvertedCalendarIndexTerm) -> InvertedCalendarIndexTerm {
+        Self::included_conclusions_or_nothing(
+            Some(
+                &self.calendar.locate_candidates_within_distance(long_lat, distance, candidates)
+            )
+        )
+    }
+
     fn search_class_index(&self, class: &str) -> InvertedCalendarIndexTerm {
         Self::included_conclusions_or_nothing(
-            self.calendar.indexed_class.get_term(&class.to_string())
+            self.calendar.indexed_class.get_matching_term(class, self.case_insensitive).as_deref()
+        )
+    }
+
+    // Matches any class term matching the regular expression (tested against every indexed
+    // term).
+    fn search_class_regex_index(&self, class_regex: &Regex) -> InvertedCalendarIndexTerm {
+        self.calendar
+            .indexed_class
+            .get_regex_matching_terms(class_regex)
+            .fold(InvertedCalendarIndexTerm::new(), |matches, (_class, inverted_calendar_index_term)| {
+                InvertedCalendarIndexTerm::merge_or(
+                    &matches,
+                    &Self::included_conclusions_or_nothing(Some(inverted_calendar_index_term)),
+                )
+            })
+    }
+
+    fn search_location_index(&self, location: &str) -> InvertedCalendarIndexTerm {
+        Self::included_conclusions_or_nothing(
+            self.calendar.indexed_location.get_matching_term(location, self.case_insensitive).as_deref()
+        )
+    }
+
+    fn search_x_property_index(&self, name_value: &KeyValuePair) -> InvertedCalendarIndexTerm {
+        Self::included_conclusions_or_nothing(
+            self.calendar.indexed_x_properties.get_term(name_value)
         )
     }
 
     fn search_not_uid_index(&self, uid: &str) -> InvertedCalendarIndexTerm {
         let mut inverse_matches = InvertedCalendarIndexTerm::new();
 
+        let matching_event_uids = self.calendar.get_matching_event_uids(uid);
+
+        let included_event_uids_iter = self.event_uids
+            .iter()
+            .filter(|event_uid| !matching_event_uids.contains(event_uid));
+
+        for event_uid in included_event_uids_iter {
+            inverse_matches.insert_included_event(event_uid.to_owned(), None);
+        }
+
+        inverse_matches
+    }
+
+    fn search_not_uid_prefix_index(&self, uid_prefix: &str) -> InvertedCalendarIndexTerm {
+        let mut inverse_matches = InvertedCalendarIndexTerm::new();
+
+        let matching_event_uids = self.calendar.get_prefixed_event_uids(uid_prefix);
+
         let included_event_uids_iter = self.event_uids
             .iter()
-            .filter(|event_uid| *event_uid != uid);
+            .filter(|event_uid| !matching_event_uids.contains(event_uid));
+
+        for event_uid in included_event_uids_iter {
+            inverse_matches.insert_included_event(event_uid.to_owned(), None);
+        }
+
+        inverse_matches
+    }
+
+    fn search_not_uid_regex_index(&self, uid_regex: &Regex) -> InvertedCalendarIndexTerm {
+        let mut inverse_matches = InvertedCalendarIndexTerm::new();
+
+        let matching_event_uids = self.calendar.get_regex_matching_event_uids(uid_regex);
+
+        let included_event_uids_iter = self.event_uids
+            .iter()
+            .filter(|event_uid| !matching_event_uids.contains(event_uid));
 
         for event_uid in included_event_uids_iter {
             inverse_matches.insert_included_event(event_uid.to_owned(), None);
@@ -115,8 +246,9 @@ impl<'cal> QueryIndexAccessor<'cal> for EventQueryIndexAccessor<'cal> {
     }
 
     fn search_not_location_type_index(&self, location_type: &str) -> InvertedCalendarIndexTerm {
-        let inverse_matches = self.calendar.indexed_location_type.get_not_term(
-            &location_type.to_string(),
+        let inverse_matches = self.calendar.indexed_location_type.get_not_matching_term(
+            location_type,
+            self.case_insensitive,
             &self.event_uids,
         );
 
@@ -126,8 +258,9 @@ impl<'cal> QueryIndexAccessor<'cal> for EventQueryIndexAccessor<'cal> {
     }
 
     fn search_not_categories_index(&self, category: &str) -> InvertedCalendarIndexTerm {
-        let inverse_matches = self.calendar.indexed_categories.get_not_term(
-            &category.to_string(),
+        let inverse_matches = self.calendar.indexed_categories.get_not_matching_term(
+            category,
+            self.case_insensitive,
             &self.event_uids,
         );
 
@@ -160,8 +293,32 @@ impl<'cal> QueryIndexAccessor<'cal> for EventQueryIndexAccessor<'cal> {
     }
 
     fn search_not_class_index(&self, class: &str) -> InvertedCalendarIndexTerm {
-        let inverse_matches = self.calendar.indexed_class.get_not_term(
-            &class.to_string(),
+        let inverse_matches = self.calendar.indexed_class.get_not_matching_term(
+            class,
+            self.case_insensitive,
+            &self.event_uids,
+        );
+
+        Self::included_conclusions_or_nothing(
+            Some(&inverse_matches)
+        )
+    }
+
+    fn search_not_location_index(&self, location: &str) -> InvertedCalendarIndexTerm {
+        let inverse_matches = self.calendar.indexed_location.get_not_matching_term(
+            location,
+            self.case_insensitive,
+            &self.event_uids,
+        );
+
+        Self::included_conclusions_or_nothing(
+            Some(&inverse_matches)
+        )
+    }
+
+    fn search_not_x_property_index(&self, name_value: &KeyValuePair) -> InvertedCalendarIndexTerm {
+        let inverse_matches = self.calendar.indexed_x_properties.get_not_term(
+            name_value,
             &self.event_uids,
         );
 
@@ -179,10 +336,14 @@ pub struct EventQuery {
     pub ordering_condition: OrderingCondition,
     pub lower_bound_range_condition: Option<LowerBoundRangeCondition>,
     pub upper_bound_range_condition: Option<UpperBoundRangeCondition>,
+    pub duration_range_conditions: Vec<DurationRangeCondition>,
     pub in_timezone: Tz,
     pub distinct_uids: bool,
     pub offset: usize,
     pub limit: usize,
+    pub return_properties: Option<Vec<String>>,
+    pub count_only: bool,
+    pub warnings: Vec<String>,
 }
 
 impl FromStr for EventQuery {
@@ -196,19 +357,28 @@ impl FromStr for EventQuery {
 }
 
 impl Query<Event> for EventQuery {
-    fn execute(&mut self, calendar: &Calendar) -> Result<QueryResults<Event>, String> {
+    fn execute_with_deadline(&mut self, calendar: &Calendar, execution_deadline: &ExecutionDeadline) -> Result<QueryResults<Event>, String> {
         let query_index_accessor = EventQueryIndexAccessor::new(calendar);
 
         let where_conditional_result = if let Some(where_conditional) = &mut self.where_conditional
         {
-            Some(where_conditional.execute(&query_index_accessor)?)
+            Some(where_conditional.execute(&query_index_accessor, execution_deadline)?)
         } else {
             None
         };
 
+        // Results ordered by relevance score (or by geographical distance within a shared
+        // dtstart) can only be offset once they have been collected and sorted, so the offset is
+        // applied after execution instead.
+        let offset = if matches!(self.ordering_condition, OrderingCondition::Score(_) | OrderingCondition::DtStartGeoDist(_)) {
+            0
+        } else {
+            self.offset
+        };
+
         let mut query_results = QueryResults::new(
-            self.ordering_condition.clone(),
-            self.offset,
+            self.ordering_condition.bind_where_conditional(&self.where_conditional),
+            offset,
             self.distinct_uids,
         );
 
@@ -218,6 +388,7 @@ impl Query<Event> for EventQuery {
                     calendar,
                     &mut query_results,
                     &where_conditional_result,
+                    execution_deadline,
                 )?;
             }
 
@@ -226,6 +397,7 @@ impl Query<Event> for EventQuery {
                     calendar,
                     &mut query_results,
                     &where_conditional_result,
+                    execution_deadline,
                 )?;
             }
 
@@ -235,6 +407,16 @@ impl Query<Event> for EventQuery {
                     calendar,
                     &mut query_results,
                     &where_conditional_result,
+                    execution_deadline,
+                )?;
+            }
+
+            OrderingCondition::Score(_) => {
+                self.execute_for_score_ordering(
+                    calendar,
+                    &mut query_results,
+                    &where_conditional_result,
+                    execution_deadline,
                 )?;
             }
         }
@@ -262,6 +444,10 @@ impl Query<Event> for EventQuery {
         self.upper_bound_range_condition = upper_bound_range_condition;
     }
 
+    fn insert_duration_range_condition(&mut self, duration_range_condition: DurationRangeCondition) {
+        self.duration_range_conditions.push(duration_range_condition);
+    }
+
     fn set_in_timezone(&mut self, in_timezone: Tz) {
         self.in_timezone = in_timezone;
     }
@@ -270,16 +456,79 @@ impl Query<Event> for EventQuery {
         self.distinct_uids = distinct_uids;
     }
 
+    // Each event is only ever returned once, so there is nothing to group.
+    fn set_group_by_uid(&mut self, _group_by_uid: bool) {}
+
     fn set_offset(&mut self, offset: usize) {
         self.offset = offset;
     }
 
+    fn get_offset(&self) -> usize {
+        self.offset
+    }
+
     fn set_limit(&mut self, limit: usize) {
         self.limit = limit;
     }
+
+    fn get_limit(&self) -> usize {
+        self.limit
+    }
+
+    // Each event is only ever returned once, so there is nothing to limit.
+    fn set_limit_per_event(&mut self, _limit_per_event: Option<usize>) {}
+
+    // Events are returned without expanding their occurrences, so there is nothing to resolve.
+    fn set_dst_policy(&mut self, _dst_policy: Option<DstPolicy>) {}
+
+    fn set_return_properties(&mut self, return_properties: Option<Vec<String>>) {
+        self.return_properties = return_properties;
+    }
+
+    fn get_return_properties(&self) -> &Option<Vec<String>> {
+        &self.return_properties
+    }
+
+    fn set_count_only(&mut self, count_only: bool) {
+        self.count_only = count_only;
+    }
+
+    // Events are returned without expanding their occurrences, so there are no intervals to merge.
+    fn set_merge_intervals(&mut self, _merge_intervals: bool) {}
+
+    fn set_warnings(&mut self, warnings: Vec<String>) {
+        self.warnings = warnings;
+    }
+
+    fn get_warnings(&self) -> &Vec<String> {
+        &self.warnings
+    }
 }
 
 impl EventQuery {
+    /// Plans the query against the calendar without ordering any events, describing the resolved
+    /// where conditional tree, the candidate events, and how the results would be ordered and
+    /// offset.
+    pub fn explain(&self, calendar: &Calendar, execution_deadline: &ExecutionDeadline) -> Result<QueryExplanation, String> {
+        let query_index_accessor = EventQueryIndexAccessor::new(calendar);
+
+        let where_conditional = if let Some(where_conditional) = &self.where_conditional {
+            Some(WhereConditionalExplanation::build(where_conditional, &query_index_accessor, execution_deadline)?)
+        } else {
+            None
+        };
+
+        Ok(QueryExplanation::new(
+            where_conditional,
+            calendar.events.keys().collect(),
+            None,
+            &self.ordering_condition,
+            self.offset,
+            self.limit,
+            self.distinct_uids,
+        ))
+    }
+
     fn get_lower_bound_filter_condition(&self) -> Option<LowerBoundFilterCondition> {
         self.lower_bound_range_condition
             .to_owned()
@@ -292,6 +541,14 @@ impl EventQuery {
             .map(UpperBoundFilterCondition::from)
     }
 
+    fn get_duration_filter_conditions(&self) -> Vec<DurationFilterCondition> {
+        self.duration_range_conditions
+            .iter()
+            .cloned()
+            .map(DurationFilterCondition::from)
+            .collect()
+    }
+
     fn is_event_within_bound_filter_conditions(
         &self,
         event: &Event,
@@ -340,7 +597,18 @@ impl EventQuery {
                 _ => true
             };
 
-        within_lower_bound_filter_condition && within_upper_bound_filter_condition
+        // We only consider the base Event duration, not any occurrence specific overridden
+        // durations - consistent with the DTSTART/DTEND range filtering above.
+        let event_duration = event.schedule_properties.get_duration_in_seconds().unwrap_or(0);
+
+        let within_duration_filter_conditions =
+            self.get_duration_filter_conditions()
+                .iter()
+                .all(|duration_filter_condition| duration_filter_condition.is_satisfied_by(&event_duration));
+
+        within_lower_bound_filter_condition
+            && within_upper_bound_filter_condition
+            && within_duration_filter_conditions
     }
 
     #[allow(clippy::borrowed_box)]
@@ -348,6 +616,7 @@ impl EventQuery {
         &self,
         calendar: &'cal Calendar,
         where_conditional_result: &Option<InvertedCalendarIndexTerm>,
+        execution_deadline: &ExecutionDeadline,
     ) -> Result<Vec<&'event Box<Event>>, String> {
         let lower_bound_filter_condition = self.get_lower_bound_filter_condition();
         let upper_bound_filter_condition = self.get_upper_bound_filter_condition();
@@ -357,7 +626,9 @@ impl EventQuery {
         match where_conditional_result {
             Some(inverted_calendar_index_term) => {
                 for (event_uid, indexed_conclusion) in &inverted_calendar_index_term.events {
-                    let Some(event) = calendar.events.get(event_uid) else {
+                    execution_deadline.check()?;
+
+                    let Some(event) = calendar.events.get(event_uid.as_str()) else {
                         // TODO: handle missing indexed event...
 
                         continue;
@@ -381,6 +652,8 @@ impl EventQuery {
 
             None => {
                 for event in calendar.events.values() {
+                    execution_deadline.check()?;
+
                     if self.is_event_within_bound_filter_conditions(event, &lower_bound_filter_condition, &upper_bound_filter_condition) {
                         sorted_events.push(event);
                     }
@@ -388,11 +661,15 @@ impl EventQuery {
             }
         }
 
+        // Events sharing the same dtstart are ordered by UID (as with the query results), so that
+        // they are consistently offset regardless of the (unordered) index they were found in.
         sorted_events.sort_by(|event, other_event| {
             let timestamp       = event.schedule_properties.get_dtstart_timestamp();
             let other_timestamp = other_event.schedule_properties.get_dtstart_timestamp();
 
-            timestamp.cmp(&other_timestamp)
+            timestamp
+                .cmp(&other_timestamp)
+                .then_with(|| event.uid.uid.0.cmp(&other_event.uid.uid.0))
         });
 
         Ok(sorted_events)
@@ -403,8 +680,9 @@ impl EventQuery {
         calendar: &Calendar,
         query_results: &mut QueryResults<Event>,
         where_conditional_result: &Option<InvertedCalendarIndexTerm>,
+        execution_deadline: &ExecutionDeadline,
     ) -> Result<(), String> {
-        let sorted_events = self.populate_sorted_vec_for_dtstart_ordering(calendar, where_conditional_result)?;
+        let sorted_events = self.populate_sorted_vec_for_dtstart_ordering(calendar, where_conditional_result, execution_deadline)?;
 
         for event in sorted_events {
             if query_results.len() >= self.limit {
@@ -422,8 +700,9 @@ impl EventQuery {
         calendar: &Calendar,
         query_results: &mut QueryResults<Event>,
         where_conditional_result: &Option<InvertedCalendarIndexTerm>,
+        execution_deadline: &ExecutionDeadline,
     ) -> Result<(), String> {
-        let sorted_events = self.populate_sorted_vec_for_dtstart_ordering(calendar, where_conditional_result)?;
+        let sorted_events = self.populate_sorted_vec_for_dtstart_ordering(calendar, where_conditional_result, execution_deadline)?;
 
         // This is functionally similar to the DtStart ordering, except we need to include all the
         // Event sharing the same dtstart_timestamp before truncating so that they can be ordered
@@ -434,7 +713,10 @@ impl EventQuery {
         // than those pulled in earlier.
         //
         // We can enforce the result limit after this has finished, as the result set will sort
-        // itself.
+        // itself. The offset is likewise only applied once sorted, so that a group straddling a
+        // page boundary is split consistently between the pages either side of it.
+        let result_limit = self.offset + self.limit;
+
         let mut previous_dtstart_timestamp = None;
 
         for event in sorted_events {
@@ -443,7 +725,7 @@ impl EventQuery {
                     dtstart_timestamp != event.schedule_properties.get_dtstart_timestamp()
                 });
 
-            if is_unique_dtstart_timestamp && query_results.len() >= self.limit {
+            if is_unique_dtstart_timestamp && query_results.len() >= result_limit {
                 break;
             }
 
@@ -452,7 +734,8 @@ impl EventQuery {
             query_results.push(*event.to_owned());
         }
 
-        query_results.truncate(self.limit);
+        query_results.truncate(result_limit);
+        query_results.skip(self.offset);
 
         Ok(())
     }
@@ -463,23 +746,27 @@ impl EventQuery {
         calendar: &Calendar,
         query_results: &mut QueryResults<Event>,
         where_conditional_result: &Option<InvertedCalendarIndexTerm>,
+        execution_deadline: &ExecutionDeadline,
     ) -> Result<(), String> {
         let lower_bound_filter_condition = self.get_lower_bound_filter_condition();
         let upper_bound_filter_condition = self.get_upper_bound_filter_condition();
 
-        'outer: for (point, _distance) in calendar
+        'outer: for (_point, indexed_term, _distance) in calendar
             .indexed_geo
-            .coords
-            .nearest_neighbor_iter_with_distance_2(&geo_point.to_point())
+            .nearest_neighbor_iter(geo_point)
         {
+            execution_deadline.check()?;
+
             let current_inverted_index_calendar_term = match where_conditional_result {
                 Some(inverted_calendar_index_term) => {
-                    InvertedCalendarIndexTerm::merge_and(&point.data, inverted_calendar_index_term)
+                    InvertedCalendarIndexTerm::merge_and(indexed_term, inverted_calendar_index_term)
                 }
 
-                None => point.data.to_owned(),
+                None => indexed_term.to_owned(),
             };
 
+            let mut point_events = Vec::new();
+
             for (event_uid, indexed_conclusion) in &current_inverted_index_calendar_term.events {
                 // We only care about matching base properties defined on the Event, not any
                 // occurrence specific overrides - we are querying the events not the event
@@ -491,25 +778,58 @@ impl EventQuery {
                     continue;
                 }
 
-                let Some(event) = calendar.events.get(event_uid) else {
+                let Some(event) = calendar.events.get(event_uid.as_str()) else {
                     // TODO: handle missing indexed event...
                     continue;
                 };
 
                 if self.is_event_within_bound_filter_conditions(event, &lower_bound_filter_condition, &upper_bound_filter_condition) {
-                    if query_results.len() >= self.limit {
-                        break 'outer;
-                    }
+                    point_events.push(event);
+                }
+            }
 
-                    // TODO: Consider maybe reusing the distance available from iterator instead of
-                    //       wastefully re-calculating it.
-                    query_results.push(*event.to_owned());
+            // Events sharing the same point are pushed in result order (dtstart, then UID) rather
+            // than (unordered) index order, so that they are consistently offset and limited.
+            point_events.sort_by(|event, other_event| {
+                event.schedule_properties.get_dtstart_timestamp()
+                    .cmp(&other_event.schedule_properties.get_dtstart_timestamp())
+                    .then_with(|| event.uid.uid.0.cmp(&other_event.uid.uid.0))
+            });
+
+            for event in point_events {
+                if query_results.len() >= self.limit {
+                    break 'outer;
                 }
+
+                // TODO: Consider maybe reusing the distance available from iterator instead of
+                //       wastefully re-calculating it.
+                query_results.push(*event.to_owned());
             }
         }
 
         Ok(())
     }
+
+    fn execute_for_score_ordering(
+        &self,
+        calendar: &Calendar,
+        query_results: &mut QueryResults<Event>,
+        where_conditional_result: &Option<InvertedCalendarIndexTerm>,
+        execution_deadline: &ExecutionDeadline,
+    ) -> Result<(), String> {
+        let sorted_events = self.populate_sorted_vec_for_dtstart_ordering(calendar, where_conditional_result, execution_deadline)?;
+
+        // Every Event could potentially score higher than those already collected, so we include
+        // them all and let the result set sort itself before applying the offset and limit.
+        for event in sorted_events {
+            query_results.push(*event.to_owned());
+            query_results.truncate(self.offset + self.limit);
+        }
+
+        query_results.skip(self.offset);
+
+        Ok(())
+    }
 }
 
 impl Default for EventQuery {
@@ -519,10 +839,14 @@ impl Default for EventQuery {
             ordering_condition: OrderingCondition::DtStart,
             lower_bound_range_condition: None,
             upper_bound_range_condition: None,
+            duration_range_conditions: Vec::new(),
             in_timezone: Tz::UTC,
             distinct_uids: false,
             offset: 0,
             limit: 50,
+            return_properties: None,
+            count_only: false,
+            warnings: Vec::new(),
         }
     }
 }
@@ -539,7 +863,7 @@ mod test {
         LowerBoundRangeCondition, RangeConditionProperty, UpperBoundRangeCondition,
     };
 
-    use crate::{GeoPoint, KeyValuePair};
+    use crate::{GeoPoint, InternedString, KeyValuePair};
     use pretty_assertions_sorted::{assert_eq, assert_eq_sorted};
 
     use std::collections::{HashSet, HashMap};
@@ -564,19 +888,16 @@ mod test {
             accessor.search_uid_index("EVENT_ONE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("EVENT_ONE"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_ONE"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
 
-        // TODO: Shouldn't this return an empty event set?
         // Positive matching: term does not exist
         assert_eq!(
             accessor.search_uid_index("EVENT_FOUR"),
             InvertedCalendarIndexTerm {
-                events: HashMap::from([
-                    (String::from("EVENT_FOUR"), IndexedConclusion::Include(None)),
-                ]),
+                events: HashMap::new(),
             }
         );
 
@@ -585,8 +906,8 @@ mod test {
             accessor.search_not_uid_index("EVENT_ONE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("EVENT_TWO"), IndexedConclusion::Include(None)),
-                    (String::from("EVENT_THREE"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_TWO"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_THREE"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -596,9 +917,9 @@ mod test {
             accessor.search_not_uid_index("EVENT_FOUR"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("EVENT_ONE"), IndexedConclusion::Include(None)),
-                    (String::from("EVENT_TWO"), IndexedConclusion::Include(None)),
-                    (String::from("EVENT_THREE"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_ONE"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_TWO"), IndexedConclusion::Include(None)),
+                    (InternedString::from("EVENT_THREE"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -612,17 +933,17 @@ mod test {
             (
                 String::from("ONLINE"),
                 [
-                    (String::from("All online"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly online"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly in person"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All online"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly online"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly in person"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
             (
                 String::from("IN-PERSON"),
                 [
-                    (String::from("All in person"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in person"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly online"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All in person"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in person"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly online"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             )
         ];
@@ -647,15 +968,15 @@ mod test {
             String::from("Other event 2"),
         ];
 
-        let accessor = EventQueryIndexAccessor { calendar: &calendar, event_uids };
+        let accessor = EventQueryIndexAccessor { calendar: &calendar, event_uids, case_insensitive: false };
 
         // Positive matching: term exists
         assert_eq!(
             accessor.search_location_type_index("ONLINE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All online"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly online"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All online"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly online"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -673,10 +994,10 @@ mod test {
             accessor.search_not_location_type_index("ONLINE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All in person"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in person"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All in person"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in person"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ])
             }
         );
@@ -686,12 +1007,12 @@ mod test {
             accessor.search_not_categories_index("FOOBAR"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All online"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly online"), IndexedConclusion::Include(None)),
-                    (String::from("All in person"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in person"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All online"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly online"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All in person"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in person"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -705,17 +1026,17 @@ mod test {
             (
                 String::from("Adults"),
                 [
-                    (String::from("All adults"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly adults"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly kids"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All adults"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly adults"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly kids"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
             (
                 String::from("Kids"),
                 [
-                    (String::from("All kids"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly kids"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly adults"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All kids"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly kids"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly adults"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
         ];
@@ -740,15 +1061,15 @@ mod test {
             String::from("Other event 2"),
         ];
 
-        let accessor = EventQueryIndexAccessor { calendar: &calendar, event_uids };
+        let accessor = EventQueryIndexAccessor { calendar: &calendar, event_uids, case_insensitive: false };
 
         // Positive matching: term exists
         assert_eq!(
             accessor.search_categories_index("Kids"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All kids"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly kids"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All kids"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly kids"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -766,10 +1087,10 @@ mod test {
             accessor.search_not_categories_index("Kids"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All adults"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly adults"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All adults"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly adults"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ])
             }
         );
@@ -779,12 +1100,12 @@ mod test {
             accessor.search_not_categories_index("FOOBAR"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All adults"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly adults"), IndexedConclusion::Include(None)),
-                    (String::from("All kids"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly kids"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All adults"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly adults"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All kids"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly kids"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -801,9 +1122,9 @@ mod test {
                     String::from("account-1"),
                 ),
                 [
-                    (String::from("All account-1"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-1"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly account-2"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All account-1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-1"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly account-2"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
             (
@@ -812,9 +1133,9 @@ mod test {
                     String::from("account-2"),
                 ),
                 [
-                    (String::from("All account-2"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-2"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly account-1"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All account-2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-2"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly account-1"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
         ];
@@ -839,7 +1160,7 @@ mod test {
             String::from("Other event 2"),
         ];
 
-        let accessor = EventQueryIndexAccessor { calendar: &calendar, event_uids };
+        let accessor = EventQueryIndexAccessor { calendar: &calendar, event_uids, case_insensitive: false };
 
         // Positive matching: term exists
         assert_eq!(
@@ -851,8 +1172,8 @@ mod test {
             ),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All account-1"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All account-1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-1"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -880,10 +1201,10 @@ mod test {
             ),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All account-2"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-2"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All account-2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ])
             }
         );
@@ -898,17 +1219,133 @@ mod test {
             ),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All account-1"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-1"), IndexedConclusion::Include(None)),
-                    (String::from("All account-2"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly account-2"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All account-1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All account-2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly account-2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
     }
 
+    #[test]
+    fn test_transitive_related_to_index_retrieval() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        // PROGRAMME -> COURSE_ONE -> SESSION_ONE -> ACTIVITY_ONE -> PROGRAMME (cyclic)
+        //           -> COURSE_TWO
+        let indexed_related_to = [
+            ("COURSE_ONE", "PROGRAMME"),
+            ("COURSE_TWO", "PROGRAMME"),
+            ("SESSION_ONE", "COURSE_ONE"),
+            ("ACTIVITY_ONE", "SESSION_ONE"),
+            ("PROGRAMME", "ACTIVITY_ONE"),
+        ];
+
+        for (event_uid, parent_uid) in indexed_related_to {
+            calendar.indexed_related_to.insert(
+                event_uid.to_string(),
+                KeyValuePair::new(String::from("PARENT"), parent_uid.to_string()),
+                &IndexedConclusion::Include(None),
+            ).unwrap();
+        }
+
+        let accessor = EventQueryIndexAccessor::new(&calendar);
+
+        let transitive_related_event_uids = |uid: &str, depth: usize| -> Vec<String> {
+            let mut event_uids: Vec<String> =
+                accessor
+                    .search_transitive_related_to_index(&KeyValuePair::new(String::from("PARENT"), uid.to_string()), depth)
+                    .events
+                    .keys()
+                    .map(|event_uid| event_uid.to_string())
+                    .collect();
+
+            event_uids.sort();
+
+            event_uids
+        };
+
+        assert_eq!(
+            transitive_related_event_uids("PROGRAMME", 1),
+            vec![String::from("COURSE_ONE"), String::from("COURSE_TWO")],
+        );
+
+        assert_eq!(
+            transitive_related_event_uids("PROGRAMME", 2),
+            vec![String::from("COURSE_ONE"), String::from("COURSE_TWO"), String::from("SESSION_ONE")],
+        );
+
+        assert_eq!(
+            transitive_related_event_uids("PROGRAMME", 3),
+            vec![String::from("ACTIVITY_ONE"), String::from("COURSE_ONE"), String::from("COURSE_TWO"), String::from("SESSION_ONE")],
+        );
+
+        // Assert cyclic relations terminate (the root itself is matched as it relates back to it).
+        assert_eq!(
+            transitive_related_event_uids("PROGRAMME", 100),
+            vec![
+                String::from("ACTIVITY_ONE"),
+                String::from("COURSE_ONE"),
+                String::from("COURSE_TWO"),
+                String::from("PROGRAMME"),
+                String::from("SESSION_ONE"),
+            ],
+        );
+
+        assert_eq!(
+            transitive_related_event_uids("COURSE_TWO", 3),
+            Vec::<String>::new(),
+        );
+    }
+
+    #[test]
+    fn test_categories_prefix_index_retrieval() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        let indexed_categories = [
+            ("FOOTBALL_EVENT", "SPORT_FOOTBALL", IndexedConclusion::Include(None)),
+            ("TENNIS_EVENT", "SPORT_TENNIS", IndexedConclusion::Include(Some(HashSet::from([100])))),
+            ("OVERRIDDEN_TENNIS_EVENT", "SPORT_TENNIS", IndexedConclusion::Exclude(Some(HashSet::from([100])))),
+            ("MUSIC_EVENT", "MUSIC", IndexedConclusion::Include(None)),
+            ("SPORTSWEAR_EVENT", "SPORTSWEAR", IndexedConclusion::Include(None)),
+        ];
+
+        for (event_uid, category, indexed_conclusion) in indexed_categories {
+            calendar.indexed_categories.insert(event_uid, category, &indexed_conclusion).unwrap();
+        }
+
+        let accessor = EventQueryIndexAccessor::new(&calendar);
+
+        assert_eq!(
+            accessor.search_categories_prefix_index("SPORT_"),
+            InvertedCalendarIndexTerm {
+                events: HashMap::from([
+                    (InternedString::from("FOOTBALL_EVENT"), IndexedConclusion::Include(None)),
+                    (InternedString::from("TENNIS_EVENT"), IndexedConclusion::Include(None)),
+                ]),
+            },
+        );
+
+        assert_eq!(
+            accessor.search_categories_prefix_index("SPORT"),
+            InvertedCalendarIndexTerm {
+                events: HashMap::from([
+                    (InternedString::from("FOOTBALL_EVENT"), IndexedConclusion::Include(None)),
+                    (InternedString::from("TENNIS_EVENT"), IndexedConclusion::Include(None)),
+                    (InternedString::from("SPORTSWEAR_EVENT"), IndexedConclusion::Include(None)),
+                ]),
+            },
+        );
+
+        assert_eq!(
+            accessor.search_categories_prefix_index("ART"),
+            InvertedCalendarIndexTerm::new(),
+        );
+    }
+
     #[test]
     fn test_geo_index_retrieval() {
         let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
@@ -921,17 +1358,17 @@ mod test {
             (
                 LONDON,
                 [
-                    (String::from("All in London"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in London"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly in Oxford"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All in London"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in London"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly in Oxford"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
             (
                 OXFORD,
                 [
-                    (String::from("All in Oxford"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in Oxford"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly in London"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All in Oxford"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in Oxford"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly in London"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
         ];
@@ -956,7 +1393,7 @@ mod test {
             String::from("Other event 2"),
         ];
 
-        let accessor = EventQueryIndexAccessor { calendar: &calendar, event_uids };
+        let accessor = EventQueryIndexAccessor { calendar: &calendar, event_uids, case_insensitive: false };
 
         let search_distance = GeoDistance::new_from_miles_float(10.0_f64);
 
@@ -965,8 +1402,8 @@ mod test {
             accessor.search_geo_index(&search_distance, &OXFORD),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All in Oxford"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in Oxford"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All in Oxford"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in Oxford"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -984,10 +1421,10 @@ mod test {
             accessor.search_not_geo_index(&search_distance, &OXFORD),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All in London"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in London"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All in London"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in London"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ])
             }
         );
@@ -997,12 +1434,12 @@ mod test {
             accessor.search_not_geo_index(&search_distance, &NEW_YORK_CITY),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All in London"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in London"), IndexedConclusion::Include(None)),
-                    (String::from("All in Oxford"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly in Oxford"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All in London"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in London"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All in Oxford"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly in Oxford"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -1016,17 +1453,17 @@ mod test {
             (
                 String::from("PUBLIC"),
                 [
-                    (String::from("All public"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly public"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly private"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All public"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly public"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly private"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
             (
                 String::from("PRIVATE"),
                 [
-                    (String::from("All private"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly private"), IndexedConclusion::Include(Some([100].into()))),
-                    (String::from("Mostly public"), IndexedConclusion::Exclude(Some([100].into()))),
+                    (InternedString::from("All private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly private"), IndexedConclusion::Include(Some([100].into()))),
+                    (InternedString::from("Mostly public"), IndexedConclusion::Exclude(Some([100].into()))),
                 ]
             ),
         ];
@@ -1051,15 +1488,15 @@ mod test {
             String::from("Other event 2"),
         ];
 
-        let accessor = EventQueryIndexAccessor { calendar: &calendar, event_uids };
+        let accessor = EventQueryIndexAccessor { calendar: &calendar, event_uids, case_insensitive: false };
 
         // Positive matching: term exists
         assert_eq!(
             accessor.search_class_index("PRIVATE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All private"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly private"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -1077,10 +1514,10 @@ mod test {
             accessor.search_not_class_index("PUBLIC"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All private"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly private"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ])
             }
         );
@@ -1090,12 +1527,12 @@ mod test {
             accessor.search_not_class_index("FOOBAR"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
-                    (String::from("All public"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly public"), IndexedConclusion::Include(None)),
-                    (String::from("All private"), IndexedConclusion::Include(None)),
-                    (String::from("Mostly private"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 1"), IndexedConclusion::Include(None)),
-                    (String::from("Other event 2"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All public"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly public"), IndexedConclusion::Include(None)),
+                    (InternedString::from("All private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Mostly private"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 1"), IndexedConclusion::Include(None)),
+                    (InternedString::from("Other event 2"), IndexedConclusion::Include(None)),
                 ]),
             }
         );
@@ -1172,9 +1609,10 @@ mod test {
 
         let event_query_index_accessor = EventQueryIndexAccessor::new(&calendar);
 
+        // Test UID not present within the calendar.
         assert_eq_sorted!(
             event_query_index_accessor.search_uid_index("UID"),
-            InvertedCalendarIndexTerm::new_with_event(String::from("UID"), IndexedConclusion::Include(None)),
+            InvertedCalendarIndexTerm::new(),
         );
 
         // Test existing calendar categories index entry.
@@ -1182,8 +1620,8 @@ mod test {
             event_query_index_accessor.search_categories_index("CATEGORY"),
             InvertedCalendarIndexTerm::new_with_events(
                 vec![
-                    (String::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
-                    (String::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(None)),
+                    (InternedString::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
+                    (InternedString::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(None)),
                 ]
             ),
         );
@@ -1199,8 +1637,8 @@ mod test {
             event_query_index_accessor.search_location_type_index("ONLINE"),
             InvertedCalendarIndexTerm::new_with_events(
                 vec![
-                    (String::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
-                    (String::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(None)),
+                    (InternedString::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
+                    (InternedString::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(None)),
                 ]
             ),
         );
@@ -1218,8 +1656,8 @@ mod test {
             ),
             InvertedCalendarIndexTerm::new_with_events(
                 vec![
-                    (String::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
-                    (String::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(None)),
+                    (InternedString::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
+                    (InternedString::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(None)),
                 ]
             ),
         );
@@ -1240,8 +1678,8 @@ mod test {
             ),
             InvertedCalendarIndexTerm::new_with_events(
                 vec![
-                    (String::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
-                    (String::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(None)),
+                    (InternedString::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
+                    (InternedString::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(None)),
                 ]
             ),
         );
@@ -1260,8 +1698,8 @@ mod test {
             event_query_index_accessor.search_class_index("PUBLIC"),
             InvertedCalendarIndexTerm::new_with_events(
                 vec![
-                    (String::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
-                    (String::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(None)),
+                    (InternedString::from("FULLY_INCLUDED_EVENT_UID"),     IndexedConclusion::Include(None)),
+                    (InternedString::from("PARTIALLY_INCLUDED_EVENT_UID"), IndexedConclusion::Include(None)),
                 ]
             ),
         );
@@ -1333,6 +1771,7 @@ mod test {
 
                 lower_bound_range_condition: Some(LowerBoundRangeCondition::GreaterThan(RangeConditionProperty::DtStart(875779200))),
                 upper_bound_range_condition: Some(UpperBoundRangeCondition::LessEqualThan(RangeConditionProperty::DtStart(878461200))),
+                duration_range_conditions: Vec::new(),
 
                 in_timezone: chrono_tz::Tz::Europe__Vilnius,
 
@@ -1340,6 +1779,9 @@ mod test {
 
                 offset: 0,
                 limit: 50,
+                return_properties: None,
+                count_only: false,
+                warnings: Vec::new(),
             })
         );
     }
@@ -0,0 +1,232 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use redical_ical::{ICalendarComponent, RenderingContext};
+use redical_ical::content_line::ContentLine;
+
+use crate::{Calendar, EventInstance};
+use crate::queries::query::Query;
+use crate::queries::results::{QueryableEntity, QueryResults};
+use crate::queries::results_ordering::QueryResultOrdering;
+
+/// The event instances of a single event returned by a query (`X-GROUP-BY:UID`), with the
+/// properties of the base event included once rather than repeated in each event instance.
+///
+/// Each event instance only includes the properties differing from the base event (e.g. `DTSTART`,
+/// `RECURRENCE-ID`, and anything overridden), with any property included replacing all those of
+/// the same name on the base event.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EventInstanceGroup {
+    pub uid: String,
+    pub base_properties: BTreeSet<ContentLine>,
+    pub event_instances: Vec<(QueryResultOrdering, BTreeSet<ContentLine>)>,
+}
+
+impl EventInstanceGroup {
+    /// Restricts the properties of the base event and each event instance to those returned by
+    /// the query (`X-RETURN`).
+    pub fn project_content_lines<Q: Query<EventInstance>>(mut self, query: &Q) -> Self {
+        self.base_properties = query.project_content_lines(self.base_properties);
+
+        self.event_instances =
+            self.event_instances
+                .into_iter()
+                .map(|(result_ordering, event_instance_properties)| {
+                    (result_ordering, query.project_content_lines(event_instance_properties))
+                })
+                .collect();
+
+        self
+    }
+}
+
+// Group the content lines by their property name, retaining their order.
+fn group_content_lines_by_name(content_lines: &BTreeSet<ContentLine>) -> BTreeMap<&str, Vec<&ContentLine>> {
+    let mut grouped_content_lines: BTreeMap<&str, Vec<&ContentLine>> = BTreeMap::new();
+
+    for content_line in content_lines {
+        grouped_content_lines
+            .entry(content_line.0.as_str())
+            .or_default()
+            .push(content_line);
+    }
+
+    grouped_content_lines
+}
+
+// Exclude each property of the event instance identical to that of the base event (all content
+// lines sharing the property name are compared, so multi-valued properties are kept intact).
+fn exclude_base_properties(event_instance_properties: BTreeSet<ContentLine>, base_properties: &BTreeSet<ContentLine>) -> BTreeSet<ContentLine> {
+    let grouped_base_properties = group_content_lines_by_name(base_properties);
+
+    let excluded_property_names: Vec<String> =
+        group_content_lines_by_name(&event_instance_properties)
+            .into_iter()
+            .filter(|(name, content_lines)| grouped_base_properties.get(name) == Some(content_lines))
+            .map(|(name, _content_lines)| name.to_owned())
+            .collect();
+
+    event_instance_properties
+        .into_iter()
+        .filter(|content_line| !excluded_property_names.contains(&content_line.0))
+        .collect()
+}
+
+/// Groups the event instance query results by their event UID, retaining the order of the results
+/// (with each group ordered by its first event instance).
+pub fn group_event_instances_by_uid(
+    calendar: &Calendar,
+    query_results: &QueryResults<EventInstance>,
+    rendering_context: Option<&RenderingContext>,
+) -> Vec<EventInstanceGroup> {
+    let mut event_instance_groups: Vec<EventInstanceGroup> = Vec::new();
+    let mut event_instance_group_indexes: HashMap<String, usize> = HashMap::new();
+
+    for query_result in &query_results.results {
+        let uid = query_result.result.get_uid();
+
+        let event_instance_group_index =
+            *event_instance_group_indexes
+                .entry(uid.to_owned())
+                .or_insert_with(|| {
+                    let base_properties =
+                        calendar.events
+                            .get(&uid)
+                            .map(|event| event.to_content_line_set_with_context(rendering_context))
+                            .unwrap_or_default();
+
+                    event_instance_groups.push(
+                        EventInstanceGroup {
+                            uid: uid.to_owned(),
+                            base_properties,
+                            event_instances: Vec::new(),
+                        }
+                    );
+
+                    event_instance_groups.len() - 1
+                });
+
+        let event_instance_group = &mut event_instance_groups[event_instance_group_index];
+
+        let event_instance_properties =
+            exclude_base_properties(
+                query_result.result.to_content_line_set_with_context(rendering_context),
+                &event_instance_group.base_properties,
+            );
+
+        event_instance_group.event_instances.push((query_result.result_ordering.to_owned(), event_instance_properties));
+    }
+
+    event_instance_groups
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::str::FromStr;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use crate::EventOccurrenceOverride;
+    use crate::testing::utils::build_event_from_ical;
+    use crate::queries::event_instance_query::EventInstanceQuery;
+
+    use redical_ical::ICalendarEntity;
+
+    fn render_content_lines(content_lines: &BTreeSet<ContentLine>) -> Vec<String> {
+        content_lines
+            .iter()
+            .map(|content_line| content_line.render_ical())
+            .collect()
+    }
+
+    #[test]
+    fn test_group_event_instances_by_uid() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        let mut event_one = build_event_from_ical("EVENT_ONE", vec!["DTSTART:20210105T090000Z RRULE:FREQ=DAILY;INTERVAL=1;COUNT=3 SUMMARY:Event one LAST-MODIFIED:20210501T090000Z"]);
+
+        event_one.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210106T090000Z", "SUMMARY:Overridden event one LAST-MODIFIED:20210501T090000Z").unwrap(),
+            false,
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(event_one).unwrap();
+
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_TWO", vec!["DTSTART:20210105T120000Z SUMMARY:Event two LAST-MODIFIED:20210501T090000Z"])
+        ).unwrap();
+
+        let mut query = EventInstanceQuery::from_str("X-GROUP-BY:UID X-LIMIT:10").unwrap();
+
+        assert!(query.group_by_uid);
+
+        let query_results = query.execute(&calendar).unwrap();
+
+        let event_instance_groups = group_event_instances_by_uid(&calendar, &query_results, None);
+
+        assert_eq!(
+            event_instance_groups
+                .iter()
+                .map(|event_instance_group| {
+                    (
+                        event_instance_group.uid.to_owned(),
+                        render_content_lines(&event_instance_group.base_properties),
+                        event_instance_group.event_instances
+                            .iter()
+                            .map(|(_result_ordering, event_instance_properties)| render_content_lines(event_instance_properties))
+                            .collect::<Vec<Vec<String>>>(),
+                    )
+                })
+                .collect::<Vec<(String, Vec<String>, Vec<Vec<String>>)>>(),
+            vec![
+                (
+                    String::from("EVENT_ONE"),
+                    vec![
+                        String::from("DTSTART:20210105T090000Z"),
+                        String::from("LAST-MODIFIED:20210501T090000Z"),
+                        String::from("RRULE:COUNT=3;FREQ=DAILY;INTERVAL=1"),
+                        String::from("SUMMARY:Event one"),
+                        String::from("UID:EVENT_ONE"),
+                    ],
+                    vec![
+                        vec![
+                            String::from("DTEND:20210105T090000Z"),
+                            String::from("DURATION:PT0S"),
+                            String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210105T090000Z"),
+                        ],
+                        vec![
+                            String::from("DTEND:20210106T090000Z"),
+                            String::from("DTSTART:20210106T090000Z"),
+                            String::from("DURATION:PT0S"),
+                            String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210106T090000Z"),
+                            String::from("SUMMARY:Overridden event one"),
+                        ],
+                        vec![
+                            String::from("DTEND:20210107T090000Z"),
+                            String::from("DTSTART:20210107T090000Z"),
+                            String::from("DURATION:PT0S"),
+                            String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210107T090000Z"),
+                        ],
+                    ],
+                ),
+                (
+                    String::from("EVENT_TWO"),
+                    vec![
+                        String::from("DTSTART:20210105T120000Z"),
+                        String::from("LAST-MODIFIED:20210501T090000Z"),
+                        String::from("SUMMARY:Event two"),
+                        String::from("UID:EVENT_TWO"),
+                    ],
+                    vec![
+                        vec![
+                            String::from("DTEND:20210105T120000Z"),
+                            String::from("DURATION:PT0S"),
+                            String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210105T120000Z"),
+                        ],
+                    ],
+                ),
+            ],
+        );
+    }
+}
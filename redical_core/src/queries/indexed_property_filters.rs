@@ -1,8 +1,12 @@
-use crate::{GeoDistance, GeoPoint, InvertedCalendarIndexTerm, KeyValuePair};
+use geo::HaversineDistance;
+
+use crate::{ExecutionDeadline, GeoDistance, GeoPoint, IndexedProperties, InvertedCalendarIndexTerm, KeyValuePair, PassiveProperties};
 
 use redical_ical::values::where_operator as ical_where_operator;
 
 use crate::queries::query::QueryIndexAccessor;
+use crate::queries::query_regex::compile_term_regex;
+use crate::queries::results_ordering::RelevanceScore;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum WhereOperator {
@@ -25,9 +29,32 @@ impl WhereOperator {
         where_conditional_a: &WhereConditional,
         where_conditional_b: &WhereConditional,
         query_index_accessor: &impl QueryIndexAccessor<'cal>,
+        execution_deadline: &ExecutionDeadline,
     ) -> Result<InvertedCalendarIndexTerm, String> {
-        let inverted_calendar_index_term_a = &where_conditional_a.execute(query_index_accessor)?;
-        let inverted_calendar_index_term_b = &where_conditional_b.execute(query_index_accessor)?;
+        if let WhereOperator::And = &self {
+            if let Some(inverted_calendar_index_term) = Self::execute_geo_refined_and(
+                where_conditional_a,
+                where_conditional_b,
+                query_index_accessor,
+                execution_deadline,
+            )? {
+                return Ok(inverted_calendar_index_term);
+            }
+        }
+
+        let inverted_calendar_index_term_a = &where_conditional_a.execute(query_index_accessor, execution_deadline)?;
+
+        // Nothing can satisfy an `AND` if it's first where conditional resolved to nothing, so the
+        // second (potentially costly, e.g. a GEO search or negated property) is not resolved at
+        // all. As groups just resolve their nested where conditional, the empty term is
+        // propagated through any enclosing groups (short-circuiting any `AND`s they are part of).
+        if let WhereOperator::And = &self {
+            if inverted_calendar_index_term_a.events.is_empty() {
+                return Ok(InvertedCalendarIndexTerm::new());
+            }
+        }
+
+        let inverted_calendar_index_term_b = &where_conditional_b.execute(query_index_accessor, execution_deadline)?;
 
         let merged_inverted_calendar_index_term = match &self {
             WhereOperator::Or => InvertedCalendarIndexTerm::merge_or(
@@ -43,6 +70,47 @@ impl WhereOperator {
 
         Ok(merged_inverted_calendar_index_term)
     }
+
+    /// Resolves a GEO property `AND` any other (non GEO) where conditional (e.g. "yoga within
+    /// 10km") by resolving the other where conditional first, and then only refining the distance
+    /// of the candidate events it matched (short-circuiting if there are none), rather than fully
+    /// resolving both and intersecting them afterwards.
+    ///
+    /// Returns None if the where conditionals are not of this shape.
+    pub fn execute_geo_refined_and<'cal>(
+        where_conditional_a: &WhereConditional,
+        where_conditional_b: &WhereConditional,
+        query_index_accessor: &impl QueryIndexAccessor<'cal>,
+        execution_deadline: &ExecutionDeadline,
+    ) -> Result<Option<InvertedCalendarIndexTerm>, String> {
+        let (candidate_where_conditional, (distance, long_lat), geo_first) =
+            match (where_conditional_a.get_geo_property(), where_conditional_b.get_geo_property()) {
+                (None, Some(geo_property)) => (where_conditional_a, geo_property, false),
+                (Some(geo_property), None) => (where_conditional_b, geo_property, true),
+
+                _ => return Ok(None),
+            };
+
+        let candidate_inverted_calendar_index_term = candidate_where_conditional.execute(query_index_accessor, execution_deadline)?;
+
+        if candidate_inverted_calendar_index_term.events.is_empty() {
+            return Ok(Some(candidate_inverted_calendar_index_term));
+        }
+
+        let geo_inverted_calendar_index_term = query_index_accessor.search_geo_index_within_candidates(
+            distance,
+            long_lat,
+            &candidate_inverted_calendar_index_term,
+        );
+
+        let merged_inverted_calendar_index_term = if geo_first {
+            InvertedCalendarIndexTerm::merge_and(&geo_inverted_calendar_index_term, &candidate_inverted_calendar_index_term)
+        } else {
+            InvertedCalendarIndexTerm::merge_and(&candidate_inverted_calendar_index_term, &geo_inverted_calendar_index_term)
+        };
+
+        Ok(Some(merged_inverted_calendar_index_term))
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -58,10 +126,15 @@ pub enum WhereConditional {
 }
 
 impl WhereConditional {
+    /// Resolves the where conditional into the matching index term, aborting with an error if the
+    /// execution deadline is exceeded part way through.
     pub fn execute<'cal>(
         &self,
-        query_index_accessor: &impl QueryIndexAccessor<'cal>
+        query_index_accessor: &impl QueryIndexAccessor<'cal>,
+        execution_deadline: &ExecutionDeadline,
     ) -> Result<InvertedCalendarIndexTerm, String> {
+        execution_deadline.check()?;
+
         match self {
             WhereConditional::Property(where_conditional_property) => {
                 let inverted_calendar_index_term = where_conditional_property.execute(
@@ -87,7 +160,8 @@ impl WhereConditional {
                 let inverted_calendar_index_term = where_operator.execute(
                     where_conditional_a,
                     where_conditional_b,
-                    query_index_accessor
+                    query_index_accessor,
+                    execution_deadline,
                 )?;
 
                 Ok(inverted_calendar_index_term)
@@ -95,36 +169,125 @@ impl WhereConditional {
 
             WhereConditional::Group(where_conditional) => {
                 let inverted_calendar_index_term = where_conditional.execute(
-                    query_index_accessor
+                    query_index_accessor,
+                    execution_deadline,
                 )?;
 
                 Ok(inverted_calendar_index_term)
             }
         }
     }
+
+    /// The distance and point of a (non negated) GEO property where conditional, looking through
+    /// any groups wrapping it.
+    pub fn get_geo_property(&self) -> Option<(&GeoDistance, &GeoPoint)> {
+        match self {
+            WhereConditional::Property(WhereConditionalProperty::Geo(distance, long_lat)) => Some((distance, long_lat)),
+
+            WhereConditional::Group(where_conditional) => where_conditional.get_geo_property(),
+
+            _ => None,
+        }
+    }
+
+    /// Calculates the relevance score of a matched result (either an event or event instance) by
+    /// accumulating the weight of each where conditional property it satisfies, so that results
+    /// satisfying more branches of an `OR` rank higher.
+    ///
+    /// Negated properties are purely filters and do not contribute to the score.
+    pub fn score(&self, uid: &String, indexed_properties: &IndexedProperties, passive_properties: &PassiveProperties) -> RelevanceScore {
+        match self {
+            WhereConditional::Property(where_conditional_property) => {
+                where_conditional_property.score(uid, indexed_properties, passive_properties)
+            }
+
+            WhereConditional::NegatedProperty(_) => RelevanceScore::default(),
+
+            WhereConditional::Operator(where_conditional_a, where_conditional_b, _) => {
+                where_conditional_a.score(uid, indexed_properties, passive_properties) + where_conditional_b.score(uid, indexed_properties, passive_properties)
+            }
+
+            WhereConditional::Group(where_conditional) => {
+                where_conditional.score(uid, indexed_properties, passive_properties)
+            }
+        }
+    }
+
+    /// The highest relevance score achievable by any result (every property satisfied).
+    pub fn max_score(&self) -> RelevanceScore {
+        match self {
+            WhereConditional::Property(_) => RelevanceScore::new_from_float(1_f64),
+
+            WhereConditional::NegatedProperty(_) => RelevanceScore::default(),
+
+            WhereConditional::Operator(where_conditional_a, where_conditional_b, _) => {
+                where_conditional_a.max_score() + where_conditional_b.max_score()
+            }
+
+            WhereConditional::Group(where_conditional) => where_conditional.max_score(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum WhereConditionalProperty {
     UID(String),
+    UIDPrefix(String),
+    UIDRegex(String),
     Categories(String),
+    CategoriesPrefix(String),
+    CategoriesRegex(String),
     LocationType(String),
     RelatedTo(KeyValuePair),
+    TransitiveRelatedTo(KeyValuePair, usize),
     Geo(GeoDistance, GeoPoint),
     Class(String),
+    ClassRegex(String),
+    Location(String),
+    XProperty(KeyValuePair),
 }
 
 impl WhereConditionalProperty {
+    /// The name of the index this property is resolved against, and the term it is searched for
+    /// (e.g. `("CATEGORIES", "ART")`), as described in query explanations and warnings.
+    pub fn get_index_and_term(&self) -> (&'static str, String) {
+        match self {
+            WhereConditionalProperty::UID(uid) => ("UID", uid.to_owned()),
+            WhereConditionalProperty::UIDPrefix(uid_prefix) => ("UID", format!("{uid_prefix}*")),
+            WhereConditionalProperty::UIDRegex(uid_pattern) => ("UID", format!("{uid_pattern};OP=REGEX")),
+            WhereConditionalProperty::Categories(category) => ("CATEGORIES", category.to_owned()),
+            WhereConditionalProperty::CategoriesPrefix(category_prefix) => ("CATEGORIES", format!("{category_prefix};OP=PREFIX")),
+            WhereConditionalProperty::CategoriesRegex(category_pattern) => ("CATEGORIES", format!("{category_pattern};OP=REGEX")),
+            WhereConditionalProperty::LocationType(location_type) => ("LOCATION-TYPE", location_type.to_owned()),
+            WhereConditionalProperty::RelatedTo(reltype_uids) => ("RELATED-TO", format!("{};{}", reltype_uids.key, reltype_uids.value)),
+            WhereConditionalProperty::TransitiveRelatedTo(reltype_uids, depth) => ("RELATED-TO", format!("{};{};DEPTH={depth}", reltype_uids.key, reltype_uids.value)),
+            WhereConditionalProperty::Geo(distance, long_lat) => ("GEO", format!("{long_lat};{distance}")),
+            WhereConditionalProperty::Class(classification) => ("CLASS", classification.to_owned()),
+            WhereConditionalProperty::ClassRegex(classification_pattern) => ("CLASS", format!("{classification_pattern};OP=REGEX")),
+            WhereConditionalProperty::Location(location) => ("LOCATION", location.to_owned()),
+            WhereConditionalProperty::XProperty(name_value) => ("X-PROP", format!("{};{}", name_value.key, name_value.value)),
+        }
+    }
+
     pub fn execute<'cal>(
         &self,
         query_index_accessor: &impl QueryIndexAccessor<'cal>
     ) -> Result<InvertedCalendarIndexTerm, String> {
         match &self {
-            // For UID, we just return an "include all" consensus for that event UID.
             WhereConditionalProperty::UID(uid) => {
                 Ok(query_index_accessor.search_uid_index(uid))
             },
 
+            WhereConditionalProperty::UIDPrefix(uid_prefix) => {
+                Ok(query_index_accessor.search_uid_prefix_index(uid_prefix))
+            },
+
+            WhereConditionalProperty::UIDRegex(uid_pattern) => {
+                let uid_regex = compile_term_regex(uid_pattern, query_index_accessor.is_term_matching_case_insensitive())?;
+
+                Ok(query_index_accessor.search_uid_regex_index(&uid_regex))
+            },
+
             WhereConditionalProperty::LocationType(location_type) => {
                 Ok(query_index_accessor.search_location_type_index(location_type))
             },
@@ -133,10 +296,24 @@ impl WhereConditionalProperty {
                 Ok(query_index_accessor.search_categories_index(category))
             },
 
+            WhereConditionalProperty::CategoriesPrefix(category_prefix) => {
+                Ok(query_index_accessor.search_categories_prefix_index(category_prefix))
+            },
+
+            WhereConditionalProperty::CategoriesRegex(category_pattern) => {
+                let category_regex = compile_term_regex(category_pattern, query_index_accessor.is_term_matching_case_insensitive())?;
+
+                Ok(query_index_accessor.search_categories_regex_index(&category_regex))
+            },
+
             WhereConditionalProperty::RelatedTo(reltype_uids) => {
                 Ok(query_index_accessor.search_related_to_index(reltype_uids))
             },
 
+            WhereConditionalProperty::TransitiveRelatedTo(reltype_uids, depth) => {
+                Ok(query_index_accessor.search_transitive_related_to_index(reltype_uids, *depth))
+            },
+
             WhereConditionalProperty::Geo(distance, long_lat) => {
                 Ok(query_index_accessor.search_geo_index(distance, long_lat))
             },
@@ -144,6 +321,20 @@ impl WhereConditionalProperty {
             WhereConditionalProperty::Class(classification) => {
                 Ok(query_index_accessor.search_class_index(classification))
             },
+
+            WhereConditionalProperty::ClassRegex(classification_pattern) => {
+                let classification_regex = compile_term_regex(classification_pattern, query_index_accessor.is_term_matching_case_insensitive())?;
+
+                Ok(query_index_accessor.search_class_regex_index(&classification_regex))
+            },
+
+            WhereConditionalProperty::Location(location) => {
+                Ok(query_index_accessor.search_location_index(location))
+            },
+
+            WhereConditionalProperty::XProperty(name_value) => {
+                Ok(query_index_accessor.search_x_property_index(name_value))
+            },
         }
     }
 
@@ -156,6 +347,16 @@ impl WhereConditionalProperty {
                 Ok(query_index_accessor.search_not_uid_index(uid))
             },
 
+            WhereConditionalProperty::UIDPrefix(uid_prefix) => {
+                Ok(query_index_accessor.search_not_uid_prefix_index(uid_prefix))
+            },
+
+            WhereConditionalProperty::UIDRegex(uid_pattern) => {
+                let uid_regex = compile_term_regex(uid_pattern, query_index_accessor.is_term_matching_case_insensitive())?;
+
+                Ok(query_index_accessor.search_not_uid_regex_index(&uid_regex))
+            },
+
             WhereConditionalProperty::LocationType(location_type) => {
                 Ok(query_index_accessor.search_not_location_type_index(location_type))
             },
@@ -164,10 +365,22 @@ impl WhereConditionalProperty {
                 Ok(query_index_accessor.search_not_categories_index(category))
             },
 
+            WhereConditionalProperty::CategoriesPrefix(_category_prefix) => {
+                Err(String::from("Negated CATEGORIES prefix (OP=PREFIX or trailing *) conditions are not supported"))
+            },
+
+            WhereConditionalProperty::CategoriesRegex(_category_pattern) => {
+                Err(String::from("Negated CATEGORIES regex (OP=REGEX) conditions are not supported"))
+            },
+
             WhereConditionalProperty::RelatedTo(reltype_uids) => {
                 Ok(query_index_accessor.search_not_related_to_index(reltype_uids))
             },
 
+            WhereConditionalProperty::TransitiveRelatedTo(_reltype_uids, _depth) => {
+                Err(String::from("Negated transitive RELATED-TO (with DEPTH) conditions are not supported"))
+            },
+
             WhereConditionalProperty::Geo(distance, long_lat) => {
                 Ok(query_index_accessor.search_not_geo_index(distance, long_lat))
             },
@@ -175,6 +388,132 @@ impl WhereConditionalProperty {
             WhereConditionalProperty::Class(classification) => {
                 Ok(query_index_accessor.search_not_class_index(classification))
             },
+
+            WhereConditionalProperty::ClassRegex(_classification_pattern) => {
+                Err(String::from("Negated CLASS regex (OP=REGEX) conditions are not supported"))
+            },
+
+            WhereConditionalProperty::Location(location) => {
+                Ok(query_index_accessor.search_not_location_index(location))
+            },
+
+            WhereConditionalProperty::XProperty(name_value) => {
+                Ok(query_index_accessor.search_not_x_property_index(name_value))
+            },
+        }
+    }
+
+    /// Each satisfied property is worth a single point, except for geo which is scaled by
+    /// proximity (1 point at the exact location, tending to 0 at the edge of the distance).
+    pub fn score(&self, uid: &String, indexed_properties: &IndexedProperties, passive_properties: &PassiveProperties) -> RelevanceScore {
+        let is_satisfied = match &self {
+            WhereConditionalProperty::UID(property_uid) => {
+                property_uid == uid
+            },
+
+            WhereConditionalProperty::UIDPrefix(uid_prefix) => {
+                uid.starts_with(uid_prefix)
+            },
+
+            WhereConditionalProperty::UIDRegex(uid_pattern) => {
+                compile_term_regex(uid_pattern, false)
+                    .is_ok_and(|uid_regex| uid_regex.is_match(uid))
+            },
+
+            WhereConditionalProperty::LocationType(location_type) => {
+                indexed_properties
+                    .extract_all_location_type_strings()
+                    .is_some_and(|location_types| location_types.contains(location_type))
+            },
+
+            WhereConditionalProperty::Categories(category) => {
+                indexed_properties
+                    .extract_all_category_strings()
+                    .is_some_and(|categories| categories.contains(category))
+            },
+
+            WhereConditionalProperty::CategoriesPrefix(category_prefix) => {
+                indexed_properties
+                    .extract_all_category_strings()
+                    .is_some_and(|categories| categories.iter().any(|category| category.starts_with(category_prefix)))
+            },
+
+            WhereConditionalProperty::CategoriesRegex(category_pattern) => {
+                let Ok(category_regex) = compile_term_regex(category_pattern, false) else {
+                    return RelevanceScore::default();
+                };
+
+                indexed_properties
+                    .extract_all_category_strings()
+                    .is_some_and(|categories| categories.iter().any(|category| category_regex.is_match(category)))
+            },
+
+            WhereConditionalProperty::RelatedTo(reltype_uids) => {
+                indexed_properties
+                    .extract_all_related_to_key_value_pairs()
+                    .is_some_and(|related_to| related_to.contains(reltype_uids))
+            },
+
+            // Whether the result is related to the UID indirectly (via the events in between) is
+            // only known to the index, so any relation of the same RELTYPE is deemed satisfying.
+            WhereConditionalProperty::TransitiveRelatedTo(reltype_uids, _depth) => {
+                indexed_properties
+                    .extract_all_related_to_key_value_pairs()
+                    .is_some_and(|related_to| related_to.iter().any(|related_to| related_to.key == reltype_uids.key))
+            },
+
+            WhereConditionalProperty::Geo(distance, long_lat) => {
+                let Some(geo_point) = indexed_properties.extract_geo_point() else {
+                    return RelevanceScore::default();
+                };
+
+                let max_meters = distance.to_meters_float();
+                let meters = geo_point.haversine_distance(long_lat);
+
+                if meters > max_meters {
+                    return RelevanceScore::default();
+                }
+
+                if max_meters <= 0_f64 {
+                    return RelevanceScore::new_from_float(1_f64);
+                }
+
+                return RelevanceScore::new_from_float(1_f64 - (meters / max_meters));
+            },
+
+            WhereConditionalProperty::Class(classification) => {
+                indexed_properties
+                    .extract_class()
+                    .is_some_and(|class| &class == classification)
+            },
+
+            WhereConditionalProperty::ClassRegex(classification_pattern) => {
+                let Ok(classification_regex) = compile_term_regex(classification_pattern, false) else {
+                    return RelevanceScore::default();
+                };
+
+                indexed_properties
+                    .extract_class()
+                    .is_some_and(|class| classification_regex.is_match(&class))
+            },
+
+            WhereConditionalProperty::Location(location) => {
+                indexed_properties
+                    .extract_location()
+                    .is_some_and(|indexed_location| &indexed_location == location)
+            },
+
+            WhereConditionalProperty::XProperty(name_value) => {
+                passive_properties
+                    .extract_x_property_key_value_pairs()
+                    .contains(name_value)
+            },
+        };
+
+        if is_satisfied {
+            RelevanceScore::new_from_float(1_f64)
+        } else {
+            RelevanceScore::default()
         }
     }
 }
@@ -185,7 +524,7 @@ mod test {
 
     use pretty_assertions_sorted::assert_eq;
 
-    use crate::{IndexedConclusion, Calendar, Event};
+    use crate::{IndexedConclusion, InternedString, Calendar, Event, EventOccurrenceOverride};
     use crate::queries::event_query::EventQueryIndexAccessor;
     use crate::queries::event_instance_query::EventInstanceQueryIndexAccessor;
     use std::collections::{HashMap, HashSet};
@@ -197,7 +536,7 @@ mod test {
     macro_rules! assert_event_query_results {
         ($calendar:expr, $conditional:expr, $expected:expr) => {
             let accessor = EventQueryIndexAccessor::new($calendar);
-            let actual = $conditional.execute(&accessor).unwrap();
+            let actual = $conditional.execute(&accessor, &ExecutionDeadline::default()).unwrap();
 
             assert_eq!(actual, $expected);
         }
@@ -206,7 +545,7 @@ mod test {
     macro_rules! assert_event_instance_query_results {
         ($calendar:expr, $conditional:expr, $expected:expr) => {
             let accessor = EventInstanceQueryIndexAccessor::new($calendar);
-            let actual = $conditional.execute(&accessor).unwrap();
+            let actual = $conditional.execute(&accessor, &ExecutionDeadline::default()).unwrap();
 
             assert_eq!(actual, $expected);
         }
@@ -231,7 +570,7 @@ mod test {
         let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
 
         // Contains extra event uids to simulate events referenced on other indexes.
-        let event_uids = vec![
+        let event_uids = [
             String::from("All in person"),
             String::from("All online"),
             String::from("Mostly in person"),
@@ -249,15 +588,15 @@ mod test {
                 String::from("ONLINE"),
                 [
                     (
-                        String::from("All online"),
+                        InternedString::from("All online"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly online"),
+                        InternedString::from("Mostly online"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly in person"),
+                        InternedString::from("Mostly in person"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]
@@ -266,15 +605,15 @@ mod test {
                 String::from("IN-PERSON"),
                 [
                     (
-                        String::from("All in person"),
+                        InternedString::from("All in person"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in person"),
+                        InternedString::from("Mostly in person"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly online"),
+                        InternedString::from("Mostly online"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]
@@ -298,7 +637,7 @@ mod test {
         let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
 
         // Contains extra event uids to simulate events referenced on other indexes.
-        let event_uids = vec![
+        let event_uids = [
             String::from("All adults"),
             String::from("All kids"),
             String::from("Mostly adults"),
@@ -316,15 +655,15 @@ mod test {
                 String::from("Adults"),
                 [
                     (
-                        String::from("All adults"),
+                        InternedString::from("All adults"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly adults"),
+                        InternedString::from("Mostly adults"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly kids"),
+                        InternedString::from("Mostly kids"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]
@@ -333,15 +672,15 @@ mod test {
                 String::from("Kids"),
                 [
                     (
-                        String::from("All kids"),
+                        InternedString::from("All kids"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly kids"),
+                        InternedString::from("Mostly kids"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly adults"),
+                        InternedString::from("Mostly adults"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]
@@ -365,7 +704,7 @@ mod test {
         let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
 
         // Contains extra event uids to simulate events referenced on other indexes.
-        let event_uids = vec![
+        let event_uids = [
             String::from("All account-1"),
             String::from("All account-2"),
             String::from("Mostly account-1"),
@@ -386,15 +725,15 @@ mod test {
                 ),
                 [
                     (
-                        String::from("All account-1"),
+                        InternedString::from("All account-1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly account-1"),
+                        InternedString::from("Mostly account-1"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly account-2"),
+                        InternedString::from("Mostly account-2"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]
@@ -406,15 +745,15 @@ mod test {
                 ),
                 [
                     (
-                        String::from("All account-2"),
+                        InternedString::from("All account-2"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly account-2"),
+                        InternedString::from("Mostly account-2"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly account-1"),
+                        InternedString::from("Mostly account-1"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]
@@ -438,7 +777,7 @@ mod test {
         let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
 
         // Contains extra event uids to simulate events referenced on other indexes.
-        let event_uids = vec![
+        let event_uids = [
             String::from("All in London"),
             String::from("All in Oxford"),
             String::from("Mostly in London"),
@@ -456,15 +795,15 @@ mod test {
                 LONDON,
                 [
                     (
-                        String::from("All in London"),
+                        InternedString::from("All in London"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in London"),
+                        InternedString::from("Mostly in London"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly in Oxford"),
+                        InternedString::from("Mostly in Oxford"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]
@@ -473,15 +812,15 @@ mod test {
                 OXFORD,
                 [
                     (
-                        String::from("All in Oxford"),
+                        InternedString::from("All in Oxford"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in Oxford"),
+                        InternedString::from("Mostly in Oxford"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly in London"),
+                        InternedString::from("Mostly in London"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]
@@ -505,7 +844,7 @@ mod test {
         let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
 
         // Contains extra event uids to simulate events referenced on other indexes.
-        let event_uids = vec![
+        let event_uids = [
             String::from("All public"),
             String::from("All private"),
             String::from("Mostly public"),
@@ -523,15 +862,15 @@ mod test {
                 String::from("PUBLIC"),
                 [
                     (
-                        String::from("All public"),
+                        InternedString::from("All public"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly public"),
+                        InternedString::from("Mostly public"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly private"),
+                        InternedString::from("Mostly private"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]
@@ -540,15 +879,15 @@ mod test {
                 String::from("PRIVATE"),
                 [
                     (
-                        String::from("All private"),
+                        InternedString::from("All private"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly private"),
+                        InternedString::from("Mostly private"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly public"),
+                        InternedString::from("Mostly public"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]
@@ -572,31 +911,31 @@ mod test {
         let mut calendar = Calendar::new(String::from("Calendar_UID"));
 
         calendar.indexed_categories.terms.insert(
-            String::from("CATEGORY_ONE"),
+            InternedString::from("CATEGORY_ONE"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("CATEGORY_ONE_EVENT_INCLUDE_ALL"),
+                        InternedString::from("CATEGORY_ONE_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("CATEGORY_ONE_EVENT_INCLUDE_MOST"),
+                        InternedString::from("CATEGORY_ONE_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("CATEGORY_ONE_EVENT_INCLUDE_FEW"),
+                        InternedString::from("CATEGORY_ONE_EVENT_INCLUDE_FEW"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_ALL"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_MOST"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_FEW"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_FEW"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200]))),
                     ),
                 ]),
@@ -604,31 +943,31 @@ mod test {
         );
 
         calendar.indexed_categories.terms.insert(
-            String::from("CATEGORY_TWO"),
+            InternedString::from("CATEGORY_TWO"),
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("CATEGORY_TWO_EVENT_INCLUDE_ALL"),
+                        InternedString::from("CATEGORY_TWO_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("CATEGORY_TWO_EVENT_INCLUDE_MOST"),
+                        InternedString::from("CATEGORY_TWO_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("CATEGORY_TWO_EVENT_INCLUDE_FEW"),
+                        InternedString::from("CATEGORY_TWO_EVENT_INCLUDE_FEW"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_ALL"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_MOST"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_FEW"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_FEW"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200]))),
                     ),
                 ]),
@@ -640,27 +979,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("RELATED_TO_PARENT_EVENT_INCLUDE_ALL"),
+                        InternedString::from("RELATED_TO_PARENT_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("RELATED_TO_PARENT_EVENT_INCLUDE_MOST"),
+                        InternedString::from("RELATED_TO_PARENT_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("RELATED_TO_PARENT_EVENT_INCLUDE_FEW"),
+                        InternedString::from("RELATED_TO_PARENT_EVENT_INCLUDE_FEW"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_ALL"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_MOST"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_FEW"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_FEW"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200]))),
                     ),
                 ]),
@@ -672,27 +1011,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("RELATED_TO_CHILD_EVENT_INCLUDE_ALL"),
+                        InternedString::from("RELATED_TO_CHILD_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("RELATED_TO_CHILD_EVENT_INCLUDE_MOST"),
+                        InternedString::from("RELATED_TO_CHILD_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("RELATED_TO_CHILD_EVENT_INCLUDE_FEW"),
+                        InternedString::from("RELATED_TO_CHILD_EVENT_INCLUDE_FEW"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_ALL"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None),
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_MOST"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(Some(HashSet::from([100, 200]))),
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_FEW"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_FEW"),
                         IndexedConclusion::Exclude(Some(HashSet::from([100, 200]))),
                     ),
                 ]),
@@ -716,7 +1055,7 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("EVENT_ONE"),
+                        InternedString::from("EVENT_ONE"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -728,7 +1067,6 @@ mod test {
     fn test_event_uid_querying_with_unindexed_term() {
         let calendar = calendar_with_events();
 
-        // TODO: fix this. Should return an empty event set
         assert_event_query_results!(
             &calendar,
             WhereConditional::Property(
@@ -737,12 +1075,7 @@ mod test {
                 )
             ),
             InvertedCalendarIndexTerm {
-                events: HashMap::from([
-                    (
-                        String::from("EVENT_FOUR"),
-                        IndexedConclusion::Include(None)
-                    ),
-                ]),
+                events: HashMap::new(),
             }
         );
     }
@@ -761,11 +1094,11 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All in person"),
+                        InternedString::from("All in person"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in person"),
+                        InternedString::from("Mostly in person"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -804,11 +1137,11 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All kids"),
+                        InternedString::from("All kids"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly kids"),
+                        InternedString::from("Mostly kids"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -850,11 +1183,11 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All account-1"),
+                        InternedString::from("All account-1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly account-1"),
+                        InternedString::from("Mostly account-1"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -897,11 +1230,11 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All in Oxford"),
+                        InternedString::from("All in Oxford"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in Oxford"),
+                        InternedString::from("Mostly in Oxford"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -941,11 +1274,11 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All private"),
+                        InternedString::from("All private"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly private"),
+                        InternedString::from("Mostly private"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1016,19 +1349,19 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_ALL"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_MOST"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_ALL"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_MOST"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(None)
                     ),
                 ])
@@ -1050,7 +1383,7 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("EVENT_ONE"),
+                        InternedString::from("EVENT_ONE"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1062,7 +1395,6 @@ mod test {
     fn test_event_instance_uid_querying_with_unindexed_term() {
         let calendar = calendar_with_events();
 
-        // TODO: fix this. Should return an empty event set
         assert_event_instance_query_results!(
             &calendar,
             WhereConditional::Property(
@@ -1071,12 +1403,7 @@ mod test {
                 )
             ),
             InvertedCalendarIndexTerm {
-                events: HashMap::from([
-                    (
-                        String::from("EVENT_FOUR"),
-                        IndexedConclusion::Include(None)
-                    ),
-                ]),
+                events: HashMap::new(),
             }
         );
     }
@@ -1095,15 +1422,15 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All in person"),
+                        InternedString::from("All in person"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in person"),
+                        InternedString::from("Mostly in person"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly online"),
+                        InternedString::from("Mostly online"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]),
@@ -1142,15 +1469,15 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All kids"),
+                        InternedString::from("All kids"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly kids"),
+                        InternedString::from("Mostly kids"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly adults"),
+                        InternedString::from("Mostly adults"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]),
@@ -1192,15 +1519,15 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All account-1"),
+                        InternedString::from("All account-1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly account-1"),
+                        InternedString::from("Mostly account-1"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly account-2"),
+                        InternedString::from("Mostly account-2"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]),
@@ -1243,15 +1570,15 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All in Oxford"),
+                        InternedString::from("All in Oxford"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in Oxford"),
+                        InternedString::from("Mostly in Oxford"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly in London"),
+                        InternedString::from("Mostly in London"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]),
@@ -1291,15 +1618,15 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All private"),
+                        InternedString::from("All private"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly private"),
+                        InternedString::from("Mostly private"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly public"),
+                        InternedString::from("Mostly public"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                 ]),
@@ -1338,11 +1665,11 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("EVENT_TWO"),
+                        InternedString::from("EVENT_TWO"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("EVENT_THREE"),
+                        InternedString::from("EVENT_THREE"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1364,15 +1691,15 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("EVENT_ONE"),
+                        InternedString::from("EVENT_ONE"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("EVENT_TWO"),
+                        InternedString::from("EVENT_TWO"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("EVENT_THREE"),
+                        InternedString::from("EVENT_THREE"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1394,19 +1721,19 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All online"),
+                        InternedString::from("All online"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly online"),
+                        InternedString::from("Mostly online"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1428,27 +1755,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All online"), 
+                        InternedString::from("All online"), 
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly online"),
+                        InternedString::from("Mostly online"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("All in person"),
+                        InternedString::from("All in person"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in person"),
+                        InternedString::from("Mostly in person"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ])
@@ -1470,19 +1797,19 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All adults"),
+                        InternedString::from("All adults"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly adults"),
+                        InternedString::from("Mostly adults"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1504,27 +1831,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All adults"), 
+                        InternedString::from("All adults"), 
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly adults"),
+                        InternedString::from("Mostly adults"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("All kids"),
+                        InternedString::from("All kids"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly kids"),
+                        InternedString::from("Mostly kids"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ])
@@ -1549,19 +1876,19 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All account-2"),
+                        InternedString::from("All account-2"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly account-2"),
+                        InternedString::from("Mostly account-2"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1586,27 +1913,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All account-1"), 
+                        InternedString::from("All account-1"), 
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly account-1"),
+                        InternedString::from("Mostly account-1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("All account-2"),
+                        InternedString::from("All account-2"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly account-2"),
+                        InternedString::from("Mostly account-2"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ])
@@ -1629,19 +1956,19 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All in London"),
+                        InternedString::from("All in London"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in London"),
+                        InternedString::from("Mostly in London"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1664,27 +1991,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All in Oxford"), 
+                        InternedString::from("All in Oxford"), 
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in Oxford"),
+                        InternedString::from("Mostly in Oxford"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("All in London"),
+                        InternedString::from("All in London"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in London"),
+                        InternedString::from("Mostly in London"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ])
@@ -1706,19 +2033,19 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All public"),
+                        InternedString::from("All public"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly public"),
+                        InternedString::from("Mostly public"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1740,27 +2067,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All private"),
+                        InternedString::from("All private"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly private"),
+                        InternedString::from("Mostly private"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("All public"),
+                        InternedString::from("All public"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly public"),
+                        InternedString::from("Mostly public"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1782,11 +2109,11 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("EVENT_TWO"),
+                        InternedString::from("EVENT_TWO"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("EVENT_THREE"),
+                        InternedString::from("EVENT_THREE"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1808,15 +2135,15 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("EVENT_ONE"),
+                        InternedString::from("EVENT_ONE"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("EVENT_TWO"),
+                        InternedString::from("EVENT_TWO"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("EVENT_THREE"),
+                        InternedString::from("EVENT_THREE"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1838,23 +2165,23 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All online"),
+                        InternedString::from("All online"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly online"),
+                        InternedString::from("Mostly online"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly in person"),
+                        InternedString::from("Mostly in person"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1876,27 +2203,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All online"),
+                        InternedString::from("All online"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly online"),
+                        InternedString::from("Mostly online"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("All in person"),
+                        InternedString::from("All in person"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in person"),
+                        InternedString::from("Mostly in person"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1918,23 +2245,23 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All adults"),
+                        InternedString::from("All adults"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly adults"),
+                        InternedString::from("Mostly adults"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly kids"),
+                        InternedString::from("Mostly kids"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -1956,27 +2283,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All adults"),
+                        InternedString::from("All adults"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly adults"),
+                        InternedString::from("Mostly adults"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("All kids"),
+                        InternedString::from("All kids"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly kids"),
+                        InternedString::from("Mostly kids"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -2001,23 +2328,23 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All account-2"),
+                        InternedString::from("All account-2"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly account-2"),
+                        InternedString::from("Mostly account-2"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly account-1"),
+                        InternedString::from("Mostly account-1"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -2042,27 +2369,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All account-1"),
+                        InternedString::from("All account-1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly account-1"),
+                        InternedString::from("Mostly account-1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("All account-2"),
+                        InternedString::from("All account-2"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly account-2"),
+                        InternedString::from("Mostly account-2"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -2085,23 +2412,23 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All in London"),
+                        InternedString::from("All in London"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in London"),
+                        InternedString::from("Mostly in London"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly in Oxford"),
+                        InternedString::from("Mostly in Oxford"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -2124,27 +2451,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All in London"),
+                        InternedString::from("All in London"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in London"),
+                        InternedString::from("Mostly in London"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("All in Oxford"),
+                        InternedString::from("All in Oxford"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly in Oxford"),
+                        InternedString::from("Mostly in Oxford"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -2166,23 +2493,23 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All public"),
+                        InternedString::from("All public"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly public"),
+                        InternedString::from("Mostly public"),
                         IndexedConclusion::Include(Some([100].into()))
                     ),
                     (
-                        String::from("Mostly private"),
+                        InternedString::from("Mostly private"),
                         IndexedConclusion::Exclude(Some([100].into()))
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -2204,27 +2531,27 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("All public"),
+                        InternedString::from("All public"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly public"),
+                        InternedString::from("Mostly public"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("All private"),
+                        InternedString::from("All private"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Mostly private"),
+                        InternedString::from("Mostly private"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 1"),
+                        InternedString::from("Unindexed event 1"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("Unindexed event 2"),
+                        InternedString::from("Unindexed event 2"),
                         IndexedConclusion::Include(None)
                     ),
                 ]),
@@ -2278,31 +2605,229 @@ mod test {
             InvertedCalendarIndexTerm {
                 events: HashMap::from([
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_ALL"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_MOST"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(Some([100, 200].into()))
                     ),
                     (
-                        String::from("ALL_CATEGORIES_EVENT_INCLUDE_FEW"),
+                        InternedString::from("ALL_CATEGORIES_EVENT_INCLUDE_FEW"),
                         IndexedConclusion::Exclude(Some([100, 200].into()))
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_ALL"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_ALL"),
                         IndexedConclusion::Include(None)
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_MOST"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_MOST"),
                         IndexedConclusion::Include(Some([100, 200].into()))
                     ),
                     (
-                        String::from("RELATED_TO_ALL_EVENT_INCLUDE_FEW"),
+                        InternedString::from("RELATED_TO_ALL_EVENT_INCLUDE_FEW"),
                         IndexedConclusion::Exclude(Some([100, 200].into()))
                     ),
                 ])
             }
         );
     }
+
+    #[test]
+    fn test_geo_refined_and_conditional_querying() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        let mut yoga_in_london = Event::parse_ical(
+            "YOGA_IN_LONDON",
+            "DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;COUNT=2;INTERVAL=1 CATEGORIES:YOGA GEO:51.5074;-0.1278",
+        ).unwrap();
+
+        yoga_in_london.validate().unwrap();
+
+        // The second occurrence has moved to Oxford.
+        yoga_in_london.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210112T183000Z", "GEO:51.8773;-1.2475878").unwrap(),
+            false,
+        ).unwrap();
+
+        calendar.insert_event(yoga_in_london);
+
+        for (event_uid, event_ical) in [
+            ("YOGA_IN_OXFORD", "CATEGORIES:YOGA GEO:51.8773;-1.2475878"),
+            ("YOGA_ONLINE", "CATEGORIES:YOGA"),
+            ("SPORT_IN_LONDON", "CATEGORIES:SPORT GEO:51.5074;-0.1278"),
+            ("SPORT_IN_BRISTOL", "CATEGORIES:SPORT GEO:51.4545;-2.5879"),
+            ("SPORT_IN_NEW_YORK_CITY", "CATEGORIES:SPORT GEO:40.7128;-74.006"),
+        ] {
+            calendar.insert_event(Event::parse_ical(event_uid, event_ical).unwrap());
+        }
+
+        calendar.rebuild_indexes().unwrap();
+
+        let within_london = || WhereConditional::Property(
+            WhereConditionalProperty::Geo(GeoDistance::new_from_kilometers_float(10.0_f64), LONDON),
+        );
+
+        let categories = |category: &str| WhereConditional::Property(
+            WhereConditionalProperty::Categories(String::from(category)),
+        );
+
+        // The GEO condition is only refined for the YOGA events (as there are fewer of them than
+        // indexed points), with the results the same as if both sides were resolved in full.
+        let yoga_within_london = WhereConditional::Operator(
+            Box::new(categories("YOGA")),
+            Box::new(WhereConditional::Group(Box::new(within_london()))),
+            WhereOperator::And,
+        );
+
+        assert_event_query_results!(
+            &calendar,
+            yoga_within_london,
+            InvertedCalendarIndexTerm {
+                events: HashMap::from([
+                    (
+                        InternedString::from("YOGA_IN_LONDON"),
+                        IndexedConclusion::Include(None)
+                    ),
+                ]),
+            }
+        );
+
+        assert_event_instance_query_results!(
+            &calendar,
+            yoga_within_london,
+            InvertedCalendarIndexTerm {
+                events: HashMap::from([
+                    (
+                        InternedString::from("YOGA_IN_LONDON"),
+                        IndexedConclusion::Include(Some([1610476200].into()))
+                    ),
+                ]),
+            }
+        );
+
+        let deadline = ExecutionDeadline::default();
+
+        for where_conditional in [
+            WhereConditional::Operator(Box::new(within_london()), Box::new(categories("SPORT")), WhereOperator::And),
+            // Falls back to resolving the GEO condition in full as there are more candidate events
+            // than indexed points.
+            WhereConditional::Operator(
+                Box::new(WhereConditional::Operator(Box::new(categories("YOGA")), Box::new(categories("SPORT")), WhereOperator::Or)),
+                Box::new(within_london()),
+                WhereOperator::And,
+            ),
+            // Short circuits without resolving the GEO condition.
+            WhereConditional::Operator(Box::new(categories("UNKNOWN")), Box::new(within_london()), WhereOperator::And),
+        ] {
+            let WhereConditional::Operator(where_conditional_a, where_conditional_b, _) = &where_conditional else {
+                panic!("Expected operator where conditional");
+            };
+
+            let event_accessor = EventQueryIndexAccessor::new(&calendar);
+            let event_instance_accessor = EventInstanceQueryIndexAccessor::new(&calendar);
+
+            assert_eq!(
+                where_conditional.execute(&event_accessor, &deadline).unwrap(),
+                InvertedCalendarIndexTerm::merge_and(
+                    &where_conditional_a.execute(&event_accessor, &deadline).unwrap(),
+                    &where_conditional_b.execute(&event_accessor, &deadline).unwrap(),
+                ),
+            );
+
+            assert_eq!(
+                where_conditional.execute(&event_instance_accessor, &deadline).unwrap(),
+                InvertedCalendarIndexTerm::merge_and(
+                    &where_conditional_a.execute(&event_instance_accessor, &deadline).unwrap(),
+                    &where_conditional_b.execute(&event_instance_accessor, &deadline).unwrap(),
+                ),
+            );
+        }
+
+        // Only GEO conditions are not refined against one another.
+        assert_eq!(
+            WhereOperator::execute_geo_refined_and(
+                &within_london(),
+                &WhereConditional::Group(Box::new(within_london())),
+                &EventInstanceQueryIndexAccessor::new(&calendar),
+                &deadline,
+            ),
+            Ok(None),
+        );
+    }
+
+    #[test]
+    fn test_short_circuited_and_conditional_querying() {
+        let calendar = calendar_with_composite_indexes();
+
+        // Negated CATEGORIES prefix conditions error when resolved, so succeeding shows that they
+        // were never resolved.
+        let unresolvable = || WhereConditional::Group(Box::new(
+            WhereConditional::NegatedProperty(WhereConditionalProperty::CategoriesPrefix(String::from("CATEGORY"))),
+        ));
+
+        let unknown_category = || WhereConditional::Property(
+            WhereConditionalProperty::Categories(String::from("UNKNOWN_CATEGORY")),
+        );
+
+        // The empty term is propagated through the enclosing group and short-circuits the outer AND.
+        let short_circuited_where_conditional = WhereConditional::Operator(
+            Box::new(WhereConditional::Group(Box::new(
+                WhereConditional::Operator(Box::new(unknown_category()), Box::new(unresolvable()), WhereOperator::And),
+            ))),
+            Box::new(unresolvable()),
+            WhereOperator::And,
+        );
+
+        assert_event_query_results!(&calendar, short_circuited_where_conditional, InvertedCalendarIndexTerm::new());
+        assert_event_instance_query_results!(&calendar, short_circuited_where_conditional, InvertedCalendarIndexTerm::new());
+
+        // Whereas the second where conditional is still resolved if the first matched anything, or
+        // if they are OR-ed.
+        for where_conditional in [
+            WhereConditional::Operator(
+                Box::new(WhereConditional::Property(WhereConditionalProperty::Categories(String::from("CATEGORY_ONE")))),
+                Box::new(unresolvable()),
+                WhereOperator::And,
+            ),
+            WhereConditional::Operator(Box::new(unknown_category()), Box::new(unresolvable()), WhereOperator::Or),
+        ] {
+            assert_eq!(
+                where_conditional.execute(&EventQueryIndexAccessor::new(&calendar), &ExecutionDeadline::default()),
+                Err(String::from("Negated CATEGORIES prefix (OP=PREFIX or trailing *) conditions are not supported")),
+            );
+
+            assert_eq!(
+                where_conditional.execute(&EventInstanceQueryIndexAccessor::new(&calendar), &ExecutionDeadline::default()),
+                Err(String::from("Negated CATEGORIES prefix (OP=PREFIX or trailing *) conditions are not supported")),
+            );
+        }
+    }
+
+    #[test]
+    fn test_conditional_querying_with_exceeded_execution_deadline() {
+        let calendar = calendar_with_composite_indexes();
+
+        let query_where_conditional = WhereConditional::Operator(
+            Box::new(WhereConditional::Property(
+                WhereConditionalProperty::Categories(String::from("CATEGORY_ONE")),
+            )),
+            Box::new(WhereConditional::Property(
+                WhereConditionalProperty::Categories(String::from("CATEGORY_TWO")),
+            )),
+            WhereOperator::Or,
+        );
+
+        let execution_deadline = ExecutionDeadline::new(std::time::Duration::ZERO);
+
+        assert_eq!(
+            query_where_conditional.execute(&EventQueryIndexAccessor::new(&calendar), &execution_deadline),
+            Err(String::from("query exceeded timeout")),
+        );
+
+        assert_eq!(
+            query_where_conditional.execute(&EventInstanceQueryIndexAccessor::new(&calendar), &execution_deadline),
+            Err(String::from("query exceeded timeout")),
+        );
+    }
 }
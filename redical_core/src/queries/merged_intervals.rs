@@ -0,0 +1,144 @@
+use std::collections::BTreeSet;
+
+use redical_ical::RenderingContext;
+use redical_ical::content_line::ContentLine;
+use redical_ical::properties::{ICalendarDateTimeProperty, ICalendarProperty, DTEndProperty, DTStartProperty};
+
+use crate::EventInstance;
+use crate::queries::results::QueryResults;
+
+/// A busy interval spanning one or more overlapping (or adjacent) event instances returned by a
+/// query (`X-MERGE-INTERVALS:TRUE`).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MergedInterval {
+    pub dtstart_timestamp: i64,
+    pub dtend_timestamp: i64,
+}
+
+impl MergedInterval {
+    pub fn to_content_line_set_with_context(&self, context: Option<&RenderingContext>) -> BTreeSet<ContentLine> {
+        BTreeSet::from([
+            DTStartProperty::new_from_utc_timestamp(&self.dtstart_timestamp).to_content_line_with_context(context),
+            DTEndProperty::new_from_utc_timestamp(&self.dtend_timestamp).to_content_line_with_context(context),
+        ])
+    }
+}
+
+/// Collapses the event instance query results into the minimal set of busy intervals spanning
+/// them (ordered by DTSTART), merging those overlapping or adjacent to each other (one ending as
+/// the next starts), regardless of how the results themselves are ordered.
+pub fn merge_event_instance_intervals(query_results: &QueryResults<EventInstance>) -> Vec<MergedInterval> {
+    let mut intervals: Vec<(i64, i64)> =
+        query_results
+            .results
+            .iter()
+            .map(|query_result| {
+                let dtstart_timestamp = query_result.result.dtstart.get_utc_timestamp();
+                let dtend_timestamp = query_result.result.dtend.get_utc_timestamp();
+
+                (dtstart_timestamp, dtend_timestamp.max(dtstart_timestamp))
+            })
+            .collect();
+
+    intervals.sort_unstable();
+
+    let mut merged_intervals: Vec<MergedInterval> = Vec::new();
+
+    for (dtstart_timestamp, dtend_timestamp) in intervals {
+        if let Some(last_merged_interval) = merged_intervals.last_mut() {
+            if dtstart_timestamp <= last_merged_interval.dtend_timestamp {
+                last_merged_interval.dtend_timestamp = last_merged_interval.dtend_timestamp.max(dtend_timestamp);
+
+                continue;
+            }
+        }
+
+        merged_intervals.push(
+            MergedInterval {
+                dtstart_timestamp,
+                dtend_timestamp,
+            }
+        );
+    }
+
+    merged_intervals
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::str::FromStr;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use crate::{Calendar, EventOccurrenceOverride};
+    use crate::testing::utils::build_event_from_ical;
+    use crate::queries::event_instance_query::EventInstanceQuery;
+    use crate::queries::query::Query;
+
+    use redical_ical::ICalendarEntity;
+
+    #[test]
+    fn test_merge_event_instance_intervals() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        // Daily 09:00 - 10:00, with the second occurrence overridden to run until 12:30.
+        let mut event_one = build_event_from_ical("EVENT_ONE", vec!["DTSTART:20210105T090000Z DTEND:20210105T100000Z RRULE:FREQ=DAILY;INTERVAL=1;COUNT=3"]);
+
+        event_one.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210106T090000Z", "DTEND:20210106T123000Z").unwrap(),
+            false,
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(event_one).unwrap();
+
+        // Overlapping the first occurrence of EVENT_ONE.
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_TWO", vec!["DTSTART:20210105T093000Z DTEND:20210105T103000Z"])
+        ).unwrap();
+
+        // Adjacent to the (overridden) second occurrence of EVENT_ONE.
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_THREE", vec!["DTSTART:20210106T123000Z DTEND:20210106T130000Z"])
+        ).unwrap();
+
+        // Within the (overridden) second occurrence of EVENT_ONE.
+        calendar.insert_event_and_reindex(
+            build_event_from_ical("EVENT_FOUR", vec!["DTSTART:20210106T100000Z DTEND:20210106T110000Z"])
+        ).unwrap();
+
+        let mut query = EventInstanceQuery::from_str("X-MERGE-INTERVALS:TRUE X-ORDER-BY:DTSTART X-LIMIT:10").unwrap();
+
+        assert!(query.merge_intervals);
+
+        let query_results = query.execute(&calendar).unwrap();
+
+        let merged_intervals = merge_event_instance_intervals(&query_results);
+
+        assert_eq!(
+            merged_intervals,
+            vec![
+                MergedInterval { dtstart_timestamp: 1609837200, dtend_timestamp: 1609842600 }, // 2021-01-05T09:00:00Z - 2021-01-05T10:30:00Z
+                MergedInterval { dtstart_timestamp: 1609923600, dtend_timestamp: 1609938000 }, // 2021-01-06T09:00:00Z - 2021-01-06T13:00:00Z
+                MergedInterval { dtstart_timestamp: 1610010000, dtend_timestamp: 1610013600 }, // 2021-01-07T09:00:00Z - 2021-01-07T10:00:00Z
+            ],
+        );
+
+        assert_eq!(
+            merged_intervals[0]
+                .to_content_line_set_with_context(None)
+                .iter()
+                .map(|content_line| content_line.render_ical())
+                .collect::<Vec<String>>(),
+            vec![
+                String::from("DTEND:20210105T103000Z"),
+                String::from("DTSTART:20210105T090000Z"),
+            ],
+        );
+
+        let mut query = EventInstanceQuery::from_str("X-MERGE-INTERVALS:TRUE X-FROM;PROP=DTSTART;OP=GT:20210108T000000Z").unwrap();
+
+        assert_eq!(merge_event_instance_intervals(&query.execute(&calendar).unwrap()), Vec::new());
+    }
+}
@@ -2,6 +2,12 @@ pub mod indexed_property_filters;
 pub mod query;
 pub mod event_query;
 pub mod event_instance_query;
+pub mod grouped_results;
+pub mod merged_intervals;
+pub mod query_explain;
+pub mod query_lint;
+pub mod query_profile;
+pub mod query_regex;
 pub mod query_parser;
 pub mod results;
 pub mod results_ordering;
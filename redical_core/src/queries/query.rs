@@ -1,13 +1,19 @@
+use std::collections::{BTreeSet, HashSet};
 use std::str::FromStr;
 
 use chrono_tz::Tz;
 
-use crate::{Calendar, InvertedCalendarIndexTerm, KeyValuePair, GeoDistance, GeoPoint};
+use regex::Regex;
+
+use redical_ical::content_line::ContentLine;
+
+use crate::{Calendar, DstPolicy, ExecutionDeadline, InvertedCalendarIndexTerm, KeyValuePair, GeoDistance, GeoPoint};
 use crate::queries::results::{QueryableEntity, QueryResults};
+use crate::queries::query_parser::parse_query_string_with_default_timezone;
 
 use crate::queries::results_ordering::OrderingCondition;
 use crate::queries::results_range_bounds::{
-    LowerBoundRangeCondition, UpperBoundRangeCondition,
+    DurationRangeCondition, LowerBoundRangeCondition, UpperBoundRangeCondition,
 };
 
 use crate::queries::indexed_property_filters::{
@@ -29,37 +35,167 @@ use crate::queries::indexed_property_filters::{
 pub trait QueryIndexAccessor<'cal> {
     fn new(calendar: &'cal Calendar) -> Self;
 
+    // Whether terms are matched case-insensitively (as declared via X-TERM-NORMALIZATION), which
+    // regular expressions are compiled with consideration to.
+    fn is_term_matching_case_insensitive(&self) -> bool;
+
     // Positive term matching
     fn search_uid_index(&self, uid: &str) -> InvertedCalendarIndexTerm;
+    fn search_uid_prefix_index(&self, uid_prefix: &str) -> InvertedCalendarIndexTerm;
+    fn search_uid_regex_index(&self, uid_regex: &Regex) -> InvertedCalendarIndexTerm;
     fn search_location_type_index(&self, location_type: &str) -> InvertedCalendarIndexTerm;
     fn search_categories_index(&self, category: &str) -> InvertedCalendarIndexTerm;
+    fn search_categories_prefix_index(&self, category_prefix: &str) -> InvertedCalendarIndexTerm;
+    fn search_categories_regex_index(&self, category_regex: &Regex) -> InvertedCalendarIndexTerm;
     fn search_related_to_index(&self, reltype_uids: &KeyValuePair) -> InvertedCalendarIndexTerm;
     fn search_geo_index(&self, distance: &GeoDistance, long_lat: &GeoPoint) -> InvertedCalendarIndexTerm;
     fn search_class_index(&self, class: &str) -> InvertedCalendarIndexTerm;
+    fn search_class_regex_index(&self, class_regex: &Regex) -> InvertedCalendarIndexTerm;
+    fn search_location_index(&self, location: &str) -> InvertedCalendarIndexTerm;
+    fn search_x_property_index(&self, name_value: &KeyValuePair) -> InvertedCalendarIndexTerm;
+
+    // Only search the GEO index for the provided candidate events (see
+    // `Calendar::locate_candidates_within_distance`), e.g. those already matched by the other
+    // side of an AND.
+    fn search_geo_index_within_candidates(&self, distance: &GeoDistance, long_lat: &GeoPoint, candidates: &InvertedCalendarIndexTerm) -> InvertedCalendarIndexTerm;
+
+    // Walk the relations transitively through the RELATED-TO index (e.g. the children of the
+    // provided parent, then their children, and so on) up to the provided depth, matching every
+    // event related at any level. Events already visited are not walked again, so cyclic
+    // relations terminate.
+    fn search_transitive_related_to_index(&self, reltype_uids: &KeyValuePair, depth: usize) -> InvertedCalendarIndexTerm {
+        let mut visited_uids = HashSet::from([reltype_uids.value.to_owned()]);
+        let mut related_uids = vec![reltype_uids.value.to_owned()];
+
+        let mut inverted_calendar_index_term = InvertedCalendarIndexTerm::new();
+
+        for _ in 0..depth {
+            let mut level_inverted_calendar_index_term = InvertedCalendarIndexTerm::new();
+
+            for related_uid in related_uids {
+                level_inverted_calendar_index_term = InvertedCalendarIndexTerm::merge_or(
+                    &level_inverted_calendar_index_term,
+                    &self.search_related_to_index(&KeyValuePair::new(reltype_uids.key.to_owned(), related_uid)),
+                );
+            }
+
+            related_uids =
+                level_inverted_calendar_index_term
+                    .events
+                    .keys()
+                    .map(|event_uid| event_uid.to_string())
+                    .filter(|event_uid| visited_uids.insert(event_uid.to_owned()))
+                    .collect();
+
+            inverted_calendar_index_term = InvertedCalendarIndexTerm::merge_or(
+                &inverted_calendar_index_term,
+                &level_inverted_calendar_index_term,
+            );
+
+            if related_uids.is_empty() {
+                break;
+            }
+        }
+
+        inverted_calendar_index_term
+    }
 
     // Negative term (NOT) matching
     fn search_not_uid_index(&self, uid: &str) -> InvertedCalendarIndexTerm;
+    fn search_not_uid_prefix_index(&self, uid_prefix: &str) -> InvertedCalendarIndexTerm;
+    fn search_not_uid_regex_index(&self, uid_regex: &Regex) -> InvertedCalendarIndexTerm;
     fn search_not_location_type_index(&self, location_type: &str) -> InvertedCalendarIndexTerm;
     fn search_not_categories_index(&self, category: &str) -> InvertedCalendarIndexTerm;
     fn search_not_related_to_index(&self, reltype_uids: &KeyValuePair) -> InvertedCalendarIndexTerm;
     fn search_not_geo_index(&self, distance: &GeoDistance, long_lat: &GeoPoint) -> InvertedCalendarIndexTerm;
     fn search_not_class_index(&self, class: &str) -> InvertedCalendarIndexTerm;
+    fn search_not_location_index(&self, location: &str) -> InvertedCalendarIndexTerm;
+    fn search_not_x_property_index(&self, name_value: &KeyValuePair) -> InvertedCalendarIndexTerm;
 }
 
 /// The purpose of this trait is to allow it's implementers to specify the query logic specific to
 /// the requirements of the query it is associated with (e.g. querying event instances or just
 /// events).
 pub trait Query<T: QueryableEntity>: FromStr + PartialEq + Clone + Default {
-    fn execute(&mut self, calendar: &Calendar) -> Result<QueryResults<T>, String>;
+    /// Parses the query, interpreting floating X-FROM and X-UNTIL date-times in the provided
+    /// (Calendar) default timezone, which the results are also rendered in unless X-TZID is
+    /// specified.
+    fn from_str_with_default_timezone(input: &str, default_tz: Option<&Tz>) -> Result<Self, String> {
+        parse_query_string_with_default_timezone(input.trim(), default_tz)
+    }
+
+    fn execute(&mut self, calendar: &Calendar) -> Result<QueryResults<T>, String> {
+        self.execute_with_deadline(calendar, &ExecutionDeadline::default())
+    }
+
+    /// Executes the query, aborting with a "query exceeded timeout" error if the provided
+    /// execution deadline is exceeded part way through.
+    fn execute_with_deadline(&mut self, calendar: &Calendar, execution_deadline: &ExecutionDeadline) -> Result<QueryResults<T>, String>;
     fn set_where_conditional(&mut self, where_conditional: Option<WhereConditional>);
     fn get_where_conditional(&self) -> &Option<WhereConditional>;
     fn set_ordering_condition(&mut self, ordering_condition: OrderingCondition);
     fn set_lower_bound_range_condition(&mut self, lower_bound_range_condition: Option<LowerBoundRangeCondition>);
     fn set_upper_bound_range_condition(&mut self, upper_bound_range_condition: Option<UpperBoundRangeCondition>);
+    fn insert_duration_range_condition(&mut self, duration_range_condition: DurationRangeCondition);
     fn set_in_timezone(&mut self, in_timezone: Tz);
     fn set_distinct_uids(&mut self, distinct_uids: bool);
+    fn set_group_by_uid(&mut self, group_by_uid: bool);
     fn set_offset(&mut self, offset: usize);
+    fn get_offset(&self) -> usize;
     fn set_limit(&mut self, limit: usize);
+    fn set_limit_per_event(&mut self, limit_per_event: Option<usize>);
+    fn set_dst_policy(&mut self, dst_policy: Option<DstPolicy>);
+    fn set_return_properties(&mut self, return_properties: Option<Vec<String>>);
+    fn get_return_properties(&self) -> &Option<Vec<String>>;
+    fn set_count_only(&mut self, count_only: bool);
+    fn set_merge_intervals(&mut self, merge_intervals: bool);
+    fn set_warnings(&mut self, warnings: Vec<String>);
+    fn get_warnings(&self) -> &Vec<String>;
+    fn get_limit(&self) -> usize;
+
+    /// Restricts the content lines rendered for each result to those of the properties specified
+    /// (via X-RETURN), returning them unchanged if not specified.
+    fn project_content_lines(&self, content_lines: BTreeSet<ContentLine>) -> BTreeSet<ContentLine> {
+        let Some(return_properties) = self.get_return_properties() else {
+            return content_lines;
+        };
+
+        content_lines
+            .into_iter()
+            .filter(|content_line| return_properties.contains(&content_line.0))
+            .collect()
+    }
+
+    /// Caps the query limit to the provided max number of results, retaining one extra result
+    /// beyond it so that the results can be flagged as truncated (via
+    /// `QueryResults::truncate_to_max_results`) if there were more.
+    fn cap_limit(&mut self, max_results: usize) {
+        if self.get_limit() > max_results {
+            self.set_limit(max_results.saturating_add(1));
+        }
+    }
+
+    /// Builds a copy of the query returning every matching result (disregarding the offset and
+    /// limit) up to one beyond the provided max count, so that the total number of matches can be
+    /// counted (and flagged as capped if it exceeds the max count).
+    fn build_count_query(&self, max_count: Option<usize>) -> Self {
+        let mut count_query = self.clone();
+
+        count_query.set_offset(0);
+        count_query.set_limit(max_count.map_or(usize::MAX, |max_count| max_count.saturating_add(1)));
+
+        count_query
+    }
+
+    /// Counts the results matching the query (disregarding the offset and limit) up to one beyond
+    /// the provided max count, so that the count can be flagged as capped if it exceeds it.
+    fn count_with_deadline(&self, calendar: &Calendar, max_count: Option<usize>, execution_deadline: &ExecutionDeadline) -> Result<usize, String> {
+        let query_results =
+            self.build_count_query(max_count)
+                .execute_with_deadline(calendar, execution_deadline)?;
+
+        Ok(query_results.len())
+    }
 
     // TODO: Clean this up!
     fn insert_new_where_conditional(&mut self, new_where_conditional: Option<WhereConditional>) {
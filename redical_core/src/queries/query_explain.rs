@@ -0,0 +1,575 @@
+use std::collections::HashSet;
+
+use crate::{ExecutionDeadline, InternedString, InvertedCalendarIndexTerm};
+
+use crate::queries::indexed_property_filters::{WhereConditional, WhereConditionalProperty, WhereOperator};
+use crate::queries::query::QueryIndexAccessor;
+use crate::queries::results_ordering::OrderingCondition;
+
+/// A single node of the planned where conditional tree, along with the number of candidate events
+/// it resolves to (the size of the index term it produces).
+#[derive(Debug, PartialEq, Clone)]
+pub struct WhereConditionalExplanation {
+    pub node: String,
+    pub index: Option<String>,
+    pub candidate_count: usize,
+    pub children: Vec<WhereConditionalExplanation>,
+}
+
+impl WhereConditionalExplanation {
+    /// Resolves each node of the where conditional against the indexes (exactly as query
+    /// execution would), returning the explanation of the tree alongside the resolved index term.
+    pub fn build<'cal>(
+        where_conditional: &WhereConditional,
+        query_index_accessor: &impl QueryIndexAccessor<'cal>,
+        execution_deadline: &ExecutionDeadline,
+    ) -> Result<(Self, InvertedCalendarIndexTerm), String> {
+        execution_deadline.check()?;
+
+        match where_conditional {
+            WhereConditional::Property(where_conditional_property) => {
+                let inverted_calendar_index_term = where_conditional_property.execute(query_index_accessor)?;
+
+                let explanation = Self::build_for_property(where_conditional_property, false, &inverted_calendar_index_term);
+
+                Ok((explanation, inverted_calendar_index_term))
+            }
+
+            WhereConditional::NegatedProperty(where_conditional_property) => {
+                let inverted_calendar_index_term = where_conditional_property.execute_not(query_index_accessor)?;
+
+                let explanation = Self::build_for_property(where_conditional_property, true, &inverted_calendar_index_term);
+
+                Ok((explanation, inverted_calendar_index_term))
+            }
+
+            WhereConditional::Operator(where_conditional_a, where_conditional_b, WhereOperator::And) if Self::is_geo_refined_and(where_conditional_a, where_conditional_b) => {
+                Self::build_for_geo_refined_and(where_conditional_a, where_conditional_b, query_index_accessor, execution_deadline)
+            }
+
+            WhereConditional::Operator(where_conditional_a, where_conditional_b, where_operator) => {
+                let (explanation_a, inverted_calendar_index_term_a) = Self::build(where_conditional_a, query_index_accessor, execution_deadline)?;
+
+                if let (WhereOperator::And, true) = (where_operator, inverted_calendar_index_term_a.events.is_empty()) {
+                    let explanation = WhereConditionalExplanation {
+                        node: String::from("AND"),
+                        index: None,
+                        candidate_count: 0,
+                        children: vec![explanation_a, Self::build_short_circuited(where_conditional_b)],
+                    };
+
+                    return Ok((explanation, InvertedCalendarIndexTerm::new()));
+                }
+
+                let (explanation_b, inverted_calendar_index_term_b) = Self::build(where_conditional_b, query_index_accessor, execution_deadline)?;
+
+                let (node, inverted_calendar_index_term) = match where_operator {
+                    WhereOperator::Or => (
+                        "OR",
+                        InvertedCalendarIndexTerm::merge_or(&inverted_calendar_index_term_a, &inverted_calendar_index_term_b),
+                    ),
+
+                    WhereOperator::And => (
+                        "AND",
+                        InvertedCalendarIndexTerm::merge_and(&inverted_calendar_index_term_a, &inverted_calendar_index_term_b),
+                    ),
+                };
+
+                let explanation = WhereConditionalExplanation {
+                    node: String::from(node),
+                    index: None,
+                    candidate_count: inverted_calendar_index_term.events.len(),
+                    children: vec![explanation_a, explanation_b],
+                };
+
+                Ok((explanation, inverted_calendar_index_term))
+            }
+
+            WhereConditional::Group(where_conditional) => {
+                let (explanation, inverted_calendar_index_term) = Self::build(where_conditional, query_index_accessor, execution_deadline)?;
+
+                let explanation = WhereConditionalExplanation {
+                    node: String::from("GROUP"),
+                    index: None,
+                    candidate_count: explanation.candidate_count,
+                    children: vec![explanation],
+                };
+
+                Ok((explanation, inverted_calendar_index_term))
+            }
+        }
+    }
+
+    // Mirrors the short-circuiting of `WhereOperator::execute`, where the second where
+    // conditional of an `AND` is not resolved at all if the first resolved to nothing (so none of
+    // it's nodes count any candidate events).
+    fn build_short_circuited(where_conditional: &WhereConditional) -> Self {
+        match where_conditional {
+            WhereConditional::Property(where_conditional_property) => {
+                Self::build_for_property(where_conditional_property, false, &InvertedCalendarIndexTerm::new())
+            }
+
+            WhereConditional::NegatedProperty(where_conditional_property) => {
+                Self::build_for_property(where_conditional_property, true, &InvertedCalendarIndexTerm::new())
+            }
+
+            WhereConditional::Operator(where_conditional_a, where_conditional_b, where_operator) => {
+                let node = match where_operator {
+                    WhereOperator::Or => "OR",
+                    WhereOperator::And => "AND",
+                };
+
+                WhereConditionalExplanation {
+                    node: String::from(node),
+                    index: None,
+                    candidate_count: 0,
+                    children: vec![
+                        Self::build_short_circuited(where_conditional_a),
+                        Self::build_short_circuited(where_conditional_b),
+                    ],
+                }
+            }
+
+            WhereConditional::Group(where_conditional) => WhereConditionalExplanation {
+                node: String::from("GROUP"),
+                index: None,
+                candidate_count: 0,
+                children: vec![Self::build_short_circuited(where_conditional)],
+            },
+        }
+    }
+
+    fn is_geo_refined_and(where_conditional_a: &WhereConditional, where_conditional_b: &WhereConditional) -> bool {
+        where_conditional_a.get_geo_property().is_some() != where_conditional_b.get_geo_property().is_some()
+    }
+
+    // Mirrors `WhereOperator::execute_geo_refined_and`, where only the distance of the candidate
+    // events matched by the other where conditional is refined (so the GEO node only counts those
+    // within the distance, and is not resolved at all if there are none).
+    fn build_for_geo_refined_and<'cal>(
+        where_conditional_a: &WhereConditional,
+        where_conditional_b: &WhereConditional,
+        query_index_accessor: &impl QueryIndexAccessor<'cal>,
+        execution_deadline: &ExecutionDeadline,
+    ) -> Result<(Self, InvertedCalendarIndexTerm), String> {
+        let (candidate_where_conditional, geo_where_conditional, geo_first) =
+            if where_conditional_a.get_geo_property().is_some() {
+                (where_conditional_b, where_conditional_a, true)
+            } else {
+                (where_conditional_a, where_conditional_b, false)
+            };
+
+        let Some((distance, long_lat)) = geo_where_conditional.get_geo_property() else {
+            return Err(String::from("Expected GEO where conditional"));
+        };
+
+        let (candidate_explanation, candidate_inverted_calendar_index_term) = Self::build(candidate_where_conditional, query_index_accessor, execution_deadline)?;
+
+        let geo_inverted_calendar_index_term = if candidate_inverted_calendar_index_term.events.is_empty() {
+            InvertedCalendarIndexTerm::new()
+        } else {
+            query_index_accessor.search_geo_index_within_candidates(distance, long_lat, &candidate_inverted_calendar_index_term)
+        };
+
+        let geo_explanation = Self::build_for_property(
+            &WhereConditionalProperty::Geo(distance.to_owned(), long_lat.to_owned()),
+            false,
+            &geo_inverted_calendar_index_term,
+        );
+
+        let (children, inverted_calendar_index_term) = if geo_first {
+            (
+                vec![geo_explanation, candidate_explanation],
+                InvertedCalendarIndexTerm::merge_and(&geo_inverted_calendar_index_term, &candidate_inverted_calendar_index_term),
+            )
+        } else {
+            (
+                vec![candidate_explanation, geo_explanation],
+                InvertedCalendarIndexTerm::merge_and(&candidate_inverted_calendar_index_term, &geo_inverted_calendar_index_term),
+            )
+        };
+
+        let explanation = WhereConditionalExplanation {
+            node: String::from("AND"),
+            index: None,
+            candidate_count: inverted_calendar_index_term.events.len(),
+            children,
+        };
+
+        Ok((explanation, inverted_calendar_index_term))
+    }
+
+    fn build_for_property(
+        where_conditional_property: &WhereConditionalProperty,
+        negated: bool,
+        inverted_calendar_index_term: &InvertedCalendarIndexTerm,
+    ) -> Self {
+        let (index, term) = where_conditional_property.get_index_and_term();
+
+        let node = if negated {
+            format!("NOT {index}:{term}")
+        } else {
+            format!("{index}:{term}")
+        };
+
+        WhereConditionalExplanation {
+            node,
+            index: Some(String::from(index)),
+            candidate_count: inverted_calendar_index_term.events.len(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// How the query offset is applied to the ordered results.
+#[derive(Debug, PartialEq, Clone)]
+pub enum OffsetStrategy {
+    // Results are skipped as they are streamed out in order, so no more than the offset plus the
+    // limit are ever collected.
+    SkipWhileIterating,
+
+    // Results ordered by relevance score (or by geographical distance within a shared dtstart)
+    // are only skipped once every competing result has been collected and sorted.
+    SkipAfterSorting,
+}
+
+impl std::fmt::Display for OffsetStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OffsetStrategy::SkipWhileIterating => write!(f, "SKIP-WHILE-ITERATING"),
+            OffsetStrategy::SkipAfterSorting => write!(f, "SKIP-AFTER-SORTING"),
+        }
+    }
+}
+
+impl From<&OrderingCondition> for OffsetStrategy {
+    fn from(ordering_condition: &OrderingCondition) -> Self {
+        match ordering_condition {
+            OrderingCondition::Score(_) | OrderingCondition::DtStartGeoDist(_) => OffsetStrategy::SkipAfterSorting,
+            _ => OffsetStrategy::SkipWhileIterating,
+        }
+    }
+}
+
+/// The planned execution of a query against a specific calendar, without any of the events
+/// (or event instances) being expanded or ordered, intended for debugging slow queries.
+#[derive(Debug, PartialEq, Clone)]
+pub struct QueryExplanation {
+    pub where_conditional: Option<WhereConditionalExplanation>,
+    pub event_count: usize,
+    pub candidate_count: usize,
+    pub time_bucketed: bool,
+    pub ordering: String,
+    pub offset_strategy: OffsetStrategy,
+    pub offset: usize,
+    pub limit: usize,
+    pub distinct_uids: bool,
+}
+
+impl QueryExplanation {
+    /// The candidate events are those matching the where conditional (or every event without
+    /// one), further narrowed to those within the time bucketed event UIDs (if provided).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        where_conditional: Option<(WhereConditionalExplanation, InvertedCalendarIndexTerm)>,
+        event_uids: Vec<&String>,
+        time_bucketed_event_uids: Option<HashSet<String>>,
+        ordering_condition: &OrderingCondition,
+        offset: usize,
+        limit: usize,
+        distinct_uids: bool,
+    ) -> Self {
+        let event_count = event_uids.len();
+
+        let (where_conditional, candidate_event_uids): (_, Vec<&str>) = match &where_conditional {
+            Some((explanation, inverted_calendar_index_term)) => {
+                (Some(explanation.to_owned()), inverted_calendar_index_term.events.keys().map(InternedString::as_str).collect())
+            },
+
+            None => (None, event_uids.into_iter().map(String::as_str).collect()),
+        };
+
+        let candidate_count = match &time_bucketed_event_uids {
+            Some(time_bucketed_event_uids) => {
+                candidate_event_uids
+                    .into_iter()
+                    .filter(|event_uid| time_bucketed_event_uids.contains(*event_uid))
+                    .count()
+            },
+
+            None => candidate_event_uids.len(),
+        };
+
+        QueryExplanation {
+            where_conditional,
+            event_count,
+            candidate_count,
+            time_bucketed: time_bucketed_event_uids.is_some(),
+            ordering: Self::describe_ordering_condition(ordering_condition),
+            offset_strategy: OffsetStrategy::from(ordering_condition),
+            offset,
+            limit,
+            distinct_uids,
+        }
+    }
+
+    fn describe_ordering_condition(ordering_condition: &OrderingCondition) -> String {
+        match ordering_condition {
+            OrderingCondition::DtStart => String::from("DTSTART"),
+            OrderingCondition::DtStartGeoDist(geo_point) => format!("DTSTART-GEO-DIST;{geo_point}"),
+            OrderingCondition::GeoDistDtStart(geo_point) => format!("GEO-DIST-DTSTART;{geo_point}"),
+            OrderingCondition::Score(_) => String::from("SCORE"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use crate::{Calendar, Event, GeoDistance, GeoPoint};
+    use crate::queries::event_query::EventQueryIndexAccessor;
+
+    fn calendar_with_categorised_events() -> Calendar {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event(Event::parse_ical("EVENT_ONE", "CATEGORIES:ART,MUSIC").unwrap());
+        calendar.insert_event(Event::parse_ical("EVENT_TWO", "CATEGORIES:ART").unwrap());
+        calendar.insert_event(Event::parse_ical("EVENT_THREE", "CATEGORIES:SPORT").unwrap());
+        calendar.rebuild_indexes().unwrap();
+
+        calendar
+    }
+
+    #[test]
+    fn test_where_conditional_explanation_build() {
+        let calendar = calendar_with_categorised_events();
+
+        let accessor = EventQueryIndexAccessor::new(&calendar);
+
+        let where_conditional = WhereConditional::Operator(
+            Box::new(WhereConditional::Property(
+                WhereConditionalProperty::Categories(String::from("ART")),
+            )),
+            Box::new(WhereConditional::Group(
+                Box::new(WhereConditional::NegatedProperty(
+                    WhereConditionalProperty::Categories(String::from("MUSIC")),
+                )),
+            )),
+            WhereOperator::And,
+        );
+
+        let (explanation, inverted_calendar_index_term) =
+            WhereConditionalExplanation::build(&where_conditional, &accessor, &ExecutionDeadline::default()).unwrap();
+
+        assert_eq!(
+            inverted_calendar_index_term,
+            where_conditional.execute(&accessor, &ExecutionDeadline::default()).unwrap(),
+        );
+
+        assert_eq!(
+            explanation,
+            WhereConditionalExplanation {
+                node: String::from("AND"),
+                index: None,
+                candidate_count: 1,
+                children: vec![
+                    WhereConditionalExplanation {
+                        node: String::from("CATEGORIES:ART"),
+                        index: Some(String::from("CATEGORIES")),
+                        candidate_count: 2,
+                        children: Vec::new(),
+                    },
+                    WhereConditionalExplanation {
+                        node: String::from("GROUP"),
+                        index: None,
+                        candidate_count: 2,
+                        children: vec![
+                            WhereConditionalExplanation {
+                                node: String::from("NOT CATEGORIES:MUSIC"),
+                                index: Some(String::from("CATEGORIES")),
+                                candidate_count: 2,
+                                children: Vec::new(),
+                            },
+                        ],
+                    },
+                ],
+            },
+        );
+    }
+
+    #[test]
+    fn test_where_conditional_explanation_build_with_short_circuited_and() {
+        let calendar = calendar_with_categorised_events();
+
+        let accessor = EventQueryIndexAccessor::new(&calendar);
+
+        let where_conditional = WhereConditional::Operator(
+            Box::new(WhereConditional::Property(
+                WhereConditionalProperty::Categories(String::from("THEATRE")),
+            )),
+            Box::new(WhereConditional::Group(
+                Box::new(WhereConditional::NegatedProperty(
+                    WhereConditionalProperty::Categories(String::from("MUSIC")),
+                )),
+            )),
+            WhereOperator::And,
+        );
+
+        let (explanation, inverted_calendar_index_term) =
+            WhereConditionalExplanation::build(&where_conditional, &accessor, &ExecutionDeadline::default()).unwrap();
+
+        assert_eq!(
+            inverted_calendar_index_term,
+            where_conditional.execute(&accessor, &ExecutionDeadline::default()).unwrap(),
+        );
+
+        // The negated MUSIC condition is never resolved, as no events are categorised THEATRE.
+        assert_eq!(
+            explanation,
+            WhereConditionalExplanation {
+                node: String::from("AND"),
+                index: None,
+                candidate_count: 0,
+                children: vec![
+                    WhereConditionalExplanation {
+                        node: String::from("CATEGORIES:THEATRE"),
+                        index: Some(String::from("CATEGORIES")),
+                        candidate_count: 0,
+                        children: Vec::new(),
+                    },
+                    WhereConditionalExplanation {
+                        node: String::from("GROUP"),
+                        index: None,
+                        candidate_count: 0,
+                        children: vec![
+                            WhereConditionalExplanation {
+                                node: String::from("NOT CATEGORIES:MUSIC"),
+                                index: Some(String::from("CATEGORIES")),
+                                candidate_count: 0,
+                                children: Vec::new(),
+                            },
+                        ],
+                    },
+                ],
+            },
+        );
+    }
+
+    #[test]
+    fn test_where_conditional_explanation_build_with_geo_refined_and() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event(Event::parse_ical("EVENT_ONE", "CATEGORIES:YOGA GEO:51.5074;-0.1278").unwrap());
+        calendar.insert_event(Event::parse_ical("EVENT_TWO", "CATEGORIES:YOGA GEO:51.8773;-1.2475878").unwrap());
+        calendar.insert_event(Event::parse_ical("EVENT_THREE", "CATEGORIES:SPORT GEO:51.5074;-0.1278").unwrap());
+        calendar.insert_event(Event::parse_ical("EVENT_FOUR", "CATEGORIES:SPORT GEO:40.7128;-74.006").unwrap());
+        calendar.rebuild_indexes().unwrap();
+
+        let accessor = EventQueryIndexAccessor::new(&calendar);
+
+        let where_conditional = WhereConditional::Operator(
+            Box::new(WhereConditional::Property(
+                WhereConditionalProperty::Geo(GeoDistance::new_from_kilometers_float(10.0_f64), GeoPoint::new(51.5074_f64, -0.1278_f64)),
+            )),
+            Box::new(WhereConditional::Property(
+                WhereConditionalProperty::Categories(String::from("YOGA")),
+            )),
+            WhereOperator::And,
+        );
+
+        let (explanation, inverted_calendar_index_term) =
+            WhereConditionalExplanation::build(&where_conditional, &accessor, &ExecutionDeadline::default()).unwrap();
+
+        assert_eq!(
+            inverted_calendar_index_term,
+            where_conditional.execute(&accessor, &ExecutionDeadline::default()).unwrap(),
+        );
+
+        // Only the distance of the YOGA events is refined, so EVENT_THREE is not counted.
+        assert_eq!(
+            explanation,
+            WhereConditionalExplanation {
+                node: String::from("AND"),
+                index: None,
+                candidate_count: 1,
+                children: vec![
+                    WhereConditionalExplanation {
+                        node: String::from("GEO:51.5074;-0.1278;10KM"),
+                        index: Some(String::from("GEO")),
+                        candidate_count: 1,
+                        children: Vec::new(),
+                    },
+                    WhereConditionalExplanation {
+                        node: String::from("CATEGORIES:YOGA"),
+                        index: Some(String::from("CATEGORIES")),
+                        candidate_count: 2,
+                        children: Vec::new(),
+                    },
+                ],
+            },
+        );
+    }
+
+    #[test]
+    fn test_query_explanation_new() {
+        let calendar = calendar_with_categorised_events();
+
+        let accessor = EventQueryIndexAccessor::new(&calendar);
+
+        let where_conditional = WhereConditional::Property(
+            WhereConditionalProperty::Categories(String::from("ART")),
+        );
+
+        let where_conditional_explanation =
+            WhereConditionalExplanation::build(&where_conditional, &accessor, &ExecutionDeadline::default()).unwrap();
+
+        assert_eq!(
+            QueryExplanation::new(
+                Some(where_conditional_explanation.clone()),
+                calendar.events.keys().collect(),
+                Some(HashSet::from([String::from("EVENT_ONE"), String::from("EVENT_THREE")])),
+                &OrderingCondition::Score(None),
+                10,
+                50,
+                true,
+            ),
+            QueryExplanation {
+                where_conditional: Some(where_conditional_explanation.0),
+                event_count: 3,
+                candidate_count: 1,
+                time_bucketed: true,
+                ordering: String::from("SCORE"),
+                offset_strategy: OffsetStrategy::SkipAfterSorting,
+                offset: 10,
+                limit: 50,
+                distinct_uids: true,
+            },
+        );
+
+        assert_eq!(
+            QueryExplanation::new(
+                None,
+                calendar.events.keys().collect(),
+                None,
+                &OrderingCondition::GeoDistDtStart(GeoPoint { lat: 51.5074_f64, long: -0.1278_f64 }),
+                0,
+                50,
+                false,
+            ),
+            QueryExplanation {
+                where_conditional: None,
+                event_count: 3,
+                candidate_count: 3,
+                time_bucketed: false,
+                ordering: String::from("GEO-DIST-DTSTART;51.5074;-0.1278"),
+                offset_strategy: OffsetStrategy::SkipWhileIterating,
+                offset: 0,
+                limit: 50,
+                distinct_uids: false,
+            },
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use geo::HaversineDistance;
+
+use redical_ical::properties::query::{GroupedWhereProperty, QueryProperties, QueryProperty, WherePropertiesGroup};
+
+use crate::inverted_index::TermNormalization;
+use crate::queries::indexed_property_filters::{WhereConditional, WhereConditionalProperty, WhereOperator};
+
+/// Lints the parsed query for obviously degenerate where conditions, returning a warning
+/// describing each of them so that clients learn why their query can never match anything (or
+/// ignores part of itself) rather than silently receiving empty results.
+///
+/// This does not attempt to prove satisfiability, it only flags:
+///
+/// * Empty where groups `()` (which have no conditions and are ignored).
+/// * `AND`-ed (single valued) UID, CLASS or LOCATION properties with differing values.
+/// * `AND`-ed GEO properties whose circles do not overlap.
+/// * A property `AND`-ed with its own negation (e.g. `X-CATEGORIES:ART AND X-CATEGORIES-NOT:ART`).
+pub fn lint_query(query_properties: &QueryProperties, where_conditional: Option<&WhereConditional>) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for query_property in query_properties.0.iter() {
+        if let QueryProperty::WherePropertiesGroup(where_properties_group) = query_property {
+            lint_where_properties_group(where_properties_group, &mut warnings);
+        }
+    }
+
+    if let Some(where_conditional) = where_conditional {
+        lint_where_conditional(where_conditional, &mut warnings);
+    }
+
+    warnings
+}
+
+fn lint_where_properties_group(where_properties_group: &WherePropertiesGroup, warnings: &mut Vec<String>) {
+    if where_properties_group.properties.is_empty() {
+        warnings.push(String::from("empty where group () has no conditions and is ignored"));
+
+        return;
+    }
+
+    for grouped_where_property in where_properties_group.properties.iter() {
+        if let GroupedWhereProperty::WherePropertiesGroup(_, nested_where_properties_group) = grouped_where_property {
+            lint_where_properties_group(nested_where_properties_group, warnings);
+        }
+    }
+}
+
+fn lint_where_conditional(where_conditional: &WhereConditional, warnings: &mut Vec<String>) {
+    match where_conditional {
+        WhereConditional::Property(_) | WhereConditional::NegatedProperty(_) => {}
+
+        WhereConditional::Group(where_conditional) => lint_where_conditional(where_conditional, warnings),
+
+        WhereConditional::Operator(where_conditional_a, where_conditional_b, WhereOperator::Or) => {
+            lint_where_conditional(where_conditional_a, warnings);
+            lint_where_conditional(where_conditional_b, warnings);
+        }
+
+        WhereConditional::Operator(_, _, WhereOperator::And) => {
+            let mut conjunction = Vec::new();
+
+            collect_conjunction(where_conditional, &mut conjunction, warnings);
+
+            for (index, conjunct_a) in conjunction.iter().enumerate() {
+                for conjunct_b in conjunction.iter().skip(index + 1) {
+                    if is_contradiction(conjunct_a, conjunct_b) {
+                        warnings.push(
+                            format!(
+                                "{} AND {} can never both be satisfied",
+                                describe_conjunct(conjunct_a),
+                                describe_conjunct(conjunct_b),
+                            )
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Flattens the `AND`-ed (and grouped) properties into a single conjunction of properties (paired
+// with whether they are negated), linting any nested `OR`-ed where conditionals on their own.
+fn collect_conjunction<'a>(
+    where_conditional: &'a WhereConditional,
+    conjunction: &mut Vec<(&'a WhereConditionalProperty, bool)>,
+    warnings: &mut Vec<String>,
+) {
+    match where_conditional {
+        WhereConditional::Property(where_conditional_property) => conjunction.push((where_conditional_property, false)),
+
+        WhereConditional::NegatedProperty(where_conditional_property) => conjunction.push((where_conditional_property, true)),
+
+        WhereConditional::Group(where_conditional) => collect_conjunction(where_conditional, conjunction, warnings),
+
+        WhereConditional::Operator(where_conditional_a, where_conditional_b, WhereOperator::And) => {
+            collect_conjunction(where_conditional_a, conjunction, warnings);
+            collect_conjunction(where_conditional_b, conjunction, warnings);
+        }
+
+        WhereConditional::Operator(_, _, WhereOperator::Or) => lint_where_conditional(where_conditional, warnings),
+    }
+}
+
+fn is_contradiction(
+    (property_a, negated_a): &(&WhereConditionalProperty, bool),
+    (property_b, negated_b): &(&WhereConditionalProperty, bool),
+) -> bool {
+    match (negated_a, negated_b) {
+        (false, false) => is_mutually_exclusive(property_a, property_b),
+
+        // Prefixed or regex matched categories (and classes), and transitive relations cannot be
+        // negated (erroring when executed).
+        (false, true) | (true, false) => {
+            property_a == property_b
+                && !matches!(
+                    property_a,
+                    WhereConditionalProperty::CategoriesPrefix(_)
+                        | WhereConditionalProperty::CategoriesRegex(_)
+                        | WhereConditionalProperty::ClassRegex(_)
+                        | WhereConditionalProperty::TransitiveRelatedTo(_, _)
+                )
+        }
+
+        (true, true) => false,
+    }
+}
+
+// Events only have a single UID, CLASS and LOCATION, so no event can match two different values of
+// them (compared with every term normalisation applied to err on the side of caution, as the
+// Calendar may declare any of them), likewise no event can be within the distance of two GEO points
+// that are further apart than both distances combined.
+fn is_mutually_exclusive(property_a: &WhereConditionalProperty, property_b: &WhereConditionalProperty) -> bool {
+    match (property_a, property_b) {
+        (WhereConditionalProperty::UID(value_a), WhereConditionalProperty::UID(value_b))
+        | (WhereConditionalProperty::Class(value_a), WhereConditionalProperty::Class(value_b))
+        | (WhereConditionalProperty::Location(value_a), WhereConditionalProperty::Location(value_b)) => {
+            let term_normalization = TermNormalization::new(true, true, true);
+
+            term_normalization.normalize(value_a) != term_normalization.normalize(value_b)
+        }
+
+        (WhereConditionalProperty::Geo(distance_a, long_lat_a), WhereConditionalProperty::Geo(distance_b, long_lat_b)) => {
+            long_lat_a.haversine_distance(long_lat_b) > distance_a.to_meters_float() + distance_b.to_meters_float()
+        }
+
+        _ => false,
+    }
+}
+
+fn describe_conjunct((where_conditional_property, negated): &(&WhereConditionalProperty, bool)) -> String {
+    let (index, term) = where_conditional_property.get_index_and_term();
+
+    if *negated {
+        format!("NOT {index}:{term}")
+    } else {
+        format!("{index}:{term}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions_sorted::assert_eq;
+
+    use std::str::FromStr;
+
+    use crate::queries::event_instance_query::EventInstanceQuery;
+    use crate::queries::query::Query;
+
+    // Followed by X-LIMIT, as a closing bracket ending the query is otherwise parsed as part of the
+    // preceding TEXT value.
+    fn lint_query_string(input: &str) -> Vec<String> {
+        let query = EventInstanceQuery::from_str(format!("{input} X-LIMIT:50").as_str()).unwrap();
+
+        query.get_warnings().to_owned()
+    }
+
+    #[test]
+    fn test_lint_query_without_degenerate_conditions() {
+        assert_eq!(lint_query_string(""), Vec::<String>::new());
+
+        assert_eq!(
+            lint_query_string("X-UID:A X-CATEGORIES:ART X-CLASS:PUBLIC"),
+            Vec::<String>::new(),
+        );
+
+        // Differing UIDs are fine when OR-ed.
+        assert_eq!(
+            lint_query_string("(X-UID:A OR X-UID:B) X-CATEGORIES:ART"),
+            Vec::<String>::new(),
+        );
+
+        // Values differing only by a term normalisation may both be satisfied.
+        assert_eq!(
+            lint_query_string("X-UID:Café X-UID:cafe"),
+            Vec::<String>::new(),
+        );
+
+        // Overlapping GEO circles can both be satisfied.
+        assert_eq!(
+            lint_query_string("X-GEO;DIST=10KM:48.85299;2.36885 X-GEO;DIST=10KM:48.86;2.37"),
+            Vec::<String>::new(),
+        );
+    }
+
+    #[test]
+    fn test_lint_query_with_degenerate_conditions() {
+        assert_eq!(
+            lint_query_string("X-UID:A X-UID:B"),
+            vec![String::from("UID:A AND UID:B can never both be satisfied")],
+        );
+
+        assert_eq!(
+            lint_query_string("(X-CLASS:PUBLIC AND (X-CATEGORIES:ART AND X-CLASS:PRIVATE))"),
+            vec![String::from("CLASS:PUBLIC AND CLASS:PRIVATE can never both be satisfied")],
+        );
+
+        assert_eq!(
+            lint_query_string("X-CATEGORIES:ART X-CATEGORIES-NOT:ART"),
+            vec![String::from("CATEGORIES:ART AND NOT CATEGORIES:ART can never both be satisfied")],
+        );
+
+        // London and Paris are ~340km apart.
+        assert_eq!(
+            lint_query_string("X-GEO;DIST=10KM:51.5074;-0.1278 X-GEO;DIST=10KM:48.85299;2.36885"),
+            vec![String::from("GEO:51.5074;-0.1278;10KM AND GEO:48.85299;2.36885;10KM can never both be satisfied")],
+        );
+
+        // Contradictions are found within each branch of an OR.
+        assert_eq!(
+            lint_query_string("((X-UID:A AND X-UID:B) OR X-CATEGORIES:ART)"),
+            vec![String::from("UID:A AND UID:B can never both be satisfied")],
+        );
+
+        assert_eq!(
+            lint_query_string("X-CATEGORIES:ART (X-UID:A OR ())"),
+            vec![String::from("empty where group () has no conditions and is ignored")],
+        );
+    }
+}
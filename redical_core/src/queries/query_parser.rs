@@ -1,44 +1,69 @@
 use std::str::FromStr;
 
+use chrono_tz::Tz;
+
 use crate::queries::indexed_property_filters::{
     WhereConditional, WhereConditionalProperty, WhereOperator,
 };
 
+use crate::event::localize_floating_date_times;
 use crate::geo_index::GeoPoint;
 use crate::queries::query::Query;
+use crate::queries::query_lint::lint_query;
 use crate::queries::results::QueryableEntity;
 
 use crate::{GeoDistance, KeyValuePair};
 
 use redical_ical::properties::ICalendarGeoProperty;
+use redical_ical::values::tzid::Tzid;
 
 use redical_ical::properties::query::{
     QueryProperty,
     QueryProperties,
     XUIDProperty,
     XDistinctProperty,
+    XGroupByProperty,
     XCategoriesProperty,
     XLocationTypeProperty,
     XRelatedToProperty,
     XGeoProperty,
     XClassProperty,
+    XLocationProperty,
+    XPropProperty,
     WherePropertiesGroup,
     GroupedWhereProperty,
 };
 
 pub fn parse_query_string<T: QueryableEntity, Q: Query<T>>(input: &str) -> Result<Q, String> {
+    parse_query_string_with_default_timezone(input, None)
+}
+
+// Parse the query with consideration to the (Calendar) default timezone, in which the floating
+// X-FROM and X-UNTIL date-times (specified without a UTC designator or TZID) are interpreted, and
+// in which the results are rendered unless overridden by X-TZID.
+pub fn parse_query_string_with_default_timezone<T: QueryableEntity, Q: Query<T>>(input: &str, default_tz: Option<&Tz>) -> Result<Q, String> {
+    let mut default_query = Q::default();
+
+    if let Some(default_tz) = default_tz {
+        default_query.set_in_timezone(default_tz.to_owned());
+    }
+
     // Just return the default Query (return everything) if passed empty string ("").
     if input.is_empty() {
-        return Ok(Q::default());
+        return Ok(default_query);
     }
 
-    let query_properties = QueryProperties::from_str(input)?;
+    let mut query_properties = QueryProperties::from_str(input)?;
 
-    let query =
+    if let Some(default_tz) = default_tz {
+        localize_query_properties(&mut query_properties, default_tz)?;
+    }
+
+    let mut query: Q =
         query_properties
             .0
             .iter()
-            .fold(Q::default(), |mut query, query_property| {
+            .fold(default_query, |mut query, query_property| {
                 match query_property {
                     QueryProperty::XOffset(x_offset_property) => {
                         query.set_offset(x_offset_property.into());
@@ -48,10 +73,34 @@ pub fn parse_query_string<T: QueryableEntity, Q: Query<T>>(input: &str) -> Resul
                         query.set_limit(x_limit_property.into());
                     }
 
+                    QueryProperty::XLimitPerEvent(x_limit_per_event_property) => {
+                        query.set_limit_per_event(Some(x_limit_per_event_property.into()));
+                    }
+
+                    QueryProperty::XDstPolicy(x_dst_policy_property) => {
+                        query.set_dst_policy(Some(x_dst_policy_property.dst_policy.into()));
+                    }
+
                     QueryProperty::XDistinct(XDistinctProperty::UID) => {
                         query.set_distinct_uids(true);
                     }
 
+                    QueryProperty::XGroupBy(XGroupByProperty::UID) => {
+                        query.set_group_by_uid(true);
+                    }
+
+                    QueryProperty::XReturn(x_return_property) => {
+                        query.set_return_properties(Some(x_return_property.property_names.to_owned()));
+                    }
+
+                    QueryProperty::XCount(x_count_property) => {
+                        query.set_count_only(x_count_property.count);
+                    }
+
+                    QueryProperty::XMergeIntervals(x_merge_intervals_property) => {
+                        query.set_merge_intervals(x_merge_intervals_property.merge_intervals);
+                    }
+
                     QueryProperty::XFrom(x_from_property) => {
                         query.set_lower_bound_range_condition(Some(x_from_property.into()));
                     }
@@ -60,6 +109,10 @@ pub fn parse_query_string<T: QueryableEntity, Q: Query<T>>(input: &str) -> Resul
                         query.set_upper_bound_range_condition(Some(x_until_property.into()));
                     }
 
+                    QueryProperty::XDuration(x_duration_property) => {
+                        query.insert_duration_range_condition(x_duration_property.into());
+                    }
+
                     QueryProperty::XTzid(x_tzid_property) => {
                         query.set_in_timezone(x_tzid_property.into());
                     }
@@ -104,6 +157,18 @@ pub fn parse_query_string<T: QueryableEntity, Q: Query<T>>(input: &str) -> Resul
                         );
                     }
 
+                    QueryProperty::XLocation(x_location_property) => {
+                        query.insert_new_where_conditional(
+                            build_location_property_condition(x_location_property)
+                        );
+                    }
+
+                    QueryProperty::XProp(x_prop_property) => {
+                        query.insert_new_where_conditional(
+                            build_x_prop_property_condition(x_prop_property)
+                        );
+                    }
+
                     QueryProperty::WherePropertiesGroup(where_properties_group) => {
                         query.insert_new_where_conditional(
                             build_grouped_conditional(where_properties_group)
@@ -114,9 +179,33 @@ pub fn parse_query_string<T: QueryableEntity, Q: Query<T>>(input: &str) -> Resul
                 query
             });
 
+    let warnings = lint_query(&query_properties, query.get_where_conditional().as_ref());
+
+    query.set_warnings(warnings);
+
     Ok(query)
 }
 
+fn localize_query_properties(query_properties: &mut QueryProperties, default_tz: &Tz) -> Result<(), String> {
+    let default_tzid = Tzid(default_tz.to_owned());
+
+    for query_property in query_properties.0.iter_mut() {
+        match query_property {
+            QueryProperty::XFrom(x_from_property) => {
+                localize_floating_date_times(&mut x_from_property.params.tzid, [&x_from_property.date_time], &default_tzid)?;
+            }
+
+            QueryProperty::XUntil(x_until_property) => {
+                localize_floating_date_times(&mut x_until_property.params.tzid, [&x_until_property.date_time], &default_tzid)?;
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
 macro_rules! fold_terms {
     ($variant:ident, $terms:expr, $op:expr) => {{
         if $terms.len() == 0 { return None }
@@ -165,22 +254,58 @@ macro_rules! fold_negated_terms {
     }}
 }
 
+// Values ending with a `*` wildcard (e.g. `COURSE/*`) match any term starting with the value
+// preceding it (e.g. `COURSE/`), which is resolved via an ordered scan of the index terms rather
+// than enumerating each of them.
+fn build_wildcard_terms(
+    values: Vec<String>,
+    negated: bool,
+    where_operator: WhereOperator,
+    build_term: fn(String) -> WhereConditionalProperty,
+    build_prefix_term: fn(String) -> WhereConditionalProperty,
+) -> Option<WhereConditional> {
+    let where_conditionals =
+        values
+            .into_iter()
+            .map(|value| {
+                let where_conditional_property = match value.strip_suffix('*') {
+                    Some(prefix) => build_prefix_term(prefix.to_owned()),
+                    None => build_term(value),
+                };
+
+                if negated {
+                    WhereConditional::NegatedProperty(where_conditional_property)
+                } else {
+                    WhereConditional::Property(where_conditional_property)
+                }
+            });
+
+    fold_where_conditionals(where_conditionals, where_operator)
+}
+
 // Operator is hardcoded for UIDs due to the mutual exclusivity between separate
 // events (ie an event can't have multiple UIDs).
 fn build_uid_property_condition(property: &XUIDProperty) -> Option<WhereConditional> {
-    if property.negated {
-        fold_negated_terms!(
-            UID,
-            property.get_uids(),
-            WhereOperator::And
-        )
-    } else {
-        fold_terms!(
-            UID,
-            property.get_uids(),
-            WhereOperator::Or
-        )
+    use redical_ical::properties::query::x_uid::OpValue as XUIDOpValue;
+
+    let where_operator = if property.negated { WhereOperator::And } else { WhereOperator::Or };
+
+    // Each pattern is tested against every event UID, matching any of them.
+    if property.params.op == XUIDOpValue::Regex {
+        if property.negated {
+            return fold_negated_terms!(UIDRegex, property.get_uids(), where_operator.clone());
+        } else {
+            return fold_terms!(UIDRegex, property.get_uids(), where_operator.clone());
+        }
     }
+
+    build_wildcard_terms(
+        property.get_uids(),
+        property.negated,
+        where_operator,
+        WhereConditionalProperty::UID,
+        WhereConditionalProperty::UIDPrefix,
+    )
 }
 
 fn build_location_type_property_condition(property: &XLocationTypeProperty) -> Option<WhereConditional> {
@@ -200,42 +325,113 @@ fn build_location_type_property_condition(property: &XLocationTypeProperty) -> O
 }
 
 fn build_categories_property_condition(property: &XCategoriesProperty) -> Option<WhereConditional> {
-    if property.negated {
-        fold_negated_terms!(
-            Categories,
-            property.get_categories(),
-            property.params.op.clone().into()
-        )
-    } else {
-        fold_terms!(
-            Categories,
-            property.get_categories(),
-            property.params.op.clone().into()
-        )
-    }
+    use redical_ical::properties::query::x_categories::OpValue as XCategoriesOpValue;
+
+    let where_operator: WhereOperator = match &property.params.op {
+        XCategoriesOpValue::Where(where_operator) => where_operator.clone().into(),
+
+        // Each prefix is range scanned over the ordered categories index terms, matching any of
+        // them.
+        XCategoriesOpValue::Prefix => {
+            return fold_terms!(
+                CategoriesPrefix,
+                property.get_categories(),
+                WhereOperator::Or
+            );
+        },
+
+        // Each pattern is tested against every categories index term, matching any of them.
+        XCategoriesOpValue::Regex => {
+            return fold_terms!(
+                CategoriesRegex,
+                property.get_categories(),
+                WhereOperator::Or
+            );
+        },
+    };
+
+    build_wildcard_terms(
+        property.get_categories(),
+        property.negated,
+        where_operator,
+        WhereConditionalProperty::Categories,
+        WhereConditionalProperty::CategoriesPrefix,
+    )
 }
 
 fn build_related_to_property_condition(property: &XRelatedToProperty) -> Option<WhereConditional> {
-    let reltype = property.get_reltype();
+    let reltypes = property.get_reltypes();
+
+    // Relations walked transitively (DEPTH > 1) are resolved through the RELATED-TO index when
+    // the query is executed.
+    let depth = property.get_depth();
 
-    let key_values: Vec<_> = property.get_uids()
-        .into_iter()
-        .map(|uid| KeyValuePair::new(reltype.to_string(), uid))
-        .collect();
+    let build_where_conditional_property = |key_value: KeyValuePair| {
+        if depth > 1 {
+            WhereConditionalProperty::TransitiveRelatedTo(key_value, depth)
+        } else {
+            WhereConditionalProperty::RelatedTo(key_value)
+        }
+    };
 
+    // Events not related to a UID by any of the RELTYPEs are related by neither of them, e.g.
+    // `X-RELATED-TO-NOT;RELTYPE=PARENT,CHILD:UID` is equivalent to
+    // `(X-RELATED-TO-NOT;RELTYPE=PARENT:UID AND X-RELATED-TO-NOT;RELTYPE=CHILD:UID)`.
     if property.negated {
-        fold_negated_terms!(
-            RelatedTo,
-            key_values,
-            property.params.op.clone().into()
-        )
-    } else {
-        fold_terms!(
+        let key_values: Vec<_> = property.get_uids()
+            .into_iter()
+            .flat_map(|uid| reltypes.iter().map(move |reltype| KeyValuePair::new(reltype.to_string(), uid.to_owned())))
+            .collect();
+
+        return fold_negated_terms!(
             RelatedTo,
             key_values,
             property.params.op.clone().into()
-        )
+        );
+    }
+
+    // Events related to each UID by any of the RELTYPEs are matched, e.g.
+    // `X-RELATED-TO;RELTYPE=PARENT,CHILD;OP=AND:UID_ONE,UID_TWO` is equivalent to
+    // `((X-RELATED-TO;RELTYPE=PARENT:UID_ONE OR X-RELATED-TO;RELTYPE=CHILD:UID_ONE) AND (...))`.
+    let uid_conditions =
+        property.get_uids()
+            .into_iter()
+            .filter_map(|uid| {
+                fold_where_conditionals(
+                    reltypes
+                        .iter()
+                        .map(|reltype| {
+                            WhereConditional::Property(
+                                build_where_conditional_property(KeyValuePair::new(reltype.to_string(), uid.to_owned()))
+                            )
+                        }),
+                    WhereOperator::Or,
+                )
+            });
+
+    fold_where_conditionals(uid_conditions, property.params.op.clone().into())
+}
+
+// Combines each where conditional with the operator, grouping them if there are more than one.
+fn fold_where_conditionals(
+    mut where_conditionals: impl Iterator<Item = WhereConditional>,
+    where_operator: WhereOperator,
+) -> Option<WhereConditional> {
+    let first_where_conditional = where_conditionals.next()?;
+
+    let mut is_grouped = false;
+
+    let where_conditional = where_conditionals.fold(first_where_conditional, |last, where_conditional| {
+        is_grouped = true;
+
+        WhereConditional::Operator(Box::new(last), Box::new(where_conditional), where_operator.clone())
+    });
+
+    if !is_grouped {
+        return Some(where_conditional);
     }
+
+    Some(WhereConditional::Group(Box::new(where_conditional)))
 }
 
 fn build_geo_property_condition(property: &XGeoProperty) -> Option<WhereConditional> {
@@ -267,22 +463,90 @@ fn build_geo_property_condition(property: &XGeoProperty) -> Option<WhereConditio
 }
 
 fn build_class_property_condition(property: &XClassProperty) -> Option<WhereConditional> {
+    use redical_ical::properties::query::x_class::OpValue as XClassOpValue;
+
+    let where_operator: WhereOperator = match &property.params.op {
+        XClassOpValue::Where(where_operator) => where_operator.to_owned().into(),
+
+        // Each pattern is tested against every class index term, matching any of them.
+        XClassOpValue::Regex => {
+            return fold_terms!(
+                ClassRegex,
+                property.get_classifications(),
+                WhereOperator::Or
+            );
+        },
+    };
+
     if property.negated {
         fold_negated_terms!(
             Class,
             property.get_classifications(),
-            property.params.op.to_owned().into()
+            where_operator.clone()
         )
     } else {
         fold_terms!(
             Class,
             property.get_classifications(),
+            where_operator.clone()
+        )
+    }
+}
+
+fn build_location_property_condition(property: &XLocationProperty) -> Option<WhereConditional> {
+    if property.negated {
+        fold_negated_terms!(
+            Location,
+            property.get_locations(),
+            property.params.op.to_owned().into()
+        )
+    } else {
+        fold_terms!(
+            Location,
+            property.get_locations(),
             property.params.op.to_owned().into()
         )
     }
 }
 
+fn build_x_prop_property_condition(property: &XPropProperty) -> Option<WhereConditional> {
+    let name = property.get_name();
+
+    let name_values: Vec<KeyValuePair> =
+        property.get_values()
+            .into_iter()
+            .map(|value| KeyValuePair::new(name.to_owned(), value))
+            .collect();
+
+    if property.negated {
+        fold_negated_terms!(
+            XProperty,
+            name_values,
+            property.params.op.to_owned().into()
+        )
+    } else {
+        fold_terms!(
+            XProperty,
+            name_values,
+            property.params.op.to_owned().into()
+        )
+    }
+}
+
+// Builds the where conditional of the group, combining each of it's properties with the operator
+// preceding them (defaulting to AND).
+//
+// An empty group `()` (or one only containing empty groups) has no conditions, so is defined as a
+// no-op which is dropped as though it were never specified, along with the operator joining it to
+// the preceding condition (or to the following condition if it is the first of it's group), e.g.
+// `(X-UID:A OR () AND X-UID:B)` is equivalent to `(X-UID:A AND X-UID:B)`. This is consistent
+// regardless of the operator joining it, whereas matching either everything or nothing would
+// make `X-UID:A AND ()` and `X-UID:A OR ()` differ.
 fn build_grouped_conditional(where_properties_group: &WherePropertiesGroup) -> Option<WhereConditional> {
+    if where_properties_group.properties.is_empty() {
+        return None;
+    }
+
     let mut current_where_conditional: Option<WhereConditional> = None;
 
     for grouped_where_property in &where_properties_group.properties {
@@ -317,6 +581,16 @@ fn build_grouped_conditional(where_properties_group: &WherePropertiesGroup) -> O
                 external_operator,
             ),
 
+            GroupedWhereProperty::XLocation(external_operator, x_location_property) => (
+                build_location_property_condition(x_location_property),
+                external_operator,
+            ),
+
+            GroupedWhereProperty::XProp(external_operator, x_prop_property) => (
+                build_x_prop_property_condition(x_prop_property),
+                external_operator,
+            ),
+
             GroupedWhereProperty::WherePropertiesGroup(external_operator, nested_where_properties_group) => (
                 build_grouped_conditional(nested_where_properties_group),
                 external_operator,
@@ -348,6 +622,7 @@ fn build_grouped_conditional(where_properties_group: &WherePropertiesGroup) -> O
 #[cfg(test)]
 mod test {
     use crate::queries::event_instance_query::EventInstanceQuery;
+    use crate::EventInstance;
 
     use super::*;
     use pretty_assertions_sorted::assert_eq;
@@ -362,7 +637,8 @@ mod test {
 
     use crate::queries::results_ordering::OrderingCondition;
     use crate::queries::results_range_bounds::{
-        LowerBoundRangeCondition, RangeConditionProperty, UpperBoundRangeCondition,
+        DurationRangeCondition, LowerBoundRangeCondition, RangeConditionProperty,
+        UpperBoundRangeCondition,
     };
 
     use crate::{GeoDistance, KeyValuePair};
@@ -403,6 +679,86 @@ mod test {
                 )),
             )),
         );
+
+        // Regex patterns are always OR-ed (an event only has a single class).
+        assert_eq!(
+            build_class_property_condition(&build_property_from_ical!(XClassProperty, "X-CLASS;OP=REGEX:X-TIER-[0-9]+,PUB.*")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::ClassRegex(String::from("X-TIER-[0-9]+")),
+                    )),
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::ClassRegex(String::from("PUB.*")),
+                    )),
+                    WhereOperator::Or,
+                )),
+            )),
+        );
+    }
+
+    #[test]
+    fn test_build_location_property_condition() {
+        assert_eq!(
+            build_location_property_condition(&build_property_from_ical!(XLocationProperty, "X-LOCATION:")),
+            None,
+        );
+
+        assert_eq!(
+            build_location_property_condition(&build_property_from_ical!(XLocationProperty, "X-LOCATION-NOT: Royal  Albert Hall")),
+            Some(WhereConditional::NegatedProperty(
+                WhereConditionalProperty::Location(String::from("Royal Albert Hall")),
+            )),
+        );
+
+        assert_eq!(
+            build_location_property_condition(
+                &build_property_from_ical!(XLocationProperty, "X-LOCATION;OP=OR:Royal Albert Hall,Wembley Arena")
+            ),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::Location(String::from("Royal Albert Hall")),
+                    )),
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::Location(String::from("Wembley Arena")),
+                    )),
+                    WhereOperator::Or,
+                )),
+            )),
+        );
+    }
+
+    #[test]
+    fn test_build_x_prop_property_condition() {
+        assert_eq!(
+            build_x_prop_property_condition(&build_property_from_ical!(XPropProperty, "X-PROP;NAME=X-INSTRUCTOR:")),
+            None,
+        );
+
+        assert_eq!(
+            build_x_prop_property_condition(&build_property_from_ical!(XPropProperty, "X-PROP-NOT;NAME=X-Instructor:Jane Doe")),
+            Some(WhereConditional::NegatedProperty(
+                WhereConditionalProperty::XProperty(KeyValuePair::new(String::from("X-INSTRUCTOR"), String::from("Jane Doe"))),
+            )),
+        );
+
+        assert_eq!(
+            build_x_prop_property_condition(
+                &build_property_from_ical!(XPropProperty, "X-PROP;NAME=X-LEVEL;OP=OR:Beginner,Advanced")
+            ),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::XProperty(KeyValuePair::new(String::from("X-LEVEL"), String::from("Beginner"))),
+                    )),
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::XProperty(KeyValuePair::new(String::from("X-LEVEL"), String::from("Advanced"))),
+                    )),
+                    WhereOperator::Or,
+                )),
+            )),
+        );
     }
 
     #[test]
@@ -439,10 +795,171 @@ mod test {
                 )),
             )),
         );
+
+        assert_eq!(
+            build_categories_property_condition(&build_property_from_ical!(XCategoriesProperty, "X-CATEGORIES;OP=PREFIX:SPORT")),
+            Some(WhereConditional::Property(
+                WhereConditionalProperty::CategoriesPrefix(String::from("SPORT")),
+            )),
+        );
+
+        assert_eq!(
+            build_categories_property_condition(&build_property_from_ical!(XCategoriesProperty, "X-CATEGORIES;OP=PREFIX:SPORT,MUSIC")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::CategoriesPrefix(String::from("SPORT")),
+                    )),
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::CategoriesPrefix(String::from("MUSIC")),
+                    )),
+                    WhereOperator::Or,
+                )),
+            )),
+        );
+
+        // Values with a trailing wildcard are matched as prefixes.
+        assert_eq!(
+            build_categories_property_condition(&build_property_from_ical!(XCategoriesProperty, "X-CATEGORIES;OP=OR:COURSE/*,WORKSHOP")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::CategoriesPrefix(String::from("COURSE/")),
+                    )),
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::Categories(String::from("WORKSHOP")),
+                    )),
+                    WhereOperator::Or,
+                )),
+            )),
+        );
+
+        // Regex patterns match any of them (trailing wildcards included).
+        assert_eq!(
+            build_categories_property_condition(&build_property_from_ical!(XCategoriesProperty, "X-CATEGORIES;OP=REGEX:COURSE/(MATHS|HISTORY),SPORT.*")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::CategoriesRegex(String::from("COURSE/(MATHS|HISTORY)")),
+                    )),
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::CategoriesRegex(String::from("SPORT.*")),
+                    )),
+                    WhereOperator::Or,
+                )),
+            )),
+        );
+    }
+
+    #[test]
+    fn test_build_uid_property_condition() {
+        assert_eq!(
+            build_uid_property_condition(&build_property_from_ical!(XUIDProperty, "X-UID:UID_ONE")),
+            Some(WhereConditional::Property(
+                WhereConditionalProperty::UID(String::from("UID_ONE")),
+            )),
+        );
+
+        assert_eq!(
+            build_uid_property_condition(&build_property_from_ical!(XUIDProperty, "X-UID:TENANT_123_*,UID_ONE")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::UIDPrefix(String::from("TENANT_123_")),
+                    )),
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::UID(String::from("UID_ONE")),
+                    )),
+                    WhereOperator::Or,
+                )),
+            )),
+        );
+
+        assert_eq!(
+            build_uid_property_condition(&build_property_from_ical!(XUIDProperty, "X-UID-NOT:TENANT_123_*,UID_ONE")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::NegatedProperty(
+                        WhereConditionalProperty::UIDPrefix(String::from("TENANT_123_")),
+                    )),
+                    Box::new(WhereConditional::NegatedProperty(
+                        WhereConditionalProperty::UID(String::from("UID_ONE")),
+                    )),
+                    WhereOperator::And,
+                )),
+            )),
+        );
+
+        assert_eq!(
+            build_uid_property_condition(&build_property_from_ical!(XUIDProperty, "X-UID;OP=REGEX:TENANT_[0-9]+_ONE")),
+            Some(WhereConditional::Property(
+                WhereConditionalProperty::UIDRegex(String::from("TENANT_[0-9]+_ONE")),
+            )),
+        );
+
+        assert_eq!(
+            build_uid_property_condition(&build_property_from_ical!(XUIDProperty, "X-UID-NOT;OP=REGEX:TENANT_[0-9]+_ONE,TENANT.*")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::NegatedProperty(
+                        WhereConditionalProperty::UIDRegex(String::from("TENANT_[0-9]+_ONE")),
+                    )),
+                    Box::new(WhereConditional::NegatedProperty(
+                        WhereConditionalProperty::UIDRegex(String::from("TENANT.*")),
+                    )),
+                    WhereOperator::And,
+                )),
+            )),
+        );
     }
 
     #[test]
     fn test_build_related_to_property_condition() {
+        assert_eq!(
+            build_related_to_property_condition(&build_property_from_ical!(XRelatedToProperty, "X-RELATED-TO;DEPTH=3:ROOT_UID")),
+            Some(WhereConditional::Property(
+                WhereConditionalProperty::TransitiveRelatedTo(
+                    KeyValuePair::new(
+                        String::from("PARENT"),
+                        String::from("ROOT_UID"),
+                    ),
+                    3,
+                ),
+            )),
+        );
+
+        assert_eq!(
+            build_related_to_property_condition(&build_property_from_ical!(XRelatedToProperty, "X-RELATED-TO;OP=OR;DEPTH=2:ROOT_UID_ONE,ROOT_UID_TWO")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::TransitiveRelatedTo(
+                            KeyValuePair::new(String::from("PARENT"), String::from("ROOT_UID_ONE")),
+                            2,
+                        ),
+                    )),
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::TransitiveRelatedTo(
+                            KeyValuePair::new(String::from("PARENT"), String::from("ROOT_UID_TWO")),
+                            2,
+                        ),
+                    )),
+                    WhereOperator::Or,
+                )),
+            )),
+        );
+
+        // DEPTH=1 is equivalent to only matching direct relations.
+        assert_eq!(
+            build_related_to_property_condition(&build_property_from_ical!(XRelatedToProperty, "X-RELATED-TO;DEPTH=1:PARENT_UID_ONE")),
+            Some(WhereConditional::Property(
+                WhereConditionalProperty::RelatedTo(KeyValuePair::new(
+                    String::from("PARENT"),
+                    String::from("PARENT_UID_ONE"),
+                )),
+            )),
+        );
+
         assert_eq!(
             build_related_to_property_condition(&build_property_from_ical!(XRelatedToProperty, "X-RELATED-TO:")),
             None,
@@ -487,6 +1004,84 @@ mod test {
                 )),
             )),
         );
+
+        let related_to = |reltype: &str, uid: &str| WhereConditional::Property(
+            WhereConditionalProperty::RelatedTo(KeyValuePair::new(String::from(reltype), String::from(uid))),
+        );
+
+        let not_related_to = |reltype: &str, uid: &str| WhereConditional::NegatedProperty(
+            WhereConditionalProperty::RelatedTo(KeyValuePair::new(String::from(reltype), String::from(uid))),
+        );
+
+        // Multiple RELTYPE values are OR-ed for each UID (disregarding duplicates).
+        assert_eq!(
+            build_related_to_property_condition(&build_property_from_ical!(XRelatedToProperty, "X-RELATED-TO;RELTYPE=PARENT,SIBLING,PARENT:SOME_UID")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(related_to("PARENT", "SOME_UID")),
+                    Box::new(related_to("SIBLING", "SOME_UID")),
+                    WhereOperator::Or,
+                )),
+            )),
+        );
+
+        assert_eq!(
+            build_related_to_property_condition(&build_property_from_ical!(XRelatedToProperty, "X-RELATED-TO;RELTYPE=PARENT,CHILD;OP=AND:UID_ONE,UID_TWO")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Group(
+                        Box::new(WhereConditional::Operator(
+                            Box::new(related_to("PARENT", "UID_ONE")),
+                            Box::new(related_to("CHILD", "UID_ONE")),
+                            WhereOperator::Or,
+                        )),
+                    )),
+                    Box::new(WhereConditional::Group(
+                        Box::new(WhereConditional::Operator(
+                            Box::new(related_to("PARENT", "UID_TWO")),
+                            Box::new(related_to("CHILD", "UID_TWO")),
+                            WhereOperator::Or,
+                        )),
+                    )),
+                    WhereOperator::And,
+                )),
+            )),
+        );
+
+        assert_eq!(
+            build_related_to_property_condition(&build_property_from_ical!(XRelatedToProperty, "X-RELATED-TO;RELTYPE=PARENT,CHILD;DEPTH=2:ROOT_UID")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::TransitiveRelatedTo(KeyValuePair::new(String::from("PARENT"), String::from("ROOT_UID")), 2),
+                    )),
+                    Box::new(WhereConditional::Property(
+                        WhereConditionalProperty::TransitiveRelatedTo(KeyValuePair::new(String::from("CHILD"), String::from("ROOT_UID")), 2),
+                    )),
+                    WhereOperator::Or,
+                )),
+            )),
+        );
+
+        // Negated, events are related to each UID by none of the RELTYPE values.
+        assert_eq!(
+            build_related_to_property_condition(&build_property_from_ical!(XRelatedToProperty, "X-RELATED-TO-NOT;RELTYPE=PARENT,SIBLING:UID_ONE,UID_TWO")),
+            Some(WhereConditional::Group(
+                Box::new(WhereConditional::Operator(
+                    Box::new(WhereConditional::Operator(
+                        Box::new(WhereConditional::Operator(
+                            Box::new(not_related_to("PARENT", "UID_ONE")),
+                            Box::new(not_related_to("SIBLING", "UID_ONE")),
+                            WhereOperator::And,
+                        )),
+                        Box::new(not_related_to("PARENT", "UID_TWO")),
+                        WhereOperator::And,
+                    )),
+                    Box::new(not_related_to("SIBLING", "UID_TWO")),
+                    WhereOperator::And,
+                )),
+            )),
+        );
     }
 
     #[test]
@@ -496,11 +1091,14 @@ mod test {
         let query_string = [
             "X-FROM;PROP=DTSTART;OP=GT;TZID=Europe/London:19971002T090000",
             "X-UNTIL;PROP=DTSTART;OP=LTE;TZID=UTC:19971102T090000",
+            "X-DURATION;OP=GTE:PT1H",
+            "X-DURATION;OP=LT:PT3H",
             "X-CATEGORIES;OP=OR:CATEGORY_ONE,CATEGORY_TWO",
             "X-RELATED-TO:PARENT_UID",
             "X-GEO;DIST=1.5KM:48.85299;2.36885",
             "X-CLASS-NOT:PRIVATE",
             "X-LIMIT:50",
+            "X-LIMIT-PER-EVENT:3",
             "X-TZID:Europe/Vilnius",
             "X-ORDER-BY:DTSTART-GEO-DIST;48.85299;2.36885",
         ]
@@ -560,18 +1158,79 @@ mod test {
 
                     lower_bound_range_condition: Some(LowerBoundRangeCondition::GreaterThan(RangeConditionProperty::DtStart(875779200))),
                     upper_bound_range_condition: Some(UpperBoundRangeCondition::LessEqualThan(RangeConditionProperty::DtStart(878461200))),
+                    duration_range_conditions: vec![
+                        DurationRangeCondition::GreaterEqualThan(3600),
+                        DurationRangeCondition::LessThan(10800),
+                    ],
 
                     in_timezone: chrono_tz::Tz::Europe__Vilnius,
 
                     distinct_uids: false,
+                    group_by_uid: false,
 
                     offset: 0,
                     limit: 50,
+                    limit_per_event: Some(3),
+                    dst_policy: None,
+                    return_properties: None,
+                    count_only: false,
+                    merge_intervals: false,
+                    warnings: Vec::new(),
                 }
             )
         );
     }
 
+    #[test]
+    fn test_parse_query_string_with_default_timezone() {
+        let default_tz = chrono_tz::Tz::Europe__London;
+
+        assert_eq!(
+            parse_query_string_with_default_timezone("", Some(&default_tz)),
+            Ok(
+                EventInstanceQuery {
+                    in_timezone: chrono_tz::Tz::Europe__London,
+                    ..EventInstanceQuery::default()
+                }
+            )
+        );
+
+        // Floating date-times are interpreted in the default timezone, UTC date-times are not.
+        assert_eq!(
+            parse_query_string_with_default_timezone(
+                "X-FROM;PROP=DTSTART;OP=GT:19971002T090000 X-UNTIL;PROP=DTSTART;OP=LTE:19971102T090000Z",
+                Some(&default_tz),
+            ),
+            Ok(
+                EventInstanceQuery {
+                    lower_bound_range_condition: Some(LowerBoundRangeCondition::GreaterThan(RangeConditionProperty::DtStart(875779200))),
+                    upper_bound_range_condition: Some(UpperBoundRangeCondition::LessEqualThan(RangeConditionProperty::DtStart(878461200))),
+                    in_timezone: chrono_tz::Tz::Europe__London,
+                    ..EventInstanceQuery::default()
+                }
+            )
+        );
+
+        // X-TZID takes precedence over the default timezone when rendering.
+        assert_eq!(
+            parse_query_string_with_default_timezone("X-TZID:Europe/Vilnius", Some(&default_tz)),
+            Ok(
+                EventInstanceQuery {
+                    in_timezone: chrono_tz::Tz::Europe__Vilnius,
+                    ..EventInstanceQuery::default()
+                }
+            )
+        );
+
+        // Floating date-times within a DST transition gap of the default timezone are rejected.
+        assert!(
+            parse_query_string_with_default_timezone::<EventInstance, EventInstanceQuery>(
+                "X-FROM;PROP=DTSTART;OP=GT:20210328T013000",
+                Some(&default_tz),
+            ).is_err()
+        );
+    }
+
     #[test]
     fn test_parse_query_string_with_grouped_conditionals() {
         let query_string = [
@@ -595,6 +1254,7 @@ mod test {
             "X-LIMIT:50",
             "X-OFFSET:10",
             "X-DISTINCT:UID",
+            "X-GROUP-BY:UID",
             "X-TZID:Europe/Vilnius",
             "X-ORDER-BY:DTSTART-GEO-DIST;48.85299;2.36885",
         ]
@@ -661,15 +1321,68 @@ mod test {
 
                     lower_bound_range_condition: Some(LowerBoundRangeCondition::GreaterThan(RangeConditionProperty::DtStart(875779200))),
                     upper_bound_range_condition: Some(UpperBoundRangeCondition::LessEqualThan(RangeConditionProperty::DtStart(878461200))),
+                    duration_range_conditions: Vec::new(),
 
                     in_timezone: chrono_tz::Tz::Europe__Vilnius,
 
                     distinct_uids: true,
+                    group_by_uid: true,
 
                     offset: 10,
                     limit: 50,
+                    limit_per_event: None,
+                    dst_policy: None,
+                    return_properties: None,
+                    count_only: false,
+                    merge_intervals: false,
+                    warnings: Vec::new(),
                 }
             )
         );
     }
+
+    #[test]
+    fn test_parse_query_string_with_empty_grouped_conditionals() {
+        // Followed by X-LIMIT, as a closing bracket ending the query is otherwise parsed as part of
+        // the preceding TEXT value.
+        let parse_where_conditional = |query_string: &str| -> Option<WhereConditional> {
+            parse_query_string::<EventInstance, EventInstanceQuery>(format!("{query_string} X-LIMIT:50").as_str()).unwrap().where_conditional
+        };
+
+        // Empty groups (or groups of only empty groups) have no conditions.
+        assert_eq!(parse_where_conditional("()"), None);
+        assert_eq!(parse_where_conditional("(())"), None);
+        assert_eq!(parse_where_conditional("() (() ())"), None);
+
+        // Empty groups are dropped (along with their operator) regardless of the operator joining them.
+        assert_eq!(parse_where_conditional("() X-UID:A"), parse_where_conditional("X-UID:A"));
+        assert_eq!(parse_where_conditional("(X-UID:A AND ())"), parse_where_conditional("(X-UID:A)"));
+        assert_eq!(parse_where_conditional("(X-UID:A OR ())"), parse_where_conditional("(X-UID:A)"));
+        assert_eq!(parse_where_conditional("(() OR X-UID:A)"), parse_where_conditional("(X-UID:A)"));
+
+        assert_eq!(
+            parse_where_conditional("(X-UID:A OR () AND X-UID:B)"),
+            parse_where_conditional("(X-UID:A AND X-UID:B)"),
+        );
+
+        assert_eq!(
+            parse_where_conditional("(X-UID:A OR (()) OR X-UID:B)"),
+            Some(WhereConditional::Group(Box::new(WhereConditional::Operator(
+                Box::new(WhereConditional::Property(WhereConditionalProperty::UID(String::from("A")))),
+                Box::new(WhereConditional::Property(WhereConditionalProperty::UID(String::from("B")))),
+                WhereOperator::Or,
+            )))),
+        );
+
+        // Otherwise returning everything (as an empty query does), with a warning.
+        assert_eq!(
+            parse_query_string::<EventInstance, EventInstanceQuery>("()"),
+            Ok(
+                EventInstanceQuery {
+                    warnings: vec![String::from("empty where group () has no conditions and is ignored")],
+                    ..EventInstanceQuery::default()
+                }
+            ),
+        );
+    }
 }
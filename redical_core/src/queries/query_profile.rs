@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The time spent within each phase of executing an event instance query, along with the
+/// expansion of each event considered.
+///
+/// The ordering phase covers everything not attributed to another phase (merging, scoring,
+/// offsetting, and limiting the results), so is derived from the overall execution duration.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct QueryProfile {
+    pub index_filtering_duration: Duration,
+    pub expansion_duration: Duration,
+    pub override_application_duration: Duration,
+    pub execution_duration: Duration,
+    pub event_expansions: BTreeMap<String, EventExpansionProfile>,
+}
+
+impl QueryProfile {
+    pub fn get_ordering_duration(&self) -> Duration {
+        self.execution_duration
+            .saturating_sub(self.index_filtering_duration)
+            .saturating_sub(self.expansion_duration)
+            .saturating_sub(self.override_application_duration)
+    }
+}
+
+/// The occurrences expanded for a specific event, the event instances yielded from them (after
+/// applying any overrides and filters), and the time spent expanding them.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct EventExpansionProfile {
+    pub expanded_count: usize,
+    pub instance_count: usize,
+    pub duration: Duration,
+}
+
+/// Records the `QueryProfile` of a query execution, shared with each event instance iterator
+/// expanding the events queried.
+#[derive(Debug, Clone, Default)]
+pub struct QueryProfiler {
+    query_profile: Arc<Mutex<QueryProfile>>,
+}
+
+impl QueryProfiler {
+    pub fn new() -> Self {
+        QueryProfiler::default()
+    }
+
+    pub fn get_profile(&self) -> QueryProfile {
+        self.query_profile.lock().unwrap().clone()
+    }
+
+    pub(crate) fn record_index_filtering(&self, duration: Duration) {
+        self.query_profile.lock().unwrap().index_filtering_duration += duration;
+    }
+
+    pub(crate) fn record_execution(&self, duration: Duration) {
+        self.query_profile.lock().unwrap().execution_duration += duration;
+    }
+
+    pub(crate) fn record_event_expansion(&self, event_uid: &str, expanded_count: usize, duration: Duration) {
+        let mut query_profile = self.query_profile.lock().unwrap();
+
+        query_profile.expansion_duration += duration;
+
+        let event_expansion_profile = query_profile.event_expansions.entry(event_uid.to_owned()).or_default();
+
+        event_expansion_profile.expanded_count += expanded_count;
+        event_expansion_profile.duration += duration;
+    }
+
+    pub(crate) fn record_override_application(&self, event_uid: &str, duration: Duration) {
+        let mut query_profile = self.query_profile.lock().unwrap();
+
+        query_profile.override_application_duration += duration;
+
+        query_profile.event_expansions.entry(event_uid.to_owned()).or_default().instance_count += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn test_query_profiler() {
+        let query_profiler = QueryProfiler::new();
+
+        query_profiler.record_execution(Duration::from_millis(100));
+        query_profiler.record_index_filtering(Duration::from_millis(10));
+        query_profiler.record_event_expansion("EVENT_ONE", 2, Duration::from_millis(20));
+        query_profiler.record_event_expansion("EVENT_ONE", 1, Duration::from_millis(5));
+        query_profiler.record_event_expansion("EVENT_TWO", 1, Duration::from_millis(15));
+        query_profiler.record_override_application("EVENT_ONE", Duration::from_millis(3));
+        query_profiler.record_override_application("EVENT_ONE", Duration::from_millis(2));
+
+        let query_profile = query_profiler.get_profile();
+
+        assert_eq!(
+            query_profile,
+            QueryProfile {
+                index_filtering_duration: Duration::from_millis(10),
+                expansion_duration: Duration::from_millis(40),
+                override_application_duration: Duration::from_millis(5),
+                execution_duration: Duration::from_millis(100),
+                event_expansions: BTreeMap::from([
+                    (
+                        String::from("EVENT_ONE"),
+                        EventExpansionProfile {
+                            expanded_count: 3,
+                            instance_count: 2,
+                            duration: Duration::from_millis(25),
+                        },
+                    ),
+                    (
+                        String::from("EVENT_TWO"),
+                        EventExpansionProfile {
+                            expanded_count: 1,
+                            instance_count: 0,
+                            duration: Duration::from_millis(15),
+                        },
+                    ),
+                ]),
+            },
+        );
+
+        assert_eq!(query_profile.get_ordering_duration(), Duration::from_millis(45));
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use regex::{Regex, RegexBuilder};
+
+lazy_static::lazy_static! {
+    static ref COMPILED_REGEX_CACHE: Mutex<HashMap<(String, bool), Regex>> = Mutex::new(HashMap::new());
+}
+
+/// The maximum length (in bytes) of a query `OP=REGEX` pattern.
+pub const MAX_REGEX_PATTERN_LENGTH: usize = 256;
+
+// Complexity limits applied when compiling each pattern. The regex crate guarantees matching in
+// linear time (no backtracking), so bounding the size of the compiled program (and the lazy DFA
+// built whilst matching) and the nesting depth bounds both the memory and the time spent matching
+// each index term, however pathological the pattern.
+const MAX_REGEX_COMPILED_SIZE: usize = 256 * 1024;
+const MAX_REGEX_DFA_SIZE: usize = 1024 * 1024;
+const MAX_REGEX_NEST_DEPTH: u32 = 16;
+
+// The cache is cleared once it holds this many compiled patterns, as the same (typically few)
+// patterns are expected to be queried repeatedly.
+const MAX_CACHED_REGEXES: usize = 1_024;
+
+/// Compile the (TEXT escaped) query `OP=REGEX` pattern, matched against whole index terms (i.e.
+/// implicitly anchored at both ends), reusing the previously compiled pattern where cached.
+///
+/// Patterns exceeding the length or complexity limits are rejected rather than compiled.
+pub fn compile_term_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, String> {
+    let cache_key = (pattern.to_owned(), case_insensitive);
+
+    if let Some(regex) = COMPILED_REGEX_CACHE.lock().unwrap().get(&cache_key) {
+        return Ok(regex.clone());
+    }
+
+    let unescaped_pattern = unescape_text(pattern);
+
+    if unescaped_pattern.len() > MAX_REGEX_PATTERN_LENGTH {
+        return Err(format!("REGEX pattern exceeds the maximum length of {MAX_REGEX_PATTERN_LENGTH} bytes"));
+    }
+
+    let regex =
+        RegexBuilder::new(&format!("^(?:{unescaped_pattern})$"))
+            .case_insensitive(case_insensitive)
+            .size_limit(MAX_REGEX_COMPILED_SIZE)
+            .dfa_size_limit(MAX_REGEX_DFA_SIZE)
+            .nest_limit(MAX_REGEX_NEST_DEPTH)
+            .build()
+            .map_err(|error| format!("invalid REGEX pattern: {pattern} - {error}"))?;
+
+    let mut compiled_regex_cache = COMPILED_REGEX_CACHE.lock().unwrap();
+
+    if compiled_regex_cache.len() >= MAX_CACHED_REGEXES {
+        compiled_regex_cache.clear();
+    }
+
+    compiled_regex_cache.insert(cache_key, regex.clone());
+
+    Ok(regex)
+}
+
+// Query values are parsed as TEXT, so the pattern is unescaped before it is compiled (e.g.
+// `\\d+` is compiled as `\d+` and `[a\,b]` as `[a,b]`).
+fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            unescaped.push(char);
+
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(escaped_char) => unescaped.push(escaped_char),
+            None => unescaped.push(char),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn test_compile_term_regex() {
+        let regex = compile_term_regex("COURSE/(MATHS|HISTORY)", false).unwrap();
+
+        assert!(regex.is_match("COURSE/MATHS"));
+        assert!(regex.is_match("COURSE/HISTORY"));
+
+        // Whole terms are matched.
+        assert!(!regex.is_match("COURSE/MATHS/ALGEBRA"));
+        assert!(!regex.is_match("OLD_COURSE/MATHS"));
+        assert!(!regex.is_match("course/maths"));
+
+        assert!(compile_term_regex("COURSE/(MATHS|HISTORY)", true).unwrap().is_match("course/maths"));
+
+        // TEXT escapes are unescaped.
+        let regex = compile_term_regex("TENANT_\\\\d+,[a\\,b]", false).unwrap();
+
+        assert!(regex.is_match("TENANT_123,a"));
+        assert!(regex.is_match("TENANT_123,,"));
+        assert!(!regex.is_match("TENANT_ABC,a"));
+    }
+
+    #[test]
+    fn test_compile_term_regex_limits() {
+        assert!(compile_term_regex("UNCLOSED_(GROUP", false).unwrap_err().starts_with("invalid REGEX pattern: UNCLOSED_(GROUP"));
+
+        assert_eq!(
+            compile_term_regex(&"A".repeat(MAX_REGEX_PATTERN_LENGTH + 1), false).unwrap_err(),
+            String::from("REGEX pattern exceeds the maximum length of 256 bytes"),
+        );
+
+        assert!(compile_term_regex(&format!("{}A{}", "(".repeat(32), ")".repeat(32)), false).is_err());
+
+        assert!(compile_term_regex("\\\\w{100}\\\\w{100}\\\\w{100}", false).is_err());
+    }
+}
@@ -9,6 +9,12 @@ pub trait QueryableEntity: ICalendarComponent + Eq {
     fn build_result_ordering(&self, ordering_condition: &OrderingCondition) -> QueryResultOrdering;
 
     fn get_uid(&self) -> String;
+
+    /// The RECURRENCE-ID (as a UTC timestamp) of the entity, if it is a specific occurrence of
+    /// an event.
+    fn get_recurrence_id_timestamp(&self) -> Option<i64> {
+        None
+    }
 }
 
 #[derive(Debug)]
@@ -18,6 +24,7 @@ pub struct QueryResults<T: QueryableEntity> {
     pub distinct_uid_lookup: Option<HashSet<String>>,
     pub count: usize,
     pub offset: usize,
+    pub is_truncated: bool,
 }
 
 impl<T> QueryResults<T>
@@ -41,6 +48,7 @@ where
             distinct_uid_lookup,
             count: 1,
             results: BTreeSet::new(),
+            is_truncated: false,
         }
     }
 
@@ -50,6 +58,26 @@ where
         }
     }
 
+    /// Truncates the results to the max number of results permitted, flagging them as truncated
+    /// if any were removed.
+    pub fn truncate_to_max_results(&mut self, max_results: usize) {
+        if self.len() > max_results {
+            self.truncate(max_results);
+
+            self.is_truncated = true;
+        }
+    }
+
+    /// Removes the first `count` results, used where the offset can only be applied once all
+    /// results have been collected and sorted (e.g. relevance score ordering).
+    pub fn skip(&mut self, count: usize) {
+        for _ in 0..count {
+            if self.results.pop_first().is_none() {
+                break;
+            }
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.results.len()
     }
@@ -124,14 +152,14 @@ impl<T> Ord for QueryResult<T>
 where
     T: QueryableEntity,
 {
+    // Results sharing the same ordering (e.g. the same DTSTART) are deterministically ordered by
+    // their UID, then their RECURRENCE-ID, so that they are consistently paginated across
+    // queries.
     fn cmp(&self, other: &Self) -> Ordering {
-        let ordering = self.result_ordering.cmp(&other.result_ordering);
-
-        if ordering.is_eq() {
-            self.result.get_uid().cmp(&other.result.get_uid())
-        } else {
-            ordering
-        }
+        self.result_ordering
+            .cmp(&other.result_ordering)
+            .then_with(|| self.result.get_uid().cmp(&other.result.get_uid()))
+            .then_with(|| self.result.get_recurrence_id_timestamp().cmp(&other.result.get_recurrence_id_timestamp()))
     }
 }
 
@@ -145,6 +173,12 @@ mod test {
 
     use crate::{EventInstance, GeoDistance, GeoPoint, IndexedProperties, PassiveProperties};
 
+    use crate::queries::indexed_property_filters::{
+        WhereConditional, WhereConditionalProperty, WhereOperator,
+    };
+
+    use crate::queries::results_ordering::RelevanceScore;
+
     use pretty_assertions_sorted::assert_eq;
 
     use crate::testing::macros::build_property_from_ical;
@@ -178,6 +212,7 @@ mod test {
             duration: build_property_from_ical!(DurationProperty, "DURATION:PT10S"),
             indexed_properties: IndexedProperties {
                 class: None,
+                location: None,
                 geo: Some(build_property_from_ical!(
                     GeoProperty,
                     "GEO:51.899779;-2.0760367"
@@ -198,6 +233,7 @@ mod test {
             duration: build_property_from_ical!(DurationProperty, "DURATION:PT10S"),
             indexed_properties: IndexedProperties {
                 class: None,
+                location: None,
                 geo: Some(build_property_from_ical!(
                     GeoProperty,
                     "GEO:51.7504163;-1.2475878"
@@ -218,6 +254,7 @@ mod test {
             duration: build_property_from_ical!(DurationProperty, "DURATION:PT10S"),
             indexed_properties: IndexedProperties {
                 class: None,
+                location: None,
                 geo: Some(build_property_from_ical!(
                     GeoProperty,
                     "GEO:51.4517446;-1.004574"
@@ -412,6 +449,43 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_query_results_truncate_to_max_results() {
+        let mut query_results: QueryResults<EventInstance> =
+            QueryResults::new(OrderingCondition::DtStart, 0, false);
+
+        query_results.push(build_event_instance_one());
+        query_results.push(build_event_instance_two());
+        query_results.push(build_event_instance_three());
+
+        query_results.truncate_to_max_results(3);
+
+        assert_eq!(query_results.len(), 3);
+        assert_eq!(query_results.is_truncated, false);
+
+        query_results.truncate_to_max_results(2);
+
+        assert_eq!(
+            query_results
+                .results
+                .clone()
+                .into_iter()
+                .collect::<Vec<QueryResult<EventInstance>>>(),
+            vec![
+                QueryResult {
+                    result_ordering: QueryResultOrdering::DtStart(100),
+                    result: build_event_instance_one(),
+                },
+                QueryResult {
+                    result_ordering: QueryResultOrdering::DtStart(200),
+                    result: build_event_instance_two(),
+                },
+            ],
+        );
+
+        assert_eq!(query_results.is_truncated, true);
+    }
+
     #[test]
     fn test_query_results_dtstart_ordering() {
         let mut query_results: QueryResults<EventInstance> =
@@ -664,4 +738,60 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn test_query_results_score_ordering() {
+        // UID_ONE OR within 100KM of Oxford.
+        let where_conditional = WhereConditional::Operator(
+            Box::new(WhereConditional::Property(WhereConditionalProperty::UID(String::from("UID_ONE")))),
+            Box::new(WhereConditional::Property(WhereConditionalProperty::Geo(
+                GeoDistance::new_from_kilometers_float(100.0),
+                GeoPoint::new(51.7504163_f64, -1.2475878_f64), // Oxford
+            ))),
+            WhereOperator::Or,
+        );
+
+        let mut query_results: QueryResults<EventInstance> = QueryResults::new(
+            OrderingCondition::Score(None).bind_where_conditional(&Some(where_conditional)),
+            0,
+            false,
+        );
+
+        assert!(query_results.results.is_empty());
+
+        query_results.push(build_event_instance_four());
+        query_results.push(build_event_instance_two());
+        query_results.push(build_event_instance_three());
+        query_results.push(build_event_instance_one());
+
+        assert_eq!(
+            query_results
+                .results
+                .clone()
+                .into_iter()
+                .collect::<Vec<QueryResult<EventInstance>>>(),
+            vec![
+                QueryResult {
+                    result_ordering: QueryResultOrdering::Score(RelevanceScore::new_from_float(1.0), 100),
+                    result: build_event_instance_one(),
+                },
+                QueryResult {
+                    result_ordering: QueryResultOrdering::Score(RelevanceScore::new_from_float(1.0), 300),
+                    result: build_event_instance_three(),
+                },
+                QueryResult {
+                    result_ordering: QueryResultOrdering::Score(RelevanceScore::new_from_float(0.628), 400),
+                    result: build_event_instance_four(),
+                },
+                QueryResult {
+                    result_ordering: QueryResultOrdering::Score(RelevanceScore::new_from_float(0.407), 200),
+                    result: build_event_instance_two(),
+                },
+            ]
+        );
+
+        query_results.skip(1);
+
+        assert_eq!(query_results.len(), 3);
+    }
 }
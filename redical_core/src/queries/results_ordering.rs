@@ -5,6 +5,8 @@ use geo::HaversineDistance;
 
 use crate::{Event, EventInstance, GeoDistance, GeoPoint};
 
+use crate::queries::indexed_property_filters::WhereConditional;
+
 use redical_ical::{
     ICalendarComponent,
     RenderingContext,
@@ -25,6 +27,9 @@ pub enum OrderingCondition {
     DtStart,
     DtStartGeoDist(GeoPoint),
     GeoDistDtStart(GeoPoint),
+    // Holds the where conditional the relevance score is calculated against, this is bound at
+    // query execution time (see `OrderingCondition::bind_where_conditional`).
+    Score(Option<Box<WhereConditional>>),
 }
 
 impl From<XOrderByProperty> for OrderingCondition {
@@ -45,6 +50,10 @@ impl From<XOrderByProperty> for OrderingCondition {
                     GeoPoint::new(latitude.into(), longitude.into())
                 )
             },
+
+            XOrderByProperty::Score => {
+                OrderingCondition::Score(None)
+            },
         }
     }
 }
@@ -56,6 +65,19 @@ impl From<&XOrderByProperty> for OrderingCondition {
 }
 
 impl OrderingCondition {
+    /// Relevance scoring is calculated against the where conditional of the query, which is not
+    /// known until the whole query has been parsed, so this returns a copy of the
+    /// `OrderingCondition::Score` with it bound (all other variants are returned as is).
+    pub fn bind_where_conditional(&self, where_conditional: &Option<WhereConditional>) -> OrderingCondition {
+        match &self {
+            OrderingCondition::Score(_) => {
+                OrderingCondition::Score(where_conditional.clone().map(Box::new))
+            }
+
+            _ => self.clone(),
+        }
+    }
+
     pub fn build_result_ordering_for_event_instance(
         &self,
         event_instance: &EventInstance,
@@ -110,6 +132,24 @@ impl OrderingCondition {
 
                 QueryResultOrdering::GeoDistDtStart(geo_distance, dtstart_timestamp)
             }
+
+            OrderingCondition::Score(where_conditional) => {
+                let dtstart_timestamp = event_instance.dtstart.get_utc_timestamp();
+
+                let score =
+                    where_conditional
+                        .as_ref()
+                        .map(|where_conditional| {
+                            where_conditional.score(
+                                &event_instance.uid.uid.to_string(),
+                                &event_instance.indexed_properties,
+                                &event_instance.passive_properties,
+                            )
+                        })
+                        .unwrap_or_default();
+
+                QueryResultOrdering::Score(score, dtstart_timestamp)
+            }
         }
     }
 
@@ -167,15 +207,65 @@ impl OrderingCondition {
 
                 QueryResultOrdering::GeoDistDtStart(geo_distance, dtstart_timestamp)
             }
+
+            OrderingCondition::Score(where_conditional) => {
+                let dtstart_timestamp = event.schedule_properties.get_dtstart_timestamp().unwrap_or(0);
+
+                let score =
+                    where_conditional
+                        .as_ref()
+                        .map(|where_conditional| {
+                            where_conditional.score(
+                                &event.uid.uid.to_string(),
+                                &event.indexed_properties,
+                                &event.passive_properties,
+                            )
+                        })
+                        .unwrap_or_default();
+
+                QueryResultOrdering::Score(score, dtstart_timestamp)
+            }
         }
     }
 }
 
+/// Relevance score accumulated by a result for each satisfied where conditional property.
+///
+/// This is stored as an integer number of thousandths of a point so that it can be totally
+/// ordered (and hashed) unlike a raw float.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct RelevanceScore(u64);
+
+impl RelevanceScore {
+    pub fn new_from_float(score: f64) -> Self {
+        RelevanceScore((score.max(0_f64) * 1000_f64).round() as u64)
+    }
+
+    pub fn to_float(&self) -> f64 {
+        self.0 as f64 / 1000_f64
+    }
+}
+
+impl std::ops::Add for RelevanceScore {
+    type Output = RelevanceScore;
+
+    fn add(self, other: RelevanceScore) -> RelevanceScore {
+        RelevanceScore(self.0 + other.0)
+    }
+}
+
+impl std::fmt::Display for RelevanceScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_float())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum QueryResultOrdering {
     DtStart(i64),
     DtStartGeoDist(i64, Option<GeoDistance>),
     GeoDistDtStart(Option<GeoDistance>, i64),
+    Score(RelevanceScore, i64),
 }
 
 impl ICalendarComponent for QueryResultOrdering {
@@ -230,6 +320,16 @@ impl ICalendarComponent for QueryResultOrdering {
 
                 serialized_ical_set.insert(dtstart_property.to_content_line_with_context(context));
             }
+
+            QueryResultOrdering::Score(score, dtstart_timestamp) => {
+                let x_score_property = ContentLine::from((String::from("X-SCORE"), Vec::new(), score.to_string()));
+
+                serialized_ical_set.insert(x_score_property);
+
+                let dtstart_property = DTStartProperty::new_from_utc_timestamp(dtstart_timestamp);
+
+                serialized_ical_set.insert(dtstart_property.to_content_line_with_context(context));
+            }
         }
 
         serialized_ical_set
@@ -288,6 +388,20 @@ impl Ord for QueryResultOrdering {
                 }
             }
 
+            (
+                QueryResultOrdering::Score(self_score, self_dtstart_timestamp),
+                QueryResultOrdering::Score(other_score, other_dtstart_timestamp),
+            ) => {
+                // Highest score first.
+                let score_comparison = other_score.cmp(self_score);
+
+                if score_comparison.is_eq() {
+                    self_dtstart_timestamp.cmp(other_dtstart_timestamp)
+                } else {
+                    score_comparison
+                }
+            }
+
             _ => {
                 panic!("Unexpected comparison between disparate QueryResultOrdering variants, self: {self:#?} other: {other:#?}");
             }
@@ -1,8 +1,8 @@
-use crate::{FilterProperty, LowerBoundFilterCondition, UpperBoundFilterCondition};
+use crate::{DurationFilterCondition, FilterProperty, LowerBoundFilterCondition, UpperBoundFilterCondition};
 
 use redical_ical::properties::ICalendarDateTimeProperty;
-use redical_ical::properties::query::{XFromProperty, XUntilProperty};
-use redical_ical::values::where_range_operator::{WhereFromRangeOperator, WhereUntilRangeOperator};
+use redical_ical::properties::query::{XDurationProperty, XFromProperty, XUntilProperty};
+use redical_ical::values::where_range_operator::{WhereComparisonOperator, WhereFromRangeOperator, WhereUntilRangeOperator};
 use redical_ical::values::where_range_property::WhereRangeProperty;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -179,3 +179,44 @@ impl UpperBoundRangeCondition {
         }
     }
 }
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum DurationRangeCondition {
+    GreaterThan(i64),
+    GreaterEqualThan(i64),
+    LessThan(i64),
+    LessEqualThan(i64),
+    Equal(i64),
+}
+
+impl From<XDurationProperty> for DurationRangeCondition {
+    fn from(x_duration_property: XDurationProperty) -> Self {
+        let duration = x_duration_property.get_duration_in_seconds();
+
+        match x_duration_property.params.op {
+            WhereComparisonOperator::GreaterThan => DurationRangeCondition::GreaterThan(duration),
+            WhereComparisonOperator::GreaterEqualThan => DurationRangeCondition::GreaterEqualThan(duration),
+            WhereComparisonOperator::LessThan => DurationRangeCondition::LessThan(duration),
+            WhereComparisonOperator::LessEqualThan => DurationRangeCondition::LessEqualThan(duration),
+            WhereComparisonOperator::Equal => DurationRangeCondition::Equal(duration),
+        }
+    }
+}
+
+impl From<&XDurationProperty> for DurationRangeCondition {
+    fn from(x_duration_property: &XDurationProperty) -> Self {
+        Self::from(x_duration_property.to_owned())
+    }
+}
+
+impl From<DurationRangeCondition> for DurationFilterCondition {
+    fn from(duration_range_condition: DurationRangeCondition) -> Self {
+        match duration_range_condition {
+            DurationRangeCondition::GreaterThan(duration) => DurationFilterCondition::GreaterThan(duration),
+            DurationRangeCondition::GreaterEqualThan(duration) => DurationFilterCondition::GreaterEqualThan(duration),
+            DurationRangeCondition::LessThan(duration) => DurationFilterCondition::LessThan(duration),
+            DurationRangeCondition::LessEqualThan(duration) => DurationFilterCondition::LessEqualThan(duration),
+            DurationRangeCondition::Equal(duration) => DurationFilterCondition::Equal(duration),
+        }
+    }
+}
@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use redical_ical::{ICalendarComponent, ICalendarEntity};
+use redical_ical::properties::{CalendarProperty, EventProperty};
+
+use crate::calendar::Calendar;
+use crate::event::{Event, IndexedProperties, PassiveProperties};
+use crate::event_instance::EventInstance;
+use crate::event_occurrence_override::EventOccurrenceOverride;
+
+// The Calendar, Event, EventOccurrenceOverride, and EventInstance are (de)serialized as their
+// rendered iCalendar content lines (as with the RDB persistence), so that the encoding is
+// independent of the internal data model and is re-validated when deserialized.
+//
+// The Calendar indexes are derived from it's Events, so they are rebuilt when deserialized
+// instead of being included.
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct SerializedCalendar {
+    uid: String,
+    properties: Vec<String>,
+    events: Vec<SerializedEvent>,
+    event_expirations: BTreeMap<String, i64>,
+    indexes_active: bool,
+}
+
+impl TryFrom<&Calendar> for SerializedCalendar {
+    type Error = String;
+
+    fn try_from(calendar: &Calendar) -> Result<Self, Self::Error> {
+        let events =
+            calendar.events
+                .values()
+                .map(|event| SerializedEvent::try_from(event.as_ref()))
+                .collect::<Result<Vec<SerializedEvent>, String>>()?;
+
+        Ok(
+            SerializedCalendar {
+                uid: calendar.uid.uid.to_string(),
+                properties: calendar.to_rendered_content_lines(),
+                events,
+                event_expirations: calendar.event_expirations.clone(),
+                indexes_active: calendar.indexes_active,
+            }
+        )
+    }
+}
+
+impl TryFrom<SerializedCalendar> for Calendar {
+    type Error = String;
+
+    fn try_from(serialized_calendar: SerializedCalendar) -> Result<Self, Self::Error> {
+        let mut calendar = Calendar::new(serialized_calendar.uid.clone());
+
+        for property in &serialized_calendar.properties {
+            calendar.insert(CalendarProperty::from_str(property.as_str())?)?;
+        }
+
+        for serialized_event in serialized_calendar.events {
+            calendar.insert_event(Event::try_from(serialized_event)?);
+        }
+
+        for (event_uid, expires_at) in &serialized_calendar.event_expirations {
+            if !calendar.set_event_expiration(event_uid, *expires_at) {
+                return Err(format!("Event expiration for: {event_uid} does not match an Event on Calendar: {}", serialized_calendar.uid));
+            }
+        }
+
+        if serialized_calendar.indexes_active {
+            calendar.rebuild_indexes()?;
+        } else {
+            calendar.disable_indexes();
+        }
+
+        Ok(calendar)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct SerializedEvent {
+    uid: String,
+    properties: Vec<String>,
+    overrides: Vec<SerializedEventOccurrenceOverride>,
+}
+
+impl TryFrom<&Event> for SerializedEvent {
+    type Error = String;
+
+    fn try_from(event: &Event) -> Result<Self, Self::Error> {
+        let overrides =
+            event.overrides
+                .values()
+                .map(SerializedEventOccurrenceOverride::try_from)
+                .collect::<Result<Vec<SerializedEventOccurrenceOverride>, String>>()?;
+
+        Ok(
+            SerializedEvent {
+                uid: event.uid.uid.to_string(),
+                properties: event.to_rendered_content_lines(),
+                overrides,
+            }
+        )
+    }
+}
+
+impl TryFrom<SerializedEvent> for Event {
+    type Error = String;
+
+    fn try_from(serialized_event: SerializedEvent) -> Result<Self, Self::Error> {
+        let mut event = Event::new(serialized_event.uid.clone());
+
+        for property in &serialized_event.properties {
+            event.insert(EventProperty::from_str(property.as_str())?)?;
+        }
+
+        event.validate()?;
+
+        for serialized_event_occurrence_override in serialized_event.overrides {
+            event.override_occurrence(&EventOccurrenceOverride::try_from(serialized_event_occurrence_override)?, false)?;
+        }
+
+        event.rebuild_indexes()?;
+
+        Ok(event)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct SerializedEventOccurrenceOverride {
+    dtstart: String,
+    properties: Vec<String>,
+}
+
+impl TryFrom<&EventOccurrenceOverride> for SerializedEventOccurrenceOverride {
+    type Error = String;
+
+    fn try_from(event_occurrence_override: &EventOccurrenceOverride) -> Result<Self, Self::Error> {
+        let Some(dtstart_property) = event_occurrence_override.dtstart.as_ref() else {
+            return Err(String::from("EventOccurrenceOverride is invalid, requires defined DTSTART property"));
+        };
+
+        Ok(
+            SerializedEventOccurrenceOverride {
+                dtstart: dtstart_property.date_time.render_formatted_date_time(None),
+                properties: event_occurrence_override.to_rendered_content_lines(),
+            }
+        )
+    }
+}
+
+impl TryFrom<SerializedEventOccurrenceOverride> for EventOccurrenceOverride {
+    type Error = String;
+
+    fn try_from(serialized_event_occurrence_override: SerializedEventOccurrenceOverride) -> Result<Self, Self::Error> {
+        let mut event_occurrence_override = EventOccurrenceOverride::default();
+
+        for property in &serialized_event_occurrence_override.properties {
+            event_occurrence_override.insert(EventProperty::from_str(property.as_str())?)?;
+        }
+
+        event_occurrence_override.validate()?;
+
+        let parsed_dtstart =
+            event_occurrence_override.dtstart
+                .as_ref()
+                .map(|dtstart_property| dtstart_property.date_time.render_formatted_date_time(None));
+
+        if parsed_dtstart.as_ref() != Some(&serialized_event_occurrence_override.dtstart) {
+            return Err(format!(
+                "EventOccurrenceOverride DTSTART property: {parsed_dtstart:?} does not match serialized DTSTART: {}",
+                serialized_event_occurrence_override.dtstart,
+            ));
+        }
+
+        Ok(event_occurrence_override)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct SerializedEventInstance {
+    uid: String,
+    properties: Vec<String>,
+}
+
+impl From<&EventInstance> for SerializedEventInstance {
+    fn from(event_instance: &EventInstance) -> Self {
+        // The RECURRENCE-ID is derived from the DTSTART, so it is omitted.
+        let properties =
+            event_instance
+                .to_rendered_content_lines()
+                .into_iter()
+                .filter(|property| !property.starts_with("RECURRENCE-ID"))
+                .collect();
+
+        SerializedEventInstance {
+            uid: event_instance.uid.uid.to_string(),
+            properties,
+        }
+    }
+}
+
+impl TryFrom<SerializedEventInstance> for EventInstance {
+    type Error = String;
+
+    fn try_from(serialized_event_instance: SerializedEventInstance) -> Result<Self, Self::Error> {
+        let mut uid = None;
+        let mut dtstart = None;
+        let mut dtend = None;
+        let mut duration = None;
+
+        let mut indexed_properties = IndexedProperties::new();
+        let mut passive_properties = PassiveProperties::new();
+
+        for property in &serialized_event_instance.properties {
+            match EventProperty::from_str(property.as_str())? {
+                EventProperty::UID(property) => uid = Some(property),
+                EventProperty::DTStart(property) => dtstart = Some(property),
+                EventProperty::DTEnd(property) => dtend = Some(property),
+                EventProperty::Duration(property) => duration = Some(property),
+
+                property @ (
+                    EventProperty::Class(_)
+                    | EventProperty::Geo(_)
+                    | EventProperty::Categories(_)
+                    | EventProperty::LocationType(_)
+                    | EventProperty::Location(_)
+                    | EventProperty::RelatedTo(_)
+                ) => {
+                    indexed_properties.insert(property)?;
+                },
+
+                property @ EventProperty::Passive(_) => {
+                    passive_properties.insert(property)?;
+                },
+
+                property => {
+                    return Err(format!("Unexpected EventInstance property: {}", property.render_ical()));
+                },
+            }
+        }
+
+        let missing_property_error = |property_name: &str| {
+            format!("EventInstance: {} is invalid, requires defined {property_name} property", serialized_event_instance.uid)
+        };
+
+        Ok(
+            EventInstance {
+                uid: uid.ok_or_else(|| missing_property_error("UID"))?,
+                dtstart: dtstart.ok_or_else(|| missing_property_error("DTSTART"))?,
+                dtend: dtend.ok_or_else(|| missing_property_error("DTEND"))?,
+                duration: duration.ok_or_else(|| missing_property_error("DURATION"))?,
+                indexed_properties,
+                passive_properties,
+            }
+        )
+    }
+}
+
+macro_rules! impl_serde_via {
+    ($type:ty, $serialized_type:ty) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                <$serialized_type>::try_from(self)
+                    .map_err(serde::ser::Error::custom)?
+                    .serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <$type>::try_from(<$serialized_type>::deserialize(deserializer)?)
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+impl_serde_via!(Calendar, SerializedCalendar);
+impl_serde_via!(Event, SerializedEvent);
+impl_serde_via!(EventOccurrenceOverride, SerializedEventOccurrenceOverride);
+impl_serde_via!(EventInstance, SerializedEventInstance);
+
+// (De)serialize a map (HashMap or BTreeMap) as a sequence of key value pairs, for index terms
+// keyed on non-string values (e.g. GeoPoint and KeyValuePair) which cannot be map keys in formats
+// such as JSON.
+pub(crate) mod map_as_pairs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<'a, M, K, V, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+        K: Serialize + 'a,
+        V: Serialize + 'a,
+        S: Serializer,
+    {
+        serializer.collect_seq(map)
+    }
+
+    pub fn deserialize<'de, M, K, V, D>(deserializer: D) -> Result<M, D::Error>
+    where
+        M: FromIterator<(K, V)>,
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use crate::{EventInstanceIterator, GeoSpatialCalendarIndex, InvertedCalendarIndex, KeyValuePair, TimeBucketCalendarIndex};
+
+    fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> T {
+        bincode::deserialize(&bincode::serialize(value).unwrap()).unwrap()
+    }
+
+    fn build_calendar() -> Calendar {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        let mut event = Event::parse_ical(
+            "EVENT_UID",
+            "DTSTART:20210105T183000Z LAST-MODIFIED:20210501T090000Z RRULE:FREQ=WEEKLY;COUNT=3;INTERVAL=1 CATEGORIES:CATEGORY_ONE RELATED-TO;RELTYPE=PARENT:PARENT_UID GEO:48.85299;2.36885 DESCRIPTION:Event description",
+        ).unwrap();
+
+        event.validate().unwrap();
+
+        event.override_occurrence(
+            &EventOccurrenceOverride::parse_ical("20210112T183000Z", "LAST-MODIFIED:20210501T090000Z CATEGORIES:CATEGORY_TWO").unwrap(),
+            false,
+        ).unwrap();
+
+        calendar.insert_event_and_reindex(event).unwrap();
+
+        calendar.set_event_expiration(&String::from("EVENT_UID"), 1610000000);
+
+        calendar
+    }
+
+    #[test]
+    fn test_calendar_serde_round_trip() {
+        let calendar = build_calendar();
+
+        assert_eq!(round_trip(&calendar), calendar);
+
+        let mut calendar_without_indexes = build_calendar();
+
+        calendar_without_indexes.disable_indexes();
+
+        assert_eq!(round_trip(&calendar_without_indexes), calendar_without_indexes);
+    }
+
+    #[test]
+    fn test_event_and_event_instance_serde_round_trip() {
+        let calendar = build_calendar();
+
+        let event = calendar.get_event(&String::from("EVENT_UID")).unwrap();
+
+        assert_eq!(&round_trip(event), event);
+
+        for event_occurrence_override in event.overrides.values() {
+            assert_eq!(&round_trip(event_occurrence_override), event_occurrence_override);
+        }
+
+        let event_instances: Vec<EventInstance> = EventInstanceIterator::new(event, None, None, None, None).unwrap().collect();
+
+        assert_eq!(event_instances.len(), 3);
+
+        for event_instance in &event_instances {
+            assert_eq!(&round_trip(event_instance), event_instance);
+        }
+    }
+
+    #[test]
+    fn test_index_serde_round_trip() {
+        let calendar = build_calendar();
+
+        assert_eq!(round_trip(&calendar.indexed_categories), calendar.indexed_categories);
+        assert_eq!(round_trip(&calendar.indexed_related_to), calendar.indexed_related_to);
+        assert_eq!(round_trip(&calendar.indexed_geo), calendar.indexed_geo);
+        assert_eq!(round_trip(&calendar.indexed_time_buckets), calendar.indexed_time_buckets);
+
+        assert_eq!(
+            round_trip::<InvertedCalendarIndex<KeyValuePair>>(&calendar.indexed_related_to)
+                .terms
+                .keys()
+                .cloned()
+                .collect::<Vec<KeyValuePair>>(),
+            vec![KeyValuePair::new(String::from("PARENT"), String::from("PARENT_UID"))],
+        );
+
+        assert!(!round_trip::<GeoSpatialCalendarIndex>(&calendar.indexed_geo).is_empty());
+        assert!(!round_trip::<TimeBucketCalendarIndex>(&calendar.indexed_time_buckets).events.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_serialized_event() {
+        let serialized_event = SerializedEvent {
+            uid: String::from("EVENT_UID"),
+            properties: vec![String::from("CATEGORIES:CATEGORY_ONE")],
+            overrides: vec![],
+        };
+
+        assert!(
+            bincode::deserialize::<Event>(&bincode::serialize(&serialized_event).unwrap()).is_err()
+        );
+    }
+}
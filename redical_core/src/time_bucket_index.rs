@@ -0,0 +1,466 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use chrono::{Datelike, TimeZone, Utc};
+
+use crate::{Event, EventOccurrenceIterator, LowerBoundFilterCondition, UpperBoundFilterCondition, FilterProperty};
+
+// Events with more occurrences than this are treated as open ended from their first occurrence
+// instead of being bucketed occurrence by occurrence. This keeps the cost of (re)indexing an
+// event with an excessive (but bounded) recurrence in check, at the expense of pre-filtering
+// precision for windows after it has concluded.
+const MAX_BUCKETED_OCCURRENCES: usize = 10_000;
+
+/// The month buckets occupied by the occurrences of a single event, along with the latest DTEND
+/// of them (absent if the event is open ended).
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+pub struct EventTimeBuckets {
+    pub buckets: BTreeSet<i64>,
+    pub open_ended_from: Option<i64>,
+    pub ends_at: Option<i64>,
+}
+
+impl EventTimeBuckets {
+    pub fn new_from_event(event: &Event) -> Result<Self, String> {
+        let mut event_time_buckets = EventTimeBuckets::default();
+
+        let is_open_ended = event.is_open_ended();
+
+        let limit = if is_open_ended { Some(1) } else { Some(MAX_BUCKETED_OCCURRENCES + 1) };
+
+        let event_occurrence_iterator = EventOccurrenceIterator::new(
+            &event.schedule_properties,
+            &event.overrides,
+            limit,
+            None,
+            None,
+            None,
+        )?;
+
+        for (count, (dtstart_timestamp, dtend_timestamp, _)) in event_occurrence_iterator.enumerate() {
+            let dtstart_bucket = TimeBucketCalendarIndex::month_bucket(dtstart_timestamp);
+
+            if is_open_ended || count >= MAX_BUCKETED_OCCURRENCES {
+                let open_ended_from = event_time_buckets.buckets.first().cloned().unwrap_or(dtstart_bucket);
+
+                event_time_buckets.buckets.clear();
+                event_time_buckets.open_ended_from = Some(open_ended_from);
+                event_time_buckets.ends_at = None;
+
+                break;
+            }
+
+            let dtend_bucket = TimeBucketCalendarIndex::month_bucket(dtend_timestamp.max(dtstart_timestamp));
+
+            event_time_buckets.buckets.extend(dtstart_bucket..=dtend_bucket);
+
+            // Overridden durations can leave an earlier occurrence ending after the final one.
+            event_time_buckets.ends_at = Some(event_time_buckets.ends_at.map_or(dtend_timestamp, |ends_at| ends_at.max(dtend_timestamp)));
+        }
+
+        Ok(event_time_buckets)
+    }
+}
+
+/// Coarse (month long) time bucket index of all the events on a calendar, keyed on the months
+/// occupied by each event occurrence (from DTSTART to DTEND).
+///
+/// This is used to pre-filter the events queried within a time window (X-FROM / X-UNTIL) so that
+/// those with no occurrences inside the window are skipped entirely instead of being expanded
+/// and filtered afterwards. Events recurring indefinitely are held separately as open ended from
+/// the month of their first occurrence.
+///
+/// The events which are not open ended are also keyed on the latest DTEND of their occurrences,
+/// so that those concluding before a window (e.g. events still running after X, or the events to
+/// prune) are found precisely without expanding them.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+pub struct TimeBucketCalendarIndex {
+    pub buckets: BTreeMap<i64, HashSet<String>>,
+    pub open_ended: BTreeMap<i64, HashSet<String>>,
+    pub ends: BTreeMap<i64, HashSet<String>>,
+    pub events: HashMap<String, EventTimeBuckets>,
+}
+
+impl TimeBucketCalendarIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Months since the Unix epoch (UTC) for the provided timestamp.
+    pub fn month_bucket(timestamp: i64) -> i64 {
+        let datetime = Utc.timestamp_opt(timestamp, 0).single().unwrap_or_default();
+
+        (datetime.year() as i64 - 1970) * 12 + datetime.month0() as i64
+    }
+
+    pub fn insert(&mut self, event: &Event) -> Result<bool, String> {
+        let event_uid = event.uid.uid.to_string();
+        let event_time_buckets = EventTimeBuckets::new_from_event(event)?;
+
+        self.remove(&event_uid);
+
+        for bucket in &event_time_buckets.buckets {
+            self.buckets
+                .entry(*bucket)
+                .or_default()
+                .insert(event_uid.to_owned());
+        }
+
+        if let Some(open_ended_from) = event_time_buckets.open_ended_from {
+            self.open_ended
+                .entry(open_ended_from)
+                .or_default()
+                .insert(event_uid.to_owned());
+        }
+
+        if let Some(ends_at) = event_time_buckets.ends_at {
+            self.ends
+                .entry(ends_at)
+                .or_default()
+                .insert(event_uid.to_owned());
+        }
+
+        self.events.insert(event_uid, event_time_buckets);
+
+        Ok(true)
+    }
+
+    pub fn remove(&mut self, event_uid: &String) -> bool {
+        let Some(event_time_buckets) = self.events.remove(event_uid) else {
+            return false;
+        };
+
+        for bucket in &event_time_buckets.buckets {
+            Self::remove_from_bucket(&mut self.buckets, bucket, event_uid);
+        }
+
+        if let Some(open_ended_from) = &event_time_buckets.open_ended_from {
+            Self::remove_from_bucket(&mut self.open_ended, open_ended_from, event_uid);
+        }
+
+        if let Some(ends_at) = &event_time_buckets.ends_at {
+            Self::remove_from_bucket(&mut self.ends, ends_at, event_uid);
+        }
+
+        true
+    }
+
+    fn remove_from_bucket(buckets: &mut BTreeMap<i64, HashSet<String>>, bucket: &i64, event_uid: &String) {
+        if let Some(event_uids) = buckets.get_mut(bucket) {
+            event_uids.remove(event_uid);
+
+            if event_uids.is_empty() {
+                buckets.remove(bucket);
+            }
+        }
+    }
+
+    /// Returns the UIDs of all the events with occurrences potentially overlapping the time
+    /// window described by the provided filter conditions. Returns `None` if there is no window
+    /// to pre-filter on.
+    pub fn get_event_uids_within(
+        &self,
+        lower_bound_filter_condition: &Option<LowerBoundFilterCondition>,
+        upper_bound_filter_condition: &Option<UpperBoundFilterCondition>,
+    ) -> Option<HashSet<String>> {
+        let from_bucket =
+            lower_bound_filter_condition
+                .as_ref()
+                .map(|lower_bound_filter_condition| {
+                    match lower_bound_filter_condition {
+                        LowerBoundFilterCondition::GreaterThan(filter_property)
+                        | LowerBoundFilterCondition::GreaterEqualThan(filter_property) => {
+                            Self::filter_property_month_bucket(filter_property)
+                        }
+                    }
+                });
+
+        let until_bucket =
+            upper_bound_filter_condition
+                .as_ref()
+                .map(|upper_bound_filter_condition| {
+                    match upper_bound_filter_condition {
+                        UpperBoundFilterCondition::LessThan(filter_property)
+                        | UpperBoundFilterCondition::LessEqualThan(filter_property) => {
+                            Self::filter_property_month_bucket(filter_property)
+                        }
+                    }
+                });
+
+        if from_bucket.is_none() && until_bucket.is_none() {
+            return None;
+        }
+
+        let from_bucket = from_bucket.unwrap_or(i64::MIN);
+        let until_bucket = until_bucket.unwrap_or(i64::MAX);
+
+        let mut event_uids = HashSet::new();
+
+        if from_bucket > until_bucket {
+            return Some(event_uids);
+        }
+
+        for (_, bucket_event_uids) in self.buckets.range(from_bucket..=until_bucket) {
+            event_uids.extend(bucket_event_uids.iter().cloned());
+        }
+
+        for (_, open_ended_event_uids) in self.open_ended.range(..=until_bucket) {
+            event_uids.extend(open_ended_event_uids.iter().cloned());
+        }
+
+        // The month buckets are coarse, so the events concluding earlier in the month of a DTEND
+        // lower bound (i.e. no longer running after it) are excluded by when they end.
+        if let Some(lower_bound_filter_condition) = lower_bound_filter_condition {
+            event_uids.retain(|event_uid| !self.is_ended_before(event_uid, lower_bound_filter_condition));
+        }
+
+        Some(event_uids)
+    }
+
+    // Whether every occurrence of the event ends before the DTEND lower bound.
+    //
+    // DTSTART lower bounds are not considered, as an event DTEND is not guaranteed to be after
+    // it's DTSTART.
+    fn is_ended_before(&self, event_uid: &String, lower_bound_filter_condition: &LowerBoundFilterCondition) -> bool {
+        let Some(ends_at) = self.events.get(event_uid).and_then(|event_time_buckets| event_time_buckets.ends_at) else {
+            return false;
+        };
+
+        match lower_bound_filter_condition {
+            LowerBoundFilterCondition::GreaterThan(FilterProperty::DtEnd(timestamp)) => ends_at <= *timestamp,
+            LowerBoundFilterCondition::GreaterEqualThan(FilterProperty::DtEnd(timestamp)) => ends_at < *timestamp,
+
+            _ => false,
+        }
+    }
+
+    /// Returns the UIDs of the events whose latest occurrence DTEND is between the from and until
+    /// timestamps (inclusive).
+    pub fn get_event_uids_ending_between(&self, from: i64, until: i64) -> HashSet<String> {
+        if from > until {
+            return HashSet::new();
+        }
+
+        self.ends
+            .range(from..=until)
+            .flat_map(|(_, event_uids)| event_uids.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the UIDs of the events with occurrences but without a known latest DTEND, either
+    /// recurring indefinitely or with too many occurrences to bucket.
+    pub fn get_event_uids_without_ends(&self) -> HashSet<String> {
+        self.open_ended
+            .values()
+            .flat_map(|event_uids| event_uids.iter().cloned())
+            .collect()
+    }
+
+    fn filter_property_month_bucket(filter_property: &FilterProperty) -> i64 {
+        match filter_property {
+            FilterProperty::DtStart(timestamp) | FilterProperty::DtEnd(timestamp) => {
+                Self::month_bucket(*timestamp)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    fn build_event(uid: &str, ical: &str) -> Event {
+        let mut event = Event::parse_ical(uid, ical).unwrap();
+
+        event.validate().unwrap();
+
+        event
+    }
+
+    #[test]
+    fn test_month_bucket() {
+        assert_eq!(TimeBucketCalendarIndex::month_bucket(0), 0);
+        assert_eq!(TimeBucketCalendarIndex::month_bucket(2678399), 0); // 1970-01-31T23:59:59Z
+        assert_eq!(TimeBucketCalendarIndex::month_bucket(2678400), 1); // 1970-02-01T00:00:00Z
+        assert_eq!(TimeBucketCalendarIndex::month_bucket(-1), -1);     // 1969-12-31T23:59:59Z
+        assert_eq!(TimeBucketCalendarIndex::month_bucket(1609459200), 612); // 2021-01-01T00:00:00Z
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut index = TimeBucketCalendarIndex::new();
+
+        // Three monthly occurrences (Jan, Feb and Mar 2021).
+        let bounded_event = build_event(
+            "BOUNDED",
+            "DTSTART:20210115T090000Z DTEND:20210115T100000Z RRULE:FREQ=MONTHLY;COUNT=3;INTERVAL=1",
+        );
+
+        // Single occurrence spanning the end of April into May 2021.
+        let spanning_event = build_event(
+            "SPANNING",
+            "DTSTART:20210430T220000Z DTEND:20210501T020000Z",
+        );
+
+        // Weekly forever from June 2021.
+        let open_ended_event = build_event(
+            "OPEN_ENDED",
+            "DTSTART:20210601T090000Z DTEND:20210601T100000Z RRULE:FREQ=WEEKLY;INTERVAL=1",
+        );
+
+        index.insert(&bounded_event).unwrap();
+        index.insert(&spanning_event).unwrap();
+        index.insert(&open_ended_event).unwrap();
+
+        assert_eq!(
+            index.buckets,
+            BTreeMap::from([
+                (612, HashSet::from([String::from("BOUNDED")])),
+                (613, HashSet::from([String::from("BOUNDED")])),
+                (614, HashSet::from([String::from("BOUNDED")])),
+                (615, HashSet::from([String::from("SPANNING")])),
+                (616, HashSet::from([String::from("SPANNING")])),
+            ]),
+        );
+
+        assert_eq!(
+            index.open_ended,
+            BTreeMap::from([
+                (617, HashSet::from([String::from("OPEN_ENDED")])),
+            ]),
+        );
+
+        assert_eq!(
+            index.ends,
+            BTreeMap::from([
+                (1615802400, HashSet::from([String::from("BOUNDED")])),  // 2021-03-15T10:00:00Z
+                (1619834400, HashSet::from([String::from("SPANNING")])), // 2021-05-01T02:00:00Z
+            ]),
+        );
+
+        assert!(index.remove(&String::from("BOUNDED")));
+        assert!(!index.remove(&String::from("BOUNDED")));
+
+        assert_eq!(
+            index.buckets,
+            BTreeMap::from([
+                (615, HashSet::from([String::from("SPANNING")])),
+                (616, HashSet::from([String::from("SPANNING")])),
+            ]),
+        );
+
+        assert_eq!(
+            index.ends,
+            BTreeMap::from([
+                (1619834400, HashSet::from([String::from("SPANNING")])), // 2021-05-01T02:00:00Z
+            ]),
+        );
+
+        // Re-inserting replaces the existing buckets.
+        let spanning_event = build_event(
+            "SPANNING",
+            "DTSTART:20210801T090000Z DTEND:20210801T100000Z",
+        );
+
+        index.insert(&spanning_event).unwrap();
+
+        assert_eq!(
+            index.buckets,
+            BTreeMap::from([
+                (619, HashSet::from([String::from("SPANNING")])),
+            ]),
+        );
+
+        assert_eq!(
+            index.ends,
+            BTreeMap::from([
+                (1627812000, HashSet::from([String::from("SPANNING")])), // 2021-08-01T10:00:00Z
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_get_event_uids_ending_between() {
+        let mut index = TimeBucketCalendarIndex::new();
+
+        index.insert(&build_event("JANUARY", "DTSTART:20210115T090000Z DTEND:20210115T100000Z")).unwrap();
+        index.insert(&build_event("MARCH", "DTSTART:20210115T090000Z DTEND:20210115T100000Z RRULE:FREQ=MONTHLY;COUNT=3;INTERVAL=1")).unwrap();
+        index.insert(&build_event("OPEN_ENDED", "DTSTART:20210601T090000Z DTEND:20210601T100000Z RRULE:FREQ=DAILY;INTERVAL=1")).unwrap();
+
+        assert_eq!(
+            index.get_event_uids_ending_between(0, 1610704800), // 2021-01-15T10:00:00Z
+            HashSet::from([String::from("JANUARY")]),
+        );
+
+        assert_eq!(
+            index.get_event_uids_ending_between(0, 1615802400), // 2021-03-15T10:00:00Z
+            HashSet::from([String::from("JANUARY"), String::from("MARCH")]),
+        );
+
+        assert_eq!(
+            index.get_event_uids_ending_between(1610704801, 1893456000), // 2021-01-15T10:00:01Z - 2030-01-01T00:00:00Z
+            HashSet::from([String::from("MARCH")]),
+        );
+
+        assert_eq!(index.get_event_uids_ending_between(1615802400, 0), HashSet::new());
+
+        assert_eq!(index.get_event_uids_without_ends(), HashSet::from([String::from("OPEN_ENDED")]));
+    }
+
+    #[test]
+    fn test_get_event_uids_within() {
+        let mut index = TimeBucketCalendarIndex::new();
+
+        index.insert(&build_event("JANUARY", "DTSTART:20210115T090000Z DTEND:20210115T100000Z")).unwrap();
+        index.insert(&build_event("MARCH", "DTSTART:20210315T090000Z DTEND:20210315T100000Z")).unwrap();
+        index.insert(&build_event("OPEN_ENDED", "DTSTART:20210601T090000Z DTEND:20210601T100000Z RRULE:FREQ=DAILY;INTERVAL=1")).unwrap();
+
+        let from_february = Some(LowerBoundFilterCondition::GreaterEqualThan(FilterProperty::DtStart(1612137600))); // 2021-02-01T00:00:00Z
+        let until_april = Some(UpperBoundFilterCondition::LessThan(FilterProperty::DtEnd(1617235200))); // 2021-04-01T00:00:00Z
+        let from_july = Some(LowerBoundFilterCondition::GreaterThan(FilterProperty::DtStart(1625097600))); // 2021-07-01T00:00:00Z
+
+        assert_eq!(index.get_event_uids_within(&None, &None), None);
+
+        assert_eq!(
+            index.get_event_uids_within(&from_february, &until_april),
+            Some(HashSet::from([String::from("MARCH")])),
+        );
+
+        assert_eq!(
+            index.get_event_uids_within(&None, &until_april),
+            Some(HashSet::from([String::from("JANUARY"), String::from("MARCH")])),
+        );
+
+        assert_eq!(
+            index.get_event_uids_within(&from_february, &None),
+            Some(HashSet::from([String::from("MARCH"), String::from("OPEN_ENDED")])),
+        );
+
+        assert_eq!(
+            index.get_event_uids_within(&from_july, &None),
+            Some(HashSet::from([String::from("OPEN_ENDED")])),
+        );
+
+        assert_eq!(
+            index.get_event_uids_within(&from_july, &until_april),
+            Some(HashSet::new()),
+        );
+
+        // Events in the month of a DTEND lower bound, but concluding before it, are excluded.
+        let running_after_march_event_end = Some(LowerBoundFilterCondition::GreaterThan(FilterProperty::DtEnd(1615802400))); // 2021-03-15T10:00:00Z
+        let running_from_march_event_end = Some(LowerBoundFilterCondition::GreaterEqualThan(FilterProperty::DtEnd(1615802400))); // 2021-03-15T10:00:00Z
+
+        assert_eq!(
+            index.get_event_uids_within(&running_after_march_event_end, &None),
+            Some(HashSet::from([String::from("OPEN_ENDED")])),
+        );
+
+        assert_eq!(
+            index.get_event_uids_within(&running_from_march_event_end, &None),
+            Some(HashSet::from([String::from("MARCH"), String::from("OPEN_ENDED")])),
+        );
+    }
+}
@@ -2,14 +2,17 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::Debug;
 use std::hash::Hash;
 
+use serde::{Deserialize, Serialize};
+
 use std::cmp::Ordering;
 
 use redical_ical::{
+    ICalendarComponent,
     ICalendarEntity,
     content_line::ContentLine,
 };
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct KeyValuePair {
     pub key: String,
     pub value: String,
@@ -111,6 +114,18 @@ pub fn btree_hashset_to_hashset(
     })
 }
 
+/// The names of the properties added, removed, or changed between the existing (if present) and
+/// updated versions of an iCalendar component (e.g. an Event).
+pub fn get_changed_property_names<I: ICalendarComponent>(existing: Option<&I>, updated: &I) -> BTreeSet<String> {
+    let existing_content_lines = existing.map(|existing| existing.to_content_line_set()).unwrap_or_default();
+    let updated_content_lines = updated.to_content_line_set();
+
+    existing_content_lines
+        .symmetric_difference(&updated_content_lines)
+        .map(|content_line| content_line.0.to_owned())
+        .collect()
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum UpdatedAttribute<T>
 where
@@ -623,4 +638,45 @@ mod test {
 
         assert_eq!(merged_iterator.next(), None);
     }
+
+    #[test]
+    fn test_get_changed_property_names() {
+        let existing_event =
+            crate::Event::parse_ical(
+                "EVENT_UID",
+                "DTSTART:20210105T170000Z LAST-MODIFIED:20210501T090000Z CATEGORIES:CATEGORY_ONE SUMMARY:Event summary",
+            ).unwrap();
+
+        let updated_event =
+            crate::Event::parse_ical(
+                "EVENT_UID",
+                "DTSTART:20210105T170000Z LAST-MODIFIED:20210502T090000Z CATEGORIES:CATEGORY_TWO DESCRIPTION:Event description",
+            ).unwrap();
+
+        assert_eq!(
+            get_changed_property_names(Some(&existing_event), &updated_event),
+            BTreeSet::from([
+                String::from("CATEGORIES"),
+                String::from("DESCRIPTION"),
+                String::from("LAST-MODIFIED"),
+                String::from("SUMMARY"),
+            ]),
+        );
+
+        assert_eq!(
+            get_changed_property_names(Some(&existing_event), &existing_event),
+            BTreeSet::new(),
+        );
+
+        assert_eq!(
+            get_changed_property_names(None, &existing_event),
+            BTreeSet::from([
+                String::from("CATEGORIES"),
+                String::from("DTSTART"),
+                String::from("LAST-MODIFIED"),
+                String::from("SUMMARY"),
+                String::from("UID"),
+            ]),
+        );
+    }
 }
@@ -263,6 +263,63 @@ impl ContentLine {
 
 impl_icalendar_entity_traits!(ContentLine);
 
+/// The maximum length (in octets, excluding the line break) of a content line before it needs to
+/// be folded (RFC-5545 3.1).
+pub const MAX_CONTENT_LINE_OCTETS: usize = 75;
+
+/// Fold a rendered content line into multiple lines (joined by CRLF followed by a single space) so
+/// that no line exceeds 75 octets, without splitting multi-octet UTF-8 characters.
+pub fn fold_content_line(rendered_content_line: &str) -> String {
+    let mut folded_content_line = String::with_capacity(rendered_content_line.len());
+
+    // The first line has the full allowance, subsequent lines lose an octet to the leading space.
+    let mut line_octets = 0;
+    let mut line_max_octets = MAX_CONTENT_LINE_OCTETS;
+
+    for char in rendered_content_line.chars() {
+        let char_octets = char.len_utf8();
+
+        if line_octets + char_octets > line_max_octets {
+            folded_content_line.push_str("\r\n ");
+
+            line_octets = 0;
+            line_max_octets = MAX_CONTENT_LINE_OCTETS - 1;
+        }
+
+        folded_content_line.push(char);
+
+        line_octets += char_octets;
+    }
+
+    folded_content_line
+}
+
+/// Unfold long content lines (RFC-5545 3.1) by removing any line break immediately followed by a
+/// single whitespace character.
+pub fn unfold_content_lines(input: &str) -> String {
+    input
+        .replace("\r\n ", "")
+        .replace("\r\n\t", "")
+        .replace("\n ", "")
+        .replace("\n\t", "")
+}
+
+/// Join line break separated (and potentially folded) content lines into the whitespace separated
+/// form expected by the property parsers, dropping any blank lines. Input without line breaks is
+/// returned unchanged.
+pub fn join_line_separated_content_lines(input: &str) -> String {
+    if !input.contains('\n') {
+        return input.to_owned();
+    }
+
+    unfold_content_lines(input)
+        .lines()
+        .map(|content_line| content_line.trim())
+        .filter(|content_line| !content_line.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -615,4 +672,75 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_fold_content_line() {
+        assert_eq!(fold_content_line(""), String::from(""));
+        assert_eq!(fold_content_line("SUMMARY:Short summary"), String::from("SUMMARY:Short summary"));
+
+        let content_line = format!("DESCRIPTION:{}", "A".repeat(63));
+
+        assert_eq!(content_line.len(), 75);
+        assert_eq!(fold_content_line(content_line.as_str()), content_line);
+
+        let content_line = format!("DESCRIPTION:{}", "A".repeat(200));
+        let folded_content_line = fold_content_line(content_line.as_str());
+
+        assert_eq!(
+            folded_content_line,
+            format!(
+                "DESCRIPTION:{}\r\n {}\r\n {}",
+                "A".repeat(63),
+                "A".repeat(74),
+                "A".repeat(200 - 63 - 74),
+            ),
+        );
+
+        // Unfolding returns the original content line.
+        assert_eq!(folded_content_line.replace("\r\n ", ""), content_line);
+
+        // Multi-octet characters are never split across lines.
+        let content_line = format!("SUMMARY:{}", "🎄".repeat(30));
+        let folded_content_line = fold_content_line(content_line.as_str());
+
+        for line in folded_content_line.split("\r\n") {
+            assert!(line.len() <= MAX_CONTENT_LINE_OCTETS);
+        }
+
+        assert_eq!(folded_content_line.replace("\r\n ", ""), content_line);
+    }
+
+    #[test]
+    fn test_unfold_content_lines() {
+        let content_line = format!("DESCRIPTION:{}", "A".repeat(200));
+
+        assert_eq!(unfold_content_lines(fold_content_line(content_line.as_str()).as_str()), content_line);
+
+        assert_eq!(
+            unfold_content_lines("SUMMARY:Folded\n\t summary\r\nDTSTART:20201231T160000Z"),
+            String::from("SUMMARY:Folded summary\r\nDTSTART:20201231T160000Z"),
+        );
+    }
+
+    #[test]
+    fn test_join_line_separated_content_lines() {
+        assert_eq!(
+            join_line_separated_content_lines("SUMMARY:Some summary  DTSTART:20201231T160000Z "),
+            String::from("SUMMARY:Some summary  DTSTART:20201231T160000Z "),
+        );
+
+        assert_eq!(
+            join_line_separated_content_lines(
+                [
+                    "SUMMARY:Some summary",
+                    "DESCRIPTION;ALTREP=\"cid:some part\":Some long",
+                    "  description",
+                    "",
+                    "DTSTART:20201231T160000Z",
+                    "",
+                ].join("\r\n").as_str()
+            ),
+            String::from("SUMMARY:Some summary DESCRIPTION;ALTREP=\"cid:some part\":Some long description DTSTART:20201231T160000Z"),
+        );
+    }
 }
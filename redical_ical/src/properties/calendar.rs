@@ -8,24 +8,72 @@ use nom::multi::separated_list1;
 use crate::grammar::wsp;
 
 use crate::properties::uid::UIDProperty;
+use crate::properties::x_default_tzid::XDefaultTzidProperty;
+use crate::properties::x_default_dst_policy::XDefaultDstPolicyProperty;
+use crate::properties::x_term_normalization::XTermNormalizationProperty;
+use crate::properties::x_indexed_properties::XIndexedPropertiesProperty;
+use crate::properties::x_required_properties::XRequiredPropertiesProperty;
+use crate::properties::x_allowed_properties::XAllowedPropertiesProperty;
+use crate::properties::x_prune_older_than::XPruneOlderThanProperty;
+use crate::properties::name::NameProperty;
+use crate::properties::color::ColorProperty;
+use crate::properties::refresh_interval::RefreshIntervalProperty;
+use crate::properties::source::SourceProperty;
+use crate::properties::event::PassiveProperty;
 
-use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, convert_error};
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserContext, ParserResult, convert_error};
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum CalendarProperty {
     UID(UIDProperty),
+    DefaultTzid(XDefaultTzidProperty),
+    DefaultDstPolicy(XDefaultDstPolicyProperty),
+    TermNormalization(XTermNormalizationProperty),
+    IndexedProperties(XIndexedPropertiesProperty),
+    RequiredProperties(XRequiredPropertiesProperty),
+    AllowedProperties(XAllowedPropertiesProperty),
+    PruneOlderThan(XPruneOlderThanProperty),
+    Name(NameProperty),
+    Color(ColorProperty),
+    RefreshInterval(RefreshIntervalProperty),
+    Source(SourceProperty),
+    Passive(PassiveProperty),
 }
 
 impl ICalendarEntity for CalendarProperty {
     fn parse_ical(input: ParserInput) -> ParserResult<Self> {
         alt((
             map(UIDProperty::parse_ical, Self::UID),
+            map(XDefaultTzidProperty::parse_ical, Self::DefaultTzid),
+            map(XDefaultDstPolicyProperty::parse_ical, Self::DefaultDstPolicy),
+            map(XTermNormalizationProperty::parse_ical, Self::TermNormalization),
+            map(XIndexedPropertiesProperty::parse_ical, Self::IndexedProperties),
+            map(XRequiredPropertiesProperty::parse_ical, Self::RequiredProperties),
+            map(XAllowedPropertiesProperty::parse_ical, Self::AllowedProperties),
+            map(XPruneOlderThanProperty::parse_ical, Self::PruneOlderThan),
+            map(NameProperty::parse_ical, Self::Name),
+            map(ColorProperty::parse_ical, Self::Color),
+            map(RefreshIntervalProperty::parse_ical, Self::RefreshInterval),
+            map(SourceProperty::parse_ical, Self::Source),
+            map(PassiveProperty::parse_ical, Self::Passive),
         ))(input)
     }
 
     fn render_ical_with_context(&self, _context: Option<&RenderingContext>) -> String {
         match self {
             Self::UID(property) => property.render_ical(),
+            Self::DefaultTzid(property) => property.render_ical(),
+            Self::DefaultDstPolicy(property) => property.render_ical(),
+            Self::TermNormalization(property) => property.render_ical(),
+            Self::IndexedProperties(property) => property.render_ical(),
+            Self::RequiredProperties(property) => property.render_ical(),
+            Self::AllowedProperties(property) => property.render_ical(),
+            Self::PruneOlderThan(property) => property.render_ical(),
+            Self::Name(property) => property.render_ical(),
+            Self::Color(property) => property.render_ical(),
+            Self::RefreshInterval(property) => property.render_ical(),
+            Self::Source(property) => property.render_ical(),
+            Self::Passive(property) => property.render_ical(),
         }
     }
 }
@@ -36,7 +84,33 @@ impl std::hash::Hash for CalendarProperty {
     }
 }
 
-impl_icalendar_entity_traits!(CalendarProperty);
+impl FromStr for CalendarProperty {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        // Parse within the event parser context, so that passive property values (e.g. NAME:Team: Alpha)
+        // are only terminated by recognised properties.
+        let parser_result = all_consuming(Self::parse_ical)(ParserInput::new_extra(input, ParserContext::Event));
+
+        match parser_result {
+            Ok((_remaining, value)) => Ok(value),
+
+            Err(error) => {
+                if let nom::Err::Error(error) = error {
+                    Err(convert_error(input, error))
+                } else {
+                    Err(error.to_string())
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for CalendarProperty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render_ical())
+    }
+}
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct CalendarProperties(pub Vec<CalendarProperty>);
@@ -46,7 +120,7 @@ impl FromStr for CalendarProperties {
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let parsed_properties =
-            all_consuming(separated_list1(wsp, CalendarProperty::parse_ical))(input.into());
+            all_consuming(separated_list1(wsp, CalendarProperty::parse_ical))(ParserInput::new_extra(input, ParserContext::Event));
 
         match parsed_properties {
             Ok((_remaining, properties)) => {
@@ -83,5 +157,146 @@ mod tests {
                 ),
             ),
         );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical("X-DEFAULT-TZID:Europe/London UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::DefaultTzid(
+                    XDefaultTzidProperty::from_str("X-DEFAULT-TZID:Europe/London").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical("X-DEFAULT-DST-POLICY:SKIP UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::DefaultDstPolicy(
+                    XDefaultDstPolicyProperty::from_str("X-DEFAULT-DST-POLICY:SKIP").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical("X-TERM-NORMALIZATION:CASE-FOLD,TRIM UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::TermNormalization(
+                    XTermNormalizationProperty::from_str("X-TERM-NORMALIZATION:CASE-FOLD,TRIM").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical("X-INDEXED-PROPERTIES:X-INSTRUCTOR,X-LEVEL UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::IndexedProperties(
+                    XIndexedPropertiesProperty::from_str("X-INDEXED-PROPERTIES:X-INSTRUCTOR,X-LEVEL").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical("X-REQUIRED-PROPERTIES:GEO,CATEGORIES UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::RequiredProperties(
+                    XRequiredPropertiesProperty::from_str("X-REQUIRED-PROPERTIES:GEO,CATEGORIES").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical("X-ALLOWED-PROPERTIES:DTSTART,RRULE,SUMMARY UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::AllowedProperties(
+                    XAllowedPropertiesProperty::from_str("X-ALLOWED-PROPERTIES:DTSTART,RRULE,SUMMARY").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical("X-PRUNE-OLDER-THAN:P90D UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::PruneOlderThan(
+                    XPruneOlderThanProperty::from_str("X-PRUNE-OLDER-THAN:P90D").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical(ParserInput::new_extra("NAME;LANGUAGE=en:Team: Alpha UID:CALENDAR_UID", ParserContext::Event)),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::Name(
+                    NameProperty::from_str("NAME;LANGUAGE=en:Team: Alpha").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical("COLOR:turquoise UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::Color(
+                    ColorProperty::from_str("COLOR:turquoise").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical("REFRESH-INTERVAL;VALUE=DURATION:P1W UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::RefreshInterval(
+                    RefreshIntervalProperty::from_str("REFRESH-INTERVAL;VALUE=DURATION:P1W").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical("SOURCE:https://example.com/calendar.ics UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::Source(
+                    SourceProperty::from_str("SOURCE:https://example.com/calendar.ics").unwrap(),
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            CalendarProperty::parse_ical(ParserInput::new_extra("X-WR-CALNAME:Team Alpha UID:CALENDAR_UID", ParserContext::Event)),
+            (
+                " UID:CALENDAR_UID",
+                CalendarProperty::Passive(
+                    PassiveProperty::from_str("X-WR-CALNAME:Team Alpha").unwrap(),
+                ),
+            ),
+        );
+    }
+
+    #[test]
+    fn calendar_properties_from_str() {
+        assert_eq!(
+            CalendarProperties::from_str("UID:CALENDAR_UID COLOR:turquoise NAME:Team: Alpha X-DEFAULT-TZID:Europe/London"),
+            Ok(
+                CalendarProperties(
+                    vec![
+                        CalendarProperty::from_str("UID:CALENDAR_UID").unwrap(),
+                        CalendarProperty::Color(ColorProperty::from_str("COLOR:turquoise").unwrap()),
+                        CalendarProperty::Name(NameProperty::from_str("NAME:Team: Alpha").unwrap()),
+                        CalendarProperty::DefaultTzid(XDefaultTzidProperty::from_str("X-DEFAULT-TZID:Europe/London").unwrap()),
+                    ]
+                )
+            ),
+        );
+
+        assert!(CalendarProperties::from_str("UID:CALENDAR_UID DTSTART:20210101T090000Z").is_err());
+        assert!(CalendarProperties::from_str("UID:CALENDAR_UID REFRESH-INTERVAL;VALUE=DURATION:-P1W").is_err());
+        assert!(CalendarProperties::from_str("UID:CALENDAR_UID SOURCE:not a uri").is_err());
     }
 }
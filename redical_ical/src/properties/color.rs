@@ -0,0 +1,203 @@
+use itertools::Itertools;
+
+use nom::error::context;
+use nom::branch::alt;
+use nom::sequence::{pair, preceded};
+use nom::multi::separated_list1;
+use nom::combinator::{recognize, map, cut, opt, verify};
+
+use crate::grammar::{tag, semicolon, colon, comma, x_name, iana_token, param_value};
+
+use crate::values::text::Text;
+
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct ColorPropertyParams {
+    pub other: HashMap<String, String>,
+}
+
+impl ICalendarEntity for ColorPropertyParams {
+    define_property_params_ical_parser!(
+        ColorPropertyParams,
+        (
+            pair(alt((x_name, iana_token)), cut(preceded(tag("="), recognize(separated_list1(comma, param_value))))),
+            |params: &mut ColorPropertyParams, (key, value): (ParserInput, ParserInput)| params.other.insert(key.to_string(), value.to_string()),
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for ColorPropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        for (key, value) in self.other.clone().into_iter().sorted() {
+            content_line_params.insert(key.to_owned(), value.to_owned());
+        }
+
+        content_line_params
+    }
+}
+
+impl From<ColorPropertyParams> for ContentLineParams {
+    fn from(color_params: ColorPropertyParams) -> Self {
+        ContentLineParams::from(&color_params)
+    }
+}
+
+// Color (RFC 7986)
+//
+// Property Name:  COLOR
+//
+// Purpose:  This property specifies a color used for displaying the
+//    calendar, event, todo, or journal data.
+//
+// Value Type:  TEXT
+//
+// Property Parameters:  IANA and non-standard property parameters can
+//    be specified on this property.
+//
+// Conformance:  This property can be specified once in an iCalendar
+//    object or in "VEVENT", "VTODO", or "VJOURNAL" calendar components.
+//
+// Description:  This property specifies a color that clients MAY use
+//    when presenting the relevant data to a user.  Typically, this would
+//    appear as the "background" color of events or tasks.  The value is
+//    a case-insensitive color name taken from the CSS3 set of names,
+//    defined in Section 4.3 of [W3C.REC-css3-color-20110607].
+//
+// Format Definition:  This property is defined by the following
+//    notation:
+//
+//     color      = "COLOR" colorparam ":" text CRLF
+//                  ; Value is CSS3 color name
+//
+//     colorparam = *(";" other-param)
+//
+// Example:  This is an example of this property:
+//
+//     COLOR:turquoise
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ColorProperty {
+    pub params: ColorPropertyParams,
+    pub color: Text,
+}
+
+impl ICalendarEntity for ColorProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "COLOR",
+            preceded(
+                tag("COLOR"),
+                cut(
+                    map(
+                        pair(
+                            opt(ColorPropertyParams::parse_ical),
+                            preceded(
+                                colon,
+                                verify(
+                                    Text::parse_ical,
+                                    |color: &Text| !color.0.trim().is_empty(),
+                                ),
+                            ),
+                        ),
+                        |(params, color)| {
+                            ColorProperty {
+                                params: params.unwrap_or(ColorPropertyParams::default()),
+                                color,
+                            }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for ColorProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "COLOR",
+            (
+                ContentLineParams::from(&self.params),
+                self.color.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for ColorProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(ColorProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            ColorProperty::parse_ical("COLOR:turquoise NAME:Calendar".into()),
+            (
+                " NAME:Calendar",
+                ColorProperty {
+                    params: ColorPropertyParams::default(),
+                    color: Text(String::from("turquoise")),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            ColorProperty::parse_ical("COLOR;X-TEST=X_VALUE:darkslategray".into()),
+            (
+                "",
+                ColorProperty {
+                    params: ColorPropertyParams {
+                        other: HashMap::from([
+                            (String::from("X-TEST"), String::from("X_VALUE")),
+                        ]),
+                    },
+                    color: Text(String::from("darkslategray")),
+                },
+            ),
+        );
+
+        assert!(ColorProperty::parse_ical("COLOR:".into()).is_err());
+        assert!(ColorProperty::parse_ical(":".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            ColorProperty {
+                params: ColorPropertyParams::default(),
+                color: Text(String::from("turquoise")),
+            }.render_ical(),
+            String::from("COLOR:turquoise"),
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use itertools::Itertools;
+
+use nom::error::context;
+use nom::branch::alt;
+use nom::sequence::{pair, preceded};
+use nom::multi::separated_list1;
+use nom::combinator::{recognize, map, cut, opt};
+
+use crate::grammar::{tag, semicolon, colon, comma, x_name, iana_token, param_value};
+
+use crate::values::uri::Uri;
+
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+use std::collections::HashMap;
+
+// Feature (RFC 7986)
+//
+// Parameter Name:  FEATURE
+//
+// Purpose:  To specify a feature or features of a conference or
+//    broadcast system.
+//
+// Format Definition:  This property parameter is defined by the
+//    following notation:
+//
+//     featureparam = "FEATURE" "=" featuretext *("," featuretext)
+//     featuretext  =  ("AUDIO" /     ; Audio capability
+//                      "CHAT" /      ; Chat or instant messaging
+//                      "FEED" /      ; Blog or Atom feed
+//                      "MODERATOR" / ; Moderator dial-in code
+//                      "PHONE" /     ; Phone conference
+//                      "SCREEN" /    ; Screen sharing
+//                      "VIDEO" /     ; Video capability
+//                      x-name /      ; Experimental type
+//                      iana-token)   ; Other IANA-registered type
+pub fn feature_param_value(input: ParserInput) -> ParserResult<ParserInput> {
+    recognize(separated_list1(comma, alt((x_name, iana_token))))(input)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct ConferencePropertyParams {
+    pub feature: Option<String>,
+    pub label: Option<String>,
+    pub language: Option<String>,
+    pub other: HashMap<String, String>,
+}
+
+impl ICalendarEntity for ConferencePropertyParams {
+    define_property_params_ical_parser!(
+        ConferencePropertyParams,
+        (
+            pair(tag("FEATURE"), cut(preceded(tag("="), feature_param_value))),
+            |params: &mut ConferencePropertyParams, (_key, value): (ParserInput, ParserInput)| params.feature = Some(value.to_string()),
+        ),
+        (
+            pair(tag("LABEL"), cut(preceded(tag("="), param_value))),
+            |params: &mut ConferencePropertyParams, (_key, value): (ParserInput, ParserInput)| params.label = Some(value.to_string()),
+        ),
+        (
+            pair(tag("LANGUAGE"), cut(preceded(tag("="), param_value))),
+            |params: &mut ConferencePropertyParams, (_key, value): (ParserInput, ParserInput)| params.language = Some(value.to_string()),
+        ),
+        // The value is always a URI, so the (otherwise mandatory) VALUE=URI param is accepted, but
+        // not retained, as it is always rendered.
+        (
+            pair(tag("VALUE"), cut(preceded(tag("="), tag("URI")))),
+            |_params: &mut ConferencePropertyParams, (_key, _value): (ParserInput, ParserInput)| {},
+        ),
+        (
+            pair(alt((x_name, iana_token)), cut(preceded(tag("="), recognize(separated_list1(comma, param_value))))),
+            |params: &mut ConferencePropertyParams, (key, value): (ParserInput, ParserInput)| params.other.insert(key.to_string(), value.to_string()),
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for ConferencePropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        for (key, value) in self.other.clone().into_iter().sorted() {
+            content_line_params.insert(key.to_owned(), value.to_owned());
+        }
+
+        if let Some(feature) = self.feature.as_ref() {
+            content_line_params.insert(String::from("FEATURE"), feature.to_owned());
+        }
+
+        if let Some(label) = self.label.as_ref() {
+            content_line_params.insert(String::from("LABEL"), label.to_owned());
+        }
+
+        if let Some(language) = self.language.as_ref() {
+            content_line_params.insert(String::from("LANGUAGE"), language.to_owned());
+        }
+
+        content_line_params.insert(String::from("VALUE"), String::from("URI"));
+
+        content_line_params
+    }
+}
+
+impl From<ConferencePropertyParams> for ContentLineParams {
+    fn from(conference_params: ConferencePropertyParams) -> Self {
+        ContentLineParams::from(&conference_params)
+    }
+}
+
+// Conference (RFC 7986)
+//
+// Property Name:  CONFERENCE
+//
+// Purpose:  This property specifies information for accessing a
+//    conferencing system.
+//
+// Value Type:  URI -- no default.
+//
+// Property Parameters:  IANA, non-standard, feature, and label property
+//    parameters can be specified on this property.
+//
+// Conformance:  This property can be specified multiple times in a
+//    "VEVENT" or "VTODO" calendar component.
+//
+// Description:  This property specifies information for accessing a
+//    conferencing system for attendees of a meeting or task.  This
+//    might be for a telephone-based conference number dial-in with
+//    access codes included (such as a tel: URI [RFC3966] or a sip: or
+//    sips: URI [RFC3261]), for a web-based video chat (such as an http:
+//    or https: URI [RFC7230]), or for an instant messaging group chat
+//    room (such as an xmpp: URI [RFC5122]).  If a specific URI for a
+//    conferencing system is not available, a data: URI [RFC2397]
+//    containing a text description can be used.
+//
+// Format Definition:  This property is defined by the following
+//    notation:
+//
+//     conference      = "CONFERENCE" confparam ":" uri CRLF
+//
+//     confparam       = *(
+//                       ;
+//                       ; The following is REQUIRED,
+//                       ; but MUST NOT occur more than once.
+//                       ;
+//                       (";" "VALUE" "=" "URI") /
+//                       ;
+//                       ; The following are OPTIONAL,
+//                       ; and MUST NOT occur more than once.
+//                       ;
+//                       (";" featureparam) / (";" labelparam) /
+//                       (";" languageparam ) /
+//                       ;
+//                       ; The following is OPTIONAL,
+//                       ; and MAY occur more than once.
+//                       ;
+//                       (";" other-param)
+//                       ;
+//                       )
+//
+// Example:  The following are examples of this property:
+//
+//     CONFERENCE;VALUE=URI;FEATURE=PHONE,MODERATOR;
+//      LABEL=Moderator dial-in:tel:+1-412-555-0123,,,654321
+//     CONFERENCE;VALUE=URI;FEATURE=PHONE;
+//      LABEL=Attendee dial-in:tel:+1-412-555-0123,,,555123
+//     CONFERENCE;VALUE=URI;FEATURE=PHONE;
+//      LABEL=Attendee dial-in:tel:+1-888-555-0456,,,555123
+//     CONFERENCE;VALUE=URI;FEATURE=CHAT;
+//      LABEL=Chat room:xmpp:chat-123@conference.example.com
+//     CONFERENCE;VALUE=URI;FEATURE=AUDIO,VIDEO;
+//      LABEL=Attendee dial-in:https://chat.example.com/audio?id=123456
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ConferenceProperty {
+    pub params: ConferencePropertyParams,
+    pub uri: Uri,
+}
+
+impl ICalendarEntity for ConferenceProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "CONFERENCE",
+            preceded(
+                tag("CONFERENCE"),
+                cut(
+                    map(
+                        pair(
+                            opt(ConferencePropertyParams::parse_ical),
+                            preceded(colon, Uri::parse_ical),
+                        ),
+                        |(params, uri)| {
+                            ConferenceProperty {
+                                params: params.unwrap_or(ConferencePropertyParams::default()),
+                                uri,
+                            }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for ConferenceProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "CONFERENCE",
+            (
+                ContentLineParams::from(&self.params),
+                self.uri.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for ConferenceProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(ConferenceProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            ConferenceProperty::parse_ical("CONFERENCE:https://chat.example.com/audio?id=123456 SUMMARY:Meeting".into()),
+            (
+                " SUMMARY:Meeting",
+                ConferenceProperty {
+                    params: ConferencePropertyParams::default(),
+                    uri: Uri(String::from("https://chat.example.com/audio?id=123456")),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            ConferenceProperty::parse_ical("CONFERENCE;VALUE=URI;FEATURE=PHONE,MODERATOR;LABEL=\"Moderator dial-in\";X-TEST=X_VALUE:tel:+1-412-555-0123,,,654321".into()),
+            (
+                "",
+                ConferenceProperty {
+                    params: ConferencePropertyParams {
+                        feature: Some(String::from("PHONE,MODERATOR")),
+                        label: Some(String::from("\"Moderator dial-in\"")),
+                        language: None,
+                        other: HashMap::from([
+                            (String::from("X-TEST"), String::from("X_VALUE")),
+                        ]),
+                    },
+                    uri: Uri(String::from("tel:+1-412-555-0123,,,654321")),
+                },
+            ),
+        );
+
+        assert!(ConferenceProperty::parse_ical("CONFERENCE;VALUE=TEXT:https://chat.example.com".into()).is_err());
+        assert!(ConferenceProperty::parse_ical("CONFERENCE;FEATURE=:https://chat.example.com".into()).is_err());
+        assert!(ConferenceProperty::parse_ical("CONFERENCE:Dial in".into()).is_err());
+        assert!(ConferenceProperty::parse_ical("CONFERENCE:".into()).is_err());
+        assert!(ConferenceProperty::parse_ical(":".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            ConferenceProperty {
+                params: ConferencePropertyParams::default(),
+                uri: Uri(String::from("https://chat.example.com/audio?id=123456")),
+            }.render_ical(),
+            String::from("CONFERENCE;VALUE=URI:https://chat.example.com/audio?id=123456"),
+        );
+
+        assert_eq!(
+            ConferenceProperty {
+                params: ConferencePropertyParams {
+                    feature: Some(String::from("AUDIO,VIDEO")),
+                    label: Some(String::from("\"Attendee dial-in\"")),
+                    language: Some(String::from("en")),
+                    other: HashMap::from([
+                        (String::from("X-TEST"), String::from("X_VALUE")),
+                    ]),
+                },
+                uri: Uri(String::from("https://chat.example.com/audio?id=123456")),
+            }.render_ical(),
+            String::from("CONFERENCE;X-TEST=X_VALUE;FEATURE=AUDIO,VIDEO;LABEL=\"Attendee dial-in\";LANGUAGE=en;VALUE=URI:https://chat.example.com/audio?id=123456"),
+        );
+    }
+}
@@ -0,0 +1,338 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+use nom::error::context;
+use nom::branch::alt;
+use nom::sequence::{preceded, terminated};
+use nom::multi::many0;
+use nom::combinator::{map, cut, not, verify};
+
+use crate::grammar::{tag, wsp};
+
+use crate::values::date_time::DateTime;
+
+use crate::properties::{ICalendarProperty, UIDProperty};
+use crate::properties::event::PassiveProperty;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+// The properties permitted within the VALARM component, in addition to the passive properties
+// (which include ACTION, TRIGGER, REPEAT, ACKNOWLEDGED, and PROXIMITY).
+//
+// Nested components (i.e. VLOCATION) are not supported.
+fn alarm_property(input: ParserInput) -> ParserResult<ContentLine> {
+    preceded(
+        not(tag("BEGIN")),
+        alt((
+            map(UIDProperty::parse_ical, |property| property.to_content_line()),
+            map(PassiveProperty::parse_ical, |property| property.to_content_line()),
+            ContentLine::parse_ical_for_property("DURATION"),
+            ContentLine::parse_ical_for_property("RELATED-TO"),
+        )),
+    )(input)
+}
+
+// Alarm Component (RFC 5545, extended by RFC 9074)
+//
+// Component Name:  VALARM
+//
+// Purpose:  Provide a grouping of component properties that define an
+//    alarm.
+//
+// Description:  A "VALARM" calendar component is a grouping of
+//    component properties that is a reminder or alarm for an event or a
+//    to-do.  For example, it may be used to define a reminder for a
+//    pending event or an overdue to-do.
+//
+//    RFC 9074 extends the component with a "UID" property (so that an
+//    alarm can be uniquely identified, and so related to by snoozed
+//    alarms), an "ACKNOWLEDGED" property (recording when the alarm was
+//    last sent or handled), and a "PROXIMITY" property (so that an alarm
+//    can be triggered by location rather than time).
+//
+// Format Definition:  This component is defined by the following
+//    notation:
+//
+//     alarmc     = "BEGIN" ":" "VALARM" CRLF
+//                  alarmprop *locationc
+//                  "END" ":" "VALARM" CRLF
+//
+//     alarmprop  = *(
+//                ;
+//                ; The following are REQUIRED,
+//                ; but MUST NOT occur more than once.
+//                ;
+//                action / trigger /
+//                ;
+//                ; One or more of the following are OPTIONAL,
+//                ; but MUST NOT occur more than once.
+//                ;
+//                uid / acknowledged / proximity /
+//                ;
+//                ; 'duration' and 'repeat' are both OPTIONAL,
+//                ; and MUST NOT occur more than once each;
+//                ; but if one occurs, so MUST the other.
+//                ;
+//                duration / repeat /
+//                ;
+//                ; The following are OPTIONAL,
+//                ; and MAY occur more than once.
+//                ;
+//                attach / description / summary / attendee /
+//                related / x-prop / iana-prop
+//                ;
+//                )
+//
+// Example:  The following is an example of this component:
+//
+//     BEGIN:VALARM
+//     UID:8297C37D-BA2D-4476-91AE-C1EAA364F8E1
+//     TRIGGER:-PT15M
+//     DESCRIPTION:Event reminder
+//     ACTION:DISPLAY
+//     ACKNOWLEDGED:20090604T084500Z
+//     END:VALARM
+//
+// As with the PARTICIPANT component, the component is flattened into the (whitespace separated)
+// sequence of it's content lines, and so is rendered as a single content line spanning them all
+// (with the order of the properties within retained).
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct AlarmComponent {
+    pub properties: Vec<ContentLine>,
+}
+
+impl AlarmComponent {
+    pub fn get_uid(&self) -> Option<&String> {
+        self.get_property_values("UID").next()
+    }
+
+    pub fn get_action(&self) -> Option<&String> {
+        self.get_property_values("ACTION").next()
+    }
+
+    pub fn get_acknowledged(&self) -> Option<&String> {
+        self.get_property_values("ACKNOWLEDGED").next()
+    }
+
+    pub fn get_proximity(&self) -> Option<&String> {
+        self.get_property_values("PROXIMITY").next()
+    }
+
+    fn get_property_values<'a>(&'a self, property_name: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        self.properties
+            .iter()
+            .filter(move |content_line| content_line.0 == property_name)
+            .map(|content_line| &content_line.2)
+    }
+
+    /// Whether the component has exactly one of each of the required ACTION and TRIGGER
+    /// properties, no more than one of each of the UID, ACKNOWLEDGED (a UTC date-time), and
+    /// PROXIMITY properties, and either both or neither of the DURATION and REPEAT properties.
+    pub fn is_valid(&self) -> bool {
+        let count = |property_name: &str| self.get_property_values(property_name).count();
+
+        let is_acknowledged_valid =
+            match self.get_acknowledged() {
+                Some(acknowledged) => matches!(DateTime::from_str(acknowledged), Ok(DateTime::UtcDateTime(_))),
+                None => true,
+            };
+
+        count("ACTION") == 1 &&
+        count("TRIGGER") == 1 &&
+        count("UID") <= 1 &&
+        count("ACKNOWLEDGED") <= 1 &&
+        count("PROXIMITY") <= 1 &&
+        count("DURATION") <= 1 &&
+        count("DURATION") == count("REPEAT") &&
+        is_acknowledged_valid
+    }
+
+    /// The content lines of the component, bounded by it's BEGIN and END content lines (as
+    /// rendered within an iCalendar document).
+    pub fn to_content_lines(&self) -> Vec<ContentLine> {
+        let mut content_lines = Vec::with_capacity(self.properties.len() + 2);
+
+        content_lines.push(ContentLine::from(("BEGIN", (ContentLineParams::default(), String::from("VALARM")))));
+        content_lines.extend(self.properties.iter().cloned());
+        content_lines.push(ContentLine::from(("END", (ContentLineParams::default(), String::from("VALARM")))));
+
+        content_lines
+    }
+}
+
+impl ICalendarEntity for AlarmComponent {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "VALARM",
+            preceded(
+                tag("BEGIN:VALARM"),
+                cut(
+                    map_err_message!(
+                        verify(
+                            map(
+                                terminated(
+                                    many0(preceded(wsp, alarm_property)),
+                                    preceded(wsp, tag("END:VALARM")),
+                                ),
+                                |properties| AlarmComponent { properties },
+                            ),
+                            |alarm: &AlarmComponent| alarm.is_valid(),
+                        ),
+                        "expected VALARM component with a single ACTION and TRIGGER property, terminated by END:VALARM",
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for AlarmComponent {
+    /// Build a `ContentLine` instance (spanning all the content lines of the component) with
+    /// consideration to the optionally provided `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        let value =
+            self.to_content_lines()
+                .iter()
+                .skip(1)
+                .map(|content_line| content_line.render_ical())
+                .join(" ");
+
+        ContentLine::from((
+            "BEGIN",
+            (
+                ContentLineParams::default(),
+                format!("VALARM {value}"),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for AlarmComponent {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(AlarmComponent);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    use crate::ParserContext;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            AlarmComponent::parse_ical(
+                ParserInput::new_extra(
+                    "BEGIN:VALARM UID:8297C37D TRIGGER;RELATED=START:-PT15M DESCRIPTION:Event reminder ACTION:DISPLAY ACKNOWLEDGED:20090604T084500Z PROXIMITY:ARRIVE END:VALARM SUMMARY:Meeting",
+                    ParserContext::Event,
+                )
+            ),
+            (
+                " SUMMARY:Meeting",
+                AlarmComponent {
+                    properties: vec![
+                        ContentLine::from(("UID", vec![], "8297C37D")),
+                        ContentLine::from(("TRIGGER", vec![("RELATED", "START")], "-PT15M")),
+                        ContentLine::from(("DESCRIPTION", vec![], "Event reminder")),
+                        ContentLine::from(("ACTION", vec![], "DISPLAY")),
+                        ContentLine::from(("ACKNOWLEDGED", vec![], "20090604T084500Z")),
+                        ContentLine::from(("PROXIMITY", vec![], "ARRIVE")),
+                    ],
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            AlarmComponent::parse_ical(
+                ParserInput::new_extra(
+                    "BEGIN:VALARM ACTION:AUDIO TRIGGER:-PT30M DURATION:PT5M REPEAT:2 RELATED-TO;RELTYPE=SNOOZE:8297C37D END:VALARM",
+                    ParserContext::Event,
+                )
+            ),
+            (
+                "",
+                AlarmComponent {
+                    properties: vec![
+                        ContentLine::from(("ACTION", vec![], "AUDIO")),
+                        ContentLine::from(("TRIGGER", vec![], "-PT30M")),
+                        ContentLine::from(("DURATION", vec![], "PT5M")),
+                        ContentLine::from(("REPEAT", vec![], "2")),
+                        ContentLine::from(("RELATED-TO", vec![("RELTYPE", "SNOOZE")], "8297C37D")),
+                    ],
+                },
+            ),
+        );
+
+        // Missing the required ACTION and TRIGGER properties.
+        assert!(AlarmComponent::parse_ical("BEGIN:VALARM TRIGGER:-PT15M END:VALARM".into()).is_err());
+        assert!(AlarmComponent::parse_ical("BEGIN:VALARM ACTION:DISPLAY END:VALARM".into()).is_err());
+
+        // Properties occurring more than once, or without their counterpart.
+        assert!(AlarmComponent::parse_ical("BEGIN:VALARM ACTION:DISPLAY TRIGGER:-PT15M UID:ONE UID:TWO END:VALARM".into()).is_err());
+        assert!(AlarmComponent::parse_ical("BEGIN:VALARM ACTION:DISPLAY TRIGGER:-PT15M PROXIMITY:ARRIVE PROXIMITY:DEPART END:VALARM".into()).is_err());
+        assert!(AlarmComponent::parse_ical("BEGIN:VALARM ACTION:DISPLAY TRIGGER:-PT15M REPEAT:2 END:VALARM".into()).is_err());
+
+        // ACKNOWLEDGED is required to be a UTC date-time.
+        assert!(AlarmComponent::parse_ical("BEGIN:VALARM ACTION:DISPLAY TRIGGER:-PT15M ACKNOWLEDGED:20090604T084500 END:VALARM".into()).is_err());
+        assert!(AlarmComponent::parse_ical("BEGIN:VALARM ACTION:DISPLAY TRIGGER:-PT15M ACKNOWLEDGED:yesterday END:VALARM".into()).is_err());
+
+        // Unterminated, and nested components.
+        assert!(AlarmComponent::parse_ical("BEGIN:VALARM ACTION:DISPLAY TRIGGER:-PT15M".into()).is_err());
+        assert!(AlarmComponent::parse_ical("BEGIN:VALARM ACTION:DISPLAY TRIGGER:-PT15M BEGIN:VLOCATION UID:LOCATION END:VLOCATION END:VALARM".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        let alarm = AlarmComponent {
+            properties: vec![
+                ContentLine::from(("UID", vec![], "8297C37D")),
+                ContentLine::from(("TRIGGER", vec![], "-PT15M")),
+                ContentLine::from(("ACTION", vec![], "DISPLAY")),
+                ContentLine::from(("ACKNOWLEDGED", vec![], "20090604T084500Z")),
+            ],
+        };
+
+        assert_eq!(
+            alarm.render_ical(),
+            String::from("BEGIN:VALARM UID:8297C37D TRIGGER:-PT15M ACTION:DISPLAY ACKNOWLEDGED:20090604T084500Z END:VALARM"),
+        );
+
+        assert_eq!(
+            alarm
+                .to_content_lines()
+                .iter()
+                .map(|content_line| content_line.render_ical())
+                .collect::<Vec<String>>(),
+            vec![
+                String::from("BEGIN:VALARM"),
+                String::from("UID:8297C37D"),
+                String::from("TRIGGER:-PT15M"),
+                String::from("ACTION:DISPLAY"),
+                String::from("ACKNOWLEDGED:20090604T084500Z"),
+                String::from("END:VALARM"),
+            ],
+        );
+
+        assert_eq!(
+            AlarmComponent::from_str(alarm.render_ical().as_str()),
+            Ok(alarm.clone()),
+        );
+
+        assert_eq!(alarm.get_uid(), Some(&String::from("8297C37D")));
+        assert_eq!(alarm.get_action(), Some(&String::from("DISPLAY")));
+        assert_eq!(alarm.get_acknowledged(), Some(&String::from("20090604T084500Z")));
+        assert_eq!(alarm.get_proximity(), None);
+    }
+}
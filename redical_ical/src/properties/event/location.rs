@@ -0,0 +1,287 @@
+use itertools::Itertools;
+
+use nom::error::context;
+use nom::branch::alt;
+use nom::sequence::{pair, preceded, terminated};
+use nom::multi::separated_list1;
+use nom::combinator::{recognize, map, cut, opt, not};
+
+use crate::grammar::{tag, semicolon, colon, comma, x_name, iana_token, param_value};
+
+use crate::values::text::Text;
+
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+use std::collections::HashMap;
+
+/// Normalize LOCATION text for indexing and querying, trimming it and collapsing each run of
+/// whitespace into a single space (so that "Royal  Albert Hall " matches "Royal Albert Hall").
+pub fn normalize_location(location: &str) -> String {
+    location.split_whitespace().join(" ")
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct LocationPropertyParams {
+    pub altrep: Option<String>,
+    pub language: Option<String>,
+    pub other: HashMap<String, String>,
+}
+
+impl ICalendarEntity for LocationPropertyParams {
+    define_property_params_ical_parser!(
+        LocationPropertyParams,
+        (
+            pair(tag("ALTREP"), cut(preceded(tag("="), param_value))),
+            |params: &mut LocationPropertyParams, (_key, value): (ParserInput, ParserInput)| params.altrep = Some(value.to_string()),
+        ),
+        (
+            pair(tag("LANGUAGE"), cut(preceded(tag("="), param_value))),
+            |params: &mut LocationPropertyParams, (_key, value): (ParserInput, ParserInput)| params.language = Some(value.to_string()),
+        ),
+        (
+            pair(alt((x_name, iana_token)), cut(preceded(tag("="), recognize(separated_list1(comma, param_value))))),
+            |params: &mut LocationPropertyParams, (key, value): (ParserInput, ParserInput)| params.other.insert(key.to_string(), value.to_string()),
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for LocationPropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        for (key, value) in self.other.clone().into_iter().sorted() {
+            content_line_params.insert(key.to_owned(), value.to_owned());
+        }
+
+        if let Some(altrep) = self.altrep.as_ref() {
+            content_line_params.insert(String::from("ALTREP"), altrep.to_owned());
+        }
+
+        if let Some(language) = self.language.as_ref() {
+            content_line_params.insert(String::from("LANGUAGE"), language.to_owned());
+        }
+
+        content_line_params
+    }
+}
+
+impl From<LocationPropertyParams> for ContentLineParams {
+    fn from(location_params: LocationPropertyParams) -> Self {
+        ContentLineParams::from(&location_params)
+    }
+}
+
+// Location
+//
+// Property Name:  LOCATION
+//
+// Purpose:  This property defines the intended venue for the activity
+//    defined by a calendar component.
+//
+// Value Type:  TEXT
+//
+// Property Parameters:  IANA, non-standard, alternate text
+//    representation, and language property parameters can be specified
+//    on this property.
+//
+// Conformance:  This property can be specified in "VEVENT" or "VTODO"
+//    calendar component.
+//
+// Description:  Specific venues such as conference or meeting rooms may
+//    be explicitly specified using this property.  An alternate
+//    representation may be specified that is a URI that points to
+//    directory information with more structured specification of the
+//    location.  For example, the alternate representation may specify
+//    either an LDAP URL [RFC4516] pointing to an LDAP server entry or a
+//    CID URL [RFC2392] pointing to a MIME body part containing a
+//    Virtual-Information Card (vCard) [RFC2426] for the location.
+//
+// Format Definition:  This property is defined by the following
+//    notation:
+//
+//     location   = "LOCATION"  locparam ":" text CRLF
+//
+//     locparam   = *(
+//                ;
+//                ; The following are OPTIONAL,
+//                ; but MUST NOT occur more than once.
+//                ;
+//                (";" altrepparam) / (";" languageparam) /
+//                ;
+//                ; The following is OPTIONAL,
+//                ; and MAY occur more than once.
+//                ;
+//                (";" other-param)
+//                ;
+//                )
+//
+// Example:  The following are some examples of this property:
+//
+//     LOCATION:Conference Room - F123\, Bldg. 002
+//
+//     LOCATION;ALTREP="http://xyzcorp.com/conf-rooms/f123.vcf":
+//      Conference Room - F123\, Bldg. 002
+//
+// Unlike the LOCATION-TYPE property, the text of this property is indexed as a whole (normalized
+// by `normalize_location`), rather than as a list of values.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LocationProperty {
+    pub params: LocationPropertyParams,
+    pub location: Text,
+}
+
+impl ICalendarEntity for LocationProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "LOCATION",
+            preceded(
+                // Prevent the LOCATION-TYPE property being mistaken for this one.
+                terminated(tag("LOCATION"), not(tag("-"))),
+                cut(
+                    map(
+                        pair(
+                            opt(LocationPropertyParams::parse_ical),
+                            preceded(colon, Text::parse_ical),
+                        ),
+                        |(params, location)| {
+                            LocationProperty {
+                                params: params.unwrap_or(LocationPropertyParams::default()),
+                                location,
+                            }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for LocationProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "LOCATION",
+            (
+                ContentLineParams::from(&self.params),
+                self.location.to_string(),
+            )
+        ))
+    }
+}
+
+impl LocationProperty {
+    /// Return the normalized location text (as indexed), or None if blank.
+    pub fn get_normalized_location(&self) -> Option<String> {
+        let normalized_location = normalize_location(self.location.to_string().as_str());
+
+        if normalized_location.is_empty() {
+            None
+        } else {
+            Some(normalized_location)
+        }
+    }
+}
+
+impl std::hash::Hash for LocationProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(LocationProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            LocationProperty::parse_ical("LOCATION:Conference Room - F123\\, Bldg. 002 DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                LocationProperty {
+                    params: LocationPropertyParams::default(),
+                    location: Text(String::from("Conference Room - F123\\, Bldg. 002")),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            LocationProperty::parse_ical("LOCATION;X-TEST=X_VALUE;LANGUAGE=en;ALTREP=\"http://xyzcorp.com/conf-rooms/f123.vcf\":Conference Room".into()),
+            (
+                "",
+                LocationProperty {
+                    params: LocationPropertyParams {
+                        altrep: Some(String::from("\"http://xyzcorp.com/conf-rooms/f123.vcf\"")),
+                        language: Some(String::from("en")),
+                        other: HashMap::from([
+                            (String::from("X-TEST"), String::from("X_VALUE")),
+                        ]),
+                    },
+                    location: Text(String::from("Conference Room")),
+                },
+            ),
+        );
+
+        assert!(LocationProperty::parse_ical("LOCATION-TYPE:HOTEL".into()).is_err());
+        assert!(LocationProperty::parse_ical(":".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            LocationProperty {
+                params: LocationPropertyParams::default(),
+                location: Text(String::from("Conference Room - F123\\, Bldg. 002")),
+            }.render_ical(),
+            String::from("LOCATION:Conference Room - F123\\, Bldg. 002"),
+        );
+
+        assert_eq!(
+            LocationProperty {
+                params: LocationPropertyParams {
+                    altrep: Some(String::from("\"http://xyzcorp.com/conf-rooms/f123.vcf\"")),
+                    language: Some(String::from("en")),
+                    other: HashMap::from([
+                        (String::from("X-TEST"), String::from("X_VALUE")),
+                    ]),
+                },
+                location: Text(String::from("Conference Room")),
+            }.render_ical(),
+            String::from("LOCATION;X-TEST=X_VALUE;ALTREP=\"http://xyzcorp.com/conf-rooms/f123.vcf\";LANGUAGE=en:Conference Room"),
+        );
+    }
+
+    #[test]
+    fn get_normalized_location() {
+        assert_eq!(
+            LocationProperty::from_str("LOCATION: Royal  Albert\tHall ").unwrap().get_normalized_location(),
+            Some(String::from("Royal Albert Hall")),
+        );
+
+        assert_eq!(
+            LocationProperty::from_str("LOCATION:   ").unwrap().get_normalized_location(),
+            None,
+        );
+    }
+}
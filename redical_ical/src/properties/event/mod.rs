@@ -16,13 +16,19 @@ mod exrule;
 
 mod categories;
 mod location_type;
+mod location;
 mod class;
 mod geo;
 mod related_to;
 
+mod x_override_mode;
+
+mod participant;
+mod alarm;
+
 mod passive;
 
-use crate::grammar::{wsp, wsp_1_1};
+use crate::grammar::{tag, wsp, wsp_1_1};
 
 pub use dtstart::{DTStartProperty, DTStartPropertyParams};
 pub use dtend::{DTEndProperty, DTEndPropertyParams};
@@ -34,10 +40,16 @@ pub use exrule::{ExRuleProperty, ExRulePropertyParams};
 
 pub use categories::{CategoriesProperty, CategoriesPropertyParams};
 pub use location_type::{LocationTypeProperty, LocationTypePropertyParams};
+pub use location::{LocationProperty, LocationPropertyParams, normalize_location};
 pub use class::{ClassProperty, ClassPropertyParams};
 pub use geo::{GeoProperty, GeoPropertyParams};
 pub use related_to::{RelatedToProperty, RelatedToPropertyParams};
 
+pub use x_override_mode::XOverrideModeProperty;
+
+pub use participant::ParticipantComponent;
+pub use alarm::AlarmComponent;
+
 use crate::content_line::ContentLine;
 
 pub use passive::PassiveProperty;
@@ -60,9 +72,11 @@ pub enum EventProperty {
     ExRule(ExRuleProperty),
     Categories(CategoriesProperty),
     LocationType(LocationTypeProperty),
+    Location(LocationProperty),
     Class(ClassProperty),
     Geo(GeoProperty),
     RelatedTo(RelatedToProperty),
+    OverrideMode(XOverrideModeProperty),
     Passive(PassiveProperty),
 }
 
@@ -88,6 +102,18 @@ impl EventProperty {
                     recognize(ContentLine::parse_ical_for_property("CLASS")),
                     recognize(ContentLine::parse_ical_for_property("GEO")),
                     recognize(ContentLine::parse_ical_for_property("RELATED-TO")),
+                    recognize(ContentLine::parse_ical_for_property("X-OVERRIDE-MODE")),
+                    alt((
+                        recognize(ContentLine::parse_ical_for_property("LOCATION")),
+                        recognize(ContentLine::parse_ical_for_property("COLOR")),
+                        recognize(ContentLine::parse_ical_for_property("IMAGE")),
+                        recognize(ContentLine::parse_ical_for_property("CONFERENCE")),
+                        recognize(ContentLine::parse_ical_for_property("STRUCTURED-DATA")),
+                        tag("BEGIN:PARTICIPANT"),
+                        tag("END:PARTICIPANT"),
+                        tag("BEGIN:VALARM"),
+                        tag("END:VALARM"),
+                    )),
                     recognize(PassiveProperty::parse_ical),
                 )),
             ),
@@ -109,9 +135,11 @@ impl ICalendarEntity for EventProperty {
             map(ExRuleProperty::parse_ical, Self::ExRule),
             map(CategoriesProperty::parse_ical, Self::Categories),
             map(LocationTypeProperty::parse_ical, Self::LocationType),
+            map(LocationProperty::parse_ical, Self::Location),
             map(ClassProperty::parse_ical, Self::Class),
             map(GeoProperty::parse_ical, Self::Geo),
             map(RelatedToProperty::parse_ical, Self::RelatedTo),
+            map(XOverrideModeProperty::parse_ical, Self::OverrideMode),
             map(PassiveProperty::parse_ical, Self::Passive),
         ))(input)
     }
@@ -129,9 +157,11 @@ impl ICalendarEntity for EventProperty {
             Self::ExRule(property) => property.render_ical(),
             Self::Categories(property) => property.render_ical(),
             Self::LocationType(property) => property.render_ical(),
+            Self::Location(property) => property.render_ical(),
             Self::Class(property) => property.render_ical(),
             Self::Geo(property) => property.render_ical(),
             Self::RelatedTo(property) => property.render_ical(),
+            Self::OverrideMode(property) => property.render_ical(),
             Self::Passive(property) => property.render_ical(),
         }
     }
@@ -339,6 +369,16 @@ mod tests {
             ),
         );
 
+        assert_parser_output!(
+            EventProperty::parse_ical("LOCATION:Royal Albert Hall DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                EventProperty::Location(
+                    LocationProperty::from_str("LOCATION:Royal Albert Hall").unwrap()
+                ),
+            ),
+        );
+
         assert_parser_output!(
             EventProperty::parse_ical("GEO:37.386013;-122.082932 DESCRIPTION:Description text".into()),
             (
@@ -359,6 +399,16 @@ mod tests {
             ),
         );
 
+        assert_parser_output!(
+            EventProperty::parse_ical("X-OVERRIDE-MODE:REPLACE DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                EventProperty::OverrideMode(
+                    XOverrideModeProperty::from_str("X-OVERRIDE-MODE:REPLACE").unwrap()
+                ),
+            ),
+        );
+
         assert_parser_output!(
             EventProperty::parse_ical(ParserInput::new_extra("DESCRIPTION:Description text TEST:ING DTSTART:19960401T150000Z", ParserContext::Event)),
             (
@@ -369,6 +419,49 @@ mod tests {
             ),
         );
 
+        // Invalid typed (RFC 7986) properties still terminate the preceding passive property value.
+        assert_parser_output!(
+            EventProperty::parse_ical(ParserInput::new_extra("SUMMARY:Team sync IMAGE:iVBORw0KGgo=", ParserContext::Event)),
+            (
+                " IMAGE:iVBORw0KGgo=",
+                EventProperty::Passive(
+                    PassiveProperty::from_str("SUMMARY:Team sync").unwrap()
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            EventProperty::parse_ical(ParserInput::new_extra("CONFERENCE;FEATURE=AUDIO,VIDEO:https://chat.example.com/audio?id=123456 COLOR:turquoise", ParserContext::Event)),
+            (
+                " COLOR:turquoise",
+                EventProperty::Passive(
+                    PassiveProperty::from_str("CONFERENCE;FEATURE=AUDIO,VIDEO;VALUE=URI:https://chat.example.com/audio?id=123456").unwrap()
+                ),
+            ),
+        );
+
+        // Passive values within the PARTICIPANT component are terminated by the properties of the
+        // component, and the component by the END content line.
+        assert_parser_output!(
+            EventProperty::parse_ical(ParserInput::new_extra("DESCRIPTION:Cup final BEGIN:PARTICIPANT PARTICIPANT-TYPE:PERFORMER UID:HOME_TEAM DESCRIPTION:Home team END:PARTICIPANT", ParserContext::Event)),
+            (
+                " BEGIN:PARTICIPANT PARTICIPANT-TYPE:PERFORMER UID:HOME_TEAM DESCRIPTION:Home team END:PARTICIPANT",
+                EventProperty::Passive(
+                    PassiveProperty::from_str("DESCRIPTION:Cup final").unwrap()
+                ),
+            ),
+        );
+
+        assert_parser_output!(
+            EventProperty::parse_ical(ParserInput::new_extra("BEGIN:PARTICIPANT PARTICIPANT-TYPE:PERFORMER UID:HOME_TEAM DESCRIPTION:Home team END:PARTICIPANT SUMMARY:Cup final", ParserContext::Event)),
+            (
+                " SUMMARY:Cup final",
+                EventProperty::Passive(
+                    PassiveProperty::from_str("BEGIN:PARTICIPANT PARTICIPANT-TYPE:PERFORMER UID:HOME_TEAM DESCRIPTION:Home team END:PARTICIPANT").unwrap()
+                ),
+            ),
+        );
+
         assert_parser_output!(
             EventProperty::parse_ical("LAST-MODIFIED:19960401T080045Z DESCRIPTION:Description text".into()),
             (
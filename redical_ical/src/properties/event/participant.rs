@@ -0,0 +1,274 @@
+use itertools::Itertools;
+
+use nom::error::context;
+use nom::branch::alt;
+use nom::sequence::{preceded, terminated};
+use nom::multi::many0;
+use nom::combinator::{map, cut, not, verify};
+
+use crate::grammar::{tag, wsp};
+
+use crate::properties::{ICalendarProperty, UIDProperty};
+use crate::properties::event::PassiveProperty;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+// The properties permitted within the PARTICIPANT component, in addition to the passive
+// properties (which include PARTICIPANT-TYPE, CALENDAR-ADDRESS, and STRUCTURED-DATA).
+//
+// Nested components (i.e. VLOCATION and VRESOURCE) are not supported.
+fn participant_property(input: ParserInput) -> ParserResult<ContentLine> {
+    preceded(
+        not(tag("BEGIN")),
+        alt((
+            map(UIDProperty::parse_ical, |property| property.to_content_line()),
+            map(PassiveProperty::parse_ical, |property| property.to_content_line()),
+            ContentLine::parse_ical_for_property("LAST-MODIFIED"),
+            ContentLine::parse_ical_for_property("GEO"),
+            ContentLine::parse_ical_for_property("CATEGORIES"),
+            ContentLine::parse_ical_for_property("RELATED-TO"),
+            ContentLine::parse_ical_for_property("RESOURCES"),
+        )),
+    )(input)
+}
+
+// Participant (RFC 9073)
+//
+// Component Name:  PARTICIPANT
+//
+// Purpose:  This component provides information about a participant in
+//    an event or optionally a plain text typed value.
+//
+// Conformance:  This component can be specified multiple times in
+//    "VEVENT", "VTODO", "VJOURNAL", "VFREEBUSY", and "VAVAILABILITY"
+//    calendar components.
+//
+// Description:  This component provides information about a participant
+//    in a calendar component.  A participant may be an attendee in a
+//    scheduling sense and the ATTENDEE property may be specified in
+//    addition.  Participants can be individuals or organizations, for
+//    example, a soccer team, the spectators, or the musicians.
+//
+//    The "PARTICIPANT-TYPE" property MUST be specified and identifies the
+//    role of the participant in the containing component.
+//
+// Format Definition:  This component is defined by the following
+//    notation:
+//
+//     participantc  = "BEGIN" ":" "PARTICIPANT" CRLF
+//                     partprop *locationc *resourcec
+//                     "END" ":" "PARTICIPANT" CRLF
+//
+//     partprop      = ; The elements herein may appear in any order,
+//                     ; and the order is not significant.
+//
+//                     uid
+//                     participanttype
+//
+//                     [calendaraddress] [created] [description]
+//                     [dtstamp] [geo] [last-mod] [priority] [seq]
+//                     [status] [summary] [url]
+//
+//                     *attach *categories *comment
+//                     *contact *location *rstatus *related
+//                     *resources *strucloc *strucres *styleddescription
+//                     *sdataprop *iana-prop
+//
+// Example:  The following is an example of this component:
+//
+//     BEGIN:PARTICIPANT
+//     PARTICIPANT-TYPE:SPEAKER
+//     UID:5EE5F1C6-3BCB-45E7-A2C3-C12C8D0C2E97
+//     CALENDAR-ADDRESS:mailto:speaker@example.com
+//     STRUCTURED-DATA;FMTTYPE=application/ld+json;VALUE=URI:
+//      https://example.com/speakers/jane-doe.jsonld
+//     END:PARTICIPANT
+//
+// As with events themselves, the component is flattened into the (whitespace separated) sequence
+// of it's content lines, and so is rendered as a single content line spanning them all (with the
+// order of the properties within retained).
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ParticipantComponent {
+    pub properties: Vec<ContentLine>,
+}
+
+impl ParticipantComponent {
+    pub fn get_uid(&self) -> Option<&String> {
+        self.get_property_values("UID").next()
+    }
+
+    pub fn get_participant_type(&self) -> Option<&String> {
+        self.get_property_values("PARTICIPANT-TYPE").next()
+    }
+
+    fn get_property_values<'a>(&'a self, property_name: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        self.properties
+            .iter()
+            .filter(move |content_line| content_line.0 == property_name)
+            .map(|content_line| &content_line.2)
+    }
+
+    /// Whether the component has exactly one of each of the required UID and PARTICIPANT-TYPE
+    /// properties.
+    pub fn is_valid(&self) -> bool {
+        self.get_property_values("UID").count() == 1 && self.get_property_values("PARTICIPANT-TYPE").count() == 1
+    }
+
+    /// The content lines of the component, bounded by it's BEGIN and END content lines (as
+    /// rendered within an iCalendar document).
+    pub fn to_content_lines(&self) -> Vec<ContentLine> {
+        let mut content_lines = Vec::with_capacity(self.properties.len() + 2);
+
+        content_lines.push(ContentLine::from(("BEGIN", (ContentLineParams::default(), String::from("PARTICIPANT")))));
+        content_lines.extend(self.properties.iter().cloned());
+        content_lines.push(ContentLine::from(("END", (ContentLineParams::default(), String::from("PARTICIPANT")))));
+
+        content_lines
+    }
+}
+
+impl ICalendarEntity for ParticipantComponent {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "PARTICIPANT",
+            preceded(
+                tag("BEGIN:PARTICIPANT"),
+                cut(
+                    map_err_message!(
+                        verify(
+                            map(
+                                terminated(
+                                    many0(preceded(wsp, participant_property)),
+                                    preceded(wsp, tag("END:PARTICIPANT")),
+                                ),
+                                |properties| ParticipantComponent { properties },
+                            ),
+                            |participant: &ParticipantComponent| participant.is_valid(),
+                        ),
+                        "expected PARTICIPANT component with a single UID and PARTICIPANT-TYPE property, terminated by END:PARTICIPANT",
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for ParticipantComponent {
+    /// Build a `ContentLine` instance (spanning all the content lines of the component) with
+    /// consideration to the optionally provided `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        let value =
+            self.to_content_lines()
+                .iter()
+                .skip(1)
+                .map(|content_line| content_line.render_ical())
+                .join(" ");
+
+        ContentLine::from((
+            "BEGIN",
+            (
+                ContentLineParams::default(),
+                format!("PARTICIPANT {value}"),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for ParticipantComponent {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(ParticipantComponent);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    use crate::tests::assert_parser_output;
+
+    use crate::ParserContext;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            ParticipantComponent::parse_ical(
+                ParserInput::new_extra(
+                    "BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:5EE5F1C6 DESCRIPTION:Keynote speaker STRUCTURED-DATA;FMTTYPE=application/ld+json;VALUE=URI:https://example.com/speakers/jane-doe.jsonld END:PARTICIPANT SUMMARY:Conference",
+                    ParserContext::Event,
+                )
+            ),
+            (
+                " SUMMARY:Conference",
+                ParticipantComponent {
+                    properties: vec![
+                        ContentLine::from(("PARTICIPANT-TYPE", vec![], "SPEAKER")),
+                        ContentLine::from(("UID", vec![], "5EE5F1C6")),
+                        ContentLine::from(("DESCRIPTION", vec![], "Keynote speaker")),
+                        ContentLine::from(("STRUCTURED-DATA", vec![("FMTTYPE", "application/ld+json"), ("VALUE", "URI")], "https://example.com/speakers/jane-doe.jsonld")),
+                    ],
+                },
+            ),
+        );
+
+        // Missing the required UID and PARTICIPANT-TYPE properties.
+        assert!(ParticipantComponent::parse_ical("BEGIN:PARTICIPANT UID:5EE5F1C6 END:PARTICIPANT".into()).is_err());
+        assert!(ParticipantComponent::parse_ical("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER END:PARTICIPANT".into()).is_err());
+        assert!(ParticipantComponent::parse_ical("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:ONE UID:TWO END:PARTICIPANT".into()).is_err());
+
+        // Unterminated, and nested components.
+        assert!(ParticipantComponent::parse_ical("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:5EE5F1C6".into()).is_err());
+        assert!(ParticipantComponent::parse_ical("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:5EE5F1C6 BEGIN:VLOCATION UID:LOCATION END:VLOCATION END:PARTICIPANT".into()).is_err());
+
+        // Invalid properties within.
+        assert!(ParticipantComponent::parse_ical("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:5EE5F1C6 STRUCTURED-DATA;VALUE=URI:jane-doe END:PARTICIPANT".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        let participant = ParticipantComponent {
+            properties: vec![
+                ContentLine::from(("PARTICIPANT-TYPE", vec![], "SPEAKER")),
+                ContentLine::from(("UID", vec![], "5EE5F1C6")),
+                ContentLine::from(("CALENDAR-ADDRESS", vec![], "mailto:speaker@example.com")),
+            ],
+        };
+
+        assert_eq!(
+            participant.render_ical(),
+            String::from("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:5EE5F1C6 CALENDAR-ADDRESS:mailto:speaker@example.com END:PARTICIPANT"),
+        );
+
+        assert_eq!(
+            participant
+                .to_content_lines()
+                .iter()
+                .map(|content_line| content_line.render_ical())
+                .collect::<Vec<String>>(),
+            vec![
+                String::from("BEGIN:PARTICIPANT"),
+                String::from("PARTICIPANT-TYPE:SPEAKER"),
+                String::from("UID:5EE5F1C6"),
+                String::from("CALENDAR-ADDRESS:mailto:speaker@example.com"),
+                String::from("END:PARTICIPANT"),
+            ],
+        );
+
+        assert_eq!(
+            ParticipantComponent::from_str(participant.render_ical().as_str()),
+            Ok(participant.clone()),
+        );
+
+        assert_eq!(participant.get_uid(), Some(&String::from("5EE5F1C6")));
+        assert_eq!(participant.get_participant_type(), Some(&String::from("SPEAKER")));
+    }
+}
@@ -4,7 +4,8 @@ use nom::combinator::{map, all_consuming};
 use crate::content_line::{ContentLine, ContentLineParams};
 
 use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, ParserContext};
-use crate::properties::ICalendarProperty;
+use crate::properties::{ICalendarProperty, ColorProperty, ImageProperty, ConferenceProperty, StructuredDataProperty};
+use crate::properties::event::{ParticipantComponent, AlarmComponent};
 
 #[derive(Debug, Eq, PartialEq, Clone, Ord, PartialOrd)]
 pub enum PassiveProperty {
@@ -60,12 +61,91 @@ pub enum PassiveProperty {
     Description(ContentLineParams, String),
     Summary(ContentLineParams, String),
     Location(ContentLineParams, String),
+    Participant(ContentLineParams, String),
+    Alarm(ContentLineParams, String),
     X(ContentLine),
 }
 
 impl PassiveProperty {
     pub fn get_property_name(&self) -> String {
-        self.to_content_line().0
+        // Components are named after the component, rather than the BEGIN content line they are
+        // rendered as.
+        match self {
+            PassiveProperty::Participant(_params, _value) => String::from("PARTICIPANT"),
+            PassiveProperty::Alarm(_params, _value)       => String::from("VALARM"),
+            _                                             => self.get_name_params_value().0.to_owned(),
+        }
+    }
+
+    pub fn get_params(&self) -> &ContentLineParams {
+        self.get_name_params_value().1
+    }
+
+    pub fn get_value(&self) -> &String {
+        self.get_name_params_value().2
+    }
+
+    /// The (name, params, value) of the property exactly as parsed, which (in that order) re-render
+    /// the original content line.
+    pub fn get_name_params_value(&self) -> (&str, &ContentLineParams, &String) {
+        match self {
+            PassiveProperty::Calscale(params, value)          => ("CALSCALE", params, value),
+            PassiveProperty::Method(params, value)            => ("METHOD", params, value),
+            PassiveProperty::Prodid(params, value)            => ("PRODID", params, value),
+            PassiveProperty::Version(params, value)           => ("VERSION", params, value),
+            PassiveProperty::Attach(params, value)            => ("ATTACH", params, value),
+            PassiveProperty::Comment(params, value)           => ("COMMENT", params, value),
+            PassiveProperty::PercentComplete(params, value)   => ("PERCENT-COMPLETE", params, value),
+            PassiveProperty::Priority(params, value)          => ("PRIORITY", params, value),
+            PassiveProperty::Status(params, value)            => ("STATUS", params, value),
+            PassiveProperty::Completed(params, value)         => ("COMPLETED", params, value),
+            PassiveProperty::Due(params, value)               => ("DUE", params, value),
+            PassiveProperty::Freebusy(params, value)          => ("FREEBUSY", params, value),
+            PassiveProperty::Transp(params, value)            => ("TRANSP", params, value),
+            PassiveProperty::Tzid(params, value)              => ("TZID", params, value),
+            PassiveProperty::Tzname(params, value)            => ("TZNAME", params, value),
+            PassiveProperty::Tzoffsetfrom(params, value)      => ("TZOFFSETFROM", params, value),
+            PassiveProperty::Tzoffsetto(params, value)        => ("TZOFFSETTO", params, value),
+            PassiveProperty::Tzurl(params, value)             => ("TZURL", params, value),
+            PassiveProperty::Attendee(params, value)          => ("ATTENDEE", params, value),
+            PassiveProperty::Contact(params, value)           => ("CONTACT", params, value),
+            PassiveProperty::Organizer(params, value)         => ("ORGANIZER", params, value),
+            PassiveProperty::Url(params, value)               => ("URL", params, value),
+            PassiveProperty::Action(params, value)            => ("ACTION", params, value),
+            PassiveProperty::Repeat(params, value)            => ("REPEAT", params, value),
+            PassiveProperty::Trigger(params, value)           => ("TRIGGER", params, value),
+            PassiveProperty::Created(params, value)           => ("CREATED", params, value),
+            PassiveProperty::Dtstamp(params, value)           => ("DTSTAMP", params, value),
+            PassiveProperty::Sequence(params, value)          => ("SEQUENCE", params, value),
+            PassiveProperty::RequestStatus(params, value)     => ("REQUEST-STATUS", params, value),
+            PassiveProperty::Xml(params, value)               => ("XML", params, value),
+            PassiveProperty::Tzuntil(params, value)           => ("TZUNTIL", params, value),
+            PassiveProperty::TzidAliasOf(params, value)       => ("TZID-ALIAS-OF", params, value),
+            PassiveProperty::Busytype(params, value)          => ("BUSYTYPE", params, value),
+            PassiveProperty::Name(params, value)              => ("NAME", params, value),
+            PassiveProperty::RefreshInterval(params, value)   => ("REFRESH-INTERVAL", params, value),
+            PassiveProperty::Source(params, value)            => ("SOURCE", params, value),
+            PassiveProperty::Color(params, value)             => ("COLOR", params, value),
+            PassiveProperty::Image(params, value)             => ("IMAGE", params, value),
+            PassiveProperty::Conference(params, value)        => ("CONFERENCE", params, value),
+            PassiveProperty::CalendarAddress(params, value)   => ("CALENDAR-ADDRESS", params, value),
+            PassiveProperty::ParticipantType(params, value)   => ("PARTICIPANT-TYPE", params, value),
+            PassiveProperty::ResourceType(params, value)      => ("RESOURCE-TYPE", params, value),
+            PassiveProperty::StructuredData(params, value)    => ("STRUCTURED-DATA", params, value),
+            PassiveProperty::StyledDescription(params, value) => ("STYLED-DESCRIPTION", params, value),
+            PassiveProperty::Acknowledged(params, value)      => ("ACKNOWLEDGED", params, value),
+            PassiveProperty::Proximity(params, value)         => ("PROXIMITY", params, value),
+            PassiveProperty::Concept(params, value)           => ("CONCEPT", params, value),
+            PassiveProperty::Link(params, value)              => ("LINK", params, value),
+            PassiveProperty::Refid(params, value)             => ("REFID", params, value),
+            PassiveProperty::Description(params, value)       => ("DESCRIPTION", params, value),
+            PassiveProperty::Summary(params, value)           => ("SUMMARY", params, value),
+            PassiveProperty::Location(params, value)          => ("LOCATION", params, value),
+            PassiveProperty::Participant(params, value)       => ("BEGIN", params, value),
+            PassiveProperty::Alarm(params, value)             => ("BEGIN", params, value),
+
+            PassiveProperty::X(ContentLine(name, params, value)) => (name.as_str(), params, value),
+        }
     }
 }
 
@@ -80,6 +160,21 @@ impl ICalendarEntity for PassiveProperty {
             }
         }
 
+        // Typed (RFC 7986 and RFC 9073) properties and components are parsed (and so validated) as
+        // such, but retained as the (normalized) content line they render.
+        macro_rules! define_typed_property_parser {
+            ($enum_key:ident, $property:ident) => {
+                map(
+                    $property::parse_ical,
+                    |property| {
+                        let ContentLine(_name, params, value) = property.to_content_line();
+
+                        PassiveProperty::$enum_key(params, value)
+                    }
+                )
+            }
+        }
+
         alt((
             alt((
                 define_property_parser!(Calscale, "CALSCALE"),
@@ -122,13 +217,13 @@ impl ICalendarEntity for PassiveProperty {
             )),
             alt((
                 define_property_parser!(Source, "SOURCE"),
-                define_property_parser!(Color, "COLOR"),
-                define_property_parser!(Image, "IMAGE"),
-                define_property_parser!(Conference, "CONFERENCE"),
+                define_typed_property_parser!(Color, ColorProperty),
+                define_typed_property_parser!(Image, ImageProperty),
+                define_typed_property_parser!(Conference, ConferenceProperty),
                 define_property_parser!(CalendarAddress, "CALENDAR-ADDRESS"),
                 define_property_parser!(ParticipantType, "PARTICIPANT-TYPE"),
                 define_property_parser!(ResourceType, "RESOURCE-TYPE"),
-                define_property_parser!(StructuredData, "STRUCTURED-DATA"),
+                define_typed_property_parser!(StructuredData, StructuredDataProperty),
                 define_property_parser!(StyledDescription, "STYLED-DESCRIPTION"),
                 define_property_parser!(Acknowledged, "ACKNOWLEDGED"),
                 define_property_parser!(Proximity, "PROXIMITY"),
@@ -138,6 +233,8 @@ impl ICalendarEntity for PassiveProperty {
                 define_property_parser!(Description, "DESCRIPTION"),
                 define_property_parser!(Summary, "SUMMARY"),
                 define_property_parser!(Location, "LOCATION"),
+                define_typed_property_parser!(Participant, ParticipantComponent),
+                define_typed_property_parser!(Alarm, AlarmComponent),
             )),
             map(ContentLine::parse_ical_for_x_property(), Self::X),
         ))(input)
@@ -158,62 +255,9 @@ impl ICalendarProperty for PassiveProperty {
     /// Build a `ContentLine` instance with consideration to the optionally provided
     /// `RenderingContext`.
     fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
-        match self.to_owned() {
-            PassiveProperty::Calscale(params, value)          => ContentLine::from(("CALSCALE", (params, value))),
-            PassiveProperty::Method(params, value)            => ContentLine::from(("METHOD", (params, value))),
-            PassiveProperty::Prodid(params, value)            => ContentLine::from(("PRODID", (params, value))),
-            PassiveProperty::Version(params, value)           => ContentLine::from(("VERSION", (params, value))),
-            PassiveProperty::Attach(params, value)            => ContentLine::from(("ATTACH", (params, value))),
-            PassiveProperty::Comment(params, value)           => ContentLine::from(("COMMENT", (params, value))),
-            PassiveProperty::PercentComplete(params, value)   => ContentLine::from(("PERCENT-COMPLETE", (params, value))),
-            PassiveProperty::Priority(params, value)          => ContentLine::from(("PRIORITY", (params, value))),
-            PassiveProperty::Status(params, value)            => ContentLine::from(("STATUS", (params, value))),
-            PassiveProperty::Completed(params, value)         => ContentLine::from(("COMPLETED", (params, value))),
-            PassiveProperty::Due(params, value)               => ContentLine::from(("DUE", (params, value))),
-            PassiveProperty::Freebusy(params, value)          => ContentLine::from(("FREEBUSY", (params, value))),
-            PassiveProperty::Transp(params, value)            => ContentLine::from(("TRANSP", (params, value))),
-            PassiveProperty::Tzid(params, value)              => ContentLine::from(("TZID", (params, value))),
-            PassiveProperty::Tzname(params, value)            => ContentLine::from(("TZNAME", (params, value))),
-            PassiveProperty::Tzoffsetfrom(params, value)      => ContentLine::from(("TZOFFSETFROM", (params, value))),
-            PassiveProperty::Tzoffsetto(params, value)        => ContentLine::from(("TZOFFSETTO", (params, value))),
-            PassiveProperty::Tzurl(params, value)             => ContentLine::from(("TZURL", (params, value))),
-            PassiveProperty::Attendee(params, value)          => ContentLine::from(("ATTENDEE", (params, value))),
-            PassiveProperty::Contact(params, value)           => ContentLine::from(("CONTACT", (params, value))),
-            PassiveProperty::Organizer(params, value)         => ContentLine::from(("ORGANIZER", (params, value))),
-            PassiveProperty::Url(params, value)               => ContentLine::from(("URL", (params, value))),
-            PassiveProperty::Action(params, value)            => ContentLine::from(("ACTION", (params, value))),
-            PassiveProperty::Repeat(params, value)            => ContentLine::from(("REPEAT", (params, value))),
-            PassiveProperty::Trigger(params, value)           => ContentLine::from(("TRIGGER", (params, value))),
-            PassiveProperty::Created(params, value)           => ContentLine::from(("CREATED", (params, value))),
-            PassiveProperty::Dtstamp(params, value)           => ContentLine::from(("DTSTAMP", (params, value))),
-            PassiveProperty::Sequence(params, value)          => ContentLine::from(("SEQUENCE", (params, value))),
-            PassiveProperty::RequestStatus(params, value)     => ContentLine::from(("REQUEST-STATUS", (params, value))),
-            PassiveProperty::Xml(params, value)               => ContentLine::from(("XML", (params, value))),
-            PassiveProperty::Tzuntil(params, value)           => ContentLine::from(("TZUNTIL", (params, value))),
-            PassiveProperty::TzidAliasOf(params, value)       => ContentLine::from(("TZID-ALIAS-OF", (params, value))),
-            PassiveProperty::Busytype(params, value)          => ContentLine::from(("BUSYTYPE", (params, value))),
-            PassiveProperty::Name(params, value)              => ContentLine::from(("NAME", (params, value))),
-            PassiveProperty::RefreshInterval(params, value)   => ContentLine::from(("REFRESH-INTERVAL", (params, value))),
-            PassiveProperty::Source(params, value)            => ContentLine::from(("SOURCE", (params, value))),
-            PassiveProperty::Color(params, value)             => ContentLine::from(("COLOR", (params, value))),
-            PassiveProperty::Image(params, value)             => ContentLine::from(("IMAGE", (params, value))),
-            PassiveProperty::Conference(params, value)        => ContentLine::from(("CONFERENCE", (params, value))),
-            PassiveProperty::CalendarAddress(params, value)   => ContentLine::from(("CALENDAR-ADDRESS", (params, value))),
-            PassiveProperty::ParticipantType(params, value)   => ContentLine::from(("PARTICIPANT-TYPE", (params, value))),
-            PassiveProperty::ResourceType(params, value)      => ContentLine::from(("RESOURCE-TYPE", (params, value))),
-            PassiveProperty::StructuredData(params, value)    => ContentLine::from(("STRUCTURED-DATA", (params, value))),
-            PassiveProperty::StyledDescription(params, value) => ContentLine::from(("STYLED-DESCRIPTION", (params, value))),
-            PassiveProperty::Acknowledged(params, value)      => ContentLine::from(("ACKNOWLEDGED", (params, value))),
-            PassiveProperty::Proximity(params, value)         => ContentLine::from(("PROXIMITY", (params, value))),
-            PassiveProperty::Concept(params, value)           => ContentLine::from(("CONCEPT", (params, value))),
-            PassiveProperty::Link(params, value)              => ContentLine::from(("LINK", (params, value))),
-            PassiveProperty::Refid(params, value)             => ContentLine::from(("REFID", (params, value))),
-            PassiveProperty::Description(params, value)       => ContentLine::from(("DESCRIPTION", (params, value))),
-            PassiveProperty::Summary(params, value)           => ContentLine::from(("SUMMARY", (params, value))),
-            PassiveProperty::Location(params, value)          => ContentLine::from(("LOCATION", (params, value))),
-
-            PassiveProperty::X(content_line) => content_line,
-        }
+        let (name, params, value) = self.get_name_params_value();
+
+        ContentLine(name.to_owned(), params.to_owned(), value.to_owned())
     }
 }
 
@@ -253,6 +297,8 @@ impl std::fmt::Display for PassiveProperty {
 mod tests {
     use super::*;
 
+    use std::str::FromStr;
+
     use crate::tests::assert_parser_output;
 
     #[test]
@@ -305,13 +351,9 @@ mod tests {
         assert_passive_property_parse_ical!(Name, "NAME");
         assert_passive_property_parse_ical!(RefreshInterval, "REFRESH-INTERVAL");
         assert_passive_property_parse_ical!(Source, "SOURCE");
-        assert_passive_property_parse_ical!(Color, "COLOR");
-        assert_passive_property_parse_ical!(Image, "IMAGE");
-        assert_passive_property_parse_ical!(Conference, "CONFERENCE");
         assert_passive_property_parse_ical!(CalendarAddress, "CALENDAR-ADDRESS");
         assert_passive_property_parse_ical!(ParticipantType, "PARTICIPANT-TYPE");
         assert_passive_property_parse_ical!(ResourceType, "RESOURCE-TYPE");
-        assert_passive_property_parse_ical!(StructuredData, "STRUCTURED-DATA");
         assert_passive_property_parse_ical!(StyledDescription, "STYLED-DESCRIPTION");
         assert_passive_property_parse_ical!(Acknowledged, "ACKNOWLEDGED");
         assert_passive_property_parse_ical!(Proximity, "PROXIMITY");
@@ -322,6 +364,78 @@ mod tests {
         assert_passive_property_parse_ical!(Summary, "SUMMARY");
         assert_passive_property_parse_ical!(Location, "LOCATION");
 
+        // Typed (RFC 7986 and RFC 9073) properties have their params normalized, and values validated.
+        assert_parser_output!(
+            PassiveProperty::parse_ical("COLOR;X-KEY=X-VALUE;KEY=VALUE:VALUE".into()),
+            (
+                "",
+                PassiveProperty::Color(ContentLineParams::from(vec![("KEY", "VALUE"), ("X-KEY", "X-VALUE")]), String::from("VALUE"))
+            )
+        );
+
+        assert_parser_output!(
+            PassiveProperty::parse_ical("IMAGE;FMTTYPE=image/png;X-KEY=X-VALUE;DISPLAY=BADGE,THUMBNAIL;VALUE=URI:https://example.com/images/party.png".into()),
+            (
+                "",
+                PassiveProperty::Image(
+                    ContentLineParams::from(vec![("X-KEY", "X-VALUE"), ("DISPLAY", "BADGE,THUMBNAIL"), ("FMTTYPE", "image/png"), ("VALUE", "URI")]),
+                    String::from("https://example.com/images/party.png"),
+                )
+            )
+        );
+
+        assert_parser_output!(
+            PassiveProperty::parse_ical("CONFERENCE;LABEL=Chat room;FEATURE=CHAT:xmpp:chat-123@conference.example.com".into()),
+            (
+                "",
+                PassiveProperty::Conference(
+                    ContentLineParams::from(vec![("FEATURE", "CHAT"), ("LABEL", "Chat room"), ("VALUE", "URI")]),
+                    String::from("xmpp:chat-123@conference.example.com"),
+                )
+            )
+        );
+
+        assert_parser_output!(
+            PassiveProperty::parse_ical("STRUCTURED-DATA;VALUE=URI;FMTTYPE=application/ld+json;X-KEY=X-VALUE:https://example.com/event.jsonld".into()),
+            (
+                "",
+                PassiveProperty::StructuredData(
+                    ContentLineParams::from(vec![("X-KEY", "X-VALUE"), ("FMTTYPE", "application/ld+json"), ("VALUE", "URI")]),
+                    String::from("https://example.com/event.jsonld"),
+                )
+            )
+        );
+
+        // Components are retained as a single content line spanning all of it's content lines.
+        assert_parser_output!(
+            PassiveProperty::parse_ical("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:5EE5F1C6 END:PARTICIPANT".into()),
+            (
+                "",
+                PassiveProperty::Participant(
+                    ContentLineParams::default(),
+                    String::from("PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:5EE5F1C6 END:PARTICIPANT"),
+                )
+            )
+        );
+
+        assert_parser_output!(
+            PassiveProperty::parse_ical("BEGIN:VALARM TRIGGER:-PT15M ACTION:DISPLAY END:VALARM".into()),
+            (
+                "",
+                PassiveProperty::Alarm(
+                    ContentLineParams::default(),
+                    String::from("VALARM TRIGGER:-PT15M ACTION:DISPLAY END:VALARM"),
+                )
+            )
+        );
+
+        assert!(PassiveProperty::parse_ical("COLOR:".into()).is_err());
+        assert!(PassiveProperty::parse_ical("IMAGE:iVBORw0KGgo=".into()).is_err());
+        assert!(PassiveProperty::parse_ical("CONFERENCE:Dial in".into()).is_err());
+        assert!(PassiveProperty::parse_ical("STRUCTURED-DATA;VALUE=BINARY:iVBORw0KGgo=".into()).is_err());
+        assert!(PassiveProperty::parse_ical("BEGIN:PARTICIPANT UID:5EE5F1C6 END:PARTICIPANT".into()).is_err());
+        assert!(PassiveProperty::parse_ical("BEGIN:VALARM ACTION:DISPLAY END:VALARM".into()).is_err());
+
         assert_parser_output!(
             PassiveProperty::parse_ical("X-PROPERTY;X-KEY=X-VALUE;KEY=VALUE:VALUE".into()),
             (
@@ -332,4 +446,54 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn get_name_params_value() {
+        let passive_property = PassiveProperty::from_str("DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\";X-KEY=X-VALUE:The Fall'98 Wild Wizards Conference - - Las Vegas\\, NV\\, USA").unwrap();
+
+        assert_eq!(
+            passive_property.get_name_params_value(),
+            (
+                "DESCRIPTION",
+                &ContentLineParams::from(vec![("ALTREP", "\"cid:part1.0001@example.org\""), ("X-KEY", "X-VALUE")]),
+                &String::from("The Fall'98 Wild Wizards Conference - - Las Vegas\\, NV\\, USA"),
+            )
+        );
+
+        assert_eq!(passive_property.get_property_name(), String::from("DESCRIPTION"));
+        assert_eq!(passive_property.get_value(), "The Fall'98 Wild Wizards Conference - - Las Vegas\\, NV\\, USA");
+
+        let passive_property = PassiveProperty::from_str("X-PROPERTY;KEY=VALUE;X-KEY=X-VALUE:X-PROPERTY value").unwrap();
+
+        assert_eq!(
+            passive_property.get_name_params_value(),
+            (
+                "X-PROPERTY",
+                &ContentLineParams::from(vec![("KEY", "VALUE"), ("X-KEY", "X-VALUE")]),
+                &String::from("X-PROPERTY value"),
+            )
+        );
+
+        let passive_property = PassiveProperty::from_str("BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:5EE5F1C6 END:PARTICIPANT").unwrap();
+
+        assert_eq!(passive_property.get_name_params_value().0, "BEGIN");
+        assert_eq!(passive_property.get_property_name(), String::from("PARTICIPANT"));
+
+        let passive_property = PassiveProperty::from_str("BEGIN:VALARM UID:8297C37D TRIGGER:-PT15M ACTION:DISPLAY END:VALARM").unwrap();
+
+        assert_eq!(passive_property.get_name_params_value().0, "BEGIN");
+        assert_eq!(passive_property.get_property_name(), String::from("VALARM"));
+
+        // Assert the parsed properties are re-rendered exactly as provided (param order and quoting retained).
+        for ical in [
+            "DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\";X-KEY=X-VALUE:The Fall'98 Wild Wizards Conference - - Las Vegas\\, NV\\, USA",
+            "ATTENDEE;RSVP=TRUE;ROLE=REQ-PARTICIPANT:mailto:jsmith@example.com",
+            "X-PROPERTY;X-KEY=X-VALUE;KEY=VALUE:X-PROPERTY value",
+            "SUMMARY:Event summary",
+            "BEGIN:PARTICIPANT PARTICIPANT-TYPE:SPEAKER UID:5EE5F1C6 END:PARTICIPANT",
+            "BEGIN:VALARM UID:8297C37D TRIGGER:-PT15M ACTION:DISPLAY ACKNOWLEDGED:20090604T084500Z END:VALARM",
+        ] {
+            assert_eq!(PassiveProperty::from_str(ical).unwrap().render_ical(), String::from(ical));
+        }
+    }
 }
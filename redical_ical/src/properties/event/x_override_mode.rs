@@ -0,0 +1,102 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon};
+
+use crate::properties::ICalendarProperty;
+use crate::values::override_mode::OverrideModeValue;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Event occurrence override mode property.
+///
+/// Determines whether the properties of an event occurrence override are merged onto those of
+/// the base event (MERGE), or replace them entirely (REPLACE).
+///
+/// Example:
+///
+/// X-OVERRIDE-MODE:REPLACE
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XOverrideModeProperty {
+    pub override_mode: OverrideModeValue,
+}
+
+impl ICalendarEntity for XOverrideModeProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-OVERRIDE-MODE",
+            preceded(
+                tag("X-OVERRIDE-MODE"),
+                cut(
+                    map(
+                        preceded(colon, OverrideModeValue::parse_ical),
+                        |override_mode| {
+                            XOverrideModeProperty { override_mode }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XOverrideModeProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-OVERRIDE-MODE",
+            (
+                ContentLineParams::default(),
+                self.override_mode.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XOverrideModeProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XOverrideModeProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XOverrideModeProperty::parse_ical("X-OVERRIDE-MODE:REPLACE CATEGORIES:SPORT".into()),
+            (
+                " CATEGORIES:SPORT",
+                XOverrideModeProperty {
+                    override_mode: OverrideModeValue::Replace,
+                },
+            ),
+        );
+
+        assert!(XOverrideModeProperty::parse_ical("X-OVERRIDE-MODE:UNION".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XOverrideModeProperty {
+                override_mode: OverrideModeValue::Merge,
+            }.render_ical(),
+            String::from("X-OVERRIDE-MODE:MERGE"),
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use itertools::Itertools;
+
+use nom::error::context;
+use nom::branch::alt;
+use nom::sequence::{pair, preceded};
+use nom::multi::separated_list1;
+use nom::combinator::{recognize, map, cut, opt, verify};
+
+use crate::grammar::{tag, semicolon, colon, comma, x_name, iana_token, param_value};
+
+use crate::values::uri::Uri;
+use crate::values::binary::Binary;
+
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+use std::collections::HashMap;
+
+// Display (RFC 7986)
+//
+// Parameter Name:  DISPLAY
+//
+// Purpose:  To specify different ways in which an image for a calendar
+//    or component can be displayed.
+//
+// Format Definition:  This property parameter is defined by the
+//    following notation:
+//
+//     displayparam = "DISPLAY" "=" displayval *("," displayval)
+//
+//     displayval =  ("BADGE" /     ; image inline with the title of the
+//                                  ; event
+//                    "GRAPHIC" /   ; a full image replacement for the event
+//                                  ; itself
+//                    "FULLSIZE" /  ; an image that is used to enhance the
+//                                  ; event
+//                    "THUMBNAIL" / ; a smaller variant of "FULLSIZE" to be
+//                                  ; used when space for the image is
+//                                  ; constrained
+//                    x-name /      ; Experimental type
+//                    iana-token)   ; Other IANA-registered type
+//                                  ;
+//                                  ; Default is BADGE
+pub fn display_param_value(input: ParserInput) -> ParserResult<ParserInput> {
+    recognize(separated_list1(comma, alt((x_name, iana_token))))(input)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct ImagePropertyParams {
+    pub altrep: Option<String>,
+    pub display: Option<String>,
+    pub encoding: Option<String>,
+    pub fmttype: Option<String>,
+    pub value_type: Option<String>,
+    pub other: HashMap<String, String>,
+}
+
+impl ICalendarEntity for ImagePropertyParams {
+    define_property_params_ical_parser!(
+        ImagePropertyParams,
+        (
+            pair(tag("ALTREP"), cut(preceded(tag("="), param_value))),
+            |params: &mut ImagePropertyParams, (_key, value): (ParserInput, ParserInput)| params.altrep = Some(value.to_string()),
+        ),
+        (
+            pair(tag("DISPLAY"), cut(preceded(tag("="), display_param_value))),
+            |params: &mut ImagePropertyParams, (_key, value): (ParserInput, ParserInput)| params.display = Some(value.to_string()),
+        ),
+        (
+            pair(tag("ENCODING"), cut(preceded(tag("="), alt((tag("8BIT"), tag("BASE64")))))),
+            |params: &mut ImagePropertyParams, (_key, value): (ParserInput, ParserInput)| params.encoding = Some(value.to_string()),
+        ),
+        (
+            pair(tag("FMTTYPE"), cut(preceded(tag("="), param_value))),
+            |params: &mut ImagePropertyParams, (_key, value): (ParserInput, ParserInput)| params.fmttype = Some(value.to_string()),
+        ),
+        (
+            pair(tag("VALUE"), cut(preceded(tag("="), alt((tag("URI"), tag("BINARY")))))),
+            |params: &mut ImagePropertyParams, (_key, value): (ParserInput, ParserInput)| params.value_type = Some(value.to_string()),
+        ),
+        (
+            pair(alt((x_name, iana_token)), cut(preceded(tag("="), recognize(separated_list1(comma, param_value))))),
+            |params: &mut ImagePropertyParams, (key, value): (ParserInput, ParserInput)| params.other.insert(key.to_string(), value.to_string()),
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for ImagePropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        for (key, value) in self.other.clone().into_iter().sorted() {
+            content_line_params.insert(key.to_owned(), value.to_owned());
+        }
+
+        if let Some(altrep) = self.altrep.as_ref() {
+            content_line_params.insert(String::from("ALTREP"), altrep.to_owned());
+        }
+
+        if let Some(display) = self.display.as_ref() {
+            content_line_params.insert(String::from("DISPLAY"), display.to_owned());
+        }
+
+        if let Some(encoding) = self.encoding.as_ref() {
+            content_line_params.insert(String::from("ENCODING"), encoding.to_owned());
+        }
+
+        if let Some(fmttype) = self.fmttype.as_ref() {
+            content_line_params.insert(String::from("FMTTYPE"), fmttype.to_owned());
+        }
+
+        if let Some(value_type) = self.value_type.as_ref() {
+            content_line_params.insert(String::from("VALUE"), value_type.to_owned());
+        }
+
+        content_line_params
+    }
+}
+
+impl From<ImagePropertyParams> for ContentLineParams {
+    fn from(image_params: ImagePropertyParams) -> Self {
+        ContentLineParams::from(&image_params)
+    }
+}
+
+/// The image referenced by URI (the default), or inlined as BASE64 encoded binary data (requiring
+/// `VALUE=BINARY` and `ENCODING=BASE64`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ImageValue {
+    Uri(Uri),
+    Binary(Binary),
+}
+
+impl ImageValue {
+    /// Whether the (URI or BINARY) value agrees with the VALUE and ENCODING params describing it.
+    pub fn is_valid_for_params(&self, params: &ImagePropertyParams) -> bool {
+        match self {
+            ImageValue::Uri(_) => {
+                params.encoding.is_none() && matches!(params.value_type.as_deref(), None | Some("URI"))
+            },
+
+            ImageValue::Binary(_) => {
+                params.encoding.as_deref() == Some("BASE64") && params.value_type.as_deref() == Some("BINARY")
+            },
+        }
+    }
+}
+
+impl ICalendarEntity for ImageValue {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        alt((
+            map(Uri::parse_ical, ImageValue::Uri),
+            map(Binary::parse_ical, ImageValue::Binary),
+        ))(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        match self {
+            ImageValue::Uri(uri) => uri.render_ical_with_context(context),
+            ImageValue::Binary(binary) => binary.render_ical_with_context(context),
+        }
+    }
+}
+
+impl_icalendar_entity_traits!(ImageValue);
+
+// Image (RFC 7986)
+//
+// Property Name:  IMAGE
+//
+// Purpose:  This property specifies an image associated with the
+//    calendar or a calendar component.
+//
+// Value Type:  URI or BINARY -- no default.  The value MUST be data
+//    with a media type of "image" or refer to such data.
+//
+// Property Parameters:  IANA, non-standard, display, inline encoding,
+//    and value data type property parameters can be specified on this
+//    property.  The format type parameter can be specified on this
+//    property and is RECOMMENDED for inline binary encoded content
+//    information.
+//
+// Conformance:  This property can be specified multiple times in an
+//    iCalendar object or in "VEVENT", "VTODO", or "VJOURNAL" calendar
+//    components.
+//
+// Description:  This property specifies an image for an iCalendar
+//    object or a calendar component via a URI or directly with inline
+//    data that can be used by calendar user agents when presenting the
+//    calendar data to a user.  Multiple properties MAY be used to
+//    specify alternative sets of images with, for example, varying
+//    media subtypes, resolutions, or sizes.  When multiple properties
+//    are present, calendar user agents SHOULD display only one of them,
+//    picking one that provides the most appropriate image quality, or
+//    display none.  The "DISPLAY" parameter is used to indicate the
+//    intended display mode for the image.  The "ALTREP" parameter,
+//    defined in [RFC5545], can be used to provide a "clickable" image
+//    where the URI in the parameter value can be "launched" by a click
+//    on the image in the calendar user agent.
+//
+// Format Definition:  This property is defined by the following
+//    notation:
+//
+//     image      = "IMAGE" imageparam
+//                  (
+//                    (
+//                      ";" "VALUE" "=" "URI"
+//                      ":" uri
+//                    ) /
+//                    (
+//                      ";" "ENCODING" "=" "BASE64"
+//                      ";" "VALUE" "=" "BINARY"
+//                      ":" binary
+//                    )
+//                  )
+//                  CRLF
+//
+//     imageparam = *(
+//                  ;
+//                  ; The following is OPTIONAL for a URI value,
+//                  ; RECOMMENDED for a BINARY value,
+//                  ; and MUST NOT occur more than once.
+//                  ;
+//                  (";" fmttypeparam) /
+//                  ;
+//                  ; The following are OPTIONAL,
+//                  ; and MUST NOT occur more than once.
+//                  ;
+//                  (";" altrepparam) / (";" displayparam) /
+//                  ;
+//                  ; The following is OPTIONAL,
+//                  ; and MAY occur more than once.
+//                  ;
+//                  (";" other-param)
+//                  ;
+//                  )
+//
+// Example:  The following is an example of this property:
+//
+//     IMAGE;VALUE=URI;DISPLAY=BADGE;FMTTYPE=image/png:h
+//      ttp://example.com/images/party.png
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ImageProperty {
+    pub params: ImagePropertyParams,
+    pub image: ImageValue,
+}
+
+impl ICalendarEntity for ImageProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "IMAGE",
+            preceded(
+                tag("IMAGE"),
+                cut(
+                    map(
+                        map_err_message!(
+                            verify(
+                                pair(
+                                    map(opt(ImagePropertyParams::parse_ical), Option::unwrap_or_default),
+                                    preceded(colon, ImageValue::parse_ical),
+                                ),
+                                |(params, image): &(ImagePropertyParams, ImageValue)| image.is_valid_for_params(params),
+                            ),
+                            "expected either a URI value, or a BINARY value with VALUE=BINARY and ENCODING=BASE64 params",
+                        ),
+                        |(params, image)| {
+                            ImageProperty {
+                                params,
+                                image,
+                            }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for ImageProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "IMAGE",
+            (
+                ContentLineParams::from(&self.params),
+                self.image.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for ImageProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(ImageProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            ImageProperty::parse_ical("IMAGE:https://example.com/images/party.png SUMMARY:Party".into()),
+            (
+                " SUMMARY:Party",
+                ImageProperty {
+                    params: ImagePropertyParams::default(),
+                    image: ImageValue::Uri(Uri(String::from("https://example.com/images/party.png"))),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            ImageProperty::parse_ical("IMAGE;VALUE=URI;DISPLAY=BADGE,THUMBNAIL;FMTTYPE=image/png;X-TEST=X_VALUE:https://example.com/images/party.png".into()),
+            (
+                "",
+                ImageProperty {
+                    params: ImagePropertyParams {
+                        altrep: None,
+                        display: Some(String::from("BADGE,THUMBNAIL")),
+                        encoding: None,
+                        fmttype: Some(String::from("image/png")),
+                        value_type: Some(String::from("URI")),
+                        other: HashMap::from([
+                            (String::from("X-TEST"), String::from("X_VALUE")),
+                        ]),
+                    },
+                    image: ImageValue::Uri(Uri(String::from("https://example.com/images/party.png"))),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            ImageProperty::parse_ical("IMAGE;ENCODING=BASE64;VALUE=BINARY;FMTTYPE=image/png:iVBORw0KGgo=".into()),
+            (
+                "",
+                ImageProperty {
+                    params: ImagePropertyParams {
+                        altrep: None,
+                        display: None,
+                        encoding: Some(String::from("BASE64")),
+                        fmttype: Some(String::from("image/png")),
+                        value_type: Some(String::from("BINARY")),
+                        other: HashMap::new(),
+                    },
+                    image: ImageValue::Binary(Binary(String::from("iVBORw0KGgo="))),
+                },
+            ),
+        );
+
+        // Binary values without the VALUE=BINARY and ENCODING=BASE64 params (and vice versa).
+        assert!(ImageProperty::parse_ical("IMAGE:iVBORw0KGgo=".into()).is_err());
+        assert!(ImageProperty::parse_ical("IMAGE;ENCODING=BASE64:iVBORw0KGgo=".into()).is_err());
+        assert!(ImageProperty::parse_ical("IMAGE;ENCODING=BASE64;VALUE=BINARY:https://example.com/images/party.png".into()).is_err());
+        assert!(ImageProperty::parse_ical("IMAGE;VALUE=URI:iVBORw0KGgo=".into()).is_err());
+
+        assert!(ImageProperty::parse_ical("IMAGE;DISPLAY=:https://example.com/images/party.png".into()).is_err());
+        assert!(ImageProperty::parse_ical("IMAGE;VALUE=TEXT:https://example.com/images/party.png".into()).is_err());
+        assert!(ImageProperty::parse_ical("IMAGE:".into()).is_err());
+        assert!(ImageProperty::parse_ical(":".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            ImageProperty {
+                params: ImagePropertyParams::default(),
+                image: ImageValue::Uri(Uri(String::from("https://example.com/images/party.png"))),
+            }.render_ical(),
+            String::from("IMAGE:https://example.com/images/party.png"),
+        );
+
+        assert_eq!(
+            ImageProperty {
+                params: ImagePropertyParams {
+                    altrep: Some(String::from("\"https://example.com/party\"")),
+                    display: Some(String::from("BADGE,THUMBNAIL")),
+                    encoding: Some(String::from("BASE64")),
+                    fmttype: Some(String::from("image/png")),
+                    value_type: Some(String::from("BINARY")),
+                    other: HashMap::from([
+                        (String::from("X-TEST"), String::from("X_VALUE")),
+                    ]),
+                },
+                image: ImageValue::Binary(Binary(String::from("iVBORw0KGgo="))),
+            }.render_ical(),
+            String::from("IMAGE;X-TEST=X_VALUE;ALTREP=\"https://example.com/party\";DISPLAY=BADGE,THUMBNAIL;ENCODING=BASE64;FMTTYPE=image/png;VALUE=BINARY:iVBORw0KGgo="),
+        );
+    }
+}
@@ -105,8 +105,10 @@ impl LastModifiedProperty {
     }
 
     pub fn new_from_now(set_millis: bool) -> Self {
-        let current_date_time = chrono::offset::Utc::now();
+        Self::new_from_utc_date_time(chrono::offset::Utc::now(), set_millis)
+    }
 
+    pub fn new_from_utc_date_time(current_date_time: chrono::DateTime<chrono::Utc>, set_millis: bool) -> Self {
         let millis = current_date_time.timestamp_subsec_millis() as i64;
 
         let date_time = DateTime::UtcDateTime(current_date_time.naive_utc());
@@ -469,4 +471,19 @@ mod tests {
 
         assert_eq!(0, last_modified_property.get_millis());
     }
+
+    #[test]
+    fn new_from_utc_date_time() {
+        let utc_date_time = chrono::DateTime::from_timestamp_millis(1609430400250).unwrap();
+
+        assert_eq!(
+            LastModifiedProperty::new_from_utc_date_time(utc_date_time, true).render_ical(),
+            String::from("LAST-MODIFIED;X-MILLIS=250:20201231T160000Z"),
+        );
+
+        assert_eq!(
+            LastModifiedProperty::new_from_utc_date_time(utc_date_time, false).render_ical(),
+            String::from("LAST-MODIFIED:20201231T160000Z"),
+        );
+    }
 }
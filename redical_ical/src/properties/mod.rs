@@ -1,6 +1,20 @@
 mod uid;
 mod recurrence_id;
 mod last_modified;
+mod x_default_tzid;
+mod x_default_dst_policy;
+mod x_term_normalization;
+mod x_indexed_properties;
+mod x_required_properties;
+mod x_allowed_properties;
+mod x_prune_older_than;
+mod name;
+mod color;
+mod refresh_interval;
+mod source;
+mod image;
+mod conference;
+mod structured_data;
 
 pub mod event;
 pub mod calendar;
@@ -9,6 +23,20 @@ pub mod query;
 pub use uid::*;
 pub use recurrence_id::*;
 pub use last_modified::*;
+pub use x_default_tzid::*;
+pub use x_default_dst_policy::*;
+pub use x_term_normalization::*;
+pub use x_indexed_properties::*;
+pub use x_required_properties::*;
+pub use x_allowed_properties::*;
+pub use x_prune_older_than::*;
+pub use name::*;
+pub use color::*;
+pub use refresh_interval::*;
+pub use source::*;
+pub use image::*;
+pub use conference::*;
+pub use structured_data::*;
 
 pub use event::*;
 pub use calendar::*;
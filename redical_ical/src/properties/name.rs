@@ -0,0 +1,236 @@
+use itertools::Itertools;
+
+use nom::error::context;
+use nom::branch::alt;
+use nom::sequence::{pair, preceded};
+use nom::multi::separated_list1;
+use nom::combinator::{recognize, map, cut, opt};
+
+use crate::grammar::{tag, semicolon, colon, comma, x_name, iana_token, param_value};
+
+use crate::values::text::Text;
+
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct NamePropertyParams {
+    pub altrep: Option<String>,
+    pub language: Option<String>,
+    pub other: HashMap<String, String>,
+}
+
+impl ICalendarEntity for NamePropertyParams {
+    define_property_params_ical_parser!(
+        NamePropertyParams,
+        (
+            pair(tag("ALTREP"), cut(preceded(tag("="), param_value))),
+            |params: &mut NamePropertyParams, (_key, value): (ParserInput, ParserInput)| params.altrep = Some(value.to_string()),
+        ),
+        (
+            pair(tag("LANGUAGE"), cut(preceded(tag("="), param_value))),
+            |params: &mut NamePropertyParams, (_key, value): (ParserInput, ParserInput)| params.language = Some(value.to_string()),
+        ),
+        (
+            pair(alt((x_name, iana_token)), cut(preceded(tag("="), recognize(separated_list1(comma, param_value))))),
+            |params: &mut NamePropertyParams, (key, value): (ParserInput, ParserInput)| params.other.insert(key.to_string(), value.to_string()),
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for NamePropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        for (key, value) in self.other.clone().into_iter().sorted() {
+            content_line_params.insert(key.to_owned(), value.to_owned());
+        }
+
+        if let Some(altrep) = self.altrep.as_ref() {
+            content_line_params.insert(String::from("ALTREP"), altrep.to_owned());
+        }
+
+        if let Some(language) = self.language.as_ref() {
+            content_line_params.insert(String::from("LANGUAGE"), language.to_owned());
+        }
+
+        content_line_params
+    }
+}
+
+impl From<NamePropertyParams> for ContentLineParams {
+    fn from(name_params: NamePropertyParams) -> Self {
+        ContentLineParams::from(&name_params)
+    }
+}
+
+// Name (RFC 7986)
+//
+// Property Name:  NAME
+//
+// Purpose:  This property specifies the name of the calendar.
+//
+// Value Type:  TEXT
+//
+// Property Parameters:  IANA, non-standard, alternate text
+//    representation, and language property parameters can be specified
+//    on this property.
+//
+// Conformance:  This property can be specified multiple times in an
+//    iCalendar object.  However, each property MUST represent the name
+//    of the calendar in a different language.
+//
+// Format Definition:  This property is defined by the following
+//    notation:
+//
+//     name      = "NAME" nameparam ":" text CRLF
+//
+//     nameparam = *(
+//                 ;
+//                 ; The following are OPTIONAL,
+//                 ; but MUST NOT occur more than once.
+//                 ;
+//                 (";" altrepparam) / (";" languageparam) /
+//                 ;
+//                 ; The following is OPTIONAL,
+//                 ; and MAY occur more than once.
+//                 ;
+//                 (";" other-param)
+//                 ;
+//                 )
+//
+// Example:  This is an example of this property:
+//
+//     NAME:Company Vacation Days
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NameProperty {
+    pub params: NamePropertyParams,
+    pub name: Text,
+}
+
+impl ICalendarEntity for NameProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "NAME",
+            preceded(
+                tag("NAME"),
+                cut(
+                    map(
+                        pair(
+                            opt(NamePropertyParams::parse_ical),
+                            preceded(colon, Text::parse_ical),
+                        ),
+                        |(params, name)| {
+                            NameProperty {
+                                params: params.unwrap_or(NamePropertyParams::default()),
+                                name,
+                            }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for NameProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "NAME",
+            (
+                ContentLineParams::from(&self.params),
+                self.name.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for NameProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(NameProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            NameProperty::parse_ical("NAME:Company Vacation Days\\, 2021 COLOR:red".into()),
+            (
+                " COLOR:red",
+                NameProperty {
+                    params: NamePropertyParams::default(),
+                    name: Text(String::from("Company Vacation Days\\, 2021")),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            NameProperty::parse_ical("NAME;X-TEST=X_VALUE;LANGUAGE=fr;ALTREP=\"https://example.com/fr\":Jours de vacances".into()),
+            (
+                "",
+                NameProperty {
+                    params: NamePropertyParams {
+                        altrep: Some(String::from("\"https://example.com/fr\"")),
+                        language: Some(String::from("fr")),
+                        other: HashMap::from([
+                            (String::from("X-TEST"), String::from("X_VALUE")),
+                        ]),
+                    },
+                    name: Text(String::from("Jours de vacances")),
+                },
+            ),
+        );
+
+        assert!(NameProperty::parse_ical(":".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            NameProperty {
+                params: NamePropertyParams::default(),
+                name: Text(String::from("Company Vacation Days")),
+            }.render_ical(),
+            String::from("NAME:Company Vacation Days"),
+        );
+
+        assert_eq!(
+            NameProperty {
+                params: NamePropertyParams {
+                    altrep: Some(String::from("\"https://example.com/fr\"")),
+                    language: Some(String::from("fr")),
+                    other: HashMap::from([
+                        (String::from("X-TEST"), String::from("X_VALUE")),
+                    ]),
+                },
+                name: Text(String::from("Jours de vacances")),
+            }.render_ical(),
+            String::from("NAME;X-TEST=X_VALUE;ALTREP=\"https://example.com/fr\";LANGUAGE=fr:Jours de vacances"),
+        );
+    }
+}
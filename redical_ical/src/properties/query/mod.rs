@@ -8,17 +8,26 @@ use nom::branch::alt;
 
 pub mod x_offset;
 pub mod x_limit;
+pub mod x_limit_per_event;
 pub mod x_distinct;
+pub mod x_group_by;
 pub mod x_from;
 pub mod x_until;
+pub mod x_duration;
 pub mod x_tzid;
+pub mod x_dst_policy;
 pub mod x_order_by;
 pub mod x_categories;
 pub mod x_location_type;
+pub mod x_location;
 pub mod x_related_to;
 pub mod x_geo;
 pub mod x_class;
 pub mod x_uid;
+pub mod x_prop;
+pub mod x_return;
+pub mod x_count;
+pub mod x_merge_intervals;
 pub mod where_properties_group;
 
 
@@ -26,17 +35,26 @@ use crate::grammar::{tag, wsp, wsp_1_1, contentline};
 
 pub use x_offset::XOffsetProperty;
 pub use x_limit::XLimitProperty;
+pub use x_limit_per_event::XLimitPerEventProperty;
 pub use x_distinct::XDistinctProperty;
+pub use x_group_by::XGroupByProperty;
 pub use x_from::{XFromProperty, XFromPropertyParams};
 pub use x_until::{XUntilProperty, XUntilPropertyParams};
+pub use x_duration::{XDurationProperty, XDurationPropertyParams};
 pub use x_tzid::XTzidProperty;
+pub use x_dst_policy::XDstPolicyProperty;
 pub use x_order_by::XOrderByProperty;
 pub use x_categories::{XCategoriesProperty, XCategoriesPropertyParams};
 pub use x_location_type::{XLocationTypeProperty, XLocationTypePropertyParams};
+pub use x_location::{XLocationProperty, XLocationPropertyParams};
 pub use x_related_to::{XRelatedToProperty, XRelatedToPropertyParams};
 pub use x_geo::{DistValue, XGeoProperty, XGeoPropertyParams};
 pub use x_class::{XClassProperty, XClassPropertyParams};
-pub use x_uid::XUIDProperty;
+pub use x_uid::{XUIDProperty, XUIDPropertyParams};
+pub use x_prop::{XPropProperty, XPropPropertyParams};
+pub use x_return::XReturnProperty;
+pub use x_count::XCountProperty;
+pub use x_merge_intervals::XMergeIntervalsProperty;
 pub use where_properties_group::{WherePropertiesGroup, GroupedWhereProperty};
 
 use crate::values::where_operator::WhereOperator;
@@ -46,17 +64,26 @@ use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, Parser
 pub enum QueryProperty {
     XOffset(XOffsetProperty),
     XLimit(XLimitProperty),
+    XLimitPerEvent(XLimitPerEventProperty),
     XDistinct(XDistinctProperty),
+    XGroupBy(XGroupByProperty),
     XFrom(XFromProperty),
     XUntil(XUntilProperty),
+    XDuration(XDurationProperty),
     XTzid(XTzidProperty),
+    XDstPolicy(XDstPolicyProperty),
     XOrderBy(XOrderByProperty),
     XUID(XUIDProperty),
     XCategories(XCategoriesProperty),
     XLocationType(XLocationTypeProperty),
+    XLocation(XLocationProperty),
     XRelatedTo(XRelatedToProperty),
     XGeo(XGeoProperty),
     XClass(XClassProperty),
+    XProp(XPropProperty),
+    XReturn(XReturnProperty),
+    XCount(XCountProperty),
+    XMergeIntervals(XMergeIntervalsProperty),
     WherePropertiesGroup(WherePropertiesGroup),
 }
 
@@ -127,17 +154,30 @@ impl ICalendarEntity for QueryProperty {
     fn parse_ical(input: ParserInput) -> ParserResult<Self> {
         alt((
             map(XOffsetProperty::parse_ical, Self::XOffset),
+            // Attempted before X-LIMIT which would otherwise match the X-LIMIT-PER-EVENT prefix.
+            map(XLimitPerEventProperty::parse_ical, Self::XLimitPerEvent),
             map(XLimitProperty::parse_ical, Self::XLimit),
             map(XDistinctProperty::parse_ical, Self::XDistinct),
+            map(XGroupByProperty::parse_ical, Self::XGroupBy),
             map(XFromProperty::parse_ical, Self::XFrom),
             map(XUntilProperty::parse_ical, Self::XUntil),
+            map(XDurationProperty::parse_ical, Self::XDuration),
             map(XTzidProperty::parse_ical, Self::XTzid),
+            map(XDstPolicyProperty::parse_ical, Self::XDstPolicy),
             map(XOrderByProperty::parse_ical, Self::XOrderBy),
-            map(XCategoriesProperty::parse_ical, Self::XCategories),
-            map(XLocationTypeProperty::parse_ical, Self::XLocationType),
-            map(XRelatedToProperty::parse_ical, Self::XRelatedTo),
-            map(XGeoProperty::parse_ical, Self::XGeo),
-            map(XClassProperty::parse_ical, Self::XClass),
+            alt((
+                map(XCategoriesProperty::parse_ical, Self::XCategories),
+                map(XLocationTypeProperty::parse_ical, Self::XLocationType),
+                map(XLocationProperty::parse_ical, Self::XLocation),
+                map(XRelatedToProperty::parse_ical, Self::XRelatedTo),
+                map(XGeoProperty::parse_ical, Self::XGeo),
+                map(XClassProperty::parse_ical, Self::XClass),
+                map(XUIDProperty::parse_ical, Self::XUID),
+                map(XPropProperty::parse_ical, Self::XProp),
+            )),
+            map(XReturnProperty::parse_ical, Self::XReturn),
+            map(XCountProperty::parse_ical, Self::XCount),
+            map(XMergeIntervalsProperty::parse_ical, Self::XMergeIntervals),
             map(WherePropertiesGroup::parse_ical, Self::WherePropertiesGroup),
         ))(input)
     }
@@ -146,17 +186,26 @@ impl ICalendarEntity for QueryProperty {
         match self {
             Self::XOffset(property) => property.render_ical(),
             Self::XLimit(property) => property.render_ical(),
+            Self::XLimitPerEvent(property) => property.render_ical(),
             Self::XDistinct(property) => property.render_ical(),
+            Self::XGroupBy(property) => property.render_ical(),
             Self::XFrom(property) => property.render_ical(),
             Self::XUntil(property) => property.render_ical(),
+            Self::XDuration(property) => property.render_ical(),
             Self::XTzid(property) => property.render_ical(),
+            Self::XDstPolicy(property) => property.render_ical(),
             Self::XOrderBy(property) => property.render_ical(),
             Self::XCategories(property) => property.render_ical(),
             Self::XUID(property) => property.render_ical(),
             Self::XLocationType(property) => property.render_ical(),
+            Self::XLocation(property) => property.render_ical(),
             Self::XRelatedTo(property) => property.render_ical(),
             Self::XGeo(property) => property.render_ical(),
             Self::XClass(property) => property.render_ical(),
+            Self::XProp(property) => property.render_ical(),
+            Self::XReturn(property) => property.render_ical(),
+            Self::XCount(property) => property.render_ical(),
+            Self::XMergeIntervals(property) => property.render_ical(),
             Self::WherePropertiesGroup(property) => property.render_ical(),
         }
     }
@@ -276,6 +325,14 @@ mod tests {
             ),
         );
 
+        assert_parser_output!(
+            QueryProperty::parse_ical("X-LOCATION:Royal Albert Hall DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                QueryProperty::XLocation(XLocationProperty::from_str("X-LOCATION:Royal Albert Hall").unwrap()),
+            ),
+        );
+
         assert_parser_output!(
             QueryProperty::parse_ical("X-TZID:Europe/London DESCRIPTION:Description text".into()),
             (
@@ -316,6 +373,14 @@ mod tests {
             ),
         );
 
+        assert_parser_output!(
+            QueryProperty::parse_ical("X-DURATION;OP=GTE:PT1H DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                QueryProperty::XDuration(XDurationProperty::from_str("X-DURATION;OP=GTE:PT1H").unwrap()),
+            ),
+        );
+
         assert_parser_output!(
             QueryProperty::parse_ical("X-LIMIT:50 DESCRIPTION:Description text".into()),
             (
@@ -324,6 +389,14 @@ mod tests {
             ),
         );
 
+        assert_parser_output!(
+            QueryProperty::parse_ical("X-LIMIT-PER-EVENT:3 DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                QueryProperty::XLimitPerEvent(XLimitPerEventProperty::from_str("X-LIMIT-PER-EVENT:3").unwrap()),
+            ),
+        );
+
         assert_parser_output!(
             QueryProperty::parse_ical("X-RELATED-TO:parent.uid.one,parent.uid.two DESCRIPTION:Description text".into()),
             (
@@ -340,6 +413,22 @@ mod tests {
             ),
         );
 
+        assert_parser_output!(
+            QueryProperty::parse_ical("X-GROUP-BY:UID DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                QueryProperty::XGroupBy(XGroupByProperty::UID),
+            ),
+        );
+
+        assert_parser_output!(
+            QueryProperty::parse_ical("X-DST-POLICY:SKIP DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                QueryProperty::XDstPolicy(XDstPolicyProperty::from_str("X-DST-POLICY:SKIP").unwrap()),
+            ),
+        );
+
 
         assert_parser_output!(
             QueryProperty::parse_ical("X-GEO:48.85299;2.36885 DESCRIPTION:Description text".into()),
@@ -348,5 +437,29 @@ mod tests {
                 QueryProperty::XGeo(XGeoProperty::from_str("X-GEO:48.85299;2.36885").unwrap()),
             ),
         );
+
+        assert_parser_output!(
+            QueryProperty::parse_ical("X-RETURN:UID,DTSTART,SUMMARY DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                QueryProperty::XReturn(XReturnProperty::from_str("X-RETURN:UID,DTSTART,SUMMARY").unwrap()),
+            ),
+        );
+
+        assert_parser_output!(
+            QueryProperty::parse_ical("X-COUNT:TRUE DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                QueryProperty::XCount(XCountProperty { count: true }),
+            ),
+        );
+
+        assert_parser_output!(
+            QueryProperty::parse_ical("X-MERGE-INTERVALS:TRUE DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                QueryProperty::XMergeIntervals(XMergeIntervalsProperty { merge_intervals: true }),
+            ),
+        );
     }
 }
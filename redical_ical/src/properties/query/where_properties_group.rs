@@ -21,6 +21,8 @@ use crate::properties::query::{
     x_related_to::XRelatedToProperty,
     x_categories::XCategoriesProperty,
     x_location_type::XLocationTypeProperty,
+    x_location::XLocationProperty,
+    x_prop::XPropProperty,
 };
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -31,6 +33,8 @@ pub enum GroupedWhereProperty {
     XRelatedTo(Option<WhereOperator>, XRelatedToProperty),
     XCategories(Option<WhereOperator>, XCategoriesProperty),
     XLocationType(Option<WhereOperator>, XLocationTypeProperty),
+    XLocation(Option<WhereOperator>, XLocationProperty),
+    XProp(Option<WhereOperator>, XPropProperty),
     WherePropertiesGroup(Option<WhereOperator>, WherePropertiesGroup),
 }
 
@@ -43,6 +47,8 @@ impl GroupedWhereProperty {
             Self::XRelatedTo(external_operator, _) => external_operator,
             Self::XCategories(external_operator, _) => external_operator,
             Self::XLocationType(external_operator, _) => external_operator,
+            Self::XLocation(external_operator, _) => external_operator,
+            Self::XProp(external_operator, _) => external_operator,
             Self::WherePropertiesGroup(external_operator, _) => external_operator,
         }
     }
@@ -55,6 +61,8 @@ impl GroupedWhereProperty {
             Self::XRelatedTo(_, property) => property.to_content_line_with_context(context),
             Self::XCategories(_, property) => property.to_content_line_with_context(context),
             Self::XLocationType(_, property) => property.to_content_line_with_context(context),
+            Self::XLocation(_, property) => property.to_content_line_with_context(context),
+            Self::XProp(_, property) => property.to_content_line_with_context(context),
             Self::WherePropertiesGroup(_, property) => property.to_content_line_with_context(context),
         }
     }
@@ -101,6 +109,16 @@ impl ICalendarEntity for GroupedWhereProperty {
                         pair(opt(terminated(WhereOperator::parse_ical, wsp)), XLocationTypeProperty::parse_ical),
                         |(external_operator, x_location_type_property)| GroupedWhereProperty::XLocationType(external_operator, x_location_type_property),
                     ),
+
+                    map(
+                        pair(opt(terminated(WhereOperator::parse_ical, wsp)), XLocationProperty::parse_ical),
+                        |(external_operator, x_location_property)| GroupedWhereProperty::XLocation(external_operator, x_location_property),
+                    ),
+
+                    map(
+                        pair(opt(terminated(WhereOperator::parse_ical, wsp)), XPropProperty::parse_ical),
+                        |(external_operator, x_prop_property)| GroupedWhereProperty::XProp(external_operator, x_prop_property),
+                    ),
                 )),
             )
         )(input)
@@ -238,7 +256,8 @@ mod tests {
     use crate::ParserContext;
 
     use crate::properties::query::{
-        x_class::XClassPropertyParams,
+        x_uid::XUIDPropertyParams,
+        x_class::{XClassPropertyParams, XClassValues},
         x_categories::XCategoriesPropertyParams,
         x_location_type::XLocationTypePropertyParams,
     };
@@ -295,7 +314,7 @@ mod tests {
                             None,
                             XClassProperty {
                                 params: XClassPropertyParams::default(),
-                                classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                                classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                                 negated: false,
                             },
                         ),
@@ -314,7 +333,7 @@ mod tests {
                             None,
                             XClassProperty {
                                 params: XClassPropertyParams::default(),
-                                classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                                classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                                 negated: false,
                             },
                         ),
@@ -341,7 +360,7 @@ mod tests {
                             None,
                             XClassProperty {
                                 params: XClassPropertyParams::default(),
-                                classes: List::from(vec![ClassValue::Public]),
+                                classes: XClassValues::Classes(List::from(vec![ClassValue::Public])),
                                 negated: false,
                             },
                         ),
@@ -361,7 +380,7 @@ mod tests {
                                         None,
                                         XClassProperty {
                                             params: XClassPropertyParams::default(),
-                                            classes: List::from(vec![ClassValue::Private]),
+                                            classes: XClassValues::Classes(List::from(vec![ClassValue::Private])),
                                             negated: false,
                                         },
                                     ),
@@ -391,7 +410,7 @@ mod tests {
                             None,
                             XClassProperty {
                                 params: XClassPropertyParams::default(),
-                                classes: List::from(vec![ClassValue::Public]),
+                                classes: XClassValues::Classes(List::from(vec![ClassValue::Public])),
                                 negated: false,
                             },
                         ),
@@ -411,7 +430,7 @@ mod tests {
                                         None,
                                         XClassProperty {
                                             params: XClassPropertyParams::default(),
-                                            classes: List::from(vec![ClassValue::Private]),
+                                            classes: XClassValues::Classes(List::from(vec![ClassValue::Private])),
                                             negated: false,
                                         },
                                     ),
@@ -449,7 +468,7 @@ mod tests {
                         None,
                         XClassProperty {
                             params: XClassPropertyParams::default(),
-                            classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                            classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                             negated: false,
                         },
                     ),
@@ -465,7 +484,7 @@ mod tests {
                         None,
                         XClassProperty {
                             params: XClassPropertyParams::default(),
-                            classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                            classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                             negated: false,
                         },
                     ),
@@ -489,7 +508,7 @@ mod tests {
                         None,
                         XClassProperty {
                             params: XClassPropertyParams::default(),
-                            classes: List::from(vec![ClassValue::Public]),
+                            classes: XClassValues::Classes(List::from(vec![ClassValue::Public])),
                             negated: false,
                         },
                     ),
@@ -509,7 +528,7 @@ mod tests {
                                     None,
                                     XClassProperty {
                                         params: XClassPropertyParams::default(),
-                                        classes: List::from(vec![ClassValue::Private]),
+                                        classes: XClassValues::Classes(List::from(vec![ClassValue::Private])),
                                         negated: false,
                                     },
                                 ),
@@ -536,7 +555,7 @@ mod tests {
                         None,
                         XClassProperty {
                             params: XClassPropertyParams::default(),
-                            classes: List::from(vec![ClassValue::Public]),
+                            classes: XClassValues::Classes(List::from(vec![ClassValue::Public])),
                             negated: false,
                         },
                     ),
@@ -556,13 +575,14 @@ mod tests {
                                     None,
                                     XClassProperty {
                                         params: XClassPropertyParams::default(),
-                                        classes: List::from(vec![ClassValue::Private]),
+                                        classes: XClassValues::Classes(List::from(vec![ClassValue::Private])),
                                         negated: false,
                                     },
                                 ),
                                 GroupedWhereProperty::XUID(
                                     Some(WhereOperator::Or),
                                     XUIDProperty {
+                                        params: XUIDPropertyParams::default(),
                                         uids: List::from(vec![Text(String::from("UID_ONE")), Text(String::from("UID_TWO"))]),
                                         negated: false,
                                     },
@@ -1,6 +1,7 @@
 use nom::error::context;
+use nom::branch::alt;
 use nom::sequence::{pair, preceded, tuple};
-use nom::combinator::{map_res, cut, opt};
+use nom::combinator::{map, map_res, cut, opt};
 
 use crate::grammar::{tag, semicolon, colon};
 
@@ -12,19 +13,64 @@ use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_prope
 
 use crate::content_line::{ContentLineParams, ContentLine};
 
-use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, ParserError, impl_icalendar_entity_traits};
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, ParserError, impl_icalendar_entity_traits, map_err_message};
 
+// OP = "OR" / "AND" / "PREFIX" / "REGEX"
+//
+// ;Default is AND
+//
+// ;PREFIX matches categories starting with any of the values (e.g. for typeahead searches).
+// ;REGEX matches categories matching any of the (regular expression) values.
 #[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OpValue {
+    Where(WhereOperator),
+    Prefix,
+    Regex,
+}
+
+impl ICalendarEntity for OpValue {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "OP",
+            map_err_message!(
+                alt((
+                    map(tag("PREFIX"), |_| OpValue::Prefix),
+                    map(tag("REGEX"), |_| OpValue::Regex),
+                    map(WhereOperator::parse_ical, OpValue::Where),
+                )),
+                "expected either \"OR\", \"AND\", \"PREFIX\", or \"REGEX\"",
+            ),
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        match self {
+           Self::Where(where_operator) => where_operator.render_ical_with_context(context),
+           Self::Prefix => String::from("PREFIX"),
+           Self::Regex => String::from("REGEX"),
+        }
+    }
+}
+
+impl Default for OpValue {
+    fn default() -> Self {
+        OpValue::Where(WhereOperator::And)
+    }
+}
+
+impl_icalendar_entity_traits!(OpValue);
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct XCategoriesPropertyParams {
-    pub op: WhereOperator,
+    pub op: OpValue,
 }
 
 impl ICalendarEntity for XCategoriesPropertyParams {
     define_property_params_ical_parser!(
         XCategoriesPropertyParams,
         (
-            pair(tag("OP"), cut(preceded(tag("="), WhereOperator::parse_ical))),
-            |params: &mut XCategoriesPropertyParams, (_key, value): (ParserInput, WhereOperator)| params.op = value,
+            pair(tag("OP"), cut(preceded(tag("="), OpValue::parse_ical))),
+            |params: &mut XCategoriesPropertyParams, (_key, value): (ParserInput, OpValue)| params.op = value,
         ),
     );
 
@@ -51,14 +97,6 @@ impl From<XCategoriesPropertyParams> for ContentLineParams {
     }
 }
 
-impl Default for XCategoriesPropertyParams {
-    fn default() -> Self {
-        XCategoriesPropertyParams {
-            op: WhereOperator::And,
-        }
-    }
-}
-
 /// Query CATEGORIES where condition property.
 ///
 /// Example:
@@ -67,6 +105,9 @@ impl Default for XCategoriesPropertyParams {
 /// X-CATEGORIES:CATEGORY_ONE,CATEGORY_TWO (equivalent X-CATEGORIES;OP=AND:CATEGORY_ONE,CATEGORY_TWO)
 /// X-CATEGORIES;OP=OR:CATEGORY_ONE,CATEGORY_TWO
 /// X-CATEGORIES;OP=AND:CATEGORY_ONE,CATEGORY_TWO
+/// X-CATEGORIES;OP=PREFIX:SPORT (any category starting with SPORT)
+/// X-CATEGORIES;OP=PREFIX:SPORT,MUSIC (any category starting with either SPORT or MUSIC)
+/// X-CATEGORIES;OP=REGEX:COURSE/(MATHS|HISTORY)/.* (any category matching the pattern)
 ///
 /// Negated:
 ///
@@ -74,6 +115,8 @@ impl Default for XCategoriesPropertyParams {
 /// X-CATEGORIES-NOT:CATEGORY_ONE,CATEGORY_TWO (equivalent X-CATEGORIES;OP=AND:CATEGORY_ONE,CATEGORY_TWO)
 /// X-CATEGORIES-NOT;OP=OR:CATEGORY_ONE,CATEGORY_TWO
 /// X-CATEGORIES-NOT;OP=AND:CATEGORY_ONE,CATEGORY_TWO
+///
+/// Prefix and regex matching (OP=PREFIX and OP=REGEX) cannot be negated.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct XCategoriesProperty {
     pub params: XCategoriesPropertyParams,
@@ -103,7 +146,7 @@ impl ICalendarEntity for XCategoriesProperty {
                                 negated: not.is_some(),
                             };
 
-                            if property.negated && property.params.op != WhereOperator::And {
+                            if property.negated && property.params.op != OpValue::default() {
                                 return Err(
                                     ParserError::new(
                                         String::from("incompatible NOT operator"),
@@ -173,7 +216,7 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XCategoriesProperty {
-                    params: XCategoriesPropertyParams { op: WhereOperator::And },
+                    params: XCategoriesPropertyParams { op: OpValue::Where(WhereOperator::And) },
                     categories: List::from(vec![Text(String::from("APPOINTMENT")), Text(String::from("EDUCATION"))]),
                     negated: false,
                 },
@@ -185,7 +228,7 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XCategoriesProperty {
-                    params: XCategoriesPropertyParams { op: WhereOperator::And },
+                    params: XCategoriesPropertyParams { op: OpValue::Where(WhereOperator::And) },
                     categories: List::from(vec![Text(String::from("APPOINTMENT")), Text(String::from("EDUCATION"))]),
                     negated: true,
                 },
@@ -197,7 +240,7 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XCategoriesProperty {
-                    params: XCategoriesPropertyParams { op: WhereOperator::And },
+                    params: XCategoriesPropertyParams { op: OpValue::Where(WhereOperator::And) },
                     categories: List::from(vec![Text(String::from("APPOINTMENT")), Text(String::from("EDUCATION"))]),
                     negated: false,
                 },
@@ -209,7 +252,7 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XCategoriesProperty {
-                    params: XCategoriesPropertyParams { op: WhereOperator::And },
+                    params: XCategoriesPropertyParams { op: OpValue::Where(WhereOperator::And) },
                     categories: List::from(vec![Text(String::from("APPOINTMENT")), Text(String::from("EDUCATION"))]),
                     negated: true,
                 },
@@ -221,14 +264,40 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XCategoriesProperty {
-                    params: XCategoriesPropertyParams { op: WhereOperator::Or },
+                    params: XCategoriesPropertyParams { op: OpValue::Where(WhereOperator::Or) },
                     categories: List::from(vec![Text(String::from("APPOINTMENT")), Text(String::from("EDUCATION"))]),
                     negated: false,
                 },
             ),
         );
 
+        assert_parser_output!(
+            XCategoriesProperty::parse_ical("X-CATEGORIES;OP=PREFIX:SPORT,MUSIC DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XCategoriesProperty {
+                    params: XCategoriesPropertyParams { op: OpValue::Prefix },
+                    categories: List::from(vec![Text(String::from("SPORT")), Text(String::from("MUSIC"))]),
+                    negated: false,
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XCategoriesProperty::parse_ical("X-CATEGORIES;OP=REGEX:COURSE/(MATHS|HISTORY)/.*,SPORT_[0-9]+ DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XCategoriesProperty {
+                    params: XCategoriesPropertyParams { op: OpValue::Regex },
+                    categories: List::from(vec![Text(String::from("COURSE/(MATHS|HISTORY)/.*")), Text(String::from("SPORT_[0-9]+"))]),
+                    negated: false,
+                },
+            ),
+        );
+
         assert!(XCategoriesProperty::parse_ical("X-CATEGORIES-NOT;OP=OR:APPOINTMENT".into()).is_err());
+        assert!(XCategoriesProperty::parse_ical("X-CATEGORIES-NOT;OP=PREFIX:SPORT".into()).is_err());
+        assert!(XCategoriesProperty::parse_ical("X-CATEGORIES-NOT;OP=REGEX:SPORT.*".into()).is_err());
         assert!(XCategoriesProperty::parse_ical(":".into()).is_err());
         assert!(XCategoriesProperty::parse_ical("X-CATEGORIES;OP=WRONG:APPOINTMENT".into()).is_err());
     }
@@ -237,7 +306,7 @@ mod tests {
     fn render_ical() {
         assert_eq!(
             XCategoriesProperty {
-                params: XCategoriesPropertyParams { op: WhereOperator::And },
+                params: XCategoriesPropertyParams { op: OpValue::Where(WhereOperator::And) },
                 categories: List::from(vec![Text(String::from("APPOINTMENT")), Text(String::from("EDUCATION"))]),
                 negated: false,
             }.render_ical(),
@@ -246,7 +315,7 @@ mod tests {
 
         assert_eq!(
             XCategoriesProperty {
-                params: XCategoriesPropertyParams { op: WhereOperator::And },
+                params: XCategoriesPropertyParams { op: OpValue::Where(WhereOperator::And) },
                 categories: List::from(vec![Text(String::from("APPOINTMENT")), Text(String::from("EDUCATION"))]),
                 negated: true,
             }.render_ical(),
@@ -255,7 +324,7 @@ mod tests {
 
         assert_eq!(
             XCategoriesProperty {
-                params: XCategoriesPropertyParams { op: WhereOperator::Or },
+                params: XCategoriesPropertyParams { op: OpValue::Where(WhereOperator::Or) },
                 categories: List::from(vec![Text(String::from("APPOINTMENT")), Text(String::from("EDUCATION"))]),
                 negated: false,
             }.render_ical(),
@@ -264,11 +333,29 @@ mod tests {
 
         assert_eq!(
             XCategoriesProperty {
-                params: XCategoriesPropertyParams { op: WhereOperator::Or },
+                params: XCategoriesPropertyParams { op: OpValue::Where(WhereOperator::Or) },
                 categories: List::from(vec![Text(String::from("APPOINTMENT")), Text(String::from("EDUCATION"))]),
                 negated: true,
             }.render_ical(),
             String::from("X-CATEGORIES-NOT;OP=OR:APPOINTMENT,EDUCATION"),
         );
+
+        assert_eq!(
+            XCategoriesProperty {
+                params: XCategoriesPropertyParams { op: OpValue::Prefix },
+                categories: List::from(vec![Text(String::from("SPORT"))]),
+                negated: false,
+            }.render_ical(),
+            String::from("X-CATEGORIES;OP=PREFIX:SPORT"),
+        );
+
+        assert_eq!(
+            XCategoriesProperty {
+                params: XCategoriesPropertyParams { op: OpValue::Regex },
+                categories: List::from(vec![Text(String::from("SPORT_[0-9]+"))]),
+                negated: false,
+            }.render_ical(),
+            String::from("X-CATEGORIES;OP=REGEX:SPORT_[0-9]+"),
+        );
     }
 }
@@ -1,10 +1,12 @@
 use nom::error::context;
-use nom::sequence::{pair, preceded, tuple};
-use nom::combinator::{map_res, cut, opt};
+use nom::branch::alt;
+use nom::sequence::{pair, preceded};
+use nom::combinator::{map, map_res, flat_map, cut, opt};
 
 use crate::grammar::{tag, semicolon, colon};
 
 use crate::values::list::List;
+use crate::values::text::Text;
 
 use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
 
@@ -13,19 +15,60 @@ use crate::values::where_operator::WhereOperator;
 
 use crate::content_line::{ContentLineParams, ContentLine};
 
-use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, ParserError, impl_icalendar_entity_traits};
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, ParserError, impl_icalendar_entity_traits, map_err_message};
 
+// OP = "OR" / "AND" / "REGEX"
+//
+// ;Default is AND
+//
+// ;REGEX matches classes matching any of the (regular expression) values.
 #[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OpValue {
+    Where(WhereOperator),
+    Regex,
+}
+
+impl ICalendarEntity for OpValue {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "OP",
+            map_err_message!(
+                alt((
+                    map(tag("REGEX"), |_| OpValue::Regex),
+                    map(WhereOperator::parse_ical, OpValue::Where),
+                )),
+                "expected either \"OR\", \"AND\", or \"REGEX\"",
+            ),
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        match self {
+           Self::Where(where_operator) => where_operator.render_ical_with_context(context),
+           Self::Regex => String::from("REGEX"),
+        }
+    }
+}
+
+impl Default for OpValue {
+    fn default() -> Self {
+        OpValue::Where(WhereOperator::And)
+    }
+}
+
+impl_icalendar_entity_traits!(OpValue);
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct XClassPropertyParams {
-    pub op: WhereOperator,
+    pub op: OpValue,
 }
 
 impl ICalendarEntity for XClassPropertyParams {
     define_property_params_ical_parser!(
         XClassPropertyParams,
         (
-            pair(tag("OP"), cut(preceded(tag("="), WhereOperator::parse_ical))),
-            |params: &mut XClassPropertyParams, (_key, value): (ParserInput, WhereOperator)| params.op = value,
+            pair(tag("OP"), cut(preceded(tag("="), OpValue::parse_ical))),
+            |params: &mut XClassPropertyParams, (_key, value): (ParserInput, OpValue)| params.op = value,
         ),
     );
 
@@ -52,10 +95,31 @@ impl From<XClassPropertyParams> for ContentLineParams {
     }
 }
 
-impl Default for XClassPropertyParams {
-    fn default() -> Self {
-        XClassPropertyParams {
-            op: WhereOperator::And,
+/// The queried CLASS values, or (with OP=REGEX) the patterns matched against them, which are not
+/// restricted to the CLASS value grammar.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum XClassValues {
+    Classes(List<ClassValue>),
+    Patterns(List<Text>),
+}
+
+impl XClassValues {
+    fn parse_ical_with_op<'a>(op: &OpValue) -> impl FnMut(ParserInput<'a>) -> ParserResult<'a, Self> {
+        let is_regex = op == &OpValue::Regex;
+
+        move |input: ParserInput<'a>| {
+            if is_regex {
+                map(List::parse_ical, XClassValues::Patterns)(input)
+            } else {
+                map(List::parse_ical, XClassValues::Classes)(input)
+            }
+        }
+    }
+
+    fn render_ical(&self) -> String {
+        match self {
+            XClassValues::Classes(classes) => classes.to_string(),
+            XClassValues::Patterns(patterns) => patterns.to_string(),
         }
     }
 }
@@ -68,6 +132,7 @@ impl Default for XClassPropertyParams {
 /// X-CLASS:PUBLIC,CONFIDENTIAL  => X-CLASS;OP=AND:PUBLIC,CONFIDENTIAL
 /// X-CLASS;OP=OR:PUBLIC,CONFIDENTIAL
 /// X-CLASS;OP=AND:PUBLIC,CONFIDENTIAL
+/// X-CLASS;OP=REGEX:X-TIER-[0-9]+ (any class matching the pattern)
 ///
 /// Negated:
 ///
@@ -75,10 +140,12 @@ impl Default for XClassPropertyParams {
 /// X-CLASS-NOT:PUBLIC,CONFIDENTIAL  => X-CLASS;OP=AND:PUBLIC,CONFIDENTIAL
 /// X-CLASS-NOT;OP=OR:PUBLIC,CONFIDENTIAL
 /// X-CLASS-NOT;OP=AND:PUBLIC,CONFIDENTIAL
+///
+/// Regex matching (OP=REGEX) cannot be negated.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct XClassProperty {
     pub params: XClassPropertyParams,
-    pub classes: List<ClassValue>,
+    pub classes: XClassValues,
     pub negated: bool,
 }
 
@@ -90,21 +157,27 @@ impl ICalendarEntity for XClassProperty {
                 tag("X-CLASS"),
                 cut(
                     map_res(
-                        tuple(
-                            (
-                                opt(tag("-NOT")),
-                                opt(XClassPropertyParams::parse_ical),
-                                preceded(colon, List::parse_ical),
-                            )
+                        pair(
+                            opt(tag("-NOT")),
+                            // The values are parsed as patterns rather than CLASS values with OP=REGEX.
+                            flat_map(
+                                map(opt(XClassPropertyParams::parse_ical), Option::unwrap_or_default),
+                                |params: XClassPropertyParams| {
+                                    map(
+                                        preceded(colon, XClassValues::parse_ical_with_op(&params.op)),
+                                        move |classes| (params.to_owned(), classes),
+                                    )
+                                },
+                            ),
                         ),
-                        |(not, params, classes)| {
+                        |(not, (params, classes))| {
                             let property = XClassProperty {
-                                params: params.unwrap_or_default(),
+                                params,
                                 classes,
                                 negated: not.is_some(),
                             };
 
-                            if property.negated && property.params.op != WhereOperator::And {
+                            if property.negated && property.params.op != OpValue::default() {
                                 return Err(
                                     ParserError::new(
                                         String::from("incompatible NOT operator"),
@@ -136,18 +209,30 @@ impl ICalendarProperty for XClassProperty {
             property,
             (
                 ContentLineParams::from(&self.params),
-                self.classes.to_string(),
+                self.classes.render_ical(),
             )
         ))
     }
 }
 
 impl XClassProperty {
+    /// Return all the queried class Strings (or patterns with OP=REGEX).
     pub fn get_classifications(&self) -> Vec<String> {
-        self.classes
-            .iter()
-            .map(|text| text.to_string())
-            .collect::<Vec<String>>()
+        match &self.classes {
+            XClassValues::Classes(classes) => {
+                classes
+                    .iter()
+                    .map(|class| class.to_string())
+                    .collect::<Vec<String>>()
+            },
+
+            XClassValues::Patterns(patterns) => {
+                patterns
+                    .iter()
+                    .map(|pattern| pattern.to_string())
+                    .collect::<Vec<String>>()
+            },
+        }
     }
 }
 
@@ -172,8 +257,8 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XClassProperty {
-                    params: XClassPropertyParams { op: WhereOperator::And },
-                    classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                    params: XClassPropertyParams { op: OpValue::Where(WhereOperator::And) },
+                    classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                     negated: false,
                 },
             ),
@@ -184,8 +269,8 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XClassProperty {
-                    params: XClassPropertyParams { op: WhereOperator::And },
-                    classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                    params: XClassPropertyParams { op: OpValue::Where(WhereOperator::And) },
+                    classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                     negated: true,
                 },
             ),
@@ -196,8 +281,8 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XClassProperty {
-                    params: XClassPropertyParams { op: WhereOperator::And },
-                    classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                    params: XClassPropertyParams { op: OpValue::Where(WhereOperator::And) },
+                    classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                     negated: false,
                 },
             ),
@@ -208,8 +293,8 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XClassProperty {
-                    params: XClassPropertyParams { op: WhereOperator::And },
-                    classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                    params: XClassPropertyParams { op: OpValue::Where(WhereOperator::And) },
+                    classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                     negated: true,
                 },
             ),
@@ -220,14 +305,27 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XClassProperty {
-                    params: XClassPropertyParams { op: WhereOperator::Or },
-                    classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                    params: XClassPropertyParams { op: OpValue::Where(WhereOperator::Or) },
+                    classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
+                    negated: false,
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XClassProperty::parse_ical("X-CLASS;OP=REGEX:X-TIER-[0-9]+,PUB.* DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XClassProperty {
+                    params: XClassPropertyParams { op: OpValue::Regex },
+                    classes: XClassValues::Patterns(List::from(vec![Text(String::from("X-TIER-[0-9]+")), Text(String::from("PUB.*"))])),
                     negated: false,
                 },
             ),
         );
 
         assert!(XClassProperty::parse_ical("X-CLASS-NOT;OP=OR:PUBLIC,PRIVATE".into()).is_err());
+        assert!(XClassProperty::parse_ical("X-CLASS-NOT;OP=REGEX:PUB.*".into()).is_err());
         assert!(XClassProperty::parse_ical(":".into()).is_err());
         assert!(XClassProperty::parse_ical("X-CLASS;OP=WRONG:PUBLIC".into()).is_err());
     }
@@ -236,8 +334,8 @@ mod tests {
     fn render_ical() {
         assert_eq!(
             XClassProperty {
-                params: XClassPropertyParams { op: WhereOperator::And },
-                classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                params: XClassPropertyParams { op: OpValue::Where(WhereOperator::And) },
+                classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                 negated: false,
             }.render_ical(),
             String::from("X-CLASS;OP=AND:PRIVATE,PUBLIC"),
@@ -245,8 +343,8 @@ mod tests {
 
         assert_eq!(
             XClassProperty {
-                params: XClassPropertyParams { op: WhereOperator::And },
-                classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                params: XClassPropertyParams { op: OpValue::Where(WhereOperator::And) },
+                classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                 negated: true,
             }.render_ical(),
             String::from("X-CLASS-NOT;OP=AND:PRIVATE,PUBLIC"),
@@ -254,8 +352,8 @@ mod tests {
 
         assert_eq!(
             XClassProperty {
-                params: XClassPropertyParams { op: WhereOperator::Or },
-                classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                params: XClassPropertyParams { op: OpValue::Where(WhereOperator::Or) },
+                classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                 negated: false,
             }.render_ical(),
             String::from("X-CLASS;OP=OR:PRIVATE,PUBLIC"),
@@ -263,11 +361,20 @@ mod tests {
 
         assert_eq!(
             XClassProperty {
-                params: XClassPropertyParams { op: WhereOperator::Or },
-                classes: List::from(vec![ClassValue::Public, ClassValue::Private]),
+                params: XClassPropertyParams { op: OpValue::Where(WhereOperator::Or) },
+                classes: XClassValues::Classes(List::from(vec![ClassValue::Public, ClassValue::Private])),
                 negated: true,
             }.render_ical(),
             String::from("X-CLASS-NOT;OP=OR:PRIVATE,PUBLIC"),
         );
+
+        assert_eq!(
+            XClassProperty {
+                params: XClassPropertyParams { op: OpValue::Regex },
+                classes: XClassValues::Patterns(List::from(vec![Text(String::from("PUB.*"))])),
+                negated: false,
+            }.render_ical(),
+            String::from("X-CLASS;OP=REGEX:PUB.*"),
+        );
     }
 }
@@ -0,0 +1,118 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::branch::alt;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon};
+
+use crate::properties::ICalendarProperty;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Query count property, only returns the number of results matching the query (instead of the
+/// results themselves).
+///
+/// Example:
+///
+/// X-COUNT:TRUE
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XCountProperty {
+    pub count: bool,
+}
+
+impl ICalendarEntity for XCountProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-COUNT",
+            preceded(
+                tag("X-COUNT"),
+                cut(
+                    map(
+                        preceded(
+                            colon,
+                            alt((
+                                map(tag("TRUE"), |_| true),
+                                map(tag("FALSE"), |_| false),
+                            )),
+                        ),
+                        |count| {
+                            XCountProperty { count }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XCountProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        let count = if self.count { "TRUE" } else { "FALSE" };
+
+        ContentLine::from((
+            "X-COUNT",
+            (
+                ContentLineParams::default(),
+                String::from(count),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XCountProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XCountProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XCountProperty::parse_ical("X-COUNT:TRUE DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XCountProperty {
+                    count: true,
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XCountProperty::parse_ical("X-COUNT:FALSE DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XCountProperty {
+                    count: false,
+                },
+            ),
+        );
+
+        assert!(XCountProperty::parse_ical("X-COUNT:SOMETHING".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XCountProperty {
+                count: true,
+            }.render_ical(),
+            String::from("X-COUNT:TRUE"),
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon};
+
+use crate::properties::ICalendarProperty;
+use crate::values::dst_policy::DstPolicyValue;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Query DST policy property, determines how event instances landing on a local time skipped or
+/// repeated by a daylight saving time transition are resolved (overriding the Calendar
+/// X-DEFAULT-DST-POLICY).
+///
+/// Example:
+///
+/// X-DST-POLICY:SKIP
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XDstPolicyProperty {
+    pub dst_policy: DstPolicyValue,
+}
+
+impl ICalendarEntity for XDstPolicyProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-DST-POLICY",
+            preceded(
+                tag("X-DST-POLICY"),
+                cut(
+                    map(
+                        preceded(colon, DstPolicyValue::parse_ical),
+                        |dst_policy| {
+                            XDstPolicyProperty { dst_policy }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XDstPolicyProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-DST-POLICY",
+            (
+                ContentLineParams::default(),
+                self.dst_policy.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XDstPolicyProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XDstPolicyProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XDstPolicyProperty::parse_ical("X-DST-POLICY:BOTH-INSTANTS X-LIMIT:50".into()),
+            (
+                " X-LIMIT:50",
+                XDstPolicyProperty {
+                    dst_policy: DstPolicyValue::BothInstants,
+                },
+            ),
+        );
+
+        assert!(XDstPolicyProperty::parse_ical("X-DST-POLICY:SOMETHING".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XDstPolicyProperty {
+                dst_policy: DstPolicyValue::Skip,
+            }.render_ical(),
+            String::from("X-DST-POLICY:SKIP"),
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use nom::error::context;
+use nom::sequence::{pair, preceded};
+use nom::combinator::{map_res, cut, opt};
+
+use crate::grammar::{tag, semicolon, colon};
+
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::values::duration::Duration;
+use crate::values::where_range_operator::WhereComparisonOperator;
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, ParserError, impl_icalendar_entity_traits};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XDurationPropertyParams {
+    pub op: WhereComparisonOperator,
+}
+
+impl ICalendarEntity for XDurationPropertyParams {
+    define_property_params_ical_parser!(
+        XDurationPropertyParams,
+        (
+            pair(tag("OP"), cut(preceded(tag("="), WhereComparisonOperator::parse_ical))),
+            |params: &mut XDurationPropertyParams, (_key, value): (ParserInput, WhereComparisonOperator)| params.op = value,
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for XDurationPropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        content_line_params.insert(String::from("OP"), self.op.render_ical());
+
+        content_line_params
+    }
+}
+
+impl Default for XDurationPropertyParams {
+    fn default() -> Self {
+        XDurationPropertyParams {
+            op: WhereComparisonOperator::GreaterEqualThan,
+        }
+    }
+}
+
+/// Query event instance (effective) duration filter property.
+///
+/// Example:
+///
+/// X-DURATION:PT1H          => X-DURATION;OP=GTE:PT1H
+/// X-DURATION;OP=GT:PT1H
+/// X-DURATION;OP=GTE:PT1H
+/// X-DURATION;OP=LT:PT1H
+/// X-DURATION;OP=LTE:PT1H
+/// X-DURATION;OP=EQ:PT1H
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XDurationProperty {
+    pub params: XDurationPropertyParams,
+    pub duration: Duration,
+}
+
+impl XDurationProperty {
+    pub fn get_op(&self) -> WhereComparisonOperator {
+        self.params.op.to_owned()
+    }
+
+    pub fn get_duration_in_seconds(&self) -> i64 {
+        self.duration.get_duration_in_seconds()
+    }
+}
+
+impl ICalendarEntity for XDurationProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-DURATION",
+            preceded(
+                tag("X-DURATION"),
+                cut(
+                    map_res(
+                        pair(
+                            opt(XDurationPropertyParams::parse_ical),
+                            preceded(colon, Duration::parse_ical),
+                        ),
+                        |(params, duration)| {
+                            let x_duration_property =
+                                XDurationProperty {
+                                    params: params.unwrap_or_default(),
+                                    duration,
+                                };
+
+                            if let Err(error) = ICalendarEntity::validate(&x_duration_property) {
+                                return Err(
+                                    ParserError::new(error, input)
+                                );
+                            }
+
+                            Ok(x_duration_property)
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.get_duration_in_seconds() < 0 {
+            return Err(String::from("duration cannot be negative"));
+        }
+
+        Ok(())
+    }
+}
+
+impl ICalendarProperty for XDurationProperty {
+    /// Build a `ContentLine` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-DURATION",
+            (
+                self.params.to_content_line_params_with_context(context),
+                self.duration.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XDurationProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XDurationProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XDurationProperty::parse_ical("X-DURATION:PT1H DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XDurationProperty {
+                    params: XDurationPropertyParams { op: WhereComparisonOperator::GreaterEqualThan },
+                    duration: Duration {
+                        hours: Some(1),
+                        ..Duration::default()
+                    },
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XDurationProperty::parse_ical("X-DURATION;OP=LT:P1DT30M DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XDurationProperty {
+                    params: XDurationPropertyParams { op: WhereComparisonOperator::LessThan },
+                    duration: Duration {
+                        days: Some(1),
+                        minutes: Some(30),
+                        ..Duration::default()
+                    },
+                },
+            ),
+        );
+
+        assert!(XDurationProperty::parse_ical("X-DURATION;OP=NE:PT1H".into()).is_err());
+        assert!(XDurationProperty::parse_ical("X-DURATION:-PT1H".into()).is_err());
+        assert!(XDurationProperty::parse_ical("X-DURATION:INVALID".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XDurationProperty {
+                params: XDurationPropertyParams { op: WhereComparisonOperator::GreaterEqualThan },
+                duration: Duration {
+                    hours: Some(1),
+                    ..Duration::default()
+                },
+            }.render_ical(),
+            String::from("X-DURATION;OP=GTE:PT1H"),
+        );
+
+        assert_eq!(
+            XDurationProperty {
+                params: XDurationPropertyParams { op: WhereComparisonOperator::Equal },
+                duration: Duration {
+                    minutes: Some(45),
+                    ..Duration::default()
+                },
+            }.render_ical(),
+            String::from("X-DURATION;OP=EQ:PT45M"),
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon};
+
+use crate::properties::ICalendarProperty;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+
+/// Query group by property, grouping the results (in the reply) by the specified value.
+///
+/// Currently only UID value is available.
+///
+/// Example:
+///
+/// X-GROUP-BY:UID
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum XGroupByProperty {
+    UID,
+}
+
+impl ICalendarEntity for XGroupByProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-GROUP-BY",
+            preceded(
+                tag("X-GROUP-BY"),
+                cut(
+                    map(
+                        preceded(colon, tag("UID")),
+                        |_| {
+                            XGroupByProperty::UID
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XGroupByProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-GROUP-BY",
+            (
+                ContentLineParams::default(),
+                String::from("UID"),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XGroupByProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XGroupByProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XGroupByProperty::parse_ical("X-GROUP-BY:UID DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XGroupByProperty::UID,
+            ),
+        );
+
+        assert!(XGroupByProperty::parse_ical("X-GROUP-BY:SOMETHING".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XGroupByProperty::UID.render_ical(),
+            String::from("X-GROUP-BY:UID"),
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::combinator::{map_res, cut};
+
+use crate::grammar::{tag, colon};
+
+use crate::properties::ICalendarProperty;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, ParserError, impl_icalendar_entity_traits};
+
+use crate::values::integer::Integer;
+
+/// Query limit per event property, limiting how many instances any single (recurring) event can
+/// contribute to the query results.
+///
+/// Example:
+///
+/// X-LIMIT-PER-EVENT:3
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XLimitPerEventProperty {
+    pub limit: Integer,
+}
+
+impl From<XLimitPerEventProperty> for usize {
+    fn from(limit_per_event_property: XLimitPerEventProperty) -> Self {
+        *limit_per_event_property.limit as usize
+    }
+}
+
+impl From<&XLimitPerEventProperty> for usize {
+    fn from(limit_per_event_property: &XLimitPerEventProperty) -> Self {
+        *limit_per_event_property.limit as usize
+    }
+}
+
+impl ICalendarEntity for XLimitPerEventProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-LIMIT-PER-EVENT",
+            preceded(
+                tag("X-LIMIT-PER-EVENT"),
+                cut(
+                    map_res(
+                        preceded(colon, Integer::parse_ical),
+                        |limit| {
+                            if *limit < 1 {
+                                return Err(
+                                    ParserError::new(String::from("limit per event cannot be less than 1"), input)
+                                );
+                            }
+
+                            Ok(XLimitPerEventProperty { limit })
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XLimitPerEventProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-LIMIT-PER-EVENT",
+            (
+                ContentLineParams::default(),
+                self.limit.to_string(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XLimitPerEventProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XLimitPerEventProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XLimitPerEventProperty::parse_ical("X-LIMIT-PER-EVENT:3 DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XLimitPerEventProperty {
+                    limit: Integer(3_i64),
+                },
+            ),
+        );
+
+        assert!(XLimitPerEventProperty::parse_ical("X-LIMIT-PER-EVENT:0".into()).is_err());
+        assert!(XLimitPerEventProperty::parse_ical("X-LIMIT-PER-EVENT:-3".into()).is_err());
+        assert!(XLimitPerEventProperty::parse_ical("X-LIMIT:3".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XLimitPerEventProperty {
+                limit: Integer(3_i64),
+            }.render_ical(),
+            String::from("X-LIMIT-PER-EVENT:3"),
+        );
+    }
+}
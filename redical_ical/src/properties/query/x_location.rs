@@ -0,0 +1,249 @@
+use nom::error::context;
+use nom::sequence::{pair, preceded, terminated, tuple};
+use nom::combinator::{map_res, cut, opt, not};
+
+use crate::grammar::{tag, semicolon, colon};
+
+use crate::values::text::Text;
+use crate::values::list::List;
+use crate::values::where_operator::WhereOperator;
+
+use crate::properties::event::normalize_location;
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, ParserError, impl_icalendar_entity_traits};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XLocationPropertyParams {
+    pub op: WhereOperator,
+}
+
+impl ICalendarEntity for XLocationPropertyParams {
+    define_property_params_ical_parser!(
+        XLocationPropertyParams,
+        (
+            pair(tag("OP"), cut(preceded(tag("="), WhereOperator::parse_ical))),
+            |params: &mut XLocationPropertyParams, (_key, value): (ParserInput, WhereOperator)| params.op = value,
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for XLocationPropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        content_line_params.insert(String::from("OP"), self.op.render_ical());
+
+        content_line_params
+    }
+}
+
+impl From<XLocationPropertyParams> for ContentLineParams {
+    fn from(locations_params: XLocationPropertyParams) -> Self {
+        ContentLineParams::from(&locations_params)
+    }
+}
+
+impl Default for XLocationPropertyParams {
+    fn default() -> Self {
+        XLocationPropertyParams {
+            op: WhereOperator::And,
+        }
+    }
+}
+
+/// Query LOCATION where condition property.
+///
+/// Each value is matched against the whole (normalized) LOCATION text of the event, with any
+/// commas within a single location escaped (as with the LOCATION property itself).
+///
+/// Example:
+///
+/// X-LOCATION:Royal Albert Hall
+/// X-LOCATION:Royal Albert Hall,Wembley Arena (equivalent X-LOCATION;OP=AND:Royal Albert Hall,Wembley Arena)
+/// X-LOCATION;OP=OR:Royal Albert Hall,Wembley Arena
+/// X-LOCATION;OP=AND:Royal Albert Hall,Wembley Arena
+///
+/// Negated:
+///
+/// X-LOCATION-NOT:Royal Albert Hall
+/// X-LOCATION-NOT:Royal Albert Hall,Wembley Arena (equivalent X-LOCATION-NOT;OP=AND:Royal Albert Hall,Wembley Arena)
+/// X-LOCATION-NOT;OP=AND:Royal Albert Hall,Wembley Arena
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XLocationProperty {
+    pub params: XLocationPropertyParams,
+    pub locations: List<Text>,
+    pub negated: bool,
+}
+
+impl ICalendarEntity for XLocationProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-LOCATION",
+            preceded(
+                // Prevent the X-LOCATION-TYPE property being mistaken for this one.
+                terminated(tag("X-LOCATION"), not(tag("-TYPE"))),
+                cut(
+                    map_res(
+                        tuple(
+                            (
+                                opt(tag("-NOT")),
+                                opt(XLocationPropertyParams::parse_ical),
+                                preceded(colon, List::parse_ical),
+                            )
+                        ),
+                        |(not, params, locations)| {
+                            let property = XLocationProperty {
+                                params: params.unwrap_or_default(),
+                                locations,
+                                negated: not.is_some(),
+                            };
+
+                            if property.negated && property.params.op != WhereOperator::And {
+                                return Err(
+                                    ParserError::new(
+                                        String::from("incompatible NOT operator"),
+                                        input
+                                    )
+                                );
+                            }
+
+                            Ok(property)
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XLocationProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        let property = if self.negated { "X-LOCATION-NOT" } else { "X-LOCATION" };
+
+        ContentLine::from((
+            property,
+            (
+                ContentLineParams::from(&self.params),
+                self.locations.to_string(),
+            )
+        ))
+    }
+}
+
+impl XLocationProperty {
+    /// Return all normalized location Strings (blanks stripped out).
+    pub fn get_locations(&self) -> Vec<String> {
+        self.locations
+            .iter()
+            .map(|text| normalize_location(text.to_string().as_str()))
+            .filter(|location| !location.is_empty())
+            .collect::<Vec<String>>()
+    }
+}
+
+impl std::hash::Hash for XLocationProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XLocationProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XLocationProperty::parse_ical("X-LOCATION:Royal Albert Hall,Conference Room - F123\\, Bldg. 002 X-LOCATION-TYPE:HOTEL".into()),
+            (
+                " X-LOCATION-TYPE:HOTEL",
+                XLocationProperty {
+                    params: XLocationPropertyParams { op: WhereOperator::And },
+                    locations: List::from(vec![Text(String::from("Royal Albert Hall")), Text(String::from("Conference Room - F123\\, Bldg. 002"))]),
+                    negated: false,
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XLocationProperty::parse_ical("X-LOCATION-NOT:Royal Albert Hall X-LOCATION-TYPE:HOTEL".into()),
+            (
+                " X-LOCATION-TYPE:HOTEL",
+                XLocationProperty {
+                    params: XLocationPropertyParams { op: WhereOperator::And },
+                    locations: List::from(vec![Text(String::from("Royal Albert Hall"))]),
+                    negated: true,
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XLocationProperty::parse_ical("X-LOCATION;OP=OR:Royal Albert Hall,Wembley Arena".into()),
+            (
+                "",
+                XLocationProperty {
+                    params: XLocationPropertyParams { op: WhereOperator::Or },
+                    locations: List::from(vec![Text(String::from("Royal Albert Hall")), Text(String::from("Wembley Arena"))]),
+                    negated: false,
+                },
+            ),
+        );
+
+        assert!(XLocationProperty::parse_ical("X-LOCATION-TYPE:HOTEL".into()).is_err());
+        assert!(XLocationProperty::parse_ical("X-LOCATION-NOT;OP=OR:Royal Albert Hall".into()).is_err());
+        assert!(XLocationProperty::parse_ical("X-LOCATION;OP=WRONG:Royal Albert Hall".into()).is_err());
+        assert!(XLocationProperty::parse_ical(":".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XLocationProperty {
+                params: XLocationPropertyParams { op: WhereOperator::And },
+                locations: List::from(vec![Text(String::from("Wembley Arena")), Text(String::from("Royal Albert Hall"))]),
+                negated: false,
+            }.render_ical(),
+            String::from("X-LOCATION;OP=AND:Royal Albert Hall,Wembley Arena"),
+        );
+
+        assert_eq!(
+            XLocationProperty {
+                params: XLocationPropertyParams { op: WhereOperator::And },
+                locations: List::from(vec![Text(String::from("Royal Albert Hall"))]),
+                negated: true,
+            }.render_ical(),
+            String::from("X-LOCATION-NOT;OP=AND:Royal Albert Hall"),
+        );
+    }
+
+    #[test]
+    fn get_locations() {
+        assert_eq!(
+            XLocationProperty {
+                params: XLocationPropertyParams { op: WhereOperator::And },
+                locations: List::from(vec![Text(String::from(" Royal  Albert Hall")), Text(String::from("  "))]),
+                negated: false,
+            }.get_locations(),
+            vec![String::from("Royal Albert Hall")],
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::branch::alt;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon};
+
+use crate::properties::ICalendarProperty;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Query merge intervals property, collapses the overlapping (or adjacent) event instances matching
+/// the query into the minimal set of (DTSTART, DTEND) busy intervals spanning them (instead of
+/// returning the event instances themselves).
+///
+/// Example:
+///
+/// X-MERGE-INTERVALS:TRUE
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XMergeIntervalsProperty {
+    pub merge_intervals: bool,
+}
+
+impl ICalendarEntity for XMergeIntervalsProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-MERGE-INTERVALS",
+            preceded(
+                tag("X-MERGE-INTERVALS"),
+                cut(
+                    map(
+                        preceded(
+                            colon,
+                            alt((
+                                map(tag("TRUE"), |_| true),
+                                map(tag("FALSE"), |_| false),
+                            )),
+                        ),
+                        |merge_intervals| {
+                            XMergeIntervalsProperty { merge_intervals }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XMergeIntervalsProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        let merge_intervals = if self.merge_intervals { "TRUE" } else { "FALSE" };
+
+        ContentLine::from((
+            "X-MERGE-INTERVALS",
+            (
+                ContentLineParams::default(),
+                String::from(merge_intervals),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XMergeIntervalsProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XMergeIntervalsProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XMergeIntervalsProperty::parse_ical("X-MERGE-INTERVALS:TRUE DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XMergeIntervalsProperty {
+                    merge_intervals: true,
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XMergeIntervalsProperty::parse_ical("X-MERGE-INTERVALS:FALSE DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XMergeIntervalsProperty {
+                    merge_intervals: false,
+                },
+            ),
+        );
+
+        assert!(XMergeIntervalsProperty::parse_ical("X-MERGE-INTERVALS:SOMETHING".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XMergeIntervalsProperty {
+                merge_intervals: true,
+            }.render_ical(),
+            String::from("X-MERGE-INTERVALS:TRUE"),
+        );
+    }
+}
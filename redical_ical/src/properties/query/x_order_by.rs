@@ -15,17 +15,20 @@ use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_i
 /// * DTSTART
 /// * DTSTART / GEO-DIST
 /// * GEO-DIST / DTSTART
+/// * SCORE (relevance score / DTSTART)
 ///
 /// Example:
 ///
 // X-ORDER-BY:DTSTART
 // X-ORDER-BY:DTSTART-GEO-DIST;48.85299;2.36885
 // X-ORDER-BY:GEO-DIST-DTSTART;48.85299;2.36885
+// X-ORDER-BY:SCORE
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum XOrderByProperty {
     DTStart,
     DTStartGeoDist(Float, Float),
     GeoDistDTStart(Float, Float),
+    Score,
 }
 
 impl ICalendarEntity for XOrderByProperty {
@@ -50,6 +53,10 @@ impl ICalendarEntity for XOrderByProperty {
                                 tag("DTSTART"),
                                 |_| XOrderByProperty::DTStart
                             ),
+                            map(
+                                tag("SCORE"),
+                                |_| XOrderByProperty::Score
+                            ),
                         ))
                     )
                 )
@@ -90,6 +97,10 @@ impl ICalendarProperty for XOrderByProperty {
                     )
                 ))
             },
+
+            Self::Score => {
+                ContentLine::from(("X-ORDER-BY", (ContentLineParams::default(), String::from("SCORE"))))
+            },
         }
     }
 }
@@ -134,6 +145,14 @@ mod tests {
             ),
         );
 
+        assert_parser_output!(
+            XOrderByProperty::parse_ical("X-ORDER-BY:SCORE DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XOrderByProperty::Score,
+            ),
+        );
+
         assert!(XOrderByProperty::parse_ical("X-ORDER-BY:DTSTART-GEO-DIST".into()).is_err());
         assert!(XOrderByProperty::parse_ical("X-ORDER-BY:GEO-DIST-DTSTART".into()).is_err());
         assert!(XOrderByProperty::parse_ical("X-ORDER-BY:DTSTART-GEO-DIST;48.85299".into()).is_err());
@@ -156,5 +175,10 @@ mod tests {
             XOrderByProperty::GeoDistDTStart(Float(48.85299_f64), Float(2.36885_f64)).render_ical(),
             String::from("X-ORDER-BY:GEO-DIST-DTSTART;48.85299;2.36885"),
         );
+
+        assert_eq!(
+            XOrderByProperty::Score.render_ical(),
+            String::from("X-ORDER-BY:SCORE"),
+        );
     }
 }
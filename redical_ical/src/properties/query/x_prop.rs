@@ -0,0 +1,273 @@
+use nom::error::context;
+use nom::sequence::{pair, preceded, tuple};
+use nom::combinator::{map_res, cut, opt};
+
+use crate::grammar::{tag, semicolon, colon, x_name};
+
+use crate::values::text::Text;
+use crate::values::list::List;
+use crate::values::where_operator::WhereOperator;
+
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, ParserError, impl_icalendar_entity_traits};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XPropPropertyParams {
+    pub name: Option<String>,
+    pub op: WhereOperator,
+}
+
+impl ICalendarEntity for XPropPropertyParams {
+    define_property_params_ical_parser!(
+        XPropPropertyParams,
+        (
+            pair(tag("NAME"), cut(preceded(tag("="), x_name))),
+            |params: &mut XPropPropertyParams, (_key, name): (ParserInput, ParserInput)| params.name = Some(name.to_string().to_uppercase()),
+        ),
+        (
+            pair(tag("OP"), cut(preceded(tag("="), WhereOperator::parse_ical))),
+            |params: &mut XPropPropertyParams, (_key, value): (ParserInput, WhereOperator)| params.op = value,
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for XPropPropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        if let Some(name) = self.name.as_ref() {
+            content_line_params.insert(String::from("NAME"), name.to_owned());
+        }
+
+        content_line_params.insert(String::from("OP"), self.op.render_ical());
+
+        content_line_params
+    }
+}
+
+impl From<XPropPropertyParams> for ContentLineParams {
+    fn from(prop_params: XPropPropertyParams) -> Self {
+        ContentLineParams::from(&prop_params)
+    }
+}
+
+impl Default for XPropPropertyParams {
+    fn default() -> Self {
+        XPropPropertyParams {
+            name: None,
+            op: WhereOperator::And,
+        }
+    }
+}
+
+/// Query indexed X-property where condition property.
+///
+/// Matches events by the exact value of the passive X-property named by the (required) NAME param,
+/// which the Calendar must list within its X-INDEXED-PROPERTIES property for it to be indexed.
+///
+/// Example:
+///
+/// X-PROP;NAME=X-INSTRUCTOR:Jane Doe
+/// X-PROP;NAME=X-INSTRUCTOR:Jane Doe,John Doe (equivalent X-PROP;NAME=X-INSTRUCTOR;OP=AND:Jane Doe,John Doe)
+/// X-PROP;NAME=X-INSTRUCTOR;OP=OR:Jane Doe,John Doe
+///
+/// Negated:
+///
+/// X-PROP-NOT;NAME=X-INSTRUCTOR:Jane Doe
+/// X-PROP-NOT;NAME=X-INSTRUCTOR;OP=AND:Jane Doe,John Doe
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XPropProperty {
+    pub params: XPropPropertyParams,
+    pub values: List<Text>,
+    pub negated: bool,
+}
+
+impl ICalendarEntity for XPropProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-PROP",
+            preceded(
+                tag("X-PROP"),
+                cut(
+                    map_res(
+                        tuple(
+                            (
+                                opt(tag("-NOT")),
+                                opt(XPropPropertyParams::parse_ical),
+                                preceded(colon, List::parse_ical),
+                            )
+                        ),
+                        |(not, params, values)| {
+                            let property = XPropProperty {
+                                params: params.unwrap_or_default(),
+                                values,
+                                negated: not.is_some(),
+                            };
+
+                            if property.negated && property.params.op != WhereOperator::And {
+                                return Err(
+                                    ParserError::new(
+                                        String::from("incompatible NOT operator"),
+                                        input
+                                    )
+                                );
+                            }
+
+                            if property.params.name.is_none() {
+                                return Err(
+                                    ParserError::new(
+                                        String::from("expected NAME param"),
+                                        input
+                                    )
+                                );
+                            }
+
+                            Ok(property)
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XPropProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        let property = if self.negated { "X-PROP-NOT" } else { "X-PROP" };
+
+        ContentLine::from((
+            property,
+            (
+                ContentLineParams::from(&self.params),
+                self.values.to_string(),
+            )
+        ))
+    }
+}
+
+impl XPropProperty {
+    /// Returns the (uppercased) name of the queried X-property.
+    pub fn get_name(&self) -> String {
+        self.params.name.to_owned().unwrap_or_default()
+    }
+
+    /// Return all value Strings (blanks stripped out).
+    pub fn get_values(&self) -> Vec<String> {
+        self.values
+            .iter()
+            .map(|text| text.to_string())
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<String>>()
+    }
+}
+
+impl std::hash::Hash for XPropProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XPropProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XPropProperty::parse_ical("X-PROP;NAME=X-Instructor:Jane Doe,John Doe X-CATEGORIES:YOGA".into()),
+            (
+                " X-CATEGORIES:YOGA",
+                XPropProperty {
+                    params: XPropPropertyParams {
+                        name: Some(String::from("X-INSTRUCTOR")),
+                        op: WhereOperator::And,
+                    },
+                    values: List::from(vec![Text(String::from("Jane Doe")), Text(String::from("John Doe"))]),
+                    negated: false,
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XPropProperty::parse_ical("X-PROP-NOT;NAME=X-LEVEL:Beginner X-CATEGORIES:YOGA".into()),
+            (
+                " X-CATEGORIES:YOGA",
+                XPropProperty {
+                    params: XPropPropertyParams {
+                        name: Some(String::from("X-LEVEL")),
+                        op: WhereOperator::And,
+                    },
+                    values: List::from(vec![Text(String::from("Beginner"))]),
+                    negated: true,
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XPropProperty::parse_ical("X-PROP;OP=OR;NAME=X-LEVEL:Beginner,Advanced".into()),
+            (
+                "",
+                XPropProperty {
+                    params: XPropPropertyParams {
+                        name: Some(String::from("X-LEVEL")),
+                        op: WhereOperator::Or,
+                    },
+                    values: List::from(vec![Text(String::from("Beginner")), Text(String::from("Advanced"))]),
+                    negated: false,
+                },
+            ),
+        );
+
+        assert!(XPropProperty::parse_ical("X-PROP:Beginner".into()).is_err());
+        assert!(XPropProperty::parse_ical("X-PROP;NAME=LEVEL:Beginner".into()).is_err());
+        assert!(XPropProperty::parse_ical("X-PROP-NOT;NAME=X-LEVEL;OP=OR:Beginner,Advanced".into()).is_err());
+
+        assert!(XPropProperty::parse_ical(":".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XPropProperty {
+                params: XPropPropertyParams {
+                    name: Some(String::from("X-INSTRUCTOR")),
+                    op: WhereOperator::And,
+                },
+                values: List::from(vec![Text(String::from("Jane Doe")), Text(String::from("John Doe"))]),
+                negated: false,
+            }.render_ical(),
+            String::from("X-PROP;NAME=X-INSTRUCTOR;OP=AND:Jane Doe,John Doe"),
+        );
+
+        assert_eq!(
+            XPropProperty {
+                params: XPropPropertyParams {
+                    name: Some(String::from("X-LEVEL")),
+                    op: WhereOperator::And,
+                },
+                values: List::from(vec![Text(String::from("Beginner"))]),
+                negated: true,
+            }.render_ical(),
+            String::from("X-PROP-NOT;NAME=X-LEVEL;OP=AND:Beginner"),
+        );
+    }
+}
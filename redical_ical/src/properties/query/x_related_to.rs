@@ -2,6 +2,7 @@ use nom::error::context;
 use nom::sequence::{pair, preceded, tuple};
 use nom::combinator::{map_res, cut, opt};
 
+use crate::values::integer::Integer;
 use crate::values::text::Text;
 use crate::values::list::List;
 use crate::values::reltype::Reltype;
@@ -17,21 +18,26 @@ use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, Parser
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct XRelatedToPropertyParams {
-    pub reltype: Reltype,
+    pub reltypes: List<Reltype>,
     pub op: WhereOperator,
+    pub depth: Option<Integer>,
 }
 
 impl ICalendarEntity for XRelatedToPropertyParams {
     define_property_params_ical_parser!(
         XRelatedToPropertyParams,
         (
-            pair(tag("RELTYPE"), cut(preceded(tag("="), Reltype::parse_ical))),
-            |params: &mut XRelatedToPropertyParams, (_key, reltype): (ParserInput, Reltype)| params.reltype = reltype,
+            pair(tag("RELTYPE"), cut(preceded(tag("="), List::parse_ical))),
+            |params: &mut XRelatedToPropertyParams, (_key, reltypes): (ParserInput, List<Reltype>)| params.reltypes = reltypes,
         ),
         (
             pair(tag("OP"), cut(preceded(tag("="), WhereOperator::parse_ical))),
             |params: &mut XRelatedToPropertyParams, (_key, value): (ParserInput, WhereOperator)| params.op = value,
         ),
+        (
+            pair(tag("DEPTH"), cut(preceded(tag("="), Integer::parse_ical))),
+            |params: &mut XRelatedToPropertyParams, (_key, depth): (ParserInput, Integer)| params.depth = Some(depth),
+        ),
     );
 
     fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
@@ -45,9 +51,13 @@ impl ICalendarPropertyParams for XRelatedToPropertyParams {
     fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
         let mut content_line_params = ContentLineParams::default();
 
-        content_line_params.insert(String::from("RELTYPE"), self.reltype.render_ical());
+        content_line_params.insert(String::from("RELTYPE"), self.reltypes.render_ical());
         content_line_params.insert(String::from("OP"), self.op.render_ical());
 
+        if let Some(depth) = self.depth.as_ref() {
+            content_line_params.insert(String::from("DEPTH"), depth.render_ical());
+        }
+
         content_line_params
     }
 }
@@ -61,8 +71,9 @@ impl From<XRelatedToPropertyParams> for ContentLineParams {
 impl Default for XRelatedToPropertyParams {
     fn default() -> Self {
         XRelatedToPropertyParams {
-            reltype: Reltype::Parent,
+            reltypes: List::from(vec![Reltype::Parent]),
             op: WhereOperator::And,
+            depth: None,
         }
     }
 }
@@ -75,6 +86,16 @@ impl Default for XRelatedToPropertyParams {
 /// X-RELATED-TO;RELTYPE=PARENT:PARENT_UID_ONE,PARENT_UID_TWO => X-RELATED-TO;OP=AND;RELTYPE=PARENT:PARENT_UID_ONE,PARENT_UID_TWO
 /// X-RELATED-TO;RELTYPE=PARENT;OP=AND:PARENT_UID_ONE,PARENT_UID_TWO
 /// X-RELATED-TO;RELTYPE=PARENT;OP=OR:PARENT_UID_ONE,PARENT_UID_TWO
+///
+/// Multiple RELTYPE values match events related to each UID by any of them, e.g. every event
+/// related to SOME_UID as either it's parent or sibling:
+///
+/// X-RELATED-TO;RELTYPE=PARENT,SIBLING:SOME_UID => (X-RELATED-TO;RELTYPE=PARENT:SOME_UID OR X-RELATED-TO;RELTYPE=SIBLING:SOME_UID)
+///
+/// The DEPTH param walks the relations transitively (through the events related to the UIDs, and
+/// so on) up to the provided depth, e.g. every event nested up to 3 levels beneath ROOT_UID:
+///
+/// X-RELATED-TO;RELTYPE=PARENT;DEPTH=3:ROOT_UID
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct XRelatedToProperty {
     pub params: XRelatedToPropertyParams,
@@ -113,6 +134,33 @@ impl ICalendarEntity for XRelatedToProperty {
                                 );
                             }
 
+                            if property.params.reltypes.is_empty() {
+                                return Err(
+                                    ParserError::new(
+                                        String::from("expected RELTYPE value"),
+                                        input
+                                    )
+                                );
+                            }
+
+                            if property.params.depth.as_ref().is_some_and(|depth| **depth < 1) {
+                                return Err(
+                                    ParserError::new(
+                                        String::from("depth cannot be less than 1"),
+                                        input
+                                    )
+                                );
+                            }
+
+                            if property.negated && property.params.depth.is_some() {
+                                return Err(
+                                    ParserError::new(
+                                        String::from("incompatible NOT depth"),
+                                        input
+                                    )
+                                );
+                            }
+
                             Ok(property)
                         }
                     )
@@ -144,10 +192,23 @@ impl ICalendarProperty for XRelatedToProperty {
 }
 
 impl XRelatedToProperty {
-    /// Returns the RELTYPE for this property, if not present we return the default
+    /// Returns each (distinct) RELTYPE for this property, if not present we return the default
     /// `Reltype::Parent`.
-    pub fn get_reltype(&self) -> Reltype {
-        self.params.reltype.to_owned()
+    pub fn get_reltypes(&self) -> Vec<Reltype> {
+        let mut reltypes: Vec<Reltype> = Vec::new();
+
+        for reltype in self.params.reltypes.iter() {
+            if !reltypes.contains(reltype) {
+                reltypes.push(reltype.to_owned());
+            }
+        }
+
+        reltypes
+    }
+
+    /// Returns the depth the relations are walked transitively to (1 being only directly related).
+    pub fn get_depth(&self) -> usize {
+        self.params.depth.as_ref().map_or(1, |depth| **depth as usize)
     }
 
     /// Return all UID Strings (blanks stripped out).
@@ -184,8 +245,9 @@ mod tests {
                 " DESCRIPTION:Description text",
                 XRelatedToProperty {
                     params: XRelatedToPropertyParams {
-                        reltype: Reltype::Parent,
+                        reltypes: List::from(vec![Reltype::Parent]),
                         op: WhereOperator::And,
+                        depth: None,
                     },
                     uids: List::from(vec![Text(String::from("parent.uid.one")), Text(String::from("parent.uid.two"))]),
                     negated: false,
@@ -201,8 +263,9 @@ mod tests {
                 " DESCRIPTION:Description text",
                 XRelatedToProperty {
                     params: XRelatedToPropertyParams {
-                        reltype: Reltype::Parent,
+                        reltypes: List::from(vec![Reltype::Parent]),
                         op: WhereOperator::And,
+                        depth: None,
                     },
                     uids: List::from(vec![Text(String::from("parent.uid.one")), Text(String::from("parent.uid.two"))]),
                     negated: true,
@@ -216,8 +279,9 @@ mod tests {
                 " DESCRIPTION:Description text",
                 XRelatedToProperty {
                     params: XRelatedToPropertyParams {
-                        reltype: Reltype::XName(String::from("X-RELTYPE")),
+                        reltypes: List::from(vec![Reltype::XName(String::from("X-RELTYPE"))]),
                         op: WhereOperator::Or,
+                        depth: None,
                     },
                     uids: List::from(vec![Text(String::from("x-reltype.uid.one")), Text(String::from("x-reltype.uid.two"))]),
                     negated: false,
@@ -227,6 +291,42 @@ mod tests {
 
         assert!(XRelatedToProperty::parse_ical("X-RELATED-TO-NOT;RELTYPE=X-RELTYPE;OP=OR:x-reltype.uid.one,x-reltype.uid.two DESCRIPTION:Description text".into()).is_err());
 
+        assert_parser_output!(
+            XRelatedToProperty::parse_ical("X-RELATED-TO;RELTYPE=PARENT;DEPTH=3:root.uid DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XRelatedToProperty {
+                    params: XRelatedToPropertyParams {
+                        reltypes: List::from(vec![Reltype::Parent]),
+                        op: WhereOperator::And,
+                        depth: Some(Integer(3)),
+                    },
+                    uids: List::from(vec![Text(String::from("root.uid"))]),
+                    negated: false,
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XRelatedToProperty::parse_ical("X-RELATED-TO;RELTYPE=PARENT,SIBLING:some.uid DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XRelatedToProperty {
+                    params: XRelatedToPropertyParams {
+                        reltypes: List::from(vec![Reltype::Parent, Reltype::Sibling]),
+                        op: WhereOperator::And,
+                        depth: None,
+                    },
+                    uids: List::from(vec![Text(String::from("some.uid"))]),
+                    negated: false,
+                },
+            ),
+        );
+
+        assert!(XRelatedToProperty::parse_ical("X-RELATED-TO;RELTYPE=:some.uid".into()).is_err());
+        assert!(XRelatedToProperty::parse_ical("X-RELATED-TO;DEPTH=0:root.uid".into()).is_err());
+        assert!(XRelatedToProperty::parse_ical("X-RELATED-TO-NOT;DEPTH=2:root.uid".into()).is_err());
+
         assert!(XRelatedToProperty::parse_ical(":".into()).is_err());
     }
 
@@ -235,8 +335,9 @@ mod tests {
         assert_eq!(
             XRelatedToProperty {
                 params: XRelatedToPropertyParams {
-                    reltype: Reltype::Parent,
+                    reltypes: List::from(vec![Reltype::Parent]),
                     op: WhereOperator::And,
+                    depth: None,
                 },
                 uids: List::from(vec![Text(String::from("parent.uid.one")), Text(String::from("parent.uid.two"))]),
                 negated: false,
@@ -247,8 +348,9 @@ mod tests {
         assert_eq!(
             XRelatedToProperty {
                 params: XRelatedToPropertyParams {
-                    reltype: Reltype::Parent,
+                    reltypes: List::from(vec![Reltype::Parent]),
                     op: WhereOperator::And,
+                    depth: None,
                 },
                 uids: List::from(vec![Text(String::from("parent.uid.one")), Text(String::from("parent.uid.two"))]),
                 negated: true,
@@ -259,8 +361,9 @@ mod tests {
         assert_eq!(
             XRelatedToProperty {
                 params: XRelatedToPropertyParams {
-                    reltype: Reltype::XName(String::from("X-RELTYPE")),
+                    reltypes: List::from(vec![Reltype::XName(String::from("X-RELTYPE"))]),
                     op: WhereOperator::Or,
+                    depth: None,
                 },
                 uids: List::from(vec![Text(String::from("x-reltype.uid.one")), Text(String::from("x-reltype.uid.two"))]),
                 negated: false,
@@ -271,13 +374,53 @@ mod tests {
         assert_eq!(
             XRelatedToProperty {
                 params: XRelatedToPropertyParams {
-                    reltype: Reltype::XName(String::from("X-RELTYPE")),
+                    reltypes: List::from(vec![Reltype::XName(String::from("X-RELTYPE"))]),
                     op: WhereOperator::Or,
+                    depth: None,
                 },
                 uids: List::from(vec![Text(String::from("x-reltype.uid.one")), Text(String::from("x-reltype.uid.two"))]),
                 negated: true,
             }.render_ical(),
             String::from("X-RELATED-TO-NOT;RELTYPE=X-RELTYPE;OP=OR:x-reltype.uid.one,x-reltype.uid.two"),
         );
+
+        assert_eq!(
+            XRelatedToProperty {
+                params: XRelatedToPropertyParams {
+                    reltypes: List::from(vec![Reltype::Parent]),
+                    op: WhereOperator::And,
+                    depth: Some(Integer(3)),
+                },
+                uids: List::from(vec![Text(String::from("root.uid"))]),
+                negated: false,
+            }.render_ical(),
+            String::from("X-RELATED-TO;RELTYPE=PARENT;OP=AND;DEPTH=3:root.uid"),
+        );
+
+        assert_eq!(
+            XRelatedToProperty {
+                params: XRelatedToPropertyParams {
+                    reltypes: List::from(vec![Reltype::Sibling, Reltype::Parent]),
+                    op: WhereOperator::And,
+                    depth: None,
+                },
+                uids: List::from(vec![Text(String::from("some.uid"))]),
+                negated: true,
+            }.render_ical(),
+            String::from("X-RELATED-TO-NOT;RELTYPE=PARENT,SIBLING;OP=AND:some.uid"),
+        );
+
+        assert_eq!(
+            XRelatedToProperty {
+                params: XRelatedToPropertyParams {
+                    reltypes: List::from(vec![Reltype::Parent, Reltype::Sibling, Reltype::Parent]),
+                    op: WhereOperator::And,
+                    depth: None,
+                },
+                uids: List::from(vec![Text(String::from("some.uid"))]),
+                negated: false,
+            }.get_reltypes(),
+            vec![Reltype::Parent, Reltype::Sibling],
+        );
     }
 }
@@ -0,0 +1,112 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::multi::separated_list1;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon, comma, name};
+
+use crate::properties::ICalendarProperty;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Query return property, restricts the properties rendered for each result to those specified.
+///
+/// Example:
+///
+/// X-RETURN:UID,DTSTART,SUMMARY
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XReturnProperty {
+    pub property_names: Vec<String>,
+}
+
+impl ICalendarEntity for XReturnProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-RETURN",
+            preceded(
+                tag("X-RETURN"),
+                cut(
+                    map(
+                        preceded(colon, separated_list1(comma, name)),
+                        |property_names| {
+                            XReturnProperty {
+                                property_names: property_names
+                                    .into_iter()
+                                    .map(|property_name| property_name.to_string().to_uppercase())
+                                    .collect(),
+                            }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XReturnProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-RETURN",
+            (
+                ContentLineParams::default(),
+                self.property_names.join(","),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XReturnProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XReturnProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XReturnProperty::parse_ical("X-RETURN:UID,dtstart,X-SOMETHING DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XReturnProperty {
+                    property_names: vec![
+                        String::from("UID"),
+                        String::from("DTSTART"),
+                        String::from("X-SOMETHING"),
+                    ],
+                },
+            ),
+        );
+
+        assert!(XReturnProperty::parse_ical("X-RETURN:".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XReturnProperty {
+                property_names: vec![
+                    String::from("UID"),
+                    String::from("DTSTART"),
+                    String::from("SUMMARY"),
+                ],
+            }.render_ical(),
+            String::from("X-RETURN:UID,DTSTART,SUMMARY"),
+        );
+    }
+}
@@ -1,17 +1,91 @@
 use nom::error::context;
-use nom::sequence::{pair, preceded};
+use nom::sequence::{pair, preceded, tuple};
 use nom::combinator::{map, cut, opt};
 
-use crate::grammar::{tag, colon};
+use crate::grammar::{tag, semicolon, colon};
 
 use crate::values::text::Text;
 use crate::values::list::List;
 
-use crate::properties::ICalendarProperty;
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
 
 use crate::content_line::{ContentLineParams, ContentLine};
 
-use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+// OP = "REGEX"
+//
+// ;Default matches the UIDs exactly (or by prefix with a trailing "*" wildcard).
+//
+// ;REGEX matches UIDs matching any of the (regular expression) values.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub enum OpValue {
+    #[default]
+    Exact,
+    Regex,
+}
+
+impl ICalendarEntity for OpValue {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "OP",
+            map_err_message!(
+                map(tag("REGEX"), |_| OpValue::Regex),
+                "expected \"REGEX\"",
+            ),
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, _context: Option<&RenderingContext>) -> String {
+        match self {
+           Self::Exact => String::new(),
+           Self::Regex => String::from("REGEX"),
+        }
+    }
+}
+
+impl_icalendar_entity_traits!(OpValue);
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct XUIDPropertyParams {
+    pub op: OpValue,
+}
+
+impl ICalendarEntity for XUIDPropertyParams {
+    define_property_params_ical_parser!(
+        XUIDPropertyParams,
+        (
+            pair(tag("OP"), cut(preceded(tag("="), OpValue::parse_ical))),
+            |params: &mut XUIDPropertyParams, (_key, value): (ParserInput, OpValue)| params.op = value,
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for XUIDPropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    ///
+    /// The (default) exact matching OP is omitted as it cannot be specified explicitly.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        if self.op != OpValue::Exact {
+            content_line_params.insert(String::from("OP"), self.op.render_ical());
+        }
+
+        content_line_params
+    }
+}
+
+impl From<XUIDPropertyParams> for ContentLineParams {
+    fn from(uid_params: XUIDPropertyParams) -> Self {
+        ContentLineParams::from(&uid_params)
+    }
+}
 
 /// Query UID where condition property.
 ///
@@ -19,8 +93,15 @@ use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_i
 ///
 /// X-UID:UID_ONE
 /// X-UID:UID_ONE,UID_TWO (equivalent X-UID:UID_ONE OR X-UID:UID_TWO)
+/// X-UID;OP=REGEX:TENANT_[0-9]+_ONE (any UID matching the pattern)
+///
+/// Negated:
+///
+/// X-UID-NOT:UID_ONE
+/// X-UID-NOT;OP=REGEX:TENANT_[0-9]+_ONE
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct XUIDProperty {
+    pub params: XUIDPropertyParams,
     pub uids: List<Text>,
     pub negated: bool,
 }
@@ -33,12 +114,16 @@ impl ICalendarEntity for XUIDProperty {
                 tag("X-UID"),
                 cut(
                     map(
-                        pair(
-                            opt(tag("-NOT")),
-                            preceded(colon, List::parse_ical),
+                        tuple(
+                            (
+                                opt(tag("-NOT")),
+                                opt(XUIDPropertyParams::parse_ical),
+                                preceded(colon, List::parse_ical),
+                            )
                         ),
-                        |(not, uids)| {
+                        |(not, params, uids)| {
                             XUIDProperty {
+                                params: params.unwrap_or_default(),
                                 uids,
                                 negated: not.is_some(),
                             }
@@ -63,7 +148,7 @@ impl ICalendarProperty for XUIDProperty {
         ContentLine::from((
             property,
             (
-                ContentLineParams::default(),
+                ContentLineParams::from(&self.params),
                 self.uids.to_string(),
             )
         ))
@@ -102,6 +187,7 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XUIDProperty {
+                    params: XUIDPropertyParams::default(),
                     uids: List::from(vec![Text(String::from("UID_ONE"))]),
                     negated: false,
                 },
@@ -113,6 +199,7 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XUIDProperty {
+                    params: XUIDPropertyParams::default(),
                     uids: List::from(vec![Text(String::from("UID_ONE"))]),
                     negated: true,
                 },
@@ -124,6 +211,7 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XUIDProperty {
+                    params: XUIDPropertyParams::default(),
                     uids: List::from(vec![Text(String::from("UID_ONE")), Text(String::from("UID_TWO"))]),
                     negated: false,
                 },
@@ -135,12 +223,37 @@ mod tests {
             (
                 " DESCRIPTION:Description text",
                 XUIDProperty {
+                    params: XUIDPropertyParams::default(),
                     uids: List::from(vec![Text(String::from("UID_ONE")), Text(String::from("UID_TWO"))]),
                     negated: true,
                 },
             ),
         );
 
+        assert_parser_output!(
+            XUIDProperty::parse_ical("X-UID;OP=REGEX:TENANT_[0-9]+_ONE,UID_TWO DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XUIDProperty {
+                    params: XUIDPropertyParams { op: OpValue::Regex },
+                    uids: List::from(vec![Text(String::from("TENANT_[0-9]+_ONE")), Text(String::from("UID_TWO"))]),
+                    negated: false,
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XUIDProperty::parse_ical("X-UID-NOT;OP=REGEX:TENANT_[0-9]+_ONE DESCRIPTION:Description text".into()),
+            (
+                " DESCRIPTION:Description text",
+                XUIDProperty {
+                    params: XUIDPropertyParams { op: OpValue::Regex },
+                    uids: List::from(vec![Text(String::from("TENANT_[0-9]+_ONE"))]),
+                    negated: true,
+                },
+            ),
+        );
+
         assert!(XUIDProperty::parse_ical(":".into()).is_err());
         assert!(XUIDProperty::parse_ical("X-UID;OP=OR:UID_ONE".into()).is_err());
         assert!(XUIDProperty::parse_ical("X-UID;OP=AND:UID_ONE".into()).is_err());
@@ -150,6 +263,7 @@ mod tests {
     fn render_ical() {
         assert_eq!(
             XUIDProperty {
+                params: XUIDPropertyParams::default(),
                 uids: List::from(vec![Text(String::from("UID_ONE"))]),
                 negated: false,
             }.render_ical(),
@@ -158,6 +272,7 @@ mod tests {
 
         assert_eq!(
             XUIDProperty {
+                params: XUIDPropertyParams::default(),
                 uids: List::from(vec![Text(String::from("UID_ONE"))]),
                 negated: true,
             }.render_ical(),
@@ -166,6 +281,7 @@ mod tests {
 
         assert_eq!(
             XUIDProperty {
+                params: XUIDPropertyParams::default(),
                 uids: List::from(vec![Text(String::from("UID_ONE")), Text(String::from("UID_TWO"))]),
                 negated: false,
             }.render_ical(),
@@ -174,10 +290,20 @@ mod tests {
 
         assert_eq!(
             XUIDProperty {
+                params: XUIDPropertyParams::default(),
                 uids: List::from(vec![Text(String::from("UID_ONE")), Text(String::from("UID_TWO"))]),
                 negated: true,
             }.render_ical(),
             String::from("X-UID-NOT:UID_ONE,UID_TWO"),
         );
+
+        assert_eq!(
+            XUIDProperty {
+                params: XUIDPropertyParams { op: OpValue::Regex },
+                uids: List::from(vec![Text(String::from("TENANT_[0-9]+_ONE"))]),
+                negated: true,
+            }.render_ical(),
+            String::from("X-UID-NOT;OP=REGEX:TENANT_[0-9]+_ONE"),
+        );
     }
 }
@@ -0,0 +1,244 @@
+use itertools::Itertools;
+
+use nom::error::context;
+use nom::branch::alt;
+use nom::sequence::{pair, preceded};
+use nom::multi::separated_list1;
+use nom::combinator::{recognize, map, cut, opt, verify};
+
+use crate::grammar::{tag, semicolon, colon, comma, x_name, iana_token, param_value};
+
+use crate::values::duration::Duration;
+
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+use std::collections::HashMap;
+
+// The VALUE parameter (required by RFC 7986) is only permitted to be DURATION, so it is accepted
+// when omitted and always rendered.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct RefreshIntervalPropertyParams {
+    pub other: HashMap<String, String>,
+}
+
+impl ICalendarEntity for RefreshIntervalPropertyParams {
+    define_property_params_ical_parser!(
+        RefreshIntervalPropertyParams,
+        (
+            pair(
+                tag("VALUE"),
+                cut(
+                    preceded(
+                        tag("="),
+                        context(
+                            "VALUE",
+                            map_err_message!(
+                                tag("DURATION"),
+                                "expected iCalendar RFC-7986 VALUE (\"DURATION\")",
+                            ),
+                        ),
+                    )
+                ),
+            ),
+            |_params: &mut RefreshIntervalPropertyParams, (_key, _value): (ParserInput, ParserInput)| {},
+        ),
+        (
+            pair(alt((x_name, iana_token)), cut(preceded(tag("="), recognize(separated_list1(comma, param_value))))),
+            |params: &mut RefreshIntervalPropertyParams, (key, value): (ParserInput, ParserInput)| params.other.insert(key.to_string(), value.to_string()),
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for RefreshIntervalPropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        for (key, value) in self.other.clone().into_iter().sorted() {
+            content_line_params.insert(key.to_owned(), value.to_owned());
+        }
+
+        content_line_params.insert(String::from("VALUE"), String::from("DURATION"));
+
+        content_line_params
+    }
+}
+
+impl From<RefreshIntervalPropertyParams> for ContentLineParams {
+    fn from(refresh_interval_params: RefreshIntervalPropertyParams) -> Self {
+        ContentLineParams::from(&refresh_interval_params)
+    }
+}
+
+// Refresh Interval (RFC 7986)
+//
+// Property Name:  REFRESH-INTERVAL
+//
+// Purpose:  This property specifies a suggested minimum interval for
+//    polling for changes of the calendar data from the original source
+//    of that data.
+//
+// Value Type:  DURATION -- no default
+//
+// Property Parameters:  IANA and non-standard property parameters can
+//    be specified on this property.
+//
+// Conformance:  This property can be specified once in an iCalendar
+//    object, consisting of a positive duration of time.
+//
+// Format Definition:  This property is defined by the following
+//    notation:
+//
+//     refresh      = "REFRESH-INTERVAL" refreshparam
+//                    ":" dur-value CRLF
+//                    ;consisting of a positive duration of time.
+//
+//     refreshparam = *(
+//                    ;
+//                    ; The following is REQUIRED,
+//                    ; but MUST NOT occur more than once.
+//                    ;
+//                    (";" "VALUE" "=" "DURATION") /
+//                    ;
+//                    ; The following is OPTIONAL,
+//                    ; and MAY occur more than once.
+//                    ;
+//                    (";" other-param)
+//                    ;
+//                    )
+//
+// Example:  The following is an example of this property:
+//
+//     REFRESH-INTERVAL;VALUE=DURATION:P1W
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RefreshIntervalProperty {
+    pub params: RefreshIntervalPropertyParams,
+    pub duration: Duration,
+}
+
+impl ICalendarEntity for RefreshIntervalProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "REFRESH-INTERVAL",
+            preceded(
+                tag("REFRESH-INTERVAL"),
+                cut(
+                    map(
+                        pair(
+                            opt(RefreshIntervalPropertyParams::parse_ical),
+                            preceded(
+                                colon,
+                                verify(
+                                    Duration::parse_ical,
+                                    |duration: &Duration| duration.get_duration_in_seconds() > 0,
+                                ),
+                            ),
+                        ),
+                        |(params, duration)| {
+                            RefreshIntervalProperty {
+                                params: params.unwrap_or(RefreshIntervalPropertyParams::default()),
+                                duration,
+                            }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for RefreshIntervalProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "REFRESH-INTERVAL",
+            (
+                ContentLineParams::from(&self.params),
+                self.duration.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for RefreshIntervalProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(RefreshIntervalProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            RefreshIntervalProperty::parse_ical("REFRESH-INTERVAL;VALUE=DURATION:P1W NAME:Calendar".into()),
+            (
+                " NAME:Calendar",
+                RefreshIntervalProperty {
+                    params: RefreshIntervalPropertyParams::default(),
+                    duration: Duration {
+                        weeks: Some(1),
+                        ..Duration::default()
+                    },
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            RefreshIntervalProperty::parse_ical("REFRESH-INTERVAL;X-TEST=X_VALUE:PT12H".into()),
+            (
+                "",
+                RefreshIntervalProperty {
+                    params: RefreshIntervalPropertyParams {
+                        other: HashMap::from([
+                            (String::from("X-TEST"), String::from("X_VALUE")),
+                        ]),
+                    },
+                    duration: Duration {
+                        hours: Some(12),
+                        ..Duration::default()
+                    },
+                },
+            ),
+        );
+
+        assert!(RefreshIntervalProperty::parse_ical("REFRESH-INTERVAL;VALUE=DATE-TIME:P1W".into()).is_err());
+        assert!(RefreshIntervalProperty::parse_ical("REFRESH-INTERVAL:-P1W".into()).is_err());
+        assert!(RefreshIntervalProperty::parse_ical("REFRESH-INTERVAL:PT0S".into()).is_err());
+        assert!(RefreshIntervalProperty::parse_ical("REFRESH-INTERVAL:SOMETHING".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            RefreshIntervalProperty {
+                params: RefreshIntervalPropertyParams::default(),
+                duration: Duration {
+                    hours: Some(12),
+                    ..Duration::default()
+                },
+            }.render_ical(),
+            String::from("REFRESH-INTERVAL;VALUE=DURATION:PT12H"),
+        );
+    }
+}
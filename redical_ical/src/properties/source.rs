@@ -0,0 +1,194 @@
+use itertools::Itertools;
+
+use nom::error::context;
+use nom::branch::alt;
+use nom::sequence::{pair, preceded};
+use nom::multi::separated_list1;
+use nom::combinator::{recognize, map, cut, opt};
+
+use crate::grammar::{tag, semicolon, colon, comma, x_name, iana_token, param_value};
+
+use crate::values::uri::Uri;
+
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct SourcePropertyParams {
+    pub other: HashMap<String, String>,
+}
+
+impl ICalendarEntity for SourcePropertyParams {
+    define_property_params_ical_parser!(
+        SourcePropertyParams,
+        (
+            pair(alt((x_name, iana_token)), cut(preceded(tag("="), recognize(separated_list1(comma, param_value))))),
+            |params: &mut SourcePropertyParams, (key, value): (ParserInput, ParserInput)| params.other.insert(key.to_string(), value.to_string()),
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for SourcePropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        for (key, value) in self.other.clone().into_iter().sorted() {
+            content_line_params.insert(key.to_owned(), value.to_owned());
+        }
+
+        content_line_params
+    }
+}
+
+impl From<SourcePropertyParams> for ContentLineParams {
+    fn from(source_params: SourcePropertyParams) -> Self {
+        ContentLineParams::from(&source_params)
+    }
+}
+
+// Source (RFC 7986)
+//
+// Property Name:  SOURCE
+//
+// Purpose:  This property identifies a URI where calendar data can be
+//    refreshed from.
+//
+// Value Type:  URI -- no default
+//
+// Property Parameters:  IANA and non-standard property parameters can
+//    be specified on this property.
+//
+// Conformance:  This property can be specified once in an iCalendar
+//    object.
+//
+// Format Definition:  This property is defined by the following
+//    notation:
+//
+//     source      = "SOURCE" sourceparam ":" uri CRLF
+//
+//     sourceparam = *(";" other-param)
+//
+// Example:  The following is an example of this property:
+//
+//     SOURCE;VALUE=URI:https://example.com/holidays.ics
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SourceProperty {
+    pub params: SourcePropertyParams,
+    pub uri: Uri,
+}
+
+impl ICalendarEntity for SourceProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "SOURCE",
+            preceded(
+                tag("SOURCE"),
+                cut(
+                    map(
+                        pair(
+                            opt(SourcePropertyParams::parse_ical),
+                            preceded(colon, Uri::parse_ical),
+                        ),
+                        |(params, uri)| {
+                            SourceProperty {
+                                params: params.unwrap_or(SourcePropertyParams::default()),
+                                uri,
+                            }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for SourceProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "SOURCE",
+            (
+                ContentLineParams::from(&self.params),
+                self.uri.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for SourceProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(SourceProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            SourceProperty::parse_ical("SOURCE:https://example.com/holidays.ics NAME:Calendar".into()),
+            (
+                " NAME:Calendar",
+                SourceProperty {
+                    params: SourcePropertyParams::default(),
+                    uri: Uri(String::from("https://example.com/holidays.ics")),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            SourceProperty::parse_ical("SOURCE;VALUE=URI:https://example.com/holidays.ics".into()),
+            (
+                "",
+                SourceProperty {
+                    params: SourcePropertyParams {
+                        other: HashMap::from([
+                            (String::from("VALUE"), String::from("URI")),
+                        ]),
+                    },
+                    uri: Uri(String::from("https://example.com/holidays.ics")),
+                },
+            ),
+        );
+
+        assert!(SourceProperty::parse_ical("SOURCE:example.com/holidays.ics".into()).is_err());
+        assert!(SourceProperty::parse_ical(":".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            SourceProperty {
+                params: SourcePropertyParams {
+                    other: HashMap::from([
+                        (String::from("VALUE"), String::from("URI")),
+                    ]),
+                },
+                uri: Uri(String::from("https://example.com/holidays.ics")),
+            }.render_ical(),
+            String::from("SOURCE;VALUE=URI:https://example.com/holidays.ics"),
+        );
+    }
+}
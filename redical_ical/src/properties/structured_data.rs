@@ -0,0 +1,395 @@
+use itertools::Itertools;
+
+use nom::error::context;
+use nom::branch::alt;
+use nom::sequence::{pair, preceded};
+use nom::multi::separated_list1;
+use nom::combinator::{recognize, map, cut, opt};
+
+use crate::grammar::{tag, semicolon, colon, comma, x_name, iana_token, param_value};
+
+use crate::values::text::Text;
+use crate::values::uri::Uri;
+use crate::values::binary::Binary;
+
+use crate::properties::{ICalendarProperty, ICalendarPropertyParams, define_property_params_ical_parser};
+
+use crate::content_line::{ContentLineParams, ContentLine};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct StructuredDataPropertyParams {
+    pub encoding: Option<String>,
+    pub fmttype: Option<String>,
+    pub schema: Option<String>,
+    pub value_type: Option<String>,
+    pub other: HashMap<String, String>,
+}
+
+impl ICalendarEntity for StructuredDataPropertyParams {
+    define_property_params_ical_parser!(
+        StructuredDataPropertyParams,
+        (
+            pair(tag("ENCODING"), cut(preceded(tag("="), alt((tag("8BIT"), tag("BASE64")))))),
+            |params: &mut StructuredDataPropertyParams, (_key, value): (ParserInput, ParserInput)| params.encoding = Some(value.to_string()),
+        ),
+        (
+            pair(tag("FMTTYPE"), cut(preceded(tag("="), param_value))),
+            |params: &mut StructuredDataPropertyParams, (_key, value): (ParserInput, ParserInput)| params.fmttype = Some(value.to_string()),
+        ),
+        (
+            pair(tag("SCHEMA"), cut(preceded(tag("="), param_value))),
+            |params: &mut StructuredDataPropertyParams, (_key, value): (ParserInput, ParserInput)| params.schema = Some(value.to_string()),
+        ),
+        (
+            pair(tag("VALUE"), cut(preceded(tag("="), alt((tag("TEXT"), tag("URI"), tag("BINARY")))))),
+            |params: &mut StructuredDataPropertyParams, (_key, value): (ParserInput, ParserInput)| params.value_type = Some(value.to_string()),
+        ),
+        (
+            pair(alt((x_name, iana_token)), cut(preceded(tag("="), recognize(separated_list1(comma, param_value))))),
+            |params: &mut StructuredDataPropertyParams, (key, value): (ParserInput, ParserInput)| params.other.insert(key.to_string(), value.to_string()),
+        ),
+    );
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_params_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarPropertyParams for StructuredDataPropertyParams {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_params_with_context(&self, _context: Option<&RenderingContext>) -> ContentLineParams {
+        let mut content_line_params = ContentLineParams::default();
+
+        for (key, value) in self.other.clone().into_iter().sorted() {
+            content_line_params.insert(key.to_owned(), value.to_owned());
+        }
+
+        if let Some(encoding) = self.encoding.as_ref() {
+            content_line_params.insert(String::from("ENCODING"), encoding.to_owned());
+        }
+
+        if let Some(fmttype) = self.fmttype.as_ref() {
+            content_line_params.insert(String::from("FMTTYPE"), fmttype.to_owned());
+        }
+
+        if let Some(schema) = self.schema.as_ref() {
+            content_line_params.insert(String::from("SCHEMA"), schema.to_owned());
+        }
+
+        if let Some(value_type) = self.value_type.as_ref() {
+            content_line_params.insert(String::from("VALUE"), value_type.to_owned());
+        }
+
+        content_line_params
+    }
+}
+
+impl From<StructuredDataPropertyParams> for ContentLineParams {
+    fn from(structured_data_params: StructuredDataPropertyParams) -> Self {
+        ContentLineParams::from(&structured_data_params)
+    }
+}
+
+/// The structured data inlined as TEXT (the default), referenced by URI (`VALUE=URI`), or inlined
+/// as BASE64 encoded binary data (`VALUE=BINARY` with `ENCODING=BASE64`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum StructuredDataValue {
+    Text(Text),
+    Uri(Uri),
+    Binary(Binary),
+}
+
+impl StructuredDataValue {
+    /// Parse the value of the type declared by the VALUE (and ENCODING) params, as the TEXT value
+    /// is otherwise indistinguishable from the others.
+    pub fn parse_ical_for_params(params: &StructuredDataPropertyParams) -> impl FnMut(ParserInput) -> ParserResult<Self> + '_ {
+        move |input: ParserInput| {
+            match (params.value_type.as_deref(), params.encoding.as_deref()) {
+                (None | Some("TEXT"), None) => {
+                    map(Text::parse_ical, StructuredDataValue::Text)(input)
+                },
+
+                (Some("URI"), None) => {
+                    map(Uri::parse_ical, StructuredDataValue::Uri)(input)
+                },
+
+                (Some("BINARY"), Some("BASE64")) => {
+                    map(Binary::parse_ical, StructuredDataValue::Binary)(input)
+                },
+
+                _ => {
+                    Err(
+                        nom::Err::Error(
+                            crate::ParserError::new(
+                                String::from("unexpected VALUE and ENCODING param combination"),
+                                input,
+                            )
+                        )
+                    )
+                },
+            }
+        }
+    }
+}
+
+impl ICalendarEntity for StructuredDataValue {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        Self::parse_ical_for_params(&StructuredDataPropertyParams::default())(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        match self {
+            StructuredDataValue::Text(text) => text.render_ical_with_context(context),
+            StructuredDataValue::Uri(uri) => uri.render_ical_with_context(context),
+            StructuredDataValue::Binary(binary) => binary.render_ical_with_context(context),
+        }
+    }
+}
+
+impl_icalendar_entity_traits!(StructuredDataValue);
+
+// Structured-Data (RFC 9073)
+//
+// Property Name:  STRUCTURED-DATA
+//
+// Purpose:  This property specifies ancillary data associated with the
+//    calendar component.
+//
+// Value Type:  TEXT, BINARY, or URI
+//
+// Property Parameters:  IANA and non-standard property parameters can
+//    be specified on this property.  The format type and schema
+//    parameters can be specified on this property and are RECOMMENDED
+//    for text or inline binary-encoded content information.
+//
+// Conformance:  This property can be specified multiple times in an
+//    iCalendar object.  Typically, it would be used in "VEVENT",
+//    "VTODO", or "VJOURNAL" calendar components, as well as the
+//    "PARTICIPANT", "VLOCATION", and "VRESOURCE" components.
+//
+// Description:  The value of this property may be defined by a schema
+//    that is identified by the "SCHEMA" parameter, and the media type
+//    is identified by the "FMTTYPE" parameter.
+//
+// Format Definition:  This property is defined by the following
+//    notation:
+//
+//     sdataprop   = "STRUCTURED-DATA" sdataparam
+//                   (":" text) /
+//                   (
+//                     ";" "ENCODING" "=" "BASE64"
+//                     ";" "VALUE" "=" "BINARY"
+//                     ":" binary
+//                   ) /
+//                   (
+//                     ";" "VALUE" "=" "URI"
+//                     ":" uri
+//                   )
+//                   CRLF
+//
+//     sdataparam  = *(
+//                   ;
+//                   ; The following is REQUIRED,
+//                   ; but MUST NOT occur more than once.
+//                   ;
+//                   (";" fmttypeparam) /
+//                   (";" schemaparam) /
+//                   ;
+//                   ; The following is OPTIONAL,
+//                   ; and MAY occur more than once.
+//                   ;
+//                   (";" other-param)
+//                   ;
+//                   )
+//
+// Example:  The following is an example of this property:
+//
+//     STRUCTURED-DATA;FMTTYPE=application/ld+json;
+//      SCHEMA="https://schema.org/SportsEvent";
+//      VALUE=TEXT:{\n
+//      "@context": "http://schema.org"\,\n
+//      "@type": "SportsEvent"\,\n
+//      "homeTeam": "Pittsburgh Pirates"\,\n
+//      "awayTeam": "San Francisco Giants"\n
+//      }\n
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StructuredDataProperty {
+    pub params: StructuredDataPropertyParams,
+    pub value: StructuredDataValue,
+}
+
+impl StructuredDataProperty {
+    // The value type depends on the parsed VALUE and ENCODING params.
+    fn parse_params_and_value(input: ParserInput) -> ParserResult<Self> {
+        let (remaining, params) = map(opt(StructuredDataPropertyParams::parse_ical), Option::unwrap_or_default)(input)?;
+
+        let (remaining, value) =
+            preceded(
+                colon,
+                map_err_message!(
+                    StructuredDataValue::parse_ical_for_params(&params),
+                    "expected either a TEXT value, a URI value with VALUE=URI, or a BINARY value with VALUE=BINARY and ENCODING=BASE64 params",
+                ),
+            )(remaining)?;
+
+        Ok((remaining, StructuredDataProperty { params, value }))
+    }
+}
+
+impl ICalendarEntity for StructuredDataProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "STRUCTURED-DATA",
+            preceded(
+                tag("STRUCTURED-DATA"),
+                cut(Self::parse_params_and_value),
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for StructuredDataProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "STRUCTURED-DATA",
+            (
+                ContentLineParams::from(&self.params),
+                self.value.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for StructuredDataProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(StructuredDataProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    use crate::ParserContext;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            StructuredDataProperty::parse_ical(
+                ParserInput::new_extra(
+                    "STRUCTURED-DATA;FMTTYPE=application/ld+json;SCHEMA=\"https://schema.org/SportsEvent\";VALUE=TEXT:{\"@type\": \"SportsEvent\"\\, \"homeTeam\": \"Pittsburgh Pirates\"} SUMMARY:Game",
+                    ParserContext::Event,
+                )
+            ),
+            (
+                " SUMMARY:Game",
+                StructuredDataProperty {
+                    params: StructuredDataPropertyParams {
+                        encoding: None,
+                        fmttype: Some(String::from("application/ld+json")),
+                        schema: Some(String::from("\"https://schema.org/SportsEvent\"")),
+                        value_type: Some(String::from("TEXT")),
+                        other: HashMap::new(),
+                    },
+                    value: StructuredDataValue::Text(Text(String::from("{\"@type\": \"SportsEvent\"\\, \"homeTeam\": \"Pittsburgh Pirates\"}"))),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            StructuredDataProperty::parse_ical("STRUCTURED-DATA;X-TEST=X_VALUE;VALUE=URI:https://example.com/events/game.jsonld".into()),
+            (
+                "",
+                StructuredDataProperty {
+                    params: StructuredDataPropertyParams {
+                        encoding: None,
+                        fmttype: None,
+                        schema: None,
+                        value_type: Some(String::from("URI")),
+                        other: HashMap::from([
+                            (String::from("X-TEST"), String::from("X_VALUE")),
+                        ]),
+                    },
+                    value: StructuredDataValue::Uri(Uri(String::from("https://example.com/events/game.jsonld"))),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            StructuredDataProperty::parse_ical("STRUCTURED-DATA;ENCODING=BASE64;VALUE=BINARY;FMTTYPE=application/ld+json:eyJAdHlwZSI6IkV2ZW50In0=".into()),
+            (
+                "",
+                StructuredDataProperty {
+                    params: StructuredDataPropertyParams {
+                        encoding: Some(String::from("BASE64")),
+                        fmttype: Some(String::from("application/ld+json")),
+                        schema: None,
+                        value_type: Some(String::from("BINARY")),
+                        other: HashMap::new(),
+                    },
+                    value: StructuredDataValue::Binary(Binary(String::from("eyJAdHlwZSI6IkV2ZW50In0="))),
+                },
+            ),
+        );
+
+        // TEXT values are the default, and retain their URI-like values as such.
+        assert_parser_output!(
+            StructuredDataProperty::parse_ical("STRUCTURED-DATA:https://example.com/events/game.jsonld".into()),
+            (
+                "",
+                StructuredDataProperty {
+                    params: StructuredDataPropertyParams::default(),
+                    value: StructuredDataValue::Text(Text(String::from("https://example.com/events/game.jsonld"))),
+                },
+            ),
+        );
+
+        assert!(StructuredDataProperty::parse_ical("STRUCTURED-DATA;VALUE=URI:game.jsonld".into()).is_err());
+        assert!(StructuredDataProperty::parse_ical("STRUCTURED-DATA;VALUE=BINARY:eyJAdHlwZSI6IkV2ZW50In0=".into()).is_err());
+        assert!(StructuredDataProperty::parse_ical("STRUCTURED-DATA;ENCODING=BASE64;VALUE=URI:https://example.com".into()).is_err());
+        assert!(StructuredDataProperty::parse_ical("STRUCTURED-DATA;VALUE=DATE:20210105".into()).is_err());
+        assert!(StructuredDataProperty::parse_ical(":".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            StructuredDataProperty {
+                params: StructuredDataPropertyParams::default(),
+                value: StructuredDataValue::Text(Text(String::from("{\"@type\": \"Event\"}"))),
+            }.render_ical(),
+            String::from("STRUCTURED-DATA:{\"@type\": \"Event\"}"),
+        );
+
+        assert_eq!(
+            StructuredDataProperty {
+                params: StructuredDataPropertyParams {
+                    encoding: None,
+                    fmttype: Some(String::from("application/ld+json")),
+                    schema: Some(String::from("\"https://schema.org/SportsEvent\"")),
+                    value_type: Some(String::from("URI")),
+                    other: HashMap::from([
+                        (String::from("X-TEST"), String::from("X_VALUE")),
+                    ]),
+                },
+                value: StructuredDataValue::Uri(Uri(String::from("https://example.com/events/game.jsonld"))),
+            }.render_ical(),
+            String::from("STRUCTURED-DATA;X-TEST=X_VALUE;FMTTYPE=application/ld+json;SCHEMA=\"https://schema.org/SportsEvent\";VALUE=URI:https://example.com/events/game.jsonld"),
+        );
+    }
+}
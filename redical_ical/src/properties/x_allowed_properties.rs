@@ -0,0 +1,129 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::multi::separated_list1;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon, comma, name};
+
+use crate::properties::ICalendarProperty;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Calendar allowed properties property.
+///
+/// Declares the only properties (or components, e.g. VALARM) that events (excluding occurrence
+/// overrides) set on the Calendar may specify, with events specifying any others being rejected.
+///
+/// The UID and LAST-MODIFIED properties (as well as any required properties) are always allowed.
+///
+/// Example:
+///
+/// X-ALLOWED-PROPERTIES:DTSTART,RRULE,SUMMARY
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XAllowedPropertiesProperty {
+    pub property_names: Vec<String>,
+}
+
+impl XAllowedPropertiesProperty {
+    pub fn contains(&self, property_name: &str) -> bool {
+        self.property_names.iter().any(|declared_property_name| declared_property_name == property_name)
+    }
+}
+
+impl ICalendarEntity for XAllowedPropertiesProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-ALLOWED-PROPERTIES",
+            preceded(
+                tag("X-ALLOWED-PROPERTIES"),
+                cut(
+                    map(
+                        preceded(colon, separated_list1(comma, name)),
+                        |property_names| {
+                            let mut declared_property_names: Vec<String> = Vec::new();
+
+                            for property_name in property_names {
+                                let property_name = property_name.to_string().to_uppercase();
+
+                                if !declared_property_names.contains(&property_name) {
+                                    declared_property_names.push(property_name);
+                                }
+                            }
+
+                            XAllowedPropertiesProperty { property_names: declared_property_names }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XAllowedPropertiesProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-ALLOWED-PROPERTIES",
+            (
+                ContentLineParams::default(),
+                self.property_names.join(","),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XAllowedPropertiesProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XAllowedPropertiesProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XAllowedPropertiesProperty::parse_ical("X-ALLOWED-PROPERTIES:DTSTART,rrule,X-Level,DTSTART UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                XAllowedPropertiesProperty {
+                    property_names: vec![
+                        String::from("DTSTART"),
+                        String::from("RRULE"),
+                        String::from("X-LEVEL"),
+                    ],
+                },
+            ),
+        );
+
+        assert!(XAllowedPropertiesProperty::parse_ical("X-ALLOWED-PROPERTIES:,DTSTART".into()).is_err());
+        assert!(XAllowedPropertiesProperty::parse_ical("X-ALLOWED-PROPERTIES:".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XAllowedPropertiesProperty {
+                property_names: vec![
+                    String::from("DTSTART"),
+                    String::from("RRULE"),
+                    String::from("X-LEVEL"),
+                ],
+            }.render_ical(),
+            String::from("X-ALLOWED-PROPERTIES:DTSTART,RRULE,X-LEVEL"),
+        );
+    }
+}
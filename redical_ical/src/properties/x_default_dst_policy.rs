@@ -0,0 +1,103 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon};
+
+use crate::properties::ICalendarProperty;
+use crate::values::dst_policy::DstPolicyValue;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Calendar default DST policy property.
+///
+/// Determines how the recurrence instances of the Calendar's events landing on a local time
+/// skipped or repeated by a daylight saving time transition are resolved, unless a query
+/// specifies otherwise via X-DST-POLICY.
+///
+/// Example:
+///
+/// X-DEFAULT-DST-POLICY:SKIP
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XDefaultDstPolicyProperty {
+    pub dst_policy: DstPolicyValue,
+}
+
+impl ICalendarEntity for XDefaultDstPolicyProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-DEFAULT-DST-POLICY",
+            preceded(
+                tag("X-DEFAULT-DST-POLICY"),
+                cut(
+                    map(
+                        preceded(colon, DstPolicyValue::parse_ical),
+                        |dst_policy| {
+                            XDefaultDstPolicyProperty { dst_policy }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XDefaultDstPolicyProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-DEFAULT-DST-POLICY",
+            (
+                ContentLineParams::default(),
+                self.dst_policy.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XDefaultDstPolicyProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XDefaultDstPolicyProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XDefaultDstPolicyProperty::parse_ical("X-DEFAULT-DST-POLICY:BOTH-INSTANTS UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                XDefaultDstPolicyProperty {
+                    dst_policy: DstPolicyValue::BothInstants,
+                },
+            ),
+        );
+
+        assert!(XDefaultDstPolicyProperty::parse_ical("X-DEFAULT-DST-POLICY:SOMETHING".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XDefaultDstPolicyProperty {
+                dst_policy: DstPolicyValue::Skip,
+            }.render_ical(),
+            String::from("X-DEFAULT-DST-POLICY:SKIP"),
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use chrono_tz::Tz;
+
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon};
+
+use crate::properties::ICalendarProperty;
+use crate::values::tzid::Tzid;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Calendar default timezone property.
+///
+/// Date-time values (of the Calendar's events and queries) specified without a UTC designator or
+/// TZID are interpreted in this timezone, and query results are rendered in it when no X-TZID is
+/// specified.
+///
+/// Example:
+///
+/// X-DEFAULT-TZID:Europe/London
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XDefaultTzidProperty {
+    pub tzid: Tzid,
+}
+
+impl ICalendarEntity for XDefaultTzidProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-DEFAULT-TZID",
+            preceded(
+                tag("X-DEFAULT-TZID"),
+                cut(
+                    map(
+                        preceded(colon, Tzid::parse_ical),
+                        |tzid| {
+                            XDefaultTzidProperty { tzid }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XDefaultTzidProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-DEFAULT-TZID",
+            (
+                ContentLineParams::default(),
+                self.tzid.to_string(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XDefaultTzidProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl From<Tz> for XDefaultTzidProperty {
+    fn from(tz: Tz) -> Self {
+        XDefaultTzidProperty {
+            tzid: Tzid(tz),
+        }
+    }
+}
+
+impl From<&XDefaultTzidProperty> for Tz {
+    fn from(x_default_tzid_property: &XDefaultTzidProperty) -> Self {
+        Tz::from(x_default_tzid_property.tzid.to_owned())
+    }
+}
+
+impl_icalendar_entity_traits!(XDefaultTzidProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XDefaultTzidProperty::parse_ical("X-DEFAULT-TZID:UTC UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                XDefaultTzidProperty {
+                    tzid: Tzid(chrono_tz::Tz::UTC),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XDefaultTzidProperty::parse_ical("X-DEFAULT-TZID:Europe/London UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                XDefaultTzidProperty {
+                    tzid: Tzid(chrono_tz::Tz::Europe__London),
+                },
+            ),
+        );
+
+        assert!(XDefaultTzidProperty::parse_ical("X-DEFAULT-TZID:ssokko".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XDefaultTzidProperty {
+                tzid: Tzid(chrono_tz::Tz::UTC),
+            }.render_ical(),
+            String::from("X-DEFAULT-TZID:UTC"),
+        );
+
+        assert_eq!(
+            XDefaultTzidProperty {
+                tzid: Tzid(chrono_tz::Tz::Europe__London),
+            }.render_ical(),
+            String::from("X-DEFAULT-TZID:Europe/London"),
+        );
+    }
+}
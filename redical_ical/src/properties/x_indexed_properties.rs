@@ -0,0 +1,125 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::multi::separated_list1;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon, comma, x_name};
+
+use crate::properties::ICalendarProperty;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Calendar indexed properties property.
+///
+/// Opts the named passive X-properties of the Calendar events into being indexed (by their exact
+/// value), so that they can be queried with the `X-PROP;NAME=...` where condition property.
+///
+/// Example:
+///
+/// X-INDEXED-PROPERTIES:X-INSTRUCTOR,X-LEVEL
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XIndexedPropertiesProperty {
+    pub property_names: Vec<String>,
+}
+
+impl XIndexedPropertiesProperty {
+    pub fn contains(&self, property_name: &str) -> bool {
+        self.property_names.iter().any(|indexed_property_name| indexed_property_name == property_name)
+    }
+}
+
+impl ICalendarEntity for XIndexedPropertiesProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-INDEXED-PROPERTIES",
+            preceded(
+                tag("X-INDEXED-PROPERTIES"),
+                cut(
+                    map(
+                        preceded(colon, separated_list1(comma, x_name)),
+                        |property_names| {
+                            let mut indexed_property_names: Vec<String> = Vec::new();
+
+                            for property_name in property_names {
+                                let property_name = property_name.to_string().to_uppercase();
+
+                                if !indexed_property_names.contains(&property_name) {
+                                    indexed_property_names.push(property_name);
+                                }
+                            }
+
+                            XIndexedPropertiesProperty { property_names: indexed_property_names }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XIndexedPropertiesProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-INDEXED-PROPERTIES",
+            (
+                ContentLineParams::default(),
+                self.property_names.join(","),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XIndexedPropertiesProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XIndexedPropertiesProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XIndexedPropertiesProperty::parse_ical("X-INDEXED-PROPERTIES:X-INSTRUCTOR,X-Level,X-INSTRUCTOR UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                XIndexedPropertiesProperty {
+                    property_names: vec![
+                        String::from("X-INSTRUCTOR"),
+                        String::from("X-LEVEL"),
+                    ],
+                },
+            ),
+        );
+
+        assert!(XIndexedPropertiesProperty::parse_ical("X-INDEXED-PROPERTIES:SUMMARY".into()).is_err());
+        assert!(XIndexedPropertiesProperty::parse_ical("X-INDEXED-PROPERTIES:".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XIndexedPropertiesProperty {
+                property_names: vec![
+                    String::from("X-INSTRUCTOR"),
+                    String::from("X-LEVEL"),
+                ],
+            }.render_ical(),
+            String::from("X-INDEXED-PROPERTIES:X-INSTRUCTOR,X-LEVEL"),
+        );
+    }
+}
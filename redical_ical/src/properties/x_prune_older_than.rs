@@ -0,0 +1,116 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::combinator::{map, cut, verify};
+
+use crate::grammar::{tag, colon};
+
+use crate::properties::ICalendarProperty;
+use crate::values::duration::Duration;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Calendar retention property.
+///
+/// Events (and event occurrence overrides) ending longer ago than this (positive) duration are
+/// periodically pruned from the Calendar.
+///
+/// Example:
+///
+/// X-PRUNE-OLDER-THAN:P90D
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XPruneOlderThanProperty {
+    pub duration: Duration,
+}
+
+impl ICalendarEntity for XPruneOlderThanProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-PRUNE-OLDER-THAN",
+            preceded(
+                tag("X-PRUNE-OLDER-THAN"),
+                cut(
+                    map(
+                        preceded(
+                            colon,
+                            verify(
+                                Duration::parse_ical,
+                                |duration: &Duration| duration.get_duration_in_seconds() > 0,
+                            ),
+                        ),
+                        |duration| {
+                            XPruneOlderThanProperty { duration }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XPruneOlderThanProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-PRUNE-OLDER-THAN",
+            (
+                ContentLineParams::default(),
+                self.duration.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XPruneOlderThanProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XPruneOlderThanProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XPruneOlderThanProperty::parse_ical("X-PRUNE-OLDER-THAN:P90D UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                XPruneOlderThanProperty {
+                    duration: Duration {
+                        days: Some(90),
+                        ..Duration::default()
+                    },
+                },
+            ),
+        );
+
+        assert!(XPruneOlderThanProperty::parse_ical("X-PRUNE-OLDER-THAN:-P90D".into()).is_err());
+        assert!(XPruneOlderThanProperty::parse_ical("X-PRUNE-OLDER-THAN:PT0S".into()).is_err());
+        assert!(XPruneOlderThanProperty::parse_ical("X-PRUNE-OLDER-THAN:SOMETHING".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XPruneOlderThanProperty {
+                duration: Duration {
+                    weeks: Some(2),
+                    ..Duration::default()
+                },
+            }.render_ical(),
+            String::from("X-PRUNE-OLDER-THAN:P2W"),
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::multi::separated_list1;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon, comma, name};
+
+use crate::properties::ICalendarProperty;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Calendar required properties property.
+///
+/// Declares the properties (or components, e.g. VALARM) that every event (excluding occurrence
+/// overrides) set on the Calendar must specify, with events missing any of them being rejected.
+///
+/// Example:
+///
+/// X-REQUIRED-PROPERTIES:GEO,CATEGORIES
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XRequiredPropertiesProperty {
+    pub property_names: Vec<String>,
+}
+
+impl XRequiredPropertiesProperty {
+    pub fn contains(&self, property_name: &str) -> bool {
+        self.property_names.iter().any(|declared_property_name| declared_property_name == property_name)
+    }
+}
+
+impl ICalendarEntity for XRequiredPropertiesProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-REQUIRED-PROPERTIES",
+            preceded(
+                tag("X-REQUIRED-PROPERTIES"),
+                cut(
+                    map(
+                        preceded(colon, separated_list1(comma, name)),
+                        |property_names| {
+                            let mut declared_property_names: Vec<String> = Vec::new();
+
+                            for property_name in property_names {
+                                let property_name = property_name.to_string().to_uppercase();
+
+                                if !declared_property_names.contains(&property_name) {
+                                    declared_property_names.push(property_name);
+                                }
+                            }
+
+                            XRequiredPropertiesProperty { property_names: declared_property_names }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XRequiredPropertiesProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-REQUIRED-PROPERTIES",
+            (
+                ContentLineParams::default(),
+                self.property_names.join(","),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XRequiredPropertiesProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XRequiredPropertiesProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XRequiredPropertiesProperty::parse_ical("X-REQUIRED-PROPERTIES:GEO,categories,GEO UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                XRequiredPropertiesProperty {
+                    property_names: vec![
+                        String::from("GEO"),
+                        String::from("CATEGORIES"),
+                    ],
+                },
+            ),
+        );
+
+        assert!(XRequiredPropertiesProperty::parse_ical("X-REQUIRED-PROPERTIES:,GEO".into()).is_err());
+        assert!(XRequiredPropertiesProperty::parse_ical("X-REQUIRED-PROPERTIES:".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XRequiredPropertiesProperty {
+                property_names: vec![
+                    String::from("GEO"),
+                    String::from("CATEGORIES"),
+                ],
+            }.render_ical(),
+            String::from("X-REQUIRED-PROPERTIES:GEO,CATEGORIES"),
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::multi::separated_list1;
+use nom::combinator::{map, cut};
+
+use crate::grammar::{tag, colon, comma};
+
+use crate::properties::ICalendarProperty;
+use crate::values::list::List;
+use crate::values::term_normalization::TermNormalizationValue;
+
+use crate::content_line::{ContentLine, ContentLineParams};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits};
+
+/// Calendar term normalization property.
+///
+/// Determines how the CATEGORIES, LOCATION-TYPE, LOCATION, and CLASS terms of the Calendar events
+/// are normalised before they are indexed, with the queried values normalised in the same way, so
+/// that terms differing only by their casing, surrounding whitespace, or diacritics match.
+///
+/// Example:
+///
+/// X-TERM-NORMALIZATION:CASE-FOLD,TRIM,STRIP-DIACRITICS
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XTermNormalizationProperty {
+    pub normalizations: List<TermNormalizationValue>,
+}
+
+impl XTermNormalizationProperty {
+    pub fn contains(&self, normalization: &TermNormalizationValue) -> bool {
+        self.normalizations.contains(normalization)
+    }
+}
+
+impl ICalendarEntity for XTermNormalizationProperty {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "X-TERM-NORMALIZATION",
+            preceded(
+                tag("X-TERM-NORMALIZATION"),
+                cut(
+                    map(
+                        preceded(colon, separated_list1(comma, TermNormalizationValue::parse_ical)),
+                        |normalizations| {
+                            XTermNormalizationProperty { normalizations: List::from(normalizations) }
+                        }
+                    )
+                )
+            )
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, context: Option<&RenderingContext>) -> String {
+        self.to_content_line_with_context(context).render_ical()
+    }
+}
+
+impl ICalendarProperty for XTermNormalizationProperty {
+    /// Build a `ContentLineParams` instance with consideration to the optionally provided
+    /// `RenderingContext`.
+    fn to_content_line_with_context(&self, _context: Option<&RenderingContext>) -> ContentLine {
+        ContentLine::from((
+            "X-TERM-NORMALIZATION",
+            (
+                ContentLineParams::default(),
+                self.normalizations.render_ical(),
+            )
+        ))
+    }
+}
+
+impl std::hash::Hash for XTermNormalizationProperty {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.render_ical().hash(state)
+    }
+}
+
+impl_icalendar_entity_traits!(XTermNormalizationProperty);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::assert_parser_output;
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            XTermNormalizationProperty::parse_ical("X-TERM-NORMALIZATION:CASE-FOLD,STRIP-DIACRITICS UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                XTermNormalizationProperty {
+                    normalizations: List::from(vec![
+                        TermNormalizationValue::CaseFold,
+                        TermNormalizationValue::StripDiacritics,
+                    ]),
+                },
+            ),
+        );
+
+        assert_parser_output!(
+            XTermNormalizationProperty::parse_ical("X-TERM-NORMALIZATION:NONE UID:CALENDAR_UID".into()),
+            (
+                " UID:CALENDAR_UID",
+                XTermNormalizationProperty {
+                    normalizations: List::from(vec![TermNormalizationValue::None]),
+                },
+            ),
+        );
+
+        assert!(XTermNormalizationProperty::parse_ical("X-TERM-NORMALIZATION:LOWERCASE".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            XTermNormalizationProperty {
+                normalizations: List::from(vec![
+                    TermNormalizationValue::Trim,
+                    TermNormalizationValue::CaseFold,
+                ]),
+            }.render_ical(),
+            String::from("X-TERM-NORMALIZATION:CASE-FOLD,TRIM"),
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use nom::error::context;
+use nom::sequence::pair;
+use nom::combinator::{recognize, map, verify};
+use nom::bytes::complete::{take_while1, take_while_m_n};
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+// b-char = ALPHA / DIGIT / "+" / "/"
+pub fn is_b_char(input: char) -> bool {
+    input.is_ascii_alphanumeric() || matches!(input, '+' | '/')
+}
+
+// binary     = *(4b-char) [b-end]
+// ; A "BASE64" encoded character string, as defined by [RFC4648].
+//
+// b-end      = (2b-char "==") / (3b-char "=")
+pub fn binary(input: ParserInput) -> ParserResult<ParserInput> {
+    verify(
+        recognize(
+            pair(
+                take_while1(is_b_char),
+                take_while_m_n(0, 2, |char: char| char == '='),
+            )
+        ),
+        |value: &ParserInput| value.len().is_multiple_of(4),
+    )(input)
+}
+
+// Value Name:  BINARY
+//
+// Purpose:  This value type is used to identify properties that contain
+//    a character encoding of inline binary data.  For example, an
+//    inline attachment of a document might be included in an iCalendar
+//    object.
+//
+// Format Definition:  This value type is defined by the following
+//    notation:
+//
+//     binary     = *(4b-char) [b-end]
+//     ; A "BASE64" encoded character string, as defined by [RFC4648].
+//
+//     b-end      = (2b-char "==") / (3b-char "=")
+//
+//     b-char = ALPHA / DIGIT / "+" / "/"
+//
+// Description:  Property values with this value type MUST also include
+//    the inline encoding parameter sequence of ";ENCODING=BASE64".
+//    That is, all inline binary data MUST first be character encoded
+//    using the "BASE64" encoding method defined in [RFC2045].  No
+//    additional content value encoding (i.e., BACKSLASH character
+//    encoding, see Section 3.3.11) is defined for this value type.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Binary(pub String);
+
+impl ICalendarEntity for Binary {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self>
+    where
+        Self: Sized
+    {
+        context(
+            "BINARY",
+            map_err_message!(
+                map(binary, |value| Self(value.to_string())),
+                "expected iCalendar RFC-5545 BINARY",
+            ),
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, _context: Option<&RenderingContext>) -> String {
+        self.0.to_string()
+    }
+}
+
+impl From<&str> for Binary {
+    fn from(value: &str) -> Self {
+        Binary(String::from(value))
+    }
+}
+
+impl_icalendar_entity_traits!(Binary);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::{assert_parser_output, assert_parser_error};
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            Binary::parse_ical("iVBORw0KGgo= SUMMARY:Summary".into()),
+            (
+                " SUMMARY:Summary",
+                Binary(String::from("iVBORw0KGgo=")),
+            ),
+        );
+
+        assert_parser_output!(
+            Binary::parse_ical("AAECAw==".into()),
+            (
+                "",
+                Binary(String::from("AAECAw==")),
+            ),
+        );
+
+        assert_parser_error!(
+            Binary::parse_ical("=AAECAw==".into()),
+            nom::Err::Error(
+                span: "=AAECAw==",
+                message: "expected iCalendar RFC-5545 BINARY",
+                context: ["BINARY"],
+            ),
+        );
+
+        assert!(Binary::parse_ical("AAECA".into()).is_err());
+        assert!(Binary::parse_ical("".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            Binary(String::from("AAECAw==")).render_ical(),
+            String::from("AAECAw=="),
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use nom::error::context;
+use nom::branch::alt;
+use nom::combinator::map;
+
+use crate::grammar::tag;
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+// dstpolicyvalue = "SKIP" / "SHIFT-FORWARD" / "BOTH-INSTANTS"
+// ;Default is SHIFT-FORWARD
+//
+// How recurrence instances landing on a local time skipped (spring forward) or repeated (fall
+// back) by a daylight saving time transition are resolved:
+//
+// * SKIP - Instances on a skipped local time are omitted, those on a repeated local time
+//          resolve to the first instant.
+// * SHIFT-FORWARD - Instances on a skipped local time are shifted forward by the length of the
+//                   gap (RFC-5545 3.3.5), those on a repeated local time resolve to the first
+//                   instant.
+// * BOTH-INSTANTS - Instances on a skipped local time are shifted forward (as with
+//                   SHIFT-FORWARD), those on a repeated local time occur at both instants.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum DstPolicyValue {
+    Skip,
+    #[default]
+    ShiftForward,
+    BothInstants,
+}
+
+impl ICalendarEntity for DstPolicyValue {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "DSTPOLICYVALUE",
+            map_err_message!(
+                alt((
+                    map(tag("SKIP"), |_| DstPolicyValue::Skip),
+                    map(tag("SHIFT-FORWARD"), |_| DstPolicyValue::ShiftForward),
+                    map(tag("BOTH-INSTANTS"), |_| DstPolicyValue::BothInstants),
+                )),
+                "expected either \"SKIP\", \"SHIFT-FORWARD\" or \"BOTH-INSTANTS\"",
+            ),
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, _context: Option<&RenderingContext>) -> String {
+        match self {
+           Self::Skip => String::from("SKIP"),
+           Self::ShiftForward => String::from("SHIFT-FORWARD"),
+           Self::BothInstants => String::from("BOTH-INSTANTS"),
+        }
+    }
+}
+
+impl_icalendar_entity_traits!(DstPolicyValue);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::{assert_parser_output, assert_parser_error};
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            DstPolicyValue::parse_ical("SKIP TESTING".into()),
+            (
+                " TESTING",
+                DstPolicyValue::Skip,
+            ),
+        );
+
+        assert_parser_output!(
+            DstPolicyValue::parse_ical("SHIFT-FORWARD TESTING".into()),
+            (
+                " TESTING",
+                DstPolicyValue::ShiftForward,
+            ),
+        );
+
+        assert_parser_output!(
+            DstPolicyValue::parse_ical("BOTH-INSTANTS TESTING".into()),
+            (
+                " TESTING",
+                DstPolicyValue::BothInstants,
+            ),
+        );
+
+        assert_parser_error!(
+            DstPolicyValue::parse_ical("SHIFT-BACKWARD".into()),
+            nom::Err::Error(
+                span: "SHIFT-BACKWARD",
+                message: "expected either \"SKIP\", \"SHIFT-FORWARD\" or \"BOTH-INSTANTS\"",
+                context: ["DSTPOLICYVALUE"],
+            ),
+        );
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(DstPolicyValue::Skip.render_ical(), String::from("SKIP"));
+        assert_eq!(DstPolicyValue::ShiftForward.render_ical(), String::from("SHIFT-FORWARD"));
+        assert_eq!(DstPolicyValue::BothInstants.render_ical(), String::from("BOTH-INSTANTS"));
+    }
+}
@@ -6,10 +6,15 @@ pub mod float;
 pub mod integer;
 pub mod recur;
 pub mod text;
+pub mod uri;
+pub mod binary;
 pub mod tzid;
 pub mod list;
 pub mod reltype;
 pub mod class;
+pub mod dst_policy;
+pub mod override_mode;
+pub mod term_normalization;
 pub mod where_operator;
 pub mod where_range_property;
 pub mod where_range_operator;
@@ -0,0 +1,90 @@
+use nom::error::context;
+use nom::branch::alt;
+use nom::combinator::map;
+
+use crate::grammar::tag;
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+// overridemodevalue = "MERGE" / "REPLACE"
+// ;Default is MERGE
+//
+// How the properties of an event occurrence override are applied to the base event properties
+// of the occurrence it overrides:
+//
+// * MERGE - Only the properties defined on the override replace those of the base event (grouped
+//           by property name), all others are inherited from the base event.
+// * REPLACE - The properties defined on the override replace all those of the base event, so
+//             properties not defined on the override are not inherited (e.g. an override only
+//             defining CATEGORIES has exactly those categories and nothing else).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum OverrideModeValue {
+    #[default]
+    Merge,
+    Replace,
+}
+
+impl ICalendarEntity for OverrideModeValue {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "OVERRIDEMODEVALUE",
+            map_err_message!(
+                alt((
+                    map(tag("MERGE"), |_| OverrideModeValue::Merge),
+                    map(tag("REPLACE"), |_| OverrideModeValue::Replace),
+                )),
+                "expected either \"MERGE\" or \"REPLACE\"",
+            ),
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, _context: Option<&RenderingContext>) -> String {
+        match self {
+           Self::Merge => String::from("MERGE"),
+           Self::Replace => String::from("REPLACE"),
+        }
+    }
+}
+
+impl_icalendar_entity_traits!(OverrideModeValue);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::{assert_parser_output, assert_parser_error};
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            OverrideModeValue::parse_ical("MERGE TESTING".into()),
+            (
+                " TESTING",
+                OverrideModeValue::Merge,
+            ),
+        );
+
+        assert_parser_output!(
+            OverrideModeValue::parse_ical("REPLACE TESTING".into()),
+            (
+                " TESTING",
+                OverrideModeValue::Replace,
+            ),
+        );
+
+        assert_parser_error!(
+            OverrideModeValue::parse_ical("UNION".into()),
+            nom::Err::Error(
+                span: "UNION",
+                message: "expected either \"MERGE\" or \"REPLACE\"",
+                context: ["OVERRIDEMODEVALUE"],
+            ),
+        );
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(OverrideModeValue::Merge.render_ical(), String::from("MERGE"));
+        assert_eq!(OverrideModeValue::Replace.render_ical(), String::from("REPLACE"));
+    }
+}
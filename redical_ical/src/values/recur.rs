@@ -1,3 +1,7 @@
+use chrono::prelude::TimeZone;
+use chrono::LocalResult;
+use chrono_tz::Tz;
+
 use nom::sequence::{pair, preceded};
 use nom::error::context;
 use nom::branch::alt;
@@ -41,7 +45,32 @@ macro_rules! build_ical_param {
 }
 
 build_ical_param!(FreqParam, "FREQ", freq, Frequency);
-build_ical_param!(UntilParam, "UNTIL", enddate, DateTime);
+// UNTIL is rendered as specified (instead of converted to UTC like other date-times), as a local
+// date-time UNTIL is interpreted relative to DTSTART (see `Recur::with_until_resolved_against`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UntilParam(pub DateTime);
+
+impl ICalendarEntity for UntilParam {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        map(
+            pair(
+                tag("UNTIL"),
+                preceded(tag("="), cut(enddate)),
+            ),
+            |(_key, value)| Self(value)
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, _context: Option<&RenderingContext>) -> String {
+        match &self.0 {
+            DateTime::LocalDateTime(date_time) => format!("UNTIL={}", date_time.format("%Y%m%dT%H%M%S")),
+            date_time => format!("UNTIL={}", date_time.render_ical()),
+        }
+    }
+}
+
+impl_icalendar_entity_traits!(UntilParam);
+
 build_ical_param!(CountParam, "COUNT", count, Integer);
 build_ical_param!(IntervalParam, "INTERVAL", interval, Integer);
 build_ical_param!(BysecondParam, "BYSECOND", byseclist, List<Integer>);
@@ -604,6 +633,45 @@ impl Recur {
             RecurRulePart::Wkst(param) => self.wkst = Some(param),
         };
     }
+
+    /// Returns a copy with the UNTIL rule part resolved against the DTSTART date-time (and optional
+    /// TZID) the recurrence is anchored to.
+    ///
+    /// RFC 5545 requires UNTIL to be specified as a UTC date-time whenever DTSTART is specified as
+    /// either a UTC date-time, or a local date-time with a TZID. As a local date-time UNTIL
+    /// alongside a TZID DTSTART is common in the wild, it is interpreted in that timezone (and
+    /// converted to UTC) instead of being rejected. Alongside a UTC DTSTART it is rejected.
+    ///
+    /// A floating DTSTART (without a TZID) is treated as UTC, and so is a local date-time UNTIL
+    /// alongside it.
+    pub fn with_until_resolved_against(&self, dtstart: &DateTime, dtstart_tz: Option<&Tz>) -> Result<Self, String> {
+        let Some(UntilParam(DateTime::LocalDateTime(until))) = self.until.as_ref() else {
+            return Ok(self.clone());
+        };
+
+        if matches!(dtstart, DateTime::UtcDateTime(_)) {
+            return Err(String::from("UNTIL must be specified as a UTC date-time when DTSTART is specified as a UTC date-time"));
+        }
+
+        let dtstart_tz = dtstart_tz.unwrap_or(&Tz::UTC);
+
+        // Ambiguous local times (repeated as the clocks go back) resolve to the latest, as UNTIL
+        // is inclusive.
+        let until_timestamp =
+            match dtstart_tz.from_local_datetime(until) {
+                LocalResult::Single(until) | LocalResult::Ambiguous(_, until) => until.timestamp(),
+
+                LocalResult::None => {
+                    return Err(format!("UNTIL local date-time: {until} does not exist in timezone: {dtstart_tz}"));
+                },
+            };
+
+        let mut recur = self.clone();
+
+        recur.until = Some(UntilParam(DateTime::from(until_timestamp)));
+
+        Ok(recur)
+    }
 }
 
 impl_icalendar_entity_traits!(Recur);
@@ -731,6 +799,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn with_until_resolved_against() {
+        let recur = Recur::parse_ical("FREQ=DAILY;INTERVAL=1;UNTIL=20210106T090000".into()).unwrap().1;
+
+        let local_dtstart = DateTime::parse_ical("20210104T090000".into()).unwrap().1;
+        let utc_dtstart = DateTime::parse_ical("20210104T090000Z".into()).unwrap().1;
+
+        // Interpreted in the DTSTART TZID.
+        assert_eq!(
+            recur.with_until_resolved_against(&local_dtstart, Some(&Tz::America__New_York)).map(|recur| recur.render_ical()),
+            Ok(String::from("FREQ=DAILY;INTERVAL=1;UNTIL=20210106T140000Z")),
+        );
+
+        // Rendered as specified.
+        assert_eq!(recur.render_ical(), String::from("FREQ=DAILY;INTERVAL=1;UNTIL=20210106T090000"));
+
+        // Treated as UTC with a floating DTSTART.
+        assert_eq!(
+            recur.with_until_resolved_against(&local_dtstart, None).map(|recur| recur.render_ical()),
+            Ok(String::from("FREQ=DAILY;INTERVAL=1;UNTIL=20210106T090000Z")),
+        );
+
+        // Rejected with a UTC DTSTART.
+        assert_eq!(
+            recur.with_until_resolved_against(&utc_dtstart, None),
+            Err(String::from("UNTIL must be specified as a UTC date-time when DTSTART is specified as a UTC date-time")),
+        );
+
+        // Skipped by the clocks going forward.
+        let recur = Recur::parse_ical("FREQ=DAILY;INTERVAL=1;UNTIL=20210314T023000".into()).unwrap().1;
+
+        assert_eq!(
+            recur.with_until_resolved_against(&local_dtstart, Some(&Tz::America__New_York)),
+            Err(String::from("UNTIL local date-time: 2021-03-14 02:30:00 does not exist in timezone: America/New_York")),
+        );
+
+        // A UTC UNTIL is left as it is.
+        let recur = Recur::parse_ical("FREQ=DAILY;INTERVAL=1;UNTIL=20210106T090000Z".into()).unwrap().1;
+
+        assert_eq!(recur.with_until_resolved_against(&utc_dtstart, None), Ok(recur.clone()));
+        assert_eq!(recur.with_until_resolved_against(&local_dtstart, Some(&Tz::America__New_York)), Ok(recur.clone()));
+    }
+
     #[test]
     fn validate() {
         assert_eq!(
@@ -13,7 +13,7 @@ use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_i
 //                        ; iCalendar relationship type
 //          / x-name)     ; A non-standard, experimental
 //                        ; relationship type
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum Reltype {
     Parent,            // Parent relationship - Default
     Child,             // Child relationship
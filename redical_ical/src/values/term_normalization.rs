@@ -0,0 +1,111 @@
+use nom::error::context;
+use nom::branch::alt;
+use nom::combinator::map;
+
+use crate::grammar::tag;
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+// termnormalizationvalue = "CASE-FOLD" / "TRIM" / "STRIP-DIACRITICS" / "NONE"
+//
+// How the CATEGORIES, LOCATION-TYPE, LOCATION, and CLASS terms are normalised before they are
+// indexed (and queried):
+//
+// * CASE-FOLD - Terms are matched regardless of their casing (e.g. "Sport" matches "SPORT").
+// * TRIM - Leading and trailing whitespace is trimmed (e.g. " Sport " matches "Sport").
+// * STRIP-DIACRITICS - Diacritics are stripped (e.g. "Café" matches "Cafe").
+// * NONE - Terms are indexed (and queried) exactly as they are.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum TermNormalizationValue {
+    CaseFold,
+    Trim,
+    StripDiacritics,
+    None,
+}
+
+impl ICalendarEntity for TermNormalizationValue {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "TERMNORMALIZATIONVALUE",
+            map_err_message!(
+                alt((
+                    map(tag("CASE-FOLD"), |_| TermNormalizationValue::CaseFold),
+                    map(tag("TRIM"), |_| TermNormalizationValue::Trim),
+                    map(tag("STRIP-DIACRITICS"), |_| TermNormalizationValue::StripDiacritics),
+                    map(tag("NONE"), |_| TermNormalizationValue::None),
+                )),
+                "expected either \"CASE-FOLD\", \"TRIM\", \"STRIP-DIACRITICS\", or \"NONE\"",
+            ),
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, _context: Option<&RenderingContext>) -> String {
+        match self {
+           Self::CaseFold => String::from("CASE-FOLD"),
+           Self::Trim => String::from("TRIM"),
+           Self::StripDiacritics => String::from("STRIP-DIACRITICS"),
+           Self::None => String::from("NONE"),
+        }
+    }
+}
+
+impl_icalendar_entity_traits!(TermNormalizationValue);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::{assert_parser_output, assert_parser_error};
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            TermNormalizationValue::parse_ical("CASE-FOLD TESTING".into()),
+            (
+                " TESTING",
+                TermNormalizationValue::CaseFold,
+            ),
+        );
+
+        assert_parser_output!(
+            TermNormalizationValue::parse_ical("TRIM TESTING".into()),
+            (
+                " TESTING",
+                TermNormalizationValue::Trim,
+            ),
+        );
+
+        assert_parser_output!(
+            TermNormalizationValue::parse_ical("STRIP-DIACRITICS TESTING".into()),
+            (
+                " TESTING",
+                TermNormalizationValue::StripDiacritics,
+            ),
+        );
+
+        assert_parser_output!(
+            TermNormalizationValue::parse_ical("NONE TESTING".into()),
+            (
+                " TESTING",
+                TermNormalizationValue::None,
+            ),
+        );
+
+        assert_parser_error!(
+            TermNormalizationValue::parse_ical("LOWERCASE".into()),
+            nom::Err::Error(
+                span: "LOWERCASE",
+                message: "expected either \"CASE-FOLD\", \"TRIM\", \"STRIP-DIACRITICS\", or \"NONE\"",
+                context: ["TERMNORMALIZATIONVALUE"],
+            ),
+        );
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(TermNormalizationValue::CaseFold.render_ical(), String::from("CASE-FOLD"));
+        assert_eq!(TermNormalizationValue::Trim.render_ical(), String::from("TRIM"));
+        assert_eq!(TermNormalizationValue::StripDiacritics.render_ical(), String::from("STRIP-DIACRITICS"));
+        assert_eq!(TermNormalizationValue::None.render_ical(), String::from("NONE"));
+    }
+}
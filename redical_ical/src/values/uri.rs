@@ -0,0 +1,126 @@
+use nom::error::context;
+use nom::sequence::{pair, tuple};
+use nom::combinator::{recognize, map};
+use nom::bytes::complete::{take_while, take_while1, take_while_m_n};
+
+use crate::grammar::colon;
+
+use crate::{RenderingContext, ICalendarEntity, ParserInput, ParserResult, impl_icalendar_entity_traits, map_err_message};
+
+// scheme      = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+pub fn scheme(input: ParserInput) -> ParserResult<ParserInput> {
+    recognize(
+        pair(
+            take_while_m_n(1, 1, |char: char| char.is_ascii_alphabetic()),
+            take_while(|char: char| char.is_ascii_alphanumeric() || matches!(char, '+' | '-' | '.')),
+        )
+    )(input)
+}
+
+// Any character permitted within a URI (which is not validated beyond it's scheme), excluding
+// whitespace (which separates the properties that follow), CONTROL, and DQUOTE.
+pub fn is_uri_char(input: char) -> bool {
+    !input.is_whitespace() && !input.is_control() && input != '"'
+}
+
+// uri         = scheme ":" hier-part [ "?" query ] [ "#" fragment ]
+pub fn uri(input: ParserInput) -> ParserResult<ParserInput> {
+    recognize(
+        tuple((
+            scheme,
+            colon,
+            take_while1(is_uri_char),
+        ))
+    )(input)
+}
+
+// Value Name:  URI
+//
+// Purpose:  This value type is used to identify values that contain a
+//    uniform resource identifier (URI) type of reference to the
+//    property value.
+//
+// Format Definition:  This value type is defined by the following
+//    notation:
+//
+//     uri = <As defined in Section 3 of [RFC3986]>
+//
+// Example:  The following is a URI for a network file:
+//
+//     http://example.com/my-report.txt
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Uri(pub String);
+
+impl ICalendarEntity for Uri {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self>
+    where
+        Self: Sized
+    {
+        context(
+            "URI",
+            map_err_message!(
+                map(uri, |value| Self(value.to_string())),
+                "expected iCalendar RFC-5545 URI",
+            ),
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, _context: Option<&RenderingContext>) -> String {
+        self.0.to_string()
+    }
+}
+
+impl From<&str> for Uri {
+    fn from(value: &str) -> Self {
+        Uri(String::from(value))
+    }
+}
+
+impl_icalendar_entity_traits!(Uri);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tests::{assert_parser_output, assert_parser_error};
+
+    #[test]
+    fn parse_ical() {
+        assert_parser_output!(
+            Uri::parse_ical("https://example.com/calendar.ics?key=value#fragment NAME:Calendar".into()),
+            (
+                " NAME:Calendar",
+                Uri(String::from("https://example.com/calendar.ics?key=value#fragment")),
+            ),
+        );
+
+        assert_parser_output!(
+            Uri::parse_ical("mailto:someone@example.com".into()),
+            (
+                "",
+                Uri(String::from("mailto:someone@example.com")),
+            ),
+        );
+
+        assert_parser_error!(
+            Uri::parse_ical("1https://example.com".into()),
+            nom::Err::Error(
+                span: "1https://example.com",
+                message: "expected iCalendar RFC-5545 URI",
+                context: ["URI"],
+            ),
+        );
+
+        assert!(Uri::parse_ical("example.com/calendar.ics".into()).is_err());
+        assert!(Uri::parse_ical("https:".into()).is_err());
+        assert!(Uri::parse_ical(":".into()).is_err());
+    }
+
+    #[test]
+    fn render_ical() {
+        assert_eq!(
+            Uri(String::from("https://example.com/calendar.ics")).render_ical(),
+            String::from("https://example.com/calendar.ics"),
+        );
+    }
+}
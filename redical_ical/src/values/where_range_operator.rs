@@ -72,6 +72,48 @@ impl ICalendarEntity for WhereUntilRangeOperator {
 
 impl_icalendar_entity_traits!(WhereUntilRangeOperator);
 
+// OP = "GT" / "GTE" / "LT" / "LTE" / "EQ"
+//
+// ;Default is GTE
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum WhereComparisonOperator {
+    GreaterThan,
+    GreaterEqualThan,
+    LessThan,
+    LessEqualThan,
+    Equal,
+}
+
+impl ICalendarEntity for WhereComparisonOperator {
+    fn parse_ical(input: ParserInput) -> ParserResult<Self> {
+        context(
+            "OP",
+            map_err_message!(
+                alt((
+                    map(tag("GTE"), |_| WhereComparisonOperator::GreaterEqualThan),
+                    map(tag("GT"), |_| WhereComparisonOperator::GreaterThan),
+                    map(tag("LTE"), |_| WhereComparisonOperator::LessEqualThan),
+                    map(tag("LT"), |_| WhereComparisonOperator::LessThan),
+                    map(tag("EQ"), |_| WhereComparisonOperator::Equal),
+                )),
+                "expected either \"GTE\", \"GT\", \"LTE\", \"LT\" or \"EQ\"",
+            ),
+        )(input)
+    }
+
+    fn render_ical_with_context(&self, _context: Option<&RenderingContext>) -> String {
+        match self {
+           Self::GreaterThan => String::from("GT"),
+           Self::GreaterEqualThan => String::from("GTE"),
+           Self::LessThan => String::from("LT"),
+           Self::LessEqualThan => String::from("LTE"),
+           Self::Equal => String::from("EQ"),
+        }
+    }
+}
+
+impl_icalendar_entity_traits!(WhereComparisonOperator);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,6 +139,15 @@ mod tests {
                 context: ["OP"],
             ),
         );
+
+        assert_parser_error!(
+            WhereComparisonOperator::parse_ical(":::: DESCRIPTION:Description text".into()),
+            nom::Err::Error(
+                span: ":::: DESCRIPTION:Description text",
+                message: "expected either \"GTE\", \"GT\", \"LTE\", \"LT\" or \"EQ\"",
+                context: ["OP"],
+            ),
+        );
     }
 
     #[test]
@@ -168,4 +219,27 @@ mod tests {
             String::from("LTE"),
         );
     }
+
+    #[test]
+    fn comparison_operator_parse_ical() {
+        for (input, expected) in [
+            ("GT", WhereComparisonOperator::GreaterThan),
+            ("GTE", WhereComparisonOperator::GreaterEqualThan),
+            ("LT", WhereComparisonOperator::LessThan),
+            ("LTE", WhereComparisonOperator::LessEqualThan),
+            ("EQ", WhereComparisonOperator::Equal),
+        ] {
+            let ical = format!("{input} DESCRIPTION:Description text");
+
+            assert_parser_output!(
+                WhereComparisonOperator::parse_ical(ical.as_str().into()),
+                (
+                    " DESCRIPTION:Description text",
+                    expected.clone(),
+                ),
+            );
+
+            assert_eq!(expected.render_ical(), String::from(input));
+        }
+    }
 }
@@ -0,0 +1,253 @@
+#[macro_use]
+extern crate afl;
+extern crate redical_core;
+extern crate redical_ical;
+
+use redical_core::{Calendar, CalendarIndexUpdater, Event, EventOccurrenceOverride};
+use redical_ical::values::date_time::DateTime;
+
+use std::str::FromStr;
+
+// Each mutation is decoded from this many bytes of the fuzzer input (the operation, followed by
+// the two operands selecting it's arguments from the fixtures below).
+const MUTATION_BYTE_LENGTH: usize = 3;
+
+const EVENT_UIDS: [&str; 3] = [
+    "EVENT_ONE",
+    "EVENT_TWO",
+    "EVENT_THREE",
+];
+
+const EVENT_ICALS: [&str; 4] = [
+    "RRULE:FREQ=WEEKLY;UNTIL=20210331T183000Z;INTERVAL=1;BYDAY=TU DTSTART:20201231T183000Z DTEND:20201231T190000Z CATEGORIES:CATEGORY_ONE,CATEGORY_TWO CLASS:PUBLIC GEO:51.751365550307604;-1.2601196837753945",
+    "RRULE:FREQ=DAILY;INTERVAL=1 DTSTART:20210101T183000Z DURATION:PT30M LOCATION-TYPE:ONLINE RELATED-TO;RELTYPE=PARENT:PARENT_UID",
+    "DTSTART:20210112T183000Z DTEND:20210112T190000Z CATEGORIES:CATEGORY_THREE CLASS:PRIVATE LOCATION:Oxford Town Hall",
+    "RRULE:FREQ=WEEKLY;COUNT=4;INTERVAL=1;BYDAY=TU DTSTART:20210105T183000Z",
+];
+
+const OVERRIDE_DATE_STRINGS: [&str; 4] = [
+    "20210105T183000Z",
+    "20210112T183000Z",
+    "20210119T183000Z",
+    "20210102T183000Z",
+];
+
+const OVERRIDE_ICALS: [&str; 5] = [
+    "CATEGORIES:CATEGORY_TWO,CATEGORY_THREE",
+    "CLASS:PRIVATE GEO:51.5074;-0.1278",
+    "LOCATION-TYPE:IN-PERSON RELATED-TO;RELTYPE=CHILD:CHILD_UID",
+    "GEO:;",
+    "SUMMARY:Overridden summary text LOCATION:Royal Albert Hall",
+];
+
+// The (from, until) timestamp ranges pruned, spanning none, some, and all of the fixtures above.
+const PRUNE_RANGES: [(i64, i64); 4] = [
+    (0, 1609459200),
+    (0, 1610476200),
+    (1610476200, 1610476200),
+    (0, 1617235200),
+];
+
+#[derive(Debug)]
+enum Mutation {
+    EventSet(&'static str, &'static str),
+    EventDel(&'static str),
+    EventOverrideSet(&'static str, &'static str, &'static str),
+    EventOverrideDel(&'static str, &'static str),
+    EventPrune(i64, i64),
+    EventOverridePrune(&'static str, i64, i64),
+}
+
+impl Mutation {
+    fn decode(bytes: &[u8]) -> Self {
+        let (operation, first, second) = (bytes[0], bytes[1] as usize, bytes[2] as usize);
+
+        let event_uid = EVENT_UIDS[first % EVENT_UIDS.len()];
+
+        match operation % 6 {
+            0 => Mutation::EventSet(event_uid, EVENT_ICALS[second % EVENT_ICALS.len()]),
+            1 => Mutation::EventDel(event_uid),
+            2 => {
+                Mutation::EventOverrideSet(
+                    event_uid,
+                    OVERRIDE_DATE_STRINGS[second % OVERRIDE_DATE_STRINGS.len()],
+                    OVERRIDE_ICALS[(second / OVERRIDE_DATE_STRINGS.len()) % OVERRIDE_ICALS.len()],
+                )
+            },
+            3 => Mutation::EventOverrideDel(event_uid, OVERRIDE_DATE_STRINGS[second % OVERRIDE_DATE_STRINGS.len()]),
+            4 => {
+                let (from, until) = PRUNE_RANGES[second % PRUNE_RANGES.len()];
+
+                Mutation::EventPrune(from, until)
+            },
+            _ => {
+                let (from, until) = PRUNE_RANGES[second % PRUNE_RANGES.len()];
+
+                Mutation::EventOverridePrune(event_uid, from, until)
+            },
+        }
+    }
+
+    // Apply the mutation to the Calendar as the equivalent command does, incrementally updating
+    // the Calendar indexes with the difference between the event before and after.
+    //
+    // Mutations of events which do not exist are skipped, as the commands reject them.
+    fn apply(&self, calendar: &mut Calendar) {
+        match self {
+            Mutation::EventSet(event_uid, event_ical) => {
+                let mut event = Event::parse_ical(event_uid, event_ical).unwrap();
+
+                event.validate().unwrap();
+
+                let existing_event = calendar.events.get(*event_uid).cloned();
+
+                if let Some(existing_event) = existing_event.as_ref() {
+                    event.overrides = existing_event.overrides.clone();
+                }
+
+                event.rebuild_indexes().unwrap();
+
+                update_event_indexes(calendar, event_uid, existing_event.as_deref(), Some(&event));
+
+                calendar.insert_event(event);
+            },
+
+            Mutation::EventDel(event_uid) => {
+                calendar.remove_event_and_reindex(&event_uid.to_string()).unwrap();
+            },
+
+            Mutation::EventOverrideSet(event_uid, override_date_string, override_ical) => {
+                let Some(mut event) = calendar.events.get(*event_uid).cloned() else {
+                    return;
+                };
+
+                let event_occurrence_override = EventOccurrenceOverride::parse_ical(override_date_string, override_ical).unwrap();
+
+                event.override_occurrence(&event_occurrence_override, calendar.indexes_active).unwrap();
+
+                let existing_event = calendar.insert_event(*event.clone());
+
+                update_event_indexes(calendar, event_uid, existing_event.as_ref(), Some(event.as_ref()));
+            },
+
+            Mutation::EventOverrideDel(event_uid, override_date_string) => {
+                let Some(mut event) = calendar.events.get(*event_uid).cloned() else {
+                    return;
+                };
+
+                let override_timestamp = DateTime::from_str(override_date_string).unwrap().get_utc_timestamp(None);
+
+                event.remove_occurrence_override(override_timestamp, calendar.indexes_active).unwrap();
+
+                let existing_event = calendar.insert_event(*event.clone());
+
+                update_event_indexes(calendar, event_uid, existing_event.as_ref(), Some(event.as_ref()));
+            },
+
+            Mutation::EventPrune(from, until) => {
+                let pruned_events = calendar.prune_events(*from, *until).unwrap();
+
+                for (event_uid, pruned_event) in pruned_events.iter() {
+                    update_event_indexes(calendar, event_uid, Some(pruned_event.as_ref()), None);
+                }
+            },
+
+            Mutation::EventOverridePrune(event_uid, from, until) => {
+                let Some(mut event) = calendar.events.get(*event_uid).cloned() else {
+                    return;
+                };
+
+                event.prune_event_overrides(*from, *until).unwrap();
+
+                let existing_event = calendar.insert_event(*event.clone());
+
+                update_event_indexes(calendar, event_uid, existing_event.as_ref(), Some(event.as_ref()));
+            },
+        }
+    }
+}
+
+fn update_event_indexes(calendar: &mut Calendar, event_uid: &str, existing_event: Option<&Event>, updated_event: Option<&Event>) {
+    if !calendar.indexes_active {
+        return;
+    }
+
+    CalendarIndexUpdater::new(&event_uid.to_string(), calendar)
+        .update_event_indexes(existing_event, updated_event)
+        .unwrap();
+}
+
+// The oracle: the incrementally maintained Calendar indexes must match the index terms recomputed
+// from each event after every mutation.
+fn assert_indexes_consistent(calendar: &Calendar, applied_mutations: &[Mutation]) {
+    let index_verification = calendar.verify_indexes(None).unwrap();
+
+    if !index_verification.is_consistent() {
+        panic!(
+            "Calendar indexes inconsistent after mutations: {:#?} -- discrepancies: {:#?}",
+            applied_mutations,
+            index_verification.discrepancies,
+        );
+    }
+}
+
+fn apply_mutations(fuzz_input: &[u8]) {
+    let mut calendar = Calendar::new(String::from("FUZZ_CALENDAR_UID"));
+
+    let mut applied_mutations = Vec::new();
+
+    for mutation_bytes in fuzz_input.chunks_exact(MUTATION_BYTE_LENGTH) {
+        let mutation = Mutation::decode(mutation_bytes);
+
+        mutation.apply(&mut calendar);
+
+        applied_mutations.push(mutation);
+
+        assert_indexes_consistent(&calendar, &applied_mutations);
+    }
+}
+
+fn main() {
+    fuzz!(|data: &[u8]| {
+        apply_mutations(data);
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Deterministic (xorshift) pseudo-random bytes, so that failing sequences are reproducible.
+    fn pseudo_random_bytes(seed: u64, length: usize) -> Vec<u8> {
+        let mut state = seed.max(1);
+
+        (0..length)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+
+                (state >> 24) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_random_mutation_sequences_keep_indexes_consistent() {
+        for seed in 1..=250 {
+            apply_mutations(&pseudo_random_bytes(seed, MUTATION_BYTE_LENGTH * 40));
+        }
+    }
+
+    #[test]
+    fn test_replaced_event_occurrence_override_keeps_indexes_consistent() {
+        apply_mutations(&[
+            0, 0, 0, // EventSet(EVENT_ONE, weekly with CATEGORIES)
+            2, 0, 1, // EventOverrideSet(EVENT_ONE, 20210112T183000Z, CATEGORIES)
+            2, 0, 5, // EventOverrideSet(EVENT_ONE, 20210112T183000Z, CLASS and GEO)
+            2, 0, 17, // EventOverrideSet(EVENT_ONE, 20210112T183000Z, SUMMARY and LOCATION)
+            5, 0, 1, // EventOverridePrune(EVENT_ONE, 0 - 20210112T183000Z)
+            4, 0, 3, // EventPrune(0 - 20210401T000000Z)
+        ]);
+    }
+}
@@ -0,0 +1,116 @@
+#[macro_use]
+extern crate afl;
+extern crate redical_core;
+
+use redical_core::queries::query::Query;
+use redical_core::queries::event_query::EventQuery;
+use redical_core::queries::event_instance_query::EventInstanceQuery;
+use redical_core::{Calendar, Event, EventOccurrenceOverride, ExecutionDeadline};
+
+use std::str::FromStr;
+use std::time::Duration;
+
+// Generous enough for the small synthetic Calendar, but well within the AFL hang timeout so that
+// only queries failing to cooperatively abort are reported as hangs.
+const QUERY_EXECUTION_TIMEOUT_MS: u64 = 250;
+
+fn build_event(event_uid: &str, event_ical_parts: Vec<&str>, event_overrides: Vec<(&str, Vec<&str>)>) -> Event {
+    let mut event = Event::parse_ical(event_uid, event_ical_parts.join(" ").as_str()).unwrap();
+
+    event.validate().unwrap();
+
+    for (dtstart_date_string, override_ical_parts) in event_overrides {
+        let event_occurrence_override = EventOccurrenceOverride::parse_ical(dtstart_date_string, override_ical_parts.join(" ").as_str()).unwrap();
+
+        event.override_occurrence(&event_occurrence_override, true).unwrap();
+    }
+
+    event.rebuild_indexes().unwrap();
+
+    event
+}
+
+// A small Calendar covering each indexed property (including overridden ones), alongside
+// finite, indefinite, and single occurrence events for the query to expand and order.
+fn build_synthetic_calendar() -> Calendar {
+    let mut calendar = Calendar::new(String::from("FUZZ_CALENDAR_UID"));
+
+    calendar.insert_event(
+        build_event(
+            "EVENT_IN_OXFORD_MON_WED",
+            vec![
+                "RRULE:FREQ=WEEKLY;UNTIL=20210331T170000Z;BYDAY=MO,WE",
+                "DTSTART:20201231T160000Z",
+                "DTEND:20201231T170000Z",
+                "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO",
+                "LOCATION-TYPE:ONLINE,IN-PERSON",
+                "RELATED-TO;RELTYPE=PARENT:PARENT_UID",
+                "CLASS:PUBLIC",
+                "GEO:51.751365550307604;-1.2601196837753945",
+                "SUMMARY:Event in Oxford summary text",
+            ],
+            vec![
+                (
+                    "20210104T160000Z",
+                    vec![
+                        "CATEGORIES:OVERRIDDEN_CATEGORY",
+                        "CLASS:PRIVATE",
+                        "GEO:51.5074;-0.1278",
+                    ],
+                ),
+            ],
+        )
+    );
+
+    calendar.insert_event(
+        build_event(
+            "EVENT_EVERY_DAY",
+            vec![
+                "RRULE:FREQ=DAILY;INTERVAL=1",
+                "DTSTART;TZID=Europe/London:20210101T090000",
+                "DURATION:PT30M",
+                "CATEGORIES:CATEGORY_TWO",
+                "RELATED-TO;RELTYPE=CHILD:CHILD_UID",
+                "CLASS:CONFIDENTIAL",
+            ],
+            vec![],
+        )
+    );
+
+    calendar.insert_event(
+        build_event(
+            "EVENT_SINGLE_OCCURRENCE",
+            vec![
+                "DTSTART:20210105T180000Z",
+                "DTEND:20210105T190000Z",
+                "LOCATION-TYPE:ONLINE",
+                "GEO:48.85299;2.36885",
+            ],
+            vec![],
+        )
+    );
+
+    calendar.rebuild_indexes().unwrap();
+
+    calendar
+}
+
+fn main() {
+    let calendar = build_synthetic_calendar();
+
+    fuzz!(|data: &[u8]| {
+        if let Ok(fuzz_input) = std::str::from_utf8(data) {
+            if let Ok(mut event_instance_query) = EventInstanceQuery::from_str(fuzz_input) {
+                let execution_deadline = ExecutionDeadline::new(Duration::from_millis(QUERY_EXECUTION_TIMEOUT_MS));
+
+                let _ = event_instance_query.execute_with_deadline(&calendar, &execution_deadline);
+            }
+
+            if let Ok(mut event_query) = EventQuery::from_str(fuzz_input) {
+                let execution_deadline = ExecutionDeadline::new(Duration::from_millis(QUERY_EXECUTION_TIMEOUT_MS));
+
+                let _ = event_query.execute_with_deadline(&calendar, &execution_deadline);
+            }
+        }
+    });
+}
@@ -0,0 +1,176 @@
+use redis_module::{CallOptionsBuilder, CallResult, Context, ContextFlags, RedisString};
+
+use redical_ical::properties::LastModifiedProperty;
+
+use crate::{CONFIGURATION_CHANGE_FEED_ENABLED, CONFIGURATION_CHANGE_FEED_MAX_LEN};
+
+pub const CHANGE_FEED_KEY_PREFIX: &str = "rdcl:changes:";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ChangeType {
+    EventSet,
+    EventDel,
+    OverrideSet,
+    OverrideDel,
+}
+
+impl ChangeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeType::EventSet => "evt_set",
+            ChangeType::EventDel => "evt_del",
+            ChangeType::OverrideSet => "evo_set",
+            ChangeType::OverrideDel => "evo_del",
+        }
+    }
+}
+
+// A single Event (or Event occurrence override) mutation appended to the Calendar change feed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Change {
+    pub change_type: ChangeType,
+    pub event_uid: String,
+    pub override_date_string: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Change {
+    pub fn new(change_type: ChangeType, event_uid: &str) -> Self {
+        Change {
+            change_type,
+            event_uid: event_uid.to_owned(),
+            override_date_string: None,
+            last_modified: None,
+        }
+    }
+
+    pub fn with_override_date_string(mut self, override_date_string: &str) -> Self {
+        self.override_date_string = Some(override_date_string.to_owned());
+
+        self
+    }
+
+    pub fn with_last_modified(mut self, last_modified: &LastModifiedProperty) -> Self {
+        self.last_modified = Some(last_modified.date_time.render_formatted_date_time(None));
+
+        self
+    }
+
+    // The XADD field value pairs describing the change.
+    fn to_stream_fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("type", self.change_type.as_str().to_owned()),
+            ("uid", self.event_uid.to_owned()),
+        ];
+
+        if let Some(override_date_string) = &self.override_date_string {
+            fields.push(("recurrence_id", override_date_string.to_owned()));
+        }
+
+        if let Some(last_modified) = &self.last_modified {
+            fields.push(("last_modified", last_modified.to_owned()));
+        }
+
+        fields
+    }
+}
+
+// The Calendar key is hash-tagged within the change feed stream key, so that (when running Redis
+// Cluster) it is always owned by the same shard as the Calendar it is derived from.
+pub fn get_change_feed_key(calendar_uid: &str) -> String {
+    format!("{CHANGE_FEED_KEY_PREFIX}{{{calendar_uid}}}")
+}
+
+// Append each change to the Calendar change feed stream (rdcl:changes:{<calendar uid>}) if enabled
+// via the change-feed-enabled config.
+//
+// The XADD is replicated (with the resolved stream entry ID) to the replicas and the AOF, so that
+// the change feed is identical across them, so replicated (and AOF loaded) commands skip it to
+// avoid appending each change twice.
+//
+// This is called once the Calendar has already been mutated, so a failure to append a change is
+// logged rather than failing the command (which would misreport the mutation as not applied).
+pub fn append_changes(ctx: &Context, calendar_uid: &RedisString, changes: &[Change]) {
+    if changes.is_empty() || !*CONFIGURATION_CHANGE_FEED_ENABLED.lock(ctx) {
+        return;
+    }
+
+    let flags = ctx.get_flags();
+
+    if flags.contains(ContextFlags::REPLICATED) || flags.contains(ContextFlags::LOADING) {
+        return;
+    }
+
+    let change_feed_key = get_change_feed_key(&calendar_uid.to_string());
+
+    // Zero represents an unbounded change feed.
+    let max_len = Some(*CONFIGURATION_CHANGE_FEED_MAX_LEN.lock(ctx)).filter(|max_len| *max_len > 0).map(|max_len| max_len.to_string());
+
+    let call_options = CallOptionsBuilder::new().replicate().errors_as_replies().build();
+
+    for change in changes {
+        let stream_fields = change.to_stream_fields();
+
+        let mut args: Vec<&str> = vec![change_feed_key.as_str()];
+
+        if let Some(max_len) = &max_len {
+            args.extend(["MAXLEN", "~", max_len.as_str()]);
+        }
+
+        args.push("*");
+
+        for (field, value) in &stream_fields {
+            args.extend([*field, value.as_str()]);
+        }
+
+        let call_result: CallResult = ctx.call_ext("XADD", &call_options, args.as_slice());
+
+        if let Err(error) = call_result {
+            ctx.log_warning(
+                format!(
+                    "Append change feed entry to: \"{change_feed_key}\" for event: \"{}\" failed: {}",
+                    change.event_uid,
+                    error.to_utf8_string().unwrap_or_default(),
+                ).as_str()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use std::str::FromStr;
+
+    #[test]
+    fn test_change_to_stream_fields() {
+        assert_eq!(
+            Change::new(ChangeType::EventDel, "EVENT_UID").to_stream_fields(),
+            vec![
+                ("type", String::from("evt_del")),
+                ("uid", String::from("EVENT_UID")),
+            ],
+        );
+
+        assert_eq!(
+            Change::new(ChangeType::OverrideSet, "EVENT_UID")
+                .with_override_date_string("20210102T170000Z")
+                .with_last_modified(&LastModifiedProperty::from_str("LAST-MODIFIED:20210501T090000Z").unwrap())
+                .to_stream_fields(),
+            vec![
+                ("type", String::from("evo_set")),
+                ("uid", String::from("EVENT_UID")),
+                ("recurrence_id", String::from("20210102T170000Z")),
+                ("last_modified", String::from("20210501T090000Z")),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_get_change_feed_key() {
+        assert_eq!(get_change_feed_key("CALENDAR_UID"), String::from("rdcl:changes:{CALENDAR_UID}"));
+    }
+}
@@ -0,0 +1,194 @@
+use redis_module::{InfoContext, RedisResult, Status};
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+// The upper bound (in microseconds) of each command latency histogram bucket, with a final
+// unbounded bucket for anything slower.
+pub const LATENCY_BUCKET_UPPER_BOUNDS_US: [u64; 12] = [
+    100,
+    250,
+    500,
+    1_000,
+    2_500,
+    5_000,
+    10_000,
+    25_000,
+    50_000,
+    100_000,
+    500_000,
+    1_000_000,
+];
+
+// The call count, failure count, and latency histogram of a single command since the module was
+// loaded (or the metrics were last reset).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CommandMetrics {
+    pub calls: u64,
+    pub failed_calls: u64,
+    pub total_duration_us: u64,
+    pub max_duration_us: u64,
+    pub latency_buckets: [u64; LATENCY_BUCKET_UPPER_BOUNDS_US.len() + 1],
+}
+
+impl CommandMetrics {
+    pub fn record(&mut self, duration_us: u64, is_failed: bool) {
+        self.calls += 1;
+
+        if is_failed {
+            self.failed_calls += 1;
+        }
+
+        self.total_duration_us = self.total_duration_us.saturating_add(duration_us);
+        self.max_duration_us = self.max_duration_us.max(duration_us);
+
+        let bucket_index =
+            LATENCY_BUCKET_UPPER_BOUNDS_US
+                .iter()
+                .position(|upper_bound_us| duration_us <= *upper_bound_us)
+                .unwrap_or(LATENCY_BUCKET_UPPER_BOUNDS_US.len());
+
+        self.latency_buckets[bucket_index] += 1;
+    }
+
+    pub fn average_duration_us(&self) -> u64 {
+        if self.calls == 0 {
+            return 0;
+        }
+
+        self.total_duration_us / self.calls
+    }
+
+    // Estimate the latency percentile (between 0.0 and 1.0) as the upper bound of the histogram
+    // bucket containing it, capped at the slowest recorded call (which also covers the final
+    // unbounded bucket).
+    pub fn percentile_duration_us(&self, percentile: f64) -> u64 {
+        if self.calls == 0 {
+            return 0;
+        }
+
+        let target_calls = ((self.calls as f64) * percentile).ceil().max(1.0) as u64;
+
+        let mut accumulated_calls = 0;
+
+        for (bucket_index, bucket_calls) in self.latency_buckets.iter().enumerate() {
+            accumulated_calls += bucket_calls;
+
+            if accumulated_calls >= target_calls {
+                return LATENCY_BUCKET_UPPER_BOUNDS_US
+                    .get(bucket_index)
+                    .map_or(self.max_duration_us, |upper_bound_us| (*upper_bound_us).min(self.max_duration_us));
+            }
+        }
+
+        self.max_duration_us
+    }
+}
+
+// The metrics of each command (by name) called since the module was loaded.
+//
+// Metrics are held in memory only, so they are not persisted, replicated, or retained across
+// restarts.
+static COMMAND_METRICS: Mutex<BTreeMap<&'static str, CommandMetrics>> = Mutex::new(BTreeMap::new());
+
+// Call the command, recording it's latency (and whether it failed) against the command name.
+pub fn measure<F: FnOnce() -> RedisResult>(command_name: &'static str, command: F) -> RedisResult {
+    let started_at = Instant::now();
+
+    let result = command();
+
+    let duration_us = started_at.elapsed().as_micros().try_into().unwrap_or(u64::MAX);
+
+    COMMAND_METRICS
+        .lock()
+        .unwrap()
+        .entry(command_name)
+        .or_default()
+        .record(duration_us, result.is_err());
+
+    result
+}
+
+pub fn get_command_metrics() -> BTreeMap<&'static str, CommandMetrics> {
+    COMMAND_METRICS.lock().unwrap().clone()
+}
+
+pub fn reset_command_metrics() {
+    COMMAND_METRICS.lock().unwrap().clear();
+}
+
+// Add the "commandstats" section (mirroring the Redis INFO commandstats format) to the module INFO
+// output, with a field for each command called.
+pub fn add_info_section(ctx: &InfoContext) -> Result<(), String> {
+    if ctx.add_info_section(Some("commandstats")) == Status::Err {
+        return Err(String::from("Failed to add INFO section: commandstats"));
+    }
+
+    for (command_name, command_metrics) in get_command_metrics() {
+        let command_stats = format!(
+            "calls={},failed_calls={},usec={},usec_per_call={},p50_usec={},p99_usec={},max_usec={}",
+            command_metrics.calls,
+            command_metrics.failed_calls,
+            command_metrics.total_duration_us,
+            command_metrics.average_duration_us(),
+            command_metrics.percentile_duration_us(0.5),
+            command_metrics.percentile_duration_us(0.99),
+            command_metrics.max_duration_us,
+        );
+
+        if ctx.add_info_field_str(command_name, &command_stats) == Status::Err {
+            return Err(format!("Failed to add INFO field: {command_name}"));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn test_command_metrics_record() {
+        let mut command_metrics = CommandMetrics::default();
+
+        assert_eq!(command_metrics.average_duration_us(), 0);
+        assert_eq!(command_metrics.percentile_duration_us(0.5), 0);
+
+        for duration_us in [50, 80, 90, 200, 700, 3_000_000] {
+            command_metrics.record(duration_us, false);
+        }
+
+        command_metrics.record(120, true);
+
+        assert_eq!(
+            command_metrics,
+            CommandMetrics {
+                calls: 7,
+                failed_calls: 1,
+                total_duration_us: 3_001_240,
+                max_duration_us: 3_000_000,
+                latency_buckets: [3, 2, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            },
+        );
+
+        assert_eq!(command_metrics.average_duration_us(), 428_748);
+
+        assert_eq!(command_metrics.percentile_duration_us(0.0), 100);
+        assert_eq!(command_metrics.percentile_duration_us(0.5), 250);
+        assert_eq!(command_metrics.percentile_duration_us(0.8), 1_000);
+        assert_eq!(command_metrics.percentile_duration_us(0.99), 3_000_000);
+    }
+
+    #[test]
+    fn test_percentile_duration_capped_at_max_duration() {
+        let mut command_metrics = CommandMetrics::default();
+
+        command_metrics.record(30, false);
+
+        assert_eq!(command_metrics.percentile_duration_us(0.99), 30);
+    }
+}
@@ -1,41 +1,110 @@
 mod rdcl_evi_query;
 mod rdcl_cal_set;
 mod rdcl_cal_get;
+mod rdcl_cal_stats;
+mod rdcl_cal_export;
 mod rdcl_cal_idx_disable;
 mod rdcl_cal_idx_rebuild;
+mod rdcl_cal_idx_verify;
+mod rdcl_cal_idx_terms;
 
+mod rdcl_evi_add;
+mod rdcl_evi_get;
 mod rdcl_evi_list;
+mod rdcl_evi_next;
 
 mod rdcl_evo_del;
 mod rdcl_evo_get;
 mod rdcl_evo_set;
+mod rdcl_evo_mset;
 mod rdcl_evo_list;
 mod rdcl_evo_prune;
 
 mod rdcl_evt_get;
+mod rdcl_evt_children;
 mod rdcl_evt_set;
+mod rdcl_evt_mset;
+mod rdcl_evt_import;
 mod rdcl_evt_del;
+mod rdcl_evt_del_query;
+mod rdcl_evt_expire;
 mod rdcl_evt_list;
+mod rdcl_evt_scan;
 mod rdcl_evt_query;
+mod rdcl_evt_update_query;
 mod rdcl_evt_prune;
 
-pub use rdcl_evi_query::redical_event_instance_query;
-pub use rdcl_cal_set::redical_calendar_set;
-pub use rdcl_cal_get::redical_calendar_get;
-pub use rdcl_cal_idx_disable::redical_calendar_idx_disable;
-pub use rdcl_cal_idx_rebuild::redical_calendar_idx_rebuild;
-
-pub use rdcl_evi_list::redical_event_instance_list;
-
-pub use rdcl_evo_del::redical_event_override_del;
-pub use rdcl_evo_get::redical_event_override_get;
-pub use rdcl_evo_set::redical_event_override_set;
-pub use rdcl_evo_list::redical_event_override_list;
-pub use rdcl_evo_prune::redical_event_override_prune;
-
-pub use rdcl_evt_get::redical_event_get;
-pub use rdcl_evt_set::redical_event_set;
-pub use rdcl_evt_del::redical_event_del;
-pub use rdcl_evt_list::redical_event_list;
-pub use rdcl_evt_query::redical_event_query;
-pub use rdcl_evt_prune::redical_event_prune;
+mod rdcl_query_explain;
+mod rdcl_query_profile;
+mod rdcl_query_sub;
+mod rdcl_query_unsub;
+mod rdcl_query_sub_list;
+
+mod rdcl_metrics;
+
+use redis_module::{Context, RedisResult, RedisString};
+
+pub use rdcl_evt_del::{remove_event_and_reindex, notify_keyspace_event as notify_event_del_keyspace_event};
+pub use rdcl_evt_prune::{prune_and_reindex as prune_events_and_reindex, notify_keyspace_event as notify_event_prune_keyspace_event};
+pub use rdcl_evo_prune::{prune_calendar_events_overrides, notify_keyspace_event as notify_event_override_prune_keyspace_event};
+pub use rdcl_cal_idx_rebuild::notify_rebuild_complete_keyspace_event as notify_calendar_idx_rebuild_complete_keyspace_event;
+
+pub use rdcl_metrics::redical_metrics;
+
+// Define a public command handler (with the same name) wrapping each of the provided command
+// handlers, measuring each call against the command name.
+macro_rules! metered_commands {
+    ($($command_name:expr => $command_module:ident::$command_handler:ident),+ $(,)*) => {
+        $(
+            pub fn $command_handler(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+                crate::command_metrics::measure($command_name, || $command_module::$command_handler(ctx, args))
+            }
+        )+
+    }
+}
+
+// Each command handler is wrapped to record it's call count and latency (see rdcl.metrics).
+metered_commands! {
+    "rdcl.evi_query" => rdcl_evi_query::redical_event_instance_query,
+
+    "rdcl.cal_set" => rdcl_cal_set::redical_calendar_set,
+    "rdcl.cal_get" => rdcl_cal_get::redical_calendar_get,
+    "rdcl.cal_stats" => rdcl_cal_stats::redical_calendar_stats,
+    "rdcl.cal_export" => rdcl_cal_export::redical_calendar_export,
+    "rdcl.cal_idx_disable" => rdcl_cal_idx_disable::redical_calendar_idx_disable,
+    "rdcl.cal_idx_rebuild" => rdcl_cal_idx_rebuild::redical_calendar_idx_rebuild,
+    "rdcl.cal_idx_verify" => rdcl_cal_idx_verify::redical_calendar_idx_verify,
+    "rdcl.cal_idx_terms" => rdcl_cal_idx_terms::redical_calendar_idx_terms,
+
+    "rdcl.evi_add" => rdcl_evi_add::redical_event_instance_add,
+    "rdcl.evi_get" => rdcl_evi_get::redical_event_instance_get,
+    "rdcl.evi_list" => rdcl_evi_list::redical_event_instance_list,
+    "rdcl.evi_next" => rdcl_evi_next::redical_event_instance_next,
+
+    "rdcl.evo_del" => rdcl_evo_del::redical_event_override_del,
+    "rdcl.evo_get" => rdcl_evo_get::redical_event_override_get,
+    "rdcl.evo_set" => rdcl_evo_set::redical_event_override_set,
+    "rdcl.evo_mset" => rdcl_evo_mset::redical_event_override_mset,
+    "rdcl.evo_list" => rdcl_evo_list::redical_event_override_list,
+    "rdcl.evo_prune" => rdcl_evo_prune::redical_event_override_prune,
+
+    "rdcl.evt_get" => rdcl_evt_get::redical_event_get,
+    "rdcl.evt_children" => rdcl_evt_children::redical_event_children,
+    "rdcl.evt_set" => rdcl_evt_set::redical_event_set,
+    "rdcl.evt_mset" => rdcl_evt_mset::redical_event_mset,
+    "rdcl.evt_import" => rdcl_evt_import::redical_event_import,
+    "rdcl.evt_del" => rdcl_evt_del::redical_event_del,
+    "rdcl.evt_del_query" => rdcl_evt_del_query::redical_event_del_query,
+    "rdcl.evt_expire" => rdcl_evt_expire::redical_event_expire,
+    "rdcl.evt_list" => rdcl_evt_list::redical_event_list,
+    "rdcl.evt_scan" => rdcl_evt_scan::redical_event_scan,
+    "rdcl.evt_query" => rdcl_evt_query::redical_event_query,
+    "rdcl.evt_update_query" => rdcl_evt_update_query::redical_event_update_query,
+    "rdcl.evt_prune" => rdcl_evt_prune::redical_event_prune,
+
+    "rdcl.query_explain" => rdcl_query_explain::redical_query_explain,
+    "rdcl.query_profile" => rdcl_query_profile::redical_query_profile,
+    "rdcl.query_sub" => rdcl_query_sub::redical_query_sub,
+    "rdcl.query_unsub" => rdcl_query_unsub::redical_query_unsub,
+    "rdcl.query_sub_list" => rdcl_query_sub_list::redical_query_sub_list,
+}
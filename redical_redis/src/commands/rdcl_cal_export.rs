@@ -0,0 +1,133 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue, ThreadSafeContext};
+
+use crate::core::queries::query::Query;
+use crate::core::queries::event_query::EventQuery;
+use crate::core::{render_ics_document, Calendar, ExecutionDeadline};
+use crate::utils::{join_content_line_args, run_with_timeout, TimeoutError};
+use crate::{CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS};
+use crate::datatype::{open_indexed_calendar_key, CALENDAR_DATA_TYPE};
+
+pub fn redical_calendar_export(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() < 2 {
+        ctx.log_debug(format!("rdcl.cal_export: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+
+    let calendar_key = open_indexed_calendar_key(ctx, &calendar_uid)?;
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)?.cloned() else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    let default_tz = calendar.get_default_tz();
+
+    let query_string: String = join_content_line_args(args);
+
+    // Export the whole calendar if no query is provided.
+    let query_string = Some(query_string).filter(|query_string| !query_string.trim().is_empty());
+
+    if query_string.is_some() && !calendar.indexes_active {
+        return Err(RedisError::String(format!(
+            "rdcl.cal_export: Queries disabled on Calendar: {calendar_uid} because it's indexes have been disabled."
+        )));
+    }
+
+    ctx.log_debug(format!("rdcl.cal_export: calendar_uid: {calendar_uid} query: {query_string:?}").as_str());
+
+    let ical_parser_timeout_ms = *CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64;
+    let query_execution_timeout_ms = *CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64;
+
+    let calendar_uid = calendar_uid.to_string();
+
+    let blocked_client = ctx.block_client();
+
+    std::thread::spawn(move || {
+        let thread_ctx = ThreadSafeContext::with_blocked_client(blocked_client);
+
+        let Some(query_string) = query_string else {
+            let ics_document = render_ics_document(&calendar, calendar.events.values().map(|event| event.as_ref()));
+
+            thread_ctx.reply(Ok(RedisValue::BulkString(ics_document)));
+
+            return;
+        };
+
+        // Spawn the process of parsing the query into it's own timeout enforced thread to guard
+        // against malicious payloads intended to cause hangs.
+        let mut parsed_query =
+            match run_with_timeout(
+                move || EventQuery::from_str_with_default_timezone(query_string.as_str(), default_tz.as_ref()).map_err(RedisError::String),
+                std::time::Duration::from_millis(ical_parser_timeout_ms),
+            ) {
+                Ok(parser_result) => {
+                    match parser_result {
+                        Ok(parser_result) => parser_result,
+
+                        Err(parser_error) => {
+                            thread_ctx.reply(Err(parser_error));
+
+                            return;
+                        }
+                    }
+                },
+
+                Err(TimeoutError) => {
+                    thread_ctx.lock().log_warning(
+                        format!(
+                            "rdcl.cal_export: query iCal parser exceeded timeout -- calendar_uid: {calendar_uid}",
+                        ).as_str()
+                    );
+
+                    thread_ctx.reply(Err(RedisError::String(String::from("rdcl.cal_export: query iCal parser exceeded timeout"))));
+
+                    return;
+                },
+            };
+
+        // The query execution is cooperatively aborted once this deadline has been exceeded to
+        // guard against expensive queries hogging the thread indefinitely.
+        let execution_deadline = ExecutionDeadline::new(std::time::Duration::from_millis(query_execution_timeout_ms));
+
+        let query_results = match parsed_query.execute_with_deadline(&calendar, &execution_deadline) {
+            Ok(results) => results,
+
+            Err(_) if execution_deadline.is_exceeded() => {
+                thread_ctx.lock().log_warning(
+                    format!(
+                        "rdcl.cal_export: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                    ).as_str()
+                );
+
+                thread_ctx.reply(Err(RedisError::String(String::from("rdcl.cal_export: query exceeded timeout"))));
+
+                return;
+            },
+
+            Err(error) => {
+                thread_ctx.reply(Err(RedisError::String(error)));
+
+                return;
+            },
+        };
+
+        let ics_document = render_ics_document(
+            &calendar,
+            query_results
+                .results
+                .iter()
+                .map(|query_result| &query_result.result),
+        );
+
+        thread_ctx.reply(Ok(RedisValue::BulkString(ics_document)));
+    });
+
+    // We will reply later, from the thread
+    Ok(RedisValue::NoReply)
+}
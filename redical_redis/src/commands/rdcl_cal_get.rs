@@ -2,8 +2,10 @@ use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, Redis
 
 use crate::core::Calendar;
 use crate::datatype::CALENDAR_DATA_TYPE;
+use crate::reply::extract_trailing_flag_arg;
 
-use redical_ical::ICalendarComponent;
+use redical_ical::{ICalendarComponent, ICalendarEntity};
+use redical_ical::values::date_time::DateTime;
 
 fn serialize_calendar(calendar: &Calendar) -> RedisValue {
     RedisValue::Array(
@@ -15,26 +17,61 @@ fn serialize_calendar(calendar: &Calendar) -> RedisValue {
     )
 }
 
-pub fn redical_calendar_get(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+fn serialize_calendar_metadata(calendar: &Calendar) -> RedisValue {
+    let override_count: usize = calendar.events.values().map(|event| event.overrides.len()).sum();
+
+    let indexes_rebuilt_at =
+        calendar.indexes_rebuilt_at.map_or(
+            RedisValue::Null,
+            |timestamp| RedisValue::SimpleString(DateTime::from(timestamp).render_ical()),
+        );
+
+    let key_value_pairs = [
+        ("uid", RedisValue::SimpleString(calendar.uid.uid.to_string())),
+        ("event_count", RedisValue::Integer(calendar.events.len() as i64)),
+        ("override_count", RedisValue::Integer(override_count as i64)),
+        ("indexes_active", RedisValue::Bool(calendar.indexes_active)),
+        ("indexes_rebuilt_at", indexes_rebuilt_at),
+        ("generation", RedisValue::Integer(calendar.generation as i64)),
+        ("properties", serialize_calendar(calendar)),
+    ];
+
+    RedisValue::Array(
+        key_value_pairs
+            .into_iter()
+            .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+            .collect()
+    )
+}
+
+pub fn redical_calendar_get(ctx: &Context, mut args: Vec<RedisString>) -> RedisResult {
     if args.is_empty() {
         ctx.log_debug(format!("rdcl.cal_get: WrongArity: {}", args.len()).as_str());
 
         return Err(RedisError::WrongArity);
     }
 
+    // Reply with the metadata of the Calendar (event counts, index state, etc.) alongside it's
+    // properties.
+    let with_metadata = args.len() > 2 && extract_trailing_flag_arg(&mut args, "WITHMETADATA");
+
     let mut args = args.into_iter().skip(1);
 
     let calendar_uid = args.next_arg()?;
 
     let calendar_key = ctx.open_key(&calendar_uid);
 
-    ctx.log_debug(format!("rdcl.cal_get: key: {calendar_uid}").as_str());
+    ctx.log_debug(format!("rdcl.cal_get: key: {calendar_uid} with metadata: {with_metadata}").as_str());
 
     calendar_key
         .get_value::<Calendar>(&CALENDAR_DATA_TYPE)?
         .map_or(
             Ok(RedisValue::Null),
             |calendar| {
+                if with_metadata {
+                    return Ok(serialize_calendar_metadata(calendar));
+                }
+
                 Ok(serialize_calendar(calendar))
             },
         )
@@ -1,7 +1,7 @@
 use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue, NotifyEvent, Status};
 
 use crate::core::Calendar;
-use crate::datatype::CALENDAR_DATA_TYPE;
+use crate::datatype::{discard_pending_index_rebuild, CALENDAR_DATA_TYPE};
 
 pub fn redical_calendar_idx_disable(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     if args.is_empty() {
@@ -22,6 +22,9 @@ pub fn redical_calendar_idx_disable(ctx: &Context, args: Vec<RedisString>) -> Re
         )));
     };
 
+    // Prevent the indexes of a Calendar loaded from the RDB from being rebuilt in the background.
+    discard_pending_index_rebuild(ctx, &calendar_uid);
+
     if !calendar.indexes_active {
         ctx.log_debug(format!("rdcl.cal_idx_disable: key: {calendar_uid} skipped - already disabled").as_str());
 
@@ -30,6 +33,11 @@ pub fn redical_calendar_idx_disable(ctx: &Context, args: Vec<RedisString>) -> Re
 
     calendar.disable_indexes();
 
+    calendar.bump_generation();
+
+    // Use this command when replicating across other Redis instances.
+    ctx.replicate_verbatim();
+
     notify_keyspace_event(ctx, &calendar_uid)?;
 
     ctx.log_debug(format!("rdcl.cal_idx_disable: key: {calendar_uid}").as_str());
@@ -1,7 +1,8 @@
 use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue, NotifyEvent, Status};
 
-use redical_core::Calendar;
+use redical_core::{Calendar, IndexRebuildStats};
 use crate::datatype::CALENDAR_DATA_TYPE;
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
 
 pub fn redical_calendar_idx_rebuild(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     if args.is_empty() {
@@ -22,9 +23,20 @@ pub fn redical_calendar_idx_rebuild(ctx: &Context, args: Vec<RedisString>) -> Re
         )));
     };
 
-    calendar.rebuild_indexes().map_err(RedisError::String)?;
+    let index_rebuild_stats = calendar.rebuild_indexes_at(chrono::Utc::now().timestamp()).map_err(RedisError::String)?;
+
+    // Use this command when replicating across other Redis instances.
+    ctx.replicate_verbatim();
 
     notify_keyspace_event(ctx, &calendar_uid)?;
+    notify_rebuild_complete_keyspace_event(ctx, &calendar_uid, &index_rebuild_stats)?;
+
+    // The rebuilt indexes reflect the current term normalisation, which may change query results.
+    calendar.bump_generation();
+
+    // Query subscriptions are not re-evaluated whilst the indexes are disabled, so any mutations
+    // made in the meantime are reflected now.
+    notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
 
     ctx.log_debug(format!("rdcl.cal_idx_rebuild: key: {calendar_uid}").as_str());
 
@@ -44,3 +56,25 @@ fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString) -> Result<()
 
     Ok(())
 }
+
+// Published once the indexes have been rebuilt (explicitly, or in the background after loading)
+// with the statistics of the rebuild, so that orchestration tooling can track reindexing progress.
+pub fn notify_rebuild_complete_keyspace_event(ctx: &Context, calendar_uid: &RedisString, index_rebuild_stats: &IndexRebuildStats) -> Result<(), RedisError> {
+    let event_message =
+        format!(
+            "rdcl.cal_idx_rebuild_complete:{}:{}:{}",
+            index_rebuild_stats.duration.as_millis(),
+            index_rebuild_stats.event_count,
+            index_rebuild_stats.term_count,
+        );
+
+    if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), calendar_uid) == Status::Err {
+        return Err(
+            RedisError::String(
+                format!("Notify keyspace event \"{event_message}\" for calendar: \"{}\"", &calendar_uid)
+            )
+        );
+    }
+
+    Ok(())
+}
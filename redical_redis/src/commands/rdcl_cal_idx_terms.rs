@@ -0,0 +1,65 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::core::Calendar;
+use crate::datatype::{open_indexed_calendar_key, CALENDAR_DATA_TYPE};
+
+fn serialize_indexed_term_counts(indexed_term_counts: Vec<(String, usize)>) -> RedisValue {
+    RedisValue::Array(
+        indexed_term_counts
+            .into_iter()
+            .flat_map(|(term, event_count)| [RedisValue::BulkString(term), RedisValue::Integer(event_count as i64)])
+            .collect()
+    )
+}
+
+pub fn redical_calendar_idx_terms(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() != 3 && args.len() != 5 {
+        ctx.log_debug(format!("rdcl.cal_idx_terms: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+    let index = args.next_arg()?.to_string();
+
+    let mut prefix = None;
+
+    while let Some(option) = args.next() {
+        match option.to_string().to_ascii_uppercase().as_str() {
+            "PREFIX" => {
+                prefix = Some(args.next_arg()?.to_string());
+            },
+
+            option => {
+                return Err(RedisError::String(format!(
+                    "rdcl.cal_idx_terms: Unknown option: {option} (expected PREFIX)"
+                )));
+            },
+        }
+    }
+
+    let calendar_key = open_indexed_calendar_key(ctx, &calendar_uid)?;
+
+    ctx.log_debug(format!("rdcl.cal_idx_terms: key: {calendar_uid} index: {index} prefix: {prefix:?}").as_str());
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    if !calendar.indexes_active {
+        return Err(RedisError::String(format!(
+            "rdcl.cal_idx_terms: Calendar: {calendar_uid} terms cannot be listed because it's indexes have been disabled."
+        )));
+    }
+
+    let indexed_term_counts =
+        calendar
+            .get_indexed_term_counts(index.as_str(), prefix.as_deref())
+            .map_err(|error| RedisError::String(format!("rdcl.cal_idx_terms: {error}")))?;
+
+    Ok(serialize_indexed_term_counts(indexed_term_counts))
+}
@@ -0,0 +1,137 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use std::collections::HashSet;
+
+use crate::core::{Calendar, IndexDiscrepancy, IndexVerification};
+use crate::datatype::{open_indexed_calendar_key, CALENDAR_DATA_TYPE};
+
+// Sample the events evenly across the Calendar (ordered by UID) from a varying starting point, so
+// that repeated verifications cover different events.
+fn sample_event_uids(calendar: &Calendar, sample_count: usize) -> Option<HashSet<String>> {
+    let event_count = calendar.events.len();
+
+    if sample_count >= event_count {
+        return None;
+    }
+
+    let stride = event_count / sample_count;
+
+    let start =
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.subsec_nanos() as usize % stride);
+
+    Some(
+        calendar.events
+            .keys()
+            .skip(start)
+            .step_by(stride)
+            .take(sample_count)
+            .cloned()
+            .collect()
+    )
+}
+
+fn serialize_index_discrepancy(index_discrepancy: &IndexDiscrepancy) -> RedisValue {
+    let key_value_pairs = [
+        ("event_uid", RedisValue::BulkString(index_discrepancy.event_uid.to_owned())),
+        ("index", RedisValue::SimpleString(index_discrepancy.index.to_owned())),
+        ("term", index_discrepancy.term.to_owned().map_or(RedisValue::Null, RedisValue::BulkString)),
+        ("discrepancy", RedisValue::SimpleString(index_discrepancy.kind.to_string())),
+    ];
+
+    RedisValue::Array(
+        key_value_pairs
+            .into_iter()
+            .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+            .collect()
+    )
+}
+
+fn serialize_index_verification(index_verification: &IndexVerification) -> RedisValue {
+    let key_value_pairs = [
+        ("verified_event_count", RedisValue::Integer(index_verification.verified_event_count as i64)),
+        ("consistent", RedisValue::Bool(index_verification.is_consistent())),
+        (
+            "discrepancies",
+            RedisValue::Array(
+                index_verification.discrepancies
+                    .iter()
+                    .map(serialize_index_discrepancy)
+                    .collect()
+            ),
+        ),
+    ];
+
+    RedisValue::Array(
+        key_value_pairs
+            .into_iter()
+            .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+            .collect()
+    )
+}
+
+pub fn redical_calendar_idx_verify(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() < 2 {
+        ctx.log_debug(format!("rdcl.cal_idx_verify: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+
+    let mut sample_count = None;
+
+    while let Some(option) = args.next() {
+        match option.to_string().to_ascii_uppercase().as_str() {
+            "SAMPLE" => {
+                let count = args.next_u64()? as usize;
+
+                if count == 0 {
+                    return Err(RedisError::String(String::from("rdcl.cal_idx_verify: SAMPLE must be greater than 0")));
+                }
+
+                sample_count = Some(count);
+            },
+
+            option => {
+                return Err(RedisError::String(format!(
+                    "rdcl.cal_idx_verify: Unknown option: {option} (expected SAMPLE)"
+                )));
+            },
+        }
+    }
+
+    let calendar_key = open_indexed_calendar_key(ctx, &calendar_uid)?;
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "rdcl.cal_idx_verify: No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    if !calendar.indexes_active {
+        return Err(RedisError::String(format!(
+            "rdcl.cal_idx_verify: Calendar: {calendar_uid} cannot be verified because it's indexes have been disabled."
+        )));
+    }
+
+    ctx.log_debug(format!("rdcl.cal_idx_verify: calendar_uid: {calendar_uid} sample: {sample_count:?}").as_str());
+
+    let sampled_event_uids = sample_count.and_then(|sample_count| sample_event_uids(calendar, sample_count));
+
+    let index_verification = calendar.verify_indexes(sampled_event_uids.as_ref()).map_err(RedisError::String)?;
+
+    if !index_verification.is_consistent() {
+        ctx.log_warning(
+            format!(
+                "rdcl.cal_idx_verify: index discrepancies found -- calendar_uid: {calendar_uid} discrepancies: {} (rebuild with rdcl.cal_idx_rebuild)",
+                index_verification.discrepancies.len(),
+            ).as_str()
+        );
+    }
+
+    Ok(serialize_index_verification(&index_verification))
+}
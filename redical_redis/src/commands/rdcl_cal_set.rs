@@ -1,9 +1,17 @@
 use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, RedisValue, Status};
 
+use std::iter::Peekable;
+use std::str::FromStr;
+
 use redical_core::Calendar;
-use crate::datatype::CALENDAR_DATA_TYPE;
+use crate::commands::notify_calendar_idx_rebuild_complete_keyspace_event;
+use crate::datatype::{register_calendar, CALENDAR_DATA_TYPE};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
+use crate::scheduled_pruning::track_pruning_calendar;
+use crate::utils::{get_selected_db, join_content_line_args};
 
 use redical_ical::ICalendarComponent;
+use redical_ical::properties::{CalendarProperties, CalendarProperty};
 
 fn serialize_calendar(calendar: &Calendar) -> RedisValue {
     RedisValue::Array(
@@ -16,30 +24,93 @@ fn serialize_calendar(calendar: &Calendar) -> RedisValue {
 }
 
 pub fn redical_calendar_set(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
-    if args.is_empty() {
+    if args.len() < 2 {
         ctx.log_debug(format!("rdcl.cal_set: WrongArity: {}", args.len()).as_str());
 
         return Err(RedisError::WrongArity);
     }
 
-    let mut args = args.into_iter().skip(1);
+    let mut args = args.into_iter().skip(1).peekable();
 
     let calendar_uid = args.next_arg()?;
 
+    let generation = parse_optional_generation_args(&mut args)?;
+
+    let other = join_content_line_args(args);
+
+    // Optional calendar properties (e.g. X-DEFAULT-TZID:Europe/London, NAME:Team Calendar) to set on the Calendar.
+    let calendar_properties =
+        if other.is_empty() {
+            Vec::new()
+        } else {
+            CalendarProperties::from_str(other.as_str()).map_err(RedisError::String)?.0
+        };
+
     let calendar_key = ctx.open_key_writable(&calendar_uid);
 
     if let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? {
         ctx.log_debug(format!("rdcl.cal_set: key: {calendar_uid} -- exists: {:#?}", &calendar).as_str());
 
+        // Without any calendar properties (or generation), the existing Calendar is returned
+        // unchanged.
+        if calendar_properties.is_empty() && generation.is_none() {
+            return Ok(serialize_calendar(calendar));
+        }
+
+        // Validate the calendar properties against a new Calendar first, so that the existing
+        // Calendar is not partially updated if any are invalid.
+        insert_calendar_properties(&mut Calendar::new(calendar_uid.to_string()), calendar_properties.clone())?;
+
+        let previous_term_normalization = calendar.get_term_normalization();
+
+        insert_calendar_properties(calendar, calendar_properties)?;
+
+        // The terms already indexed are re-normalised as now declared (via X-TERM-NORMALIZATION),
+        // so that they match the queried values normalised the same way. Disabled indexes are
+        // normalised as declared once they are rebuilt.
+        if calendar.indexes_active && calendar.get_term_normalization() != previous_term_normalization {
+            let index_rebuild_stats = calendar.rebuild_indexes_at(chrono::Utc::now().timestamp()).map_err(RedisError::String)?;
+
+            notify_calendar_idx_rebuild_complete_keyspace_event(ctx, &calendar_uid, &index_rebuild_stats)?;
+        }
+
+        match generation {
+            Some(generation) => calendar.generation = generation,
+            None => { calendar.bump_generation(); },
+        }
+
+        track_pruning_calendar_if_declared(ctx, calendar);
+
+        ctx.replicate_verbatim();
+
+        notify_keyspace_event(ctx, &calendar_uid)?;
+
+        // Calendar properties (e.g. X-DEFAULT-TZID) may change query results.
+        notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
+
         return Ok(serialize_calendar(calendar));
     };
 
     ctx.log_debug(format!("rdcl.cal_set: key: {calendar_uid}").as_str());
 
-    let calendar = Calendar::new(calendar_uid.clone().into());
+    let mut calendar = Calendar::new(calendar_uid.clone().into());
+
+    insert_calendar_properties(&mut calendar, calendar_properties)?;
+
+    if let Some(generation) = generation {
+        calendar.generation = generation;
+    }
+
+    track_pruning_calendar_if_declared(ctx, &calendar);
 
     calendar_key.set_value(&CALENDAR_DATA_TYPE, calendar.clone())?;
 
+    // Register the stored Calendar (rather than the copy replied below), so that it is known when
+    // it's database is flushed.
+    if let Some(stored_calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? {
+        register_calendar(get_selected_db(ctx), stored_calendar);
+    }
+
     // Use this command when replicating across other Redis instances.
     ctx.replicate_verbatim();
 
@@ -48,6 +119,46 @@ pub fn redical_calendar_set(ctx: &Context, args: Vec<RedisString>) -> RedisResul
     Ok(serialize_calendar(&calendar))
 }
 
+// Parse the optional `GENERATION <generation>` arguments, explicitly setting the Calendar generation
+// (instead of bumping it), so that it is retained when the Calendar is reconstructed from the
+// rewritten AOF.
+fn parse_optional_generation_args<I>(args: &mut Peekable<I>) -> Result<Option<u64>, RedisError>
+where
+    I: Iterator<Item = RedisString>,
+{
+    let is_present =
+        args.next_if(|arg| {
+            arg.try_as_str()
+                .is_ok_and(|arg| arg.eq_ignore_ascii_case("GENERATION"))
+        })
+        .is_some();
+
+    if !is_present {
+        return Ok(None);
+    }
+
+    let input = args.next_arg()?;
+    let input = input.try_as_str()?;
+
+    input
+        .parse::<u64>()
+        .map(Some)
+        .map_err(|_| RedisError::String(format!("GENERATION: {input} is invalid, expected a non-negative integer")))
+}
+
+fn insert_calendar_properties(calendar: &mut Calendar, calendar_properties: Vec<CalendarProperty>) -> Result<(), RedisError> {
+    calendar.insert_properties(calendar_properties).map_err(RedisError::String)?;
+
+    Ok(())
+}
+
+// Track the Calendar for the periodic pruning sweep if it declares a retention period (via
+// X-PRUNE-OLDER-THAN).
+fn track_pruning_calendar_if_declared(ctx: &Context, calendar: &Calendar) {
+    if calendar.prune_older_than.is_some() {
+        track_pruning_calendar(get_selected_db(ctx), calendar.uid.uid.to_string());
+    }
+}
 
 fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString) -> Result<(), RedisError> {
     let event_message = "rdcl.cal_set";
@@ -0,0 +1,62 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::core::{Calendar, CalendarStats};
+use crate::datatype::CALENDAR_DATA_TYPE;
+
+use redical_ical::ICalendarEntity;
+use redical_ical::values::date_time::DateTime;
+
+fn serialize_timestamp(timestamp: Option<i64>) -> RedisValue {
+    timestamp.map_or(
+        RedisValue::Null,
+        |timestamp| RedisValue::SimpleString(DateTime::from(timestamp).render_ical()),
+    )
+}
+
+fn serialize_calendar_stats(calendar_stats: &CalendarStats) -> RedisValue {
+    let key_value_pairs = [
+        ("event_count", RedisValue::Integer(calendar_stats.event_count as i64)),
+        ("override_count", RedisValue::Integer(calendar_stats.override_count as i64)),
+        ("indexes_active", RedisValue::Bool(calendar_stats.indexes_active)),
+        ("indexed_categories_term_count", RedisValue::Integer(calendar_stats.indexed_categories_term_count as i64)),
+        ("indexed_location_type_term_count", RedisValue::Integer(calendar_stats.indexed_location_type_term_count as i64)),
+        ("indexed_related_to_term_count", RedisValue::Integer(calendar_stats.indexed_related_to_term_count as i64)),
+        ("indexed_geo_term_count", RedisValue::Integer(calendar_stats.indexed_geo_term_count as i64)),
+        ("indexed_class_term_count", RedisValue::Integer(calendar_stats.indexed_class_term_count as i64)),
+        ("indexed_location_term_count", RedisValue::Integer(calendar_stats.indexed_location_term_count as i64)),
+        ("approximate_memory_usage", RedisValue::Integer(calendar_stats.approximate_memory_usage as i64)),
+        ("earliest_dtstart", serialize_timestamp(calendar_stats.earliest_dtstart)),
+        ("latest_dtstart", serialize_timestamp(calendar_stats.latest_dtstart)),
+    ];
+
+    RedisValue::Array(
+        key_value_pairs
+            .into_iter()
+            .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+            .collect()
+    )
+}
+
+pub fn redical_calendar_stats(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() != 2 {
+        ctx.log_debug(format!("rdcl.cal_stats: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+
+    let calendar_key = ctx.open_key(&calendar_uid);
+
+    ctx.log_debug(format!("rdcl.cal_stats: key: {calendar_uid}").as_str());
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    Ok(serialize_calendar_stats(&calendar.get_stats()))
+}
@@ -0,0 +1,281 @@
+use std::str::FromStr;
+
+use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, RedisValue, Status};
+
+use crate::core::{Calendar, EventOccurrenceOverride, get_changed_property_names};
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
+use crate::datatype::CALENDAR_DATA_TYPE;
+
+use crate::reply::{ReplyFormat, icalendar_component_to_redis_value};
+use crate::utils::{join_content_line_args, run_with_timeout, TimeoutError};
+use crate::{CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, CONFIGURATION_NOTIFICATION_VERBOSITY, NotificationVerbosity};
+
+use redical_ical::{ICalendarComponent, RenderingContext};
+use redical_ical::properties::LastModifiedProperty;
+use redical_ical::values::date_time::DateTime;
+
+use super::rdcl_evt_set::update_calendar_indexes;
+
+pub fn redical_event_instance_add(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() < 4 {
+        ctx.log_debug(format!("rdcl.evi_add: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+    let event_uid = args.next_arg()?.to_string();
+    let occurrence_date_string = args.next_arg()?.try_as_str()?;
+
+    let occurrence_timestamp =
+        DateTime::from_str(occurrence_date_string)
+            .map(|datetime| datetime.get_utc_timestamp(None))
+            .map_err(RedisError::String)?;
+
+    let other: String = join_content_line_args(args);
+
+    ctx.log_debug(
+        format!("rdcl.evi_add: calendar_uid: {calendar_uid} event_uid: {event_uid} occurrence date string: {occurrence_date_string} ical: {other}").as_str()
+    );
+
+    let calendar_key = ctx.open_key_writable(&calendar_uid);
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    let Some(existing_event) = calendar.get_event(&event_uid).cloned() else {
+        return Err(RedisError::String(format!(
+            "No event with UID: '{event_uid}' found",
+        )));
+    };
+
+    // The accompanying event occurrence override is optional, the occurrence is added as it is
+    // scheduled by the event if omitted.
+    let event_occurrence_override =
+        if other.trim().is_empty() {
+            None
+        } else {
+            // Spawn the process of parsing the query into it's own timeout enforced thread to
+            // guard against malicious payloads intended to cause hangs.
+            let mut event_occurrence_override =
+                match run_with_timeout(
+                    move || EventOccurrenceOverride::parse_ical(occurrence_date_string, other.as_str()).map_err(RedisError::String),
+                    std::time::Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64),
+                ) {
+                    Ok(parser_result) => {
+                        parser_result?
+                    },
+
+                    Err(TimeoutError) => {
+                        ctx.log_warning(
+                            format!(
+                                "rdcl.evi_add: event occurrence override iCal parser exceeded timeout -- calendar_uid: {calendar_uid} event_uid: {event_uid} occurrence date string: {occurrence_date_string}",
+                            ).as_str()
+                        );
+
+                        return Err(
+                            RedisError::String(
+                                String::from("rdcl.evi_add: event occurrence override iCal parser exceeded timeout")
+                            )
+                        );
+                    },
+                };
+
+            calendar.localize_event_occurrence_override(&mut event_occurrence_override).map_err(RedisError::String)?;
+
+            Some(event_occurrence_override)
+        };
+
+    // Validate the event occurrence override's LAST-MODIFIED property (if provided) is more
+    // recent than that on the existing event occurrence override (if present), skipping adding
+    // the occurrence altogether if not (as with rdcl.evo_set).
+    if let (Some(event_occurrence_override), Some(existing_event_occurrence_override)) = (event_occurrence_override.as_ref(), existing_event.overrides.get(&occurrence_timestamp)) {
+        if event_occurrence_override.last_modified < existing_event_occurrence_override.last_modified {
+            ctx.log_debug(
+                format!(
+                    "rdcl.evi_add: key: {calendar_uid} event uid: {event_uid} - DTSTART: {occurrence_date_string} - skipped due to existing superseding LAST-MODIFIED - existing: {} new: {}",
+                    existing_event_occurrence_override.last_modified,
+                    event_occurrence_override.last_modified,
+                ).as_str()
+            );
+
+            return Ok(RedisValue::Bool(false));
+        }
+    }
+
+    let mut event = existing_event.clone();
+
+    let is_occurrence_added = event.add_occurrence(occurrence_timestamp).map_err(RedisError::String)?;
+
+    if let Some(event_occurrence_override) = event_occurrence_override.as_ref() {
+        event.override_occurrence(event_occurrence_override, calendar.indexes_active.to_owned()).map_err(RedisError::String)?;
+    }
+
+    // The event instance is rendered in the Calendar default timezone (as with query results).
+    let rendering_context =
+        calendar
+            .get_default_tz()
+            .map(|tz| RenderingContext { tz: Some(tz), distance_unit: None });
+
+    let event_instance =
+        event
+            .get_instance(occurrence_timestamp, calendar.get_default_dst_policy().unwrap_or_default())
+            .map_err(RedisError::String)?;
+
+    let serialized_event_instance =
+        event_instance.map_or(
+            RedisValue::Null,
+            |event_instance| {
+                icalendar_component_to_redis_value(&event_instance, rendering_context.as_ref(), ReplyFormat::from_context(ctx))
+            },
+        );
+
+    // Nothing to write if the event already has the occurrence and no override was provided.
+    if !is_occurrence_added && event_occurrence_override.is_none() {
+        return Ok(serialized_event_instance);
+    }
+
+    let event_message =
+        build_keyspace_event_message(
+            CONFIGURATION_NOTIFICATION_VERBOSITY.lock(ctx).clone(),
+            &event_uid,
+            occurrence_date_string,
+            is_occurrence_added,
+            existing_event.overrides.get(&occurrence_timestamp),
+            event_occurrence_override.as_ref(),
+            &event.last_modified,
+        );
+
+    let mut changes = Vec::new();
+
+    if is_occurrence_added {
+        changes.push(Change::new(ChangeType::EventSet, &event_uid).with_last_modified(&event.last_modified));
+    }
+
+    if let Some(event_occurrence_override) = event_occurrence_override.as_ref() {
+        changes.push(
+            Change::new(ChangeType::OverrideSet, &event_uid)
+                .with_override_date_string(occurrence_date_string)
+                .with_last_modified(&event_occurrence_override.last_modified)
+        );
+    }
+
+    // The added occurrence only affects the indexed time buckets, whereas the accompanying event
+    // occurrence override (if provided) can also affect the indexed terms.
+    if calendar.indexes_active {
+        update_calendar_indexes(calendar, &event_uid, Some(&existing_event), &event)?;
+    }
+
+    calendar.insert_event(event);
+
+    ctx.log_debug(
+        format!(
+            "rdcl.evi_add: key: {calendar_uid} event uid: {event_uid} - DTSTART: {occurrence_date_string} - occurrence added: {is_occurrence_added} - override: {}",
+            event_occurrence_override.is_some(),
+        ).as_str()
+    );
+
+    // Replicate the event occurrence override as stored (rather than verbatim) so that any values
+    // resolved by this instance (e.g. defaulting LAST-MODIFIED to now) are identical across
+    // replicas and the AOF.
+    match event_occurrence_override.as_ref() {
+        Some(event_occurrence_override) => {
+            ctx.replicate(
+                "rdcl.evi_add",
+                &[
+                    calendar_uid.to_string().as_str(),
+                    event_uid.as_str(),
+                    occurrence_date_string,
+                    event_occurrence_override.to_rendered_content_lines().join(" ").as_str(),
+                ],
+            );
+        },
+
+        None => {
+            ctx.replicate(
+                "rdcl.evi_add",
+                &[
+                    calendar_uid.to_string().as_str(),
+                    event_uid.as_str(),
+                    occurrence_date_string,
+                ],
+            );
+        },
+    }
+
+    notify_keyspace_event(ctx, &calendar_uid, &event_uid, occurrence_date_string, &event_message)?;
+
+    append_changes(ctx, &calendar_uid, &changes);
+
+    calendar.bump_generation();
+
+    notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
+
+    Ok(serialized_event_instance)
+}
+
+// Build the keyspace event message detailed according to the notification-verbosity config.
+fn build_keyspace_event_message(
+    notification_verbosity: NotificationVerbosity,
+    event_uid: &String,
+    occurrence_date_string: &str,
+    is_occurrence_added: bool,
+    existing_event_occurrence_override: Option<&EventOccurrenceOverride>,
+    event_occurrence_override: Option<&EventOccurrenceOverride>,
+    event_last_modified: &LastModifiedProperty,
+) -> String {
+    // The LAST-MODIFIED of the override (if provided) is the most relevant to the occurrence.
+    let last_modified =
+        event_occurrence_override
+            .map(|event_occurrence_override| &event_occurrence_override.last_modified)
+            .unwrap_or(event_last_modified);
+
+    match notification_verbosity {
+        NotificationVerbosity::minimal => {
+            format!("rdcl.evi_add:{event_uid}:{occurrence_date_string}")
+        },
+
+        NotificationVerbosity::standard => {
+            format!("rdcl.evi_add:{event_uid}:{occurrence_date_string} {last_modified}")
+        },
+
+        NotificationVerbosity::detailed => {
+            let mut changed_property_names = Vec::new();
+
+            if is_occurrence_added {
+                changed_property_names.push(String::from("RDATE"));
+            }
+
+            if let Some(event_occurrence_override) = event_occurrence_override {
+                changed_property_names.extend(get_changed_property_names(existing_event_occurrence_override, event_occurrence_override));
+            }
+
+            if changed_property_names.is_empty() {
+                return format!("rdcl.evi_add:{event_uid}:{occurrence_date_string} {last_modified}");
+            }
+
+            format!(
+                "rdcl.evi_add:{event_uid}:{occurrence_date_string} {last_modified} {}",
+                changed_property_names.join(","),
+            )
+        },
+    }
+}
+
+fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String, occurrence_date_string: &str, event_message: &str) -> Result<(), RedisError> {
+    if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message, calendar_uid) == Status::Err {
+        return Err(
+            RedisError::String(
+                format!("Notify keyspace event \"rdcl.evi_add\" for calendar: \"{}\" event: \"{}\" date string: \"{}\"", &calendar_uid, &event_uid, &occurrence_date_string)
+            )
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::str::FromStr;
+
+use chrono_tz::Tz;
+
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::core::Calendar;
+use crate::datatype::CALENDAR_DATA_TYPE;
+
+use crate::reply::{ReplyFormat, icalendar_component_to_redis_value};
+
+use redical_ical::RenderingContext;
+use redical_ical::values::date_time::DateTime;
+
+pub fn redical_event_instance_get(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if !(4..=6).contains(&args.len()) {
+        ctx.log_debug(format!("rdcl.evi_get: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+    let event_uid = args.next_arg()?.to_string();
+    let recurrence_id_date_string = args.next_arg()?.try_as_str()?;
+
+    let recurrence_id_timestamp =
+        DateTime::from_str(recurrence_id_date_string)
+            .map(|datetime| datetime.get_utc_timestamp(None))
+            .map_err(RedisError::String)?;
+
+    let mut tz = None;
+
+    while let Some(option) = args.next() {
+        match option.to_string().to_ascii_uppercase().as_str() {
+            "TZID" => {
+                let tzid = args.next_arg()?.to_string();
+
+                tz = Some(
+                    Tz::from_str(tzid.as_str()).map_err(|_error| {
+                        RedisError::String(format!("rdcl.evi_get: Invalid TZID: {tzid}"))
+                    })?
+                );
+            },
+
+            option => {
+                return Err(RedisError::String(format!(
+                    "rdcl.evi_get: Unknown option: {option} (expected TZID)"
+                )));
+            },
+        }
+    }
+
+    ctx.log_debug(
+        format!("rdcl.evi_get: calendar_uid: {calendar_uid} event_uid: {event_uid} recurrence-id date string: {recurrence_id_date_string}").as_str()
+    );
+
+    let calendar_key = ctx.open_key(&calendar_uid);
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    let Some(event) = calendar.events.get(&event_uid) else {
+        return Err(RedisError::String(format!(
+            "No event with UID: '{event_uid}' found",
+        )));
+    };
+
+    // The event instance is rendered in the Calendar default timezone (as with query results)
+    // unless a timezone is provided.
+    let rendering_context =
+        tz.or_else(|| calendar.get_default_tz())
+          .map(|tz| RenderingContext { tz: Some(tz), distance_unit: None });
+
+    let event_instance =
+        event
+            .get_instance(recurrence_id_timestamp, calendar.get_default_dst_policy().unwrap_or_default())
+            .map_err(RedisError::String)?;
+
+    event_instance.map_or(
+        Ok(RedisValue::Null),
+        |event_instance| {
+            Ok(icalendar_component_to_redis_value(&event_instance, rendering_context.as_ref(), ReplyFormat::from_context(ctx)))
+        },
+    )
+}
@@ -1,17 +1,20 @@
 use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
 
 use crate::datatype::CALENDAR_DATA_TYPE;
+use crate::reply::{ReplyFormat, event_instances_to_redis_value};
+use crate::{CONFIGURATION_OCCURRENCE_EXPANSION_SAFETY_HORIZON_YEARS, CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS};
 
-use redical_ical::ICalendarComponent;
-use redical_core::{Calendar, EventInstanceIterator};
+use redical_core::{Calendar, EventInstanceIterator, ExecutionDeadline, OccurrenceExpansionLimits};
 
-pub fn redical_event_instance_list(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+pub fn redical_event_instance_list(ctx: &Context, mut args: Vec<RedisString>) -> RedisResult {
     if args.len() < 2 {
         ctx.log_debug(format!("rdcl.evi_list: WrongArity: {}", args.len()).as_str());
 
         return Err(RedisError::WrongArity);
     }
 
+    let reply_format = ReplyFormat::from_output_args(ctx, &mut args)?;
+
     let mut args = args.into_iter().skip(1);
 
     let calendar_uid = args.next_arg()?;
@@ -20,6 +23,17 @@ pub fn redical_event_instance_list(ctx: &Context, args: Vec<RedisString>) -> Red
     let offset = args.next_u64().unwrap_or(0) as usize;
     let count = args.next_u64().unwrap_or(50) as usize;
 
+    // Zero represents no safety horizon on the expansion of event occurrences.
+    let occurrence_expansion_safety_horizon_years =
+        Some(*CONFIGURATION_OCCURRENCE_EXPANSION_SAFETY_HORIZON_YEARS.lock(ctx))
+            .filter(|occurrence_expansion_safety_horizon_years| *occurrence_expansion_safety_horizon_years > 0);
+
+    let occurrence_expansion_limits = OccurrenceExpansionLimits::default().with_safety_horizon_years(occurrence_expansion_safety_horizon_years);
+
+    // The expansion runs on the main thread, so is aborted once this deadline has been exceeded to
+    // guard against pathological recurrences (or offsets) blocking it.
+    let execution_deadline = ExecutionDeadline::new(std::time::Duration::from_millis(*CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64));
+
     let calendar_key = ctx.open_key(&calendar_uid);
 
     ctx.log_debug(
@@ -34,26 +48,42 @@ pub fn redical_event_instance_list(ctx: &Context, args: Vec<RedisString>) -> Red
         return Ok(RedisValue::Null);
     };
 
-    let event_instance_iterator = EventInstanceIterator::new(event, None, None, None, None);
+    // Event instances landing on a local time skipped or repeated by a DST transition are
+    // resolved as per the Calendar default policy (if declared).
+    let event_instance_iterator =
+        EventInstanceIterator::new(event, None, None, None, None)
+            .map(|event_instance_iterator| {
+                event_instance_iterator
+                    .with_dst_policy(calendar.get_default_dst_policy().unwrap_or_default())
+                    .with_occurrence_expansion_limits(occurrence_expansion_limits.clone())
+                    .with_execution_deadline(execution_deadline)
+            });
 
     match event_instance_iterator {
-        Ok(event_instance_iterator) => {
-            let event_instances =
-                event_instance_iterator
-                    .skip(offset)
-                    .take(count)
-                    .map(|event_instance| {
-                        RedisValue::Array(
-                            event_instance
-                                .to_rendered_content_lines()
-                                .iter()
-                                .map(|ical_part| RedisValue::SimpleString(ical_part.to_owned()))
-                                .collect(),
-                        )
-                    })
-                    .collect();
-
-            Ok(RedisValue::Array(event_instances))
+        Ok(mut event_instance_iterator) => {
+            let event_instances = event_instance_iterator.by_ref().skip(offset).take(count);
+
+            let reply = event_instances_to_redis_value(event_instances, reply_format);
+
+            if event_instance_iterator.is_execution_deadline_reached() {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.evi_list: occurrence expansion exceeded timeout -- calendar_uid: {calendar_uid} event_uid: {event_uid}",
+                    ).as_str()
+                );
+
+                return Err(RedisError::String(String::from("rdcl.evi_list: occurrence expansion exceeded timeout")));
+            }
+
+            if occurrence_expansion_limits.is_safety_horizon_reached() {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.evi_list: occurrence expansion safety horizon reached -- calendar_uid: {calendar_uid} event_uid: {event_uid}",
+                    ).as_str()
+                );
+            }
+
+            Ok(reply)
         }
 
         Err(error) => Err(RedisError::String(error)),
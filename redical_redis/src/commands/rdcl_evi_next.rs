@@ -0,0 +1,135 @@
+use std::str::FromStr;
+
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::datatype::CALENDAR_DATA_TYPE;
+use crate::reply::{ReplyFormat, event_instances_to_redis_value};
+
+use redical_ical::values::date_time::DateTime;
+use crate::{
+    CONFIGURATION_OCCURRENCE_EXPANSION_MAX, CONFIGURATION_OCCURRENCE_EXPANSION_SAFETY_HORIZON_YEARS,
+    CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS,
+};
+use redical_core::{
+    build_seeked_rrule_set, Calendar, EventInstanceIterator, ExecutionDeadline, FilterProperty, LowerBoundFilterCondition,
+    OccurrenceExpansionLimits,
+};
+
+pub fn redical_event_instance_next(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if !(4..=5).contains(&args.len()) {
+        ctx.log_debug(format!("rdcl.evi_next: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+    let event_uid = args.next_arg()?;
+
+    let count = args.next_u64()? as usize;
+
+    // Default to the next event instances from now if no anchor date-time string is provided.
+    let anchor_timestamp =
+        match args.next() {
+            Some(anchor_date_string) => {
+                DateTime::from_str(anchor_date_string.try_as_str()?)
+                    .map(|datetime| datetime.get_utc_timestamp(None))
+                    .map_err(RedisError::String)?
+            },
+
+            None => chrono::offset::Utc::now().timestamp(),
+        };
+
+    // Zero represents no limit on the number of occurrences expanded (from the anchor) per event.
+    let occurrence_expansion_max = Some(*CONFIGURATION_OCCURRENCE_EXPANSION_MAX.lock(ctx) as usize).filter(|occurrence_expansion_max| *occurrence_expansion_max > 0);
+
+    // Zero represents no safety horizon on the expansion of event occurrences.
+    let occurrence_expansion_safety_horizon_years =
+        Some(*CONFIGURATION_OCCURRENCE_EXPANSION_SAFETY_HORIZON_YEARS.lock(ctx))
+            .filter(|occurrence_expansion_safety_horizon_years| *occurrence_expansion_safety_horizon_years > 0);
+
+    let occurrence_expansion_limits =
+        OccurrenceExpansionLimits::new(occurrence_expansion_max, None)
+            .with_safety_horizon_years(occurrence_expansion_safety_horizon_years);
+
+    // The expansion runs on the main thread, so is aborted once this deadline has been exceeded to
+    // guard against pathological recurrences (or distant anchors) blocking it.
+    let execution_deadline = ExecutionDeadline::new(std::time::Duration::from_millis(*CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64));
+
+    let calendar_key = ctx.open_key(&calendar_uid);
+
+    ctx.log_debug(
+        format!("rdcl.evi_next: calendar_uid: {calendar_uid} event_uid: {event_uid} count: {count} anchor: {anchor_timestamp}").as_str(),
+    );
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Ok(RedisValue::Null);
+    };
+
+    let Some(event) = calendar.events.get(&String::from(event_uid.clone())) else {
+        return Ok(RedisValue::Null);
+    };
+
+    // Expand the recurrence from (shortly before) the anchor instead of from it's DTSTART where
+    // possible, so that distant anchors do not expand every preceding occurrence.
+    let seeked_rrule_set =
+        event.schedule_properties
+            .parsed_rrule_set
+            .as_ref()
+            .and_then(|parsed_rrule_set| build_seeked_rrule_set(parsed_rrule_set, anchor_timestamp));
+
+    // Limiting the iterator to the requested count stops it iterating as soon as enough upcoming
+    // event instances have been found, instead of extrapolating the entire recurrence set. Any
+    // occurrences preceding the anchor still expanded are not counted towards the expansion max.
+    let event_instance_iterator = EventInstanceIterator::new(
+        event,
+        Some(count),
+        Some(LowerBoundFilterCondition::GreaterEqualThan(FilterProperty::DtStart(anchor_timestamp))),
+        None,
+        None,
+    )
+    .map(|event_instance_iterator| {
+        event_instance_iterator
+            .with_dst_policy(calendar.get_default_dst_policy().unwrap_or_default())
+            .with_occurrence_expansion_limits(occurrence_expansion_limits.clone())
+            .with_execution_deadline(execution_deadline)
+            .with_seek(anchor_timestamp, seeked_rrule_set.as_ref())
+    });
+
+    match event_instance_iterator {
+        Ok(mut event_instance_iterator) => {
+            let reply = event_instances_to_redis_value(event_instance_iterator.by_ref(), ReplyFormat::from_context(ctx));
+
+            if event_instance_iterator.is_execution_deadline_reached() {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.evi_next: occurrence expansion exceeded timeout -- calendar_uid: {calendar_uid} event_uid: {event_uid}",
+                    ).as_str()
+                );
+
+                return Err(RedisError::String(String::from("rdcl.evi_next: occurrence expansion exceeded timeout")));
+            }
+
+            if occurrence_expansion_limits.is_reached() {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.evi_next: event instances partial due to occurrence expansion limits -- calendar_uid: {calendar_uid} event_uid: {event_uid}",
+                    ).as_str()
+                );
+            }
+
+            if occurrence_expansion_limits.is_safety_horizon_reached() {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.evi_next: occurrence expansion safety horizon reached -- calendar_uid: {calendar_uid} event_uid: {event_uid}",
+                    ).as_str()
+                );
+            }
+
+            Ok(reply)
+        }
+
+        Err(error) => Err(RedisError::String(error)),
+    }
+}
@@ -1,37 +1,51 @@
 use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue, ThreadSafeContext};
 
-use std::str::FromStr;
-
 use redical_ical::{ICalendarComponent, RenderingContext};
 use crate::core::queries::query::Query;
 use crate::core::queries::event_instance_query::EventInstanceQuery;
-use crate::utils::{run_with_timeout, TimeoutError};
-use crate::CONFIGURATION_ICAL_PARSER_TIMEOUT_MS;
-use redical_core::Calendar;
-use crate::datatype::CALENDAR_DATA_TYPE;
-
-fn icalendar_component_to_redis_value_array<I: ICalendarComponent>(component: &I, rendering_context: &RenderingContext) -> RedisValue {
-    RedisValue::Array(
-        component
-            .to_rendered_content_lines_with_context(Some(rendering_context))
-            .iter()
-            .map(|ical_part| RedisValue::SimpleString(ical_part.to_owned()))
-            .collect()
-    )
-}
+use crate::core::queries::grouped_results::group_event_instances_by_uid;
+use crate::core::queries::merged_intervals::merge_event_instance_intervals;
+use crate::utils::{join_content_line_args, run_with_timeout, TimeoutError};
+use crate::reply::{
+    PaginationHeader, QueryResultsHeader, ReplyFormat, content_line_set_to_redis_value, event_instance_groups_to_redis_value,
+    extract_trailing_flag_arg, icalendar_component_to_redis_value,
+};
+use crate::{
+    CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, CONFIGURATION_MAX_QUERY_RESULTS, CONFIGURATION_PAGINATION_COUNT_MAX,
+    CONFIGURATION_OCCURRENCE_EXPANSION_HORIZON_DAYS, CONFIGURATION_OCCURRENCE_EXPANSION_MAX,
+    CONFIGURATION_OCCURRENCE_EXPANSION_SAFETY_HORIZON_YEARS,
+    CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS, CONFIGURATION_SLOW_EXPANSION_THRESHOLD_MS,
+    CONFIGURATION_SLOW_EXPANSION_THRESHOLD_OCCURRENCES,
+};
+use redical_core::{Calendar, ExecutionDeadline, OccurrenceExpansionLimits};
+use crate::datatype::{open_indexed_calendar_key, CALENDAR_DATA_TYPE};
 
-pub fn redical_event_instance_query(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+pub fn redical_event_instance_query(ctx: &Context, mut args: Vec<RedisString>) -> RedisResult {
     if args.len() < 2 {
         ctx.log_debug("rdcl.evi_query: event_set WrongArity: {{args.len()}}");
 
         return Err(RedisError::WrongArity);
     }
 
+    // Resolved before blocking the client as the protocol cannot be determined from the thread.
+    let reply_format = ReplyFormat::from_output_args(ctx, &mut args)?;
+
+    // Include the pagination metadata (total count, offset, limit) in the header preceding the results.
+    let with_pagination = extract_trailing_flag_arg(&mut args, "WITHPAGINATION");
+
+    // Precede the results with the header flagging whether they were truncated, partial, or the
+    // query has warnings (implied by WITHPAGINATION, which is included within it).
+    let with_header = extract_trailing_flag_arg(&mut args, "WITHHEADER") || with_pagination;
+
+    // Reject queries with degenerate conditions (e.g. `X-UID:A X-UID:B`) instead of replying with
+    // their results (and warning about them).
+    let strict = extract_trailing_flag_arg(&mut args, "STRICT");
+
     let mut args = args.into_iter().skip(1);
 
     let calendar_uid = args.next_arg()?;
 
-    let calendar_key = ctx.open_key(&calendar_uid);
+    let calendar_key = open_indexed_calendar_key(ctx, &calendar_uid)?;
 
     let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)?.cloned() else {
         return Err(RedisError::String(format!(
@@ -39,6 +53,8 @@ pub fn redical_event_instance_query(ctx: &Context, args: Vec<RedisString>) -> Re
         )));
     };
 
+    let default_tz = calendar.get_default_tz();
+
     if !calendar.indexes_active {
         return Err(RedisError::String(format!(
             "rdcl.evi_query: Queries disabled on Calendar: {calendar_uid} because it's indexes have been disabled."
@@ -47,17 +63,50 @@ pub fn redical_event_instance_query(ctx: &Context, args: Vec<RedisString>) -> Re
 
     ctx.log_debug(format!("rdcl.evi_query: calendar_uid: {calendar_uid}").as_str());
 
-    let query_string: String = args
-        .map(|arg| arg.try_as_str().unwrap_or(""))
-        .collect::<Vec<&str>>()
-        .join(" ")
-        .as_str()
-        .to_owned();
+    let query_string: String = join_content_line_args(args);
 
     let ical_parser_timeout_ms = *CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64;
+    let query_execution_timeout_ms = *CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64;
+
+    // Zero represents no max query results.
+    let max_query_results = Some(*CONFIGURATION_MAX_QUERY_RESULTS.lock(ctx) as usize).filter(|max_query_results| *max_query_results > 0);
+
+    // Zero represents no max pagination total count.
+    let pagination_count_max = Some(*CONFIGURATION_PAGINATION_COUNT_MAX.lock(ctx) as usize).filter(|pagination_count_max| *pagination_count_max > 0);
+
+    // Zero represents no limit on the expansion of event occurrences.
+    let occurrence_expansion_max = Some(*CONFIGURATION_OCCURRENCE_EXPANSION_MAX.lock(ctx) as usize).filter(|occurrence_expansion_max| *occurrence_expansion_max > 0);
+
+    let occurrence_expansion_horizon_timestamp =
+        Some(*CONFIGURATION_OCCURRENCE_EXPANSION_HORIZON_DAYS.lock(ctx))
+            .filter(|occurrence_expansion_horizon_days| *occurrence_expansion_horizon_days > 0)
+            .map(|occurrence_expansion_horizon_days| {
+                chrono::Utc::now().timestamp().saturating_add(occurrence_expansion_horizon_days.saturating_mul(86400))
+            });
+
+    // Zero represents no safety horizon on the expansion of event occurrences.
+    let occurrence_expansion_safety_horizon_years =
+        Some(*CONFIGURATION_OCCURRENCE_EXPANSION_SAFETY_HORIZON_YEARS.lock(ctx))
+            .filter(|occurrence_expansion_safety_horizon_years| *occurrence_expansion_safety_horizon_years > 0);
+
+    // Zero represents no slow expansion logging.
+    let slow_expansion_threshold_duration =
+        Some(*CONFIGURATION_SLOW_EXPANSION_THRESHOLD_MS.lock(ctx))
+            .filter(|slow_expansion_threshold_ms| *slow_expansion_threshold_ms > 0)
+            .map(|slow_expansion_threshold_ms| std::time::Duration::from_millis(slow_expansion_threshold_ms as u64));
+
+    let slow_expansion_threshold_count = Some(*CONFIGURATION_SLOW_EXPANSION_THRESHOLD_OCCURRENCES.lock(ctx) as usize).filter(|slow_expansion_threshold_count| *slow_expansion_threshold_count > 0);
+
+    let occurrence_expansion_limits =
+        OccurrenceExpansionLimits::new(occurrence_expansion_max, occurrence_expansion_horizon_timestamp)
+            .with_safety_horizon_years(occurrence_expansion_safety_horizon_years)
+            .with_slow_expansion_thresholds(slow_expansion_threshold_duration, slow_expansion_threshold_count);
 
     let calendar_uid = calendar_uid.to_string();
 
+    // Retained to identify the query responsible for any slow occurrence expansions.
+    let logged_query_string = query_string.clone();
+
     let blocked_client = ctx.block_client();
 
     std::thread::spawn(move || {
@@ -67,7 +116,7 @@ pub fn redical_event_instance_query(ctx: &Context, args: Vec<RedisString>) -> Re
         // against malicious payloads intended to cause hangs.
         let mut parsed_query =
             match run_with_timeout(
-                move || EventInstanceQuery::from_str(query_string.as_str()).map_err(RedisError::String),
+                move || EventInstanceQuery::from_str_with_default_timezone(query_string.as_str(), default_tz.as_ref()).map_err(RedisError::String),
                 std::time::Duration::from_millis(ical_parser_timeout_ms),
             ) {
                 Ok(parser_result) => {
@@ -101,9 +150,87 @@ pub fn redical_event_instance_query(ctx: &Context, args: Vec<RedisString>) -> Re
             ).as_str(),
         );
 
-        let query_results = match parsed_query.execute(&calendar) {
+        if strict && !parsed_query.get_warnings().is_empty() {
+            thread_ctx.reply(Err(RedisError::String(format!(
+                "rdcl.evi_query: degenerate query: {}",
+                parsed_query.get_warnings().join("; "),
+            ))));
+
+            return;
+        }
+
+        // The offset and limit applied (with the limit capped to the max query results).
+        let applied_offset = parsed_query.get_offset();
+        let applied_limit = max_query_results.map_or(parsed_query.get_limit(), |max_query_results| parsed_query.get_limit().min(max_query_results));
+
+        if let Some(max_query_results) = max_query_results {
+            parsed_query.cap_limit(max_query_results);
+        }
+
+        // The query execution is cooperatively aborted once this deadline has been exceeded to
+        // guard against expensive queries hogging the thread indefinitely.
+        let execution_deadline = ExecutionDeadline::new(std::time::Duration::from_millis(query_execution_timeout_ms));
+
+        // Only the number of matching event instances is returned (bounded by the max pagination
+        // total count) without collecting, ordering, or rendering them.
+        if parsed_query.count_only {
+            let count_reply =
+                match parsed_query.count_with_occurrence_expansion_limits(&calendar, pagination_count_max, &execution_deadline, &occurrence_expansion_limits) {
+                    Ok(count) => {
+                        let count = pagination_count_max.map_or(count, |pagination_count_max| count.min(pagination_count_max));
+
+                        Ok(RedisValue::Integer(count as i64))
+                    },
+
+                    Err(_) if execution_deadline.is_exceeded() => {
+                        thread_ctx.lock().log_warning(
+                            format!(
+                                "rdcl.evi_query: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                            ).as_str()
+                        );
+
+                        Err(RedisError::String(String::from("rdcl.evi_query: query exceeded timeout")))
+                    },
+
+                    Err(error) => Err(RedisError::String(error)),
+                };
+
+            thread_ctx.reply(count_reply);
+
+            return;
+        }
+
+        let query_results = parsed_query.execute_with_occurrence_expansion_limits(&calendar, &execution_deadline, &occurrence_expansion_limits);
+
+        // Log each event whose occurrence expansion exceeded the slow expansion thresholds (even
+        // if the query timed out), so that the recurrences responsible for slow queries can be
+        // identified.
+        for slow_expansion in occurrence_expansion_limits.get_slow_expansions() {
+            thread_ctx.lock().log_warning(
+                format!(
+                    "rdcl.evi_query: slow occurrence expansion -- calendar_uid: {calendar_uid} event_uid: {} expanded: {} occurrences in {}ms query: {logged_query_string}",
+                    slow_expansion.event_uid,
+                    slow_expansion.expanded_count,
+                    slow_expansion.duration.as_millis(),
+                ).as_str()
+            );
+        }
+
+        let mut query_results = match query_results {
             Ok(results) => results,
 
+            Err(_) if execution_deadline.is_exceeded() => {
+                thread_ctx.lock().log_warning(
+                    format!(
+                        "rdcl.evi_query: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                    ).as_str()
+                );
+
+                thread_ctx.reply(Err(RedisError::String(String::from("rdcl.evi_query: query exceeded timeout"))));
+
+                return;
+            },
+
             Err(error) => {
                 thread_ctx.reply(Err(RedisError::String(error)));
 
@@ -111,22 +238,136 @@ pub fn redical_event_instance_query(ctx: &Context, args: Vec<RedisString>) -> Re
             },
         };
 
+        if let Some(max_query_results) = max_query_results {
+            query_results.truncate_to_max_results(max_query_results);
+        }
+
+        // The total number of matching results is counted by executing the query again without the
+        // offset and limit (bounded by the max pagination total count).
+        let pagination_header =
+            if with_pagination {
+                let mut count_query = parsed_query.build_count_query(pagination_count_max);
+
+                match count_query.execute_with_occurrence_expansion_limits(
+                    &calendar,
+                    &execution_deadline,
+                    &OccurrenceExpansionLimits::new(occurrence_expansion_max, occurrence_expansion_horizon_timestamp)
+                        .with_safety_horizon_years(occurrence_expansion_safety_horizon_years),
+                ) {
+                    Ok(count_query_results) => {
+                        Some(
+                            PaginationHeader::new(
+                                count_query_results.len(),
+                                pagination_count_max,
+                                applied_offset,
+                                applied_limit,
+                                calendar.generation,
+                            )
+                        )
+                    },
+
+                    Err(_) if execution_deadline.is_exceeded() => {
+                        thread_ctx.lock().log_warning(
+                            format!(
+                                "rdcl.evi_query: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                            ).as_str()
+                        );
+
+                        thread_ctx.reply(Err(RedisError::String(String::from("rdcl.evi_query: query exceeded timeout"))));
+
+                        return;
+                    },
+
+                    Err(error) => {
+                        thread_ctx.reply(Err(RedisError::String(error)));
+
+                        return;
+                    },
+                }
+            } else {
+                None
+            };
+
+        let rendering_context = RenderingContext {
+            tz: Some(parsed_query.in_timezone.to_owned()),
+            distance_unit: None,
+        };
+
         // TODO: Clean up and properly serialize this griminess
-        let query_result_items = query_results
-            .results
-            .iter()
-            .map(|query_result| {
-                let rendering_context = RenderingContext {
-                    tz: Some(parsed_query.in_timezone.to_owned()),
-                    distance_unit: None,
-                };
+        let mut query_result_items: Vec<RedisValue> =
+            if parsed_query.merge_intervals {
+                // The event instances are collapsed into the busy intervals spanning them (each
+                // with just a DTSTART and DTEND).
+                merge_event_instance_intervals(&query_results)
+                    .iter()
+                    .map(|merged_interval| {
+                        content_line_set_to_redis_value(
+                            merged_interval.to_content_line_set_with_context(Some(&rendering_context)),
+                            reply_format,
+                        )
+                    })
+                    .collect()
+            } else if parsed_query.group_by_uid {
+                event_instance_groups_to_redis_value(
+                    group_event_instances_by_uid(&calendar, &query_results, Some(&rendering_context))
+                        .into_iter()
+                        .map(|event_instance_group| event_instance_group.project_content_lines(&parsed_query))
+                        .collect(),
+                    Some(&rendering_context),
+                    reply_format,
+                )
+            } else {
+                query_results
+                    .results
+                    .iter()
+                    .map(|query_result| {
+                        RedisValue::Array(vec![
+                            icalendar_component_to_redis_value(&query_result.result_ordering, Some(&rendering_context), reply_format),
+                            content_line_set_to_redis_value(
+                                parsed_query.project_content_lines(query_result.result.to_content_line_set_with_context(Some(&rendering_context))),
+                                reply_format,
+                            ),
+                        ])
+                    })
+                    .collect()
+            };
+
+        // The results are potentially partial because the expansion of some event occurrences was
+        // cut short by the occurrence expansion limits.
+        if occurrence_expansion_limits.is_reached() {
+            // The safety horizon is only reached by pathological recurrences (or queries), so is
+            // distinctly logged to identify them.
+            if occurrence_expansion_limits.is_safety_horizon_reached() {
+                thread_ctx.lock().log_warning(
+                    format!(
+                        "rdcl.evi_query: occurrence expansion safety horizon reached -- calendar_uid: {calendar_uid} query: {logged_query_string}",
+                    ).as_str()
+                );
+            }
+
+            thread_ctx.lock().log_warning(
+                format!(
+                    "rdcl.evi_query: query results partial due to occurrence expansion limits -- calendar_uid: {calendar_uid}",
+                ).as_str()
+            );
+        }
+
+        // Flag that the results were cut short by the max query results config, are potentially
+        // partial due to the occurrence expansion limits, or warn about any degenerate conditions
+        // in the query (explaining why it matched nothing) within the header preceding them if
+        // requested, otherwise log them.
+        let query_results_header = QueryResultsHeader {
+            pagination: pagination_header,
+            is_truncated: query_results.is_truncated,
+            is_partial: occurrence_expansion_limits.is_reached(),
+            warnings: parsed_query.get_warnings().to_owned(),
+        };
 
-                RedisValue::Array(vec![
-                    icalendar_component_to_redis_value_array(&query_result.result_ordering, &rendering_context),
-                    icalendar_component_to_redis_value_array(&query_result.result, &rendering_context),
-                ])
-            })
-            .collect();
+        if with_header {
+            query_results_header.prepend_to(&mut query_result_items);
+        } else {
+            query_results_header.log(&thread_ctx.lock(), "rdcl.evi_query", &calendar_uid);
+        }
 
         thread_ctx.reply(
             Ok(RedisValue::Array(query_result_items))
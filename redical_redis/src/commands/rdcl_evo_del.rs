@@ -3,6 +3,8 @@ use std::str::FromStr;
 use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, Status, RedisValue};
 
 use redical_core::{Calendar, CalendarIndexUpdater, InvertedEventIndex};
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
 use crate::datatype::CALENDAR_DATA_TYPE;
 
 use redical_ical::values::date_time::DateTime;
@@ -95,6 +97,22 @@ pub fn redical_event_override_del(ctx: &Context, args: Vec<RedisString>) -> Redi
             event.indexed_class.as_ref(),
         );
 
+        let updated_event_location_diff = InvertedEventIndex::diff_indexed_terms(
+            existing_event
+                .as_ref()
+                .and_then(|existing_event| existing_event.indexed_location.clone())
+                .as_ref(),
+            event.indexed_location.as_ref(),
+        );
+
+        let updated_event_x_properties_diff = InvertedEventIndex::diff_indexed_terms(
+            existing_event
+                .as_ref()
+                .and_then(|existing_event| existing_event.indexed_x_properties.clone())
+                .as_ref(),
+            event.indexed_x_properties.as_ref(),
+        );
+
         let mut calendar_index_updater = CalendarIndexUpdater::new(&event_uid, calendar);
 
         calendar_index_updater
@@ -116,6 +134,18 @@ pub fn redical_event_override_del(ctx: &Context, args: Vec<RedisString>) -> Redi
         calendar_index_updater
             .update_indexed_class(&updated_event_class_diff)
             .map_err(|error| RedisError::String(error.to_string()))?;
+
+        calendar_index_updater
+            .update_indexed_location(&updated_event_location_diff)
+            .map_err(|error| RedisError::String(error.to_string()))?;
+
+        calendar_index_updater
+            .update_indexed_x_properties(&updated_event_x_properties_diff)
+            .map_err(|error| RedisError::String(error.to_string()))?;
+
+        calendar_index_updater
+            .update_indexed_time_buckets(Some(&event))
+            .map_err(|error| RedisError::String(error.to_string()))?;
     }
 
     // Use this command when replicating across other Redis instances.
@@ -123,6 +153,16 @@ pub fn redical_event_override_del(ctx: &Context, args: Vec<RedisString>) -> Redi
 
     if was_override_removed {
         notify_keyspace_event(ctx, &calendar_uid, &event_uid, override_date_string)?;
+
+        append_changes(
+            ctx,
+            &calendar_uid,
+            &[Change::new(ChangeType::OverrideDel, &event_uid).with_override_date_string(override_date_string)],
+        );
+
+        calendar.bump_generation();
+
+        notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
     }
 
     Ok(RedisValue::Bool(was_override_removed))
@@ -2,21 +2,12 @@ use std::str::FromStr;
 
 use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
 
-use crate::core::{Calendar, EventOccurrenceOverride};
+use crate::core::Calendar;
 use crate::datatype::CALENDAR_DATA_TYPE;
 
-use redical_ical::ICalendarComponent;
-use redical_ical::values::date_time::DateTime;
+use crate::reply::{ReplyFormat, icalendar_component_to_redis_value};
 
-fn serialize_event_occurrence_override(event_occurrence_override: &EventOccurrenceOverride) -> RedisValue {
-    RedisValue::Array(
-        event_occurrence_override
-            .to_rendered_content_lines()
-            .into_iter()
-            .map(RedisValue::SimpleString)
-            .collect()
-    )
-}
+use redical_ical::values::date_time::DateTime;
 
 pub fn redical_event_override_get(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     if args.len() < 3 {
@@ -60,7 +51,7 @@ pub fn redical_event_override_get(ctx: &Context, args: Vec<RedisString>) -> Redi
         .map_or(
             Ok(RedisValue::Null),
             |event_occurrence_override| {
-                Ok(serialize_event_occurrence_override(event_occurrence_override))
+                Ok(icalendar_component_to_redis_value(event_occurrence_override, None, ReplyFormat::from_context(ctx)))
             },
         )
 }
@@ -0,0 +1,251 @@
+use std::str::FromStr;
+
+use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, RedisValue, Status};
+
+use crate::core::{Calendar, EventOccurrenceOverride};
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
+use crate::datatype::CALENDAR_DATA_TYPE;
+
+use crate::utils::{run_with_timeout, TimeoutError};
+use crate::CONFIGURATION_ICAL_PARSER_TIMEOUT_MS;
+
+use super::rdcl_evt_set::update_calendar_indexes;
+
+use redical_ical::ICalendarComponent;
+use redical_ical::content_line::join_line_separated_content_lines;
+use redical_ical::values::date_time::DateTime;
+
+fn serialize_event_occurrence_override(event_occurrence_override: &EventOccurrenceOverride) -> RedisValue {
+    RedisValue::Array(
+        event_occurrence_override
+            .to_rendered_content_lines()
+            .into_iter()
+            .map(RedisValue::SimpleString)
+            .collect()
+    )
+}
+
+pub fn redical_event_override_mset(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() < 5 {
+        ctx.log_debug(format!("rdcl.evo_mset: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1).peekable();
+
+    let calendar_uid = args.next_arg()?;
+    let event_uid = args.next_arg()?.to_string();
+
+    // Opt out of validating that the override date strings are actual occurrences of the event.
+    let is_detached =
+        args.next_if(|arg| {
+            arg.try_as_str()
+                .is_ok_and(|arg| arg.eq_ignore_ascii_case("DETACHED"))
+        })
+        .is_some();
+
+    let remaining_args: Vec<RedisString> = args.collect();
+
+    // Expect at least one `recurrence-id ical` pair.
+    if remaining_args.is_empty() || !remaining_args.len().is_multiple_of(2) {
+        ctx.log_debug(format!("rdcl.evo_mset: WrongArity: {}", remaining_args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut override_date_string_ical_pairs: Vec<(String, String)> = Vec::new();
+
+    for pair in remaining_args.chunks(2) {
+        override_date_string_ical_pairs.push((
+            pair[0].try_as_str()?.to_owned(),
+            join_line_separated_content_lines(pair[1].try_as_str()?),
+        ));
+    }
+
+    ctx.log_debug(
+        format!(
+            "rdcl.evo_mset: calendar_uid: {calendar_uid} event_uid: {event_uid} detached: {is_detached} override count: {}",
+            override_date_string_ical_pairs.len(),
+        ).as_str()
+    );
+
+    let calendar_key = ctx.open_key_writable(&calendar_uid);
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    let Some(mut event) = calendar.get_event(&event_uid).cloned() else {
+        return Err(RedisError::String(format!(
+            "No event with UID: '{event_uid}' found",
+        )));
+    };
+
+    // Parse and validate every event occurrence override before touching the Calendar so that
+    // either all of the provided overrides are applied, or none of them are.
+    let mut event_occurrence_overrides: Vec<(String, EventOccurrenceOverride)> =
+        Vec::with_capacity(override_date_string_ical_pairs.len());
+
+    for (override_date_string, ical) in override_date_string_ical_pairs {
+        let override_timestamp =
+            DateTime::from_str(override_date_string.as_str())
+                .map(|datetime| datetime.get_utc_timestamp(None))
+                .map_err(|error| {
+                    RedisError::String(format!("Event occurrence override at: '{override_date_string}' invalid: {error}"))
+                })?;
+
+        // Reject overrides for dates which are not occurrences of the event (e.g. typos in the
+        // recurrence ID) unless explicitly flagged as DETACHED.
+        if !is_detached && !event.is_occurrence(override_timestamp).map_err(RedisError::String)? {
+            return Err(RedisError::String(format!(
+                "Event with UID: '{event_uid}' has no occurrence at: '{override_date_string}' (use DETACHED to set detached overrides)",
+            )));
+        }
+
+        // Clone the override_date_string for it to moved into the timeout enforced
+        // EventOccurrenceOverride iCalendar parser thread below.
+        let parsed_override_date_string = override_date_string.clone();
+
+        // Spawn the process of parsing the query into it's own timeout enforced thread to guard
+        // against malicious payloads intended to cause hangs.
+        let mut event_occurrence_override =
+            match run_with_timeout(
+                move || EventOccurrenceOverride::parse_ical(parsed_override_date_string.as_str(), ical.as_str()).map_err(RedisError::String),
+                std::time::Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64),
+            ) {
+                Ok(parser_result) => {
+                    parser_result.map_err(|error| {
+                        RedisError::String(format!("Event occurrence override at: '{override_date_string}' invalid: {error}"))
+                    })?
+                },
+
+                Err(TimeoutError) => {
+                    ctx.log_warning(
+                        format!(
+                            "rdcl.evo_mset: event occurrence override iCal parser exceeded timeout -- calendar_uid: {calendar_uid} event_uid: {event_uid} occurrence date string: {override_date_string}",
+                        ).as_str()
+                    );
+
+                    return Err(
+                        RedisError::String(
+                            String::from("rdcl.evo_mset: event occurrence override iCal parser exceeded timeout")
+                        )
+                    );
+                },
+            };
+
+        calendar.localize_event_occurrence_override(&mut event_occurrence_override).map_err(|error| {
+            RedisError::String(format!("Event occurrence override at: '{override_date_string}' invalid: {error}"))
+        })?;
+
+        event_occurrence_overrides.push((override_date_string, event_occurrence_override));
+    }
+
+    let mut results: Vec<RedisValue> = Vec::with_capacity(event_occurrence_overrides.len());
+    let mut updated_override_date_strings: Vec<String> = Vec::new();
+    let mut changes: Vec<Change> = Vec::new();
+
+    // The arguments to replicate, only including the overrides actually updated (as stored).
+    let mut replicated_args: Vec<String> = vec![calendar_uid.to_string(), event_uid.to_owned()];
+
+    if is_detached {
+        replicated_args.push(String::from("DETACHED"));
+    }
+
+    for (override_date_string, event_occurrence_override) in event_occurrence_overrides {
+        let Some(override_timestamp) = event_occurrence_override.get_dtstart_timestamp() else {
+            return Err(RedisError::String(String::from("Expected event occurrence override to have dtstart defined.")));
+        };
+
+        // Validate new event occurrence override's LAST-MODIFIED property (if provided) is more
+        // recent than that on the existing event occurrence override (if present), skipping it
+        // (without failing the whole batch) if not.
+        if let Some(existing_event_occurrence_override) = event.overrides.get(&override_timestamp) {
+            if event_occurrence_override.last_modified < existing_event_occurrence_override.last_modified {
+                ctx.log_debug(
+                    format!(
+                        "rdcl.evo_mset: key: {calendar_uid} event uid: {event_uid} - DTSTART: {override_date_string} - skipped due to existing superseding LAST-MODIFIED - existing: {} new: {}",
+                        existing_event_occurrence_override.last_modified,
+                        event_occurrence_override.last_modified,
+                    ).as_str()
+                );
+
+                results.push(RedisValue::Bool(false));
+
+                continue;
+            }
+        }
+
+        event.override_occurrence(&event_occurrence_override, calendar.indexes_active.to_owned()).map_err(RedisError::String)?;
+
+        results.push(serialize_event_occurrence_override(&event_occurrence_override));
+
+        replicated_args.push(override_date_string.to_owned());
+        replicated_args.push(event_occurrence_override.to_rendered_content_lines().join(" "));
+
+        changes.push(
+            Change::new(ChangeType::OverrideSet, &event_uid)
+                .with_override_date_string(&override_date_string)
+                .with_last_modified(&event_occurrence_override.last_modified)
+        );
+
+        updated_override_date_strings.push(override_date_string);
+    }
+
+    if updated_override_date_strings.is_empty() {
+        return Ok(RedisValue::Array(results));
+    }
+
+    // Insert the event with all of the new overrides applied at once, diffing the Calendar
+    // indexes a single time for the whole batch.
+    let existing_event = calendar.insert_event(event.clone());
+
+    if calendar.indexes_active {
+        update_calendar_indexes(calendar, &event_uid, existing_event.as_ref(), &event)?;
+    }
+
+    ctx.log_debug(
+        format!(
+            "rdcl.evo_mset: key: {calendar_uid} event uid: {event_uid} - updated: {} skipped: {} - count: {}",
+            updated_override_date_strings.len(),
+            results.len() - updated_override_date_strings.len(),
+            event.overrides.len(),
+        ).as_str()
+    );
+
+    // Replicate the updated overrides as stored (rather than verbatim) so that any values resolved
+    // by this instance (e.g. defaulting LAST-MODIFIED to now) are identical across replicas and
+    // the AOF.
+    ctx.replicate(
+        "rdcl.evo_mset",
+        &replicated_args.iter().map(String::as_str).collect::<Vec<&str>>()[..],
+    );
+
+    notify_keyspace_event(ctx, &calendar_uid, &event_uid, &updated_override_date_strings)?;
+
+    append_changes(ctx, &calendar_uid, &changes);
+
+    calendar.bump_generation();
+
+    notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
+
+    Ok(RedisValue::Array(results))
+}
+
+fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String, override_date_strings: &[String]) -> Result<(), RedisError> {
+    let event_message = format!("rdcl.evo_mset:{event_uid}:{}", override_date_strings.join(","));
+
+    if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), calendar_uid) == Status::Err {
+        return Err(
+            RedisError::String(
+                format!("Notify keyspace event \"rdcl.evo_mset\" for calendar: \"{}\" event: \"{}\" date strings: \"{}\"", &calendar_uid, &event_uid, override_date_strings.join(","))
+            )
+        );
+    }
+
+    Ok(())
+}
@@ -3,11 +3,13 @@ use std::str::FromStr;
 use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, Status, RedisValue};
 
 use redical_core::{Calendar, EventOccurrenceOverride, CalendarIndexUpdater, InvertedEventIndex};
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
 use crate::datatype::CALENDAR_DATA_TYPE;
 
 use redical_ical::values::date_time::DateTime;
 
-fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String, override_date_string: &str) -> Result<(), RedisError> {
+pub fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String, override_date_string: &str) -> Result<(), RedisError> {
     let event_message = format!("rdcl.evo_prune:{event_uid}:{override_date_string}");
 
     if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), calendar_uid) == Status::Err {
@@ -21,7 +23,7 @@ fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &
     Ok(())
 }
 
-fn prune_calendar_events_overrides(calendar: &mut Calendar, event_uid: String, from_timestamp: i64, until_timestamp: i64) -> Result<Vec<(i64, EventOccurrenceOverride)>, RedisError> {
+pub fn prune_calendar_events_overrides(calendar: &mut Calendar, event_uid: String, from_timestamp: i64, until_timestamp: i64) -> Result<Vec<(i64, EventOccurrenceOverride)>, RedisError> {
     let Some(mut event) = calendar.events.get(&event_uid).cloned() else {
         return Err(RedisError::String(format!(
             "No event with UID: '{event_uid}' found",
@@ -78,6 +80,22 @@ fn prune_calendar_events_overrides(calendar: &mut Calendar, event_uid: String, f
             event.indexed_class.as_ref(),
         );
 
+        let updated_event_location_diff = InvertedEventIndex::diff_indexed_terms(
+            existing_event
+                .as_ref()
+                .and_then(|existing_event| existing_event.indexed_location.clone())
+                .as_ref(),
+            event.indexed_location.as_ref(),
+        );
+
+        let updated_event_x_properties_diff = InvertedEventIndex::diff_indexed_terms(
+            existing_event
+                .as_ref()
+                .and_then(|existing_event| existing_event.indexed_x_properties.clone())
+                .as_ref(),
+            event.indexed_x_properties.as_ref(),
+        );
+
         let mut calendar_index_updater = CalendarIndexUpdater::new(&event_uid, calendar);
 
         calendar_index_updater
@@ -99,6 +117,18 @@ fn prune_calendar_events_overrides(calendar: &mut Calendar, event_uid: String, f
         calendar_index_updater
             .update_indexed_class(&updated_event_class_diff)
             .map_err(|error| RedisError::String(error.to_string()))?;
+
+        calendar_index_updater
+            .update_indexed_location(&updated_event_location_diff)
+            .map_err(|error| RedisError::String(error.to_string()))?;
+
+        calendar_index_updater
+            .update_indexed_x_properties(&updated_event_x_properties_diff)
+            .map_err(|error| RedisError::String(error.to_string()))?;
+
+        calendar_index_updater
+            .update_indexed_time_buckets(Some(event.as_ref()))
+            .map_err(|error| RedisError::String(error.to_string()))?;
     }
 
     Ok(removed_overrides)
@@ -168,12 +198,15 @@ pub fn redical_event_override_prune(ctx: &Context, args: Vec<RedisString>) -> Re
         let prune_count = pruned_overrides.len();
 
         for (override_timestamp, _event_occurrence_override) in pruned_overrides.into_iter() {
-            notify_keyspace_event(
+            let override_date_string = DateTime::from(override_timestamp).render_formatted_date_time(None);
+
+            notify_keyspace_event(ctx, &calendar_uid, &event_uid, &override_date_string)?;
+
+            append_changes(
                 ctx,
                 &calendar_uid,
-                &event_uid,
-                &DateTime::from(override_timestamp).render_formatted_date_time(None)
-            )?;
+                &[Change::new(ChangeType::OverrideDel, &event_uid).with_override_date_string(&override_date_string)],
+            );
         }
 
         prune_count
@@ -209,17 +242,26 @@ pub fn redical_event_override_prune(ctx: &Context, args: Vec<RedisString>) -> Re
             prune_count += pruned_overrides.len();
 
             for (override_timestamp, _event_occurrence_override) in pruned_overrides.into_iter() {
-                notify_keyspace_event(
+                let override_date_string = DateTime::from(override_timestamp).render_formatted_date_time(None);
+
+                notify_keyspace_event(ctx, &calendar_uid, &event_uid, &override_date_string)?;
+
+                append_changes(
                     ctx,
                     &calendar_uid,
-                    &event_uid,
-                    &DateTime::from(override_timestamp).render_formatted_date_time(None)
-                )?;
+                    &[Change::new(ChangeType::OverrideDel, &event_uid).with_override_date_string(&override_date_string)],
+                );
             }
         }
 
         prune_count
     };
 
+    if prune_count > 0 {
+        calendar.bump_generation();
+
+        notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
+    }
+
     Ok(RedisValue::Integer(prune_count as i64))
 }
@@ -2,11 +2,15 @@ use std::str::FromStr;
 
 use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, Status, RedisValue};
 
-use crate::core::{Calendar, CalendarIndexUpdater, EventOccurrenceOverride, InvertedEventIndex};
+use crate::core::{clock, Calendar, CalendarIndexUpdater, EventOccurrenceOverride, InvertedEventIndex, get_changed_property_names};
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
 use crate::datatype::CALENDAR_DATA_TYPE;
 
-use crate::utils::{run_with_timeout, TimeoutError};
-use crate::CONFIGURATION_ICAL_PARSER_TIMEOUT_MS;
+use crate::utils::{
+    join_content_line_args, parse_optional_now_args, run_with_timeout, ExistenceCondition, IfMatchCondition, LastModifiedConflictAction, LastModifiedConflictStrategy, SetReplyMode, TimeoutError,
+};
+use crate::{CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, CONFIGURATION_NOTIFICATION_VERBOSITY, NotificationVerbosity};
 
 use redical_ical::ICalendarComponent;
 use redical_ical::values::date_time::DateTime;
@@ -28,7 +32,7 @@ pub fn redical_event_override_set(ctx: &Context, args: Vec<RedisString>) -> Redi
         return Err(RedisError::WrongArity);
     }
 
-    let mut args = args.into_iter().skip(1);
+    let mut args = args.into_iter().skip(1).peekable();
 
     let calendar_uid = args.next_arg()?;
     let event_uid = args.next_arg()?.to_string();
@@ -39,15 +43,24 @@ pub fn redical_event_override_set(ctx: &Context, args: Vec<RedisString>) -> Redi
             .map(|datetime| datetime.get_utc_timestamp(None))
             .map_err(RedisError::String)?;
 
-    let other: String = args
-        .map(|arg| arg.try_as_str().unwrap_or(""))
-        .collect::<Vec<&str>>()
-        .join(" ")
-        .as_str()
-        .to_owned();
+    // Opt out of validating that the override date string is an actual occurrence of the event.
+    let is_detached =
+        args.next_if(|arg| {
+            arg.try_as_str()
+                .is_ok_and(|arg| arg.eq_ignore_ascii_case("DETACHED"))
+        })
+        .is_some();
+
+    let existence_condition = ExistenceCondition::parse_optional_args(&mut args);
+    let if_match_condition = IfMatchCondition::parse_optional_args(&mut args)?;
+    let now = parse_optional_now_args(&mut args)?;
+    let conflict_strategy = LastModifiedConflictStrategy::parse_optional_args(&mut args)?;
+    let reply_mode = SetReplyMode::parse_optional_args(&mut args);
+
+    let other: String = join_content_line_args(args);
 
     ctx.log_debug(
-        format!("rdcl.evo_set: calendar_uid: {calendar_uid} event_uid: {event_uid} occurrence date string: {override_date_string} ical: {other}").as_str()
+        format!("rdcl.evo_set: calendar_uid: {calendar_uid} event_uid: {event_uid} occurrence date string: {override_date_string} detached: {is_detached} existence condition: {existence_condition:?} if match: {if_match_condition:?} now: {now:?} conflict strategy: {conflict_strategy:?} reply mode: {reply_mode:?} ical: {other}").as_str()
     );
 
     let calendar_key = ctx.open_key_writable(&calendar_uid);
@@ -64,11 +77,19 @@ pub fn redical_event_override_set(ctx: &Context, args: Vec<RedisString>) -> Redi
         )));
     };
 
+    // Reject overrides for dates which are not occurrences of the event (e.g. typos in the
+    // recurrence ID) unless explicitly flagged as DETACHED.
+    if !is_detached && !event.is_occurrence(override_timestamp).map_err(RedisError::String)? {
+        return Err(RedisError::String(format!(
+            "Event with UID: '{event_uid}' has no occurrence at: '{override_date_string}' (use DETACHED to set a detached override)",
+        )));
+    }
+
     // Spawn the process of parsing the query into it's own timeout enforced thread to guard
     // against malicious payloads intended to cause hangs.
-    let event_occurrence_override =
+    let mut event_occurrence_override =
         match run_with_timeout(
-            move || EventOccurrenceOverride::parse_ical(override_date_string, other.as_str()).map_err(RedisError::String),
+            move || clock::with_now(now, || EventOccurrenceOverride::parse_ical(override_date_string, other.as_str())).map_err(RedisError::String),
             std::time::Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64),
         ) {
             Ok(parser_result) => {
@@ -90,26 +111,99 @@ pub fn redical_event_override_set(ctx: &Context, args: Vec<RedisString>) -> Redi
             },
         };
 
+    calendar.localize_event_occurrence_override(&mut event_occurrence_override).map_err(RedisError::String)?;
+
     // Validate new event occurrence override's LAST-MODIFIED property (if provided) is more
     // recent than that on the existing event occurrence override (if present).
     //
     // Only proceed with inserting the newly provided event occurrence override if it is found
     // to have a newer LAST-MODIFIED property than the existing event occurrence override, if
-    // not then we skip the insert and return false to signal this to the client.
+    // not then (by default) we skip the insert and return false to signal this to the client,
+    // otherwise the conflict is resolved with the requested strategy.
+    // Like SET, a write that is only to create (NX) or only to update (XX) the event occurrence
+    // override is skipped (returning nil) if it respectively does or does not already exist.
+    if existence_condition.is_some_and(|existence_condition| !existence_condition.is_met(event.overrides.contains_key(&override_timestamp))) {
+        ctx.log_debug(
+            format!(
+                "rdcl.evo_set: key: {calendar_uid} event uid: {event_uid} - DTSTART: {override_date_string} - skipped due to unmet existence condition: {existence_condition:?}",
+            ).as_str()
+        );
+
+        return Ok(RedisValue::Null);
+    }
+
+    // Only apply a conditional write if the stored event occurrence override is still the version
+    // the client last read (optimistic concurrency), otherwise reject it with a CONFLICT error.
+    if let Some(if_match_condition) = if_match_condition.as_ref() {
+        if_match_condition
+            .verify(
+                event.overrides
+                    .get(&override_timestamp)
+                    .map(|existing_event_occurrence_override| {
+                        (&existing_event_occurrence_override.last_modified, &existing_event_occurrence_override.passive_properties)
+                    })
+            )
+            .map_err(|error| {
+                RedisError::String(format!("CONFLICT rdcl.evo_set: event uid: {event_uid} occurrence: {override_date_string} {error}"))
+            })?;
+    }
+
+    let mut conflict_action = LastModifiedConflictAction::Set;
+
     if let Some(existing_event_occurrence_override) = event.overrides.get(&override_timestamp) {
         if event_occurrence_override.last_modified < existing_event_occurrence_override.last_modified {
-            ctx.log_debug(
-                format!(
-                    "rdcl.evo_set: key: {calendar_uid} event uid: {event_uid} - DTSTART: {override_date_string} - skipped due to existing superseding LAST-MODIFIED - existing: {} new: {}",
-                    existing_event_occurrence_override.last_modified,
-                    event_occurrence_override.last_modified,
-                ).as_str()
-            );
-
-            return Ok(RedisValue::Bool(false));
+            match conflict_strategy.unwrap_or_default() {
+                LastModifiedConflictStrategy::Ignore => {
+                    ctx.log_debug(
+                        format!(
+                            "rdcl.evo_set: key: {calendar_uid} event uid: {event_uid} - DTSTART: {override_date_string} - skipped due to existing superseding LAST-MODIFIED - existing: {} new: {}",
+                            existing_event_occurrence_override.last_modified,
+                            event_occurrence_override.last_modified,
+                        ).as_str()
+                    );
+
+                    return Ok(LastModifiedConflictAction::Ignored.build_reply(conflict_strategy, RedisValue::Bool(false)));
+                },
+
+                LastModifiedConflictStrategy::Error => {
+                    return Err(RedisError::String(format!(
+                        "rdcl.evo_set: event uid: {event_uid} occurrence: {override_date_string} {} is older than existing {}",
+                        event_occurrence_override.last_modified,
+                        existing_event_occurrence_override.last_modified,
+                    )));
+                },
+
+                LastModifiedConflictStrategy::Force => {
+                    ctx.log_debug(
+                        format!(
+                            "rdcl.evo_set: key: {calendar_uid} event uid: {event_uid} - DTSTART: {override_date_string} - forced despite existing superseding LAST-MODIFIED - existing: {} new: {}",
+                            existing_event_occurrence_override.last_modified,
+                            event_occurrence_override.last_modified,
+                        ).as_str()
+                    );
+
+                    conflict_action = LastModifiedConflictAction::Forced;
+                },
+            }
         }
     }
 
+    let is_changed =
+        event.overrides
+            .get(&override_timestamp)
+            .is_none_or(|existing_event_occurrence_override| {
+                existing_event_occurrence_override.to_rendered_content_lines() != event_occurrence_override.to_rendered_content_lines()
+            });
+
+    let event_message =
+        build_keyspace_event_message(
+            CONFIGURATION_NOTIFICATION_VERBOSITY.lock(ctx).clone(),
+            &event_uid,
+            override_date_string,
+            event.overrides.get(&override_timestamp),
+            &event_occurrence_override,
+        );
+
     event.override_occurrence(&event_occurrence_override, calendar.indexes_active.to_owned()).map_err(RedisError::String)?;
 
     // HashMap.insert returns the old value (if present) which we can use in diffing old -> new.
@@ -156,6 +250,22 @@ pub fn redical_event_override_set(ctx: &Context, args: Vec<RedisString>) -> Redi
             event.indexed_class.as_ref(),
         );
 
+        let updated_event_location_diff = InvertedEventIndex::diff_indexed_terms(
+            existing_event
+                .as_ref()
+                .and_then(|existing_event| existing_event.indexed_location.clone())
+                .as_ref(),
+            event.indexed_location.as_ref(),
+        );
+
+        let updated_event_x_properties_diff = InvertedEventIndex::diff_indexed_terms(
+            existing_event
+                .as_ref()
+                .and_then(|existing_event| existing_event.indexed_x_properties.clone())
+                .as_ref(),
+            event.indexed_x_properties.as_ref(),
+        );
+
         let mut calendar_index_updater = CalendarIndexUpdater::new(&event_uid, calendar);
 
         calendar_index_updater
@@ -177,6 +287,18 @@ pub fn redical_event_override_set(ctx: &Context, args: Vec<RedisString>) -> Redi
         calendar_index_updater
             .update_indexed_class(&updated_event_class_diff)
             .map_err(|error| RedisError::String(error.to_string()))?;
+
+        calendar_index_updater
+            .update_indexed_location(&updated_event_location_diff)
+            .map_err(|error| RedisError::String(error.to_string()))?;
+
+        calendar_index_updater
+            .update_indexed_x_properties(&updated_event_x_properties_diff)
+            .map_err(|error| RedisError::String(error.to_string()))?;
+
+        calendar_index_updater
+            .update_indexed_time_buckets(Some(&event))
+            .map_err(|error| RedisError::String(error.to_string()))?;
     }
 
     ctx.log_debug(
@@ -187,18 +309,96 @@ pub fn redical_event_override_set(ctx: &Context, args: Vec<RedisString>) -> Redi
         ).as_str()
     );
 
-    // Use this command when replicating across other Redis instances.
-    ctx.replicate_verbatim();
+    // Replicate the event occurrence override as stored (rather than verbatim) so that any values
+    // resolved by this instance (e.g. defaulting LAST-MODIFIED to now) are identical across
+    // replicas and the AOF.
+    //
+    // Forced event occurrence overrides are replicated as such so that replicas do not ignore
+    // them, and any provided NOW is replicated so that replicas resolve defaulted values
+    // identically.
+    let serialized_event_occurrence_override_ical = event_occurrence_override.to_rendered_content_lines().join(" ");
+
+    let calendar_uid_string = calendar_uid.to_string();
+
+    let mut replicated_args = vec![calendar_uid_string.as_str(), event_uid.as_str(), override_date_string];
+
+    if is_detached {
+        replicated_args.push("DETACHED");
+    }
+
+    let now_string = now.map(|now| now.format("%Y%m%dT%H%M%SZ").to_string());
+
+    if let Some(now_string) = now_string.as_ref() {
+        replicated_args.extend(["NOW", now_string.as_str()]);
+    }
+
+    if conflict_action == LastModifiedConflictAction::Forced {
+        replicated_args.extend(["ON-CONFLICT", "FORCE"]);
+    }
+
+    replicated_args.push(serialized_event_occurrence_override_ical.as_str());
+
+    ctx.replicate("rdcl.evo_set", replicated_args.as_slice());
+
+    notify_keyspace_event(ctx, &calendar_uid, &event_uid, override_date_string, &event_message)?;
+
+    append_changes(
+        ctx,
+        &calendar_uid,
+        &[
+            Change::new(ChangeType::OverrideSet, &event_uid)
+                .with_override_date_string(override_date_string)
+                .with_last_modified(&event_occurrence_override.last_modified),
+        ],
+    );
+
+    calendar.bump_generation();
 
-    notify_keyspace_event(ctx, &calendar_uid, &event_uid, override_date_string, &event_occurrence_override.last_modified.to_string())?;
+    notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
 
-    Ok(serialize_event_occurrence_override(&event_occurrence_override))
+    Ok(
+        conflict_action.build_reply(
+            conflict_strategy,
+            reply_mode.build_reply(is_changed, serialize_event_occurrence_override(&event_occurrence_override)),
+        )
+    )
 }
 
-fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String, override_date_string: &str, last_modified_ical_property: &String) -> Result<(), RedisError> {
-    let event_message = format!("rdcl.evo_set:{event_uid}:{override_date_string} {last_modified_ical_property}");
+// Build the keyspace event message detailed according to the notification-verbosity config.
+fn build_keyspace_event_message(
+    notification_verbosity: NotificationVerbosity,
+    event_uid: &String,
+    override_date_string: &str,
+    existing_event_occurrence_override: Option<&EventOccurrenceOverride>,
+    event_occurrence_override: &EventOccurrenceOverride,
+) -> String {
+    match notification_verbosity {
+        NotificationVerbosity::minimal => {
+            format!("rdcl.evo_set:{event_uid}:{override_date_string}")
+        },
+
+        NotificationVerbosity::standard => {
+            format!("rdcl.evo_set:{event_uid}:{override_date_string} {}", event_occurrence_override.last_modified)
+        },
+
+        NotificationVerbosity::detailed => {
+            let changed_property_names = get_changed_property_names(existing_event_occurrence_override, event_occurrence_override);
+
+            if changed_property_names.is_empty() {
+                return format!("rdcl.evo_set:{event_uid}:{override_date_string} {}", event_occurrence_override.last_modified);
+            }
+
+            format!(
+                "rdcl.evo_set:{event_uid}:{override_date_string} {} {}",
+                event_occurrence_override.last_modified,
+                Vec::from_iter(changed_property_names).join(","),
+            )
+        },
+    }
+}
 
-    if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), calendar_uid) == Status::Err {
+fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String, override_date_string: &str, event_message: &str) -> Result<(), RedisError> {
+    if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message, calendar_uid) == Status::Err {
         return Err(
             RedisError::String(
                 format!("Notify keyspace event \"rdcl.evo_set\" for calendar: \"{}\" event: \"{}\" date string: \"{}\"", &calendar_uid, &event_uid, &override_date_string)
@@ -0,0 +1,58 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::core::Calendar;
+use crate::datatype::{open_indexed_calendar_key, CALENDAR_DATA_TYPE};
+
+use crate::reply::{ReplyFormat, icalendar_component_to_redis_value};
+
+pub fn redical_event_children(ctx: &Context, mut args: Vec<RedisString>) -> RedisResult {
+    if args.len() < 4 {
+        ctx.log_debug(format!("rdcl.evt_children: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let reply_format = ReplyFormat::from_output_args(ctx, &mut args)?;
+
+    if args.len() != 4 {
+        ctx.log_debug(format!("rdcl.evt_children: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+    let reltype = args.next_arg()?.to_string();
+    let related_uid = args.next_arg()?.to_string();
+
+    let calendar_key = open_indexed_calendar_key(ctx, &calendar_uid)?;
+
+    ctx.log_debug(
+        format!("rdcl.evt_children: calendar_uid: {calendar_uid} reltype: {reltype} uid: {related_uid}").as_str()
+    );
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    // The related events are looked up via the RELATED-TO index, so are unavailable (rather than
+    // silently missing) whilst it is disabled.
+    if !calendar.indexes_active {
+        return Err(RedisError::String(format!(
+            "rdcl.evt_children: Disabled on Calendar: {calendar_uid} because it's indexes have been disabled."
+        )));
+    }
+
+    Ok(
+        RedisValue::Array(
+            calendar
+                .get_related_events(reltype.as_str(), related_uid.as_str())
+                .into_iter()
+                .map(|event| icalendar_component_to_redis_value(event, None, reply_format))
+                .collect()
+        )
+    )
+}
@@ -1,6 +1,8 @@
 use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, Status, RedisValue};
 
-use crate::core::{Calendar, CalendarIndexUpdater, InvertedEventIndex};
+use crate::core::Calendar;
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
 use crate::datatype::CALENDAR_DATA_TYPE;
 
 pub fn redical_event_del(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
@@ -27,70 +29,32 @@ pub fn redical_event_del(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
         )));
     };
 
-    let Some(existing_event) = calendar.events.get_mut(&event_uid) else {
+    if !remove_event_and_reindex(calendar, &event_uid)? {
         return Ok(RedisValue::Bool(false));
-    };
-
-    if calendar.indexes_active {
-        let updated_event_categories_diff = InvertedEventIndex::diff_indexed_terms(
-            existing_event.indexed_categories.as_ref(),
-            None,
-        );
-
-        let updated_event_location_type_diff = InvertedEventIndex::diff_indexed_terms(
-            existing_event.indexed_location_type.as_ref(),
-            None,
-        );
-
-        let updated_event_related_to_diff = InvertedEventIndex::diff_indexed_terms(
-            existing_event.indexed_related_to.as_ref(),
-            None,
-        );
-
-        let updated_event_geo_diff = InvertedEventIndex::diff_indexed_terms(
-            existing_event.indexed_geo.as_ref(),
-            None,
-        );
-
-        let updated_event_class_diff = InvertedEventIndex::diff_indexed_terms(
-            existing_event.indexed_class.as_ref(),
-            None,
-        );
-
-        let mut calendar_index_updater = CalendarIndexUpdater::new(&event_uid, calendar);
-
-        calendar_index_updater
-            .update_indexed_categories(&updated_event_categories_diff)
-            .map_err(|error| RedisError::String(error.to_string()))?;
-
-        calendar_index_updater
-            .update_indexed_location_type(&updated_event_location_type_diff)
-            .map_err(|error| RedisError::String(error.to_string()))?;
-
-        calendar_index_updater
-            .update_indexed_related_to(&updated_event_related_to_diff)
-            .map_err(|error| RedisError::String(error.to_string()))?;
-
-        calendar_index_updater
-            .update_indexed_geo(&updated_event_geo_diff)
-            .map_err(|error| RedisError::String(error.to_string()))?;
-
-        calendar_index_updater
-            .update_indexed_class(&updated_event_class_diff)
-            .map_err(|error| RedisError::String(error.to_string()))?;
     }
 
-    calendar.remove_event(&event_uid);
-
     // Use this command when replicating across other Redis instances.
     ctx.replicate_verbatim();
 
     notify_keyspace_event(ctx, &calendar_uid, &event_uid)?;
 
+    append_changes(ctx, &calendar_uid, &[Change::new(ChangeType::EventDel, &event_uid)]);
+
+    calendar.bump_generation();
+
+    notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
+
     Ok(RedisValue::Bool(true))
 }
 
-fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String) -> Result<(), RedisError> {
+// Remove the Event from the Calendar (and it's indexes), returning false if it does not exist.
+pub fn remove_event_and_reindex(calendar: &mut Calendar, event_uid: &String) -> Result<bool, RedisError> {
+    let removed_event = calendar.remove_event_and_reindex(event_uid).map_err(RedisError::String)?;
+
+    Ok(removed_event.is_some())
+}
+
+pub fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String) -> Result<(), RedisError> {
     let event_message = format!("rdcl.evt_del:{event_uid}");
 
     if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), calendar_uid) == Status::Err {
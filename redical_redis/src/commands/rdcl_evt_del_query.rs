@@ -0,0 +1,139 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::core::queries::query::Query;
+use crate::core::queries::event_query::EventQuery;
+use crate::core::queries::results::QueryableEntity;
+use crate::core::{Calendar, ExecutionDeadline};
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::commands::{remove_event_and_reindex, notify_event_del_keyspace_event};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
+use crate::datatype::{open_indexed_calendar_key_writable, CALENDAR_DATA_TYPE};
+use crate::utils::{join_content_line_args, run_with_timeout, TimeoutError};
+use crate::{CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS};
+
+pub fn redical_event_del_query(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() < 3 {
+        ctx.log_debug(format!("rdcl.evt_del_query: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+
+    let calendar_key = open_indexed_calendar_key_writable(ctx, &calendar_uid)?;
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "rdcl.evt_del_query: No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    if !calendar.indexes_active {
+        return Err(RedisError::String(format!(
+            "rdcl.evt_del_query: Queries disabled on Calendar: {calendar_uid} because it's indexes have been disabled."
+        )));
+    }
+
+    let query_string: String = join_content_line_args(args);
+
+    ctx.log_debug(format!("rdcl.evt_del_query: calendar_uid: {calendar_uid} query: {query_string}").as_str());
+
+    let default_tz = calendar.get_default_tz();
+
+    // Spawn the process of parsing the query into it's own timeout enforced thread to guard
+    // against malicious payloads intended to cause hangs.
+    let mut parsed_query =
+        match run_with_timeout(
+            move || EventQuery::from_str_with_default_timezone(query_string.as_str(), default_tz.as_ref()).map_err(RedisError::String),
+            std::time::Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64),
+        ) {
+            Ok(parser_result) => {
+                parser_result?
+            },
+
+            Err(TimeoutError) => {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.evt_del_query: query iCal parser exceeded timeout -- calendar_uid: {calendar_uid}",
+                    ).as_str()
+                );
+
+                return Err(RedisError::String(String::from(
+                    "rdcl.evt_del_query: query iCal parser exceeded timeout"
+                )));
+            },
+        };
+
+    // Guard against unintentionally deleting every event in the Calendar.
+    if parsed_query.where_conditional.is_none()
+        && parsed_query.lower_bound_range_condition.is_none()
+        && parsed_query.upper_bound_range_condition.is_none()
+    {
+        return Err(RedisError::String(String::from(
+            "rdcl.evt_del_query: at least one where property (or X-FROM/X-UNTIL range) is required"
+        )));
+    }
+
+    // Every matching event is deleted, so any ordering, offset, or limit is disregarded.
+    parsed_query.offset = 0;
+    parsed_query.limit = calendar.events.len();
+
+    // The query execution is cooperatively aborted once this deadline has been exceeded, before
+    // any events are deleted, so that no partial deletion is ever made.
+    let execution_deadline = ExecutionDeadline::new(
+        std::time::Duration::from_millis(*CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64)
+    );
+
+    let query_results = match parsed_query.execute_with_deadline(calendar, &execution_deadline) {
+        Ok(query_results) => query_results,
+
+        Err(_) if execution_deadline.is_exceeded() => {
+            ctx.log_warning(
+                format!(
+                    "rdcl.evt_del_query: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                ).as_str()
+            );
+
+            return Err(RedisError::String(String::from("rdcl.evt_del_query: query exceeded timeout")));
+        },
+
+        Err(error) => {
+            return Err(RedisError::String(error));
+        },
+    };
+
+    let matched_event_uids: Vec<String> =
+        query_results
+            .results
+            .iter()
+            .map(|query_result| query_result.result.get_uid())
+            .collect();
+
+    let mut deleted_event_count = 0;
+
+    for event_uid in matched_event_uids {
+        if !remove_event_and_reindex(calendar, &event_uid)? {
+            continue;
+        }
+
+        deleted_event_count += 1;
+
+        // Replicate each deletion individually (rather than verbatim) so that replicas and the
+        // AOF delete exactly the same events, regardless of their own query timeouts.
+        ctx.replicate("rdcl.evt_del", &[&calendar_uid.to_string(), &event_uid]);
+
+        notify_event_del_keyspace_event(ctx, &calendar_uid, &event_uid)?;
+
+        append_changes(ctx, &calendar_uid, &[Change::new(ChangeType::EventDel, &event_uid)]);
+    }
+
+    if deleted_event_count > 0 {
+        calendar.bump_generation();
+
+        notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
+    }
+
+    Ok(RedisValue::Integer(deleted_event_count))
+}
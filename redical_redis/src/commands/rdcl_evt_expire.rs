@@ -0,0 +1,133 @@
+use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, RedisValue, Status};
+
+use std::str::FromStr;
+
+use redical_ical::values::date_time::DateTime;
+
+use crate::core::Calendar;
+use crate::datatype::CALENDAR_DATA_TYPE;
+use crate::event_expiry::track_expiring_calendar;
+use crate::utils::get_selected_db;
+use crate::CONFIGURATION_EVENT_EXPIRY_GRACE_PERIOD_SECS;
+
+pub fn redical_event_expire(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if !(3..=5).contains(&args.len()) {
+        ctx.log_debug(format!("rdcl.evt_expire: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+    let event_uid = args.next_arg()?.to_string();
+
+    let option = args.next().map(|arg| arg.to_string().to_ascii_uppercase());
+
+    let expires_at_date_string =
+        match option.as_deref() {
+            None => None,
+            Some("AT") => Some(args.next_arg()?.to_string()),
+            Some("PERSIST") => None,
+
+            Some(option) => {
+                return Err(RedisError::String(format!(
+                    "rdcl.evt_expire: Unknown option: {option} (expected AT or PERSIST)"
+                )));
+            },
+        };
+
+    if args.next().is_some() {
+        return Err(RedisError::WrongArity);
+    }
+
+    let calendar_key = ctx.open_key_writable(&calendar_uid);
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    let Some(event) = calendar.events.get(&event_uid) else {
+        return Ok(RedisValue::Bool(false));
+    };
+
+    if option.as_deref() == Some("PERSIST") {
+        if calendar.remove_event_expiration(&event_uid).is_none() {
+            return Ok(RedisValue::Bool(false));
+        }
+
+        calendar.bump_generation();
+
+        // Use this command when replicating across other Redis instances.
+        ctx.replicate_verbatim();
+
+        notify_keyspace_event(ctx, &calendar_uid, &event_uid)?;
+
+        return Ok(RedisValue::Bool(true));
+    }
+
+    let expires_at =
+        match expires_at_date_string {
+            Some(expires_at_date_string) => {
+                DateTime::from_str(expires_at_date_string.as_str())
+                    .map(|date_time| date_time.get_utc_timestamp(None))
+                    .map_err(RedisError::String)?
+            },
+
+            // Default to the end of the final occurrence of the event plus the configured grace
+            // period.
+            None => {
+                let Some(last_occurrence_dtend_timestamp) = event.get_last_occurrence_dtend_timestamp().map_err(RedisError::String)? else {
+                    return Err(RedisError::String(format!(
+                        "rdcl.evt_expire: Event: {event_uid} recurs indefinitely, AT must be provided"
+                    )));
+                };
+
+                let grace_period_secs = *CONFIGURATION_EVENT_EXPIRY_GRACE_PERIOD_SECS.lock(ctx);
+
+                last_occurrence_dtend_timestamp.saturating_add(grace_period_secs)
+            },
+        };
+
+    calendar.set_event_expiration(&event_uid, expires_at);
+
+    calendar.bump_generation();
+
+    track_expiring_calendar(get_selected_db(ctx), calendar_uid.to_string());
+
+    let expires_at_date_string = DateTime::from(expires_at).render_formatted_date_time(None);
+
+    // Replicate the resolved expiry (instead of verbatim) so that replicas (and the AOF) do not
+    // resolve it themselves with a potentially different grace period configured.
+    ctx.replicate(
+        "rdcl.evt_expire",
+        &[
+            calendar_uid.to_string().as_str(),
+            event_uid.as_str(),
+            "AT",
+            expires_at_date_string.as_str(),
+        ],
+    );
+
+    notify_keyspace_event(ctx, &calendar_uid, &event_uid)?;
+
+    ctx.log_debug(format!("rdcl.evt_expire: key: {calendar_uid} event uid: {event_uid} expires at: {expires_at_date_string}").as_str());
+
+    Ok(RedisValue::SimpleString(expires_at_date_string))
+}
+
+fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String) -> Result<(), RedisError> {
+    let event_message = format!("rdcl.evt_expire:{event_uid}");
+
+    if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), calendar_uid) == Status::Err {
+        return Err(
+            RedisError::String(
+                format!("Notify keyspace event \"rdcl.evt_expire\" for calendar: \"{}\" event: \"{}\"", &calendar_uid, &event_uid)
+            )
+        );
+    }
+
+    Ok(())
+}
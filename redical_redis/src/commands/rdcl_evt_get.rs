@@ -3,7 +3,7 @@ use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, Redis
 use crate::core::Calendar;
 use crate::datatype::CALENDAR_DATA_TYPE;
 
-use redical_ical::ICalendarComponent;
+use crate::reply::{ReplyFormat, icalendar_component_to_redis_value};
 
 pub fn redical_event_get(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     if args.len() < 2 {
@@ -34,16 +34,6 @@ pub fn redical_event_get(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
         .get(&event_uid)
         .map_or(
             Ok(RedisValue::Null),
-            |event| {
-                Ok(
-                    RedisValue::Array(
-                        event
-                            .to_rendered_content_lines()
-                            .into_iter()
-                            .map(RedisValue::SimpleString)
-                            .collect(),
-                    )
-                )
-            },
+            |event| Ok(icalendar_component_to_redis_value(event.as_ref(), None, ReplyFormat::from_context(ctx))),
         )
 }
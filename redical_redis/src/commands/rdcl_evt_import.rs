@@ -0,0 +1,369 @@
+use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, RedisValue, Status};
+
+use crate::core::{
+    parse_ics_document, Calendar, Event, EventOccurrenceOverride, ICSEventComponent,
+};
+
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
+use crate::datatype::CALENDAR_DATA_TYPE;
+
+use crate::utils::{check_max_events_per_calendar, get_max_events_per_calendar, run_with_timeout, validate_event_schema, TimeoutError};
+use crate::CONFIGURATION_ICAL_PARSER_TIMEOUT_MS;
+
+use super::rdcl_evt_set::update_calendar_indexes;
+
+use redical_ical::{ICalendarComponent, ICalendarEntity};
+use redical_ical::properties::{ICalendarDateTimeProperty, RecurrenceIDProperty, UIDProperty};
+
+// The outcome of importing a single VEVENT component.
+enum ImportResult {
+    Imported,
+    Skipped,
+    Error(String),
+}
+
+impl ImportResult {
+    fn to_redis_value(&self) -> RedisValue {
+        match self {
+            ImportResult::Imported => RedisValue::SimpleStringStatic("IMPORTED"),
+            ImportResult::Skipped => RedisValue::SimpleStringStatic("SKIPPED"),
+            ImportResult::Error(error) => RedisValue::SimpleString(format!("ERROR: {error}")),
+        }
+    }
+}
+
+// Either the parsed event/override, or the error encountered parsing it (so that one invalid
+// component does not prevent the others from being imported).
+enum ParsedComponent {
+    Event(Result<Box<Event>, String>),
+    Override(Result<Box<EventOccurrenceOverride>, String>),
+}
+
+fn serialize_import_result(ics_event_component: &ICSEventComponent, import_result: &ImportResult) -> RedisValue {
+    RedisValue::Array(
+        vec![
+            RedisValue::SimpleString(ics_event_component.uid.to_owned()),
+            ics_event_component
+                .get_recurrence_id_date_string()
+                .map_or(RedisValue::Null, RedisValue::SimpleString),
+            import_result.to_redis_value(),
+        ]
+    )
+}
+
+// Interpret the floating date-times of the parsed event/override in the Calendar default timezone
+// (if declared), and validate parsed events against the Calendar schema (if declared), any error
+// encountered doing so is reported as the result of it's import.
+fn localize_parsed_component(ctx: &Context, calendar: &Calendar, parsed_component: ParsedComponent) -> ParsedComponent {
+    match parsed_component {
+        ParsedComponent::Event(Ok(mut event)) => {
+            ParsedComponent::Event(
+                calendar
+                    .localize_event(&mut event)
+                    .and_then(|_| validate_event_schema(ctx, calendar, &event))
+                    .map(|_| event)
+            )
+        },
+
+        ParsedComponent::Override(Ok(mut event_occurrence_override)) => {
+            ParsedComponent::Override(calendar.localize_event_occurrence_override(&mut event_occurrence_override).map(|_| event_occurrence_override))
+        },
+
+        parsed_component => parsed_component,
+    }
+}
+
+pub fn redical_event_import(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() != 3 {
+        ctx.log_debug(format!("rdcl.evt_import: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+    let ics_document = args.next_arg()?.try_as_str()?.to_owned();
+
+    let calendar_key = ctx.open_key_writable(&calendar_uid);
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    // Spawn the process of parsing the document into it's own timeout enforced thread to guard
+    // against malicious payloads intended to cause hangs.
+    let (ics_event_components, parsed_components) =
+        match run_with_timeout(
+            move || {
+                let ics_event_components = parse_ics_document(ics_document.as_str())?;
+
+                let parsed_components =
+                    ics_event_components
+                        .iter()
+                        .map(|ics_event_component| {
+                            if ics_event_component.is_override() {
+                                ParsedComponent::Override(ics_event_component.parse_event_occurrence_override().map(Box::new))
+                            } else {
+                                ParsedComponent::Event(ics_event_component.parse_event().map(Box::new))
+                            }
+                        })
+                        .collect::<Vec<ParsedComponent>>();
+
+                Ok((ics_event_components, parsed_components))
+            },
+            std::time::Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64),
+        ) {
+            Ok(parser_result) => {
+                parser_result.map_err(RedisError::String)?
+            },
+
+            Err(TimeoutError) => {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.evt_import: iCal document parser exceeded timeout -- calendar uid: {calendar_uid}",
+                    ).as_str()
+                );
+
+                return Err(RedisError::String(String::from(
+                    "rdcl.evt_import: iCal document parser exceeded timeout"
+                )));
+            },
+        };
+
+    ctx.log_debug(
+        format!("rdcl.evt_import: key: {calendar_uid} component count: {}", ics_event_components.len()).as_str(),
+    );
+
+    let max_events_per_calendar = get_max_events_per_calendar(ctx);
+
+    let mut import_results: Vec<Option<ImportResult>> = ics_event_components.iter().map(|_| None).collect();
+    let mut imported_event_uids: Vec<String> = Vec::new();
+    let mut changes: Vec<Change> = Vec::new();
+
+    // The content lines of each imported component (as stored) to replicate.
+    let mut replicated_components: Vec<Vec<String>> = Vec::new();
+
+    let (parsed_events, parsed_overrides): (Vec<_>, Vec<_>) =
+        parsed_components
+            .into_iter()
+            .map(|parsed_component| localize_parsed_component(ctx, calendar, parsed_component))
+            .enumerate()
+            .partition(|(_index, parsed_component)| matches!(parsed_component, ParsedComponent::Event(_)));
+
+    // Import all of the events before their overrides so that overrides for events defined
+    // within the same document can be applied.
+    for (index, parsed_component) in parsed_events.into_iter().chain(parsed_overrides) {
+        let event_uid = ics_event_components[index].uid.to_owned();
+
+        let (import_result, component_content_lines, change) =
+            match parsed_component {
+                ParsedComponent::Event(Ok(event)) => {
+                    let event_content_lines = event.to_rendered_content_lines();
+
+                    let change = Change::new(ChangeType::EventSet, &event_uid).with_last_modified(&event.last_modified);
+
+                    (import_event(ctx, calendar, &calendar_uid, *event, max_events_per_calendar)?, event_content_lines, Some(change))
+                },
+
+                ParsedComponent::Override(Ok(event_occurrence_override)) => {
+                    let mut override_content_lines = vec![
+                        UIDProperty::from(event_uid.to_owned()).render_ical(),
+                        RecurrenceIDProperty::new_from_utc_timestamp(&ics_event_components[index].get_recurrence_id_timestamp().unwrap_or_default()).render_ical(),
+                    ];
+
+                    override_content_lines.extend(event_occurrence_override.to_rendered_content_lines());
+
+                    let mut change = Change::new(ChangeType::OverrideSet, &event_uid).with_last_modified(&event_occurrence_override.last_modified);
+
+                    if let Some(override_date_string) = ics_event_components[index].get_recurrence_id_date_string() {
+                        change = change.with_override_date_string(&override_date_string);
+                    }
+
+                    (import_event_occurrence_override(ctx, calendar, &calendar_uid, &event_uid, *event_occurrence_override)?, override_content_lines, Some(change))
+                },
+
+                ParsedComponent::Event(Err(error)) | ParsedComponent::Override(Err(error)) => {
+                    (ImportResult::Error(error), Vec::new(), None)
+                },
+            };
+
+        if matches!(import_result, ImportResult::Imported) {
+            replicated_components.push(component_content_lines);
+
+            changes.extend(change);
+        }
+
+        if matches!(import_result, ImportResult::Imported) && !imported_event_uids.contains(&event_uid) {
+            imported_event_uids.push(event_uid);
+        }
+
+        import_results[index] = Some(import_result);
+    }
+
+    ctx.log_debug(
+        format!(
+            "rdcl.evt_import: key: {calendar_uid} imported event uids: {} - count: {}",
+            imported_event_uids.join(","),
+            calendar.events.len(),
+        ).as_str()
+    );
+
+    if !imported_event_uids.is_empty() {
+        // Replicate only the imported components as stored (rather than verbatim) so that any
+        // values resolved by this instance (e.g. defaulting LAST-MODIFIED to now) are identical
+        // across replicas and the AOF.
+        ctx.replicate(
+            "rdcl.evt_import",
+            &[
+                &calendar_uid.to_string(),
+                &build_replicated_ics_document(&replicated_components),
+            ],
+        );
+
+        notify_keyspace_event(ctx, &calendar_uid, &imported_event_uids)?;
+
+        append_changes(ctx, &calendar_uid, &changes);
+
+        calendar.bump_generation();
+
+        notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
+    }
+
+    Ok(
+        RedisValue::Array(
+            ics_event_components
+                .iter()
+                .zip(import_results)
+                .map(|(ics_event_component, import_result)| {
+                    serialize_import_result(ics_event_component, &import_result.unwrap_or(ImportResult::Skipped))
+                })
+                .collect()
+        )
+    )
+}
+
+fn build_replicated_ics_document(components: &[Vec<String>]) -> String {
+    let mut content_lines = vec![String::from("BEGIN:VCALENDAR")];
+
+    for component_content_lines in components {
+        content_lines.push(String::from("BEGIN:VEVENT"));
+        content_lines.extend(component_content_lines.iter().cloned());
+        content_lines.push(String::from("END:VEVENT"));
+    }
+
+    content_lines.push(String::from("END:VCALENDAR"));
+
+    content_lines.join("\r\n")
+}
+
+fn import_event(ctx: &Context, calendar: &mut Calendar, calendar_uid: &RedisString, mut event: Event, max_events_per_calendar: i64) -> Result<ImportResult, RedisError> {
+    let event_uid = event.uid.uid.to_string();
+
+    let existing_event =
+        calendar
+            .events
+            .get(&event_uid)
+            .cloned();
+
+    // Validate new event's LAST-MODIFIED property (if provided) is more recent than that on the
+    // existing event.
+    if let Some(existing_event) = existing_event.as_ref() {
+        if event.last_modified < existing_event.last_modified {
+            ctx.log_debug(
+                format!(
+                    "rdcl.evt_import: key: {calendar_uid} event uid: {event_uid} - skipped due to existing superseding LAST-MODIFIED - existing: {} new: {}",
+                    existing_event.last_modified,
+                    event.last_modified,
+                ).as_str()
+            );
+
+            return Ok(ImportResult::Skipped);
+        }
+
+        event.overrides = existing_event.overrides.clone();
+    }
+
+    // Only new events which would exceed the max events per calendar limit are rejected, leaving
+    // the rest of the document to be imported.
+    if existing_event.is_none() {
+        if let Err(error) = check_max_events_per_calendar(calendar, 1, max_events_per_calendar) {
+            return Ok(ImportResult::Error(error));
+        }
+    }
+
+    if calendar.indexes_active {
+        event.rebuild_indexes().map_err(RedisError::String)?;
+
+        update_calendar_indexes(calendar, &event_uid, existing_event.as_deref(), &event)?;
+    }
+
+    calendar.insert_event(event);
+
+    Ok(ImportResult::Imported)
+}
+
+fn import_event_occurrence_override(
+    ctx: &Context,
+    calendar: &mut Calendar,
+    calendar_uid: &RedisString,
+    event_uid: &String,
+    event_occurrence_override: EventOccurrenceOverride,
+) -> Result<ImportResult, RedisError> {
+    let Some(mut event) = calendar.get_event(event_uid).cloned() else {
+        return Ok(ImportResult::Error(format!("No event with UID: '{event_uid}' found")));
+    };
+
+    let Some(override_timestamp) = event_occurrence_override.get_dtstart_timestamp() else {
+        return Ok(ImportResult::Error(String::from("Expected event occurrence override to have dtstart defined.")));
+    };
+
+    // Reject overrides for dates which are not occurrences of the event.
+    if !event.is_occurrence(override_timestamp).map_err(RedisError::String)? {
+        return Ok(ImportResult::Error(format!("Event with UID: '{event_uid}' has no occurrence at RECURRENCE-ID")));
+    }
+
+    // Validate new event occurrence override's LAST-MODIFIED property (if provided) is more
+    // recent than that on the existing event occurrence override (if present).
+    if let Some(existing_event_occurrence_override) = event.overrides.get(&override_timestamp) {
+        if event_occurrence_override.last_modified < existing_event_occurrence_override.last_modified {
+            ctx.log_debug(
+                format!(
+                    "rdcl.evt_import: key: {calendar_uid} event uid: {event_uid} - DTSTART: {override_timestamp} - skipped due to existing superseding LAST-MODIFIED - existing: {} new: {}",
+                    existing_event_occurrence_override.last_modified,
+                    event_occurrence_override.last_modified,
+                ).as_str()
+            );
+
+            return Ok(ImportResult::Skipped);
+        }
+    }
+
+    event.override_occurrence(&event_occurrence_override, calendar.indexes_active.to_owned()).map_err(RedisError::String)?;
+
+    // HashMap.insert returns the old value (if present) which we can use in diffing old -> new.
+    let existing_event = calendar.insert_event(event.clone());
+
+    if calendar.indexes_active {
+        update_calendar_indexes(calendar, event_uid, existing_event.as_ref(), &event)?;
+    }
+
+    Ok(ImportResult::Imported)
+}
+
+fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uids: &[String]) -> Result<(), RedisError> {
+    let event_message = format!("rdcl.evt_import:{}", event_uids.join(","));
+
+    if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), calendar_uid) == Status::Err {
+        return Err(
+            RedisError::String(
+                format!("Notify keyspace event \"rdcl.evt_import\" for calendar: \"{}\" events: \"{}\"", &calendar_uid, event_uids.join(","))
+            )
+        );
+    }
+
+    Ok(())
+}
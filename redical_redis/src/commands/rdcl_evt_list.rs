@@ -3,37 +3,33 @@ use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, Redis
 use crate::core::{Calendar, Event};
 use crate::datatype::CALENDAR_DATA_TYPE;
 
-use redical_ical::ICalendarComponent;
+use crate::reply::{ReplyFormat, icalendar_component_to_redis_value};
 
-fn serialize_calendar_events(calendar: &Calendar, offset: usize, count: usize) -> RedisValue {
+fn serialize_calendar_events(calendar: &Calendar, offset: usize, count: usize, reply_format: ReplyFormat) -> RedisValue {
     RedisValue::Array(
         calendar.events
                 .values()
                 .skip(offset)
                 .take(count)
-                .map(serialize_event)
+                .map(|event| serialize_event(event, reply_format))
                 .collect()
     )
 }
 
 #[allow(clippy::borrowed_box)]
-fn serialize_event(event: &Box<Event>) -> RedisValue {
-    RedisValue::Array(
-        event
-            .to_rendered_content_lines()
-            .into_iter()
-            .map(RedisValue::SimpleString)
-            .collect()
-    )
+fn serialize_event(event: &Box<Event>, reply_format: ReplyFormat) -> RedisValue {
+    icalendar_component_to_redis_value(event.as_ref(), None, reply_format)
 }
 
-pub fn redical_event_list(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+pub fn redical_event_list(ctx: &Context, mut args: Vec<RedisString>) -> RedisResult {
     if args.is_empty() {
         ctx.log_debug(format!("rdcl.evt_list: WrongArity: {}", args.len()).as_str());
 
         return Err(RedisError::WrongArity);
     }
 
+    let reply_format = ReplyFormat::from_output_args(ctx, &mut args)?;
+
     let mut args = args.into_iter().skip(1);
 
     let calendar_uid = args.next_arg()?;
@@ -53,5 +49,5 @@ pub fn redical_event_list(ctx: &Context, args: Vec<RedisString>) -> RedisResult
         )));
     };
 
-    Ok(serialize_calendar_events(calendar, offset, count))
+    Ok(serialize_calendar_events(calendar, offset, count, reply_format))
 }
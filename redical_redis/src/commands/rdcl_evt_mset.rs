@@ -0,0 +1,246 @@
+use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, RedisValue, Status};
+
+use std::collections::{HashMap, HashSet};
+
+use crate::core::{Calendar, Event};
+
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
+use crate::datatype::{defer_failed_index_update, CALENDAR_DATA_TYPE};
+
+use crate::utils::{check_max_events_per_calendar, get_max_events_per_calendar, run_with_timeout, validate_event_schema, TimeoutError};
+use crate::CONFIGURATION_ICAL_PARSER_TIMEOUT_MS;
+
+use super::rdcl_evt_set::update_calendar_indexes;
+
+use redical_ical::ICalendarComponent;
+use redical_ical::properties::LastModifiedProperty;
+use redical_ical::content_line::join_line_separated_content_lines;
+
+pub fn redical_event_mset(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    // Expect the key followed by at least one `event-uid ical` pair.
+    if args.len() < 4 || !args.len().is_multiple_of(2) {
+        ctx.log_debug(format!("rdcl.evt_mset: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+
+    let mut event_uid_ical_pairs: Vec<(String, String)> = Vec::new();
+
+    while let Ok(event_uid) = args.next_arg() {
+        let event_uid = event_uid.to_string();
+        let ical = join_line_separated_content_lines(args.next_arg()?.try_as_str()?);
+
+        event_uid_ical_pairs.push((event_uid, ical));
+    }
+
+    ctx.log_debug(
+        format!("rdcl.evt_mset: key: {calendar_uid} event count: {}", event_uid_ical_pairs.len()).as_str(),
+    );
+
+    let calendar_key = ctx.open_key_writable(&calendar_uid);
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    // Parse and validate every event before touching the Calendar so that either all of the
+    // provided events are applied, or none of them are.
+    let mut events: Vec<Event> = Vec::with_capacity(event_uid_ical_pairs.len());
+
+    for (event_uid, ical) in event_uid_ical_pairs {
+        // Clone the event_uid for it to moved into the timeout enforced Event iCalendar parser
+        // thread below.
+        let parsed_event_uid = event_uid.clone();
+
+        // Spawn the process of parsing the query into it's own timeout enforced thread to guard
+        // against malicious payloads intended to cause hangs.
+        let mut event =
+            match run_with_timeout(
+                move || Event::parse_ical(parsed_event_uid.as_str(), ical.as_str()).map_err(RedisError::String),
+                std::time::Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64),
+            ) {
+                Ok(parser_result) => {
+                    parser_result.map_err(|error| {
+                        RedisError::String(format!("Event with UID: '{event_uid}' invalid: {error}"))
+                    })?
+                },
+
+                Err(TimeoutError) => {
+                    ctx.log_warning(
+                        format!(
+                            "rdcl.evt_mset: event iCal parser exceeded timeout -- calendar uid: {calendar_uid} event uid: {event_uid}",
+                        ).as_str()
+                    );
+
+                    return Err(RedisError::String(String::from(
+                        "rdcl.evt_mset: event iCal parser exceeded timeout"
+                    )));
+                },
+            };
+
+        event
+            .validate()
+            .and_then(|_| calendar.localize_event(&mut event))
+            .and_then(|_| validate_event_schema(ctx, calendar, &event))
+            .map_err(|error| {
+                RedisError::String(format!("Event with UID: '{event_uid}' invalid: {error}"))
+            })?;
+
+        events.push(event);
+    }
+
+    // Reject the whole batch if the new events (not already on the Calendar) would exceed the
+    // max events per calendar limit.
+    let new_event_uids: HashSet<String> =
+        events
+            .iter()
+            .map(|event| event.uid.uid.to_string())
+            .filter(|event_uid| !calendar.events.contains_key(event_uid))
+            .collect();
+
+    check_max_events_per_calendar(calendar, new_event_uids.len(), get_max_events_per_calendar(ctx))
+        .map_err(|error| RedisError::String(format!("rdcl.evt_mset: {error}")))?;
+
+    // Resolve which of the events supersede those already stored, and build their indexes, before
+    // touching the Calendar so that nothing fallible remains once the first event is applied.
+    //
+    // Events skipped due to an existing superseding LAST-MODIFIED are None.
+    let mut superseding_events: Vec<Option<Event>> = Vec::with_capacity(events.len());
+
+    // The LAST-MODIFIED of each event (by UID) as of the preceding events in the batch.
+    let mut last_modified_by_event_uid: HashMap<String, LastModifiedProperty> = HashMap::new();
+
+    for mut event in events {
+        let event_uid = event.uid.uid.to_string();
+
+        let existing_last_modified =
+            last_modified_by_event_uid
+                .get(&event_uid)
+                .or_else(|| calendar.events.get(&event_uid).map(|existing_event| &existing_event.last_modified));
+
+        // Validate new event's LAST-MODIFIED property (if provided) is more recent than that on
+        // the existing event, skipping it (without failing the whole batch) if not.
+        if let Some(existing_last_modified) = existing_last_modified {
+            if event.last_modified < *existing_last_modified {
+                ctx.log_debug(
+                    format!(
+                        "rdcl.evt_mset: key: {calendar_uid} event uid: {event_uid} - skipped due to existing superseding LAST-MODIFIED - existing: {} new: {}",
+                        existing_last_modified,
+                        event.last_modified,
+                    ).as_str()
+                );
+
+                superseding_events.push(None);
+
+                continue;
+            }
+        }
+
+        if let Some(existing_event) = calendar.events.get(&event_uid) {
+            event.overrides = existing_event.overrides.clone();
+        }
+
+        if calendar.indexes_active {
+            event.rebuild_indexes().map_err(|error| {
+                RedisError::String(format!("Event with UID: '{event_uid}' invalid: {error}"))
+            })?;
+        }
+
+        last_modified_by_event_uid.insert(event_uid, event.last_modified.clone());
+
+        superseding_events.push(Some(event));
+    }
+
+    let mut results: Vec<RedisValue> = Vec::with_capacity(superseding_events.len());
+    let mut updated_event_uids: Vec<String> = Vec::new();
+    let mut changes: Vec<Change> = Vec::new();
+
+    // The arguments to replicate, only including the events actually updated (as stored).
+    let mut replicated_args: Vec<String> = vec![calendar_uid.to_string()];
+
+    for superseding_event in superseding_events {
+        let Some(event) = superseding_event else {
+            results.push(RedisValue::Bool(false));
+
+            continue;
+        };
+
+        let event_uid = event.uid.uid.to_string();
+
+        if calendar.indexes_active {
+            let existing_event = calendar.events.get(&event_uid).cloned();
+
+            if let Err(error) = update_calendar_indexes(calendar, &event_uid, existing_event.as_deref(), &event) {
+                defer_failed_index_update(ctx, &calendar_uid, calendar, error);
+            }
+        }
+
+        let serialized_event_ical = event.to_rendered_content_lines();
+
+        replicated_args.push(event_uid.to_owned());
+        replicated_args.push(serialized_event_ical.join(" "));
+
+        results.push(
+            RedisValue::Array(
+                serialized_event_ical
+                    .into_iter()
+                    .map(RedisValue::SimpleString)
+                    .collect(),
+            )
+        );
+
+        changes.push(Change::new(ChangeType::EventSet, &event_uid).with_last_modified(&event.last_modified));
+
+        calendar.insert_event(event);
+
+        updated_event_uids.push(event_uid);
+    }
+
+    ctx.log_debug(
+        format!(
+            "rdcl.evt_mset: key: {calendar_uid} updated: {} skipped: {} - count: {}",
+            updated_event_uids.len(),
+            results.len() - updated_event_uids.len(),
+            calendar.events.len(),
+        ).as_str()
+    );
+
+    if !updated_event_uids.is_empty() {
+        // Replicate the updated events as stored (rather than verbatim) so that any values
+        // resolved by this instance (e.g. defaulting LAST-MODIFIED to now) are identical across
+        // replicas and the AOF.
+        ctx.replicate(
+            "rdcl.evt_mset",
+            &replicated_args.iter().map(String::as_str).collect::<Vec<&str>>()[..],
+        );
+
+        notify_keyspace_event(ctx, &calendar_uid, &updated_event_uids);
+
+        append_changes(ctx, &calendar_uid, &changes);
+
+        calendar.bump_generation();
+
+        notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
+    }
+
+    Ok(RedisValue::Array(results))
+}
+
+// The events have already been applied by this point, so a failure to notify is logged rather than
+// failing the command (which would misreport the events as not applied).
+fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uids: &[String]) {
+    let event_message = format!("rdcl.evt_mset:{}", event_uids.join(","));
+
+    if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), calendar_uid) == Status::Err {
+        ctx.log_warning(
+            format!("Notify keyspace event \"rdcl.evt_mset\" for calendar: \"{}\" events: \"{}\"", &calendar_uid, event_uids.join(",")).as_str()
+        );
+    }
+}
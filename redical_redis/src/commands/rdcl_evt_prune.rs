@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
 use crate::datatype::CALENDAR_DATA_TYPE;
 
 use redis_module::{Context,
@@ -17,7 +19,6 @@ use redical_core::{
     Calendar,
     Event,
     CalendarIndexUpdater,
-    InvertedEventIndex
 };
 
 use redical_ical::values::date_time::DateTime;
@@ -62,6 +63,14 @@ pub fn redical_event_prune(ctx: &Context, args: Vec<RedisString>) -> RedisResult
             &until,
             event_uid,
         )?;
+
+        append_changes(ctx, &calendar_uid, &[Change::new(ChangeType::EventDel, event_uid)]);
+    }
+
+    if !pruned_events.is_empty() {
+        calendar.bump_generation();
+
+        notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
     }
 
     Ok(RedisValue::Integer(pruned_events.len() as i64))
@@ -87,64 +96,21 @@ fn timestamps_from_date_strings(from: String, until: String) -> Result<(i64, i64
     Ok((from_timestamp, until_timestamp))
 }
 
-fn prune_and_reindex(calendar: &mut Calendar, from: i64, until: i64) -> Result<HashMap<String, Box<Event>>, RedisError> {
+pub fn prune_and_reindex(calendar: &mut Calendar, from: i64, until: i64) -> Result<HashMap<String, Box<Event>>, RedisError> {
     let pruned_events = calendar.prune_events(from, until).unwrap();
 
     if calendar.indexes_active {
         for (event_uid, pruned_event) in pruned_events.iter() {
-            let mut calendar_index_updater = CalendarIndexUpdater::new(event_uid, calendar);
-
-            let updated_event_categories_diff = InvertedEventIndex::diff_indexed_terms(
-                pruned_event.indexed_categories.as_ref(),
-                None,
-            );
-
-            let updated_event_location_type_diff = InvertedEventIndex::diff_indexed_terms(
-                pruned_event.indexed_location_type.as_ref(),
-                None,
-            );
-
-            let updated_event_related_to_diff = InvertedEventIndex::diff_indexed_terms(
-                pruned_event.indexed_related_to.as_ref(),
-                None,
-            );
-
-            let updated_event_geo_diff = InvertedEventIndex::diff_indexed_terms(
-                pruned_event.indexed_geo.as_ref(),
-                None,
-            );
-
-            let updated_event_class_diff = InvertedEventIndex::diff_indexed_terms(
-                pruned_event.indexed_class.as_ref(),
-                None,
-            );
-
-            calendar_index_updater
-                .update_indexed_categories(&updated_event_categories_diff)
-                .map_err(|error| RedisError::String(error.to_string()))?;
-            
-            calendar_index_updater
-                .update_indexed_location_type(&updated_event_location_type_diff)
-                .map_err(|error| RedisError::String(error.to_string()))?;
-            
-            calendar_index_updater
-                .update_indexed_related_to(&updated_event_related_to_diff)
-                .map_err(|error| RedisError::String(error.to_string()))?;
-            
-            calendar_index_updater
-                .update_indexed_geo(&updated_event_geo_diff)
-                .map_err(|error| RedisError::String(error.to_string()))?;
-            
-            calendar_index_updater
-                .update_indexed_class(&updated_event_class_diff)
-                .map_err(|error| RedisError::String(error.to_string()))?;
+            CalendarIndexUpdater::new(event_uid, calendar)
+                .update_event_indexes(Some(pruned_event.as_ref()), None)
+                .map_err(RedisError::String)?;
         }
     }
 
     Ok(pruned_events)
 }
 
-fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, from: &String, until: &String, event_uid: &String) -> Result<(), RedisError> {
+pub fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, from: &String, until: &String, event_uid: &String) -> Result<(), RedisError> {
     let event_message = format!("rdcl.evt_prune:{event_uid}:{from}-{until}");
 
     if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), calendar_uid) == Status::Err {
@@ -1,37 +1,46 @@
 use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue, ThreadSafeContext};
 
-use std::str::FromStr;
-
 use redical_ical::{ICalendarComponent, RenderingContext};
 use crate::core::queries::query::Query;
 use crate::core::queries::event_query::EventQuery;
-use crate::utils::{run_with_timeout, TimeoutError};
-use crate::CONFIGURATION_ICAL_PARSER_TIMEOUT_MS;
-use redical_core::Calendar;
-use crate::datatype::CALENDAR_DATA_TYPE;
-
-fn icalendar_component_to_redis_value_array<I: ICalendarComponent>(component: &I, rendering_context: &RenderingContext) -> RedisValue {
-    RedisValue::Array(
-        component
-            .to_rendered_content_lines_with_context(Some(rendering_context))
-            .iter()
-            .map(|ical_part| RedisValue::SimpleString(ical_part.to_owned()))
-            .collect()
-    )
-}
+use crate::utils::{join_content_line_args, run_with_timeout, TimeoutError};
+use crate::reply::{
+    PaginationHeader, QueryResultsHeader, ReplyFormat, content_line_set_to_redis_value, extract_trailing_flag_arg,
+    icalendar_component_to_redis_value,
+};
+use crate::{
+    CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, CONFIGURATION_MAX_QUERY_RESULTS, CONFIGURATION_PAGINATION_COUNT_MAX,
+    CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS,
+};
+use redical_core::{Calendar, ExecutionDeadline};
+use crate::datatype::{open_indexed_calendar_key, CALENDAR_DATA_TYPE};
 
-pub fn redical_event_query(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+pub fn redical_event_query(ctx: &Context, mut args: Vec<RedisString>) -> RedisResult {
     if args.len() < 2 {
         ctx.log_debug("rdcl.evt_query: event_set WrongArity: {{args.len()}}");
 
         return Err(RedisError::WrongArity);
     }
 
+    // Resolved before blocking the client as the protocol cannot be determined from the thread.
+    let reply_format = ReplyFormat::from_output_args(ctx, &mut args)?;
+
+    // Include the pagination metadata (total count, offset, limit) in the header preceding the results.
+    let with_pagination = extract_trailing_flag_arg(&mut args, "WITHPAGINATION");
+
+    // Precede the results with the header flagging whether they were truncated, partial, or the
+    // query has warnings (implied by WITHPAGINATION, which is included within it).
+    let with_header = extract_trailing_flag_arg(&mut args, "WITHHEADER") || with_pagination;
+
+    // Reject queries with degenerate conditions (e.g. `X-UID:A X-UID:B`) instead of replying with
+    // their results (and warning about them).
+    let strict = extract_trailing_flag_arg(&mut args, "STRICT");
+
     let mut args = args.into_iter().skip(1);
 
     let calendar_uid = args.next_arg()?;
 
-    let calendar_key = ctx.open_key(&calendar_uid);
+    let calendar_key = open_indexed_calendar_key(ctx, &calendar_uid)?;
 
     let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)?.cloned() else {
         return Err(RedisError::String(format!(
@@ -39,6 +48,8 @@ pub fn redical_event_query(ctx: &Context, args: Vec<RedisString>) -> RedisResult
         )));
     };
 
+    let default_tz = calendar.get_default_tz();
+
     if !calendar.indexes_active {
         return Err(RedisError::String(format!(
             "rdcl.evt_query: Queries disabled on Calendar: {calendar_uid} because it's indexes have been disabled."
@@ -47,14 +58,16 @@ pub fn redical_event_query(ctx: &Context, args: Vec<RedisString>) -> RedisResult
 
     ctx.log_debug(format!("rdcl.evt_query: calendar_uid: {calendar_uid}").as_str());
 
-    let query_string: String = args
-        .map(|arg| arg.try_as_str().unwrap_or(""))
-        .collect::<Vec<&str>>()
-        .join(" ")
-        .as_str()
-        .to_owned();
+    let query_string: String = join_content_line_args(args);
 
     let ical_parser_timeout_ms = *CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64;
+    let query_execution_timeout_ms = *CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64;
+
+    // Zero represents no max query results.
+    let max_query_results = Some(*CONFIGURATION_MAX_QUERY_RESULTS.lock(ctx) as usize).filter(|max_query_results| *max_query_results > 0);
+
+    // Zero represents no max pagination total count.
+    let pagination_count_max = Some(*CONFIGURATION_PAGINATION_COUNT_MAX.lock(ctx) as usize).filter(|pagination_count_max| *pagination_count_max > 0);
 
     let calendar_uid = calendar_uid.to_string();
 
@@ -67,7 +80,7 @@ pub fn redical_event_query(ctx: &Context, args: Vec<RedisString>) -> RedisResult
         // against malicious payloads intended to cause hangs.
         let mut parsed_query =
             match run_with_timeout(
-                move || EventQuery::from_str(query_string.as_str()).map_err(RedisError::String),
+                move || EventQuery::from_str_with_default_timezone(query_string.as_str(), default_tz.as_ref()).map_err(RedisError::String),
                 std::time::Duration::from_millis(ical_parser_timeout_ms),
             ) {
                 Ok(parser_result) => {
@@ -101,9 +114,71 @@ pub fn redical_event_query(ctx: &Context, args: Vec<RedisString>) -> RedisResult
             ).as_str(),
         );
 
-        let query_results = match parsed_query.execute(&calendar) {
+        if strict && !parsed_query.get_warnings().is_empty() {
+            thread_ctx.reply(Err(RedisError::String(format!(
+                "rdcl.evt_query: degenerate query: {}",
+                parsed_query.get_warnings().join("; "),
+            ))));
+
+            return;
+        }
+
+        // The offset and limit applied (with the limit capped to the max query results).
+        let applied_offset = parsed_query.get_offset();
+        let applied_limit = max_query_results.map_or(parsed_query.get_limit(), |max_query_results| parsed_query.get_limit().min(max_query_results));
+
+        if let Some(max_query_results) = max_query_results {
+            parsed_query.cap_limit(max_query_results);
+        }
+
+        // The query execution is cooperatively aborted once this deadline has been exceeded to
+        // guard against expensive queries hogging the thread indefinitely.
+        let execution_deadline = ExecutionDeadline::new(std::time::Duration::from_millis(query_execution_timeout_ms));
+
+        // Only the number of matching results is returned (bounded by the max pagination total
+        // count) without collecting or rendering them.
+        if parsed_query.count_only {
+            let count_reply =
+                match parsed_query.count_with_deadline(&calendar, pagination_count_max, &execution_deadline) {
+                    Ok(count) => {
+                        let count = pagination_count_max.map_or(count, |pagination_count_max| count.min(pagination_count_max));
+
+                        Ok(RedisValue::Integer(count as i64))
+                    },
+
+                    Err(_) if execution_deadline.is_exceeded() => {
+                        thread_ctx.lock().log_warning(
+                            format!(
+                                "rdcl.evt_query: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                            ).as_str()
+                        );
+
+                        Err(RedisError::String(String::from("rdcl.evt_query: query exceeded timeout")))
+                    },
+
+                    Err(error) => Err(RedisError::String(error)),
+                };
+
+            thread_ctx.reply(count_reply);
+
+            return;
+        }
+
+        let mut query_results = match parsed_query.execute_with_deadline(&calendar, &execution_deadline) {
             Ok(results) => results,
 
+            Err(_) if execution_deadline.is_exceeded() => {
+                thread_ctx.lock().log_warning(
+                    format!(
+                        "rdcl.evt_query: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                    ).as_str()
+                );
+
+                thread_ctx.reply(Err(RedisError::String(String::from("rdcl.evt_query: query exceeded timeout"))));
+
+                return;
+            },
+
             Err(error) => {
                 thread_ctx.reply(Err(RedisError::String(error)));
 
@@ -111,8 +186,53 @@ pub fn redical_event_query(ctx: &Context, args: Vec<RedisString>) -> RedisResult
             },
         };
 
+        if let Some(max_query_results) = max_query_results {
+            query_results.truncate_to_max_results(max_query_results);
+        }
+
+        // The total number of matching results is counted by executing the query again without the
+        // offset and limit (bounded by the max pagination total count).
+        let pagination_header =
+            if with_pagination {
+                let mut count_query = parsed_query.build_count_query(pagination_count_max);
+
+                match count_query.execute_with_deadline(&calendar, &execution_deadline) {
+                    Ok(count_query_results) => {
+                        Some(
+                            PaginationHeader::new(
+                                count_query_results.len(),
+                                pagination_count_max,
+                                applied_offset,
+                                applied_limit,
+                                calendar.generation,
+                            )
+                        )
+                    },
+
+                    Err(_) if execution_deadline.is_exceeded() => {
+                        thread_ctx.lock().log_warning(
+                            format!(
+                                "rdcl.evt_query: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                            ).as_str()
+                        );
+
+                        thread_ctx.reply(Err(RedisError::String(String::from("rdcl.evt_query: query exceeded timeout"))));
+
+                        return;
+                    },
+
+                    Err(error) => {
+                        thread_ctx.reply(Err(RedisError::String(error)));
+
+                        return;
+                    },
+                }
+            } else {
+                None
+            };
+
         // TODO: Clean up and properly serialize this griminess
-        let query_result_items = query_results
+        let mut query_result_items: Vec<RedisValue> = query_results
             .results
             .iter()
             .map(|query_result| {
@@ -122,12 +242,32 @@ pub fn redical_event_query(ctx: &Context, args: Vec<RedisString>) -> RedisResult
                 };
 
                 RedisValue::Array(vec![
-                    icalendar_component_to_redis_value_array(&query_result.result_ordering, &rendering_context),
-                    icalendar_component_to_redis_value_array(&query_result.result, &rendering_context),
+                    icalendar_component_to_redis_value(&query_result.result_ordering, Some(&rendering_context), reply_format),
+                    content_line_set_to_redis_value(
+                        parsed_query.project_content_lines(query_result.result.to_content_line_set_with_context(Some(&rendering_context))),
+                        reply_format,
+                    ),
                 ])
             })
             .collect();
 
+        // Flag that the results were cut short by the max query results config, are potentially
+        // partial due to the occurrence expansion limits, or warn about any degenerate conditions
+        // in the query (explaining why it matched nothing) within the header preceding them if
+        // requested, otherwise log them.
+        let query_results_header = QueryResultsHeader {
+            pagination: pagination_header,
+            is_truncated: query_results.is_truncated,
+            is_partial: false,
+            warnings: parsed_query.get_warnings().to_owned(),
+        };
+
+        if with_header {
+            query_results_header.prepend_to(&mut query_result_items);
+        } else {
+            query_results_header.log(&thread_ctx.lock(), "rdcl.evt_query", &calendar_uid);
+        }
+
         thread_ctx.reply(
             Ok(RedisValue::Array(query_result_items))
         );
@@ -0,0 +1,112 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::core::{Calendar, Event};
+use crate::datatype::CALENDAR_DATA_TYPE;
+
+use crate::reply::{ReplyFormat, icalendar_component_to_redis_value};
+
+// The cursor starting (and ending) a full scan, as with the Redis SCAN command.
+const INITIAL_CURSOR: &str = "0";
+
+// Cursors are the hex encoded UID of the last event returned, so that they are opaque to the
+// client and can never collide with the initial cursor (as each byte is two hex digits).
+fn encode_cursor(event_uid: &str) -> String {
+    event_uid
+        .as_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn decode_cursor(cursor: &str) -> Result<Option<String>, String> {
+    if cursor == INITIAL_CURSOR {
+        return Ok(None);
+    }
+
+    let invalid_cursor_error = || format!("rdcl.evt_scan: Invalid cursor: {cursor}");
+
+    if cursor.is_empty() || !cursor.len().is_multiple_of(2) {
+        return Err(invalid_cursor_error());
+    }
+
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|index| {
+            cursor
+                .get(index..index + 2)
+                .and_then(|hex_byte| u8::from_str_radix(hex_byte, 16).ok())
+                .ok_or_else(invalid_cursor_error)
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|_| invalid_cursor_error())
+}
+
+fn serialize_scanned_events(events: Vec<&Event>, next_event_uid: Option<String>, reply_format: ReplyFormat) -> RedisValue {
+    let next_cursor = next_event_uid.map_or(String::from(INITIAL_CURSOR), |event_uid| encode_cursor(&event_uid));
+
+    RedisValue::Array(vec![
+        RedisValue::BulkString(next_cursor),
+        RedisValue::Array(
+            events
+                .into_iter()
+                .map(|event| icalendar_component_to_redis_value(event, None, reply_format))
+                .collect()
+        ),
+    ])
+}
+
+pub fn redical_event_scan(ctx: &Context, mut args: Vec<RedisString>) -> RedisResult {
+    if args.len() < 3 {
+        ctx.log_debug(format!("rdcl.evt_scan: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let reply_format = ReplyFormat::from_output_args(ctx, &mut args)?;
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+
+    let after_event_uid = decode_cursor(args.next_arg()?.try_as_str()?).map_err(RedisError::String)?;
+
+    let mut count = 50;
+
+    while let Some(option) = args.next() {
+        match option.to_string().to_ascii_uppercase().as_str() {
+            "COUNT" => {
+                count = args.next_u64()? as usize;
+
+                if count == 0 {
+                    return Err(RedisError::String(String::from("rdcl.evt_scan: COUNT must be greater than 0")));
+                }
+            },
+
+            option => {
+                return Err(RedisError::String(format!(
+                    "rdcl.evt_scan: Unknown option: {option} (expected COUNT)"
+                )));
+            },
+        }
+    }
+
+    let calendar_key = ctx.open_key(&calendar_uid);
+
+    ctx.log_debug(
+        format!("rdcl.evt_scan: calendar_uid: {calendar_uid} after event uid: {after_event_uid:?} count: {count}").as_str()
+    );
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    let (events, next_event_uid) = calendar.scan_events(after_event_uid.as_ref(), count);
+
+    Ok(serialize_scanned_events(events, next_event_uid, reply_format))
+}
+
@@ -1,13 +1,20 @@
 use redis_module::{Context, NextArg, NotifyEvent, RedisError, RedisResult, RedisString, RedisValue, Status};
 
 use crate::core::{
-    Calendar, CalendarIndexUpdater, Event, InvertedEventIndex,
+    clock, Calendar, CalendarIndexUpdater, Event, get_changed_property_names,
 };
 
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
 use crate::datatype::CALENDAR_DATA_TYPE;
 
-use crate::utils::{run_with_timeout, TimeoutError};
-use crate::CONFIGURATION_ICAL_PARSER_TIMEOUT_MS;
+use crate::utils::{
+    check_max_events_per_calendar, get_max_events_per_calendar, join_content_line_args, parse_optional_now_args, run_with_timeout, validate_event_schema, ExistenceCondition, IfMatchCondition, LastModifiedConflictAction, LastModifiedConflictStrategy, SetReplyMode, TimeoutError,
+};
+use crate::{
+    CONFIGURATION_ICAL_PARSER_TIMEOUT_MS,
+    CONFIGURATION_NOTIFICATION_VERBOSITY, NotificationVerbosity,
+};
 
 use redical_ical::ICalendarComponent;
 
@@ -18,22 +25,23 @@ pub fn redical_event_set(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
         return Err(RedisError::WrongArity);
     }
 
-    let mut args = args.into_iter().skip(1);
+    let mut args = args.into_iter().skip(1).peekable();
 
     let calendar_uid = args.next_arg()?;
     let event_uid = args.next_arg()?.to_string();
 
+    let existence_condition = ExistenceCondition::parse_optional_args(&mut args);
+    let if_match_condition = IfMatchCondition::parse_optional_args(&mut args)?;
+    let now = parse_optional_now_args(&mut args)?;
+    let conflict_strategy = LastModifiedConflictStrategy::parse_optional_args(&mut args)?;
+    let reply_mode = SetReplyMode::parse_optional_args(&mut args);
+
     let calendar_key = ctx.open_key_writable(&calendar_uid);
 
-    let other = args
-        .map(|arg| arg.try_as_str().unwrap_or(""))
-        .collect::<Vec<&str>>()
-        .join(" ")
-        .as_str()
-        .to_owned();
+    let other = join_content_line_args(args);
 
     ctx.log_debug(
-        format!("rdcl.evt_set: key: {calendar_uid} event uid: {event_uid}, other: {other}")
+        format!("rdcl.evt_set: key: {calendar_uid} event uid: {event_uid}, existence condition: {existence_condition:?}, if match: {if_match_condition:?}, now: {now:?}, conflict strategy: {conflict_strategy:?}, reply mode: {reply_mode:?}, other: {other}")
             .as_str(),
     );
 
@@ -51,7 +59,7 @@ pub fn redical_event_set(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     // against malicious payloads intended to cause hangs.
     let mut event =
         match run_with_timeout(
-            move || Event::parse_ical(parsed_event_uid.as_str(), other.as_str()).map_err(RedisError::String),
+            move || clock::with_now(now, || Event::parse_ical(parsed_event_uid.as_str(), other.as_str())).map_err(RedisError::String),
             std::time::Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64),
         ) {
             Ok(parser_result) => {
@@ -73,28 +81,93 @@ pub fn redical_event_set(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
 
     event.validate().map_err(RedisError::String)?;
 
+    calendar.localize_event(&mut event).map_err(RedisError::String)?;
+
+    // Reject events not conforming to the Calendar schema (X-REQUIRED-PROPERTIES and
+    // X-ALLOWED-PROPERTIES) before they are indexed.
+    validate_event_schema(ctx, calendar, &event)
+        .map_err(|error| RedisError::String(format!("rdcl.evt_set: {error}")))?;
+
     let existing_event =
         calendar
             .events
             .get(&event_uid)
             .cloned();
 
+    // Like SET, a write that is only to create (NX) or only to update (XX) the event is skipped
+    // (returning nil) if the event respectively does or does not already exist.
+    if existence_condition.is_some_and(|existence_condition| !existence_condition.is_met(existing_event.is_some())) {
+        ctx.log_debug(
+            format!(
+                "rdcl.evt_set: key: {calendar_uid} event uid: {event_uid} - skipped due to unmet existence condition: {existence_condition:?}",
+            ).as_str()
+        );
+
+        return Ok(RedisValue::Null);
+    }
+
+    // Only apply a conditional write if the stored event is still the version the client last read
+    // (optimistic concurrency), otherwise reject it with a CONFLICT error for the client to re-read
+    // and retry.
+    if let Some(if_match_condition) = if_match_condition.as_ref() {
+        if_match_condition
+            .verify(
+                existing_event
+                    .as_ref()
+                    .map(|existing_event| (&existing_event.last_modified, &existing_event.passive_properties))
+            )
+            .map_err(|error| RedisError::String(format!("CONFLICT rdcl.evt_set: event uid: {event_uid} {error}")))?;
+    }
+
+    let mut conflict_action = LastModifiedConflictAction::Set;
+
     // Validate new event's LAST-MODIFIED property (if provided) is more recent than that on the
-    // existing event.
+    // existing event, resolving any conflict with the requested strategy (ignored by default).
     if let Some(existing_event) = existing_event.as_ref() {
         if event.last_modified < existing_event.last_modified {
-            ctx.log_debug(
-                format!(
-                    "rdcl.evt_set: key: {calendar_uid} event uid: {event_uid} - skipped due to existing superseding LAST-MODIFIED - existing: {} new: {}",
-                    existing_event.last_modified,
-                    event.last_modified,
-                ).as_str()
-            );
-
-            return Ok(RedisValue::Bool(false));
+            match conflict_strategy.unwrap_or_default() {
+                LastModifiedConflictStrategy::Ignore => {
+                    ctx.log_debug(
+                        format!(
+                            "rdcl.evt_set: key: {calendar_uid} event uid: {event_uid} - skipped due to existing superseding LAST-MODIFIED - existing: {} new: {}",
+                            existing_event.last_modified,
+                            event.last_modified,
+                        ).as_str()
+                    );
+
+                    return Ok(LastModifiedConflictAction::Ignored.build_reply(conflict_strategy, RedisValue::Bool(false)));
+                },
+
+                LastModifiedConflictStrategy::Error => {
+                    return Err(RedisError::String(format!(
+                        "rdcl.evt_set: event uid: {event_uid} {} is older than existing {}",
+                        event.last_modified,
+                        existing_event.last_modified,
+                    )));
+                },
+
+                LastModifiedConflictStrategy::Force => {
+                    ctx.log_debug(
+                        format!(
+                            "rdcl.evt_set: key: {calendar_uid} event uid: {event_uid} - forced despite existing superseding LAST-MODIFIED - existing: {} new: {}",
+                            existing_event.last_modified,
+                            event.last_modified,
+                        ).as_str()
+                    );
+
+                    conflict_action = LastModifiedConflictAction::Forced;
+                },
+            }
         }
     }
 
+    // Only adding a new event can exceed the max events per calendar limit, updating an existing
+    // event is always permitted.
+    if existing_event.is_none() {
+        check_max_events_per_calendar(calendar, 1, get_max_events_per_calendar(ctx))
+            .map_err(|error| RedisError::String(format!("rdcl.evt_set: {error}")))?;
+    }
+
     if let Some(existing_event) = existing_event.as_ref() {
         event.overrides = existing_event.overrides.clone();
     }
@@ -102,102 +175,122 @@ pub fn redical_event_set(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     if calendar.indexes_active {
         event.rebuild_indexes().map_err(RedisError::String)?;
 
-        let updated_event_categories_diff = InvertedEventIndex::diff_indexed_terms(
-            existing_event
-                .as_ref()
-                .and_then(|existing_event| existing_event.indexed_categories.clone())
-                .as_ref(),
-            event.indexed_categories.as_ref(),
-        );
+        update_calendar_indexes(calendar, &event_uid, existing_event.as_deref(), &event)?;
+    }
 
-        let updated_event_location_type_diff = InvertedEventIndex::diff_indexed_terms(
-            existing_event
-                .as_ref()
-                .and_then(|existing_event| existing_event.indexed_location_type.clone())
-                .as_ref(),
-            event.indexed_location_type.as_ref(),
-        );
+    let serialized_event_ical = event.to_rendered_content_lines();
 
-        let updated_event_related_to_diff = InvertedEventIndex::diff_indexed_terms(
-            existing_event
-                .as_ref()
-                .and_then(|existing_event| existing_event.indexed_related_to.clone())
-                .as_ref(),
-            event.indexed_related_to.as_ref(),
-        );
+    let is_changed =
+        existing_event
+            .as_ref()
+            .is_none_or(|existing_event| existing_event.to_rendered_content_lines() != serialized_event_ical);
 
-        let updated_event_geo_diff = InvertedEventIndex::diff_indexed_terms(
-            existing_event
-                .as_ref()
-                .and_then(|existing_event| existing_event.indexed_geo.clone())
-                .as_ref(),
-            event.indexed_geo.as_ref(),
-        );
+    ctx.log_debug(
+        format!(
+            "rdcl.evt_set: key: {calendar_uid} event uid: {event_uid} - count: {}",
+            calendar.events.len(),
+        ).as_str()
+    );
 
-        let updated_event_class_diff = InvertedEventIndex::diff_indexed_terms(
-            existing_event
-                .as_ref()
-                .and_then(|existing_event| existing_event.indexed_class.clone())
-                .as_ref(),
-            event.indexed_class.as_ref(),
+    let event_message =
+        build_keyspace_event_message(
+            CONFIGURATION_NOTIFICATION_VERBOSITY.lock(ctx).clone(),
+            &event_uid,
+            existing_event.as_deref(),
+            &event,
         );
 
-        let mut calendar_index_updater =
-            CalendarIndexUpdater::new(&event_uid, calendar);
+    let change = Change::new(ChangeType::EventSet, &event_uid).with_last_modified(&event.last_modified);
 
-        calendar_index_updater
-            .update_indexed_categories(&updated_event_categories_diff)
-            .map_err(RedisError::String)?;
+    calendar.insert_event(event);
 
-        calendar_index_updater
-            .update_indexed_location_type(&updated_event_location_type_diff)
-            .map_err(RedisError::String)?;
+    // Replicate the event as stored (rather than verbatim) so that any values resolved by this
+    // instance (e.g. defaulting LAST-MODIFIED to now) are identical across replicas and the AOF.
+    //
+    // Forced events are replicated as such so that replicas do not ignore them, and any provided
+    // NOW is replicated so that replicas resolve defaulted values identically.
+    let calendar_uid_string = calendar_uid.to_string();
+    let serialized_event_ical_string = serialized_event_ical.join(" ");
 
-        calendar_index_updater
-            .update_indexed_related_to(&updated_event_related_to_diff)
-            .map_err(RedisError::String)?;
+    let mut replicated_args = vec![calendar_uid_string.as_str(), event_uid.as_str()];
 
-        calendar_index_updater
-            .update_indexed_geo(&updated_event_geo_diff)
-            .map_err(RedisError::String)?;
+    let now_string = now.map(|now| now.format("%Y%m%dT%H%M%SZ").to_string());
 
-        calendar_index_updater
-            .update_indexed_class(&updated_event_class_diff)
-            .map_err(RedisError::String)?;
+    if let Some(now_string) = now_string.as_ref() {
+        replicated_args.extend(["NOW", now_string.as_str()]);
     }
 
-    let serialized_event_ical = event.to_rendered_content_lines();
+    if conflict_action == LastModifiedConflictAction::Forced {
+        replicated_args.extend(["ON-CONFLICT", "FORCE"]);
+    }
 
-    ctx.log_debug(
-        format!(
-            "rdcl.evt_set: key: {calendar_uid} event uid: {event_uid} - count: {}",
-            calendar.events.len(),
-        ).as_str()
-    );
+    replicated_args.push(serialized_event_ical_string.as_str());
 
-    let last_modified_ical_property = event.last_modified.to_string();
+    ctx.replicate("rdcl.evt_set", replicated_args.as_slice());
 
-    calendar.insert_event(event);
+    notify_keyspace_event(ctx, &calendar_uid, &event_uid, &event_message)?;
 
-    // Use this command when replicating across other Redis instances.
-    ctx.replicate_verbatim();
+    append_changes(ctx, &calendar_uid, &[change]);
 
-    notify_keyspace_event(ctx, &calendar_uid, &event_uid, &last_modified_ical_property)?;
+    calendar.bump_generation();
+
+    notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
 
     Ok(
-        RedisValue::Array(
-            serialized_event_ical
-                .into_iter()
-                .map(RedisValue::SimpleString)
-                .collect(),
+        conflict_action.build_reply(
+            conflict_strategy,
+            reply_mode.build_reply(
+                is_changed,
+                RedisValue::Array(
+                    serialized_event_ical
+                        .into_iter()
+                        .map(RedisValue::SimpleString)
+                        .collect(),
+                ),
+            ),
         )
     )
 }
 
-fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String, last_modified_ical_property: &String) -> Result<(), RedisError> {
-    let event_message = format!("rdcl.evt_set:{event_uid} {last_modified_ical_property}");
+// Diff the indexed terms of the existing event (if any) against those of the new event and apply
+// the changes to the Calendar indexes.
+pub(crate) fn update_calendar_indexes(calendar: &mut Calendar, event_uid: &String, existing_event: Option<&Event>, event: &Event) -> Result<(), RedisError> {
+    CalendarIndexUpdater::new(event_uid, calendar)
+        .update_event_indexes(existing_event, Some(event))
+        .map_err(RedisError::String)?;
+
+    Ok(())
+}
+
+// Build the keyspace event message detailed according to the notification-verbosity config.
+pub(crate) fn build_keyspace_event_message(notification_verbosity: NotificationVerbosity, event_uid: &String, existing_event: Option<&Event>, event: &Event) -> String {
+    match notification_verbosity {
+        NotificationVerbosity::minimal => {
+            format!("rdcl.evt_set:{event_uid}")
+        },
+
+        NotificationVerbosity::standard => {
+            format!("rdcl.evt_set:{event_uid} {}", event.last_modified)
+        },
+
+        NotificationVerbosity::detailed => {
+            let changed_property_names = get_changed_property_names(existing_event, event);
+
+            if changed_property_names.is_empty() {
+                return format!("rdcl.evt_set:{event_uid} {}", event.last_modified);
+            }
+
+            format!(
+                "rdcl.evt_set:{event_uid} {} {}",
+                event.last_modified,
+                Vec::from_iter(changed_property_names).join(","),
+            )
+        },
+    }
+}
 
-    if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), calendar_uid) == Status::Err {
+pub(crate) fn notify_keyspace_event(ctx: &Context, calendar_uid: &RedisString, event_uid: &String, event_message: &str) -> Result<(), RedisError> {
+    if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message, calendar_uid) == Status::Err {
         return Err(
             RedisError::String(
                 format!("Notify keyspace event \"rdcl.evt_set\" for calendar: \"{}\" event: \"{}\"", &calendar_uid, &event_uid)
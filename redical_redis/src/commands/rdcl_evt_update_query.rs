@@ -0,0 +1,232 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use std::str::FromStr;
+
+use crate::core::queries::query::Query;
+use crate::core::queries::event_query::EventQuery;
+use crate::core::queries::results::QueryableEntity;
+use crate::core::{Calendar, Event, ExecutionDeadline};
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
+use crate::datatype::{open_indexed_calendar_key_writable, CALENDAR_DATA_TYPE};
+use crate::utils::{run_with_timeout, TimeoutError};
+use crate::{
+    CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, CONFIGURATION_NOTIFICATION_VERBOSITY,
+    CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS,
+};
+
+use super::rdcl_evt_set::{build_keyspace_event_message, notify_keyspace_event, update_calendar_indexes};
+
+use redical_ical::ICalendarComponent;
+use redical_ical::content_line::join_line_separated_content_lines;
+use redical_ical::properties::{EventProperties, EventProperty};
+
+pub fn redical_event_update_query(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    // Expect the key, at least one query property, SET, and at least one iCal property.
+    if args.len() < 5 {
+        ctx.log_debug(format!("rdcl.evt_update_query: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+
+    let args: Vec<String> = args.map(|arg| join_line_separated_content_lines(arg.to_string().as_str())).collect();
+
+    let Some(set_position) = args.iter().position(|arg| arg.eq_ignore_ascii_case("SET")) else {
+        return Err(RedisError::String(String::from(
+            "rdcl.evt_update_query: expected SET followed by the iCal properties to update"
+        )));
+    };
+
+    let (query_args, patch_args) = args.split_at(set_position);
+
+    let query_string = query_args.join(" ");
+    let patch_ical = patch_args[1..].join(" ");
+
+    if query_string.is_empty() || patch_ical.is_empty() {
+        return Err(RedisError::WrongArity);
+    }
+
+    let calendar_key = open_indexed_calendar_key_writable(ctx, &calendar_uid)?;
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "rdcl.evt_update_query: No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    if !calendar.indexes_active {
+        return Err(RedisError::String(format!(
+            "rdcl.evt_update_query: Queries disabled on Calendar: {calendar_uid} because it's indexes have been disabled."
+        )));
+    }
+
+    ctx.log_debug(
+        format!("rdcl.evt_update_query: calendar_uid: {calendar_uid} query: {query_string} patch: {patch_ical}").as_str()
+    );
+
+    let default_tz = calendar.get_default_tz();
+
+    // Spawn the process of parsing the query and patch into it's own timeout enforced thread to
+    // guard against malicious payloads intended to cause hangs.
+    let (mut parsed_query, patch_properties) =
+        match run_with_timeout(
+            move || -> Result<(EventQuery, Vec<EventProperty>), RedisError> {
+                let parsed_query =
+                    EventQuery::from_str_with_default_timezone(query_string.as_str(), default_tz.as_ref())
+                        .map_err(RedisError::String)?;
+
+                let EventProperties(patch_properties) =
+                    EventProperties::from_str(patch_ical.as_str()).map_err(RedisError::String)?;
+
+                Ok((parsed_query, patch_properties))
+            },
+            std::time::Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64),
+        ) {
+            Ok(parser_result) => {
+                parser_result?
+            },
+
+            Err(TimeoutError) => {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.evt_update_query: query iCal parser exceeded timeout -- calendar_uid: {calendar_uid}",
+                    ).as_str()
+                );
+
+                return Err(RedisError::String(String::from(
+                    "rdcl.evt_update_query: query iCal parser exceeded timeout"
+                )));
+            },
+        };
+
+    if patch_properties.iter().any(|property| matches!(property, EventProperty::UID(_))) {
+        return Err(RedisError::String(String::from(
+            "rdcl.evt_update_query: the UID of matching events cannot be updated"
+        )));
+    }
+
+    // Every matching event is updated, so any ordering, offset, or limit is disregarded.
+    parsed_query.offset = 0;
+    parsed_query.limit = calendar.events.len();
+
+    let execution_deadline = ExecutionDeadline::new(
+        std::time::Duration::from_millis(*CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64)
+    );
+
+    let query_results = match parsed_query.execute_with_deadline(calendar, &execution_deadline) {
+        Ok(query_results) => query_results,
+
+        Err(_) if execution_deadline.is_exceeded() => {
+            ctx.log_warning(
+                format!(
+                    "rdcl.evt_update_query: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                ).as_str()
+            );
+
+            return Err(RedisError::String(String::from("rdcl.evt_update_query: query exceeded timeout")));
+        },
+
+        Err(error) => {
+            return Err(RedisError::String(error));
+        },
+    };
+
+    // Patch every matching event before updating any of them, so that an invalid patch (e.g.
+    // resulting in an invalid schedule) leaves the Calendar untouched.
+    let mut patched_events: Vec<(Box<Event>, Event)> = Vec::with_capacity(query_results.len());
+
+    for query_result in query_results.results.iter() {
+        let event_uid = query_result.result.get_uid();
+
+        let Some(existing_event) = calendar.events.get(&event_uid).cloned() else {
+            continue;
+        };
+
+        // The patch LAST-MODIFIED defaults to now, bumping that of every patched event.
+        let mut patch = Event::new(event_uid.to_owned());
+
+        for patch_property in patch_properties.iter().cloned() {
+            patch.insert(patch_property).map_err(RedisError::String)?;
+        }
+
+        let mut patched_event =
+            existing_event
+                .patch(&patch)
+                .map_err(|error| RedisError::String(format!("rdcl.evt_update_query: event uid: {event_uid} - {error}")))?;
+
+        calendar.localize_event(&mut patched_event).map_err(RedisError::String)?;
+
+        calendar
+            .validate_event_schema(&patched_event)
+            .map_err(|error| RedisError::String(format!("rdcl.evt_update_query: event uid: {event_uid} - {error}")))?;
+
+        patched_event.rebuild_indexes().map_err(RedisError::String)?;
+
+        patched_events.push((existing_event, patched_event));
+    }
+
+    if patched_events.is_empty() {
+        return Ok(RedisValue::Integer(0));
+    }
+
+    let notification_verbosity = CONFIGURATION_NOTIFICATION_VERBOSITY.lock(ctx).clone();
+
+    let mut changes: Vec<Change> = Vec::with_capacity(patched_events.len());
+    let mut event_messages: Vec<(String, String)> = Vec::with_capacity(patched_events.len());
+
+    // The updated events (as stored) to replicate.
+    let mut replicated_events: Vec<(String, String)> = Vec::with_capacity(patched_events.len());
+
+    for (existing_event, patched_event) in patched_events {
+        let event_uid = patched_event.uid.uid.to_string();
+
+        update_calendar_indexes(calendar, &event_uid, Some(existing_event.as_ref()), &patched_event)?;
+
+        replicated_events.push((event_uid.to_owned(), patched_event.to_rendered_content_lines().join(" ")));
+
+        event_messages.push((
+            event_uid.to_owned(),
+            build_keyspace_event_message(notification_verbosity.clone(), &event_uid, Some(existing_event.as_ref()), &patched_event),
+        ));
+
+        changes.push(Change::new(ChangeType::EventSet, &event_uid).with_last_modified(&patched_event.last_modified));
+
+        calendar.insert_event(patched_event);
+    }
+
+    // Replicate the updated events as stored (rather than verbatim) so that the resolved
+    // LAST-MODIFIED values and matched events are identical across replicas and the AOF.
+    //
+    // Each is forced, as the patch may have set an older LAST-MODIFIED which replicas would
+    // otherwise ignore.
+    let calendar_uid_string = calendar_uid.to_string();
+
+    for (event_uid, serialized_event_ical_string) in replicated_events.iter() {
+        ctx.replicate(
+            "rdcl.evt_set",
+            &[
+                calendar_uid_string.as_str(),
+                event_uid.as_str(),
+                "ON-CONFLICT",
+                "FORCE",
+                serialized_event_ical_string.as_str(),
+            ],
+        );
+    }
+
+    for (event_uid, event_message) in event_messages.iter() {
+        notify_keyspace_event(ctx, &calendar_uid, event_uid, event_message)?;
+    }
+
+    append_changes(ctx, &calendar_uid, &changes);
+
+    calendar.bump_generation();
+
+    notify_dirty_query_subscriptions(ctx, &calendar_uid, calendar);
+
+    Ok(RedisValue::Integer(changes.len() as i64))
+}
@@ -0,0 +1,76 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::command_metrics::{get_command_metrics, reset_command_metrics, CommandMetrics, LATENCY_BUCKET_UPPER_BOUNDS_US};
+
+fn serialize_latency_histogram(command_metrics: &CommandMetrics) -> RedisValue {
+    let bucket_upper_bounds =
+        LATENCY_BUCKET_UPPER_BOUNDS_US
+            .iter()
+            .map(|upper_bound_us| RedisValue::Integer(*upper_bound_us as i64))
+            .chain(std::iter::once(RedisValue::SimpleStringStatic("+inf")));
+
+    RedisValue::Array(
+        bucket_upper_bounds
+            .zip(command_metrics.latency_buckets.iter())
+            .flat_map(|(upper_bound, bucket_calls)| [upper_bound, RedisValue::Integer(*bucket_calls as i64)])
+            .collect()
+    )
+}
+
+fn serialize_command_metrics(command_metrics: &CommandMetrics) -> RedisValue {
+    let key_value_pairs = [
+        ("calls", RedisValue::Integer(command_metrics.calls as i64)),
+        ("failed_calls", RedisValue::Integer(command_metrics.failed_calls as i64)),
+        ("usec", RedisValue::Integer(command_metrics.total_duration_us as i64)),
+        ("usec_per_call", RedisValue::Integer(command_metrics.average_duration_us() as i64)),
+        ("p50_usec", RedisValue::Integer(command_metrics.percentile_duration_us(0.5) as i64)),
+        ("p99_usec", RedisValue::Integer(command_metrics.percentile_duration_us(0.99) as i64)),
+        ("max_usec", RedisValue::Integer(command_metrics.max_duration_us as i64)),
+        ("latency_histogram", serialize_latency_histogram(command_metrics)),
+    ];
+
+    RedisValue::Array(
+        key_value_pairs
+            .into_iter()
+            .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+            .collect()
+    )
+}
+
+pub fn redical_metrics(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() > 2 {
+        ctx.log_debug(format!("rdcl.metrics: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    if let Ok(subcommand) = args.next_arg() {
+        if !subcommand.to_string().eq_ignore_ascii_case("RESET") {
+            return Err(RedisError::String(format!("Unknown subcommand: {subcommand} (expected RESET)")));
+        }
+
+        ctx.log_debug("rdcl.metrics: reset");
+
+        reset_command_metrics();
+
+        return Ok(RedisValue::SimpleStringStatic("OK"));
+    }
+
+    ctx.log_debug("rdcl.metrics");
+
+    Ok(
+        RedisValue::Array(
+            get_command_metrics()
+                .iter()
+                .flat_map(|(command_name, command_metrics)| {
+                    [
+                        RedisValue::SimpleString(command_name.to_string()),
+                        serialize_command_metrics(command_metrics),
+                    ]
+                })
+                .collect()
+        )
+    )
+}
@@ -0,0 +1,173 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::core::queries::query::Query;
+use crate::core::queries::event_instance_query::EventInstanceQuery;
+use crate::core::queries::event_query::EventQuery;
+use crate::core::queries::query_explain::{QueryExplanation, WhereConditionalExplanation};
+use crate::utils::{join_content_line_args, run_with_timeout, TimeoutError};
+use crate::{CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS};
+use redical_core::{Calendar, ExecutionDeadline};
+use crate::datatype::{open_indexed_calendar_key, CALENDAR_DATA_TYPE};
+
+fn serialize_where_conditional_explanation(explanation: &WhereConditionalExplanation) -> RedisValue {
+    let key_value_pairs = [
+        ("node", RedisValue::BulkString(explanation.node.to_owned())),
+        ("index", explanation.index.to_owned().map_or(RedisValue::Null, RedisValue::SimpleString)),
+        ("candidate_count", RedisValue::Integer(explanation.candidate_count as i64)),
+        (
+            "children",
+            RedisValue::Array(
+                explanation.children
+                    .iter()
+                    .map(serialize_where_conditional_explanation)
+                    .collect()
+            ),
+        ),
+    ];
+
+    RedisValue::Array(
+        key_value_pairs
+            .into_iter()
+            .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+            .collect()
+    )
+}
+
+fn serialize_query_explanation(query_type: &str, explanation: &QueryExplanation) -> RedisValue {
+    let key_value_pairs = [
+        ("query_type", RedisValue::SimpleString(query_type.to_owned())),
+        (
+            "where",
+            explanation.where_conditional
+                .as_ref()
+                .map_or(RedisValue::Null, serialize_where_conditional_explanation),
+        ),
+        ("event_count", RedisValue::Integer(explanation.event_count as i64)),
+        ("candidate_count", RedisValue::Integer(explanation.candidate_count as i64)),
+        ("time_bucketed", RedisValue::Bool(explanation.time_bucketed)),
+        ("ordering", RedisValue::SimpleString(explanation.ordering.to_owned())),
+        ("offset_strategy", RedisValue::SimpleString(explanation.offset_strategy.to_string())),
+        ("offset", RedisValue::Integer(explanation.offset as i64)),
+        ("limit", RedisValue::Integer(explanation.limit as i64)),
+        ("distinct_uids", RedisValue::Bool(explanation.distinct_uids)),
+    ];
+
+    RedisValue::Array(
+        key_value_pairs
+            .into_iter()
+            .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+            .collect()
+    )
+}
+
+pub fn redical_query_explain(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() < 3 {
+        ctx.log_debug(format!("rdcl.query_explain: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+
+    let query_type = args.next_arg()?.to_string().to_ascii_uppercase();
+
+    if !matches!(query_type.as_str(), "EVI" | "EVT") {
+        return Err(RedisError::String(format!(
+            "rdcl.query_explain: Unknown query type: {query_type} (expected EVI or EVT)"
+        )));
+    }
+
+    let calendar_key = open_indexed_calendar_key(ctx, &calendar_uid)?;
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "rdcl.query_explain: No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    let default_tz = calendar.get_default_tz();
+
+    if !calendar.indexes_active {
+        return Err(RedisError::String(format!(
+            "rdcl.query_explain: Queries disabled on Calendar: {calendar_uid} because it's indexes have been disabled."
+        )));
+    }
+
+    ctx.log_debug(format!("rdcl.query_explain: calendar_uid: {calendar_uid} query type: {query_type}").as_str());
+
+    let query_string: String = join_content_line_args(args);
+
+    let ical_parser_timeout = std::time::Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64);
+    let query_execution_timeout = std::time::Duration::from_millis(*CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64);
+
+    let parser_result =
+        if query_type == "EVI" {
+            run_with_timeout(
+                move || {
+                    EventInstanceQuery::from_str_with_default_timezone(query_string.as_str(), default_tz.as_ref())
+                        .map(ParsedQuery::EventInstance)
+                        .map_err(RedisError::String)
+                },
+                ical_parser_timeout,
+            )
+        } else {
+            run_with_timeout(
+                move || {
+                    EventQuery::from_str_with_default_timezone(query_string.as_str(), default_tz.as_ref())
+                        .map(ParsedQuery::Event)
+                        .map_err(RedisError::String)
+                },
+                ical_parser_timeout,
+            )
+        };
+
+    let parsed_query =
+        match parser_result {
+            Ok(parser_result) => {
+                parser_result?
+            },
+
+            Err(TimeoutError) => {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.query_explain: query iCal parser exceeded timeout -- calendar_uid: {calendar_uid}",
+                    ).as_str()
+                );
+
+                return Err(RedisError::String(String::from("rdcl.query_explain: query iCal parser exceeded timeout")));
+            },
+        };
+
+    // Explaining a query resolves the where conditional against the indexes (without expanding
+    // any events), so the query execution timeout still applies.
+    let execution_deadline = ExecutionDeadline::new(query_execution_timeout);
+
+    let explanation =
+        match &parsed_query {
+            ParsedQuery::EventInstance(query) => query.explain(calendar, &execution_deadline),
+            ParsedQuery::Event(query) => query.explain(calendar, &execution_deadline),
+        };
+
+    match explanation {
+        Ok(explanation) => Ok(serialize_query_explanation(query_type.as_str(), &explanation)),
+
+        Err(_) if execution_deadline.is_exceeded() => {
+            ctx.log_warning(
+                format!(
+                    "rdcl.query_explain: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                ).as_str()
+            );
+
+            Err(RedisError::String(String::from("rdcl.query_explain: query exceeded timeout")))
+        },
+
+        Err(error) => Err(RedisError::String(error)),
+    }
+}
+
+enum ParsedQuery {
+    EventInstance(EventInstanceQuery),
+    Event(EventQuery),
+}
@@ -0,0 +1,239 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use std::time::{Duration, Instant};
+
+use redical_ical::RenderingContext;
+use crate::core::queries::query::Query;
+use crate::core::queries::event_instance_query::EventInstanceQuery;
+use crate::core::queries::query_profile::{QueryProfile, QueryProfiler};
+use crate::utils::{join_content_line_args, run_with_timeout, TimeoutError};
+use crate::reply::{ReplyFormat, icalendar_component_to_redis_value};
+use crate::{
+    CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, CONFIGURATION_MAX_QUERY_RESULTS,
+    CONFIGURATION_OCCURRENCE_EXPANSION_HORIZON_DAYS, CONFIGURATION_OCCURRENCE_EXPANSION_MAX,
+    CONFIGURATION_OCCURRENCE_EXPANSION_SAFETY_HORIZON_YEARS,
+    CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS,
+};
+use redical_core::{Calendar, ExecutionDeadline, OccurrenceExpansionLimits};
+use crate::datatype::{open_indexed_calendar_key, CALENDAR_DATA_TYPE};
+
+fn duration_to_redis_value(duration: Duration) -> RedisValue {
+    RedisValue::Integer(duration.as_micros() as i64)
+}
+
+fn serialize_event_expansions(query_profile: &QueryProfile) -> RedisValue {
+    let mut event_expansions: Vec<_> = query_profile.event_expansions.iter().collect();
+
+    // The most expensive expansions first, as those are the ones worth investigating.
+    event_expansions.sort_by(|(event_uid_a, event_expansion_a), (event_uid_b, event_expansion_b)| {
+        event_expansion_b.duration
+            .cmp(&event_expansion_a.duration)
+            .then_with(|| event_uid_a.cmp(event_uid_b))
+    });
+
+    RedisValue::Array(
+        event_expansions
+            .into_iter()
+            .map(|(event_uid, event_expansion)| {
+                let key_value_pairs = [
+                    ("event_uid", RedisValue::BulkString(event_uid.to_owned())),
+                    ("expanded_count", RedisValue::Integer(event_expansion.expanded_count as i64)),
+                    ("instance_count", RedisValue::Integer(event_expansion.instance_count as i64)),
+                    ("expansion_us", duration_to_redis_value(event_expansion.duration)),
+                ];
+
+                RedisValue::Array(
+                    key_value_pairs
+                        .into_iter()
+                        .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+                        .collect()
+                )
+            })
+            .collect()
+    )
+}
+
+fn serialize_query_profile(
+    query_profile: &QueryProfile,
+    parse_duration: Duration,
+    rendering_duration: Duration,
+    result_count: usize,
+    is_partial: bool,
+) -> RedisValue {
+    let total_duration = parse_duration + query_profile.execution_duration + rendering_duration;
+
+    let key_value_pairs = [
+        ("result_count", RedisValue::Integer(result_count as i64)),
+        ("partial", RedisValue::Bool(is_partial)),
+        ("total_us", duration_to_redis_value(total_duration)),
+        ("parse_us", duration_to_redis_value(parse_duration)),
+        ("index_filtering_us", duration_to_redis_value(query_profile.index_filtering_duration)),
+        ("expansion_us", duration_to_redis_value(query_profile.expansion_duration)),
+        ("override_application_us", duration_to_redis_value(query_profile.override_application_duration)),
+        ("ordering_us", duration_to_redis_value(query_profile.get_ordering_duration())),
+        ("rendering_us", duration_to_redis_value(rendering_duration)),
+        ("event_expansions", serialize_event_expansions(query_profile)),
+    ];
+
+    RedisValue::Array(
+        key_value_pairs
+            .into_iter()
+            .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+            .collect()
+    )
+}
+
+pub fn redical_query_profile(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() < 3 {
+        ctx.log_debug(format!("rdcl.query_profile: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    // The results are rendered (as they would be replied) to profile the rendering, but discarded.
+    let reply_format = ReplyFormat::from_context(ctx);
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+
+    let calendar_key = open_indexed_calendar_key(ctx, &calendar_uid)?;
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "rdcl.query_profile: No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    let default_tz = calendar.get_default_tz();
+
+    if !calendar.indexes_active {
+        return Err(RedisError::String(format!(
+            "rdcl.query_profile: Queries disabled on Calendar: {calendar_uid} because it's indexes have been disabled."
+        )));
+    }
+
+    ctx.log_debug(format!("rdcl.query_profile: calendar_uid: {calendar_uid}").as_str());
+
+    let query_string: String = join_content_line_args(args);
+
+    // Zero represents no max query results.
+    let max_query_results = Some(*CONFIGURATION_MAX_QUERY_RESULTS.lock(ctx) as usize).filter(|max_query_results| *max_query_results > 0);
+
+    // Zero represents no limit on the expansion of event occurrences.
+    let occurrence_expansion_max = Some(*CONFIGURATION_OCCURRENCE_EXPANSION_MAX.lock(ctx) as usize).filter(|occurrence_expansion_max| *occurrence_expansion_max > 0);
+
+    let occurrence_expansion_horizon_timestamp =
+        Some(*CONFIGURATION_OCCURRENCE_EXPANSION_HORIZON_DAYS.lock(ctx))
+            .filter(|occurrence_expansion_horizon_days| *occurrence_expansion_horizon_days > 0)
+            .map(|occurrence_expansion_horizon_days| {
+                chrono::Utc::now().timestamp().saturating_add(occurrence_expansion_horizon_days.saturating_mul(86400))
+            });
+
+    // Zero represents no safety horizon on the expansion of event occurrences.
+    let occurrence_expansion_safety_horizon_years =
+        Some(*CONFIGURATION_OCCURRENCE_EXPANSION_SAFETY_HORIZON_YEARS.lock(ctx))
+            .filter(|occurrence_expansion_safety_horizon_years| *occurrence_expansion_safety_horizon_years > 0);
+
+    let occurrence_expansion_limits =
+        OccurrenceExpansionLimits::new(occurrence_expansion_max, occurrence_expansion_horizon_timestamp)
+            .with_safety_horizon_years(occurrence_expansion_safety_horizon_years);
+
+    let ical_parser_timeout = Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64);
+    let query_execution_timeout = Duration::from_millis(*CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64);
+
+    let parse_started_at = Instant::now();
+
+    // Spawn the process of parsing the query into it's own timeout enforced thread to guard
+    // against malicious payloads intended to cause hangs.
+    let mut parsed_query =
+        match run_with_timeout(
+            move || EventInstanceQuery::from_str_with_default_timezone(query_string.as_str(), default_tz.as_ref()).map_err(RedisError::String),
+            ical_parser_timeout,
+        ) {
+            Ok(parser_result) => {
+                parser_result?
+            },
+
+            Err(TimeoutError) => {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.query_profile: query iCal parser exceeded timeout -- calendar_uid: {calendar_uid}",
+                    ).as_str()
+                );
+
+                return Err(RedisError::String(String::from("rdcl.query_profile: query iCal parser exceeded timeout")));
+            },
+        };
+
+    let parse_duration = parse_started_at.elapsed();
+
+    if let Some(max_query_results) = max_query_results {
+        parsed_query.cap_limit(max_query_results);
+    }
+
+    // The query is executed exactly as it would be by rdcl.evi_query, so the query execution
+    // timeout still applies.
+    let execution_deadline = ExecutionDeadline::new(query_execution_timeout);
+
+    let query_profiler = QueryProfiler::new();
+
+    let query_results = parsed_query.execute_with_query_profiler(
+        calendar,
+        &execution_deadline,
+        &occurrence_expansion_limits,
+        &query_profiler,
+    );
+
+    let mut query_results = match query_results {
+        Ok(query_results) => query_results,
+
+        Err(_) if execution_deadline.is_exceeded() => {
+            ctx.log_warning(
+                format!(
+                    "rdcl.query_profile: query exceeded timeout -- calendar_uid: {calendar_uid}",
+                ).as_str()
+            );
+
+            return Err(RedisError::String(String::from("rdcl.query_profile: query exceeded timeout")));
+        },
+
+        Err(error) => {
+            return Err(RedisError::String(error));
+        },
+    };
+
+    if let Some(max_query_results) = max_query_results {
+        query_results.truncate_to_max_results(max_query_results);
+    }
+
+    let rendering_started_at = Instant::now();
+
+    let rendering_context = RenderingContext {
+        tz: Some(parsed_query.in_timezone.to_owned()),
+        distance_unit: None,
+    };
+
+    let rendered_results: Vec<RedisValue> = query_results
+        .results
+        .iter()
+        .map(|query_result| {
+            RedisValue::Array(vec![
+                icalendar_component_to_redis_value(&query_result.result_ordering, Some(&rendering_context), reply_format),
+                icalendar_component_to_redis_value(&query_result.result, Some(&rendering_context), reply_format),
+            ])
+        })
+        .collect();
+
+    let rendering_duration = rendering_started_at.elapsed();
+
+    Ok(
+        serialize_query_profile(
+            &query_profiler.get_profile(),
+            parse_duration,
+            rendering_duration,
+            rendered_results.len(),
+            occurrence_expansion_limits.is_reached(),
+        )
+    )
+}
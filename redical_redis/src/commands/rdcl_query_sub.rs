@@ -0,0 +1,75 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::core::queries::query::Query;
+use crate::core::queries::event_instance_query::EventInstanceQuery;
+use crate::query_subscriptions::{subscribe, QuerySubscription};
+use crate::utils::{get_selected_db, join_content_line_args, run_with_timeout, TimeoutError};
+use crate::{CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS};
+use redical_core::{Calendar, ExecutionDeadline};
+use crate::datatype::{open_indexed_calendar_key, CALENDAR_DATA_TYPE};
+
+pub fn redical_query_sub(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() < 3 {
+        ctx.log_debug(format!("rdcl.query_sub: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?;
+
+    let name = args.next_arg()?.to_string();
+
+    let calendar_key = open_indexed_calendar_key(ctx, &calendar_uid)?;
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Err(RedisError::String(format!(
+            "rdcl.query_sub: No Calendar found on key: {calendar_uid}"
+        )));
+    };
+
+    let default_tz = calendar.get_default_tz();
+
+    if !calendar.indexes_active {
+        return Err(RedisError::String(format!(
+            "rdcl.query_sub: Queries disabled on Calendar: {calendar_uid} because it's indexes have been disabled."
+        )));
+    }
+
+    let query_string: String = join_content_line_args(args);
+
+    ctx.log_debug(format!("rdcl.query_sub: calendar_uid: {calendar_uid} name: {name} query: {query_string}").as_str());
+
+    // Spawn the process of parsing the query into it's own timeout enforced thread to guard
+    // against malicious payloads intended to cause hangs.
+    let parsed_query =
+        match run_with_timeout(
+            move || EventInstanceQuery::from_str_with_default_timezone(query_string.as_str(), default_tz.as_ref()).map_err(RedisError::String),
+            std::time::Duration::from_millis(*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS.lock(ctx) as u64),
+        ) {
+            Ok(parser_result) => {
+                parser_result?
+            },
+
+            Err(TimeoutError) => {
+                ctx.log_warning(
+                    format!(
+                        "rdcl.query_sub: query iCal parser exceeded timeout -- calendar_uid: {calendar_uid}",
+                    ).as_str()
+                );
+
+                return Err(RedisError::String(String::from(
+                    "rdcl.query_sub: query iCal parser exceeded timeout"
+                )));
+            },
+        };
+
+    let execution_deadline = ExecutionDeadline::new(std::time::Duration::from_millis(*CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64));
+
+    let query_subscription = QuerySubscription::new(parsed_query, calendar, &execution_deadline);
+
+    subscribe(get_selected_db(ctx), calendar_uid.to_string(), name, query_subscription);
+
+    Ok(RedisValue::SimpleStringStatic("OK"))
+}
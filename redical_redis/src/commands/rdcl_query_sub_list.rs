@@ -0,0 +1,27 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::query_subscriptions::get_subscription_names;
+use crate::utils::get_selected_db;
+
+pub fn redical_query_sub_list(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() != 2 {
+        ctx.log_debug(format!("rdcl.query_sub_list: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?.to_string();
+
+    ctx.log_debug(format!("rdcl.query_sub_list: calendar_uid: {calendar_uid}").as_str());
+
+    Ok(
+        RedisValue::Array(
+            get_subscription_names(get_selected_db(ctx), &calendar_uid)
+                .into_iter()
+                .map(RedisValue::BulkString)
+                .collect()
+        )
+    )
+}
@@ -0,0 +1,22 @@
+use redis_module::{Context, NextArg, RedisError, RedisResult, RedisString, RedisValue};
+
+use crate::query_subscriptions::unsubscribe;
+use crate::utils::get_selected_db;
+
+pub fn redical_query_unsub(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    if args.len() != 3 {
+        ctx.log_debug(format!("rdcl.query_unsub: WrongArity: {}", args.len()).as_str());
+
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+
+    let calendar_uid = args.next_arg()?.to_string();
+
+    let name = args.next_arg()?.to_string();
+
+    ctx.log_debug(format!("rdcl.query_unsub: calendar_uid: {calendar_uid} name: {name}").as_str());
+
+    Ok(RedisValue::Bool(unsubscribe(get_selected_db(ctx), &calendar_uid, &name)))
+}
@@ -0,0 +1,183 @@
+use crate::core::Calendar;
+
+use redical_ical::{ICalendarComponent, ICalendarEntity};
+use redical_ical::values::date_time::DateTime;
+
+// The RediCal commands (and their arguments) which reconstruct a Calendar when replayed from the
+// rewritten AOF.
+#[derive(Debug, PartialEq)]
+pub enum AOFCommand {
+    CalendarSet(Option<String>),
+    CalendarIndexDisable,
+    EventSet(String, String),
+    EventOverrideSet(String, String, String),
+    EventExpire(String, String),
+    CalendarGeneration(u64),
+}
+
+impl AOFCommand {
+    pub fn command_name(&self) -> &'static str {
+        match self {
+            AOFCommand::CalendarSet(_) => "rdcl.cal_set",
+            AOFCommand::CalendarIndexDisable => "rdcl.cal_idx_disable",
+            AOFCommand::EventSet(_, _) => "rdcl.evt_set",
+            AOFCommand::EventOverrideSet(_, _, _) => "rdcl.evo_set",
+            AOFCommand::EventExpire(_, _) => "rdcl.evt_expire",
+            AOFCommand::CalendarGeneration(_) => "rdcl.cal_set",
+        }
+    }
+}
+
+pub fn build_aof_commands(calendar: &Calendar) -> Vec<AOFCommand> {
+    // The calendar properties (other than the UID, which the key represents) are set along with
+    // the Calendar itself.
+    let calendar_uid_content_line = calendar.uid.render_ical();
+
+    let calendar_properties =
+        calendar
+            .to_rendered_content_lines()
+            .into_iter()
+            .filter(|content_line| content_line != &calendar_uid_content_line)
+            .collect::<Vec<String>>();
+
+    let mut aof_commands = vec![
+        AOFCommand::CalendarSet(
+            Some(calendar_properties.join(" ")).filter(|calendar_properties| !calendar_properties.is_empty())
+        ),
+    ];
+
+    // Disable the indexes before replaying the events so that they are ingested as they were
+    // stored (without building indexes).
+    if !calendar.indexes_active {
+        aof_commands.push(AOFCommand::CalendarIndexDisable);
+    }
+
+    for (event_uid, event) in &calendar.events {
+        aof_commands.push(
+            AOFCommand::EventSet(
+                event_uid.to_owned(),
+                event.to_rendered_content_lines().join(" "),
+            )
+        );
+
+        for (override_timestamp, event_occurrence_override) in &event.overrides {
+            aof_commands.push(
+                AOFCommand::EventOverrideSet(
+                    event_uid.to_owned(),
+                    DateTime::from(*override_timestamp).render_formatted_date_time(None),
+                    event_occurrence_override.to_rendered_content_lines().join(" "),
+                )
+            );
+        }
+
+        if let Some(expires_at) = calendar.event_expirations.get(event_uid) {
+            aof_commands.push(
+                AOFCommand::EventExpire(
+                    event_uid.to_owned(),
+                    DateTime::from(*expires_at).render_formatted_date_time(None),
+                )
+            );
+        }
+    }
+
+    // Replaying the above bumps the generation of the reconstructed Calendar, so it is set to
+    // that of the Calendar last.
+    if calendar.generation > 0 {
+        aof_commands.push(AOFCommand::CalendarGeneration(calendar.generation));
+    }
+
+    aof_commands
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::core::{Event, EventOccurrenceOverride};
+
+    use redical_ical::properties::CalendarProperty;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use std::str::FromStr;
+
+    #[test]
+    fn test_build_aof_commands() {
+        let event_occurrence_override =
+            EventOccurrenceOverride::parse_ical(
+                "19700101T000500Z",
+                "CATEGORIES:CATEGORY_TWO LAST-MODIFIED:19700101T020500Z",
+            ).unwrap();
+
+        let mut event =
+            Event::parse_ical(
+                "EVENT_UID",
+                "RRULE:FREQ=WEEKLY;UNTIL=19700101T000500Z;INTERVAL=1 CATEGORIES:CATEGORY_ONE DTSTART:19700101T000500Z LAST-MODIFIED:19700101T010500Z",
+            ).unwrap();
+
+        event.override_occurrence(&event_occurrence_override, true).unwrap();
+
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event(event);
+
+        assert_eq!(
+            build_aof_commands(&calendar),
+            vec![
+                AOFCommand::CalendarSet(None),
+                AOFCommand::EventSet(
+                    String::from("EVENT_UID"),
+                    String::from("CATEGORIES:CATEGORY_ONE DTSTART:19700101T000500Z LAST-MODIFIED:19700101T010500Z RRULE:FREQ=WEEKLY;INTERVAL=1;UNTIL=19700101T000500Z UID:EVENT_UID"),
+                ),
+                AOFCommand::EventOverrideSet(
+                    String::from("EVENT_UID"),
+                    String::from("19700101T000500Z"),
+                    String::from("CATEGORIES:CATEGORY_TWO DTSTART:19700101T000500Z LAST-MODIFIED:19700101T020500Z"),
+                ),
+            ],
+        );
+
+        calendar.set_event_expiration(&String::from("EVENT_UID"), 86400);
+
+        assert_eq!(
+            build_aof_commands(&calendar).last(),
+            Some(
+                &AOFCommand::EventExpire(
+                    String::from("EVENT_UID"),
+                    String::from("19700102T000000Z"),
+                )
+            ),
+        );
+
+        calendar.bump_generation();
+
+        assert_eq!(
+            build_aof_commands(&calendar).last(),
+            Some(&AOFCommand::CalendarGeneration(1)),
+        );
+
+        calendar.disable_indexes();
+
+        assert_eq!(
+            build_aof_commands(&calendar)[0..2],
+            [
+                AOFCommand::CalendarSet(None),
+                AOFCommand::CalendarIndexDisable,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_build_aof_commands_with_calendar_properties() {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert(CalendarProperty::from_str("X-DEFAULT-TZID:Europe/London").unwrap()).unwrap();
+
+        assert_eq!(
+            build_aof_commands(&calendar),
+            vec![
+                AOFCommand::CalendarSet(Some(String::from("X-DEFAULT-TZID:Europe/London"))),
+            ],
+        );
+    }
+}
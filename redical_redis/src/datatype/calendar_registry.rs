@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use std::ffi::c_int;
+use std::sync::Mutex;
+
+use redical_core::Calendar;
+
+// The (database id, key) of every Calendar stored, by the address of the Calendar value.
+//
+// Registered by the data type callbacks creating Calendar values (rdb_load and copy2) and by
+// RDCL.CAL_SET, and unregistered by the free callback, so that the Calendars of a flushed database
+// are known without scanning it's keyspace. Keying by the value (rather than the key) means that
+// replacing a Calendar (e.g. RESTORE ... REPLACE) does not unregister it's replacement, as the
+// replacement is created before the replaced Calendar is freed.
+static CALENDAR_REGISTRY: Mutex<BTreeMap<usize, (c_int, String)>> = Mutex::new(BTreeMap::new());
+
+fn get_registry_id(calendar: *const Calendar) -> usize {
+    calendar as usize
+}
+
+pub fn register_calendar(db_id: c_int, calendar: &Calendar) {
+    CALENDAR_REGISTRY
+        .lock()
+        .unwrap()
+        .insert(get_registry_id(calendar), (db_id, calendar.uid.uid.to_string()));
+}
+
+pub fn unregister_calendar(calendar: *const Calendar) {
+    CALENDAR_REGISTRY.lock().unwrap().remove(&get_registry_id(calendar));
+}
+
+// Unregister the Calendars of the flushed database (or all databases if None), returning the
+// (database id, key) of each.
+//
+// The keys of renamed or moved Calendars are not updated (there is no callback for either), so
+// each returned key is not guaranteed to still hold a Calendar.
+pub fn take_flushed_calendar_keys(flushed_db_id: Option<c_int>) -> Vec<(c_int, String)> {
+    let mut calendar_registry = CALENDAR_REGISTRY.lock().unwrap();
+
+    let mut flushed_calendar_keys = Vec::new();
+
+    calendar_registry.retain(|_registry_id, (db_id, calendar_uid)| {
+        if flushed_db_id.is_some_and(|flushed_db_id| *db_id != flushed_db_id) {
+            return true;
+        }
+
+        flushed_calendar_keys.push((*db_id, calendar_uid.to_owned()));
+
+        false
+    });
+
+    flushed_calendar_keys.sort();
+    flushed_calendar_keys.dedup();
+
+    flushed_calendar_keys
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_take_flushed_calendar_keys() {
+        let calendar_one = Calendar::new(String::from("CALENDAR_ONE"));
+        let calendar_two = Calendar::new(String::from("CALENDAR_TWO"));
+        let calendar_three = Calendar::new(String::from("CALENDAR_THREE"));
+        let calendar_freed = Calendar::new(String::from("CALENDAR_FREED"));
+
+        register_calendar(10, &calendar_one);
+        register_calendar(11, &calendar_two);
+        register_calendar(11, &calendar_three);
+        register_calendar(11, &calendar_freed);
+
+        unregister_calendar(&calendar_freed);
+
+        assert_eq!(
+            take_flushed_calendar_keys(Some(11)),
+            vec![
+                (11, String::from("CALENDAR_THREE")),
+                (11, String::from("CALENDAR_TWO")),
+            ],
+        );
+
+        // Already taken.
+        assert_eq!(take_flushed_calendar_keys(Some(11)), vec![]);
+
+        assert!(take_flushed_calendar_keys(None).contains(&(10, String::from("CALENDAR_ONE"))));
+        assert!(!take_flushed_calendar_keys(None).contains(&(10, String::from("CALENDAR_ONE"))));
+    }
+}
@@ -0,0 +1,167 @@
+use redis_module::{raw, Context, RedisError, RedisString, ThreadSafeContext};
+use redis_module::key::{RedisKey, RedisKeyWritable};
+use redis_module::logging::{log_notice, log_warning};
+use redis_module::server_events::LoadingSubevent;
+use redis_module_macros::loading_event_handler;
+
+use std::collections::BTreeSet;
+use std::ffi::c_int;
+use std::sync::Mutex;
+
+use redical_core::Calendar;
+
+use super::CALENDAR_DATA_TYPE;
+use crate::commands::notify_calendar_idx_rebuild_complete_keyspace_event;
+use crate::utils::get_selected_db;
+
+// The (database id, key) of each Calendar loaded from the RDB with it's indexes still to be built.
+//
+// Building the indexes of every Calendar is deferred until loading has ended so that the server
+// becomes available as soon as possible, they are then rebuilt in the background (one Calendar
+// at a time), or on demand if queried before the background rebuild reaches them.
+static PENDING_INDEX_REBUILDS: Mutex<BTreeSet<(c_int, String)>> = Mutex::new(BTreeSet::new());
+
+pub fn defer_index_rebuild(db_id: c_int, calendar_uid: String) {
+    PENDING_INDEX_REBUILDS.lock().unwrap().insert((db_id, calendar_uid));
+}
+
+// Returns true if the Calendar was awaiting a deferred index rebuild, no longer tracking it.
+fn take_pending_index_rebuild(db_id: c_int, calendar_uid: &str) -> bool {
+    PENDING_INDEX_REBUILDS.lock().unwrap().remove(&(db_id, calendar_uid.to_owned()))
+}
+
+// Open the Calendar key with it's indexes guaranteed to have been built.
+//
+// Calendars loaded from the RDB have their indexes rebuilt in the background, so any Calendar not
+// yet reached is rebuilt immediately, so that it can be queried (or have it's indexes read)
+// straight after loading without waiting for the background rebuild to reach it.
+pub fn open_indexed_calendar_key(ctx: &Context, calendar_uid: &RedisString) -> Result<RedisKey, RedisError> {
+    rebuild_pending_calendar_indexes(ctx, calendar_uid)?;
+
+    Ok(ctx.open_key(calendar_uid))
+}
+
+// As `open_indexed_calendar_key`, but for commands mutating the indexed Calendar.
+pub fn open_indexed_calendar_key_writable(ctx: &Context, calendar_uid: &RedisString) -> Result<RedisKeyWritable, RedisError> {
+    rebuild_pending_calendar_indexes(ctx, calendar_uid)?;
+
+    Ok(ctx.open_key_writable(calendar_uid))
+}
+
+// Immediately rebuild the indexes of the Calendar if it is still awaiting it's deferred index
+// rebuild.
+fn rebuild_pending_calendar_indexes(ctx: &Context, calendar_uid: &RedisString) -> Result<(), RedisError> {
+    if !take_pending_index_rebuild(get_selected_db(ctx), &calendar_uid.to_string()) {
+        return Ok(());
+    }
+
+    let calendar_key = ctx.open_key_writable(calendar_uid);
+
+    if let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? {
+        if !calendar.indexes_active {
+            let index_rebuild_stats = calendar.rebuild_indexes_at(chrono::Utc::now().timestamp()).map_err(RedisError::String)?;
+
+            notify_calendar_idx_rebuild_complete_keyspace_event(ctx, calendar_uid, &index_rebuild_stats)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Disable the indexes of a Calendar left inconsistent by a failed incremental index update, and
+// defer rebuilding them to the next command reading them, rather than failing a command which has
+// already partially applied it's mutations.
+pub fn defer_failed_index_update(ctx: &Context, calendar_uid: &RedisString, calendar: &mut Calendar, error: RedisError) {
+    ctx.log_warning(format!("Index update failed for Calendar: {calendar_uid} with error: {error} - deferring index rebuild").as_str());
+
+    calendar.disable_indexes();
+
+    defer_index_rebuild(get_selected_db(ctx), calendar_uid.to_string());
+}
+
+// Explicitly disabling the indexes of a Calendar should not be undone by a deferred index rebuild.
+pub fn discard_pending_index_rebuild(ctx: &Context, calendar_uid: &RedisString) {
+    take_pending_index_rebuild(get_selected_db(ctx), &calendar_uid.to_string());
+}
+
+// Stop tracking the Calendars of the flushed database (or all databases if None), as they no
+// longer exist.
+pub fn discard_flushed_pending_index_rebuilds(flushed_db_id: Option<c_int>) {
+    PENDING_INDEX_REBUILDS
+        .lock()
+        .unwrap()
+        .retain(|(db_id, _calendar_uid)| flushed_db_id.is_some_and(|flushed_db_id| *db_id != flushed_db_id));
+}
+
+#[loading_event_handler]
+fn on_loading_event(_ctx: &Context, loading_subevent: LoadingSubevent) {
+    match loading_subevent {
+        LoadingSubevent::Ended => {
+            spawn_background_index_rebuild();
+        },
+
+        LoadingSubevent::Failed => {
+            PENDING_INDEX_REBUILDS.lock().unwrap().clear();
+        },
+
+        _ => {},
+    }
+}
+
+fn spawn_background_index_rebuild() {
+    let pending_count = PENDING_INDEX_REBUILDS.lock().unwrap().len();
+
+    if pending_count == 0 {
+        return;
+    }
+
+    log_notice(&format!("Rebuilding indexes of {pending_count} Calendar(s) loaded in the background"));
+
+    std::thread::spawn(move || {
+        let thread_ctx = ThreadSafeContext::new();
+
+        let mut rebuilt_count = 0;
+
+        loop {
+            // Only hold the lock for a single Calendar at a time so that other commands are able
+            // to run in between each rebuild.
+            let ctx = thread_ctx.lock();
+
+            let Some((db_id, calendar_uid)) = PENDING_INDEX_REBUILDS.lock().unwrap().pop_first() else {
+                break;
+            };
+
+            rebuilt_count += 1;
+
+            match rebuild_calendar_indexes(&ctx, db_id, &calendar_uid) {
+                Ok(()) => {
+                    log_notice(&format!("Rebuilt indexes of Calendar: {calendar_uid} ({rebuilt_count}/{pending_count})"));
+                },
+
+                Err(error) => {
+                    log_warning(&format!("Deferred index rebuild failed for Calendar: {calendar_uid} with error: {error}"));
+                },
+            }
+        }
+
+        log_notice(&format!("Finished rebuilding indexes of {rebuilt_count} Calendar(s) loaded"));
+    });
+}
+
+fn rebuild_calendar_indexes(ctx: &Context, db_id: c_int, calendar_uid: &str) -> Result<(), RedisError> {
+    unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, db_id) };
+
+    let calendar_key_name = ctx.create_string(calendar_uid);
+    let calendar_key = ctx.open_key_writable(&calendar_key_name);
+
+    // The Calendar may have since been deleted, replaced, or had it's indexes rebuilt explicitly.
+    if let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? {
+        if !calendar.indexes_active {
+            let index_rebuild_stats = calendar.rebuild_indexes_at(chrono::Utc::now().timestamp()).map_err(RedisError::String)?;
+
+            notify_calendar_idx_rebuild_complete_keyspace_event(ctx, &calendar_key_name, &index_rebuild_stats)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,20 +1,40 @@
 use redical_core::Calendar;
 
 use redis_module::{
-    native_types::RedisType, raw, RedisModuleIO, RedisModuleString, RedisModuleTypeMethods,
+    native_types::RedisType, raw, Context, ContextFlags, RedisModuleIO, RedisModuleString, RedisModuleTypeMethods,
+    RedisString,
 };
+use redis_module::logging::{log_notice, log_warning};
+
+use redical_ical::properties::UIDProperty;
+
+use crate::event_expiry::track_expiring_calendar;
+use crate::scheduled_pruning::track_pruning_calendar;
 
 use std::{
-    ffi::{c_int, c_void},
+    ffi::{c_char, c_int, c_longlong, c_void, CString},
     ptr::null_mut,
 };
 
+mod aof_data;
+mod calendar_registry;
 mod rdb_data;
+mod rdb_format;
+mod deferred_index_rebuild;
 
-use rdb_data::RDBCalendar;
+use aof_data::{build_aof_commands, AOFCommand};
+use rdb_data::{insert_rdb_events, RDBCalendar, RDBCalendarHeader, RDBEvent, RDBEventExpirations};
+use rdb_format::RDBFormatVersion;
+use deferred_index_rebuild::defer_index_rebuild;
+
+pub use calendar_registry::{register_calendar, take_flushed_calendar_keys};
+pub use deferred_index_rebuild::{
+    defer_failed_index_update, discard_flushed_pending_index_rebuilds, discard_pending_index_rebuild, open_indexed_calendar_key,
+    open_indexed_calendar_key_writable,
+};
 
 pub const CALENDAR_DATA_TYPE_NAME: &str = "RICAL_CAL";
-pub const CALENDAR_DATA_TYPE_VERSION: i32 = 1;
+pub const CALENDAR_DATA_TYPE_VERSION: i32 = RDBFormatVersion::CURRENT as i32;
 
 pub static CALENDAR_DATA_TYPE: RedisType = RedisType::new(
     CALENDAR_DATA_TYPE_NAME,
@@ -24,50 +44,189 @@ pub static CALENDAR_DATA_TYPE: RedisType = RedisType::new(
         rdb_load: Some(rdb_load),
         rdb_save: Some(rdb_save),
         aof_rewrite: Some(aof_rewrite),
-        mem_usage: Some(mem_usage),
-        digest: None,
+        mem_usage: None,
+        digest: Some(digest),
         free: Some(free),
         aux_load: None,
         aux_save: None,
         aux_save_triggers: 0,
         free_effort: None,
         unlink: None,
-        copy: Some(copy),
+        copy: None,
         defrag: None,
 
-        copy2: None,
+        copy2: Some(copy2),
         free_effort2: None,
-        mem_usage2: None,
+        mem_usage2: Some(mem_usage2),
         unlink2: None,
     },
 );
 
-pub extern "C" fn rdb_load(rdb: *mut raw::RedisModuleIO, _encver: c_int) -> *mut c_void {
-    let Ok(buffer) = raw::load_string_buffer(rdb) else {
-        return null_mut();
-    };
+// Calendar Events are loaded from the RDB in batches of this size, so that only the current batch
+// of serialized Events is held in memory at any one time (whilst still parsing each in parallel).
+const RDB_LOAD_EVENT_BATCH_SIZE: usize = 1_000;
 
-    let bytes: &[u8] = buffer.as_ref();
+// Log the progress of loading a Calendar each time this many of it's Events have been loaded.
+const RDB_LOAD_PROGRESS_LOG_INTERVAL: u64 = 100_000;
 
-    let rdb_calendar: RDBCalendar = bincode::deserialize(bytes).unwrap();
+pub extern "C" fn rdb_load(rdb: *mut raw::RedisModuleIO, encver: c_int) -> *mut c_void {
+    match rdb_load_calendar(rdb, encver) {
+        Ok(calendar) => {
+            if calendar.prune_older_than.is_some() {
+                track_pruning_calendar(get_db_id(rdb), calendar.uid.uid.to_string());
+            }
 
-    let calendar = match Calendar::try_from(&rdb_calendar) {
-        Ok(calendar) => calendar,
+            let calendar = Box::into_raw(Box::new(calendar));
+
+            register_calendar(get_db_id(rdb), unsafe { &*calendar });
+
+            calendar.cast::<libc::c_void>()
+        },
 
-        // TODO: Handle properly - log error and return null etc.
         Err(error) => {
-            panic!("rdb_load failed for Calendar with error: {:#?}", error.to_string());
+            log_warning(&format!("rdb_load failed for Calendar with error: {error}"));
+
+            null_mut()
         },
-    };
+    }
+}
+
+fn rdb_load_calendar(rdb: *mut raw::RedisModuleIO, encver: c_int) -> Result<Calendar, String> {
+    // The payload of a RESTORE (or MIGRATE) may have been dumped by a newer version of RediCal
+    // (e.g. on another shard mid-upgrade), it's encoding cannot be assumed so it is rejected before
+    // any of it is read.
+    if RDBFormatVersion::is_newer_than_current(encver as u64) {
+        return Err(RDBFormatVersion::newer_than_current_error(encver as u64));
+    }
+
+    let format_version =
+        if RDBFormatVersion::is_embedded_for_encver(encver) {
+            RDBFormatVersion::try_from(raw::load_unsigned(rdb).map_err(|error| error.to_string())?)?
+        } else {
+            RDBFormatVersion::try_from(encver as u64)?
+        };
+
+    match format_version {
+        // Migrate the whole Calendar into the streamed encoding (already loaded into memory).
+        RDBFormatVersion::WholeCalendar => {
+            let rdb_calendar: RDBCalendar = rdb_load_deserialized(rdb)?;
+
+            let (rdb_calendar_header, rdb_events) = rdb_calendar.into_header_and_events();
+
+            let mut rdb_events = rdb_events.into_iter();
+
+            load_streamed_calendar(rdb, rdb_calendar_header, rdb_events.len() as u64, || {
+                rdb_events.next().ok_or_else(|| String::from("Unexpected end of Calendar events"))
+            })
+        },
+
+        RDBFormatVersion::StreamedEvents => {
+            let rdb_calendar_header: RDBCalendarHeader = rdb_load_deserialized(rdb)?;
+
+            let event_count = raw::load_unsigned(rdb).map_err(|error| error.to_string())?;
+
+            let mut calendar = load_streamed_calendar(rdb, rdb_calendar_header, event_count, || rdb_load_deserialized(rdb))?;
+
+            let rdb_event_expirations: RDBEventExpirations = rdb_load_deserialized(rdb)?;
+
+            rdb_event_expirations.apply(&mut calendar).map_err(String::from)?;
+
+            if !calendar.event_expirations.is_empty() {
+                track_expiring_calendar(get_db_id(rdb), calendar.uid.uid.to_string());
+            }
+
+            calendar.generation = raw::load_unsigned(rdb).map_err(|error| error.to_string())?;
+
+            Ok(calendar)
+        },
+    }
+}
+
+// Stream the Calendar Events in batches, deferring building the Calendar indexes until after
+// loading has ended (see deferred_index_rebuild) to reduce stalls on startup.
+fn load_streamed_calendar<F>(
+    rdb: *mut raw::RedisModuleIO,
+    rdb_calendar_header: RDBCalendarHeader,
+    event_count: u64,
+    mut next_rdb_event: F,
+) -> Result<Calendar, String>
+where
+    F: FnMut() -> Result<RDBEvent, String>,
+{
+    let mut calendar = Calendar::try_from(&rdb_calendar_header).map_err(String::from)?;
+
+    // The Calendar UID reflects the key it is stored on, so a Calendar restored onto another key
+    // (e.g. RESTORE or MIGRATE with a different key name) takes the UID of that key.
+    if let Some(key_name) = get_key_name(rdb) {
+        calendar.uid = UIDProperty::from(key_name);
+    }
+
+    calendar.disable_indexes();
+
+    let calendar_uid = calendar.uid.uid.to_string();
+
+    if event_count >= RDB_LOAD_PROGRESS_LOG_INTERVAL {
+        log_notice(&format!("Loading Calendar: {calendar_uid} with {event_count} events"));
+    }
+
+    let mut rdb_events: Vec<RDBEvent> = Vec::with_capacity(RDB_LOAD_EVENT_BATCH_SIZE.min(event_count as usize));
+
+    for loaded_count in 1..=event_count {
+        rdb_events.push(next_rdb_event()?);
+
+        if rdb_events.len() >= RDB_LOAD_EVENT_BATCH_SIZE || loaded_count == event_count {
+            insert_rdb_events(&mut calendar, &rdb_events).map_err(String::from)?;
+
+            rdb_events.clear();
+        }
+
+        if loaded_count.is_multiple_of(RDB_LOAD_PROGRESS_LOG_INTERVAL) {
+            log_notice(&format!("Loading Calendar: {calendar_uid} - loaded {loaded_count}/{event_count} events"));
+        }
+    }
+
+    // Outside of loading the RDB on startup (or full sync) e.g. RESTORE, there is no loading ended
+    // event to trigger the deferred index rebuild, so the indexes are rebuilt immediately.
+    if is_loading(rdb) {
+        defer_index_rebuild(get_db_id(rdb), calendar_uid);
+    } else {
+        calendar.rebuild_indexes_at(chrono::Utc::now().timestamp())?;
+    }
+
+    Ok(calendar)
+}
+
+fn rdb_load_deserialized<T: serde::de::DeserializeOwned>(rdb: *mut raw::RedisModuleIO) -> Result<T, String> {
+    let buffer = raw::load_string_buffer(rdb).map_err(|error| error.to_string())?;
+
+    bincode::deserialize(buffer.as_ref()).map_err(|error| error.to_string())
+}
+
+fn is_loading(rdb: *mut raw::RedisModuleIO) -> bool {
+    let ctx = unsafe { raw::RedisModule_GetContextFromIO.unwrap()(rdb) };
+
+    Context::new(ctx).get_flags().contains(ContextFlags::LOADING)
+}
+
+fn get_key_name(rdb: *mut raw::RedisModuleIO) -> Option<String> {
+    let key_name = unsafe { raw::RedisModule_GetKeyNameFromIO.unwrap()(rdb) };
+
+    if key_name.is_null() {
+        return None;
+    }
 
-    Box::into_raw(Box::new(calendar)).cast::<libc::c_void>()
+    RedisString::from_ptr(key_name).ok().map(str::to_owned)
+}
+
+fn get_db_id(rdb: *mut raw::RedisModuleIO) -> c_int {
+    unsafe { raw::RedisModule_GetDbIdFromIO.unwrap()(rdb) }
 }
 
 pub unsafe extern "C" fn rdb_save(rdb: *mut raw::RedisModuleIO, value: *mut c_void) {
     let calendar = unsafe { &*(value as *mut Calendar) };
 
-    let rdb_calendar = match RDBCalendar::try_from(calendar) {
-        Ok(rdb_calendar) => rdb_calendar,
+    let rdb_calendar_header = match RDBCalendarHeader::try_from(calendar) {
+        Ok(rdb_calendar_header) => rdb_calendar_header,
 
         // TODO: Handle properly - log error and return null etc.
         Err(error) => {
@@ -75,23 +234,154 @@ pub unsafe extern "C" fn rdb_save(rdb: *mut raw::RedisModuleIO, value: *mut c_vo
         },
     };
 
-    let bytes: Vec<u8> = bincode::serialize(&rdb_calendar).unwrap();
+    // Always persisted in the current format version, older versions are migrated when loaded.
+    raw::save_unsigned(rdb, RDBFormatVersion::CURRENT as u64);
+
+    raw::save_slice(rdb, &bincode::serialize(&rdb_calendar_header).unwrap());
+
+    // Each Event is persisted separately so that they can be streamed when loaded.
+    raw::save_unsigned(rdb, calendar.events.len() as u64);
+
+    for event in calendar.events.values() {
+        let rdb_event = match RDBEvent::try_from(event.as_ref()) {
+            Ok(rdb_event) => rdb_event,
+
+            // TODO: Handle properly - log error and return null etc.
+            Err(error) => {
+                panic!("rdb_save failed for Calendar Event with error: {error:#?}");
+            },
+        };
 
-    let str = std::str::from_utf8_unchecked(&bytes[..]); // no save_string_buffer available in redis-module :(
+        raw::save_slice(rdb, &bincode::serialize(&rdb_event).unwrap());
+    }
+
+    raw::save_slice(rdb, &bincode::serialize(&RDBEventExpirations::from(calendar)).unwrap());
 
-    raw::save_string(rdb, str);
+    raw::save_unsigned(rdb, calendar.generation);
 }
 
+// Reconstruct the Calendar as the sequence of RediCal commands which would recreate it, so that
+// deployments relying purely on AOF persistence (without RDB) retain their Calendars.
 unsafe extern "C" fn aof_rewrite(
-    _aof: *mut RedisModuleIO,
-    _key: *mut RedisModuleString,
-    _value: *mut c_void,
+    aof: *mut RedisModuleIO,
+    key: *mut RedisModuleString,
+    value: *mut c_void,
 ) {
-    todo!();
+    let calendar = unsafe { &*(value as *mut Calendar) };
+
+    let emit_aof = raw::RedisModule_EmitAOF.unwrap();
+
+    for aof_command in build_aof_commands(calendar) {
+        let command_name = CString::new(aof_command.command_name()).unwrap();
+
+        match aof_command {
+            AOFCommand::CalendarSet(None) | AOFCommand::CalendarIndexDisable => {
+                emit_aof(aof, command_name.as_ptr(), c"s".as_ptr(), key);
+            },
+
+            AOFCommand::CalendarSet(Some(calendar_properties)) => {
+                emit_aof(
+                    aof,
+                    command_name.as_ptr(),
+                    c"sb".as_ptr(),
+                    key,
+                    calendar_properties.as_ptr().cast::<c_char>(),
+                    calendar_properties.len(),
+                );
+            },
+
+            AOFCommand::EventSet(event_uid, ical) => {
+                emit_aof(
+                    aof,
+                    command_name.as_ptr(),
+                    c"sbb".as_ptr(),
+                    key,
+                    event_uid.as_ptr().cast::<c_char>(),
+                    event_uid.len(),
+                    ical.as_ptr().cast::<c_char>(),
+                    ical.len(),
+                );
+            },
+
+            // Always replay overrides as DETACHED, as they have already been validated against
+            // the event (and may have been explicitly set as detached).
+            AOFCommand::EventOverrideSet(event_uid, override_date_string, ical) => {
+                emit_aof(
+                    aof,
+                    command_name.as_ptr(),
+                    c"sbbcb".as_ptr(),
+                    key,
+                    event_uid.as_ptr().cast::<c_char>(),
+                    event_uid.len(),
+                    override_date_string.as_ptr().cast::<c_char>(),
+                    override_date_string.len(),
+                    c"DETACHED".as_ptr(),
+                    ical.as_ptr().cast::<c_char>(),
+                    ical.len(),
+                );
+            },
+
+            AOFCommand::EventExpire(event_uid, expires_at_date_string) => {
+                emit_aof(
+                    aof,
+                    command_name.as_ptr(),
+                    c"sbcb".as_ptr(),
+                    key,
+                    event_uid.as_ptr().cast::<c_char>(),
+                    event_uid.len(),
+                    c"AT".as_ptr(),
+                    expires_at_date_string.as_ptr().cast::<c_char>(),
+                    expires_at_date_string.len(),
+                );
+            },
+
+            AOFCommand::CalendarGeneration(generation) => {
+                emit_aof(
+                    aof,
+                    command_name.as_ptr(),
+                    c"scl".as_ptr(),
+                    key,
+                    c"GENERATION".as_ptr(),
+                    generation as c_longlong,
+                );
+            },
+        }
+    }
 }
 
-unsafe extern "C" fn mem_usage(_value: *const c_void) -> usize {
-    0
+// The sample size is as requested by `MEMORY USAGE key [SAMPLES count]` (0 for all), so that
+// (like the Redis data types) the cost is not proportional to the size of the Calendar.
+unsafe extern "C" fn mem_usage2(
+    _ctx: *mut raw::RedisModuleKeyOptCtx,
+    value: *const c_void,
+    sample_size: usize,
+) -> usize {
+    if value.is_null() {
+        return 0;
+    }
+
+    let calendar = unsafe { &*(value as *const Calendar) };
+
+    calendar.approximate_memory_usage(sample_size)
+}
+
+// Feed the Calendar content into the digest in a canonical order (each component as it's own
+// sequence), so that `DEBUG DIGEST-VALUE` is consistent across primaries, replicas and restored
+// backups.
+unsafe extern "C" fn digest(md: *mut raw::RedisModuleDigest, value: *mut c_void) {
+    if value.is_null() {
+        return;
+    }
+
+    let calendar = unsafe { &*(value as *mut Calendar) };
+
+    for digest_component in calendar.to_digest_components() {
+        for content_line in digest_component {
+            raw::RedisModule_DigestAddStringBuffer.unwrap()(md, content_line.as_ptr().cast::<c_char>(), content_line.len());
+        }
+
+        raw::RedisModule_DigestEndSequence.unwrap()(md);
+    }
 }
 
 unsafe extern "C" fn free(value: *mut c_void) {
@@ -104,17 +394,44 @@ unsafe extern "C" fn free(value: *mut c_void) {
 
     // println!("Calendar data type - free - calendar : {:#?}", &calendar);
 
+    calendar_registry::unregister_calendar(calendar);
+
     drop(Box::from_raw(calendar));
 }
 
-unsafe extern "C" fn copy(
-    _fromkey: *mut RedisModuleString,
-    _tokey: *mut RedisModuleString,
+// Uses the copy2 callback (over copy) so that the destination database is available to track the
+// copied Calendar for expiring it's Events.
+unsafe extern "C" fn copy2(
+    ctx: *mut raw::RedisModuleKeyOptCtx,
     value: *const c_void,
 ) -> *mut c_void {
     let calendar = unsafe { &*(value as *mut Calendar) };
 
-    let calendar_cloned = calendar.clone();
+    // Deep clone the Calendar along with all of it's events, overrides, and indexes so that the
+    // copy is immediately queryable without requiring an index rebuild.
+    let mut calendar_cloned = calendar.clone();
+
+    // The Calendar UID reflects the key it is stored on, so the copy takes the UID of the
+    // destination key.
+    let tokey = unsafe { raw::RedisModule_GetToKeyNameFromOptCtx.unwrap()(ctx) };
+
+    if let Ok(calendar_uid) = RedisString::from_ptr(tokey) {
+        calendar_cloned.uid = UIDProperty::from(calendar_uid.to_string());
+    }
+
+    let to_db_id = unsafe { raw::RedisModule_GetToDbIdFromOptCtx.unwrap()(ctx) };
+
+    if !calendar_cloned.event_expirations.is_empty() {
+        track_expiring_calendar(to_db_id, calendar_cloned.uid.uid.to_string());
+    }
+
+    if calendar_cloned.prune_older_than.is_some() {
+        track_pruning_calendar(to_db_id, calendar_cloned.uid.uid.to_string());
+    }
+
+    let calendar_cloned = Box::into_raw(Box::new(calendar_cloned));
+
+    register_calendar(to_db_id, unsafe { &*calendar_cloned });
 
-    Box::into_raw(Box::new(calendar_cloned)).cast::<c_void>()
+    calendar_cloned.cast::<c_void>()
 }
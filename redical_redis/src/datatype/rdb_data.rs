@@ -76,15 +76,59 @@ impl TryFrom<&Calendar> for RDBCalendar {
     }
 }
 
+impl RDBCalendar {
+    // Split into the streamed encoding (RDBFormatVersion::StreamedEvents).
+    pub fn into_header_and_events(self) -> (RDBCalendarHeader, Vec<RDBEvent>) {
+        (RDBCalendarHeader(self.0, self.1), self.2)
+    }
+}
+
 impl TryFrom<&RDBCalendar> for Calendar {
     type Error = ParseRDBEntityError;
 
     fn try_from(rdb_calendar: &RDBCalendar) -> Result<Self, Self::Error> {
         let rdb_calendar_uid = rdb_calendar.0.to_owned();
 
+        let mut calendar = Calendar::try_from(&RDBCalendarHeader(rdb_calendar_uid.clone(), rdb_calendar.1.clone()))?;
+
+        insert_rdb_events(&mut calendar, &rdb_calendar.2)?;
+
+        calendar.rebuild_indexes().map_err(|error| ParseRDBEntityError::OnSelf(rdb_calendar_uid.to_string(), error))?;
+
+        Ok(
+            calendar
+        )
+    }
+}
+
+// The Calendar UID and properties, persisted ahead of each of it's Events (persisted separately)
+// so that large Calendars can be streamed from the RDB without materialising all of them at once.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RDBCalendarHeader(String, Vec<String>);
+
+impl TryFrom<&Calendar> for RDBCalendarHeader {
+    type Error = String;
+
+    fn try_from(calendar: &Calendar) -> Result<Self, Self::Error> {
+        let uid = calendar.uid.uid.to_string();
+
+        let properties: Vec<String> = calendar.to_rendered_content_lines().into_iter().collect();
+
+        Ok(
+            RDBCalendarHeader(uid, properties)
+        )
+    }
+}
+
+impl TryFrom<&RDBCalendarHeader> for Calendar {
+    type Error = ParseRDBEntityError;
+
+    fn try_from(rdb_calendar_header: &RDBCalendarHeader) -> Result<Self, Self::Error> {
+        let rdb_calendar_uid = rdb_calendar_header.0.to_owned();
+
         let mut calendar = Calendar::new(rdb_calendar_uid.clone());
 
-        for rdb_property in &rdb_calendar.1 {
+        for rdb_property in &rdb_calendar_header.1 {
             let property = CalendarProperty::from_str(rdb_property.as_str()).map_err(|error| ParseRDBEntityError::OnSelf(rdb_calendar_uid.to_string(), error))?;
 
             calendar.insert(property).map_err(|error| ParseRDBEntityError::OnSelf(rdb_calendar_uid.to_string(), error))?;
@@ -101,22 +145,61 @@ impl TryFrom<&RDBCalendar> for Calendar {
             );
         }
 
-        // Parallelise the parsed rehydration of the Calendar Events.
-        let parse_event_results: Vec<Result<Event, ParseRDBEntityError>> =
-            rdb_calendar.2.par_iter().map(|rdb_event: &RDBEvent| {
-                Event::try_from(rdb_event).map_err(|error| ParseRDBEntityError::OnChild(rdb_calendar_uid.to_string(), Box::new(error)))
-            }).collect();
+        Ok(
+            calendar
+        )
+    }
+}
+
+// The timestamp each Calendar Event expires at (RDBFormatVersion::StreamedEvents), persisted after
+// all of the Calendar Events.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RDBEventExpirations(Vec<(String, i64)>);
+
+impl From<&Calendar> for RDBEventExpirations {
+    fn from(calendar: &Calendar) -> Self {
+        RDBEventExpirations(
+            calendar.event_expirations
+                .iter()
+                .map(|(event_uid, expires_at)| (event_uid.to_owned(), *expires_at))
+                .collect()
+        )
+    }
+}
 
-        for parse_event_result in parse_event_results {
-            calendar.insert_event(parse_event_result?);
+impl RDBEventExpirations {
+    // Set the expiration of each of the (already inserted) Calendar Events.
+    pub fn apply(&self, calendar: &mut Calendar) -> Result<(), ParseRDBEntityError> {
+        for (event_uid, expires_at) in &self.0 {
+            if !calendar.set_event_expiration(event_uid, *expires_at) {
+                return Err(
+                    ParseRDBEntityError::OnSelf(
+                        calendar.uid.uid.to_string(),
+                        format!("Event expiration for: {event_uid} does not match a stored Event"),
+                    )
+                );
+            }
         }
 
-        calendar.rebuild_indexes().map_err(|error| ParseRDBEntityError::OnSelf(rdb_calendar_uid.to_string(), error))?;
+        Ok(())
+    }
+}
 
-        Ok(
-            calendar
-        )
+// Parse and insert the Events into the Calendar, without rebuilding the Calendar indexes.
+pub fn insert_rdb_events(calendar: &mut Calendar, rdb_events: &[RDBEvent]) -> Result<(), ParseRDBEntityError> {
+    let calendar_uid = calendar.uid.uid.to_string();
+
+    // Parallelise the parsed rehydration of the Calendar Events.
+    let parse_event_results: Vec<Result<Event, ParseRDBEntityError>> =
+        rdb_events.par_iter().map(|rdb_event: &RDBEvent| {
+            Event::try_from(rdb_event).map_err(|error| ParseRDBEntityError::OnChild(calendar_uid.to_string(), Box::new(error)))
+        }).collect();
+
+    for parse_event_result in parse_event_results {
+        calendar.insert_event(parse_event_result?);
     }
+
+    Ok(())
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -322,6 +405,11 @@ mod test {
             Calendar::try_from(&rdb_calendar),
             Ok(calendar),
         );
+
+        let (rdb_calendar_header, rdb_events) = rdb_calendar.clone().into_header_and_events();
+
+        assert_eq!(rdb_calendar_header, RDBCalendarHeader(rdb_calendar.0, rdb_calendar.1));
+        assert_eq!(rdb_events, rdb_calendar.2);
     }
 
     #[test]
@@ -377,6 +465,84 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_calendar_header_rdb_entity_with_inserted_rdb_events() {
+        let mut event =
+            Event::parse_ical(
+                "EVENT_UID",
+                "CLASS:PUBLIC CATEGORIES:CATEGORY_ONE DTSTART:19700101T000500Z LAST-MODIFIED:19700101T010500Z",
+            ).unwrap();
+
+        event.validate().unwrap();
+        event.rebuild_indexes().unwrap();
+
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event(event.clone());
+
+        let rdb_calendar_header = RDBCalendarHeader::try_from(&calendar).unwrap();
+
+        assert_eq!(
+            rdb_calendar_header,
+            RDBCalendarHeader(
+                String::from("CALENDAR_UID"),
+                vec![
+                    String::from("UID:CALENDAR_UID"),
+                ],
+            ),
+        );
+
+        let mut loaded_calendar = Calendar::try_from(&rdb_calendar_header).unwrap();
+
+        loaded_calendar.disable_indexes();
+
+        insert_rdb_events(&mut loaded_calendar, &[RDBEvent::try_from(&event).unwrap()]).unwrap();
+
+        assert_eq!(loaded_calendar.indexes_active, false);
+        assert_eq!(loaded_calendar.events.len(), 1);
+
+        loaded_calendar.rebuild_indexes().unwrap();
+        calendar.rebuild_indexes().unwrap();
+
+        assert_eq!(loaded_calendar, calendar);
+
+        assert_eq!(
+            Calendar::try_from(&RDBCalendarHeader(String::from("CALENDAR_UID"), vec![String::from("UID:OTHER_CALENDAR_UID")])).map_err(String::from),
+            Err(String::from("Error at CALENDAR_UID:Inserted calendar UID: OTHER_CALENDAR_UID does not match existing UID: CALENDAR_UID")),
+        );
+    }
+
+    #[test]
+    fn test_event_expirations_rdb_entity() {
+        let mut event = Event::parse_ical("EVENT_UID", "DTSTART:19700101T000500Z").unwrap();
+
+        event.validate().unwrap();
+
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event(event);
+        calendar.set_event_expiration(&String::from("EVENT_UID"), 100);
+
+        let rdb_event_expirations = RDBEventExpirations::from(&calendar);
+
+        assert_eq!(
+            rdb_event_expirations,
+            RDBEventExpirations(vec![(String::from("EVENT_UID"), 100)]),
+        );
+
+        let mut loaded_calendar = calendar.clone();
+
+        loaded_calendar.remove_event_expiration(&String::from("EVENT_UID"));
+
+        assert_eq!(rdb_event_expirations.apply(&mut loaded_calendar), Ok(()));
+        assert_eq!(loaded_calendar, calendar);
+
+        assert_eq!(
+            RDBEventExpirations(vec![(String::from("MISSING_EVENT_UID"), 100)]).apply(&mut loaded_calendar).map_err(String::from),
+            Err(String::from("Error at CALENDAR_UID:Event expiration for: MISSING_EVENT_UID does not match a stored Event")),
+        );
+    }
+
     #[test]
     fn test_parse_invalid_event_occurrence_override_rdb_entity() {
         let invalid_rdb_event_occurrence_override =
@@ -0,0 +1,101 @@
+use std::ffi::c_int;
+
+// The on-disk encoding of a persisted Calendar. From StreamedEvents onwards the format version is embedded at the start of each persisted Calendar (instead of relying solely on the
+// data type encver) so that structural changes can be detected and older encodings migrated
+// forward on load.
+//
+// When changing the encoding, add a new version (and make it CURRENT), and migrate the previous
+// encoding into the new one in rdb_load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RDBFormatVersion {
+    // The entire Calendar serialized as a single RDBCalendar buffer.
+    WholeCalendar = 1,
+
+    // The embedded format version, an RDBCalendarHeader buffer, the Event count, each RDBEvent
+    // buffer, an RDBEventExpirations buffer, then the Calendar generation.
+    StreamedEvents = 2,
+}
+
+impl RDBFormatVersion {
+    pub const CURRENT: Self = Self::StreamedEvents;
+
+    // The Calendar data type encver the embedded format version was introduced in, prior to this
+    // the format version is implied by the encver.
+    pub const EMBEDDED_SINCE_ENCVER: c_int = Self::StreamedEvents as c_int;
+
+    pub fn is_embedded_for_encver(encver: c_int) -> bool {
+        encver >= Self::EMBEDDED_SINCE_ENCVER
+    }
+
+    // Calendars persisted (or dumped via DUMP/MIGRATE) by a newer version of RediCal cannot be
+    // migrated backwards, so are explicitly rejected rather than loaded as garbage.
+    pub fn is_newer_than_current(format_version: u64) -> bool {
+        format_version > Self::CURRENT as u64
+    }
+
+    pub fn newer_than_current_error(format_version: u64) -> String {
+        format!(
+            "Calendar RDB format version: {format_version} is newer than the latest supported: {} (persisted by a newer version of RediCal, upgrade the module to load it)",
+            Self::CURRENT as u64,
+        )
+    }
+}
+
+impl TryFrom<u64> for RDBFormatVersion {
+    type Error = String;
+
+    fn try_from(format_version: u64) -> Result<Self, Self::Error> {
+        match format_version {
+            1 => Ok(Self::WholeCalendar),
+            2 => Ok(Self::StreamedEvents),
+
+            _ if Self::is_newer_than_current(format_version) => {
+                Err(Self::newer_than_current_error(format_version))
+            },
+
+            _ => {
+                Err(format!(
+                    "Unsupported Calendar RDB format version: {format_version} (latest supported: {})",
+                    Self::CURRENT as u64,
+                ))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn test_rdb_format_version_try_from() {
+        assert_eq!(RDBFormatVersion::try_from(1), Ok(RDBFormatVersion::WholeCalendar));
+        assert_eq!(RDBFormatVersion::try_from(2), Ok(RDBFormatVersion::StreamedEvents));
+
+        assert_eq!(
+            RDBFormatVersion::try_from(0),
+            Err(String::from("Unsupported Calendar RDB format version: 0 (latest supported: 2)")),
+        );
+
+        assert_eq!(
+            RDBFormatVersion::try_from(3),
+            Err(String::from("Calendar RDB format version: 3 is newer than the latest supported: 2 (persisted by a newer version of RediCal, upgrade the module to load it)")),
+        );
+    }
+
+    #[test]
+    fn test_rdb_format_version_is_newer_than_current() {
+        assert_eq!(RDBFormatVersion::is_newer_than_current(1), false);
+        assert_eq!(RDBFormatVersion::is_newer_than_current(2), false);
+        assert_eq!(RDBFormatVersion::is_newer_than_current(3), true);
+        assert_eq!(RDBFormatVersion::is_newer_than_current(u64::MAX), true);
+    }
+
+    #[test]
+    fn test_rdb_format_version_is_embedded_for_encver() {
+        assert_eq!(RDBFormatVersion::is_embedded_for_encver(1), false);
+        assert_eq!(RDBFormatVersion::is_embedded_for_encver(2), true);
+    }
+}
@@ -0,0 +1,130 @@
+use redis_module::{raw, Context, ContextFlags, RedisError};
+
+use std::collections::BTreeSet;
+use std::ffi::c_int;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::core::Calendar;
+use crate::commands::{notify_event_del_keyspace_event, remove_event_and_reindex};
+use crate::datatype::CALENDAR_DATA_TYPE;
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
+use crate::utils::get_selected_db;
+use crate::CONFIGURATION_EVENT_EXPIRY_SWEEP_INTERVAL_MS;
+
+// The (database id, key) of each Calendar with Events set to expire (via rdcl.evt_expire), so
+// that the periodic sweep only visits those Calendars instead of scanning the entire keyspace.
+//
+// Calendars are no longer tracked once they have been deleted or have no remaining expirations.
+static EXPIRING_CALENDARS: Mutex<BTreeSet<(c_int, String)>> = Mutex::new(BTreeSet::new());
+
+pub fn track_expiring_calendar(db_id: c_int, calendar_uid: String) {
+    EXPIRING_CALENDARS.lock().unwrap().insert((db_id, calendar_uid));
+}
+
+// Stop tracking the Calendars of the flushed database (or all databases if None), as they no
+// longer exist.
+pub fn untrack_flushed_expiring_calendars(flushed_db_id: Option<c_int>) {
+    EXPIRING_CALENDARS
+        .lock()
+        .unwrap()
+        .retain(|(db_id, _calendar_uid)| flushed_db_id.is_some_and(|flushed_db_id| *db_id != flushed_db_id));
+}
+
+// Schedule the next sweep of expired Events, re-scheduling itself each time it runs.
+pub fn schedule_expired_events_sweep(ctx: &Context) {
+    let sweep_interval_ms = *CONFIGURATION_EVENT_EXPIRY_SWEEP_INTERVAL_MS.lock(ctx) as u64;
+
+    ctx.create_timer(
+        Duration::from_millis(sweep_interval_ms),
+        |ctx: &Context, _data: ()| {
+            sweep_expired_events(ctx);
+
+            schedule_expired_events_sweep(ctx);
+        },
+        (),
+    );
+}
+
+// Remove the expired Events of each tracked Calendar, replicating each removal as rdcl.evt_del
+// and notifying the rdcl.evt_del keyspace event.
+//
+// Replicas do not sweep their own expired Events, relying on the replicated removals from the
+// primary instead (as with Redis key expiry).
+fn sweep_expired_events(ctx: &Context) {
+    let flags = ctx.get_flags();
+
+    if flags.contains(ContextFlags::SLAVE) || flags.contains(ContextFlags::LOADING) {
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+
+    let expiring_calendars: Vec<(c_int, String)> = EXPIRING_CALENDARS.lock().unwrap().iter().cloned().collect();
+
+    if expiring_calendars.is_empty() {
+        return;
+    }
+
+    let selected_db = get_selected_db(ctx);
+
+    for (db_id, calendar_uid) in expiring_calendars {
+        match sweep_expired_calendar_events(ctx, db_id, &calendar_uid, now) {
+            Ok(true) => {},
+
+            Ok(false) => {
+                EXPIRING_CALENDARS.lock().unwrap().remove(&(db_id, calendar_uid));
+            },
+
+            Err(error) => {
+                ctx.log_warning(
+                    format!("Expired events sweep failed for Calendar: {calendar_uid} with error: {error}").as_str()
+                );
+            },
+        }
+    }
+
+    unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, selected_db) };
+}
+
+// Returns whether the Calendar still has Events set to expire (and so should remain tracked).
+fn sweep_expired_calendar_events(ctx: &Context, db_id: c_int, calendar_uid: &str, now: i64) -> Result<bool, RedisError> {
+    unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, db_id) };
+
+    let calendar_key_name = ctx.create_string(calendar_uid);
+
+    let calendar_key = ctx.open_key_writable(&calendar_key_name);
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Ok(false);
+    };
+
+    let mut expired_event_count = 0;
+
+    for event_uid in calendar.get_expired_event_uids(now) {
+        if !remove_event_and_reindex(calendar, &event_uid)? {
+            calendar.remove_event_expiration(&event_uid);
+
+            continue;
+        }
+
+        expired_event_count += 1;
+
+        ctx.replicate("rdcl.evt_del", &[calendar_uid, event_uid.as_str()]);
+
+        notify_event_del_keyspace_event(ctx, &calendar_key_name, &event_uid)?;
+
+        append_changes(ctx, &calendar_key_name, &[Change::new(ChangeType::EventDel, &event_uid)]);
+
+        ctx.log_debug(format!("Expired event: {event_uid} from Calendar: {calendar_uid}").as_str());
+    }
+
+    if expired_event_count > 0 {
+        calendar.bump_generation();
+
+        notify_dirty_query_subscriptions(ctx, &calendar_key_name, calendar);
+    }
+
+    Ok(!calendar.event_expirations.is_empty())
+}
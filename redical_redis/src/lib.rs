@@ -1,11 +1,19 @@
 use lazy_static::lazy_static;
-use redis_module::{redis_module, Context, NotifyEvent, Status, RedisString, RedisGILGuard, configuration::ConfigurationFlags};
+use redis_module::{redis_module, enum_configuration, Context, InfoContext, NotifyEvent, Status, RedisString, RedisGILGuard, configuration::ConfigurationFlags};
+use redis_module::logging::log_warning;
 
 use redical_core as core;
 
 mod datatype;
 mod commands;
 mod utils;
+mod reply;
+mod event_expiry;
+mod change_feed;
+mod query_subscriptions;
+mod server_events;
+mod command_metrics;
+mod scheduled_pruning;
 
 use crate::datatype::CALENDAR_DATA_TYPE;
 
@@ -55,6 +63,12 @@ fn initialize(ctx: &Context, _args: &[RedisString]) -> Status {
         )
     );
 
+    event_expiry::schedule_expired_events_sweep(ctx);
+
+    scheduled_pruning::schedule_pruning_sweep(ctx, std::time::Instant::now());
+
+    server_events::subscribe_to_flush_event(ctx);
+
     Status::Ok
 }
 
@@ -79,12 +93,50 @@ fn on_keyspace_event(ctx: &Context, event_type: NotifyEvent, event: &str, key: &
 
         if ctx.open_key(&calendar_uid).get_value::<core::Calendar>(&CALENDAR_DATA_TYPE).is_ok() {
             notify_rdcl_cal_del_keyspace_event(ctx, &calendar_uid);
+
+            query_subscriptions::unsubscribe_deleted_calendar(utils::get_selected_db(ctx), &calendar_uid.to_string());
         }
     }
 }
 
+// Add the RediCal sections to the INFO output (e.g. INFO RediCal, or INFO everything).
+fn info(ctx: &InfoContext, _for_crash_report: bool) {
+    if let Err(error) = command_metrics::add_info_section(ctx) {
+        log_warning(format!("Failed to add command metrics INFO section: {error}").as_str());
+    }
+}
+
+enum_configuration! {
+    // The detail included in the rdcl.evt_set and rdcl.evo_set keyspace notification payloads:
+    // * minimal - only the UID (and override date string)
+    // * standard - also the resolved LAST-MODIFIED property
+    // * detailed - also the names of the changed properties
+    #[allow(non_camel_case_types)]
+    #[derive(Debug, PartialEq)]
+    pub enum NotificationVerbosity {
+        minimal = 0,
+        standard = 1,
+        detailed = 2,
+    }
+}
+
 lazy_static! {
     static ref CONFIGURATION_ICAL_PARSER_TIMEOUT_MS: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_MAX_EVENTS_PER_CALENDAR: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_MAX_QUERY_RESULTS: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_PAGINATION_COUNT_MAX: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_OCCURRENCE_EXPANSION_MAX: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_OCCURRENCE_EXPANSION_HORIZON_DAYS: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_OCCURRENCE_EXPANSION_SAFETY_HORIZON_YEARS: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_SLOW_EXPANSION_THRESHOLD_MS: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_SLOW_EXPANSION_THRESHOLD_OCCURRENCES: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_EVENT_EXPIRY_GRACE_PERIOD_SECS: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_EVENT_EXPIRY_SWEEP_INTERVAL_MS: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_PRUNE_SWEEP_INTERVAL_MS: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_CHANGE_FEED_MAX_LEN: RedisGILGuard<i64> = RedisGILGuard::default();
+    static ref CONFIGURATION_CHANGE_FEED_ENABLED: RedisGILGuard<bool> = RedisGILGuard::default();
+    static ref CONFIGURATION_NOTIFICATION_VERBOSITY: RedisGILGuard<NotificationVerbosity> = RedisGILGuard::new(NotificationVerbosity::standard);
 }
 
 redis_module! {
@@ -95,24 +147,49 @@ redis_module! {
         CALENDAR_DATA_TYPE
     ],
     init: initialize,
+    info: info,
+    // The trailing firstkey, lastkey, and keystep values declare the calendar key positions so that
+    // Redis Cluster can route commands (and reject cross-slot usage) - any command operating on
+    // multiple calendar keys must declare all of them here (e.g. 1, -1, 1).
     commands:   [
         ["rdcl.evt_set",         commands::redical_event_set,            "write pubsub deny-oom", 1, 1, 1],
+        ["rdcl.evt_mset",        commands::redical_event_mset,           "write pubsub deny-oom", 1, 1, 1],
+        ["rdcl.evt_import",      commands::redical_event_import,         "write pubsub deny-oom", 1, 1, 1],
         ["rdcl.evt_get",         commands::redical_event_get,            "readonly",              1, 1, 1],
         ["rdcl.evt_del",         commands::redical_event_del,            "write pubsub deny-oom", 1, 1, 1],
+        ["rdcl.evt_del_query",   commands::redical_event_del_query,      "write pubsub deny-oom", 1, 1, 1],
+        ["rdcl.evt_expire",      commands::redical_event_expire,         "write pubsub deny-oom", 1, 1, 1],
+        ["rdcl.evt_children",    commands::redical_event_children,       "readonly",              1, 1, 1],
         ["rdcl.evt_list",        commands::redical_event_list,           "readonly",              1, 1, 1],
+        ["rdcl.evt_scan",        commands::redical_event_scan,           "readonly",              1, 1, 1],
         ["rdcl.evt_query",       commands::redical_event_query,          "readonly",              1, 1, 1],
+        ["rdcl.evt_update_query", commands::redical_event_update_query,  "write pubsub deny-oom", 1, 1, 1],
         ["rdcl.evt_prune",       commands::redical_event_prune,          "write pubsub deny-oom", 1, 1, 1],
+        ["rdcl.evi_add",         commands::redical_event_instance_add,   "write pubsub deny-oom", 1, 1, 1],
+        ["rdcl.evi_get",         commands::redical_event_instance_get,   "readonly",              1, 1, 1],
         ["rdcl.evi_list",        commands::redical_event_instance_list,  "readonly",              1, 1, 1],
+        ["rdcl.evi_next",        commands::redical_event_instance_next,  "readonly",              1, 1, 1],
         ["rdcl.evi_query",       commands::redical_event_instance_query, "readonly",              1, 1, 1],
         ["rdcl.evo_get",         commands::redical_event_override_get,   "readonly",              1, 1, 1],
         ["rdcl.evo_set",         commands::redical_event_override_set,   "write pubsub deny-oom", 1, 1, 1],
+        ["rdcl.evo_mset",        commands::redical_event_override_mset,  "write pubsub deny-oom", 1, 1, 1],
         ["rdcl.evo_del",         commands::redical_event_override_del,   "write pubsub deny-oom", 1, 1, 1],
         ["rdcl.evo_list",        commands::redical_event_override_list,  "readonly",              1, 1, 1],
         ["rdcl.evo_prune",       commands::redical_event_override_prune, "write pubsub deny-oom", 1, 1, 1],
         ["rdcl.cal_set",         commands::redical_calendar_set,         "write pubsub deny-oom", 1, 1, 1],
         ["rdcl.cal_get",         commands::redical_calendar_get,         "readonly",              1, 1, 1],
+        ["rdcl.cal_stats",       commands::redical_calendar_stats,       "readonly",              1, 1, 1],
+        ["rdcl.cal_export",      commands::redical_calendar_export,      "readonly",              1, 1, 1],
         ["rdcl.cal_idx_disable", commands::redical_calendar_idx_disable, "write pubsub",          1, 1, 1],
         ["rdcl.cal_idx_rebuild", commands::redical_calendar_idx_rebuild, "write pubsub deny-oom", 1, 1, 1],
+        ["rdcl.cal_idx_verify",  commands::redical_calendar_idx_verify,  "readonly",              1, 1, 1],
+        ["rdcl.cal_idx_terms",   commands::redical_calendar_idx_terms,   "readonly",              1, 1, 1],
+        ["rdcl.query_explain",   commands::redical_query_explain,        "readonly",              1, 1, 1],
+        ["rdcl.query_profile",   commands::redical_query_profile,        "readonly",              1, 1, 1],
+        ["rdcl.query_sub",       commands::redical_query_sub,            "write",                 1, 1, 1],
+        ["rdcl.query_unsub",     commands::redical_query_unsub,          "write",                 1, 1, 1],
+        ["rdcl.query_sub_list",  commands::redical_query_sub_list,       "readonly",              1, 1, 1],
+        ["rdcl.metrics",         commands::redical_metrics,              "readonly",              0, 0, 0],
     ],
     event_handlers: [
         [@GENERIC: on_keyspace_event],
@@ -121,10 +198,27 @@ redis_module! {
     configurations: [
         i64: [
             ["ical-parser-timeout-ms", &*CONFIGURATION_ICAL_PARSER_TIMEOUT_MS, 500, 1, 60000, ConfigurationFlags::DEFAULT, None],
+            ["query-execution-timeout-ms", &*CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS, 5000, 1, 60000, ConfigurationFlags::DEFAULT, None],
+            ["max-events-per-calendar", &*CONFIGURATION_MAX_EVENTS_PER_CALENDAR, 0, 0, i64::MAX, ConfigurationFlags::DEFAULT, None],
+            ["max-query-results", &*CONFIGURATION_MAX_QUERY_RESULTS, 0, 0, i64::MAX, ConfigurationFlags::DEFAULT, None],
+            ["pagination-count-max", &*CONFIGURATION_PAGINATION_COUNT_MAX, 10000, 0, i64::MAX, ConfigurationFlags::DEFAULT, None],
+            ["occurrence-expansion-max", &*CONFIGURATION_OCCURRENCE_EXPANSION_MAX, 0, 0, i64::MAX, ConfigurationFlags::DEFAULT, None],
+            ["occurrence-expansion-horizon-days", &*CONFIGURATION_OCCURRENCE_EXPANSION_HORIZON_DAYS, 0, 0, i64::MAX, ConfigurationFlags::DEFAULT, None],
+            ["occurrence-expansion-safety-horizon-years", &*CONFIGURATION_OCCURRENCE_EXPANSION_SAFETY_HORIZON_YEARS, 0, 0, i64::MAX, ConfigurationFlags::DEFAULT, None],
+            ["slow-expansion-threshold-ms", &*CONFIGURATION_SLOW_EXPANSION_THRESHOLD_MS, 0, 0, i64::MAX, ConfigurationFlags::DEFAULT, None],
+            ["slow-expansion-threshold-occurrences", &*CONFIGURATION_SLOW_EXPANSION_THRESHOLD_OCCURRENCES, 0, 0, i64::MAX, ConfigurationFlags::DEFAULT, None],
+            ["event-expiry-grace-period-secs", &*CONFIGURATION_EVENT_EXPIRY_GRACE_PERIOD_SECS, 0, 0, i64::MAX, ConfigurationFlags::DEFAULT, None],
+            ["event-expiry-sweep-interval-ms", &*CONFIGURATION_EVENT_EXPIRY_SWEEP_INTERVAL_MS, 1000, 10, 3600000, ConfigurationFlags::DEFAULT, None],
+            ["prune-sweep-interval-ms", &*CONFIGURATION_PRUNE_SWEEP_INTERVAL_MS, 60000, 10, 86400000, ConfigurationFlags::DEFAULT, None],
+            ["change-feed-max-len", &*CONFIGURATION_CHANGE_FEED_MAX_LEN, 0, 0, i64::MAX, ConfigurationFlags::DEFAULT, None],
         ],
         string: [],
-        bool: [],
-        enum: [],
+        bool: [
+            ["change-feed-enabled", &*CONFIGURATION_CHANGE_FEED_ENABLED, false, ConfigurationFlags::DEFAULT, None],
+        ],
+        enum: [
+            ["notification-verbosity", &*CONFIGURATION_NOTIFICATION_VERBOSITY, NotificationVerbosity::standard, ConfigurationFlags::DEFAULT, None],
+        ],
         module_args_as_configuration: true,
     ]
 }
@@ -0,0 +1,343 @@
+use redis_module::{raw, Context, NotifyEvent, RedisString, Status, ThreadSafeContext};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::c_int;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use redical_ical::ICalendarComponent;
+
+use crate::core::queries::event_instance_query::EventInstanceQuery;
+use crate::core::queries::query::Query;
+use crate::core::queries::results::{QueryResults, QueryableEntity};
+use crate::core::{Calendar, ExecutionDeadline};
+use crate::datatype::CALENDAR_DATA_TYPE;
+use crate::utils::get_selected_db;
+use crate::CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS;
+
+// An event instance query registered against a Calendar (via rdcl.query_sub), along with a
+// fingerprint of it's most recently evaluated results, and the Calendar generation they were
+// evaluated at.
+//
+// A missing fingerprint represents results which could not be evaluated (e.g. the query exceeded
+// the query execution timeout), which are always considered changed.
+#[derive(Debug, Clone)]
+pub struct QuerySubscription {
+    pub query: EventInstanceQuery,
+    pub results_fingerprint: Option<u64>,
+    pub evaluated_generation: u64,
+}
+
+impl QuerySubscription {
+    pub fn new(query: EventInstanceQuery, calendar: &Calendar, execution_deadline: &ExecutionDeadline) -> Self {
+        let results_fingerprint = evaluate_results_fingerprint(&query, calendar, execution_deadline);
+
+        QuerySubscription {
+            query,
+            results_fingerprint,
+            evaluated_generation: calendar.generation,
+        }
+    }
+
+    // Re-evaluate the query against the Calendar if it has been mutated since last evaluated (the
+    // generation has since changed), returning whether the results changed.
+    pub fn refresh(&mut self, calendar: &Calendar, execution_deadline: &ExecutionDeadline) -> bool {
+        if calendar.generation == self.evaluated_generation {
+            return false;
+        }
+
+        let results_fingerprint = evaluate_results_fingerprint(&self.query, calendar, execution_deadline);
+
+        let is_changed = results_fingerprint.is_none() || results_fingerprint != self.results_fingerprint;
+
+        self.results_fingerprint = results_fingerprint;
+        self.evaluated_generation = calendar.generation;
+
+        is_changed
+    }
+}
+
+fn evaluate_results_fingerprint(query: &EventInstanceQuery, calendar: &Calendar, execution_deadline: &ExecutionDeadline) -> Option<u64> {
+    let query_results = query.clone().execute_with_deadline(calendar, execution_deadline).ok()?;
+
+    Some(fingerprint_query_results(&query_results))
+}
+
+// Hash the ordering and rendered content of each query result, so that results can be compared
+// without retaining them.
+pub fn fingerprint_query_results<T: QueryableEntity>(query_results: &QueryResults<T>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    for query_result in &query_results.results {
+        query_result.result_ordering.to_rendered_content_lines().hash(&mut hasher);
+        query_result.result.to_rendered_content_lines().hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+// The query subscriptions (by name) registered against each Calendar (by database id and key).
+//
+// Subscriptions are held in memory only (like pub/sub subscriptions), so they are not persisted,
+// replicated, or retained once the Calendar has been deleted or flushed.
+static QUERY_SUBSCRIPTIONS: Mutex<BTreeMap<(c_int, String), BTreeMap<String, QuerySubscription>>> = Mutex::new(BTreeMap::new());
+
+// The (database id, key) of each mutated Calendar with query subscriptions awaiting re-evaluation
+// in the background, and whether the background re-evaluation is already underway.
+struct PendingEvaluations {
+    calendars: BTreeSet<(c_int, String)>,
+    is_scheduled: bool,
+}
+
+static PENDING_EVALUATIONS: Mutex<PendingEvaluations> = Mutex::new(PendingEvaluations { calendars: BTreeSet::new(), is_scheduled: false });
+
+// Register (or replace) the named query subscription against the Calendar.
+pub fn subscribe(db_id: c_int, calendar_uid: String, name: String, query_subscription: QuerySubscription) {
+    QUERY_SUBSCRIPTIONS
+        .lock()
+        .unwrap()
+        .entry((db_id, calendar_uid))
+        .or_default()
+        .insert(name, query_subscription);
+}
+
+// Remove the named query subscription from the Calendar, returning false if it does not exist.
+pub fn unsubscribe(db_id: c_int, calendar_uid: &str, name: &str) -> bool {
+    let mut query_subscriptions = QUERY_SUBSCRIPTIONS.lock().unwrap();
+
+    let calendar_key = (db_id, calendar_uid.to_owned());
+
+    let Some(calendar_query_subscriptions) = query_subscriptions.get_mut(&calendar_key) else {
+        return false;
+    };
+
+    let is_removed = calendar_query_subscriptions.remove(name).is_some();
+
+    if calendar_query_subscriptions.is_empty() {
+        query_subscriptions.remove(&calendar_key);
+    }
+
+    is_removed
+}
+
+// The names of the query subscriptions registered against the Calendar.
+pub fn get_subscription_names(db_id: c_int, calendar_uid: &str) -> Vec<String> {
+    QUERY_SUBSCRIPTIONS
+        .lock()
+        .unwrap()
+        .get(&(db_id, calendar_uid.to_owned()))
+        .map(|calendar_query_subscriptions| calendar_query_subscriptions.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+// Remove the query subscriptions of the deleted (or evicted) Calendar.
+pub fn unsubscribe_deleted_calendar(db_id: c_int, calendar_uid: &str) {
+    QUERY_SUBSCRIPTIONS.lock().unwrap().remove(&(db_id, calendar_uid.to_owned()));
+}
+
+// Remove the query subscriptions of the Calendars of the flushed database (or all databases if
+// None), as they no longer exist.
+pub fn unsubscribe_flushed_calendars(flushed_db_id: Option<c_int>) {
+    QUERY_SUBSCRIPTIONS
+        .lock()
+        .unwrap()
+        .retain(|(db_id, _calendar_uid), _| flushed_db_id.is_some_and(|flushed_db_id| *db_id != flushed_db_id));
+}
+
+// Mark the query subscriptions registered against the mutated Calendar as dirty, scheduling their
+// re-evaluation in the background (dispatching the rdcl.query_dirty:<name> keyspace event for each
+// whose results have changed), so that the write which triggered it is not stalled evaluating them.
+//
+// Calendars with disabled indexes cannot be queried, so are re-evaluated once rebuilt instead.
+pub fn notify_dirty_query_subscriptions(ctx: &Context, calendar_uid: &RedisString, calendar: &Calendar) {
+    if !calendar.indexes_active {
+        return;
+    }
+
+    let calendar_key = (get_selected_db(ctx), calendar_uid.to_string());
+
+    if !QUERY_SUBSCRIPTIONS.lock().unwrap().contains_key(&calendar_key) {
+        return;
+    }
+
+    let mut pending_evaluations = PENDING_EVALUATIONS.lock().unwrap();
+
+    pending_evaluations.calendars.insert(calendar_key);
+
+    if !pending_evaluations.is_scheduled {
+        pending_evaluations.is_scheduled = true;
+
+        spawn_background_evaluation();
+    }
+}
+
+fn spawn_background_evaluation() {
+    std::thread::spawn(move || {
+        let thread_ctx = ThreadSafeContext::new();
+
+        // Only hold the lock for a single Calendar at a time so that other commands are able to
+        // run in between each evaluation.
+        loop {
+            let ctx = thread_ctx.lock();
+
+            let Some((db_id, calendar_uid)) = take_pending_evaluation() else {
+                break;
+            };
+
+            evaluate_query_subscriptions(&ctx, db_id, &calendar_uid);
+        }
+    });
+}
+
+// Returns the next Calendar awaiting re-evaluation, otherwise concluding the background
+// re-evaluation (so that the next mutation schedules another).
+fn take_pending_evaluation() -> Option<(c_int, String)> {
+    let mut pending_evaluations = PENDING_EVALUATIONS.lock().unwrap();
+
+    let pending_evaluation = pending_evaluations.calendars.pop_first();
+
+    if pending_evaluation.is_none() {
+        pending_evaluations.is_scheduled = false;
+    }
+
+    pending_evaluation
+}
+
+fn evaluate_query_subscriptions(ctx: &Context, db_id: c_int, calendar_uid: &str) {
+    unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, db_id) };
+
+    let calendar_key_name = ctx.create_string(calendar_uid);
+    let calendar_key = ctx.open_key(&calendar_key_name);
+
+    // The Calendar may have since been deleted, replaced, or had it's indexes disabled.
+    let Ok(Some(calendar)) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE) else {
+        return;
+    };
+
+    if !calendar.indexes_active {
+        return;
+    }
+
+    let mut query_subscriptions = QUERY_SUBSCRIPTIONS.lock().unwrap();
+
+    let Some(calendar_query_subscriptions) = query_subscriptions.get_mut(&(db_id, calendar_uid.to_owned())) else {
+        return;
+    };
+
+    // Each query is evaluated under the query execution timeout, so that an expensive subscription
+    // cannot hold the lock indefinitely.
+    let query_execution_timeout_ms = *CONFIGURATION_QUERY_EXECUTION_TIMEOUT_MS.lock(ctx) as u64;
+
+    for (name, query_subscription) in calendar_query_subscriptions.iter_mut() {
+        let execution_deadline = ExecutionDeadline::new(Duration::from_millis(query_execution_timeout_ms));
+
+        if !query_subscription.refresh(calendar, &execution_deadline) {
+            continue;
+        }
+
+        let event_message = format!("rdcl.query_dirty:{name}");
+
+        if ctx.notify_keyspace_event(NotifyEvent::MODULE, event_message.as_str(), &calendar_key_name) == Status::Err {
+            ctx.log_warning(
+                format!("Notify keyspace event \"rdcl.query_dirty\" for calendar: \"{calendar_uid}\" query subscription: \"{name}\" failed").as_str()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    use std::str::FromStr;
+
+    use crate::core::Event;
+
+    fn build_event(event_uid: &str, event_ical: &str) -> Event {
+        let mut event = Event::parse_ical(event_uid, event_ical).unwrap();
+
+        event.validate().unwrap();
+
+        event
+    }
+
+    fn build_calendar(event_ical: &str) -> Calendar {
+        let mut calendar = Calendar::new(String::from("CALENDAR_UID"));
+
+        calendar.insert_event_and_reindex(build_event("EVENT_UID", event_ical)).unwrap();
+
+        calendar
+    }
+
+    #[test]
+    fn test_query_subscription_refresh() {
+        let mut calendar = build_calendar("DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3 CATEGORIES:CATEGORY_ONE");
+
+        let query = EventInstanceQuery::from_str("X-CATEGORIES:CATEGORY_ONE").unwrap();
+
+        let execution_deadline = ExecutionDeadline::default();
+
+        let mut query_subscription = QuerySubscription::new(query, &calendar, &execution_deadline);
+
+        assert!(query_subscription.results_fingerprint.is_some());
+
+        // Unchanged results.
+        assert_eq!(query_subscription.refresh(&calendar, &execution_deadline), false);
+
+        calendar.insert_event_and_reindex(build_event("OTHER_EVENT_UID", "DTSTART:20210105T183000Z CATEGORIES:CATEGORY_TWO")).unwrap();
+        calendar.bump_generation();
+
+        // Mutation not affecting the results.
+        assert_eq!(query_subscription.refresh(&calendar, &execution_deadline), false);
+        assert_eq!(query_subscription.evaluated_generation, 1);
+
+        calendar.insert_event_and_reindex(build_event("EVENT_UID", "DTSTART:20210105T183000Z RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=2 CATEGORIES:CATEGORY_ONE")).unwrap();
+
+        // Mutation removing an occurrence from the results, but not yet reflected in the
+        // generation, so not re-evaluated.
+        assert_eq!(query_subscription.refresh(&calendar, &execution_deadline), false);
+
+        calendar.bump_generation();
+
+        assert_eq!(query_subscription.refresh(&calendar, &execution_deadline), true);
+        assert_eq!(query_subscription.refresh(&calendar, &execution_deadline), false);
+        assert_eq!(query_subscription.evaluated_generation, 2);
+    }
+
+    #[test]
+    fn test_subscribe_unsubscribe() {
+        let calendar = build_calendar("DTSTART:20210105T183000Z CATEGORIES:CATEGORY_ONE");
+
+        let query_subscription =
+            QuerySubscription::new(
+                EventInstanceQuery::from_str("X-CATEGORIES:CATEGORY_ONE").unwrap(),
+                &calendar,
+                &ExecutionDeadline::default(),
+            );
+
+        subscribe(10, String::from("SUBSCRIBED_CALENDAR_UID"), String::from("QUERY_TWO"), query_subscription.clone());
+        subscribe(10, String::from("SUBSCRIBED_CALENDAR_UID"), String::from("QUERY_ONE"), query_subscription.clone());
+        subscribe(11, String::from("SUBSCRIBED_CALENDAR_UID"), String::from("QUERY_ONE"), query_subscription);
+
+        assert_eq!(get_subscription_names(10, "SUBSCRIBED_CALENDAR_UID"), vec![String::from("QUERY_ONE"), String::from("QUERY_TWO")]);
+
+        assert_eq!(unsubscribe(10, "SUBSCRIBED_CALENDAR_UID", "QUERY_ONE"), true);
+        assert_eq!(unsubscribe(10, "SUBSCRIBED_CALENDAR_UID", "QUERY_ONE"), false);
+        assert_eq!(unsubscribe(10, "OTHER_CALENDAR_UID", "QUERY_TWO"), false);
+
+        assert_eq!(get_subscription_names(10, "SUBSCRIBED_CALENDAR_UID"), vec![String::from("QUERY_TWO")]);
+
+        unsubscribe_flushed_calendars(Some(10));
+
+        assert_eq!(get_subscription_names(10, "SUBSCRIBED_CALENDAR_UID"), Vec::<String>::new());
+        assert_eq!(get_subscription_names(11, "SUBSCRIBED_CALENDAR_UID"), vec![String::from("QUERY_ONE")]);
+
+        unsubscribe_deleted_calendar(11, "SUBSCRIBED_CALENDAR_UID");
+
+        assert_eq!(get_subscription_names(11, "SUBSCRIBED_CALENDAR_UID"), Vec::<String>::new());
+    }
+}
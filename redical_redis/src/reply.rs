@@ -0,0 +1,468 @@
+use std::collections::{BTreeSet, HashMap};
+use std::collections::hash_map::Entry;
+
+use redis_module::{Context, ContextFlags, RedisError, RedisString, RedisValue};
+use redis_module::redisvalue::RedisValueKey;
+
+use redical_ical::{ICalendarComponent, ICalendarEntity, RenderingContext};
+use redical_ical::content_line::ContentLine;
+use redical_ical::properties::ICalendarDateTimeProperty;
+use redical_ical::values::date_time::DateTime;
+
+use crate::core::{EventInstance, render_json_object, render_json_object_from_content_lines};
+use crate::core::queries::grouped_results::EventInstanceGroup;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplyFormat {
+    // A flat array of the rendered iCalendar content lines.
+    ContentLines,
+    // A map of each property name (with params) to its value (RESP3 only).
+    Map,
+    // A JSON object of each property name to its value (with params nested).
+    Json,
+}
+
+impl ReplyFormat {
+    // Replies default to maps if the client calling the command has negotiated the RESP3 protocol
+    // (via HELLO 3), otherwise the content lines.
+    pub fn from_context(ctx: &Context) -> Self {
+        if ctx.get_flags().contains(ContextFlags::FLAGS_RESP3) {
+            return ReplyFormat::Map;
+        }
+
+        ReplyFormat::ContentLines
+    }
+
+    // Extract the optional trailing `OUTPUT <ICAL|JSON>` arguments, falling back to the default
+    // reply format for the client if not provided.
+    pub fn from_output_args(ctx: &Context, args: &mut Vec<RedisString>) -> Result<Self, RedisError> {
+        let output_keyword_index = args.len().saturating_sub(2);
+
+        let is_output_provided =
+            args.len() >= 2 &&
+            args[output_keyword_index]
+                .try_as_str()
+                .is_ok_and(|arg| arg.eq_ignore_ascii_case("OUTPUT"));
+
+        if !is_output_provided {
+            return Ok(ReplyFormat::from_context(ctx));
+        }
+
+        let output_format = args.split_off(output_keyword_index).remove(1);
+
+        match output_format.try_as_str()?.to_ascii_uppercase().as_str() {
+            "ICAL" => Ok(ReplyFormat::from_context(ctx)),
+            "JSON" => Ok(ReplyFormat::Json),
+
+            _ => {
+                Err(RedisError::String(format!("Unknown OUTPUT format: {output_format} (expected ICAL or JSON)")))
+            },
+        }
+    }
+}
+
+// Extract the optional trailing flag argument (e.g. `WITHPAGINATION`), returning whether it was
+// provided.
+pub fn extract_trailing_flag_arg(args: &mut Vec<RedisString>, flag: &str) -> bool {
+    let is_flag_provided =
+        args.len() >= 2 &&
+        args
+            .last()
+            .is_some_and(|arg| arg.try_as_str().is_ok_and(|arg| arg.eq_ignore_ascii_case(flag)));
+
+    if is_flag_provided {
+        args.pop();
+    }
+
+    is_flag_provided
+}
+
+/// The pagination metadata of a query, replied within the header preceding the query results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaginationHeader {
+    pub total_count: usize,
+    pub is_total_count_capped: bool,
+    pub offset: usize,
+    pub limit: usize,
+    pub generation: u64,
+}
+
+impl PaginationHeader {
+    // The total count is that of the results returned by the count query (see
+    // `Query::build_count_query`), which returns one beyond the max count if there were more.
+    pub fn new(counted_results: usize, max_count: Option<usize>, offset: usize, limit: usize, generation: u64) -> Self {
+        let is_total_count_capped = max_count.is_some_and(|max_count| counted_results > max_count);
+
+        PaginationHeader {
+            total_count: max_count.map_or(counted_results, |max_count| counted_results.min(max_count)),
+            is_total_count_capped,
+            offset,
+            limit,
+            generation,
+        }
+    }
+
+    fn to_key_value_pairs(&self) -> [(&'static str, RedisValue); 5] {
+        [
+            ("total", RedisValue::Integer(self.total_count as i64)),
+            ("total_capped", RedisValue::Bool(self.is_total_count_capped)),
+            ("offset", RedisValue::Integer(self.offset as i64)),
+            ("limit", RedisValue::Integer(self.limit as i64)),
+            ("generation", RedisValue::Integer(self.generation as i64)),
+        ]
+    }
+}
+
+/// The metadata of a query, replied as a header preceding the query results only when requested
+/// (`WITHHEADER` or `WITHPAGINATION`), so that the shape of the reply is determined solely by the
+/// arguments provided.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryResultsHeader {
+    pub pagination: Option<PaginationHeader>,
+    // Cut short by the max query results config.
+    pub is_truncated: bool,
+    // Potentially missing occurrences cut short by the occurrence expansion limits.
+    pub is_partial: bool,
+    // Degenerate conditions in the query (explaining why it matched nothing).
+    pub warnings: Vec<String>,
+}
+
+impl QueryResultsHeader {
+    pub fn to_redis_value(&self) -> RedisValue {
+        let warnings =
+            self.warnings
+                .iter()
+                .map(|warning| RedisValue::SimpleString(warning.to_owned()))
+                .collect();
+
+        let key_value_pairs =
+            self.pagination
+                .iter()
+                .flat_map(PaginationHeader::to_key_value_pairs)
+                .chain([
+                    ("truncated", RedisValue::Bool(self.is_truncated)),
+                    ("partial", RedisValue::Bool(self.is_partial)),
+                    ("warnings", RedisValue::Array(warnings)),
+                ]);
+
+        RedisValue::Array(
+            key_value_pairs
+                .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+                .collect()
+        )
+    }
+
+    // Precede the query results with the header.
+    pub fn prepend_to(&self, query_result_items: &mut Vec<RedisValue>) {
+        query_result_items.insert(0, self.to_redis_value());
+    }
+
+    // Log what the header would have flagged when it was not requested, so that it is not lost
+    // entirely (partial results are always logged as a warning by the query itself).
+    pub fn log(&self, ctx: &Context, command_name: &str, calendar_uid: &str) {
+        if self.is_truncated {
+            ctx.log_notice(
+                format!(
+                    "{command_name}: query results truncated to max query results -- calendar_uid: {calendar_uid}",
+                ).as_str()
+            );
+        }
+
+        for warning in &self.warnings {
+            ctx.log_notice(
+                format!(
+                    "{command_name}: query warning: {warning} -- calendar_uid: {calendar_uid}",
+                ).as_str()
+            );
+        }
+    }
+}
+
+// Serialize an iCalendar component into either a flat array of the rendered iCalendar content
+// lines, a map of each property name (with params) to its value, or a JSON object string.
+pub fn icalendar_component_to_redis_value<I: ICalendarComponent>(component: &I, rendering_context: Option<&RenderingContext>, reply_format: ReplyFormat) -> RedisValue {
+    if reply_format == ReplyFormat::Json {
+        return RedisValue::BulkString(render_json_object(component, rendering_context));
+    }
+
+    content_line_set_to_redis_value(component.to_content_line_set_with_context(rendering_context), reply_format)
+}
+
+// Serialize a set of content lines in the same shape as `icalendar_component_to_redis_value`.
+pub fn content_line_set_to_redis_value(content_lines: BTreeSet<ContentLine>, reply_format: ReplyFormat) -> RedisValue {
+    match reply_format {
+        ReplyFormat::ContentLines => {
+            RedisValue::Array(
+                content_lines
+                    .into_iter()
+                    .map(|content_line| RedisValue::SimpleString(content_line.render_ical()))
+                    .collect()
+            )
+        },
+
+        ReplyFormat::Map => {
+            content_lines_to_redis_value_map(content_lines)
+        },
+
+        ReplyFormat::Json => {
+            RedisValue::BulkString(render_json_object_from_content_lines(content_lines))
+        },
+    }
+}
+
+// Serialize the event instances grouped by their event UID (`X-GROUP-BY:UID`) into an array of
+// each group, with the base event properties included once, followed by each event instance
+// (only including the properties differing from the base event).
+pub fn event_instance_groups_to_redis_value(event_instance_groups: Vec<EventInstanceGroup>, rendering_context: Option<&RenderingContext>, reply_format: ReplyFormat) -> Vec<RedisValue> {
+    event_instance_groups
+        .into_iter()
+        .map(|event_instance_group| {
+            let event_instances =
+                event_instance_group.event_instances
+                    .into_iter()
+                    .map(|(result_ordering, event_instance_properties)| {
+                        RedisValue::Array(vec![
+                            icalendar_component_to_redis_value(&result_ordering, rendering_context, reply_format),
+                            content_line_set_to_redis_value(event_instance_properties, reply_format),
+                        ])
+                    })
+                    .collect();
+
+            let key_value_pairs = [
+                ("uid", RedisValue::BulkString(event_instance_group.uid)),
+                ("event", content_line_set_to_redis_value(event_instance_group.base_properties, reply_format)),
+                ("instances", RedisValue::Array(event_instances)),
+            ];
+
+            RedisValue::Array(
+                key_value_pairs
+                    .into_iter()
+                    .flat_map(|(key, value)| [RedisValue::SimpleStringStatic(key), value])
+                    .collect()
+            )
+        })
+        .collect()
+}
+
+// Serialize event instances into either an array of each event instance, or a map of each event
+// instance keyed by its RECURRENCE-ID (RESP3).
+pub fn event_instances_to_redis_value(event_instances: impl Iterator<Item = EventInstance>, reply_format: ReplyFormat) -> RedisValue {
+    if reply_format != ReplyFormat::Map {
+        return RedisValue::Array(
+            event_instances
+                .map(|event_instance| icalendar_component_to_redis_value(&event_instance, None, reply_format))
+                .collect()
+        );
+    }
+
+    RedisValue::Map(
+        event_instances
+            .map(|event_instance| {
+                // The RECURRENCE-ID of an event instance is always its UTC DTSTART.
+                let recurrence_id =
+                    DateTime::from(event_instance.dtstart.get_utc_timestamp())
+                        .render_formatted_date_time(None);
+
+                (
+                    RedisValueKey::String(recurrence_id),
+                    icalendar_component_to_redis_value(&event_instance, None, reply_format),
+                )
+            })
+            .collect()
+    )
+}
+
+// Properties which occur multiple times with the same params (e.g. RELATED-TO) have all of their
+// values collected into an array.
+fn content_lines_to_redis_value_map(content_lines: BTreeSet<ContentLine>) -> RedisValue {
+    let mut redis_value_map: HashMap<RedisValueKey, RedisValue> = HashMap::new();
+
+    for ContentLine(name, params, value) in content_lines {
+        let key = RedisValueKey::String(format!("{name}{}", params.render_ical()));
+        let value = RedisValue::SimpleString(value);
+
+        match redis_value_map.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            },
+
+            Entry::Occupied(mut entry) => {
+                match entry.get_mut() {
+                    RedisValue::Array(values) => {
+                        values.push(value);
+                    },
+
+                    existing_value => {
+                        let existing_value = std::mem::replace(existing_value, RedisValue::Null);
+
+                        entry.insert(RedisValue::Array(vec![existing_value, value]));
+                    },
+                }
+            },
+        }
+    }
+
+    RedisValue::Map(redis_value_map)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::core::Event;
+
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn test_icalendar_component_to_redis_value() {
+        let event =
+            Event::parse_ical(
+                "EVENT_UID",
+                "DTSTART;TZID=Europe/London:20210105T170000 LAST-MODIFIED:20210501T090000Z RELATED-TO;RELTYPE=PARENT:PARENT_UID_ONE RELATED-TO;RELTYPE=PARENT:PARENT_UID_TWO SUMMARY:Event summary",
+            ).unwrap();
+
+        assert_eq!(
+            icalendar_component_to_redis_value(&event, None, ReplyFormat::ContentLines),
+            RedisValue::Array(vec![
+                RedisValue::SimpleString(String::from("DTSTART;TZID=Europe/London:20210105T170000")),
+                RedisValue::SimpleString(String::from("LAST-MODIFIED:20210501T090000Z")),
+                RedisValue::SimpleString(String::from("RELATED-TO;RELTYPE=PARENT:PARENT_UID_ONE")),
+                RedisValue::SimpleString(String::from("RELATED-TO;RELTYPE=PARENT:PARENT_UID_TWO")),
+                RedisValue::SimpleString(String::from("SUMMARY:Event summary")),
+                RedisValue::SimpleString(String::from("UID:EVENT_UID")),
+            ]),
+        );
+
+        assert_eq!(
+            icalendar_component_to_redis_value(&event, None, ReplyFormat::Map),
+            RedisValue::Map(HashMap::from([
+                (
+                    RedisValueKey::String(String::from("DTSTART;TZID=Europe/London")),
+                    RedisValue::SimpleString(String::from("20210105T170000")),
+                ),
+                (
+                    RedisValueKey::String(String::from("LAST-MODIFIED")),
+                    RedisValue::SimpleString(String::from("20210501T090000Z")),
+                ),
+                (
+                    RedisValueKey::String(String::from("RELATED-TO;RELTYPE=PARENT")),
+                    RedisValue::Array(vec![
+                        RedisValue::SimpleString(String::from("PARENT_UID_ONE")),
+                        RedisValue::SimpleString(String::from("PARENT_UID_TWO")),
+                    ]),
+                ),
+                (
+                    RedisValueKey::String(String::from("SUMMARY")),
+                    RedisValue::SimpleString(String::from("Event summary")),
+                ),
+                (
+                    RedisValueKey::String(String::from("UID")),
+                    RedisValue::SimpleString(String::from("EVENT_UID")),
+                ),
+            ])),
+        );
+    }
+
+    #[test]
+    fn test_pagination_header() {
+        assert_eq!(
+            PaginationHeader::new(25, Some(100), 10, 5, 3),
+            PaginationHeader {
+                total_count: 25,
+                is_total_count_capped: false,
+                offset: 10,
+                limit: 5,
+                generation: 3,
+            },
+        );
+
+        // The count query returns one beyond the max count when there are more results.
+        assert_eq!(
+            PaginationHeader::new(101, Some(100), 0, 50, 0),
+            PaginationHeader {
+                total_count: 100,
+                is_total_count_capped: true,
+                offset: 0,
+                limit: 50,
+                generation: 0,
+            },
+        );
+    }
+
+    #[test]
+    fn test_query_results_header() {
+        // The header is prepended even when there is nothing remarkable to flag, so that the
+        // shape of the reply is consistent.
+        let mut query_result_items = vec![RedisValue::Array(vec![])];
+
+        QueryResultsHeader::default().prepend_to(&mut query_result_items);
+
+        assert_eq!(
+            query_result_items,
+            vec![
+                RedisValue::Array(vec![
+                    RedisValue::SimpleStringStatic("truncated"),
+                    RedisValue::Bool(false),
+                    RedisValue::SimpleStringStatic("partial"),
+                    RedisValue::Bool(false),
+                    RedisValue::SimpleStringStatic("warnings"),
+                    RedisValue::Array(vec![]),
+                ]),
+                RedisValue::Array(vec![]),
+            ],
+        );
+
+        let mut query_result_items = vec![RedisValue::Array(vec![])];
+
+        QueryResultsHeader {
+            pagination: None,
+            is_truncated: true,
+            is_partial: false,
+            warnings: vec![String::from("UID:A AND UID:B can never both be satisfied")],
+        }.prepend_to(&mut query_result_items);
+
+        assert_eq!(
+            query_result_items,
+            vec![
+                RedisValue::Array(vec![
+                    RedisValue::SimpleStringStatic("truncated"),
+                    RedisValue::Bool(true),
+                    RedisValue::SimpleStringStatic("partial"),
+                    RedisValue::Bool(false),
+                    RedisValue::SimpleStringStatic("warnings"),
+                    RedisValue::Array(vec![
+                        RedisValue::SimpleString(String::from("UID:A AND UID:B can never both be satisfied")),
+                    ]),
+                ]),
+                RedisValue::Array(vec![]),
+            ],
+        );
+
+        assert_eq!(
+            QueryResultsHeader {
+                pagination: Some(PaginationHeader::new(1000, None, 0, 50, 7)),
+                is_truncated: false,
+                is_partial: true,
+                warnings: vec![],
+            }.to_redis_value(),
+            RedisValue::Array(vec![
+                RedisValue::SimpleStringStatic("total"),
+                RedisValue::Integer(1000),
+                RedisValue::SimpleStringStatic("total_capped"),
+                RedisValue::Bool(false),
+                RedisValue::SimpleStringStatic("offset"),
+                RedisValue::Integer(0),
+                RedisValue::SimpleStringStatic("limit"),
+                RedisValue::Integer(50),
+                RedisValue::SimpleStringStatic("generation"),
+                RedisValue::Integer(7),
+                RedisValue::SimpleStringStatic("truncated"),
+                RedisValue::Bool(false),
+                RedisValue::SimpleStringStatic("partial"),
+                RedisValue::Bool(true),
+                RedisValue::SimpleStringStatic("warnings"),
+                RedisValue::Array(vec![]),
+            ]),
+        );
+    }
+}
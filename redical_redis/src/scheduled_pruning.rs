@@ -0,0 +1,198 @@
+use redis_module::{raw, Context, ContextFlags, RedisError};
+
+use std::collections::BTreeSet;
+use std::ffi::c_int;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use redical_ical::values::date_time::DateTime;
+
+use crate::change_feed::{append_changes, Change, ChangeType};
+use crate::commands::{
+    notify_event_override_prune_keyspace_event,
+    notify_event_prune_keyspace_event,
+    prune_calendar_events_overrides,
+    prune_events_and_reindex,
+};
+use crate::core::Calendar;
+use crate::datatype::CALENDAR_DATA_TYPE;
+use crate::query_subscriptions::notify_dirty_query_subscriptions;
+use crate::utils::get_selected_db;
+use crate::CONFIGURATION_PRUNE_SWEEP_INTERVAL_MS;
+
+// Events and event occurrence overrides ending between this (the Unix epoch) and the retention
+// cutoff are pruned.
+const PRUNE_FROM_TIMESTAMP: i64 = 0;
+
+// The (database id, key) of each Calendar declaring a retention period (via X-PRUNE-OLDER-THAN),
+// so that the periodic sweep only visits those Calendars instead of scanning the entire keyspace.
+//
+// Calendars are no longer tracked once they have been deleted or no longer declare a retention
+// period.
+static PRUNING_CALENDARS: Mutex<BTreeSet<(c_int, String)>> = Mutex::new(BTreeSet::new());
+
+pub fn track_pruning_calendar(db_id: c_int, calendar_uid: String) {
+    PRUNING_CALENDARS.lock().unwrap().insert((db_id, calendar_uid));
+}
+
+// Stop tracking the Calendars of the flushed database (or all databases if None), as they no
+// longer exist.
+pub fn untrack_flushed_pruning_calendars(flushed_db_id: Option<c_int>) {
+    PRUNING_CALENDARS
+        .lock()
+        .unwrap()
+        .retain(|(db_id, _calendar_uid)| flushed_db_id.is_some_and(|flushed_db_id| *db_id != flushed_db_id));
+}
+
+// The longest the sweep waits before checking whether it is due again, so that changes to the
+// (potentially long) configured sweep interval take effect promptly.
+const PRUNE_SWEEP_CHECK_INTERVAL_MAX_MS: u64 = 1000;
+
+// Schedule the next check of whether the sweep of Calendars to prune is due (since it last swept),
+// re-scheduling itself each time it runs.
+pub fn schedule_pruning_sweep(ctx: &Context, last_swept_at: Instant) {
+    let sweep_interval_ms = *CONFIGURATION_PRUNE_SWEEP_INTERVAL_MS.lock(ctx) as u64;
+
+    ctx.create_timer(
+        Duration::from_millis(sweep_interval_ms.min(PRUNE_SWEEP_CHECK_INTERVAL_MAX_MS)),
+        |ctx: &Context, last_swept_at: Instant| {
+            let sweep_interval_ms = *CONFIGURATION_PRUNE_SWEEP_INTERVAL_MS.lock(ctx) as u64;
+
+            if last_swept_at.elapsed() < Duration::from_millis(sweep_interval_ms) {
+                schedule_pruning_sweep(ctx, last_swept_at);
+
+                return;
+            }
+
+            sweep_pruning_calendars(ctx);
+
+            schedule_pruning_sweep(ctx, Instant::now());
+        },
+        last_swept_at,
+    );
+}
+
+// Prune each tracked Calendar of the events and event occurrence overrides ending before it's
+// retention cutoff, replicating each as rdcl.evt_prune and rdcl.evo_prune (with the resolved date
+// range) and notifying their keyspace events.
+//
+// Replicas do not prune their own Calendars, relying on the replicated prunes from the primary
+// instead (as with event expiry).
+fn sweep_pruning_calendars(ctx: &Context) {
+    let flags = ctx.get_flags();
+
+    if flags.contains(ContextFlags::SLAVE) || flags.contains(ContextFlags::LOADING) {
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+
+    let pruning_calendars: Vec<(c_int, String)> = PRUNING_CALENDARS.lock().unwrap().iter().cloned().collect();
+
+    if pruning_calendars.is_empty() {
+        return;
+    }
+
+    let selected_db = get_selected_db(ctx);
+
+    for (db_id, calendar_uid) in pruning_calendars {
+        match prune_calendar(ctx, db_id, &calendar_uid, now) {
+            Ok(true) => {},
+
+            Ok(false) => {
+                PRUNING_CALENDARS.lock().unwrap().remove(&(db_id, calendar_uid));
+            },
+
+            Err(error) => {
+                ctx.log_warning(
+                    format!("Scheduled pruning failed for Calendar: {calendar_uid} with error: {error}").as_str()
+                );
+            },
+        }
+    }
+
+    unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, selected_db) };
+}
+
+// Returns whether the Calendar still declares a retention period (and so should remain tracked).
+fn prune_calendar(ctx: &Context, db_id: c_int, calendar_uid: &str, now: i64) -> Result<bool, RedisError> {
+    unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, db_id) };
+
+    let calendar_key_name = ctx.create_string(calendar_uid);
+
+    let calendar_key = ctx.open_key_writable(&calendar_key_name);
+
+    let Some(calendar) = calendar_key.get_value::<Calendar>(&CALENDAR_DATA_TYPE)? else {
+        return Ok(false);
+    };
+
+    let Some(prune_until) = calendar.get_prune_until(now) else {
+        return Ok(false);
+    };
+
+    if prune_until < PRUNE_FROM_TIMESTAMP {
+        return Ok(true);
+    }
+
+    let from_date_string = DateTime::from(PRUNE_FROM_TIMESTAMP).render_formatted_date_time(None);
+    let until_date_string = DateTime::from(prune_until).render_formatted_date_time(None);
+
+    let pruned_events = prune_events_and_reindex(calendar, PRUNE_FROM_TIMESTAMP, prune_until)?;
+
+    if !pruned_events.is_empty() {
+        ctx.replicate("rdcl.evt_prune", &[calendar_uid, from_date_string.as_str(), until_date_string.as_str()]);
+    }
+
+    for event_uid in pruned_events.keys() {
+        notify_event_prune_keyspace_event(ctx, &calendar_key_name, &from_date_string, &until_date_string, event_uid)?;
+
+        append_changes(ctx, &calendar_key_name, &[Change::new(ChangeType::EventDel, event_uid)]);
+    }
+
+    // Only the events with overrides to prune are visited, as pruning re-indexes each event.
+    let event_uids: Vec<String> =
+        calendar.events
+            .iter()
+            .filter(|(_event_uid, event)| event.overrides.range(PRUNE_FROM_TIMESTAMP..=prune_until).next().is_some())
+            .map(|(event_uid, _event)| event_uid.to_owned())
+            .collect();
+
+    let mut pruned_override_count = 0;
+
+    for event_uid in event_uids {
+        let pruned_overrides = prune_calendar_events_overrides(calendar, event_uid.to_owned(), PRUNE_FROM_TIMESTAMP, prune_until)?;
+
+        pruned_override_count += pruned_overrides.len();
+
+        for (override_timestamp, _event_occurrence_override) in pruned_overrides {
+            let override_date_string = DateTime::from(override_timestamp).render_formatted_date_time(None);
+
+            notify_event_override_prune_keyspace_event(ctx, &calendar_key_name, &event_uid, &override_date_string)?;
+
+            append_changes(
+                ctx,
+                &calendar_key_name,
+                &[Change::new(ChangeType::OverrideDel, &event_uid).with_override_date_string(&override_date_string)],
+            );
+        }
+    }
+
+    if pruned_override_count > 0 {
+        ctx.replicate("rdcl.evo_prune", &[calendar_uid, from_date_string.as_str(), until_date_string.as_str()]);
+    }
+
+    if !pruned_events.is_empty() || pruned_override_count > 0 {
+        ctx.log_debug(
+            format!(
+                "Pruned {} event(s) and {pruned_override_count} event occurrence override(s) from Calendar: {calendar_uid}",
+                pruned_events.len(),
+            ).as_str()
+        );
+
+        calendar.bump_generation();
+
+        notify_dirty_query_subscriptions(ctx, &calendar_key_name, calendar);
+    }
+
+    Ok(true)
+}
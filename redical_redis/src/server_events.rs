@@ -0,0 +1,118 @@
+use redis_module::{raw, Context};
+use redis_module::logging::{log_notice, log_warning};
+use redis_module::server_events::LoadingSubevent;
+use redis_module_macros::loading_event_handler;
+
+use std::ffi::{c_int, c_void};
+
+use crate::core::Calendar;
+use crate::datatype::{discard_flushed_pending_index_rebuilds, take_flushed_calendar_keys, CALENDAR_DATA_TYPE};
+use crate::event_expiry::untrack_flushed_expiring_calendars;
+use crate::scheduled_pruning::untrack_flushed_pruning_calendars;
+use crate::notify_rdcl_cal_del_keyspace_event;
+use crate::query_subscriptions::unsubscribe_flushed_calendars;
+use crate::utils::get_selected_db;
+
+// Subscribe to the FLUSHDB server event (fired by FLUSHDB, FLUSHALL, and when a replica empties
+// it's dataset before a full resync).
+//
+// This subscribes directly instead of via #[flush_event_handler] because the redis_module wrapper
+// does not expose which database is being flushed.
+pub fn subscribe_to_flush_event(ctx: &Context) {
+    let result = unsafe {
+        raw::RedisModule_SubscribeToServerEvent.unwrap()(
+            ctx.ctx,
+            raw::RedisModuleEvent {
+                id: raw::REDISMODULE_EVENT_FLUSHDB,
+                dataver: 1,
+            },
+            Some(on_flush_event),
+        )
+    };
+
+    if result != raw::REDISMODULE_OK as c_int {
+        ctx.log_warning("Subscribe to FLUSHDB server event failed");
+    }
+}
+
+extern "C" fn on_flush_event(
+    ctx: *mut raw::RedisModuleCtx,
+    _event: raw::RedisModuleEvent,
+    subevent: u64,
+    data: *mut c_void,
+) {
+    // Only the start of the flush has access to the Calendars about to be flushed.
+    if subevent != raw::REDISMODULE_SUBEVENT_FLUSHDB_START {
+        return;
+    }
+
+    let ctx = Context::new(ctx);
+
+    let flush_info = unsafe { &*(data as *const raw::RedisModuleFlushInfo) };
+
+    // A negative database number represents all databases being flushed (FLUSHALL).
+    let flushed_db_id = (flush_info.dbnum >= 0).then_some(flush_info.dbnum);
+
+    on_flush_started(&ctx, flushed_db_id);
+}
+
+// Notify the rdcl.cal_del keyspace event for each Calendar about to be flushed (as is done when
+// deleted or evicted), and stop tracking the flushed Calendars awaiting deferred index rebuilds,
+// Event expiry, or with query subscriptions so that they do not apply to any new Calendar later
+// set on the same key.
+//
+// The flushed Calendars are taken from the Calendar registry rather than scanning the keyspace of
+// each flushed database.
+fn on_flush_started(ctx: &Context, flushed_db_id: Option<c_int>) {
+    let selected_db = get_selected_db(ctx);
+
+    let mut flushed_calendar_count = 0;
+
+    for (db_id, calendar_uid) in take_flushed_calendar_keys(flushed_db_id) {
+        if unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, db_id) } != raw::REDISMODULE_OK as c_int {
+            continue;
+        }
+
+        let calendar_uid = ctx.create_string(calendar_uid.as_str());
+
+        // Renamed or moved Calendars are registered under their original key, so are only notified
+        // if it still holds a Calendar.
+        if !matches!(ctx.open_key(&calendar_uid).get_value::<Calendar>(&CALENDAR_DATA_TYPE), Ok(Some(_))) {
+            continue;
+        }
+
+        notify_rdcl_cal_del_keyspace_event(ctx, &calendar_uid);
+
+        flushed_calendar_count += 1;
+    }
+
+    unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, selected_db) };
+
+    discard_flushed_pending_index_rebuilds(flushed_db_id);
+    untrack_flushed_expiring_calendars(flushed_db_id);
+    untrack_flushed_pruning_calendars(flushed_db_id);
+    unsubscribe_flushed_calendars(flushed_db_id);
+
+    if flushed_calendar_count > 0 {
+        ctx.log_notice(format!("Flushing {flushed_calendar_count} Calendar(s)").as_str());
+    }
+}
+
+#[loading_event_handler]
+fn log_loading_event(_ctx: &Context, loading_subevent: LoadingSubevent) {
+    match loading_subevent {
+        LoadingSubevent::RdbStarted => log_notice("Loading Calendars from RDB"),
+        LoadingSubevent::AofStarted => log_notice("Loading Calendars from AOF"),
+        LoadingSubevent::ReplStarted => log_notice("Loading Calendars from primary"),
+        LoadingSubevent::Ended => log_notice("Finished loading Calendars"),
+
+        LoadingSubevent::Failed => {
+            log_warning("Loading Calendars failed");
+
+            // Any Calendars partially loaded before the failure are discarded.
+            take_flushed_calendar_keys(None);
+            untrack_flushed_expiring_calendars(None);
+            untrack_flushed_pruning_calendars(None);
+        },
+    }
+}
@@ -1,9 +1,22 @@
 use std::{
+    ffi::c_int,
+    iter::Peekable,
+    str::FromStr,
     sync::mpsc,
     thread,
     time::Duration,
 };
 
+use redis_module::{raw, Context, ContextFlags, NextArg, RedisError, RedisString, RedisValue};
+
+use redical_ical::ICalendarEntity;
+use redical_ical::content_line::join_line_separated_content_lines;
+use redical_ical::properties::{LastModifiedProperty, LastModifiedPropertyParams};
+use redical_ical::values::date_time::DateTime;
+
+use crate::core::{Calendar, Event, PassiveProperties};
+use crate::CONFIGURATION_MAX_EVENTS_PER_CALENDAR;
+
 #[derive(Debug)]
 pub struct TimeoutError;
 
@@ -26,3 +39,365 @@ where
         Err(mpsc::RecvTimeoutError::Disconnected) => unreachable!(),
     }
 }
+
+pub fn get_selected_db(ctx: &Context) -> c_int {
+    unsafe { raw::RedisModule_GetSelectedDb.unwrap()(ctx.ctx) }
+}
+
+// Join the remaining iCalendar content line arguments (of either an event payload or a query),
+// each of which may either be a single whitespace separated content line, or several line break
+// separated (and potentially folded) content lines so that clients need not tokenise them.
+pub fn join_content_line_args<I: Iterator<Item = RedisString>>(args: I) -> String {
+    args
+        .map(|arg| join_line_separated_content_lines(arg.try_as_str().unwrap_or("")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// Whether the command is being replayed from the AOF or replicated from the primary rather than
+// sent by a client, in which case it must apply exactly what was originally applied, regardless of
+// this server's configuration.
+pub fn is_replayed_command(ctx: &Context) -> bool {
+    let flags = ctx.get_flags();
+
+    flags.contains(ContextFlags::REPLICATED) || flags.contains(ContextFlags::LOADING)
+}
+
+// The configured `max-events-per-calendar` limit, which is only enforced on commands sent by a
+// client, so that lowering it never drops events when the AOF is reloaded, nor diverges a replica
+// configured differently to it's primary.
+pub fn get_max_events_per_calendar(ctx: &Context) -> i64 {
+    if is_replayed_command(ctx) {
+        return 0;
+    }
+
+    *CONFIGURATION_MAX_EVENTS_PER_CALENDAR.lock(ctx)
+}
+
+// Validate the event against the Calendar schema (X-REQUIRED-PROPERTIES and X-ALLOWED-PROPERTIES),
+// which is only enforced on commands sent by a client, as events replayed from the AOF or
+// replicated from the primary may predate (and not conform to) the schema now declared.
+pub fn validate_event_schema(ctx: &Context, calendar: &Calendar, event: &Event) -> Result<(), String> {
+    if is_replayed_command(ctx) {
+        return Ok(());
+    }
+
+    calendar.validate_event_schema(event)
+}
+
+// Ensure inserting the provided number of new events would not take the Calendar beyond the
+// configured `max-events-per-calendar` limit (where 0 is unlimited).
+pub fn check_max_events_per_calendar(calendar: &Calendar, new_event_count: usize, max_events_per_calendar: i64) -> Result<(), String> {
+    if max_events_per_calendar <= 0 {
+        return Ok(());
+    }
+
+    if calendar.events.len() + new_event_count > max_events_per_calendar as usize {
+        return Err(format!(
+            "Calendar: {} exceeds max events per calendar limit: {max_events_per_calendar}",
+            calendar.uid.uid,
+        ));
+    }
+
+    Ok(())
+}
+
+// How a LAST-MODIFIED conflict (setting an event or event occurrence override with a
+// LAST-MODIFIED older than that stored) is resolved, as specified by the optional
+// `ON-CONFLICT (IGNORE|ERROR|FORCE)` command arguments.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LastModifiedConflictStrategy {
+    #[default]
+    Ignore,
+    Error,
+    Force,
+}
+
+impl LastModifiedConflictStrategy {
+    // Consume the optional `ON-CONFLICT (IGNORE|ERROR|FORCE)` command arguments (if present).
+    pub fn parse_optional_args<I>(args: &mut Peekable<I>) -> Result<Option<Self>, RedisError>
+    where
+        I: Iterator<Item = RedisString>,
+    {
+        let is_present =
+            args.next_if(|arg| {
+                arg.try_as_str()
+                    .is_ok_and(|arg| arg.eq_ignore_ascii_case("ON-CONFLICT"))
+            })
+            .is_some();
+
+        if !is_present {
+            return Ok(None);
+        }
+
+        LastModifiedConflictStrategy::from_str(args.next_arg()?.try_as_str()?)
+            .map(Some)
+            .map_err(RedisError::String)
+    }
+}
+
+impl FromStr for LastModifiedConflictStrategy {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_uppercase().as_str() {
+            "IGNORE" => Ok(LastModifiedConflictStrategy::Ignore),
+            "ERROR" => Ok(LastModifiedConflictStrategy::Error),
+            "FORCE" => Ok(LastModifiedConflictStrategy::Force),
+
+            _ => Err(format!("ON-CONFLICT: {input} is invalid, expected either IGNORE, ERROR, or FORCE")),
+        }
+    }
+}
+
+// The action taken when setting an event or event occurrence override with consideration to the
+// LAST-MODIFIED of that stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LastModifiedConflictAction {
+    Set,
+    Ignored,
+    Forced,
+}
+
+impl LastModifiedConflictAction {
+    // Only detail the action taken alongside the original reply when a conflict strategy was
+    // explicitly requested, so that existing clients are unaffected.
+    pub fn build_reply(&self, conflict_strategy: Option<LastModifiedConflictStrategy>, reply: RedisValue) -> RedisValue {
+        if conflict_strategy.is_none() {
+            return reply;
+        }
+
+        RedisValue::Array(vec![RedisValue::SimpleString(self.to_string()), reply])
+    }
+}
+
+impl std::fmt::Display for LastModifiedConflictAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LastModifiedConflictAction::Set => write!(f, "SET"),
+            LastModifiedConflictAction::Ignored => write!(f, "IGNORED"),
+            LastModifiedConflictAction::Forced => write!(f, "FORCED"),
+        }
+    }
+}
+
+// Whether a write is conditional upon the event or event occurrence override already existing,
+// as specified by the optional `NX` (only create) or `XX` (only update) command argument,
+// mirroring SET.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExistenceCondition {
+    NotExists,
+    Exists,
+}
+
+impl ExistenceCondition {
+    // Consume the optional `NX` or `XX` command argument (if present).
+    pub fn parse_optional_args<I>(args: &mut Peekable<I>) -> Option<Self>
+    where
+        I: Iterator<Item = RedisString>,
+    {
+        let mut is_arg = |expected_arg: &str| {
+            args.next_if(|arg| {
+                arg.try_as_str()
+                    .is_ok_and(|arg| arg.eq_ignore_ascii_case(expected_arg))
+            })
+            .is_some()
+        };
+
+        if is_arg("NX") {
+            return Some(ExistenceCondition::NotExists);
+        }
+
+        if is_arg("XX") {
+            return Some(ExistenceCondition::Exists);
+        }
+
+        None
+    }
+
+    pub fn is_met(&self, is_existing: bool) -> bool {
+        match self {
+            ExistenceCondition::NotExists => !is_existing,
+            ExistenceCondition::Exists => is_existing,
+        }
+    }
+}
+
+// The version of the stored event or event occurrence override a write is conditional upon (for
+// optimistic concurrency), as specified by the optional `IFMATCH <last-modified|sequence>`
+// command arguments, either its LAST-MODIFIED (a UTC date-time, or the full property when
+// carrying X-MILLIS) or its SEQUENCE number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfMatchCondition {
+    LastModified(LastModifiedProperty),
+    Sequence(u64),
+}
+
+impl IfMatchCondition {
+    // Consume the optional `IFMATCH <last-modified|sequence>` command arguments (if present).
+    pub fn parse_optional_args<I>(args: &mut Peekable<I>) -> Result<Option<Self>, RedisError>
+    where
+        I: Iterator<Item = RedisString>,
+    {
+        let is_present =
+            args.next_if(|arg| {
+                arg.try_as_str()
+                    .is_ok_and(|arg| arg.eq_ignore_ascii_case("IFMATCH"))
+            })
+            .is_some();
+
+        if !is_present {
+            return Ok(None);
+        }
+
+        IfMatchCondition::from_str(args.next_arg()?.try_as_str()?)
+            .map(Some)
+            .map_err(RedisError::String)
+    }
+
+    // Verify the stored LAST-MODIFIED and passive properties (SEQUENCE) match those expected,
+    // returning a description of the mismatch if not. Nothing stored never matches, and a missing
+    // SEQUENCE is treated as 0 (its iCalendar default).
+    //
+    // LAST-MODIFIED is compared to the millisecond, with a missing X-MILLIS treated as 0 (as when
+    // ordering LAST-MODIFIED properties), so writes within the same second are only told apart
+    // when both carry X-MILLIS.
+    pub fn verify(&self, stored: Option<(&LastModifiedProperty, &PassiveProperties)>) -> Result<(), String> {
+        let Some((last_modified, passive_properties)) = stored else {
+            return Err(format!("IFMATCH {self} does not match as nothing is stored"));
+        };
+
+        match self {
+            IfMatchCondition::LastModified(expected_last_modified) => {
+                if expected_last_modified.cmp(last_modified).is_ne() {
+                    return Err(format!("IFMATCH {self} does not match stored {last_modified}"));
+                }
+            },
+
+            IfMatchCondition::Sequence(sequence) => {
+                let stored_sequence = passive_properties.get_sequence().unwrap_or(0);
+
+                if *sequence != stored_sequence {
+                    return Err(format!("IFMATCH {self} does not match stored SEQUENCE:{stored_sequence}"));
+                }
+            },
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for IfMatchCondition {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if !input.is_empty() && input.chars().all(|char| char.is_ascii_digit()) {
+            return input
+                .parse::<u64>()
+                .map(IfMatchCondition::Sequence)
+                .map_err(|_| format!("IFMATCH: {input} is invalid, expected either a LAST-MODIFIED UTC date-time or a SEQUENCE number"));
+        }
+
+        // Either a bare UTC date-time (without milliseconds), or the full LAST-MODIFIED property as
+        // stored (e.g. `LAST-MODIFIED;X-MILLIS=123:20210501T090000Z`).
+        let last_modified =
+            if input.to_ascii_uppercase().starts_with("LAST-MODIFIED") {
+                LastModifiedProperty::from_str(input).ok()
+            } else {
+                DateTime::from_str(input)
+                    .ok()
+                    .map(|date_time| {
+                        LastModifiedProperty {
+                            params: LastModifiedPropertyParams::default(),
+                            date_time,
+                        }
+                    })
+            };
+
+        match last_modified {
+            Some(last_modified @ LastModifiedProperty { date_time: DateTime::UtcDateTime(_), .. }) => Ok(IfMatchCondition::LastModified(last_modified)),
+
+            _ => Err(format!("IFMATCH: {input} is invalid, expected either a LAST-MODIFIED UTC date-time or a SEQUENCE number")),
+        }
+    }
+}
+
+impl std::fmt::Display for IfMatchCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IfMatchCondition::LastModified(last_modified) if last_modified.params.millis.is_some() => write!(f, "{}", last_modified.render_ical()),
+            IfMatchCondition::LastModified(last_modified) => write!(f, "{}", last_modified.date_time.render_ical()),
+            IfMatchCondition::Sequence(sequence) => write!(f, "{sequence}"),
+        }
+    }
+}
+
+// Consume the optional `NOW <datetime>` command arguments (if present), the UTC date-time used in
+// place of the wall clock for any defaulted values (e.g. a LAST-MODIFIED not provided), so that
+// they are deterministic.
+pub fn parse_optional_now_args<I>(args: &mut Peekable<I>) -> Result<Option<chrono::DateTime<chrono::Utc>>, RedisError>
+where
+    I: Iterator<Item = RedisString>,
+{
+    let is_present =
+        args.next_if(|arg| {
+            arg.try_as_str()
+                .is_ok_and(|arg| arg.eq_ignore_ascii_case("NOW"))
+        })
+        .is_some();
+
+    if !is_present {
+        return Ok(None);
+    }
+
+    let input = args.next_arg()?;
+    let input = input.try_as_str()?;
+
+    match DateTime::from_str(input) {
+        Ok(DateTime::UtcDateTime(date_time)) => Ok(Some(date_time.and_utc())),
+
+        _ => Err(RedisError::String(format!("NOW: {input} is invalid, expected a UTC date-time"))),
+    }
+}
+
+// The reply returned when setting an event or event occurrence override, as specified by the
+// optional `GET` (the stored properties - the default) or `CH` (whether anything changed)
+// command argument.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SetReplyMode {
+    #[default]
+    Get,
+    Changed,
+}
+
+impl SetReplyMode {
+    // Consume the optional `GET` or `CH` command argument (if present).
+    pub fn parse_optional_args<I>(args: &mut Peekable<I>) -> Self
+    where
+        I: Iterator<Item = RedisString>,
+    {
+        let mut is_arg = |expected_arg: &str| {
+            args.next_if(|arg| {
+                arg.try_as_str()
+                    .is_ok_and(|arg| arg.eq_ignore_ascii_case(expected_arg))
+            })
+            .is_some()
+        };
+
+        if is_arg("CH") {
+            return SetReplyMode::Changed;
+        }
+
+        // GET is the default, so it is just consumed.
+        is_arg("GET");
+
+        SetReplyMode::Get
+    }
+
+    pub fn build_reply(&self, is_changed: bool, stored_reply: RedisValue) -> RedisValue {
+        match self {
+            SetReplyMode::Get => stored_reply,
+            SetReplyMode::Changed => RedisValue::Bool(is_changed),
+        }
+    }
+}
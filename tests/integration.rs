@@ -13,10 +13,10 @@ mod integration {
 
     use pretty_assertions_sorted::{assert_ne, assert_eq, assert_eq_sorted};
 
-    use utils::listen_for_keyspace_events;
+    use utils::{listen_for_keyspace_events, query_results_header, wait_for_keyspace_event};
 
     use std::sync::{Mutex, Arc};
-    use std::collections::VecDeque;
+    use std::collections::{HashMap, VecDeque};
 
     // Run with:
     //  cargo build && cargo test --all
@@ -49,6 +49,194 @@ mod integration {
         })
     }
 
+    fn test_calendar_memory_usage(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        let empty_calendar_memory_usage: i64 = redis::cmd("MEMORY").arg("USAGE").arg("TEST_CALENDAR_UID").query(connection)?;
+
+        assert!(empty_calendar_memory_usage > 0);
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "CATEGORIES:CATEGORY_ONE,CATEGORY_TWO",
+                "GEO:51.751365550307604;-1.2601196837753945",
+            ],
+        );
+
+        let calendar_memory_usage: i64 = redis::cmd("MEMORY").arg("USAGE").arg("TEST_CALENDAR_UID").query(connection)?;
+
+        // Assert the reported memory usage reflects the stored events and their indexes.
+        assert!(calendar_memory_usage > empty_calendar_memory_usage);
+
+        // Sampling every element rather than the default of 5 makes no difference with so few.
+        let sampled_calendar_memory_usage: i64 = redis::cmd("MEMORY").arg("USAGE").arg("TEST_CALENDAR_UID").arg("SAMPLES").arg(0).query(connection)?;
+
+        assert_eq!(sampled_calendar_memory_usage, calendar_memory_usage);
+
+        disable_calendar_indexes!(connection, "TEST_CALENDAR_UID", 1);
+
+        let unindexed_calendar_memory_usage: i64 = redis::cmd("MEMORY").arg("USAGE").arg("TEST_CALENDAR_UID").query(connection)?;
+
+        assert!(unindexed_calendar_memory_usage < calendar_memory_usage);
+
+        Ok(())
+    }
+
+    fn test_calendar_copy(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "CATEGORIES:CATEGORY_ONE",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            "20210104T170000Z",
+            [
+                "CATEGORIES:CATEGORY_TWO",
+            ],
+        );
+
+        assert_eq!(
+            redis::cmd("COPY").arg("TEST_CALENDAR_UID").arg("TEST_CALENDAR_UID_COPY").query(connection),
+            RedisResult::Ok(Value::Int(1)),
+        );
+
+        // Assert the copied Calendar takes the UID of the destination key.
+        assert_calendar_present!(connection, "TEST_CALENDAR_UID_COPY");
+
+        assert_event_present!(
+            connection,
+            "TEST_CALENDAR_UID_COPY",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "CATEGORIES:CATEGORY_ONE",
+            ],
+        );
+
+        assert_event_override_present!(
+            connection,
+            "TEST_CALENDAR_UID_COPY",
+            "EVENT_IN_OXFORD_MON_WED",
+            "20210104T170000Z",
+            [
+                "CATEGORIES:CATEGORY_TWO",
+            ],
+        );
+
+        // Assert the copied Calendar indexes are immediately queryable.
+        let calendar_query_result: Vec<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID_COPY")
+                .arg("X-CATEGORIES:CATEGORY_TWO")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 1);
+
+        // Assert changes to the copied Calendar do not affect the source Calendar.
+        del_and_assert_event_deletion!(connection, "TEST_CALENDAR_UID_COPY", "EVENT_IN_OXFORD_MON_WED", 1);
+
+        let calendar_query_result: Vec<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-CATEGORIES:CATEGORY_TWO")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 1);
+
+        Ok(())
+    }
+
+    fn test_calendar_digest(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "CATEGORIES:CATEGORY_ONE",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            "20210104T170000Z",
+            [
+                "LAST-MODIFIED:20210501T090000Z",
+                "CATEGORIES:CATEGORY_TWO",
+            ],
+        );
+
+        let calendar_digest: Vec<String> = redis::cmd("DEBUG").arg("DIGEST-VALUE").arg("TEST_CALENDAR_UID").query(connection)?;
+
+        // Assert the digest reflects the Calendar content (as opposed to an empty digest).
+        assert_ne!(calendar_digest, vec![String::from("0000000000000000000000000000000000000000")]);
+
+        // Assert a Calendar restored from a dump (e.g. a backup) has a matching digest.
+        let calendar_dump: Vec<u8> = redis::cmd("DUMP").arg("TEST_CALENDAR_UID").query(connection)?;
+
+        assert_eq!(
+            redis::cmd("RESTORE").arg("TEST_CALENDAR_UID_RESTORED").arg(0).arg(calendar_dump).query(connection),
+            RedisResult::Ok(Value::Okay),
+        );
+
+        let restored_calendar_digest: Vec<String> = redis::cmd("DEBUG").arg("DIGEST-VALUE").arg("TEST_CALENDAR_UID_RESTORED").query(connection)?;
+
+        assert_eq!(restored_calendar_digest, calendar_digest);
+
+        // Assert the indexes do not affect the digest.
+        disable_calendar_indexes!(connection, "TEST_CALENDAR_UID_RESTORED", 1);
+
+        let restored_calendar_digest: Vec<String> = redis::cmd("DEBUG").arg("DIGEST-VALUE").arg("TEST_CALENDAR_UID_RESTORED").query(connection)?;
+
+        assert_eq!(restored_calendar_digest, calendar_digest);
+
+        // Assert changes to the Calendar content are reflected in the digest.
+        let _: Vec<String> =
+            redis::cmd("rdcl.evo_set")
+                .arg("TEST_CALENDAR_UID_RESTORED")
+                .arg("EVENT_IN_OXFORD_MON_WED")
+                .arg("20210104T170000Z")
+                .arg("LAST-MODIFIED:20210501T090000Z CATEGORIES:CATEGORY_THREE")
+                .query(connection)?;
+
+        let restored_calendar_digest: Vec<String> = redis::cmd("DEBUG").arg("DIGEST-VALUE").arg("TEST_CALENDAR_UID_RESTORED").query(connection)?;
+
+        assert_ne!(restored_calendar_digest, calendar_digest);
+
+        Ok(())
+    }
+
     fn test_event_get_set_del_list(connection: &mut Connection) -> Result<()> {
         listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
             set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
@@ -142,107 +330,512 @@ mod integration {
         })
     }
 
-    fn test_event_set_last_modified(connection: &mut Connection) -> Result<()> {
+    fn test_event_mset(connection: &mut Connection) -> Result<()> {
         listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
             set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
 
             assert_keyspace_events_published!(message_queue, "rdcl.cal_set", "TEST_CALENDAR_UID");
 
-            set_and_assert_event!(
-                connection,
-                "TEST_CALENDAR_UID",
-                "ONLINE_EVENT_MON_WED",
-                [
-                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM",
-                    "DTSTART:20201231T160000Z",
-                    "LAST-MODIFIED:20210501T090000Z",
+            let event_mset_result: Vec<Vec<String>> =
+                redis::cmd("rdcl.evt_mset")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("ONLINE_EVENT_MON_WED")
+                    .arg("SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1 DTSTART:20201231T160000Z DTEND:20201231T170000Z LAST-MODIFIED:20210501T090000Z CATEGORIES:CATEGORY_ONE")
+                    .arg("EVENT_IN_OXFORD_MON_WED")
+                    .arg("SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1 DTSTART:20201231T170000Z DTEND:20201231T173000Z LAST-MODIFIED:20210501T090000Z CATEGORIES:CATEGORY_TWO")
+                    .query(connection)?;
+
+            assert_eq!(
+                event_mset_result,
+                vec![
+                    vec![
+                        String::from("CATEGORIES:CATEGORY_ONE"),
+                        String::from("DTEND:20201231T170000Z"),
+                        String::from("DTSTART:20201231T160000Z"),
+                        String::from("LAST-MODIFIED:20210501T090000Z"),
+                        String::from("RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1"),
+                        String::from("SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM"),
+                        String::from("UID:ONLINE_EVENT_MON_WED"),
+                    ],
+                    vec![
+                        String::from("CATEGORIES:CATEGORY_TWO"),
+                        String::from("DTEND:20201231T173000Z"),
+                        String::from("DTSTART:20201231T170000Z"),
+                        String::from("LAST-MODIFIED:20210501T090000Z"),
+                        String::from("RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1"),
+                        String::from("SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM"),
+                        String::from("UID:EVENT_IN_OXFORD_MON_WED"),
+                    ],
                 ],
             );
 
-            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:ONLINE_EVENT_MON_WED LAST-MODIFIED:20210501T090000Z", "TEST_CALENDAR_UID");
+            // Assert a single batched keyspace event is published for all events set.
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_mset:ONLINE_EVENT_MON_WED,EVENT_IN_OXFORD_MON_WED", "TEST_CALENDAR_UID");
 
-            list_and_assert_matching_events!(
+            query_calendar_and_assert_matching_event_instances!(
                 connection,
                 "TEST_CALENDAR_UID",
+                [
+                    "X-CATEGORIES:CATEGORY_TWO",
+                    "X-LIMIT:1",
+                ],
                 [
                     [
-                        "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM",
-                        "DTSTART:20201231T160000Z",
-                        "LAST-MODIFIED:20210501T090000Z",
-                        "UID:ONLINE_EVENT_MON_WED",
+                        [
+                            "DTSTART:20210104T170000Z",
+                        ],
+                        [
+                            "CATEGORIES:CATEGORY_TWO",
+                            "DTEND:20210104T173000Z",
+                            "DTSTART:20210104T170000Z",
+                            "DURATION:PT30M",
+                            "RECURRENCE-ID;VALUE=DATE-TIME:20210104T170000Z",
+                            "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                            "UID:EVENT_IN_OXFORD_MON_WED",
+                        ],
                     ],
                 ],
             );
 
-            // Assert setting event with earlier LAST-MODIFIED property gets ignored.
-            set_and_assert_event_not_set!(
-                connection,
-                "TEST_CALENDAR_UID",
-                "ONLINE_EVENT_MON_WED",
-                [
-                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM (UPDATED)",
-                    "DTSTART:20201231T160000Z",
-                    "LAST-MODIFIED:20210201T090000Z", // <- Earlier LAST-MODIFIED specified!
-                ],
-            );
+            // Assert that an invalid event rejects the whole batch, leaving the existing events untouched.
+            let invalid_event_mset_result: Result<Value, String> =
+                redis::cmd("rdcl.evt_mset")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("NEW_EVENT")
+                    .arg("SUMMARY:New event DTSTART:20201231T160000Z RRULE:FREQ=DAILY;COUNT=3;INTERVAL=1")
+                    .arg("EVENT_IN_OXFORD_MON_WED")
+                    .arg("SUMMARY:Invalid event DTSTART:INVALID RRULE:FREQ=DAILY;COUNT=3;INTERVAL=1")
+                    .query(connection)
+                    .map_err(|error| error.to_string());
+
+            assert!(invalid_event_mset_result.is_err());
 
-            // Assert no key-space event notifications published.
             assert_keyspace_events_published!(message_queue, []);
 
-            // Assert not having changed!
             assert_event_present!(
                 connection,
                 "TEST_CALENDAR_UID",
-                "ONLINE_EVENT_MON_WED",
+                "EVENT_IN_OXFORD_MON_WED",
                 [
-                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM",
-                    "DTSTART:20201231T160000Z",
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                    "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                    "DTSTART:20201231T170000Z",
+                    "DTEND:20201231T173000Z",
                     "LAST-MODIFIED:20210501T090000Z",
+                    "CATEGORIES:CATEGORY_TWO",
                 ],
             );
 
-            // Assert setting event with later LAST-MODIFIED property gets acknowledged.
-            set_and_assert_event!(
+            assert_eq!(
+                redis::cmd("rdcl.evt_get").arg("TEST_CALENDAR_UID").arg("NEW_EVENT").query(connection),
+                RedisResult::Ok(Value::Nil),
+            );
+
+            // Assert events with a superseded LAST-MODIFIED are skipped without failing the batch.
+            let event_mset_result: Vec<Value> =
+                redis::cmd("rdcl.evt_mset")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("ONLINE_EVENT_MON_WED")
+                    .arg("SUMMARY:Stale online event DTSTART:20201231T160000Z RRULE:FREQ=DAILY;COUNT=3;INTERVAL=1 LAST-MODIFIED:20210401T090000Z")
+                    .arg("EVENT_IN_OXFORD_MON_WED")
+                    .arg("SUMMARY:Updated event in Oxford DTSTART:20201231T170000Z RRULE:FREQ=DAILY;COUNT=3;INTERVAL=1 LAST-MODIFIED:20210601T090000Z")
+                    .query(connection)?;
+
+            assert_eq!(event_mset_result.len(), 2);
+            assert_eq!(event_mset_result[0], Value::Int(0));
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_mset:EVENT_IN_OXFORD_MON_WED", "TEST_CALENDAR_UID");
+
+            assert_event_present!(
                 connection,
                 "TEST_CALENDAR_UID",
-                "ONLINE_EVENT_MON_WED",
+                "EVENT_IN_OXFORD_MON_WED",
                 [
-                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM (UPDATED ONE)",
-                    "DTSTART:20201231T160000Z",
-                    "LAST-MODIFIED:20210501T120000Z", // <- Later LAST-MODIFIED specified!
+                    "SUMMARY:Updated event in Oxford",
+                    "RRULE:COUNT=3;FREQ=DAILY;INTERVAL=1",
+                    "DTSTART:20201231T170000Z",
+                    "LAST-MODIFIED:20210601T090000Z",
                 ],
             );
 
-            // Assert event being changed!
+            // Assert an uneven number of event UID and iCal arguments is rejected.
+            let wrong_arity_event_mset_result: Result<Value, String> =
+                redis::cmd("rdcl.evt_mset")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("ONLINE_EVENT_MON_WED")
+                    .query(connection)
+                    .map_err(|error| error.to_string());
+
+            assert!(wrong_arity_event_mset_result.is_err());
+
+            Ok(())
+        })
+    }
+
+    fn test_event_import(connection: &mut Connection) -> Result<()> {
+        listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
+            set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+            assert_keyspace_events_published!(message_queue, "rdcl.cal_set", "TEST_CALENDAR_UID");
+
+            let ics_document = [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//RediCal//Integration tests//EN",
+                "BEGIN:VEVENT",
+                "UID:EVENT_IN_OXFORD_MON_WED",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "LAST-MODIFIED:20210501T090000Z",
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "CATEGORIES:CATEGORY_ONE",
+                "END:VEVENT",
+                "BEGIN:VEVENT",
+                "UID:EVENT_IN_OXFORD_MON_WED",
+                "RECURRENCE-ID:20210106T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "SUMMARY:Overridden event in Oxford",
+                "CATEGORIES:CATEGORY_TWO",
+                "END:VEVENT",
+                "BEGIN:VEVENT",
+                "UID:EVENT_IN_OXFORD_MON_WED",
+                "RECURRENCE-ID:20210105T170000Z",
+                "SUMMARY:Override for a non-existent occurrence",
+                "END:VEVENT",
+                "BEGIN:VEVENT",
+                "UID:INVALID_EVENT",
+                "DTSTART:INVALID",
+                "END:VEVENT",
+                "END:VCALENDAR",
+            ].join("\r\n");
+
+            let event_import_result: Vec<Value> =
+                redis::cmd("rdcl.evt_import")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg(ics_document.to_owned())
+                    .query(connection)?;
+
+            assert_eq!(event_import_result.len(), 4);
+
+            assert_eq!(
+                event_import_result[0],
+                Value::Bulk(vec![
+                    Value::Status(String::from("EVENT_IN_OXFORD_MON_WED")),
+                    Value::Nil,
+                    Value::Status(String::from("IMPORTED")),
+                ]),
+            );
+
+            assert_eq!(
+                event_import_result[1],
+                Value::Bulk(vec![
+                    Value::Status(String::from("EVENT_IN_OXFORD_MON_WED")),
+                    Value::Status(String::from("20210106T170000Z")),
+                    Value::Status(String::from("IMPORTED")),
+                ]),
+            );
+
+            let Value::Bulk(non_existent_occurrence_result) = &event_import_result[2] else {
+                panic!("expected array reply for import result");
+            };
+
+            assert!(matches!(&non_existent_occurrence_result[2], Value::Status(status) if status.starts_with("ERROR: ")));
+
+            let Value::Bulk(invalid_event_result) = &event_import_result[3] else {
+                panic!("expected array reply for import result");
+            };
+
+            assert_eq!(invalid_event_result[0], Value::Status(String::from("INVALID_EVENT")));
+            assert!(matches!(&invalid_event_result[2], Value::Status(status) if status.starts_with("ERROR: ")));
+
+            // Assert a single batched keyspace event is published for all imported event UIDs.
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_import:EVENT_IN_OXFORD_MON_WED", "TEST_CALENDAR_UID");
+
             assert_event_present!(
                 connection,
                 "TEST_CALENDAR_UID",
-                "ONLINE_EVENT_MON_WED",
+                "EVENT_IN_OXFORD_MON_WED",
                 [
-                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM (UPDATED ONE)",
-                    "DTSTART:20201231T160000Z",
-                    "LAST-MODIFIED:20210501T120000Z",
+                    "DTSTART:20201231T170000Z",
+                    "DTEND:20201231T173000Z",
+                    "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                    "CATEGORIES:CATEGORY_ONE",
                 ],
             );
 
-            // Assert event being changed key-space event notification is published.
-            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:ONLINE_EVENT_MON_WED LAST-MODIFIED:20210501T120000Z", "TEST_CALENDAR_UID");
-
-            // Assert setting event with later LAST-MODIFIED property (by a few milliseconds) gets
-            // acknowledged.
-            set_and_assert_event!(
+            assert_event_override_present!(
                 connection,
                 "TEST_CALENDAR_UID",
-                "ONLINE_EVENT_MON_WED",
+                "EVENT_IN_OXFORD_MON_WED",
+                "20210106T170000Z",
                 [
-                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM (UPDATED TWO)",
-                    "DTSTART:20201231T160000Z",
-                    "LAST-MODIFIED;X-MILLIS=123:20210501T120000Z", // <- Later LAST-MODIFIED specified (by 123 milliseconds)!
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "SUMMARY:Overridden event in Oxford",
+                    "CATEGORIES:CATEGORY_TWO",
                 ],
             );
 
-            // Assert event being changed key-space event notification is published.
-            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:ONLINE_EVENT_MON_WED LAST-MODIFIED;X-MILLIS=123:20210501T120000Z", "TEST_CALENDAR_UID");
+            assert_event_override_nil!(connection, "TEST_CALENDAR_UID", "EVENT_IN_OXFORD_MON_WED", "20210105T170000Z",);
+
+            assert_event_nil!(connection, "TEST_CALENDAR_UID", "INVALID_EVENT");
+
+            // Assert that re-importing components with a superseded LAST-MODIFIED are skipped.
+            let stale_ics_document = [
+                "BEGIN:VCALENDAR",
+                "BEGIN:VEVENT",
+                "UID:EVENT_IN_OXFORD_MON_WED",
+                "DTSTART:20201231T170000Z",
+                "RRULE:FREQ=DAILY;COUNT=3;INTERVAL=1",
+                "LAST-MODIFIED:20210401T090000Z",
+                "SUMMARY:Stale event in Oxford",
+                "END:VEVENT",
+                "BEGIN:VEVENT",
+                "UID:EVENT_IN_OXFORD_MON_WED",
+                "RECURRENCE-ID:20210106T170000Z",
+                "LAST-MODIFIED:20210401T090000Z",
+                "SUMMARY:Stale overridden event in Oxford",
+                "END:VEVENT",
+                "END:VCALENDAR",
+            ].join("\n");
+
+            let event_import_result: Vec<Value> =
+                redis::cmd("rdcl.evt_import")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg(stale_ics_document.to_owned())
+                    .query(connection)?;
+
+            assert_eq!(
+                event_import_result,
+                vec![
+                    Value::Bulk(vec![
+                        Value::Status(String::from("EVENT_IN_OXFORD_MON_WED")),
+                        Value::Nil,
+                        Value::Status(String::from("SKIPPED")),
+                    ]),
+                    Value::Bulk(vec![
+                        Value::Status(String::from("EVENT_IN_OXFORD_MON_WED")),
+                        Value::Status(String::from("20210106T170000Z")),
+                        Value::Status(String::from("SKIPPED")),
+                    ]),
+                ],
+            );
+
+            assert_keyspace_events_published!(message_queue, []);
+
+            // Assert that malformed documents are rejected entirely.
+            assert_error_returned!(
+                connection,
+                "Expected: iCalendar document to begin with BEGIN:VCALENDAR",
+                "rdcl.evt_import",
+                "TEST_CALENDAR_UID",
+                "BEGIN:VEVENT\nUID:EVENT_ONE\nEND:VEVENT",
+            );
+
+            Ok(())
+        })
+    }
+
+    fn test_calendar_export(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "CATEGORIES:CATEGORY_ONE",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "ONLINE_EVENT_MON_WED",
+            [
+                "DTSTART:20201231T160000Z",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM",
+                "CATEGORIES:CATEGORY_TWO",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            "20210104T170000Z",
+            [
+                "SUMMARY:Overridden event in Oxford",
+            ],
+        );
+
+        let ics_document: String =
+            redis::cmd("rdcl.cal_export")
+                .arg("TEST_CALENDAR_UID")
+                .query(connection)?;
+
+        assert!(ics_document.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//RediCal//RediCal//EN\r\n"));
+        assert!(ics_document.ends_with("END:VCALENDAR\r\n"));
+
+        assert_eq!(ics_document.matches("BEGIN:VEVENT\r\n").count(), 3);
+
+        assert!(ics_document.contains("UID:EVENT_IN_OXFORD_MON_WED\r\n"));
+        assert!(ics_document.contains("UID:ONLINE_EVENT_MON_WED\r\n"));
+        assert!(ics_document.contains("RECURRENCE-ID;VALUE=DATE-TIME:20210104T170000Z\r\n"));
+        assert!(ics_document.contains("SUMMARY:Overridden event in Oxford\r\n"));
+
+        // Assert the exported document can be imported back into another calendar.
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID_COPY");
+
+        let event_import_result: Vec<Value> =
+            redis::cmd("rdcl.evt_import")
+                .arg("TEST_CALENDAR_UID_COPY")
+                .arg(ics_document.to_owned())
+                .query(connection)?;
+
+        assert_eq!(event_import_result.len(), 3);
+
+        assert_event_override_present!(
+            connection,
+            "TEST_CALENDAR_UID_COPY",
+            "EVENT_IN_OXFORD_MON_WED",
+            "20210104T170000Z",
+            [
+                "SUMMARY:Overridden event in Oxford",
+                "DURATION:PT30M",
+            ],
+        );
+
+        // Assert that only the events matching the query are exported.
+        let ics_document: String =
+            redis::cmd("rdcl.cal_export")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-CATEGORIES:CATEGORY_TWO")
+                .query(connection)?;
+
+        assert_eq!(ics_document.matches("BEGIN:VEVENT\r\n").count(), 1);
+
+        assert!(ics_document.contains("UID:ONLINE_EVENT_MON_WED\r\n"));
+        assert!(!ics_document.contains("UID:EVENT_IN_OXFORD_MON_WED\r\n"));
+
+        assert_error_returned!(
+            connection,
+            "No: Calendar found on key: NON_EXISTENT_CALENDAR_UID",
+            "rdcl.cal_export",
+            "NON_EXISTENT_CALENDAR_UID",
+        );
+
+        Ok(())
+    }
+
+    fn test_event_set_last_modified(connection: &mut Connection) -> Result<()> {
+        listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
+            set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+            assert_keyspace_events_published!(message_queue, "rdcl.cal_set", "TEST_CALENDAR_UID");
+
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "ONLINE_EVENT_MON_WED",
+                [
+                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM",
+                    "DTSTART:20201231T160000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                ],
+            );
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:ONLINE_EVENT_MON_WED LAST-MODIFIED:20210501T090000Z", "TEST_CALENDAR_UID");
+
+            list_and_assert_matching_events!(
+                connection,
+                "TEST_CALENDAR_UID",
+                [
+                    [
+                        "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM",
+                        "DTSTART:20201231T160000Z",
+                        "LAST-MODIFIED:20210501T090000Z",
+                        "UID:ONLINE_EVENT_MON_WED",
+                    ],
+                ],
+            );
+
+            // Assert setting event with earlier LAST-MODIFIED property gets ignored.
+            set_and_assert_event_not_set!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "ONLINE_EVENT_MON_WED",
+                [
+                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM (UPDATED)",
+                    "DTSTART:20201231T160000Z",
+                    "LAST-MODIFIED:20210201T090000Z", // <- Earlier LAST-MODIFIED specified!
+                ],
+            );
+
+            // Assert no key-space event notifications published.
+            assert_keyspace_events_published!(message_queue, []);
+
+            // Assert not having changed!
+            assert_event_present!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "ONLINE_EVENT_MON_WED",
+                [
+                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM",
+                    "DTSTART:20201231T160000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                ],
+            );
+
+            // Assert setting event with later LAST-MODIFIED property gets acknowledged.
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "ONLINE_EVENT_MON_WED",
+                [
+                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM (UPDATED ONE)",
+                    "DTSTART:20201231T160000Z",
+                    "LAST-MODIFIED:20210501T120000Z", // <- Later LAST-MODIFIED specified!
+                ],
+            );
+
+            // Assert event being changed!
+            assert_event_present!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "ONLINE_EVENT_MON_WED",
+                [
+                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM (UPDATED ONE)",
+                    "DTSTART:20201231T160000Z",
+                    "LAST-MODIFIED:20210501T120000Z",
+                ],
+            );
+
+            // Assert event being changed key-space event notification is published.
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:ONLINE_EVENT_MON_WED LAST-MODIFIED:20210501T120000Z", "TEST_CALENDAR_UID");
+
+            // Assert setting event with later LAST-MODIFIED property (by a few milliseconds) gets
+            // acknowledged.
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "ONLINE_EVENT_MON_WED",
+                [
+                    "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM (UPDATED TWO)",
+                    "DTSTART:20201231T160000Z",
+                    "LAST-MODIFIED;X-MILLIS=123:20210501T120000Z", // <- Later LAST-MODIFIED specified (by 123 milliseconds)!
+                ],
+            );
+
+            // Assert event being changed key-space event notification is published.
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:ONLINE_EVENT_MON_WED LAST-MODIFIED;X-MILLIS=123:20210501T120000Z", "TEST_CALENDAR_UID");
 
             // Assert event being changed!
             assert_event_present!(
@@ -410,6 +1003,7 @@ mod integration {
                 "TEST_CALENDAR_UID",
                 "EVENT_IN_OXFORD_MON_WED",
                 "20210102T170000Z",
+                DETACHED,
                 [
                     "LAST-MODIFIED:20210501T090000Z",
                     "CATEGORIES:CATEGORY_ONE,OVERRIDDEN_CATEGORY",
@@ -426,6 +1020,7 @@ mod integration {
                 "TEST_CALENDAR_UID",
                 "EVENT_IN_OXFORD_MON_WED",
                 "20201231T170000Z",
+                DETACHED,
                 [
                     "LAST-MODIFIED:20210501T090000Z",
                     "SUMMARY:Overridden event in Oxford summary text",
@@ -527,6 +1122,17 @@ mod integration {
                 "SUMMARY:Some text",
             );
 
+            // Assert rdcl.evo_set rejects date strings which are not occurrences of the event
+            assert_error_returned!(
+                connection,
+                "Event: with UID: 'EVENT_IN_OXFORD_MON_WED' has no occurrence at: '20210105T170000Z' (use DETACHED to set a detached override)",
+                "rdcl.evo_set",
+                "TEST_CALENDAR_UID",
+                "EVENT_IN_OXFORD_MON_WED",
+                "20210105T170000Z",
+                "SUMMARY:Some text",
+            );
+
             // Assert rdcl.evo_get date string format validation
             assert_error_returned!(
                 connection,
@@ -719,68 +1325,205 @@ mod integration {
         })
     }
 
-    fn test_event_override_prune(connection: &mut Connection) -> Result<()> {
-        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
-
-        for event_uid in ["EVENT_ONE", "EVENT_TWO"] {
-            set_and_assert_event!(connection, "TEST_CALENDAR_UID", event_uid, ["SUMMARY:NOT-OVERRIDDEN", "DTSTART:20200101T160000Z", "RRULE:COUNT=10;FREQ=DAILY;INTERVAL=1", "LAST-MODIFIED:20210501T090000Z"]);
+    fn test_event_override_mset(connection: &mut Connection) -> Result<()> {
+        listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
+            set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
 
-            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200101T120000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"]);
-            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200102T160000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"]);
-            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200104T160000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"]);
-            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200106T160000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"]);
-            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200108T160000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"]);
-            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200110T160000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"]);
-            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200112T120000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"]);
+            assert_keyspace_events_published!(message_queue, "rdcl.cal_set", "TEST_CALENDAR_UID");
 
-            list_and_assert_matching_event_overrides!(
+            set_and_assert_event!(
                 connection,
                 "TEST_CALENDAR_UID",
-                event_uid,
+                "EVENT_IN_OXFORD_MON_WED",
                 [
-                    ["DTSTART:20200101T120000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"],
-                    ["DTSTART:20200102T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
-                    ["DTSTART:20200104T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
-                    ["DTSTART:20200106T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
-                    ["DTSTART:20200108T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
-                    ["DTSTART:20200110T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
-                    ["DTSTART:20200112T120000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"],
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                    "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                    "DTSTART:20201231T170000Z",
+                    "DTEND:20201231T173000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "CATEGORIES:CATEGORY_ONE",
                 ],
             );
-        }
-
-        // Test pruning a specific event on a calendar
-        let pruned_override_count: i64 = prune_event_overrides!(
-            connection,
-            "TEST_CALENDAR_UID",
-            "EVENT_ONE",
-            "20200105T000000Z",
-            "20200110T160000Z",
-        );
 
-        assert_eq!(pruned_override_count, 3);
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:EVENT_IN_OXFORD_MON_WED LAST-MODIFIED:20210501T090000Z", "TEST_CALENDAR_UID");
 
-        list_and_assert_matching_event_overrides!(
-            connection,
-            "TEST_CALENDAR_UID",
-            "EVENT_ONE",
-            [
-                ["DTSTART:20200101T120000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"],
-                ["DTSTART:20200102T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
-                ["DTSTART:20200104T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
-                ["DTSTART:20200112T120000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"],
-            ],
-        );
+            let event_override_mset_result: Vec<Vec<String>> =
+                redis::cmd("rdcl.evo_mset")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("EVENT_IN_OXFORD_MON_WED")
+                    .arg("20210104T170000Z")
+                    .arg("LAST-MODIFIED:20210501T090000Z CATEGORIES:CATEGORY_TWO")
+                    .arg("20210106T170000Z")
+                    .arg("LAST-MODIFIED:20210501T090000Z SUMMARY:Overridden event in Oxford")
+                    .query(connection)?;
 
-        // Test pruning some overrides on all events on a calendar
-        let pruned_override_count: i64 = prune_event_overrides!(
-            connection,
-            "TEST_CALENDAR_UID",
-            "20200101T000000Z",
-            "20200108T160000Z",
-        );
+            assert_eq!(
+                event_override_mset_result,
+                vec![
+                    vec![
+                        String::from("CATEGORIES:CATEGORY_TWO"),
+                        String::from("DTSTART:20210104T170000Z"),
+                        String::from("LAST-MODIFIED:20210501T090000Z"),
+                    ],
+                    vec![
+                        String::from("DTSTART:20210106T170000Z"),
+                        String::from("LAST-MODIFIED:20210501T090000Z"),
+                        String::from("SUMMARY:Overridden event in Oxford"),
+                    ],
+                ],
+            );
 
-        assert_eq!(pruned_override_count, 8);
+            // Assert a single batched keyspace event is published for all overrides set.
+            assert_keyspace_events_published!(message_queue, "rdcl.evo_mset:EVENT_IN_OXFORD_MON_WED:20210104T170000Z,20210106T170000Z", "TEST_CALENDAR_UID");
+
+            query_calendar_and_assert_matching_event_instances!(
+                connection,
+                "TEST_CALENDAR_UID",
+                [
+                    "X-CATEGORIES:CATEGORY_TWO",
+                ],
+                [
+                    [
+                        [
+                            "DTSTART:20210104T170000Z",
+                        ],
+                        [
+                            "CATEGORIES:CATEGORY_TWO",
+                            "DTEND:20210104T173000Z",
+                            "DTSTART:20210104T170000Z",
+                            "DURATION:PT30M",
+                            "RECURRENCE-ID;VALUE=DATE-TIME:20210104T170000Z",
+                            "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                            "UID:EVENT_IN_OXFORD_MON_WED",
+                        ],
+                    ],
+                ],
+            );
+
+            // Assert that an override for a non-existent occurrence rejects the whole batch.
+            assert_error_returned!(
+                connection,
+                "Event: with UID: 'EVENT_IN_OXFORD_MON_WED' has no occurrence at: '20210105T170000Z' (use DETACHED to set detached overrides)",
+                "rdcl.evo_mset",
+                "TEST_CALENDAR_UID",
+                "EVENT_IN_OXFORD_MON_WED",
+                "20210111T170000Z",
+                "SUMMARY:Valid override",
+                "20210105T170000Z",
+                "SUMMARY:Override for a non-existent occurrence",
+            );
+
+            assert_keyspace_events_published!(message_queue, []);
+
+            assert_event_override_nil!(connection, "TEST_CALENDAR_UID", "EVENT_IN_OXFORD_MON_WED", "20210111T170000Z",);
+
+            // Assert overrides with a superseded LAST-MODIFIED are skipped without failing the batch.
+            let event_override_mset_result: Vec<Value> =
+                redis::cmd("rdcl.evo_mset")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("EVENT_IN_OXFORD_MON_WED")
+                    .arg("20210104T170000Z")
+                    .arg("LAST-MODIFIED:20210401T090000Z CATEGORIES:STALE_CATEGORY")
+                    .arg("20210111T170000Z")
+                    .arg("LAST-MODIFIED:20210501T090000Z SUMMARY:Another overridden event in Oxford")
+                    .query(connection)?;
+
+            assert_eq!(event_override_mset_result.len(), 2);
+            assert_eq!(event_override_mset_result[0], Value::Int(0));
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evo_mset:EVENT_IN_OXFORD_MON_WED:20210111T170000Z", "TEST_CALENDAR_UID");
+
+            assert_event_override_present!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_IN_OXFORD_MON_WED",
+                "20210104T170000Z",
+                [
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "CATEGORIES:CATEGORY_TWO",
+                ],
+            );
+
+            // Assert DETACHED allows overrides for dates which are not occurrences of the event.
+            let event_override_mset_result: Vec<Vec<String>> =
+                redis::cmd("rdcl.evo_mset")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("EVENT_IN_OXFORD_MON_WED")
+                    .arg("DETACHED")
+                    .arg("20210105T170000Z")
+                    .arg("LAST-MODIFIED:20210501T090000Z SUMMARY:Detached override")
+                    .query(connection)?;
+
+            assert_eq!(event_override_mset_result.len(), 1);
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evo_mset:EVENT_IN_OXFORD_MON_WED:20210105T170000Z", "TEST_CALENDAR_UID");
+
+            Ok(())
+        })
+    }
+
+    fn test_event_override_prune(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        for event_uid in ["EVENT_ONE", "EVENT_TWO"] {
+            set_and_assert_event!(connection, "TEST_CALENDAR_UID", event_uid, ["SUMMARY:NOT-OVERRIDDEN", "DTSTART:20200101T160000Z", "RRULE:COUNT=10;FREQ=DAILY;INTERVAL=1", "LAST-MODIFIED:20210501T090000Z"]);
+
+            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200101T120000Z", DETACHED, ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"]);
+            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200102T160000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"]);
+            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200104T160000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"]);
+            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200106T160000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"]);
+            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200108T160000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"]);
+            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200110T160000Z", ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"]);
+            set_and_assert_event_override!(connection, "TEST_CALENDAR_UID", event_uid, "20200112T120000Z", DETACHED, ["LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"]);
+
+            list_and_assert_matching_event_overrides!(
+                connection,
+                "TEST_CALENDAR_UID",
+                event_uid,
+                [
+                    ["DTSTART:20200101T120000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"],
+                    ["DTSTART:20200102T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
+                    ["DTSTART:20200104T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
+                    ["DTSTART:20200106T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
+                    ["DTSTART:20200108T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
+                    ["DTSTART:20200110T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
+                    ["DTSTART:20200112T120000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"],
+                ],
+            );
+        }
+
+        // Test pruning a specific event on a calendar
+        let pruned_override_count: i64 = prune_event_overrides!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "20200105T000000Z",
+            "20200110T160000Z",
+        );
+
+        assert_eq!(pruned_override_count, 3);
+
+        list_and_assert_matching_event_overrides!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                ["DTSTART:20200101T120000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"],
+                ["DTSTART:20200102T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
+                ["DTSTART:20200104T160000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN"],
+                ["DTSTART:20200112T120000Z", "LAST-MODIFIED:20210501T090000Z", "SUMMARY:OVERRIDDEN - DETACHED"],
+            ],
+        );
+
+        // Test pruning some overrides on all events on a calendar
+        let pruned_override_count: i64 = prune_event_overrides!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "20200101T000000Z",
+            "20200108T160000Z",
+        );
+
+        assert_eq!(pruned_override_count, 8);
 
         list_and_assert_matching_event_overrides!(
             connection,
@@ -1125,6 +1868,52 @@ mod integration {
             ],
         );
 
+        // Cancelling an occurrence via override (STATUS:CANCELLED) omits it from the event instances.
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            "20210106T170000Z",
+            [
+                "LAST-MODIFIED:20210501T090000Z",
+                "STATUS:CANCELLED",
+            ],
+        );
+
+        list_and_assert_matching_event_instances!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                [
+                    "DTEND:20210104T173000Z",
+                    "DTSTART:20210104T170000Z",
+                    "RECURRENCE-ID;VALUE=DATE-TIME:20210104T170000Z",
+                    "UID:EVENT_IN_OXFORD_MON_WED",
+                    "DURATION:PT30M",
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                    "RELATED-TO;RELTYPE=PARENT:PARENT_UID",
+                    "CATEGORIES:CATEGORY TWO,CATEGORY_ONE",
+                    "LOCATION-TYPE:X-KEY=VALUE:LOCATION_TYPE",
+                    "GEO:51.751365550307604;-1.2601196837753945",
+                ],
+                [
+                    "DTEND:20210111T173000Z",
+                    "DTSTART:20210111T170000Z",
+                    "RECURRENCE-ID;VALUE=DATE-TIME:20210111T170000Z",
+                    "UID:EVENT_IN_OXFORD_MON_WED",
+                    "DURATION:PT30M",
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                    "RELATED-TO;RELTYPE=PARENT:PARENT_UID",
+                    "CATEGORIES:CATEGORY TWO,CATEGORY_ONE",
+                    "LOCATION-TYPE:X-KEY=VALUE:LOCATION_TYPE",
+                    "GEO:51.751365550307604;-1.2601196837753945",
+                ],
+            ],
+        );
+
+        del_and_assert_event_override_deletion!(connection, "TEST_CALENDAR_UID", "EVENT_IN_OXFORD_MON_WED", "20210106T170000Z", 1);
+
         list_and_assert_matching_event_instances!(
             connection,
             "TEST_CALENDAR_UID",
@@ -1137,71 +1926,498 @@ mod integration {
         Ok(())
     }
 
-    fn test_event_timezone_handling(connection: &mut Connection) -> Result<()> {
+    fn test_event_instance_next(connection: &mut Connection) -> Result<()> {
         set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
 
         set_and_assert_event!(
             connection,
             "TEST_CALENDAR_UID",
-            "TIMEZONE_EVENT",
+            "EVENT_IN_OXFORD_MON_WED",
             [
-                "LAST-MODIFIED:20241001T100000Z",
-                "RRULE:COUNT=3;FREQ=MONTHLY;INTERVAL=4",
-                "DTSTART;TZID=Europe/London:20241001T100000",
-                "DTEND;TZID=Europe/London:20241001T110000",
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
             ],
         );
 
-        list_and_assert_matching_event_instances!(
+        next_and_assert_matching_event_instances!(
             connection,
             "TEST_CALENDAR_UID",
-            "TIMEZONE_EVENT",
+            "EVENT_IN_OXFORD_MON_WED",
+            2,
+            "20210105T000000Z",
             [
                 [
-                    "DTEND:20241001T100000Z",
-                    "DTSTART:20241001T090000Z",
-                    "DURATION:PT1H",
-                    "RECURRENCE-ID;VALUE=DATE-TIME:20241001T090000Z",
-                    "UID:TIMEZONE_EVENT",
+                    "DTEND:20210106T173000Z",
+                    "DTSTART:20210106T170000Z",
+                    "RECURRENCE-ID;VALUE=DATE-TIME:20210106T170000Z",
+                    "UID:EVENT_IN_OXFORD_MON_WED",
+                    "DURATION:PT30M",
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
                 ],
                 [
-                    "DTEND:20250201T110000Z",
-                    "DTSTART:20250201T100000Z",
-                    "DURATION:PT1H",
-                    "RECURRENCE-ID;VALUE=DATE-TIME:20250201T100000Z",
-                    "UID:TIMEZONE_EVENT",
+                    "DTEND:20210111T173000Z",
+                    "DTSTART:20210111T170000Z",
+                    "RECURRENCE-ID;VALUE=DATE-TIME:20210111T170000Z",
+                    "UID:EVENT_IN_OXFORD_MON_WED",
+                    "DURATION:PT30M",
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
                 ],
+            ],
+        );
+
+        // Assert that event instances starting exactly at the anchor are included.
+        next_and_assert_matching_event_instances!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            1,
+            "20210111T170000Z",
+            [
                 [
-                    "DTEND:20250601T100000Z",
-                    "DTSTART:20250601T090000Z",
-                    "DURATION:PT1H",
-                    "RECURRENCE-ID;VALUE=DATE-TIME:20250601T090000Z",
-                    "UID:TIMEZONE_EVENT",
+                    "DTEND:20210111T173000Z",
+                    "DTSTART:20210111T170000Z",
+                    "RECURRENCE-ID;VALUE=DATE-TIME:20210111T170000Z",
+                    "UID:EVENT_IN_OXFORD_MON_WED",
+                    "DURATION:PT30M",
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
                 ],
-            ]
+            ],
         );
 
-        set_and_assert_event_override!(
+        // Assert no event instances are returned once the recurrence set has been exhausted.
+        let event_instance_next_result: Vec<Vec<String>> =
+            redis::cmd("rdcl.evi_next")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_IN_OXFORD_MON_WED")
+                .arg(2)
+                .query(connection)?;
+
+        assert!(event_instance_next_result.is_empty());
+
+        // Assert the next event instances of a never ending recurrence are returned from now.
+        set_and_assert_event!(
             connection,
             "TEST_CALENDAR_UID",
-            "TIMEZONE_EVENT",
-            "20250201T100000Z",
+            "DAILY_ONLINE_EVENT",
             [
-                "LAST-MODIFIED:20241001T100000Z",
-                "SUMMARY:Overridden event",
+                "SUMMARY:Daily online event at 4:00PM",
+                "RRULE:FREQ=DAILY;INTERVAL=1",
+                "DTSTART:20201231T160000Z",
             ],
         );
 
-        // Assert default query when mixing a couple of existing event (with overide) extrapolations.
-        query_calendar_and_assert_matching_event_instances!(
+        let event_instance_next_result: Vec<Vec<String>> =
+            redis::cmd("rdcl.evi_next")
+                .arg("TEST_CALENDAR_UID")
+                .arg("DAILY_ONLINE_EVENT")
+                .arg(3)
+                .query(connection)?;
+
+        assert_eq!(event_instance_next_result.len(), 3);
+
+        // Assert that the occurrences preceding a distant anchor do not count towards the
+        // occurrence expansion max.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.OCCURRENCE-EXPANSION-MAX")
+            .arg("2")
+            .execute(connection);
+
+        let event_instance_next_result: Vec<Vec<String>> =
+            redis::cmd("rdcl.evi_next")
+                .arg("TEST_CALENDAR_UID")
+                .arg("DAILY_ONLINE_EVENT")
+                .arg(2)
+                .arg("20300101T000000Z")
+                .query(connection)?;
+
+        assert_eq!(event_instance_next_result.len(), 2);
+        assert!(event_instance_next_result[0].contains(&String::from("DTSTART:20300101T160000Z")));
+        assert!(event_instance_next_result[1].contains(&String::from("DTSTART:20300102T160000Z")));
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.OCCURRENCE-EXPANSION-MAX")
+            .arg("0")
+            .execute(connection);
+
+        let event_instance_next_result: Option<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evi_next")
+                .arg("TEST_CALENDAR_UID")
+                .arg("NON_EXISTENT_EVENT")
+                .arg(3)
+                .query(connection)?;
+
+        assert_eq!(event_instance_next_result, None);
+
+        Ok(())
+    }
+
+    fn test_event_instance_get(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
             connection,
             "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
             [
-                "X-TZID:Europe/London",
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
             ],
-            [
-                [
-                    [
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            "20210106T170000Z",
+            [
+                "LAST-MODIFIED:20210501T090000Z",
+                "SUMMARY:Overridden event in Oxford",
+            ],
+        );
+
+        let event_instance_get_result: Vec<String> =
+            redis::cmd("rdcl.evi_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_IN_OXFORD_MON_WED")
+                .arg("20210106T170000Z")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_instance_get_result,
+            vec![
+                String::from("DTEND:20210106T173000Z"),
+                String::from("DTSTART:20210106T170000Z"),
+                String::from("DURATION:PT30M"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210106T170000Z"),
+                String::from("SUMMARY:Overridden event in Oxford"),
+                String::from("UID:EVENT_IN_OXFORD_MON_WED"),
+            ],
+        );
+
+        // Assert the event instance is rendered in the provided timezone.
+        let event_instance_get_result: Vec<String> =
+            redis::cmd("rdcl.evi_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_IN_OXFORD_MON_WED")
+                .arg("20210111T170000Z")
+                .arg("TZID")
+                .arg("America/New_York")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_instance_get_result,
+            vec![
+                String::from("DTEND;TZID=America/New_York:20210111T123000"),
+                String::from("DTSTART;TZID=America/New_York:20210111T120000"),
+                String::from("DURATION:PT30M"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME;TZID=America/New_York:20210111T120000"),
+                String::from("SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM"),
+                String::from("UID:EVENT_IN_OXFORD_MON_WED"),
+            ],
+        );
+
+        // Assert nil is returned for date-times which are not an occurrence of the event.
+        assert_eq!(
+            redis::cmd("rdcl.evi_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_IN_OXFORD_MON_WED")
+                .arg("20210106T173000Z")
+                .query(connection),
+            RedisResult::Ok(Value::Nil),
+        );
+
+        assert_error_returned!(
+            connection,
+            "No: event with UID: 'NON_EXISTENT_EVENT' found",
+            "rdcl.evi_get",
+            "TEST_CALENDAR_UID",
+            "NON_EXISTENT_EVENT",
+            "20210106T170000Z",
+        );
+
+        assert_error_returned!(
+            connection,
+            "rdcl.evi_get:: Invalid TZID: Not/A_Timezone",
+            "rdcl.evi_get",
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            "20210106T170000Z",
+            "TZID",
+            "Not/A_Timezone",
+        );
+
+        Ok(())
+    }
+
+    fn test_event_instance_add(connection: &mut Connection) -> Result<()> {
+        listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
+            set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+            assert_keyspace_events_published!(message_queue, "rdcl.cal_set", "TEST_CALENDAR_UID");
+
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_IN_OXFORD_MON_WED",
+                [
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                    "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                    "DTSTART:20201231T170000Z",
+                    "DTEND:20201231T173000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                ],
+            );
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:EVENT_IN_OXFORD_MON_WED LAST-MODIFIED:20210501T090000Z", "TEST_CALENDAR_UID");
+
+            // Assert an occurrence (Tue 5th Jan) is added alongside it's override in one step.
+            let event_instance_add_result: Vec<String> =
+                redis::cmd("rdcl.evi_add")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("EVENT_IN_OXFORD_MON_WED")
+                    .arg("20210105T170000Z")
+                    .arg("LAST-MODIFIED:20210502T090000Z")
+                    .arg("SUMMARY:Added event in Oxford")
+                    .query(connection)?;
+
+            assert_matching_ical_properties!(
+                event_instance_add_result,
+                vec![
+                    String::from("DTEND:20210105T173000Z"),
+                    String::from("DTSTART:20210105T170000Z"),
+                    String::from("DURATION:PT30M"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210105T170000Z"),
+                    String::from("SUMMARY:Added event in Oxford"),
+                    String::from("UID:EVENT_IN_OXFORD_MON_WED"),
+                ],
+            );
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evi_add:EVENT_IN_OXFORD_MON_WED:20210105T170000Z LAST-MODIFIED:20210502T090000Z", "TEST_CALENDAR_UID");
+
+            // Assert an occurrence (Fri 8th Jan) is added without an override.
+            let event_instance_add_result: Vec<String> =
+                redis::cmd("rdcl.evi_add")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("EVENT_IN_OXFORD_MON_WED")
+                    .arg("20210108T170000Z")
+                    .query(connection)?;
+
+            assert_matching_ical_properties!(
+                event_instance_add_result,
+                vec![
+                    String::from("DTEND:20210108T173000Z"),
+                    String::from("DTSTART:20210108T170000Z"),
+                    String::from("DURATION:PT30M"),
+                    String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210108T170000Z"),
+                    String::from("SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM"),
+                    String::from("UID:EVENT_IN_OXFORD_MON_WED"),
+                ],
+            );
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evi_add:EVENT_IN_OXFORD_MON_WED:20210108T170000Z LAST-MODIFIED:20210501T090000Z", "TEST_CALENDAR_UID");
+
+            // Assert the added occurrences are extrapolated alongside the scheduled ones.
+            let event_instance_list_result: Vec<Vec<String>> =
+                redis::cmd("rdcl.evi_list")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("EVENT_IN_OXFORD_MON_WED")
+                    .query(connection)?;
+
+            assert_eq!(
+                event_instance_list_result
+                    .iter()
+                    .filter_map(|event_instance| event_instance.iter().find(|property| property.starts_with("DTSTART")).cloned())
+                    .collect::<Vec<String>>(),
+                vec![
+                    String::from("DTSTART:20210104T170000Z"),
+                    String::from("DTSTART:20210105T170000Z"),
+                    String::from("DTSTART:20210106T170000Z"),
+                    String::from("DTSTART:20210108T170000Z"),
+                    String::from("DTSTART:20210111T170000Z"),
+                ],
+            );
+
+            assert_error_returned!(
+                connection,
+                "No: event with UID: 'NON_EXISTENT_EVENT' found",
+                "rdcl.evi_add",
+                "TEST_CALENDAR_UID",
+                "NON_EXISTENT_EVENT",
+                "20210105T170000Z",
+            );
+
+            Ok(())
+        })
+    }
+
+    fn test_event_children(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "PROGRAMME_UID",
+            [
+                "SUMMARY:Programme",
+                "DTSTART:20210104T090000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "SESSION_TWO",
+            [
+                "SUMMARY:Session two",
+                "DTSTART:20210106T090000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "RELATED-TO;RELTYPE=PARENT:PROGRAMME_UID",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "SESSION_ONE",
+            [
+                "SUMMARY:Session one",
+                "DTSTART:20210105T090000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "RELATED-TO;RELTYPE=PARENT:PROGRAMME_UID",
+                "RELATED-TO;RELTYPE=SIBLING:SESSION_TWO",
+            ],
+        );
+
+        let event_children_result: Vec<Vec<String>> =
+            redis::cmd("rdcl.evt_children")
+                .arg("TEST_CALENDAR_UID")
+                .arg("PARENT")
+                .arg("PROGRAMME_UID")
+                .query(connection)?;
+
+        assert_eq!(
+            event_children_result
+                .iter()
+                .filter_map(|event| event.iter().find(|property| property.starts_with("UID:")).cloned())
+                .collect::<Vec<String>>(),
+            vec![
+                String::from("UID:SESSION_ONE"),
+                String::from("UID:SESSION_TWO"),
+            ],
+        );
+
+        let event_children_result: Vec<Vec<String>> =
+            redis::cmd("rdcl.evt_children")
+                .arg("TEST_CALENDAR_UID")
+                .arg("sibling")
+                .arg("SESSION_TWO")
+                .query(connection)?;
+
+        assert_eq!(
+            event_children_result
+                .iter()
+                .filter_map(|event| event.iter().find(|property| property.starts_with("UID:")).cloned())
+                .collect::<Vec<String>>(),
+            vec![
+                String::from("UID:SESSION_ONE"),
+            ],
+        );
+
+        let event_children_result: Vec<Vec<String>> =
+            redis::cmd("rdcl.evt_children")
+                .arg("TEST_CALENDAR_UID")
+                .arg("PARENT")
+                .arg("SESSION_ONE")
+                .query(connection)?;
+
+        assert!(event_children_result.is_empty());
+
+        redis::cmd("rdcl.cal_idx_disable").arg("TEST_CALENDAR_UID").execute(connection);
+
+        assert_error_returned!(
+            connection,
+            "rdcl.evt_children:: Disabled on Calendar: TEST_CALENDAR_UID because it's indexes have been disabled.",
+            "rdcl.evt_children",
+            "TEST_CALENDAR_UID",
+            "PARENT",
+            "PROGRAMME_UID",
+        );
+
+        Ok(())
+    }
+
+    fn test_event_timezone_handling(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "TIMEZONE_EVENT",
+            [
+                "LAST-MODIFIED:20241001T100000Z",
+                "RRULE:COUNT=3;FREQ=MONTHLY;INTERVAL=4",
+                "DTSTART;TZID=Europe/London:20241001T100000",
+                "DTEND;TZID=Europe/London:20241001T110000",
+            ],
+        );
+
+        list_and_assert_matching_event_instances!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "TIMEZONE_EVENT",
+            [
+                [
+                    "DTEND:20241001T100000Z",
+                    "DTSTART:20241001T090000Z",
+                    "DURATION:PT1H",
+                    "RECURRENCE-ID;VALUE=DATE-TIME:20241001T090000Z",
+                    "UID:TIMEZONE_EVENT",
+                ],
+                [
+                    "DTEND:20250201T110000Z",
+                    "DTSTART:20250201T100000Z",
+                    "DURATION:PT1H",
+                    "RECURRENCE-ID;VALUE=DATE-TIME:20250201T100000Z",
+                    "UID:TIMEZONE_EVENT",
+                ],
+                [
+                    "DTEND:20250601T100000Z",
+                    "DTSTART:20250601T090000Z",
+                    "DURATION:PT1H",
+                    "RECURRENCE-ID;VALUE=DATE-TIME:20250601T090000Z",
+                    "UID:TIMEZONE_EVENT",
+                ],
+            ]
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "TIMEZONE_EVENT",
+            "20250201T100000Z",
+            [
+                "LAST-MODIFIED:20241001T100000Z",
+                "SUMMARY:Overridden event",
+            ],
+        );
+
+        // Assert default query when mixing a couple of existing event (with overide) extrapolations.
+        query_calendar_and_assert_matching_event_instances!(
+            connection,
+            "TEST_CALENDAR_UID",
+            [
+                "X-TZID:Europe/London",
+            ],
+            [
+                [
+                    [
                         "DTSTART;TZID=Europe/London:20241001T100000",
                     ],
                     [
@@ -1317,6 +2533,7 @@ mod integration {
             "TEST_CALENDAR_UID",
             "OVERRIDDEN_EVENT_IN_BRISTOL_TUE_THU",
             "20210108T183000Z",
+            DETACHED,
             [
                 "LAST-MODIFIED:20210501T090000Z",
                 "SUMMARY:Detatched override for Event in Bristol with invalid DTSTART",
@@ -1428,6 +2645,16 @@ mod integration {
             ],
         );
 
+        // Assert querying by (effective) duration excludes all 30 minute event instances
+        query_calendar_and_assert_matching_event_instances!(
+            connection,
+            "TEST_CALENDAR_UID",
+            [
+                "X-DURATION;OP=GT:PT30M",
+            ],
+            [],
+        );
+
         // Assert simple negative querying
         query_calendar_and_assert_matching_event_instances!(
             connection,
@@ -2507,6 +3734,24 @@ mod integration {
 
             assert_keyspace_events_published!(message_queue, "rdcl.evo_set:EVENT_IN_OXFORD_MON_WED:20210104T170000Z LAST-MODIFIED:20210501T090000Z", "TEST_CALENDAR_UID");
 
+            assert_calendar_stats!(
+                connection,
+                "TEST_CALENDAR_UID",
+                [
+                    ("event_count", Value::Int(1)),
+                    ("override_count", Value::Int(1)),
+                    ("indexes_active", Value::Int(1)),
+                    ("indexed_categories_term_count", Value::Int(3)),
+                    ("indexed_location_type_term_count", Value::Int(0)),
+                    ("indexed_related_to_term_count", Value::Int(2)),
+                    ("indexed_geo_term_count", Value::Int(1)),
+                    ("indexed_class_term_count", Value::Int(0)),
+                    ("indexed_location_term_count", Value::Int(0)),
+                    ("earliest_dtstart", Value::Status(String::from("20201231T170000Z"))),
+                    ("latest_dtstart", Value::Status(String::from("20201231T170000Z"))),
+                ],
+            );
+
             // Assert Calendar indexes working with query to strip out overridden event occurrence.
             query_calendar_and_assert_matching_event_instances!(
                 connection,
@@ -2542,6 +3787,19 @@ mod integration {
 
             assert_keyspace_events_published!(message_queue, []);
 
+            assert_calendar_stats!(
+                connection,
+                "TEST_CALENDAR_UID",
+                [
+                    ("event_count", Value::Int(1)),
+                    ("override_count", Value::Int(1)),
+                    ("indexes_active", Value::Int(0)),
+                    ("indexed_categories_term_count", Value::Int(0)),
+                    ("indexed_related_to_term_count", Value::Int(0)),
+                    ("indexed_geo_term_count", Value::Int(0)),
+                ],
+            );
+
             // Assert error reporting Calendar querying disabled
             let disabled_query_result: Result<Vec<String>, String> =
                 redis::cmd("rdcl.evi_query")
@@ -2559,6 +3817,29 @@ mod integration {
 
             rebuild_calendar_indexes!(connection, "TEST_CALENDAR_UID");
 
+            // The rebuild completion keyspace event carries the (varying) rebuild duration and
+            // statistics, so is awaited and asserted on separately.
+            let rebuild_complete_event =
+                wait_for_keyspace_event(
+                    message_queue,
+                    "rdcl.cal_idx_rebuild_complete:",
+                    "TEST_CALENDAR_UID",
+                    std::time::Duration::from_secs(5),
+                )?;
+
+            let rebuild_complete_stats: Vec<&str> =
+                rebuild_complete_event
+                    .strip_prefix("rdcl.cal_idx_rebuild_complete:")
+                    .unwrap()
+                    .split(':')
+                    .collect();
+
+            // Duration (in milliseconds), events processed, and the number of terms indexed.
+            assert_eq!(rebuild_complete_stats.len(), 3);
+            assert!(rebuild_complete_stats[0].parse::<u128>().is_ok());
+            assert_eq!(rebuild_complete_stats[1], "1");
+            assert!(rebuild_complete_stats[2].parse::<usize>().unwrap() > 0);
+
             assert_keyspace_events_published!(message_queue, "rdcl.cal_idx_rebuild", "TEST_CALENDAR_UID");
 
             // Test that querying is re-enabled and indexes work again.
@@ -2592,53 +3873,405 @@ mod integration {
         })
     }
 
-    fn test_rdb_save_load(connection: &mut Connection) -> Result<()> {
+    fn test_json_output(connection: &mut Connection) -> Result<()> {
         set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
 
-        set_and_assert_event!(
-            connection,
-            "TEST_CALENDAR_UID",
-            "ONLINE_EVENT_MON_WED",
-            [
-                "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM",
-                "RRULE:BYDAY=MO,WE;FREQ=WEEKLY;INTERVAL=1;UNTIL=20211231T170000Z",
-                "DTSTART:20201231T160000Z",
-                "DTEND:20201231T170000Z",
-                "LAST-MODIFIED:20210501T090000Z",
-                "RELATED-TO;RELTYPE=PARENT:PARENT_UID",
-                "CATEGORIES:CATEGORY TWO,CATEGORY_ONE",
-            ],
-        );
-
         set_and_assert_event!(
             connection,
             "TEST_CALENDAR_UID",
             "EVENT_IN_OXFORD_MON_WED",
             [
                 "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
-                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "RRULE:BYDAY=MO,WE;COUNT=2;FREQ=WEEKLY;INTERVAL=1",
                 "DTSTART:20201231T170000Z",
                 "DTEND:20201231T173000Z",
                 "LAST-MODIFIED:20210501T090000Z",
-                "RELATED-TO;RELTYPE=PARENT:PARENT_UID",
-                "CATEGORIES:CATEGORY TWO,CATEGORY_ONE",
-                "GEO:51.751365550307604;-1.2601196837753945",
+                "CATEGORIES:CATEGORY_ONE",
             ],
         );
 
-        list_and_assert_matching_events!(
-            connection,
-            "TEST_CALENDAR_UID",
-            [
+        let event_list_result: Vec<String> =
+            redis::cmd("rdcl.evt_list")
+                .arg("TEST_CALENDAR_UID")
+                .arg("OUTPUT")
+                .arg("JSON")
+                .query(connection)?;
+
+        assert_eq!(
+            event_list_result,
+            vec![
                 [
-                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
-                    "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
-                    "DTSTART:20201231T170000Z",
-                    "DTEND:20201231T173000Z",
-                    "LAST-MODIFIED:20210501T090000Z",
-                    "RELATED-TO;RELTYPE=PARENT:PARENT_UID",
-                    "CATEGORIES:CATEGORY TWO,CATEGORY_ONE",
-                    "GEO:51.751365550307604;-1.2601196837753945",
+                    "{",
+                    r#""CATEGORIES":"CATEGORY_ONE","#,
+                    r#""DTEND":"20201231T173000Z","#,
+                    r#""DTSTART":"20201231T170000Z","#,
+                    r#""LAST-MODIFIED":"20210501T090000Z","#,
+                    r#""RRULE":"BYDAY=MO,WE;COUNT=2;FREQ=WEEKLY;INTERVAL=1","#,
+                    r#""SUMMARY":"Event in Oxford on Mondays and Wednesdays at 5:00PM","#,
+                    r#""UID":"EVENT_IN_OXFORD_MON_WED""#,
+                    "}",
+                ].concat(),
+            ],
+        );
+
+        let event_instance_list_result: Vec<String> =
+            redis::cmd("rdcl.evi_list")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_IN_OXFORD_MON_WED")
+                .arg(0)
+                .arg(1)
+                .arg("output")
+                .arg("json")
+                .query(connection)?;
+
+        assert_eq!(
+            event_instance_list_result,
+            vec![
+                [
+                    "{",
+                    r#""CATEGORIES":"CATEGORY_ONE","#,
+                    r#""DTEND":"20210104T173000Z","#,
+                    r#""DTSTART":"20210104T170000Z","#,
+                    r#""DURATION":"PT30M","#,
+                    r#""RECURRENCE-ID":{"params":{"VALUE":"DATE-TIME"},"value":"20210104T170000Z"},"#,
+                    r#""SUMMARY":"Event in Oxford on Mondays and Wednesdays at 5:00PM","#,
+                    r#""UID":"EVENT_IN_OXFORD_MON_WED""#,
+                    "}",
+                ].concat(),
+            ],
+        );
+
+        let event_query_result: Vec<Vec<String>> =
+            redis::cmd("rdcl.evt_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-CATEGORIES:CATEGORY_ONE")
+                .arg("OUTPUT")
+                .arg("JSON")
+                .query(connection)?;
+
+        assert_eq!(
+            event_query_result,
+            vec![
+                vec![
+                    String::from(r#"{"DTSTART":"20201231T170000Z"}"#),
+                    event_list_result[0].to_owned(),
+                ],
+            ],
+        );
+
+        let event_instance_query_result: Vec<Vec<String>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-CATEGORIES:CATEGORY_ONE")
+                .arg("X-LIMIT:1")
+                .arg("OUTPUT")
+                .arg("JSON")
+                .query(connection)?;
+
+        assert_eq!(
+            event_instance_query_result,
+            vec![
+                vec![
+                    String::from(r#"{"DTSTART":"20210104T170000Z"}"#),
+                    event_instance_list_result[0].to_owned(),
+                ],
+            ],
+        );
+
+        // Assert explicitly requesting iCalendar output returns the content lines.
+        let event_list_result: Vec<Vec<String>> =
+            redis::cmd("rdcl.evt_list")
+                .arg("TEST_CALENDAR_UID")
+                .arg("OUTPUT")
+                .arg("ICAL")
+                .query(connection)?;
+
+        assert_eq!(event_list_result[0].len(), 7);
+
+        assert_error_returned!(
+            connection,
+            "Unknown: OUTPUT format: XML (expected ICAL or JSON)",
+            "rdcl.evt_list",
+            "TEST_CALENDAR_UID",
+            "OUTPUT",
+            "XML",
+        );
+
+        Ok(())
+    }
+
+    fn test_resp3_replies(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=2;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "CATEGORIES:CATEGORY_ONE",
+            ],
+        );
+
+        // The redis client crate does not support RESP3, so the replies are asserted raw.
+        let mut stream = std::net::TcpStream::connect("127.0.0.1:6480")?;
+
+        let hello_reply = utils::send_raw_redis_command(&mut stream, &["HELLO", "3"])?;
+
+        assert!(hello_reply.starts_with('%'), "unexpected HELLO reply: {hello_reply}");
+
+        let event_get_reply = utils::send_raw_redis_command(&mut stream, &["rdcl.evt_get", "TEST_CALENDAR_UID", "EVENT_IN_OXFORD_MON_WED"])?;
+
+        assert!(event_get_reply.starts_with("%7\r\n"), "unexpected rdcl.evt_get reply: {event_get_reply}");
+
+        for expected_pair in [
+            "$10\r\nCATEGORIES\r\n+CATEGORY_ONE\r\n",
+            "$5\r\nDTEND\r\n+20201231T173000Z\r\n",
+            "$7\r\nDTSTART\r\n+20201231T170000Z\r\n",
+            "$13\r\nLAST-MODIFIED\r\n+20210501T090000Z\r\n",
+            "$5\r\nRRULE\r\n+BYDAY=MO,WE;COUNT=2;FREQ=WEEKLY;INTERVAL=1\r\n",
+            "$7\r\nSUMMARY\r\n+Event in Oxford on Mondays and Wednesdays at 5:00PM\r\n",
+            "$3\r\nUID\r\n+EVENT_IN_OXFORD_MON_WED\r\n",
+        ] {
+            assert!(event_get_reply.contains(expected_pair), "rdcl.evt_get reply: {event_get_reply} missing: {expected_pair}");
+        }
+
+        // Event instances are keyed by their RECURRENCE-ID.
+        let event_instance_list_reply = utils::send_raw_redis_command(&mut stream, &["rdcl.evi_list", "TEST_CALENDAR_UID", "EVENT_IN_OXFORD_MON_WED"])?;
+
+        assert!(event_instance_list_reply.starts_with("%2\r\n"), "unexpected rdcl.evi_list reply: {event_instance_list_reply}");
+
+        for expected_key in [
+            "$16\r\n20210104T170000Z\r\n%",
+            "$16\r\n20210106T170000Z\r\n%",
+        ] {
+            assert!(event_instance_list_reply.contains(expected_key), "rdcl.evi_list reply: {event_instance_list_reply} missing: {expected_key}");
+        }
+
+        // Query results remain an array (to preserve ordering) of maps.
+        let event_query_reply = utils::send_raw_redis_command(&mut stream, &["rdcl.evt_query", "TEST_CALENDAR_UID", "X-CATEGORIES:CATEGORY_ONE"])?;
+
+        assert!(event_query_reply.starts_with("*1\r\n*2\r\n%"), "unexpected rdcl.evt_query reply: {event_query_reply}");
+        assert!(event_query_reply.contains("$3\r\nUID\r\n+EVENT_IN_OXFORD_MON_WED\r\n"), "unexpected rdcl.evt_query reply: {event_query_reply}");
+
+        // Assert RESP2 clients still receive the flat array replies.
+        assert_event_present!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "CATEGORIES:CATEGORY_ONE",
+                "DTEND:20201231T173000Z",
+                "DTSTART:20201231T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "RRULE:BYDAY=MO,WE;COUNT=2;FREQ=WEEKLY;INTERVAL=1",
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_command_getkeys(connection: &mut Connection) -> Result<()> {
+        // Assert every command declares the calendar key position, so that Redis Cluster can
+        // route it to the correct slot (and reject cross-slot usage).
+        let commands: Vec<Vec<&str>> = vec![
+            vec!["rdcl.evt_set", "TEST_CALENDAR_UID", "EVENT_UID", "DTSTART:20201231T170000Z"],
+            vec!["rdcl.evt_mset", "TEST_CALENDAR_UID", "EVENT_UID", "DTSTART:20201231T170000Z"],
+            vec!["rdcl.evt_import", "TEST_CALENDAR_UID", "BEGIN:VCALENDAR"],
+            vec!["rdcl.evt_get", "TEST_CALENDAR_UID", "EVENT_UID"],
+            vec!["rdcl.evt_del", "TEST_CALENDAR_UID", "EVENT_UID"],
+            vec!["rdcl.evt_list", "TEST_CALENDAR_UID"],
+            vec!["rdcl.evt_children", "TEST_CALENDAR_UID", "PARENT", "EVENT_UID"],
+            vec!["rdcl.evt_scan", "TEST_CALENDAR_UID", "0"],
+            vec!["rdcl.evt_query", "TEST_CALENDAR_UID", "X-LIMIT:1"],
+            vec!["rdcl.evt_prune", "TEST_CALENDAR_UID", "20201231T170000Z", "20211231T170000Z"],
+            vec!["rdcl.evi_add", "TEST_CALENDAR_UID", "EVENT_UID", "20201231T170000Z"],
+            vec!["rdcl.evi_get", "TEST_CALENDAR_UID", "EVENT_UID", "20201231T170000Z"],
+            vec!["rdcl.evi_list", "TEST_CALENDAR_UID", "EVENT_UID"],
+            vec!["rdcl.evi_next", "TEST_CALENDAR_UID", "EVENT_UID", "1"],
+            vec!["rdcl.evi_query", "TEST_CALENDAR_UID", "X-LIMIT:1"],
+            vec!["rdcl.evo_get", "TEST_CALENDAR_UID", "EVENT_UID", "20201231T170000Z"],
+            vec!["rdcl.evo_set", "TEST_CALENDAR_UID", "EVENT_UID", "20201231T170000Z", "SUMMARY:Override"],
+            vec!["rdcl.evo_mset", "TEST_CALENDAR_UID", "EVENT_UID", "20201231T170000Z", "SUMMARY:Override"],
+            vec!["rdcl.evo_del", "TEST_CALENDAR_UID", "EVENT_UID", "20201231T170000Z"],
+            vec!["rdcl.evo_list", "TEST_CALENDAR_UID", "EVENT_UID"],
+            vec!["rdcl.evo_prune", "TEST_CALENDAR_UID", "EVENT_UID", "20201231T170000Z", "20211231T170000Z"],
+            vec!["rdcl.cal_set", "TEST_CALENDAR_UID"],
+            vec!["rdcl.cal_get", "TEST_CALENDAR_UID"],
+            vec!["rdcl.cal_stats", "TEST_CALENDAR_UID"],
+            vec!["rdcl.cal_export", "TEST_CALENDAR_UID"],
+            vec!["rdcl.cal_idx_disable", "TEST_CALENDAR_UID"],
+            vec!["rdcl.cal_idx_rebuild", "TEST_CALENDAR_UID"],
+            vec!["rdcl.cal_idx_terms", "TEST_CALENDAR_UID", "CATEGORIES"],
+            vec!["rdcl.query_explain", "TEST_CALENDAR_UID", "EVI", "X-LIMIT:1"],
+        ];
+
+        for command in commands {
+            let keys: Vec<String> = redis::cmd("COMMAND").arg("GETKEYS").arg(&command).query(connection)?;
+
+            assert_eq!(keys, vec![String::from("TEST_CALENDAR_UID")], "unexpected keys for: {}", command[0]);
+        }
+
+        Ok(())
+    }
+
+    fn test_replication(connection: &mut Connection) -> Result<()> {
+        // Start another redis instance on a different port to replicate the test redis server.
+        let port: u16 = 6482;
+        let _guards = utils::start_redis_server_with_module("redical", port).with_context(|| "failed to start replica test redis server")?;
+
+        let mut replica_connection =
+            utils::get_redis_connection(port).with_context(|| "failed to connect to replica test redis server")?;
+
+        redis::cmd("REPLICAOF").arg("127.0.0.1").arg(6480).query::<()>(&mut replica_connection)?;
+
+        // Wait for the replica to complete the initial sync.
+        let mut is_replica_synced = false;
+
+        for _ in 0..100 {
+            let replication_info: String = redis::cmd("INFO").arg("replication").query(&mut replica_connection)?;
+
+            if replication_info.contains("master_link_status:up") {
+                is_replica_synced = true;
+
+                break;
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+
+        assert!(is_replica_synced, "replica test redis server failed to sync");
+
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        // Omit LAST-MODIFIED so that it is defaulted to now by the primary.
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "CATEGORIES:CATEGORY_ONE",
+            ],
+        );
+
+        let _: Vec<String> =
+            redis::cmd("rdcl.evo_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_IN_OXFORD_MON_WED")
+                .arg("20210105T170000Z")
+                .arg("DETACHED")
+                .arg("SUMMARY:Detached override")
+                .query(connection)?;
+
+        let _: Vec<Vec<String>> =
+            redis::cmd("rdcl.evo_mset")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_IN_OXFORD_MON_WED")
+                .arg("20210104T170000Z")
+                .arg("CATEGORIES:CATEGORY_TWO")
+                .arg("20210106T170000Z")
+                .arg("SUMMARY:Overridden event in Oxford")
+                .query(connection)?;
+
+        let _: Vec<Vec<String>> =
+            redis::cmd("rdcl.evt_mset")
+                .arg("TEST_CALENDAR_UID")
+                .arg("ONLINE_EVENT_MON_WED")
+                .arg("SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1 DTSTART:20201231T160000Z")
+                .query(connection)?;
+
+        disable_calendar_indexes!(connection, "TEST_CALENDAR_UID", 1);
+
+        assert_eq!(
+            redis::cmd("WAIT").arg(1).arg(5000).query(connection),
+            RedisResult::Ok(Value::Int(1)),
+        );
+
+        // Assert the replica reflects exactly what was stored on the primary (including the
+        // defaulted LAST-MODIFIED properties).
+        for event_uid in ["EVENT_IN_OXFORD_MON_WED", "ONLINE_EVENT_MON_WED"] {
+            let event_get_result: Vec<String> = redis::cmd("rdcl.evt_get").arg("TEST_CALENDAR_UID").arg(event_uid).query(connection)?;
+            let replica_event_get_result: Vec<String> = redis::cmd("rdcl.evt_get").arg("TEST_CALENDAR_UID").arg(event_uid).query(&mut replica_connection)?;
+
+            assert_eq!(replica_event_get_result, event_get_result);
+        }
+
+        let event_override_list_result: Vec<Vec<String>> = redis::cmd("rdcl.evo_list").arg("TEST_CALENDAR_UID").arg("EVENT_IN_OXFORD_MON_WED").query(connection)?;
+        let replica_event_override_list_result: Vec<Vec<String>> = redis::cmd("rdcl.evo_list").arg("TEST_CALENDAR_UID").arg("EVENT_IN_OXFORD_MON_WED").query(&mut replica_connection)?;
+
+        assert_eq!(event_override_list_result.len(), 3);
+        assert_eq!(replica_event_override_list_result, event_override_list_result);
+
+        // Assert the index disabling was also replicated.
+        assert_calendar_stats!(
+            &mut replica_connection,
+            "TEST_CALENDAR_UID",
+            [
+                ("indexes_active", Value::Int(0)),
+            ],
+        );
+
+        redis::cmd("REPLICAOF").arg("NO").arg("ONE").query::<()>(&mut replica_connection)?;
+
+        Ok(())
+    }
+
+    fn test_rdb_save_load(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "ONLINE_EVENT_MON_WED",
+            [
+                "SUMMARY:Online Event on Mondays and Wednesdays at 4:00PM",
+                "RRULE:BYDAY=MO,WE;FREQ=WEEKLY;INTERVAL=1;UNTIL=20211231T170000Z",
+                "DTSTART:20201231T160000Z",
+                "DTEND:20201231T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "RELATED-TO;RELTYPE=PARENT:PARENT_UID",
+                "CATEGORIES:CATEGORY TWO,CATEGORY_ONE",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "RELATED-TO;RELTYPE=PARENT:PARENT_UID",
+                "CATEGORIES:CATEGORY TWO,CATEGORY_ONE",
+                "GEO:51.751365550307604;-1.2601196837753945",
+            ],
+        );
+
+        list_and_assert_matching_events!(
+            connection,
+            "TEST_CALENDAR_UID",
+            [
+                [
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                    "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                    "DTSTART:20201231T170000Z",
+                    "DTEND:20201231T173000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "RELATED-TO;RELTYPE=PARENT:PARENT_UID",
+                    "CATEGORIES:CATEGORY TWO,CATEGORY_ONE",
+                    "GEO:51.751365550307604;-1.2601196837753945",
                     "UID:EVENT_IN_OXFORD_MON_WED",
                 ],
                 [
@@ -2686,6 +4319,7 @@ mod integration {
             "TEST_CALENDAR_UID",
             "EVENT_IN_OXFORD_MON_WED",
             "20210102T170000Z",
+            DETACHED,
             [
                 "LAST-MODIFIED:20210501T090000Z",
                 "CATEGORIES:CATEGORY_ONE,OVERRIDDEN_CATEGORY",
@@ -2698,6 +4332,7 @@ mod integration {
             "TEST_CALENDAR_UID",
             "EVENT_IN_OXFORD_MON_WED",
             "20201231T170000Z",
+            DETACHED,
             [
                 "LAST-MODIFIED:20210501T090000Z",
                 "SUMMARY:Overridden event in Oxford summary text",
@@ -2871,6 +4506,35 @@ mod integration {
         })
     }
 
+    fn test_flush_keyspace_events(connection: &mut Connection) -> Result<()> {
+        listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
+            set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+            assert_keyspace_events_published!(message_queue, "rdcl.cal_set", "TEST_CALENDAR_UID");
+
+            // Non-calendar keys are not notified.
+            redis::cmd("SET").arg("NOT_A_CALENDAR").arg("VALUE").execute(connection);
+
+            redis::cmd("FLUSHDB").execute(connection);
+
+            assert_calendar_nil!(connection, "TEST_CALENDAR_UID");
+
+            assert_keyspace_events_published!(message_queue, "rdcl.cal_del", "TEST_CALENDAR_UID");
+
+            set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+            assert_keyspace_events_published!(message_queue, "rdcl.cal_set", "TEST_CALENDAR_UID");
+
+            redis::cmd("FLUSHALL").execute(connection);
+
+            assert_calendar_nil!(connection, "TEST_CALENDAR_UID");
+
+            assert_keyspace_events_published!(message_queue, "rdcl.cal_del", "TEST_CALENDAR_UID");
+
+            Ok(())
+        })
+    }
+
     fn test_redical_ical_parser_timeout_ms_config(connection: &mut Connection) -> Result<()> {
         set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
 
@@ -2940,7 +4604,7 @@ mod integration {
                 redis::cmd("rdcl.evo_set")
                     .arg("TEST_CALENDAR_UID")
                     .arg("EVENT_IN_OXFORD_MON_WED")
-                    .arg("20210102T170000Z")
+                    .arg("20210104T170000Z")
                     .arg(
                         [
                             "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM (OVERRIDDEN)",
@@ -2996,21 +4660,4110 @@ mod integration {
         Ok(())
     }
 
+    fn test_redical_notification_verbosity_config(connection: &mut Connection) -> Result<()> {
+        listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
+            assert_eq!(
+                redis::cmd("CONFIG").arg("GET").arg("REDICAL.NOTIFICATION-VERBOSITY").query(connection),
+                RedisResult::Ok(vec![String::from("redical.notification-verbosity"), String::from("standard")]),
+            );
+
+            set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+            assert_keyspace_events_published!(message_queue, "rdcl.cal_set", "TEST_CALENDAR_UID");
+
+            redis::cmd("CONFIG")
+                .arg("SET")
+                .arg(b"REDICAL.NOTIFICATION-VERBOSITY")
+                .arg("minimal")
+                .execute(connection);
+
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_IN_OXFORD_MON_WED",
+                [
+                    "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                    "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                    "DTSTART:20201231T170000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "CATEGORIES:CATEGORY_ONE",
+                ],
+            );
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:EVENT_IN_OXFORD_MON_WED", "TEST_CALENDAR_UID");
+
+            set_and_assert_event_override!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_IN_OXFORD_MON_WED",
+                "20210104T170000Z",
+                [
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "CATEGORIES:CATEGORY_TWO",
+                ],
+            );
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evo_set:EVENT_IN_OXFORD_MON_WED:20210104T170000Z", "TEST_CALENDAR_UID");
+
+            redis::cmd("CONFIG")
+                .arg("SET")
+                .arg(b"REDICAL.NOTIFICATION-VERBOSITY")
+                .arg("detailed")
+                .execute(connection);
+
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_IN_OXFORD_MON_WED",
+                [
+                    "SUMMARY:Updated event in Oxford on Mondays and Wednesdays at 5:00PM",
+                    "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                    "DTSTART:20201231T170000Z",
+                    "LAST-MODIFIED:20210501T100000Z",
+                    "CATEGORIES:CATEGORY_ONE",
+                ],
+            );
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:EVENT_IN_OXFORD_MON_WED LAST-MODIFIED:20210501T100000Z LAST-MODIFIED,SUMMARY", "TEST_CALENDAR_UID");
+
+            set_and_assert_event_override!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_IN_OXFORD_MON_WED",
+                "20210104T170000Z",
+                [
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "CATEGORIES:CATEGORY_TWO",
+                    "LOCATION-TYPE:ONLINE",
+                ],
+            );
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evo_set:EVENT_IN_OXFORD_MON_WED:20210104T170000Z LAST-MODIFIED:20210501T090000Z LOCATION-TYPE", "TEST_CALENDAR_UID");
+
+            // Assert unknown verbosity levels are rejected.
+            let config_set_result: RedisResult<()> =
+                redis::cmd("CONFIG")
+                    .arg("SET")
+                    .arg(b"REDICAL.NOTIFICATION-VERBOSITY")
+                    .arg("verbose")
+                    .query(connection);
+
+            assert!(config_set_result.is_err());
+
+            // Restore the default verbosity for any subsequent tests.
+            redis::cmd("CONFIG")
+                .arg("SET")
+                .arg(b"REDICAL.NOTIFICATION-VERBOSITY")
+                .arg("standard")
+                .execute(connection);
+
+            Ok(())
+        })
+    }
+
+    fn test_redical_query_execution_timeout_ms_config(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        // Unbounded secondly recurring event, so that expanding all the occurrences prior to the
+        // queried lower bound takes far longer than the configured timeout.
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_EVERY_SECOND",
+            [
+                "SUMMARY:Event every second",
+                "RRULE:FREQ=SECONDLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T170001Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "CATEGORIES:CATEGORY_ONE",
+            ],
+        );
+
+        // Update Redis RediCal config to specify query execution timeout after 1ms.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.QUERY-EXECUTION-TIMEOUT-MS")
+            .arg("1")
+            .execute(connection);
+
+        let calendar_query_result: Result<Vec<String>, String> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-FROM;PROP=DTSTART;OP=GT;TZID=UTC:21000101T000000Z")
+                .arg("X-CATEGORIES:CATEGORY_ONE")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            calendar_query_result,
+            Err(String::from("rdcl.evi_query:: query exceeded timeout")),
+        );
+
+        // Restore Redis RediCal config to specify query execution timeout back to 5000ms.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.QUERY-EXECUTION-TIMEOUT-MS")
+            .arg("5000")
+            .execute(connection);
+
+        Ok(())
+    }
+
+    fn test_redical_max_events_per_calendar_config(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        // Update Redis RediCal config to limit each calendar to 2 events.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.MAX-EVENTS-PER-CALENDAR")
+            .arg("2")
+            .execute(connection);
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "DTSTART:20201231T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_TWO",
+            [
+                "DTSTART:20201231T180000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let event_set_result: Result<Vec<String>, String> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_THREE")
+                .arg("DTSTART:20201231T190000Z LAST-MODIFIED:20210501T090000Z")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            event_set_result,
+            Err(String::from("rdcl.evt_set:: Calendar: TEST_CALENDAR_UID exceeds max events per calendar limit: 2")),
+        );
+
+        let event_mset_result: Result<Vec<Vec<String>>, String> =
+            redis::cmd("rdcl.evt_mset")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_TWO")
+                .arg("DTSTART:20201231T183000Z LAST-MODIFIED:20210501T100000Z")
+                .arg("EVENT_THREE")
+                .arg("DTSTART:20201231T190000Z LAST-MODIFIED:20210501T090000Z")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            event_mset_result,
+            Err(String::from("rdcl.evt_mset:: Calendar: TEST_CALENDAR_UID exceeds max events per calendar limit: 2")),
+        );
+
+        // Updating an existing event is still permitted.
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_TWO",
+            [
+                "DTSTART:20201231T183000Z",
+                "LAST-MODIFIED:20210501T100000Z",
+            ],
+        );
+
+        list_and_assert_matching_events!(
+            connection,
+            "TEST_CALENDAR_UID",
+            [
+                [
+                    "DTSTART:20201231T170000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "UID:EVENT_ONE",
+                ],
+                [
+                    "DTSTART:20201231T183000Z",
+                    "LAST-MODIFIED:20210501T100000Z",
+                    "UID:EVENT_TWO",
+                ],
+            ],
+        );
+
+        // Lowering the limit does not drop existing events replayed from the AOF.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.MAX-EVENTS-PER-CALENDAR")
+            .arg("1")
+            .execute(connection);
+
+        utils::reload_from_aof(connection)?;
+
+        list_and_assert_matching_events!(
+            connection,
+            "TEST_CALENDAR_UID",
+            [
+                [
+                    "DTSTART:20201231T170000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "UID:EVENT_ONE",
+                ],
+                [
+                    "DTSTART:20201231T183000Z",
+                    "LAST-MODIFIED:20210501T100000Z",
+                    "UID:EVENT_TWO",
+                ],
+            ],
+        );
+
+        // Restore Redis RediCal config to the default unlimited events per calendar.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.MAX-EVENTS-PER-CALENDAR")
+            .arg("0")
+            .execute(connection);
+
+        Ok(())
+    }
+
+    fn test_redical_max_query_results_config(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "RRULE:COUNT=3;FREQ=DAILY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_TWO",
+            [
+                "DTSTART:20201231T180000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        // Update Redis RediCal config to limit each query to 2 results.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.MAX-QUERY-RESULTS")
+            .arg("2")
+            .execute(connection);
+
+        // More results than permitted are truncated and flagged.
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:50")
+                .arg("WITHHEADER")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 3);
+        assert_eq!(calendar_query_result[0], query_results_header(&[], 1, 0, &[]));
+
+        // Without WITHHEADER, only the truncated results are replied.
+        let calendar_query_result: Vec<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:50")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 2);
+
+        let calendar_query_result: Vec<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evt_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:1")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 1);
+
+        // Results within the permitted max are not flagged.
+        let calendar_query_result: Vec<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:2")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 2);
+
+        let calendar_query_result: Vec<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evt_query")
+                .arg("TEST_CALENDAR_UID")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 2);
+
+        // Restore Redis RediCal config to the default unlimited query results.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.MAX-QUERY-RESULTS")
+            .arg("0")
+            .execute(connection);
+
+        Ok(())
+    }
+
+    fn test_redical_occurrence_expansion_limits_config(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        // Non-terminating daily recurring event.
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_EVERY_DAY",
+            [
+                "RRULE:FREQ=DAILY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        // Update Redis RediCal config to expand at most 3 occurrences per event.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.OCCURRENCE-EXPANSION-MAX")
+            .arg("3")
+            .execute(connection);
+
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:50")
+                .arg("WITHHEADER")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 4);
+        assert_eq!(calendar_query_result[0], query_results_header(&[], 0, 1, &[]));
+
+        // Results within the expansion limits are not flagged.
+        let calendar_query_result: Vec<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:2")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 2);
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.OCCURRENCE-EXPANSION-MAX")
+            .arg("0")
+            .execute(connection);
+
+        // Update Redis RediCal config to expand occurrences at most 1 day into the future.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.OCCURRENCE-EXPANSION-HORIZON-DAYS")
+            .arg("1")
+            .execute(connection);
+
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-FROM;PROP=DTSTART;OP=GT;TZID=UTC:21000101T000000Z")
+                .arg("WITHHEADER")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result, vec![query_results_header(&[], 0, 1, &[])]);
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.OCCURRENCE-EXPANSION-HORIZON-DAYS")
+            .arg("0")
+            .execute(connection);
+
+        // Update Redis RediCal config to expand occurrences at most 1 year beyond each event DTSTART.
+        assert_eq!(
+            redis::cmd("CONFIG").arg("GET").arg("REDICAL.OCCURRENCE-EXPANSION-SAFETY-HORIZON-YEARS").query(connection),
+            RedisResult::Ok(vec![String::from("redical.occurrence-expansion-safety-horizon-years"), String::from("0")]),
+        );
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.OCCURRENCE-EXPANSION-SAFETY-HORIZON-YEARS")
+            .arg("1")
+            .execute(connection);
+
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-UNTIL;PROP=DTSTART;OP=LT;TZID=UTC:99990101T000000Z")
+                .arg("X-LIMIT:1000")
+                .arg("WITHHEADER")
+                .query(connection)?;
+
+        // Every daily occurrence from 2020-12-31 until 2021-12-31 (inclusive).
+        assert_eq!(calendar_query_result.len(), 367);
+        assert_eq!(calendar_query_result[0], query_results_header(&[], 0, 1, &[]));
+
+        let event_instance_list_result: Vec<Vec<String>> =
+            redis::cmd("rdcl.evi_list")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_EVERY_DAY")
+                .arg(0)
+                .arg(1000)
+                .query(connection)?;
+
+        assert_eq!(event_instance_list_result.len(), 366);
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.OCCURRENCE-EXPANSION-SAFETY-HORIZON-YEARS")
+            .arg("0")
+            .execute(connection);
+
+        // Update Redis RediCal config to log the expansion of events with 2 or more occurrences
+        // as slow, which does not affect the query results.
+        assert_eq!(
+            redis::cmd("CONFIG").arg("GET").arg("REDICAL.SLOW-EXPANSION-THRESHOLD-OCCURRENCES").query(connection),
+            RedisResult::Ok(vec![String::from("redical.slow-expansion-threshold-occurrences"), String::from("0")]),
+        );
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.SLOW-EXPANSION-THRESHOLD-OCCURRENCES")
+            .arg("2")
+            .execute(connection);
+
+        let calendar_query_result: Vec<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:3")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 3);
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.SLOW-EXPANSION-THRESHOLD-OCCURRENCES")
+            .arg("0")
+            .execute(connection);
+
+        Ok(())
+    }
+
+    fn test_event_expire(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "RRULE:FREQ=DAILY;COUNT=2;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_EVERY_DAY",
+            [
+                "RRULE:FREQ=DAILY;INTERVAL=1",
+                "DTSTART:20201231T180000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        // Update Redis RediCal config to sweep expired events every 10ms.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.EVENT-EXPIRY-SWEEP-INTERVAL-MS")
+            .arg("10")
+            .execute(connection);
+
+        // Events recurring indefinitely have no final occurrence to default the expiry to.
+        let event_expire_result: Result<String, String> =
+            redis::cmd("rdcl.evt_expire")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_EVERY_DAY")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            event_expire_result,
+            Err(String::from("rdcl.evt_expire:: Event: EVENT_EVERY_DAY recurs indefinitely, AT must be provided")),
+        );
+
+        assert_eq!(
+            redis::cmd("rdcl.evt_expire")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_EVERY_DAY")
+                .arg("AT")
+                .arg("20201231T180000Z")
+                .query(connection),
+            Ok(String::from("20201231T180000Z")),
+        );
+
+        assert_eq!(
+            redis::cmd("rdcl.evt_expire")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_EVERY_DAY")
+                .arg("PERSIST")
+                .query(connection),
+            Ok(true),
+        );
+
+        assert_eq!(
+            redis::cmd("rdcl.evt_expire")
+                .arg("TEST_CALENDAR_UID")
+                .arg("NON_EXISTENT_EVENT_UID")
+                .query(connection),
+            Ok(false),
+        );
+
+        // Defaults to the end of the final occurrence (plus the configured grace period).
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.EVENT-EXPIRY-GRACE-PERIOD-SECS")
+            .arg("3600")
+            .execute(connection);
+
+        assert_eq!(
+            redis::cmd("rdcl.evt_expire")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .query(connection),
+            Ok(String::from("20210101T183000Z")),
+        );
+
+        // Allow the sweep to remove the (already) expired event.
+        std::thread::sleep(std::time::Duration::from_millis(1500));
+
+        list_and_assert_matching_events!(
+            connection,
+            "TEST_CALENDAR_UID",
+            [
+                [
+                    "DTSTART:20201231T180000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "RRULE:FREQ=DAILY;INTERVAL=1",
+                    "UID:EVENT_EVERY_DAY",
+                ],
+            ],
+        );
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.EVENT-EXPIRY-GRACE-PERIOD-SECS")
+            .arg("0")
+            .execute(connection);
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.EVENT-EXPIRY-SWEEP-INTERVAL-MS")
+            .arg("1000")
+            .execute(connection);
+
+        Ok(())
+    }
+
+    fn test_query_explain(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ART_AND_MUSIC",
+            [
+                "CATEGORIES:ART,MUSIC",
+                "DTSTART:20201231T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ART",
+            [
+                "CATEGORIES:ART",
+                "DTSTART:20210105T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_SPORT",
+            [
+                "CATEGORIES:SPORT",
+                "DTSTART:20210105T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let query_explain_result: Value =
+            redis::cmd("rdcl.query_explain")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVT")
+                .arg("X-CATEGORIES:ART")
+                .arg("X-ORDER-BY:SCORE")
+                .arg("X-OFFSET:1")
+                .query(connection)?;
+
+        assert_eq!(
+            query_explain_result,
+            Value::Bulk(vec![
+                Value::Status(String::from("query_type")),
+                Value::Status(String::from("EVT")),
+                Value::Status(String::from("where")),
+                Value::Bulk(vec![
+                    Value::Status(String::from("node")),
+                    Value::Data(String::from("CATEGORIES:ART").into_bytes()),
+                    Value::Status(String::from("index")),
+                    Value::Status(String::from("CATEGORIES")),
+                    Value::Status(String::from("candidate_count")),
+                    Value::Int(2),
+                    Value::Status(String::from("children")),
+                    Value::Bulk(vec![]),
+                ]),
+                Value::Status(String::from("event_count")),
+                Value::Int(3),
+                Value::Status(String::from("candidate_count")),
+                Value::Int(2),
+                Value::Status(String::from("time_bucketed")),
+                Value::Int(0),
+                Value::Status(String::from("ordering")),
+                Value::Status(String::from("SCORE")),
+                Value::Status(String::from("offset_strategy")),
+                Value::Status(String::from("SKIP-AFTER-SORTING")),
+                Value::Status(String::from("offset")),
+                Value::Int(1),
+                Value::Status(String::from("limit")),
+                Value::Int(50),
+                Value::Status(String::from("distinct_uids")),
+                Value::Int(0),
+            ]),
+        );
+
+        // Event instance queries narrow the candidate events to the queried time window.
+        let query_explain_result: Value =
+            redis::cmd("rdcl.query_explain")
+                .arg("TEST_CALENDAR_UID")
+                .arg("evi")
+                .arg("X-FROM:20210101T000000Z")
+                .query(connection)?;
+
+        assert_eq!(
+            query_explain_result,
+            Value::Bulk(vec![
+                Value::Status(String::from("query_type")),
+                Value::Status(String::from("EVI")),
+                Value::Status(String::from("where")),
+                Value::Nil,
+                Value::Status(String::from("event_count")),
+                Value::Int(3),
+                Value::Status(String::from("candidate_count")),
+                Value::Int(2),
+                Value::Status(String::from("time_bucketed")),
+                Value::Int(1),
+                Value::Status(String::from("ordering")),
+                Value::Status(String::from("DTSTART")),
+                Value::Status(String::from("offset_strategy")),
+                Value::Status(String::from("SKIP-WHILE-ITERATING")),
+                Value::Status(String::from("offset")),
+                Value::Int(0),
+                Value::Status(String::from("limit")),
+                Value::Int(50),
+                Value::Status(String::from("distinct_uids")),
+                Value::Int(0),
+            ]),
+        );
+
+        let query_explain_result: Result<Value, String> =
+            redis::cmd("rdcl.query_explain")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVO")
+                .arg("X-LIMIT:1")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            query_explain_result,
+            Err(String::from("rdcl.query_explain:: Unknown query type: EVO (expected EVI or EVT)")),
+        );
+
+        Ok(())
+    }
+
+    fn test_event_scan(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        for event_uid in ["EVENT_ONE", "EVENT_THREE", "EVENT_TWO"] {
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                event_uid,
+                [
+                    "DTSTART:20201231T170000Z",
+                    "RDATE:20201231T170000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                ],
+            );
+        }
+
+        let (cursor, events): (String, Vec<Vec<String>>) =
+            redis::cmd("rdcl.evt_scan")
+                .arg("TEST_CALENDAR_UID")
+                .arg("0")
+                .arg("COUNT")
+                .arg("2")
+                .query(connection)?;
+
+        assert_ne!(cursor, String::from("0"));
+
+        assert_eq!(
+            events,
+            vec![
+                vec![
+                    String::from("DTSTART:20201231T170000Z"),
+                    String::from("LAST-MODIFIED:20210501T090000Z"),
+                    String::from("RDATE:20201231T170000Z"),
+                    String::from("UID:EVENT_ONE"),
+                ],
+                vec![
+                    String::from("DTSTART:20201231T170000Z"),
+                    String::from("LAST-MODIFIED:20210501T090000Z"),
+                    String::from("RDATE:20201231T170000Z"),
+                    String::from("UID:EVENT_THREE"),
+                ],
+            ],
+        );
+
+        // Events removed (or inserted before the cursor) part way through the scan do not cause
+        // any event to be returned more than once, or skipped.
+        redis::cmd("rdcl.evt_del").arg("TEST_CALENDAR_UID").arg("EVENT_THREE").execute(connection);
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_A",
+            [
+                "DTSTART:20201231T170000Z",
+                "RDATE:20201231T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let (cursor, events): (String, Vec<Vec<String>>) =
+            redis::cmd("rdcl.evt_scan")
+                .arg("TEST_CALENDAR_UID")
+                .arg(cursor)
+                .arg("COUNT")
+                .arg("2")
+                .query(connection)?;
+
+        assert_eq!(cursor, String::from("0"));
+
+        assert_eq!(
+            events,
+            vec![
+                vec![
+                    String::from("DTSTART:20201231T170000Z"),
+                    String::from("LAST-MODIFIED:20210501T090000Z"),
+                    String::from("RDATE:20201231T170000Z"),
+                    String::from("UID:EVENT_TWO"),
+                ],
+            ],
+        );
+
+        let event_scan_result: Result<Value, String> =
+            redis::cmd("rdcl.evt_scan")
+                .arg("TEST_CALENDAR_UID")
+                .arg("NOT_A_CURSOR")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            event_scan_result,
+            Err(String::from("rdcl.evt_scan:: Invalid cursor: NOT_A_CURSOR")),
+        );
+
+        let event_scan_result: Result<Value, String> =
+            redis::cmd("rdcl.evt_scan")
+                .arg("TEST_CALENDAR_UID")
+                .arg("0")
+                .arg("COUNT")
+                .arg("0")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            event_scan_result,
+            Err(String::from("rdcl.evt_scan:: COUNT must be greater than 0")),
+        );
+
+        Ok(())
+    }
+
+    fn test_change_feed(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        // The change feed is disabled by default.
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "DTSTART:20201231T170000Z",
+                "RRULE:FREQ=WEEKLY;UNTIL=20210202T170000Z;INTERVAL=1;BYDAY=TH",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        assert_eq!(
+            redis::cmd("EXISTS").arg("rdcl:changes:{TEST_CALENDAR_UID}").query(connection),
+            RedisResult::Ok(Value::Int(0)),
+        );
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.CHANGE-FEED-ENABLED")
+            .arg("yes")
+            .execute(connection);
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "DTSTART:20201231T170000Z",
+                "RRULE:FREQ=WEEKLY;UNTIL=20210202T170000Z;INTERVAL=1;BYDAY=TH",
+                "LAST-MODIFIED:20210501T100000Z",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "20210107T170000Z",
+            [
+                "LAST-MODIFIED:20210501T110000Z",
+                "CATEGORIES:CATEGORY_ONE",
+            ],
+        );
+
+        del_and_assert_event_override_deletion!(connection, "TEST_CALENDAR_UID", "EVENT_ONE", "20210107T170000Z", 1);
+
+        del_and_assert_event_deletion!(connection, "TEST_CALENDAR_UID", "EVENT_ONE", 1);
+
+        let change_feed_entries: Vec<(String, Vec<String>)> =
+            redis::cmd("XRANGE")
+                .arg("rdcl:changes:{TEST_CALENDAR_UID}")
+                .arg("-")
+                .arg("+")
+                .query(connection)?;
+
+        assert_eq!(
+            change_feed_entries
+                .into_iter()
+                .map(|(_entry_id, fields)| fields)
+                .collect::<Vec<Vec<String>>>(),
+            vec![
+                vec![
+                    String::from("type"),
+                    String::from("evt_set"),
+                    String::from("uid"),
+                    String::from("EVENT_ONE"),
+                    String::from("last_modified"),
+                    String::from("20210501T100000Z"),
+                ],
+                vec![
+                    String::from("type"),
+                    String::from("evo_set"),
+                    String::from("uid"),
+                    String::from("EVENT_ONE"),
+                    String::from("recurrence_id"),
+                    String::from("20210107T170000Z"),
+                    String::from("last_modified"),
+                    String::from("20210501T110000Z"),
+                ],
+                vec![
+                    String::from("type"),
+                    String::from("evo_del"),
+                    String::from("uid"),
+                    String::from("EVENT_ONE"),
+                    String::from("recurrence_id"),
+                    String::from("20210107T170000Z"),
+                ],
+                vec![
+                    String::from("type"),
+                    String::from("evt_del"),
+                    String::from("uid"),
+                    String::from("EVENT_ONE"),
+                ],
+            ],
+        );
+
+        // Restore Redis RediCal config to disable the change feed.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.CHANGE-FEED-ENABLED")
+            .arg("no")
+            .execute(connection);
+
+        Ok(())
+    }
+
+    fn test_query_subscriptions(connection: &mut Connection) -> Result<()> {
+        listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
+            set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+            assert_keyspace_events_published!(message_queue, "rdcl.cal_set", "TEST_CALENDAR_UID");
+
+            assert_eq!(
+                redis::cmd("rdcl.query_sub")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("CATEGORY_ONE_EVENTS")
+                    .arg("X-CATEGORIES:CATEGORY_ONE")
+                    .query(connection),
+                RedisResult::Ok(String::from("OK")),
+            );
+
+            assert_eq!(
+                redis::cmd("rdcl.query_sub_list").arg("TEST_CALENDAR_UID").query(connection),
+                RedisResult::Ok(vec![String::from("CATEGORY_ONE_EVENTS")]),
+            );
+
+            // Mutations changing the query results notify the query subscription.
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_ONE",
+                [
+                    "DTSTART:20201231T170000Z",
+                    "RRULE:FREQ=WEEKLY;UNTIL=20210202T170000Z;INTERVAL=1;BYDAY=TH",
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "CATEGORIES:CATEGORY_ONE",
+                ],
+            );
+
+            // Query subscriptions are re-evaluated in the background, so the query dirty keyspace
+            // event is awaited.
+            wait_for_keyspace_event(
+                message_queue,
+                "rdcl.query_dirty:CATEGORY_ONE_EVENTS",
+                "TEST_CALENDAR_UID",
+                std::time::Duration::from_secs(5),
+            )?;
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:EVENT_ONE LAST-MODIFIED:20210501T090000Z", "TEST_CALENDAR_UID");
+
+            // Mutations not changing the query results do not.
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_TWO",
+                [
+                    "DTSTART:20201231T170000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "CATEGORIES:CATEGORY_TWO",
+                ],
+            );
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_set:EVENT_TWO LAST-MODIFIED:20210501T090000Z", "TEST_CALENDAR_UID");
+
+            set_and_assert_event_override!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_ONE",
+                "20210107T170000Z",
+                [
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "CATEGORIES:CATEGORY_TWO",
+                ],
+            );
+
+            wait_for_keyspace_event(
+                message_queue,
+                "rdcl.query_dirty:CATEGORY_ONE_EVENTS",
+                "TEST_CALENDAR_UID",
+                std::time::Duration::from_secs(5),
+            )?;
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evo_set:EVENT_ONE:20210107T170000Z LAST-MODIFIED:20210501T090000Z", "TEST_CALENDAR_UID");
+
+            assert_eq!(
+                redis::cmd("rdcl.query_unsub").arg("TEST_CALENDAR_UID").arg("CATEGORY_ONE_EVENTS").query(connection),
+                RedisResult::Ok(Value::Int(1)),
+            );
+
+            assert_eq!(
+                redis::cmd("rdcl.query_unsub").arg("TEST_CALENDAR_UID").arg("CATEGORY_ONE_EVENTS").query(connection),
+                RedisResult::Ok(Value::Int(0)),
+            );
+
+            del_and_assert_event_deletion!(connection, "TEST_CALENDAR_UID", "EVENT_ONE", 1);
+
+            assert_keyspace_events_published!(message_queue, "rdcl.evt_del:EVENT_ONE", "TEST_CALENDAR_UID");
+
+            Ok(())
+        })
+    }
+
+    fn test_calendar_default_timezone(connection: &mut Connection) -> Result<()> {
+        let calendar_set_result: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-DEFAULT-TZID:Europe/London")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_set_result,
+            vec![
+                String::from("UID:TEST_CALENDAR_UID"),
+                String::from("X-DEFAULT-TZID:Europe/London"),
+            ],
+        );
+
+        // Setting the existing calendar without any calendar properties leaves it unchanged.
+        let calendar_set_result: Vec<String> = redis::cmd("rdcl.cal_set").arg("TEST_CALENDAR_UID").query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_set_result,
+            vec![
+                String::from("UID:TEST_CALENDAR_UID"),
+                String::from("X-DEFAULT-TZID:Europe/London"),
+            ],
+        );
+
+        let invalid_calendar_set_result: RedisResult<Vec<String>> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-DEFAULT-TZID:Invalid/Timezone")
+                .query(connection);
+
+        assert!(invalid_calendar_set_result.is_err());
+
+        // Floating date-times are interpreted (and stored) in the default timezone.
+        let event_set_result: Vec<String> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("TIMEZONE_EVENT")
+                .arg("LAST-MODIFIED:20241001T100000Z RRULE:COUNT=2;FREQ=MONTHLY;INTERVAL=4 DTSTART:20241001T100000 DTEND:20241001T110000")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_set_result,
+            vec![
+                String::from("DTEND;TZID=Europe/London:20241001T110000"),
+                String::from("DTSTART;TZID=Europe/London:20241001T100000"),
+                String::from("LAST-MODIFIED:20241001T100000Z"),
+                String::from("RRULE:COUNT=2;FREQ=MONTHLY;INTERVAL=4"),
+                String::from("UID:TIMEZONE_EVENT"),
+            ],
+        );
+
+        // Floating query date-times are interpreted in (and the results are returned in) the
+        // default timezone.
+        query_calendar_and_assert_matching_event_instances!(
+            connection,
+            "TEST_CALENDAR_UID",
+            [
+                "X-FROM;PROP=DTSTART;OP=GT:20241001T100000",
+            ],
+            [
+                [
+                    [
+                        "DTSTART;TZID=Europe/London:20250201T100000",
+                    ],
+                    [
+                        "DTEND;TZID=Europe/London:20250201T110000",
+                        "DTSTART;TZID=Europe/London:20250201T100000",
+                        "DURATION:PT1H",
+                        "RECURRENCE-ID;VALUE=DATE-TIME;TZID=Europe/London:20250201T100000",
+                        "UID:TIMEZONE_EVENT",
+                    ],
+                ],
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_event_del_query(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "CHILD_EVENT_ONE",
+            [
+                "LAST-MODIFIED:20241110T110000Z",
+                "DTSTART:20241231T163000Z",
+                "RELATED-TO;RELTYPE=PARENT:DECOMMISSIONED_PARENT_UID",
+            ]
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "CHILD_EVENT_TWO",
+            [
+                "LAST-MODIFIED:20241110T110000Z",
+                "DTSTART:20250101T163000Z",
+                "RELATED-TO;RELTYPE=PARENT:DECOMMISSIONED_PARENT_UID",
+                "CATEGORIES:CATEGORY_ONE",
+            ]
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "OTHER_EVENT",
+            [
+                "LAST-MODIFIED:20241110T110000Z",
+                "DTSTART:20241231T163000Z",
+                "RELATED-TO;RELTYPE=PARENT:OTHER_PARENT_UID",
+                "CATEGORIES:CATEGORY_ONE",
+            ]
+        );
+
+        // A query without any where properties (or range) would delete everything, so is rejected.
+        let event_del_query_result: Result<i64, String> =
+            redis::cmd("rdcl.evt_del_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:10")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            event_del_query_result,
+            Err(String::from("rdcl.evt_del_query: at least one where property (or X-FROM/X-UNTIL range) is required")),
+        );
+
+        listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
+            assert_eq!(
+                redis::cmd("rdcl.evt_del_query")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("X-RELATED-TO;RELTYPE=PARENT:DECOMMISSIONED_PARENT_UID")
+                    .arg("X-LIMIT:1")
+                    .query(connection),
+                Ok(2),
+            );
+
+            assert_keyspace_events_published!(
+                message_queue,
+                [
+                    ("rdcl.evt_del:CHILD_EVENT_ONE", "TEST_CALENDAR_UID"),
+                    ("rdcl.evt_del:CHILD_EVENT_TWO", "TEST_CALENDAR_UID"),
+                ]
+            );
+
+            assert_event_nil!(connection, "TEST_CALENDAR_UID", "CHILD_EVENT_ONE");
+            assert_event_nil!(connection, "TEST_CALENDAR_UID", "CHILD_EVENT_TWO");
+            assert_event_present!(connection, "TEST_CALENDAR_UID", "OTHER_EVENT");
+
+            // No remaining matches.
+            assert_eq!(
+                redis::cmd("rdcl.evt_del_query")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("X-RELATED-TO;RELTYPE=PARENT:DECOMMISSIONED_PARENT_UID")
+                    .query(connection),
+                Ok(0),
+            );
+
+            Ok(())
+        })
+    }
+
+    fn test_event_update_query(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "LAST-MODIFIED:20241110T110000Z",
+                "DTSTART:20241231T163000Z",
+                "CATEGORIES:OLD_CATEGORY,OTHER_CATEGORY",
+                "GEO:51.5074;-0.1278",
+            ]
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_TWO",
+            [
+                "LAST-MODIFIED:20241110T110000Z",
+                "DTSTART:20250101T163000Z",
+                "CATEGORIES:OLD_CATEGORY",
+            ]
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_THREE",
+            [
+                "LAST-MODIFIED:20241110T110000Z",
+                "DTSTART:20250101T163000Z",
+                "CATEGORIES:OTHER_CATEGORY",
+            ]
+        );
+
+        // The UID of matching events cannot be updated.
+        let event_update_query_result: Result<i64, String> =
+            redis::cmd("rdcl.evt_update_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-CATEGORIES:OLD_CATEGORY")
+                .arg("SET")
+                .arg("UID:NEW_UID")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            event_update_query_result,
+            Err(String::from("rdcl.evt_update_query: the UID of matching events cannot be updated")),
+        );
+
+        listen_for_keyspace_events(6480, |message_queue: &mut Arc<Mutex<VecDeque<redis::Msg>>>| {
+            assert_eq!(
+                redis::cmd("rdcl.evt_update_query")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("X-CATEGORIES:OLD_CATEGORY")
+                    .arg("SET")
+                    .arg("CATEGORIES:NEW_CATEGORY")
+                    .arg("GEO:51.4517446;-1.004574")
+                    .arg("LAST-MODIFIED:20241111T110000Z")
+                    .query(connection),
+                Ok(2),
+            );
+
+            assert_keyspace_events_published!(
+                message_queue,
+                [
+                    ("rdcl.evt_set:EVENT_ONE LAST-MODIFIED:20241111T110000Z", "TEST_CALENDAR_UID"),
+                    ("rdcl.evt_set:EVENT_TWO LAST-MODIFIED:20241111T110000Z", "TEST_CALENDAR_UID"),
+                ]
+            );
+
+            assert_event_present!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_ONE",
+                [
+                    "CATEGORIES:NEW_CATEGORY",
+                    "DTSTART:20241231T163000Z",
+                    "GEO:51.4517446;-1.004574",
+                    "LAST-MODIFIED:20241111T110000Z",
+                ]
+            );
+
+            assert_event_present!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_TWO",
+                [
+                    "CATEGORIES:NEW_CATEGORY",
+                    "DTSTART:20250101T163000Z",
+                    "GEO:51.4517446;-1.004574",
+                    "LAST-MODIFIED:20241111T110000Z",
+                ]
+            );
+
+            assert_event_present!(
+                connection,
+                "TEST_CALENDAR_UID",
+                "EVENT_THREE",
+                [
+                    "CATEGORIES:OTHER_CATEGORY",
+                    "DTSTART:20250101T163000Z",
+                    "LAST-MODIFIED:20241110T110000Z",
+                ]
+            );
+
+            // The indexes are updated, so the patched events no longer match the original query.
+            assert_eq!(
+                redis::cmd("rdcl.evt_update_query")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("X-CATEGORIES:OLD_CATEGORY")
+                    .arg("SET")
+                    .arg("CATEGORIES:NEWER_CATEGORY")
+                    .query(connection),
+                Ok(0),
+            );
+
+            Ok(())
+        })
+    }
+
+    fn test_query_profile(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_WEEKLY",
+            [
+                "CATEGORIES:ART",
+                "DTSTART:20210105T170000Z",
+                "RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=4",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_SPORT",
+            [
+                "CATEGORIES:SPORT",
+                "DTSTART:20210105T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let query_profile_result: Value =
+            redis::cmd("rdcl.query_profile")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-CATEGORIES:ART")
+                .arg("X-LIMIT:10")
+                .query(connection)?;
+
+        let Value::Bulk(query_profile_items) = query_profile_result else {
+            panic!("Expected rdcl.query_profile to return an array, got: {query_profile_result:#?}");
+        };
+
+        let field_names: Vec<Value> = query_profile_items.iter().step_by(2).cloned().collect();
+
+        assert_eq!(
+            field_names,
+            [
+                "result_count",
+                "partial",
+                "total_us",
+                "parse_us",
+                "index_filtering_us",
+                "expansion_us",
+                "override_application_us",
+                "ordering_us",
+                "rendering_us",
+                "event_expansions",
+            ]
+            .into_iter()
+            .map(|field_name| Value::Status(String::from(field_name)))
+            .collect::<Vec<Value>>(),
+        );
+
+        // The result count and partial flag.
+        assert_eq!(query_profile_items[1], Value::Int(4));
+        assert_eq!(query_profile_items[3], Value::Int(0));
+
+        // Each duration is in (non-negative) microseconds.
+        for duration_value in query_profile_items[4..18].iter().skip(1).step_by(2) {
+            assert!(matches!(duration_value, Value::Int(duration_us) if *duration_us >= 0), "{duration_value:#?}");
+        }
+
+        // Only the event matching the where conditional is expanded.
+        let Value::Bulk(event_expansions) = &query_profile_items[19] else {
+            panic!("Expected event_expansions to be an array, got: {:#?}", query_profile_items[19]);
+        };
+
+        assert_eq!(event_expansions.len(), 1);
+
+        let Value::Bulk(event_expansion) = &event_expansions[0] else {
+            panic!("Expected event expansion to be an array, got: {:#?}", event_expansions[0]);
+        };
+
+        assert_eq!(
+            event_expansion[0..6],
+            [
+                Value::Status(String::from("event_uid")),
+                Value::Data(String::from("EVENT_WEEKLY").into_bytes()),
+                Value::Status(String::from("expanded_count")),
+                Value::Int(4),
+                Value::Status(String::from("instance_count")),
+                Value::Int(4),
+            ],
+        );
+
+        assert_eq!(event_expansion[6], Value::Status(String::from("expansion_us")));
+
+        assert_error_returned!(
+            connection,
+            "rdcl.query_profile:: No Calendar found on key: NON_EXISTENT_CALENDAR_UID",
+            "rdcl.query_profile",
+            "NON_EXISTENT_CALENDAR_UID",
+            "X-CATEGORIES:ART",
+        );
+
+        Ok(())
+    }
+
+    fn test_calendar_index_verify(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        for (event_uid, categories) in [("EVENT_ONE", "ART,MUSIC"), ("EVENT_TWO", "ART"), ("EVENT_THREE", "SPORT")] {
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                event_uid,
+                [
+                    format!("CATEGORIES:{categories}").as_str(),
+                    "DTSTART:20210105T170000Z",
+                    "RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=3",
+                    "GEO:51.5074;-0.1278",
+                    "LAST-MODIFIED:20210501T090000Z",
+                ],
+            );
+        }
+
+        let consistent_verification = Value::Bulk(vec![
+            Value::Status(String::from("verified_event_count")),
+            Value::Int(3),
+            Value::Status(String::from("consistent")),
+            Value::Int(1),
+            Value::Status(String::from("discrepancies")),
+            Value::Bulk(vec![]),
+        ]);
+
+        assert_eq!(
+            redis::cmd("rdcl.cal_idx_verify").arg("TEST_CALENDAR_UID").query::<Value>(connection)?,
+            consistent_verification,
+        );
+
+        assert_eq!(
+            redis::cmd("rdcl.cal_idx_verify").arg("TEST_CALENDAR_UID").arg("SAMPLE").arg(2).query::<Value>(connection)?,
+            Value::Bulk(vec![
+                Value::Status(String::from("verified_event_count")),
+                Value::Int(2),
+                Value::Status(String::from("consistent")),
+                Value::Int(1),
+                Value::Status(String::from("discrepancies")),
+                Value::Bulk(vec![]),
+            ]),
+        );
+
+        // The indexes remain consistent after being incrementally updated.
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "CATEGORIES:MUSIC",
+                "DTSTART:20210105T170000Z",
+                "RRULE:FREQ=WEEKLY;INTERVAL=1;COUNT=5",
+                "LAST-MODIFIED:20210601T090000Z",
+            ],
+        );
+
+        redis::cmd("rdcl.evt_del").arg("TEST_CALENDAR_UID").arg("EVENT_TWO").execute(connection);
+
+        assert_eq!(
+            redis::cmd("rdcl.cal_idx_verify").arg("TEST_CALENDAR_UID").arg("SAMPLE").arg(10).query::<Value>(connection)?,
+            Value::Bulk(vec![
+                Value::Status(String::from("verified_event_count")),
+                Value::Int(2),
+                Value::Status(String::from("consistent")),
+                Value::Int(1),
+                Value::Status(String::from("discrepancies")),
+                Value::Bulk(vec![]),
+            ]),
+        );
+
+        assert_error_returned!(
+            connection,
+            "rdcl.cal_idx_verify:: SAMPLE must be greater than 0",
+            "rdcl.cal_idx_verify",
+            "TEST_CALENDAR_UID",
+            "SAMPLE",
+            0,
+        );
+
+        assert_error_returned!(
+            connection,
+            "rdcl.cal_idx_verify:: No Calendar found on key: NON_EXISTENT_CALENDAR_UID",
+            "rdcl.cal_idx_verify",
+            "NON_EXISTENT_CALENDAR_UID",
+        );
+
+        redis::cmd("rdcl.cal_idx_disable").arg("TEST_CALENDAR_UID").execute(connection);
+
+        assert_error_returned!(
+            connection,
+            "rdcl.cal_idx_verify:: Calendar: TEST_CALENDAR_UID cannot be verified because it's indexes have been disabled.",
+            "rdcl.cal_idx_verify",
+            "TEST_CALENDAR_UID",
+        );
+
+        Ok(())
+    }
+
+    fn test_calendar_index_terms(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        for (event_uid, categories) in [("EVENT_ONE", "SPORT_FOOTBALL,MUSIC"), ("EVENT_TWO", "SPORT_FOOTBALL"), ("EVENT_THREE", "SPORT_TENNIS")] {
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                event_uid,
+                [
+                    format!("CATEGORIES:{categories}").as_str(),
+                    "DTSTART:20210105T170000Z",
+                    "RELATED-TO;RELTYPE=PARENT:PARENT_UID",
+                    "LAST-MODIFIED:20210501T090000Z",
+                ],
+            );
+        }
+
+        assert_eq!(
+            redis::cmd("rdcl.cal_idx_terms").arg("TEST_CALENDAR_UID").arg("CATEGORIES").query::<Value>(connection)?,
+            Value::Bulk(vec![
+                Value::Data(String::from("MUSIC").into_bytes()),
+                Value::Int(1),
+                Value::Data(String::from("SPORT_FOOTBALL").into_bytes()),
+                Value::Int(2),
+                Value::Data(String::from("SPORT_TENNIS").into_bytes()),
+                Value::Int(1),
+            ]),
+        );
+
+        assert_eq!(
+            redis::cmd("rdcl.cal_idx_terms").arg("TEST_CALENDAR_UID").arg("categories").arg("PREFIX").arg("SPORT_").query::<Value>(connection)?,
+            Value::Bulk(vec![
+                Value::Data(String::from("SPORT_FOOTBALL").into_bytes()),
+                Value::Int(2),
+                Value::Data(String::from("SPORT_TENNIS").into_bytes()),
+                Value::Int(1),
+            ]),
+        );
+
+        assert_eq!(
+            redis::cmd("rdcl.cal_idx_terms").arg("TEST_CALENDAR_UID").arg("RELATED-TO").query::<Value>(connection)?,
+            Value::Bulk(vec![
+                Value::Data(String::from("PARENT;PARENT_UID").into_bytes()),
+                Value::Int(3),
+            ]),
+        );
+
+        assert_error_returned!(
+            connection,
+            "rdcl.cal_idx_terms:: Unknown index: GEO (expected CATEGORIES, LOCATION-TYPE, LOCATION, CLASS, or RELATED-TO)",
+            "rdcl.cal_idx_terms",
+            "TEST_CALENDAR_UID",
+            "GEO",
+        );
+
+        assert_error_returned!(
+            connection,
+            "No: Calendar found on key: NON_EXISTENT_CALENDAR_UID",
+            "rdcl.cal_idx_terms",
+            "NON_EXISTENT_CALENDAR_UID",
+            "CATEGORIES",
+        );
+
+        redis::cmd("rdcl.cal_idx_disable").arg("TEST_CALENDAR_UID").execute(connection);
+
+        assert_error_returned!(
+            connection,
+            "rdcl.cal_idx_terms:: Calendar: TEST_CALENDAR_UID terms cannot be listed because it's indexes have been disabled.",
+            "rdcl.cal_idx_terms",
+            "TEST_CALENDAR_UID",
+            "CATEGORIES",
+        );
+
+        Ok(())
+    }
+
+    fn test_last_modified_conflict_strategy(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "SUMMARY:Event one",
+                "DTSTART:20201231T160000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "20201231T160000Z",
+            [
+                "SUMMARY:Event one (overridden)",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        // The action taken is only returned when the ON-CONFLICT strategy is specified.
+        assert_eq!(
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("ON-CONFLICT")
+                .arg("IGNORE")
+                .arg("SUMMARY:Event one (older) DTSTART:20201231T160000Z LAST-MODIFIED:20210201T090000Z")
+                .query::<(String, bool)>(connection)?,
+            (String::from("IGNORED"), false),
+        );
+
+        let event_set_result: Result<Vec<String>, String> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("ON-CONFLICT")
+                .arg("ERROR")
+                .arg("SUMMARY:Event one (older) DTSTART:20201231T160000Z LAST-MODIFIED:20210201T090000Z")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            event_set_result,
+            Err(String::from("rdcl.evt_set:: event uid: EVENT_ONE LAST-MODIFIED:20210201T090000Z is older than existing LAST-MODIFIED:20210501T090000Z")),
+        );
+
+        assert_error_returned!(
+            connection,
+            "ON-CONFLICT:: UNKNOWN is invalid, expected either IGNORE, ERROR, or FORCE",
+            "rdcl.evt_set",
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "ON-CONFLICT",
+            "UNKNOWN",
+            "DTSTART:20201231T160000Z",
+        );
+
+        assert_event_present!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "SUMMARY:Event one",
+                "DTSTART:20201231T160000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let (action, properties) =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("ON-CONFLICT")
+                .arg("FORCE")
+                .arg("SUMMARY:Event one (forced) DTSTART:20201231T160000Z LAST-MODIFIED:20210201T090000Z")
+                .query::<(String, Vec<String>)>(connection)?;
+
+        assert_eq!(action, String::from("FORCED"));
+
+        assert_matching_ical_properties!(
+            properties,
+            [
+                "SUMMARY:Event one (forced)",
+                "DTSTART:20201231T160000Z",
+                "LAST-MODIFIED:20210201T090000Z",
+                "UID:EVENT_ONE",
+            ],
+        );
+
+        assert_event_present!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "SUMMARY:Event one (forced)",
+                "DTSTART:20201231T160000Z",
+                "LAST-MODIFIED:20210201T090000Z",
+            ],
+        );
+
+        let (action, _properties) =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("ON-CONFLICT")
+                .arg("ERROR")
+                .arg("SUMMARY:Event one (newer) DTSTART:20201231T160000Z LAST-MODIFIED:20210601T090000Z")
+                .query::<(String, Vec<String>)>(connection)?;
+
+        assert_eq!(action, String::from("SET"));
+
+        let event_override_set_result: Result<Vec<String>, String> =
+            redis::cmd("rdcl.evo_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("20201231T160000Z")
+                .arg("ON-CONFLICT")
+                .arg("ERROR")
+                .arg("SUMMARY:Event one (older override) LAST-MODIFIED:20210201T090000Z")
+                .query(connection)
+                .map_err(|redis_error| redis_error.to_string());
+
+        assert_eq!(
+            event_override_set_result,
+            Err(String::from("rdcl.evo_set:: event uid: EVENT_ONE occurrence: 20201231T160000Z LAST-MODIFIED:20210201T090000Z is older than existing LAST-MODIFIED:20210501T090000Z")),
+        );
+
+        let (action, _properties) =
+            redis::cmd("rdcl.evo_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("20201231T160000Z")
+                .arg("ON-CONFLICT")
+                .arg("FORCE")
+                .arg("SUMMARY:Event one (forced override) LAST-MODIFIED:20210201T090000Z")
+                .query::<(String, Vec<String>)>(connection)?;
+
+        assert_eq!(action, String::from("FORCED"));
+
+        assert_event_override_present!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "20201231T160000Z",
+            [
+                "SUMMARY:Event one (forced override)",
+                "LAST-MODIFIED:20210201T090000Z",
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_set_reply_modes(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        let mut set_event = |mode: &str, ical: &str| -> Result<Value> {
+            Ok(redis::cmd("rdcl.evt_set").arg("TEST_CALENDAR_UID").arg("EVENT_ONE").arg(mode).arg(ical).query::<Value>(connection)?)
+        };
+
+        // Creating the event is a change.
+        assert_eq!(set_event("CH", "DTSTART:20201231T160000Z LAST-MODIFIED:20210501T090000Z")?, Value::Int(1));
+
+        // Setting the exact same event is not.
+        assert_eq!(set_event("CH", "DTSTART:20201231T160000Z LAST-MODIFIED:20210501T090000Z")?, Value::Int(0));
+
+        assert_eq!(set_event("CH", "SUMMARY:Updated DTSTART:20201231T160000Z LAST-MODIFIED:20210501T090000Z")?, Value::Int(1));
+
+        // GET returns the stored event properties (as by default).
+        let event_set_result: Vec<String> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("GET")
+                .arg("SUMMARY:Updated DTSTART:20201231T160000Z LAST-MODIFIED:20210501T090000Z")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_set_result,
+            [
+                "SUMMARY:Updated",
+                "DTSTART:20201231T160000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "UID:EVENT_ONE",
+            ],
+        );
+
+        let mut set_event_override = |mode: &str, ical: &str| -> Result<Value> {
+            Ok(redis::cmd("rdcl.evo_set").arg("TEST_CALENDAR_UID").arg("EVENT_ONE").arg("20201231T160000Z").arg(mode).arg(ical).query::<Value>(connection)?)
+        };
+
+        assert_eq!(set_event_override("CH", "SUMMARY:Overridden LAST-MODIFIED:20210501T090000Z")?, Value::Int(1));
+        assert_eq!(set_event_override("CH", "SUMMARY:Overridden LAST-MODIFIED:20210501T090000Z")?, Value::Int(0));
+
+        // Ignored (older LAST-MODIFIED) updates are not a change.
+        assert_eq!(set_event_override("CH", "SUMMARY:Older LAST-MODIFIED:20210201T090000Z")?, Value::Int(0));
+
+        Ok(())
+    }
+
+    fn test_if_match_conditional_writes(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        let set_event = |connection: &mut Connection, if_match: &str, ical: &str| -> std::result::Result<Value, String> {
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("IFMATCH")
+                .arg(if_match)
+                .arg("CH")
+                .arg(ical)
+                .query::<Value>(connection)
+                .map_err(|redis_error| redis_error.to_string())
+        };
+
+        // Nothing is stored to match against.
+        assert_eq!(
+            set_event(connection, "0", "DTSTART:20201231T160000Z LAST-MODIFIED:20210501T090000Z"),
+            Err(String::from("CONFLICT: rdcl.evt_set: event uid: EVENT_ONE IFMATCH 0 does not match as nothing is stored")),
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "SUMMARY:Event one",
+                "DTSTART:20201231T160000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        // A missing SEQUENCE matches 0.
+        assert_eq!(
+            set_event(connection, "0", "SUMMARY:Event one (v1) DTSTART:20201231T160000Z SEQUENCE:1 LAST-MODIFIED:20210502T090000Z"),
+            Ok(Value::Int(1)),
+        );
+
+        assert_eq!(
+            set_event(connection, "0", "SUMMARY:Event one (stale) DTSTART:20201231T160000Z SEQUENCE:1 LAST-MODIFIED:20210503T090000Z"),
+            Err(String::from("CONFLICT: rdcl.evt_set: event uid: EVENT_ONE IFMATCH 0 does not match stored SEQUENCE:1")),
+        );
+
+        assert_eq!(
+            set_event(connection, "20210501T090000Z", "SUMMARY:Event one (stale) DTSTART:20201231T160000Z SEQUENCE:2 LAST-MODIFIED:20210503T090000Z"),
+            Err(String::from("CONFLICT: rdcl.evt_set: event uid: EVENT_ONE IFMATCH 20210501T090000Z does not match stored LAST-MODIFIED:20210502T090000Z")),
+        );
+
+        assert_eq!(
+            set_event(connection, "20210502T090000Z", "SUMMARY:Event one (v2) DTSTART:20201231T160000Z SEQUENCE:2 LAST-MODIFIED:20210503T090000Z"),
+            Ok(Value::Int(1)),
+        );
+
+        // Rejected writes leave the stored event untouched.
+        assert!(
+            redis::cmd("rdcl.evt_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .query::<Vec<String>>(connection)?
+                .contains(&String::from("SUMMARY:Event one (v2)")),
+        );
+
+        assert_error_returned!(
+            connection,
+            "IFMATCH:: 20210502T090000 is invalid, expected either a LAST-MODIFIED UTC date-time or a SEQUENCE number",
+            "rdcl.evt_set",
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "IFMATCH",
+            "20210502T090000",
+            "DTSTART:20201231T160000Z",
+        );
+
+        // LAST-MODIFIED is compared to the millisecond, so writes within the same second are told
+        // apart by X-MILLIS (a missing X-MILLIS being treated as 0).
+        assert_eq!(
+            set_event(connection, "20210503T090000Z", "SUMMARY:Event one (v3) DTSTART:20201231T160000Z SEQUENCE:3 LAST-MODIFIED;X-MILLIS=100:20210504T090000Z"),
+            Ok(Value::Int(1)),
+        );
+
+        assert_eq!(
+            set_event(connection, "20210504T090000Z", "SUMMARY:Event one (stale) DTSTART:20201231T160000Z SEQUENCE:4 LAST-MODIFIED;X-MILLIS=200:20210504T090000Z"),
+            Err(String::from("CONFLICT: rdcl.evt_set: event uid: EVENT_ONE IFMATCH 20210504T090000Z does not match stored LAST-MODIFIED;X-MILLIS=100:20210504T090000Z")),
+        );
+
+        // Both of these writers read v3, but the first writes v4 within the same second.
+        assert_eq!(
+            set_event(connection, "LAST-MODIFIED;X-MILLIS=100:20210504T090000Z", "SUMMARY:Event one (v4) DTSTART:20201231T160000Z SEQUENCE:4 LAST-MODIFIED;X-MILLIS=200:20210504T090000Z"),
+            Ok(Value::Int(1)),
+        );
+
+        assert_eq!(
+            set_event(connection, "LAST-MODIFIED;X-MILLIS=100:20210504T090000Z", "SUMMARY:Event one (v4 stale) DTSTART:20201231T160000Z SEQUENCE:4 LAST-MODIFIED;X-MILLIS=300:20210504T090000Z"),
+            Err(String::from("CONFLICT: rdcl.evt_set: event uid: EVENT_ONE IFMATCH LAST-MODIFIED;X-MILLIS=100:20210504T090000Z does not match stored LAST-MODIFIED;X-MILLIS=200:20210504T090000Z")),
+        );
+
+        assert_eq!(
+            set_event(connection, "3", "SUMMARY:Event one (v4 stale) DTSTART:20201231T160000Z SEQUENCE:4 LAST-MODIFIED;X-MILLIS=300:20210504T090000Z"),
+            Err(String::from("CONFLICT: rdcl.evt_set: event uid: EVENT_ONE IFMATCH 3 does not match stored SEQUENCE:4")),
+        );
+
+        let set_event_override = |connection: &mut Connection, if_match: &str, ical: &str| -> std::result::Result<Value, String> {
+            redis::cmd("rdcl.evo_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("20201231T160000Z")
+                .arg("IFMATCH")
+                .arg(if_match)
+                .arg("CH")
+                .arg(ical)
+                .query::<Value>(connection)
+                .map_err(|redis_error| redis_error.to_string())
+        };
+
+        assert_eq!(
+            set_event_override(connection, "20210501T090000Z", "SUMMARY:Overridden LAST-MODIFIED:20210501T090000Z"),
+            Err(String::from("CONFLICT: rdcl.evo_set: event uid: EVENT_ONE occurrence: 20201231T160000Z IFMATCH 20210501T090000Z does not match as nothing is stored")),
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "20201231T160000Z",
+            [
+                "SUMMARY:Overridden",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        assert_eq!(
+            set_event_override(connection, "20210501T090000Z", "SUMMARY:Overridden (v1) SEQUENCE:1 LAST-MODIFIED:20210502T090000Z"),
+            Ok(Value::Int(1)),
+        );
+
+        assert_eq!(
+            set_event_override(connection, "2", "SUMMARY:Overridden (stale) SEQUENCE:2 LAST-MODIFIED:20210503T090000Z"),
+            Err(String::from("CONFLICT: rdcl.evo_set: event uid: EVENT_ONE occurrence: 20201231T160000Z IFMATCH 2 does not match stored SEQUENCE:1")),
+        );
+
+        assert_eq!(
+            set_event_override(connection, "1", "SUMMARY:Overridden (v2) SEQUENCE:2 LAST-MODIFIED:20210503T090000Z"),
+            Ok(Value::Int(1)),
+        );
+
+        Ok(())
+    }
+
+    fn test_existence_conditional_writes(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        let set_event = |connection: &mut Connection, condition: &str, ical: &str| -> Result<Value> {
+            Ok(redis::cmd("rdcl.evt_set").arg("TEST_CALENDAR_UID").arg("EVENT_ONE").arg(condition).arg("CH").arg(ical).query::<Value>(connection)?)
+        };
+
+        // XX never creates the event.
+        assert_eq!(set_event(connection, "XX", "DTSTART:20201231T160000Z LAST-MODIFIED:20210501T090000Z")?, Value::Nil);
+        assert_eq!(redis::cmd("rdcl.evt_get").arg("TEST_CALENDAR_UID").arg("EVENT_ONE").query::<Value>(connection)?, Value::Nil);
+
+        assert_eq!(set_event(connection, "NX", "DTSTART:20201231T160000Z LAST-MODIFIED:20210501T090000Z")?, Value::Int(1));
+
+        // NX never updates the (now) existing event.
+        assert_eq!(set_event(connection, "nx", "SUMMARY:Updated DTSTART:20201231T160000Z LAST-MODIFIED:20210502T090000Z")?, Value::Nil);
+
+        assert_eq!(set_event(connection, "XX", "SUMMARY:Updated DTSTART:20201231T160000Z LAST-MODIFIED:20210502T090000Z")?, Value::Int(1));
+
+        let set_event_override = |connection: &mut Connection, condition: &str, ical: &str| -> Result<Value> {
+            Ok(redis::cmd("rdcl.evo_set").arg("TEST_CALENDAR_UID").arg("EVENT_ONE").arg("20201231T160000Z").arg(condition).arg("CH").arg(ical).query::<Value>(connection)?)
+        };
+
+        assert_eq!(set_event_override(connection, "XX", "SUMMARY:Overridden LAST-MODIFIED:20210501T090000Z")?, Value::Nil);
+        assert_eq!(
+            redis::cmd("rdcl.evo_get").arg("TEST_CALENDAR_UID").arg("EVENT_ONE").arg("20201231T160000Z").query::<Value>(connection)?,
+            Value::Nil,
+        );
+
+        assert_eq!(set_event_override(connection, "NX", "SUMMARY:Overridden LAST-MODIFIED:20210501T090000Z")?, Value::Int(1));
+        assert_eq!(set_event_override(connection, "NX", "SUMMARY:Overridden (updated) LAST-MODIFIED:20210502T090000Z")?, Value::Nil);
+        assert_eq!(set_event_override(connection, "XX", "SUMMARY:Overridden (updated) LAST-MODIFIED:20210502T090000Z")?, Value::Int(1));
+
+        Ok(())
+    }
+
+    fn test_set_with_now(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        // The defaulted LAST-MODIFIED is the provided NOW rather than the wall clock.
+        let event_set_result: Vec<String> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("NOW")
+                .arg("20210501T090000Z")
+                .arg("SUMMARY:Event one DTSTART:20201231T160000Z")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_set_result,
+            [
+                "SUMMARY:Event one",
+                "DTSTART:20201231T160000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "UID:EVENT_ONE",
+            ],
+        );
+
+        // A provided LAST-MODIFIED takes precedence.
+        let event_set_result: Vec<String> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("NOW")
+                .arg("20210502T090000Z")
+                .arg("SUMMARY:Event one DTSTART:20201231T160000Z LAST-MODIFIED:20210503T090000Z")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_set_result,
+            [
+                "SUMMARY:Event one",
+                "DTSTART:20201231T160000Z",
+                "LAST-MODIFIED:20210503T090000Z",
+                "UID:EVENT_ONE",
+            ],
+        );
+
+        let event_override_set_result: Vec<String> =
+            redis::cmd("rdcl.evo_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("20201231T160000Z")
+                .arg("NOW")
+                .arg("20210504T090000Z")
+                .arg("SUMMARY:Event one (overridden)")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_override_set_result,
+            [
+                "SUMMARY:Event one (overridden)",
+                "DTSTART:20201231T160000Z",
+                "LAST-MODIFIED:20210504T090000Z",
+            ],
+        );
+
+        assert_error_returned!(
+            connection,
+            "NOW:: 20210501T090000 is invalid, expected a UTC date-time",
+            "rdcl.evt_set",
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "NOW",
+            "20210501T090000",
+            "DTSTART:20201231T160000Z",
+        );
+
+        Ok(())
+    }
+
+    fn test_query_return_properties(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "SUMMARY:Event one",
+                "DESCRIPTION:A long description of event one",
+                "DTSTART:20201231T160000Z",
+                "CATEGORIES:CATEGORY_ONE",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let event_instance_query_results: Vec<(Vec<String>, Vec<String>)> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-RETURN:UID,DTSTART,SUMMARY")
+                .query(connection)?;
+
+        assert_eq!(
+            event_instance_query_results,
+            vec![
+                (
+                    vec![String::from("DTSTART:20201231T160000Z")],
+                    vec![
+                        String::from("DTSTART:20201231T160000Z"),
+                        String::from("SUMMARY:Event one"),
+                        String::from("UID:EVENT_ONE"),
+                    ],
+                ),
+            ],
+        );
+
+        let event_query_results: Vec<(Vec<String>, Vec<String>)> =
+            redis::cmd("rdcl.evt_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-RETURN:UID,CATEGORIES")
+                .query(connection)?;
+
+        assert_eq!(
+            event_query_results,
+            vec![
+                (
+                    vec![String::from("DTSTART:20201231T160000Z")],
+                    vec![
+                        String::from("CATEGORIES:CATEGORY_ONE"),
+                        String::from("UID:EVENT_ONE"),
+                    ],
+                ),
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_query_count_only(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_DAILY",
+            [
+                "DTSTART:20201231T160000Z",
+                "RRULE:COUNT=5;FREQ=DAILY;INTERVAL=1",
+                "CATEGORIES:CATEGORY_ONE",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONCE",
+            [
+                "DTSTART:20210101T090000Z",
+                "CATEGORIES:CATEGORY_TWO",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        // The offset and limit are disregarded.
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-LIMIT:2")?, 6);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-CATEGORIES:CATEGORY_ONE")?, 5);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-DISTINCT:UID")?, 2);
+
+        assert_eq!(count("rdcl.evt_query", "X-COUNT:TRUE X-LIMIT:1")?, 2);
+        assert_eq!(count("rdcl.evt_query", "X-COUNT:TRUE X-CATEGORIES:CATEGORY_TWO")?, 1);
+
+        Ok(())
+    }
+
+    fn test_line_separated_content_line_args(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        let event_set_result: Vec<String> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg(
+                    [
+                        "SUMMARY:Event one",
+                        "DESCRIPTION:A description folded across",
+                        "  multiple lines",
+                        "DTSTART:20201231T160000Z",
+                        "CATEGORIES:CATEGORY_ONE",
+                        "LAST-MODIFIED:20210501T090000Z",
+                        "",
+                    ].join("\r\n")
+                )
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_set_result,
+            [
+                "CATEGORIES:CATEGORY_ONE",
+                "DESCRIPTION:A description folded across multiple lines",
+                "DTSTART:20201231T160000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "SUMMARY:Event one",
+                "UID:EVENT_ONE",
+            ],
+        );
+
+        let event_query_results: Vec<(Vec<String>, Vec<String>)> =
+            redis::cmd("rdcl.evt_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-CATEGORIES:CATEGORY_ONE\nX-RETURN:UID\n")
+                .query(connection)?;
+
+        assert_eq!(
+            event_query_results,
+            vec![
+                (
+                    vec![String::from("DTSTART:20201231T160000Z")],
+                    vec![String::from("UID:EVENT_ONE")],
+                ),
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_calendar_get_with_metadata(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "DTSTART:20201231T160000Z",
+                "RRULE:COUNT=3;FREQ=DAILY;INTERVAL=1",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "20210101T160000Z",
+            [
+                "SUMMARY:Overridden",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let calendar_get_result: Value =
+            redis::cmd("rdcl.cal_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("WITHMETADATA")
+                .query(connection)?;
+
+        assert_eq!(
+            calendar_get_result,
+            Value::Bulk(vec![
+                Value::Status(String::from("uid")),
+                Value::Status(String::from("TEST_CALENDAR_UID")),
+                Value::Status(String::from("event_count")),
+                Value::Int(1),
+                Value::Status(String::from("override_count")),
+                Value::Int(1),
+                Value::Status(String::from("indexes_active")),
+                Value::Int(1),
+                Value::Status(String::from("indexes_rebuilt_at")),
+                Value::Nil,
+                Value::Status(String::from("generation")),
+                Value::Int(2),
+                Value::Status(String::from("properties")),
+                Value::Bulk(vec![Value::Status(String::from("UID:TEST_CALENDAR_UID"))]),
+            ]),
+        );
+
+        rebuild_calendar_indexes!(connection, "TEST_CALENDAR_UID");
+
+        let calendar_get_result: Vec<Value> =
+            redis::cmd("rdcl.cal_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("WITHMETADATA")
+                .query(connection)?;
+
+        assert!(matches!(calendar_get_result[9], Value::Status(_)));
+
+        // Each mutation (including rebuilding the indexes) bumps the generation, whereas reads do not.
+        assert_eq!(calendar_get_result[11], Value::Int(3));
+
+        let calendar_set_result: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("COLOR:turquoise")
+                .query(connection)?;
+
+        assert_eq!(calendar_set_result.len(), 2);
+
+        let calendar_get_result: Vec<Value> =
+            redis::cmd("rdcl.cal_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("WITHMETADATA")
+                .query(connection)?;
+
+        assert_eq!(calendar_get_result[11], Value::Int(4));
+
+        let event_expire_result: String =
+            redis::cmd("rdcl.evt_expire")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_ONE")
+                .arg("AT")
+                .arg("20500101T000000Z")
+                .query(connection)?;
+
+        assert_eq!(event_expire_result, "20500101T000000Z");
+
+        let calendar_get_result: Vec<Value> =
+            redis::cmd("rdcl.cal_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("WITHMETADATA")
+                .query(connection)?;
+
+        assert_eq!(calendar_get_result[11], Value::Int(5));
+
+        // Assert the generation is retained by the Calendar reconstructed from the rewritten AOF.
+        utils::reload_from_aof(connection)?;
+
+        let calendar_get_result: Vec<Value> =
+            redis::cmd("rdcl.cal_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("WITHMETADATA")
+                .query(connection)?;
+
+        assert_eq!(calendar_get_result[11], Value::Int(5));
+
+        Ok(())
+    }
+
+    fn test_calendar_passive_properties(connection: &mut Connection) -> Result<()> {
+        let calendar_set_result: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("NAME:Team: Alpha")
+                .arg("COLOR:turquoise")
+                .arg("X-WR-CALDESC:Team Alpha sessions")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_set_result,
+            vec![
+                String::from("COLOR:turquoise"),
+                String::from("NAME:Team: Alpha"),
+                String::from("UID:TEST_CALENDAR_UID"),
+                String::from("X-WR-CALDESC:Team Alpha sessions"),
+            ],
+        );
+
+        // Setting an existing passive property replaces it, leaving the others unchanged.
+        let calendar_set_result: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("NAME;LANGUAGE=en:Team Beta")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_set_result,
+            vec![
+                String::from("COLOR:turquoise"),
+                String::from("NAME;LANGUAGE=en:Team Beta"),
+                String::from("UID:TEST_CALENDAR_UID"),
+                String::from("X-WR-CALDESC:Team Alpha sessions"),
+            ],
+        );
+
+        // Event specific properties are not accepted as calendar properties.
+        let invalid_calendar_set_result: RedisResult<Vec<String>> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("DTSTART:20210101T090000Z")
+                .query(connection);
+
+        assert!(invalid_calendar_set_result.is_err());
+
+        // Assert the passive properties are retained by a Calendar restored from a dump.
+        let calendar_dump: Vec<u8> = redis::cmd("DUMP").arg("TEST_CALENDAR_UID").query(connection)?;
+
+        assert_eq!(
+            redis::cmd("RESTORE").arg("TEST_CALENDAR_UID_RESTORED").arg(0).arg(calendar_dump).query(connection),
+            RedisResult::Ok(Value::Okay),
+        );
+
+        let calendar_get_result: Vec<String> = redis::cmd("rdcl.cal_get").arg("TEST_CALENDAR_UID_RESTORED").query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_get_result,
+            vec![
+                String::from("COLOR:turquoise"),
+                String::from("NAME;LANGUAGE=en:Team Beta"),
+                String::from("UID:TEST_CALENDAR_UID"),
+                String::from("X-WR-CALDESC:Team Alpha sessions"),
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_command_metrics(connection: &mut Connection) -> Result<()> {
+        assert_eq!(
+            redis::cmd("rdcl.metrics").arg("RESET").query(connection),
+            RedisResult::Ok(Value::Status(String::from("OK"))),
+        );
+
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "DTSTART:20201231T160000Z",
+                "RRULE:COUNT=3;FREQ=DAILY;INTERVAL=1",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let invalid_event_set_result: RedisResult<Value> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_TWO")
+                .arg("DTSTART:INVALID")
+                .query(connection);
+
+        assert!(invalid_event_set_result.is_err());
+
+        let metrics: Vec<Value> = redis::cmd("rdcl.metrics").query(connection)?;
+
+        let command_metrics: HashMap<String, HashMap<String, Value>> =
+            metrics
+                .chunks(2)
+                .map(|chunk| {
+                    let command_name: String = redis::from_redis_value(&chunk[0]).unwrap();
+                    let command_metrics: Vec<Value> = redis::from_redis_value(&chunk[1]).unwrap();
+
+                    let command_metrics =
+                        command_metrics
+                            .chunks(2)
+                            .map(|chunk| (redis::from_redis_value(&chunk[0]).unwrap(), chunk[1].to_owned()))
+                            .collect();
+
+                    (command_name, command_metrics)
+                })
+                .collect();
+
+        assert_eq!(command_metrics["rdcl.evt_set"]["calls"], Value::Int(2));
+        assert_eq!(command_metrics["rdcl.evt_set"]["failed_calls"], Value::Int(1));
+        assert_eq!(command_metrics["rdcl.cal_set"]["calls"], Value::Int(1));
+        assert_eq!(command_metrics["rdcl.cal_set"]["failed_calls"], Value::Int(0));
+
+        let Value::Bulk(latency_histogram) = &command_metrics["rdcl.evt_set"]["latency_histogram"] else {
+            panic!("Expected latency histogram array");
+        };
+
+        // 12 bounded buckets, and the final unbounded bucket.
+        assert_eq!(latency_histogram.len(), 26);
+        assert_eq!(latency_histogram[24], Value::Status(String::from("+inf")));
+
+        // Assert the metrics are also included in the INFO output.
+        let info: String = redis::cmd("INFO").arg("RediCal_commandstats").query(connection)?;
+
+        assert!(info.contains("rdcl.evt_set:calls=2,failed_calls=1,"));
+
+        assert_eq!(
+            redis::cmd("rdcl.metrics").arg("RESET").query(connection),
+            RedisResult::Ok(Value::Status(String::from("OK"))),
+        );
+
+        assert_eq!(redis::cmd("rdcl.metrics").query(connection), RedisResult::Ok(Value::Bulk(vec![])));
+
+        let invalid_metrics_result: RedisResult<Value> = redis::cmd("rdcl.metrics").arg("INVALID").query(connection);
+
+        assert!(invalid_metrics_result.is_err());
+
+        Ok(())
+    }
+
+    fn test_scheduled_pruning(connection: &mut Connection) -> Result<()> {
+        let calendar_set_result: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-PRUNE-OLDER-THAN:P1D")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_set_result,
+            vec![
+                String::from("UID:TEST_CALENDAR_UID"),
+                String::from("X-PRUNE-OLDER-THAN:P1D"),
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ENDED",
+            [
+                "RRULE:FREQ=DAILY;COUNT=2;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_EVERY_DAY",
+            [
+                "RRULE:FREQ=DAILY;INTERVAL=1",
+                "DTSTART:20201231T180000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_EVERY_DAY",
+            "20210101T180000Z",
+            [
+                "LAST-MODIFIED:20210501T090000Z",
+                "SUMMARY:Overridden summary text",
+            ],
+        );
+
+        // Update Redis RediCal config to sweep Calendars declaring a retention period every 10ms.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.PRUNE-SWEEP-INTERVAL-MS")
+            .arg("10")
+            .execute(connection);
+
+        // Allow the sweep to prune the events and event occurrence overrides older than a day.
+        std::thread::sleep(std::time::Duration::from_millis(1500));
+
+        list_and_assert_matching_events!(
+            connection,
+            "TEST_CALENDAR_UID",
+            [
+                [
+                    "DTSTART:20201231T180000Z",
+                    "LAST-MODIFIED:20210501T090000Z",
+                    "RRULE:FREQ=DAILY;INTERVAL=1",
+                    "UID:EVENT_EVERY_DAY",
+                ],
+            ],
+        );
+
+        list_and_assert_matching_event_overrides!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_EVERY_DAY",
+            [],
+        );
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.PRUNE-SWEEP-INTERVAL-MS")
+            .arg("60000")
+            .execute(connection);
+
+        Ok(())
+    }
+
+    fn test_query_merge_intervals(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_DAILY",
+            [
+                "DTSTART:20210105T090000Z",
+                "DTEND:20210105T100000Z",
+                "RRULE:COUNT=3;FREQ=DAILY;INTERVAL=1",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        // Overlapping the first occurrence of EVENT_DAILY.
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_OVERLAPPING",
+            [
+                "DTSTART:20210105T093000Z",
+                "DTEND:20210105T103000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        // Adjacent to the second occurrence of EVENT_DAILY.
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ADJACENT",
+            [
+                "DTSTART:20210106T100000Z",
+                "DTEND:20210106T110000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let merged_intervals: Vec<Vec<String>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-MERGE-INTERVALS:TRUE")
+                .arg("X-UNTIL:20210107T000000Z")
+                .query(connection)?;
+
+        assert_eq!(
+            merged_intervals,
+            vec![
+                vec![
+                    String::from("DTEND:20210105T103000Z"),
+                    String::from("DTSTART:20210105T090000Z"),
+                ],
+                vec![
+                    String::from("DTEND:20210106T110000Z"),
+                    String::from("DTSTART:20210106T090000Z"),
+                ],
+            ],
+        );
+
+        // Rendered in the queried timezone.
+        let merged_intervals: Vec<Vec<String>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-MERGE-INTERVALS:TRUE")
+                .arg("X-FROM:20210107T000000Z")
+                .arg("X-TZID:Europe/Vilnius")
+                .query(connection)?;
+
+        assert_eq!(
+            merged_intervals,
+            vec![
+                vec![
+                    String::from("DTEND;TZID=Europe/Vilnius:20210107T120000"),
+                    String::from("DTSTART;TZID=Europe/Vilnius:20210107T110000"),
+                ],
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_calendar_description_properties(connection: &mut Connection) -> Result<()> {
+        let calendar_set_result: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("NAME;LANGUAGE=en:Team Calendar")
+                .arg("NAME;LANGUAGE=fr:Calendrier d'équipe")
+                .arg("COLOR:turquoise")
+                .arg("REFRESH-INTERVAL:P1W")
+                .arg("SOURCE:https://example.com/team.ics")
+                .query(connection)?;
+
+        // The REFRESH-INTERVAL VALUE=DURATION parameter is always returned.
+        assert_matching_ical_properties!(
+            calendar_set_result,
+            vec![
+                String::from("COLOR:turquoise"),
+                String::from("NAME;LANGUAGE=en:Team Calendar"),
+                String::from("NAME;LANGUAGE=fr:Calendrier d'équipe"),
+                String::from("REFRESH-INTERVAL;VALUE=DURATION:P1W"),
+                String::from("SOURCE:https://example.com/team.ics"),
+                String::from("UID:TEST_CALENDAR_UID"),
+            ],
+        );
+
+        // Invalid values are rejected, leaving the Calendar unchanged.
+        let invalid_calendar_set_result: RedisResult<Vec<String>> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("REFRESH-INTERVAL:-P1W")
+                .query(connection);
+
+        assert!(invalid_calendar_set_result.is_err());
+
+        let invalid_calendar_set_result: RedisResult<Vec<String>> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("SOURCE:example.com/team.ics")
+                .query(connection);
+
+        assert!(invalid_calendar_set_result.is_err());
+
+        // Setting NAME replaces all the existing NAME properties.
+        let calendar_set_result: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("NAME:Team Alpha")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_set_result,
+            vec![
+                String::from("COLOR:turquoise"),
+                String::from("NAME:Team Alpha"),
+                String::from("REFRESH-INTERVAL;VALUE=DURATION:P1W"),
+                String::from("SOURCE:https://example.com/team.ics"),
+                String::from("UID:TEST_CALENDAR_UID"),
+            ],
+        );
+
+        // Assert the exported document carries the display metadata.
+        let ics_document: String =
+            redis::cmd("rdcl.cal_export")
+                .arg("TEST_CALENDAR_UID")
+                .query(connection)?;
+
+        assert!(ics_document.contains("\r\nNAME:Team Alpha\r\n"));
+        assert!(ics_document.contains("\r\nCOLOR:turquoise\r\n"));
+        assert!(ics_document.contains("\r\nREFRESH-INTERVAL;VALUE=DURATION:P1W\r\n"));
+        assert!(ics_document.contains("\r\nSOURCE:https://example.com/team.ics\r\n"));
+
+        // Assert the properties are retained by a Calendar restored from a dump.
+        let calendar_dump: Vec<u8> = redis::cmd("DUMP").arg("TEST_CALENDAR_UID").query(connection)?;
+
+        assert_eq!(
+            redis::cmd("RESTORE").arg("TEST_CALENDAR_UID_RESTORED").arg(0).arg(calendar_dump).query(connection),
+            RedisResult::Ok(Value::Okay),
+        );
+
+        let calendar_get_result: Vec<String> = redis::cmd("rdcl.cal_get").arg("TEST_CALENDAR_UID_RESTORED").query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_get_result,
+            vec![
+                String::from("COLOR:turquoise"),
+                String::from("NAME:Team Alpha"),
+                String::from("REFRESH-INTERVAL;VALUE=DURATION:P1W"),
+                String::from("SOURCE:https://example.com/team.ics"),
+                String::from("UID:TEST_CALENDAR_UID"),
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_event_display_and_conference_properties(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "TEAM_SYNC",
+            [
+                "SUMMARY:Team sync",
+                "RRULE:COUNT=2;FREQ=DAILY;INTERVAL=1",
+                "DTSTART:20210105T090000Z",
+                "DTEND:20210105T093000Z",
+                "COLOR:turquoise",
+                "IMAGE;DISPLAY=BADGE;FMTTYPE=image/png;VALUE=URI:https://example.com/images/sync.png",
+                "CONFERENCE;FEATURE=AUDIO,VIDEO;LABEL=Video call;VALUE=URI:https://meet.example.com/team-sync",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "TEAM_SYNC",
+            "20210106T090000Z",
+            [
+                "COLOR:crimson",
+                "CONFERENCE;FEATURE=PHONE;LABEL=Dial-in;VALUE=URI:tel:+1-412-555-0123,,,654321",
+            ],
+        );
+
+        // Invalid values are rejected, leaving the event unchanged.
+        let invalid_event_set_result: RedisResult<Vec<String>> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("TEAM_SYNC")
+                .arg("DTSTART:20210105T090000Z IMAGE:iVBORw0KGgo=")
+                .query(connection);
+
+        assert!(invalid_event_set_result.is_err());
+
+        let invalid_event_set_result: RedisResult<Vec<String>> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("TEAM_SYNC")
+                .arg("DTSTART:20210105T090000Z CONFERENCE:Dial in")
+                .query(connection);
+
+        assert!(invalid_event_set_result.is_err());
+
+        // The overridden COLOR and CONFERENCE replace those of the event, whilst the IMAGE is carried over.
+        let event_instance_get_result: Vec<String> =
+            redis::cmd("rdcl.evi_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("TEAM_SYNC")
+                .arg("20210106T090000Z")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_instance_get_result,
+            vec![
+                String::from("COLOR:crimson"),
+                String::from("CONFERENCE;FEATURE=PHONE;LABEL=Dial-in;VALUE=URI:tel:+1-412-555-0123,,,654321"),
+                String::from("DTEND:20210106T093000Z"),
+                String::from("DTSTART:20210106T090000Z"),
+                String::from("DURATION:PT30M"),
+                String::from("IMAGE;DISPLAY=BADGE;FMTTYPE=image/png;VALUE=URI:https://example.com/images/sync.png"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210106T090000Z"),
+                String::from("SUMMARY:Team sync"),
+                String::from("UID:TEAM_SYNC"),
+            ],
+        );
+
+        let event_instance_get_result: Vec<String> =
+            redis::cmd("rdcl.evi_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("TEAM_SYNC")
+                .arg("20210105T090000Z")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_instance_get_result,
+            vec![
+                String::from("COLOR:turquoise"),
+                String::from("CONFERENCE;FEATURE=AUDIO,VIDEO;LABEL=Video call;VALUE=URI:https://meet.example.com/team-sync"),
+                String::from("DTEND:20210105T093000Z"),
+                String::from("DTSTART:20210105T090000Z"),
+                String::from("DURATION:PT30M"),
+                String::from("IMAGE;DISPLAY=BADGE;FMTTYPE=image/png;VALUE=URI:https://example.com/images/sync.png"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210105T090000Z"),
+                String::from("SUMMARY:Team sync"),
+                String::from("UID:TEAM_SYNC"),
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_event_structured_data_and_participants(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "CUP_FINAL",
+            [
+                "SUMMARY:Cup final",
+                "RRULE:COUNT=2;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20210105T150000Z",
+                "DTEND:20210105T170000Z",
+                "STRUCTURED-DATA;FMTTYPE=application/ld+json;SCHEMA=\"https://schema.org/SportsEvent\";VALUE=URI:https://example.com/events/cup-final.jsonld",
+                "BEGIN:PARTICIPANT PARTICIPANT-TYPE:PERFORMER UID:HOME_TEAM DESCRIPTION:Home team END:PARTICIPANT",
+                "BEGIN:PARTICIPANT PARTICIPANT-TYPE:PERFORMER UID:AWAY_TEAM DESCRIPTION:Away team END:PARTICIPANT",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "CUP_FINAL",
+            "20210112T150000Z",
+            [
+                "BEGIN:PARTICIPANT PARTICIPANT-TYPE:PERFORMER UID:REPLAY_TEAM DESCRIPTION:Replay team END:PARTICIPANT",
+            ],
+        );
+
+        // Invalid values and components are rejected, leaving the event unchanged.
+        let invalid_event_set_result: RedisResult<Vec<String>> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("CUP_FINAL")
+                .arg("DTSTART:20210105T150000Z STRUCTURED-DATA;VALUE=URI:cup-final")
+                .query(connection);
+
+        assert!(invalid_event_set_result.is_err());
+
+        let invalid_event_set_result: RedisResult<Vec<String>> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("CUP_FINAL")
+                .arg("DTSTART:20210105T150000Z BEGIN:PARTICIPANT UID:HOME_TEAM END:PARTICIPANT")
+                .query(connection);
+
+        assert!(invalid_event_set_result.is_err());
+
+        // Both participants are carried over to the first occurrence.
+        let event_instance_get_result: Vec<String> =
+            redis::cmd("rdcl.evi_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("CUP_FINAL")
+                .arg("20210105T150000Z")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_instance_get_result,
+            vec![
+                String::from("BEGIN:PARTICIPANT PARTICIPANT-TYPE:PERFORMER UID:AWAY_TEAM DESCRIPTION:Away team END:PARTICIPANT"),
+                String::from("BEGIN:PARTICIPANT PARTICIPANT-TYPE:PERFORMER UID:HOME_TEAM DESCRIPTION:Home team END:PARTICIPANT"),
+                String::from("DTEND:20210105T170000Z"),
+                String::from("DTSTART:20210105T150000Z"),
+                String::from("DURATION:PT2H"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210105T150000Z"),
+                String::from("STRUCTURED-DATA;FMTTYPE=application/ld+json;SCHEMA=\"https://schema.org/SportsEvent\";VALUE=URI:https://example.com/events/cup-final.jsonld"),
+                String::from("SUMMARY:Cup final"),
+                String::from("UID:CUP_FINAL"),
+            ],
+        );
+
+        // The overridden participant replaces both of those of the event.
+        let event_instance_get_result: Vec<String> =
+            redis::cmd("rdcl.evi_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("CUP_FINAL")
+                .arg("20210112T150000Z")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_instance_get_result,
+            vec![
+                String::from("BEGIN:PARTICIPANT PARTICIPANT-TYPE:PERFORMER UID:REPLAY_TEAM DESCRIPTION:Replay team END:PARTICIPANT"),
+                String::from("DTEND:20210112T170000Z"),
+                String::from("DTSTART:20210112T150000Z"),
+                String::from("DURATION:PT2H"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210112T150000Z"),
+                String::from("STRUCTURED-DATA;FMTTYPE=application/ld+json;SCHEMA=\"https://schema.org/SportsEvent\";VALUE=URI:https://example.com/events/cup-final.jsonld"),
+                String::from("SUMMARY:Cup final"),
+                String::from("UID:CUP_FINAL"),
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_event_alarms(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "STANDUP",
+            [
+                "SUMMARY:Standup",
+                "RRULE:COUNT=2;FREQ=DAILY;INTERVAL=1",
+                "DTSTART:20210105T090000Z",
+                "DTEND:20210105T091500Z",
+                "BEGIN:VALARM UID:STANDUP_ALARM TRIGGER:-PT5M ACTION:DISPLAY DESCRIPTION:Standup soon END:VALARM",
+            ],
+        );
+
+        // The alarm of the second occurrence has been acknowledged.
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "STANDUP",
+            "20210106T090000Z",
+            [
+                "BEGIN:VALARM UID:STANDUP_ALARM TRIGGER:-PT5M ACTION:DISPLAY DESCRIPTION:Standup soon ACKNOWLEDGED:20210106T085500Z END:VALARM",
+            ],
+        );
+
+        // Invalid components are rejected, leaving the event unchanged.
+        for invalid_alarm in [
+            "BEGIN:VALARM UID:STANDUP_ALARM ACTION:DISPLAY END:VALARM",
+            "BEGIN:VALARM TRIGGER:-PT5M ACTION:DISPLAY ACKNOWLEDGED:20210106T085500 END:VALARM",
+            "BEGIN:VALARM TRIGGER:-PT5M ACTION:DISPLAY PROXIMITY:ARRIVE PROXIMITY:DEPART END:VALARM",
+        ] {
+            let invalid_event_set_result: RedisResult<Vec<String>> =
+                redis::cmd("rdcl.evt_set")
+                    .arg("TEST_CALENDAR_UID")
+                    .arg("STANDUP")
+                    .arg(format!("DTSTART:20210105T090000Z {invalid_alarm}"))
+                    .query(connection);
+
+            assert!(invalid_event_set_result.is_err());
+        }
+
+        let event_instance_get_result: Vec<String> =
+            redis::cmd("rdcl.evi_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("STANDUP")
+                .arg("20210105T090000Z")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_instance_get_result,
+            vec![
+                String::from("BEGIN:VALARM UID:STANDUP_ALARM TRIGGER:-PT5M ACTION:DISPLAY DESCRIPTION:Standup soon END:VALARM"),
+                String::from("DTEND:20210105T091500Z"),
+                String::from("DTSTART:20210105T090000Z"),
+                String::from("DURATION:PT15M"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210105T090000Z"),
+                String::from("SUMMARY:Standup"),
+                String::from("UID:STANDUP"),
+            ],
+        );
+
+        let event_instance_get_result: Vec<String> =
+            redis::cmd("rdcl.evi_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("STANDUP")
+                .arg("20210106T090000Z")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            event_instance_get_result,
+            vec![
+                String::from("BEGIN:VALARM UID:STANDUP_ALARM TRIGGER:-PT5M ACTION:DISPLAY DESCRIPTION:Standup soon ACKNOWLEDGED:20210106T085500Z END:VALARM"),
+                String::from("DTEND:20210106T091500Z"),
+                String::from("DTSTART:20210106T090000Z"),
+                String::from("DURATION:PT15M"),
+                String::from("RECURRENCE-ID;VALUE=DATE-TIME:20210106T090000Z"),
+                String::from("SUMMARY:Standup"),
+                String::from("UID:STANDUP"),
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_event_location_query(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "CONCERT",
+            [
+                "SUMMARY:Concert",
+                "RRULE:COUNT=3;FREQ=DAILY;INTERVAL=1",
+                "DTSTART:20210105T190000Z",
+                "DTEND:20210105T210000Z",
+                "LOCATION:Royal  Albert Hall",
+                "LOCATION-TYPE:CONCERT_HALL",
+            ],
+        );
+
+        // The second occurrence has moved venue.
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "CONCERT",
+            "20210106T190000Z",
+            [
+                "LOCATION:Oxford Town Hall",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "LECTURE",
+            [
+                "SUMMARY:Lecture",
+                "DTSTART:20210110T100000Z",
+                "DTEND:20210110T110000Z",
+                "LOCATION:Oxford Town Hall",
+            ],
+        );
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        // The whitespace of the indexed and queried locations is normalized.
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-LOCATION:Royal Albert Hall")?, 2);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-LOCATION:Royal   Albert Hall")?, 2);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-LOCATION:Oxford Town Hall")?, 2);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-LOCATION;OP=OR:Royal Albert Hall,Oxford Town Hall")?, 4);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-LOCATION-NOT:Royal Albert Hall")?, 2);
+
+        // The whole location text is matched, and not confused with LOCATION-TYPE.
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-LOCATION:Royal")?, 0);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-LOCATION:CONCERT_HALL")?, 0);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-LOCATION-TYPE:CONCERT_HALL")?, 3);
+
+        assert_eq!(count("rdcl.evt_query", "X-COUNT:TRUE X-LOCATION:Royal Albert Hall")?, 1);
+
+        assert_eq!(
+            redis::cmd("rdcl.cal_idx_terms").arg("TEST_CALENDAR_UID").arg("LOCATION").query::<Value>(connection)?,
+            Value::Bulk(vec![
+                Value::Data(String::from("Oxford Town Hall").into_bytes()),
+                Value::Int(2),
+                Value::Data(String::from("Royal Albert Hall").into_bytes()),
+                Value::Int(1),
+            ]),
+        );
+
+        Ok(())
+    }
+
+    fn test_degenerate_query_warnings(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "SUMMARY:Event one",
+                "DTSTART:20210105T190000Z",
+                "DTEND:20210105T210000Z",
+                "CATEGORIES:ART",
+            ],
+        );
+
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-UID:EVENT_ONE X-UID:EVENT_TWO")
+                .arg("WITHHEADER")
+                .query(connection)?;
+
+        assert_eq!(
+            calendar_query_result,
+            vec![
+                query_results_header(&[], 0, 0, &["UID:EVENT_ONE AND UID:EVENT_TWO can never both be satisfied"]),
+            ],
+        );
+
+        // Empty groups are ignored (the remaining conditions still match), but warned about.
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evt_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-CATEGORIES:ART ()")
+                .arg("WITHHEADER")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 2);
+        assert_eq!(
+            calendar_query_result[0],
+            query_results_header(&[], 0, 0, &["empty where group () has no conditions and is ignored"]),
+        );
+
+        // Under STRICT, degenerate queries are rejected with an error.
+        let strict_query_result: RedisResult<Vec<Value>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-CATEGORIES:ART X-CATEGORIES-NOT:ART")
+                .arg("STRICT")
+                .query(connection);
+
+        assert!(strict_query_result.is_err());
+
+        let strict_query_result: RedisResult<Vec<Value>> =
+            redis::cmd("rdcl.evt_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-UID:EVENT_ONE X-UID:EVENT_TWO")
+                .arg("STRICT")
+                .arg("WITHPAGINATION")
+                .query(connection);
+
+        assert!(strict_query_result.is_err());
+
+        // Whereas STRICT queries without degenerate conditions are unaffected.
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-CATEGORIES:ART")
+                .arg("STRICT")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 1);
+
+        Ok(())
+    }
+
+    fn test_event_multiple_reltype_query(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        for (event_uid, related_to) in [
+            ("CHILD_EVENT", "RELATED-TO;RELTYPE=PARENT:SOME_UID"),
+            ("SIBLING_EVENT", "RELATED-TO;RELTYPE=SIBLING:SOME_UID"),
+            ("PARENT_EVENT", "RELATED-TO;RELTYPE=CHILD:SOME_UID"),
+            ("UNRELATED_EVENT", "RELATED-TO;RELTYPE=SIBLING:OTHER_UID"),
+        ] {
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                event_uid,
+                [
+                    "DTSTART:20210105T190000Z",
+                    "DTEND:20210105T210000Z",
+                    related_to,
+                ],
+            );
+        }
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        assert_eq!(count("rdcl.evt_query", "X-COUNT:TRUE X-RELATED-TO;RELTYPE=PARENT:SOME_UID")?, 1);
+        assert_eq!(count("rdcl.evt_query", "X-COUNT:TRUE X-RELATED-TO;RELTYPE=PARENT,SIBLING:SOME_UID")?, 2);
+        assert_eq!(count("rdcl.evt_query", "X-COUNT:TRUE X-RELATED-TO;RELTYPE=PARENT,SIBLING,CHILD:SOME_UID")?, 3);
+        assert_eq!(count("rdcl.evt_query", "X-COUNT:TRUE X-RELATED-TO;RELTYPE=SIBLING,CHILD;OP=OR:SOME_UID,OTHER_UID")?, 3);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-RELATED-TO;RELTYPE=PARENT,SIBLING:SOME_UID")?, 2);
+
+        assert_eq!(count("rdcl.evt_query", "X-COUNT:TRUE X-RELATED-TO-NOT;RELTYPE=PARENT,SIBLING:SOME_UID")?, 2);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-RELATED-TO-NOT;RELTYPE=PARENT,SIBLING:SOME_UID")?, 2);
+
+        Ok(())
+    }
+
+    fn test_event_list_valued_and_query(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        for (event_uid, categories, location_type) in [
+            ("ART_AND_MUSIC_EVENT", "CATEGORIES:ART,MUSIC", "LOCATION-TYPE:HALL,OUTDOOR"),
+            ("ART_EVENT", "CATEGORIES:ART", "LOCATION-TYPE:HALL"),
+            ("MUSIC_EVENT", "CATEGORIES:MUSIC", "LOCATION-TYPE:OUTDOOR"),
+        ] {
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                event_uid,
+                [
+                    "DTSTART:20210105T190000Z",
+                    "DTEND:20210105T210000Z",
+                    categories,
+                    location_type,
+                ],
+            );
+        }
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        // Every listed term must match (the intersection of each term's indexed events), which is
+        // also the default operator.
+        for command in ["rdcl.evt_query", "rdcl.evi_query"] {
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES;OP=AND:ART,MUSIC")?, 1);
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES:ART,MUSIC")?, 1);
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES;OP=OR:ART,MUSIC")?, 3);
+
+            assert_eq!(count(command, "X-COUNT:TRUE X-LOCATION-TYPE;OP=AND:HALL,OUTDOOR")?, 1);
+            assert_eq!(count(command, "X-COUNT:TRUE X-LOCATION-TYPE:HALL,OUTDOOR")?, 1);
+            assert_eq!(count(command, "X-COUNT:TRUE X-LOCATION-TYPE;OP=OR:HALL,OUTDOOR")?, 3);
+        }
+
+        Ok(())
+    }
+
+    fn test_event_wildcard_query(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        for (event_uid, categories) in [
+            ("TENANT_123_ONE", "CATEGORIES:COURSE/MATHS,COURSE/HISTORY"),
+            ("TENANT_123_TWO", "CATEGORIES:WORKSHOP"),
+            ("TENANT_1234_ONE", "CATEGORIES:COURSE/ART"),
+            ("TENANT_456_ONE", "CATEGORIES:COURSES"),
+        ] {
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                event_uid,
+                [
+                    "DTSTART:20210105T190000Z",
+                    "DTEND:20210105T210000Z",
+                    categories,
+                ],
+            );
+        }
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        for command in ["rdcl.evt_query", "rdcl.evi_query"] {
+            assert_eq!(count(command, "X-COUNT:TRUE X-UID:TENANT_123_*")?, 2);
+            assert_eq!(count(command, "X-COUNT:TRUE X-UID:TENANT_123*")?, 3);
+            assert_eq!(count(command, "X-COUNT:TRUE X-UID-NOT:TENANT_123_*")?, 2);
+            assert_eq!(count(command, "X-COUNT:TRUE X-UID:TENANT_456_*,TENANT_123_TWO")?, 2);
+
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES:COURSE/*")?, 2);
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES;OP=OR:COURSE/*,WORKSHOP")?, 3);
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES:COURSE/*,COURSE/MATHS")?, 1);
+            assert_eq!(count(command, "X-COUNT:TRUE X-UID:TENANT_123_* X-CATEGORIES:COURSE/*")?, 1);
+        }
+
+        Ok(())
+    }
+
+    fn test_event_regex_query(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        for (event_uid, categories, class) in [
+            ("TENANT_123_ONE", "CATEGORIES:COURSE/MATHS/2021", "CLASS:X-TIER-1"),
+            ("TENANT_456_ONE", "CATEGORIES:COURSE/HISTORY/2022", "CLASS:X-TIER-22"),
+            ("TENANT_ABC_ONE", "CATEGORIES:COURSE/ART/2021", "CLASS:PUBLIC"),
+        ] {
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                event_uid,
+                [
+                    "DTSTART:20210105T190000Z",
+                    "DTEND:20210105T210000Z",
+                    categories,
+                    class,
+                ],
+            );
+        }
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        for command in ["rdcl.evt_query", "rdcl.evi_query"] {
+            assert_eq!(count(command, "X-COUNT:TRUE X-UID;OP=REGEX:TENANT_[0-9]+_ONE")?, 2);
+            assert_eq!(count(command, "X-COUNT:TRUE X-UID-NOT;OP=REGEX:TENANT_[0-9]+_ONE")?, 1);
+            assert_eq!(count(command, "X-COUNT:TRUE X-UID;OP=REGEX:TENANT_[0-9]+")?, 0);
+
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES;OP=REGEX:COURSE/(MATHS|HISTORY)/20[0-9]{2}")?, 2);
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES;OP=REGEX:COURSE/.*/2021")?, 2);
+
+            assert_eq!(count(command, "X-COUNT:TRUE X-CLASS;OP=REGEX:X-TIER-[0-9]+")?, 2);
+            assert_eq!(count(command, "X-COUNT:TRUE X-CLASS;OP=REGEX:X-TIER-[0-9] X-CATEGORIES;OP=REGEX:COURSE/.*/2021")?, 1);
+
+            // Invalid (or overly complex) patterns are rejected.
+            assert!(count(command, "X-COUNT:TRUE X-CATEGORIES;OP=REGEX:COURSE/(MATHS/.*").is_err());
+        }
+
+        Ok(())
+    }
+
+    fn test_calendar_term_normalization(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        for (event_uid, categories) in [
+            ("EVENT_ONE", "CATEGORIES:Café"),
+            ("EVENT_TWO", "CATEGORIES:CAFE"),
+            ("EVENT_THREE", "CATEGORIES:Cafe"),
+        ] {
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                event_uid,
+                [
+                    "DTSTART:20210105T190000Z",
+                    "DTEND:20210105T210000Z",
+                    categories,
+                ],
+            );
+        }
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        for command in ["rdcl.evt_query", "rdcl.evi_query"] {
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES:Cafe")?, 1);
+        }
+
+        let calendar_set_result: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-TERM-NORMALIZATION:STRIP-DIACRITICS")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_set_result,
+            vec![
+                String::from("UID:TEST_CALENDAR_UID"),
+                String::from("X-TERM-NORMALIZATION:STRIP-DIACRITICS"),
+            ],
+        );
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        // Changing the normalisation rebuilds the indexes, re-normalising the existing terms.
+        for command in ["rdcl.evt_query", "rdcl.evi_query"] {
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES:Cafe")?, 2);
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES:Café")?, 2);
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES-NOT:Café")?, 1);
+        }
+
+        let _: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-TERM-NORMALIZATION:CASE-FOLD,STRIP-DIACRITICS")
+                .query(connection)?;
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        for command in ["rdcl.evt_query", "rdcl.evi_query"] {
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES:cafe")?, 3);
+        }
+
+        // Changing the normalisation whilst the indexes are disabled applies once they are rebuilt.
+        disable_calendar_indexes!(connection, "TEST_CALENDAR_UID", 1);
+
+        let _: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-TERM-NORMALIZATION:NONE")
+                .query(connection)?;
+
+        rebuild_calendar_indexes!(connection, "TEST_CALENDAR_UID");
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        for command in ["rdcl.evt_query", "rdcl.evi_query"] {
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES:cafe")?, 0);
+            assert_eq!(count(command, "X-COUNT:TRUE X-CATEGORIES:Cafe")?, 1);
+        }
+
+        assert!(
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-TERM-NORMALIZATION:LOWERCASE")
+                .query::<Vec<String>>(connection)
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    fn test_calendar_indexed_x_properties(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        for (event_uid, instructor) in [
+            ("EVENT_ONE", "X-INSTRUCTOR:Jane Doe"),
+            ("EVENT_TWO", "X-INSTRUCTOR:John Doe"),
+        ] {
+            set_and_assert_event!(
+                connection,
+                "TEST_CALENDAR_UID",
+                event_uid,
+                [
+                    "DTSTART:20210105T190000Z",
+                    "DTEND:20210105T210000Z",
+                    "RRULE:FREQ=WEEKLY;COUNT=2;INTERVAL=1",
+                    "X-LEVEL:Beginner",
+                    instructor,
+                ],
+            );
+        }
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        // Passive X-properties are not indexed until declared by the Calendar.
+        for command in ["rdcl.evt_query", "rdcl.evi_query"] {
+            assert_eq!(count(command, "X-COUNT:TRUE X-PROP;NAME=X-INSTRUCTOR:Jane Doe")?, 0);
+        }
+
+        let calendar_set_result: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-INDEXED-PROPERTIES:X-INSTRUCTOR")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_set_result,
+            vec![
+                String::from("UID:TEST_CALENDAR_UID"),
+                String::from("X-INDEXED-PROPERTIES:X-INSTRUCTOR"),
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "20210112T190000Z",
+            [
+                "X-INSTRUCTOR:John Doe",
+            ],
+        );
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        assert_eq!(count("rdcl.evt_query", "X-COUNT:TRUE X-PROP;NAME=X-INSTRUCTOR:Jane Doe")?, 1);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-PROP;NAME=X-INSTRUCTOR:Jane Doe")?, 1);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-PROP;NAME=X-INSTRUCTOR:John Doe")?, 3);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-PROP-NOT;NAME=X-INSTRUCTOR:John Doe")?, 1);
+
+        // Undeclared X-properties remain unindexed.
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-PROP;NAME=X-LEVEL:Beginner")?, 0);
+
+        let _: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-INDEXED-PROPERTIES:X-INSTRUCTOR,X-LEVEL")
+                .query(connection)?;
+
+        let mut count = |command: &str, query: &str| -> Result<i64> {
+            Ok(redis::cmd(command).arg("TEST_CALENDAR_UID").arg(query).query::<i64>(connection)?)
+        };
+
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE X-PROP;NAME=X-LEVEL:Beginner")?, 4);
+        assert_eq!(count("rdcl.evi_query", "X-COUNT:TRUE (X-PROP;NAME=X-LEVEL:Beginner AND X-PROP;NAME=X-INSTRUCTOR:Jane Doe)")?, 1);
+
+        assert!(
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-PROP:Beginner")
+                .query::<Vec<Value>>(connection)
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    fn test_calendar_event_schema(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        let calendar_set_result: Vec<String> =
+            redis::cmd("rdcl.cal_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-REQUIRED-PROPERTIES:GEO,CATEGORIES")
+                .arg("X-ALLOWED-PROPERTIES:DTSTART,DTEND,RRULE,SUMMARY")
+                .query(connection)?;
+
+        assert_matching_ical_properties!(
+            calendar_set_result,
+            vec![
+                String::from("UID:TEST_CALENDAR_UID"),
+                String::from("X-ALLOWED-PROPERTIES:DTSTART,DTEND,RRULE,SUMMARY"),
+                String::from("X-REQUIRED-PROPERTIES:GEO,CATEGORIES"),
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "DTSTART:20210105T190000Z",
+                "DTEND:20210105T210000Z",
+                "RRULE:FREQ=WEEKLY;COUNT=2;INTERVAL=1",
+                "CATEGORIES:CATEGORY_ONE",
+                "GEO:51.751365550307604;-1.2601196837753945",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        // Missing the required GEO property.
+        let invalid_event_set_result: RedisResult<Value> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_TWO")
+                .arg("DTSTART:20210105T190000Z CATEGORIES:CATEGORY_ONE")
+                .query(connection);
+
+        assert!(invalid_event_set_result.is_err());
+
+        // Specifying the disallowed LOCATION property.
+        let invalid_event_set_result: RedisResult<Value> =
+            redis::cmd("rdcl.evt_set")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_TWO")
+                .arg("DTSTART:20210105T190000Z CATEGORIES:CATEGORY_ONE GEO:51.751365550307604;-1.2601196837753945 LOCATION:Oxford")
+                .query(connection);
+
+        assert!(invalid_event_set_result.is_err());
+
+        let invalid_event_mset_result: RedisResult<Value> =
+            redis::cmd("rdcl.evt_mset")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_TWO")
+                .arg("DTSTART:20210105T190000Z CATEGORIES:CATEGORY_ONE")
+                .query(connection);
+
+        assert!(invalid_event_mset_result.is_err());
+
+        // Rejected events are never stored (or indexed).
+        let event_get_result: Value =
+            redis::cmd("rdcl.evt_get")
+                .arg("TEST_CALENDAR_UID")
+                .arg("EVENT_TWO")
+                .query(connection)?;
+
+        assert_eq!(event_get_result, Value::Nil);
+
+        Ok(())
+    }
+
+    fn test_calendar_event_schema_aof_reload(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        // Set before the schema is declared, so not conforming to it (missing the required GEO
+        // property and specifying the disallowed LOCATION property).
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "DTSTART:20210105T190000Z",
+                "CATEGORIES:CATEGORY_ONE",
+                "LOCATION:Oxford",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        redis::cmd("rdcl.cal_set")
+            .arg("TEST_CALENDAR_UID")
+            .arg("X-REQUIRED-PROPERTIES:GEO,CATEGORIES")
+            .arg("X-ALLOWED-PROPERTIES:DTSTART,DTEND,RRULE,SUMMARY")
+            .query::<Value>(connection)?;
+
+        // Assert the non-conforming event is not dropped when replayed from the AOF.
+        utils::reload_from_aof(connection)?;
+
+        assert_event_present!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "DTSTART:20210105T190000Z",
+                "CATEGORIES:CATEGORY_ONE",
+                "LOCATION:Oxford",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn test_calendar_dump_restore(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "CATEGORIES:CATEGORY_ONE",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_IN_OXFORD_MON_WED",
+            "20210104T170000Z",
+            [
+                "LAST-MODIFIED:20210501T090000Z",
+                "CATEGORIES:CATEGORY_TWO",
+            ],
+        );
+
+        let calendar_dump: Vec<u8> = redis::cmd("DUMP").arg("TEST_CALENDAR_UID").query(connection)?;
+
+        // Assert the dumped Calendar can be restored onto another key (e.g. when moved to another
+        // shard via MIGRATE), taking the UID of that key.
+        assert_eq!(
+            redis::cmd("RESTORE").arg("TEST_CALENDAR_UID_RESTORED").arg(0).arg(calendar_dump.clone()).query(connection),
+            RedisResult::Ok(Value::Okay),
+        );
+
+        assert_calendar_present!(connection, "TEST_CALENDAR_UID_RESTORED");
+
+        assert_event_present!(
+            connection,
+            "TEST_CALENDAR_UID_RESTORED",
+            "EVENT_IN_OXFORD_MON_WED",
+            [
+                "SUMMARY:Event in Oxford on Mondays and Wednesdays at 5:00PM",
+                "RRULE:BYDAY=MO,WE;COUNT=3;FREQ=WEEKLY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "DTEND:20201231T173000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+                "CATEGORIES:CATEGORY_ONE",
+            ],
+        );
+
+        assert_event_override_present!(
+            connection,
+            "TEST_CALENDAR_UID_RESTORED",
+            "EVENT_IN_OXFORD_MON_WED",
+            "20210104T170000Z",
+            [
+                "LAST-MODIFIED:20210501T090000Z",
+                "CATEGORIES:CATEGORY_TWO",
+            ],
+        );
+
+        // Assert the restored Calendar indexes are immediately queryable.
+        let calendar_query_result: Vec<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID_RESTORED")
+                .arg("X-CATEGORIES:CATEGORY_TWO")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 1);
+
+        // Assert the dumped Calendar can be restored over the key it was dumped from.
+        assert_eq!(
+            redis::cmd("RESTORE").arg("TEST_CALENDAR_UID").arg(0).arg(calendar_dump.clone()).arg("REPLACE").query(connection),
+            RedisResult::Ok(Value::Okay),
+        );
+
+        assert_calendar_present!(connection, "TEST_CALENDAR_UID");
+
+        // Assert a payload dumped by a newer version of RediCal (tagged with a newer data type
+        // encver) is rejected rather than restored. The encver is held within the lowest bits of
+        // the big-endian module type id following the object type and length encoding bytes.
+        let mut newer_calendar_dump = calendar_dump;
+
+        newer_calendar_dump[9] += 1;
+
+        redis::cmd("DEBUG").arg("SET-SKIP-CHECKSUM-VALIDATION").arg(1).execute(connection);
+
+        let newer_restore_result: RedisResult<Value> =
+            redis::cmd("RESTORE")
+                .arg("TEST_CALENDAR_UID_NEWER")
+                .arg(0)
+                .arg(newer_calendar_dump)
+                .query(connection);
+
+        redis::cmd("DEBUG").arg("SET-SKIP-CHECKSUM-VALIDATION").arg(0).execute(connection);
+
+        assert!(newer_restore_result.is_err());
+
+        assert_eq!(
+            redis::cmd("EXISTS").arg("TEST_CALENDAR_UID_NEWER").query(connection),
+            RedisResult::Ok(Value::Int(0)),
+        );
+
+        Ok(())
+    }
+
+    fn test_query_pagination(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "RRULE:COUNT=3;FREQ=DAILY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_TWO",
+            [
+                "DTSTART:20201231T180000Z",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let pagination_header = |total: i64, total_capped: i64, offset: i64, limit: i64, truncated: i64, generation: i64| {
+            query_results_header(
+                &[
+                    ("total", total),
+                    ("total_capped", total_capped),
+                    ("offset", offset),
+                    ("limit", limit),
+                    ("generation", generation),
+                ],
+                truncated,
+                0,
+                &[],
+            )
+        };
+
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-OFFSET:1")
+                .arg("X-LIMIT:2")
+                .arg("WITHPAGINATION")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 3);
+        assert_eq!(calendar_query_result[0], pagination_header(4, 0, 1, 2, 0, 2));
+
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evt_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:1")
+                .arg("WITHPAGINATION")
+                .arg("OUTPUT")
+                .arg("JSON")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 2);
+        assert_eq!(calendar_query_result[0], pagination_header(2, 0, 0, 1, 0, 2));
+
+        // Without WITHPAGINATION (or WITHHEADER), no header is returned.
+        let calendar_query_result: Vec<Vec<Vec<String>>> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:2")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 2);
+
+        // The total is capped to the configured max count.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.PAGINATION-COUNT-MAX")
+            .arg("3")
+            .execute(connection);
+
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:2")
+                .arg("WITHPAGINATION")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result[0], pagination_header(3, 1, 0, 2, 0, 2));
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.PAGINATION-COUNT-MAX")
+            .arg("10000")
+            .execute(connection);
+
+        // The limit applied is capped to the configured max query results, flagging the results as truncated.
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.MAX-QUERY-RESULTS")
+            .arg("1")
+            .execute(connection);
+
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-LIMIT:50")
+                .arg("WITHPAGINATION")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 2);
+        assert_eq!(calendar_query_result[0], pagination_header(4, 0, 0, 1, 1, 2));
+
+        redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("REDICAL.MAX-QUERY-RESULTS")
+            .arg("0")
+            .execute(connection);
+
+        Ok(())
+    }
+
+    fn test_query_group_by_uid(connection: &mut Connection) -> Result<()> {
+        set_and_assert_calendar!(connection, "TEST_CALENDAR_UID");
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            [
+                "RRULE:COUNT=2;FREQ=DAILY;INTERVAL=1",
+                "DTSTART:20201231T170000Z",
+                "SUMMARY:Event one",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        set_and_assert_event_override!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_ONE",
+            "20210101T170000Z",
+            [
+                "SUMMARY:Overridden event one",
+            ],
+        );
+
+        set_and_assert_event!(
+            connection,
+            "TEST_CALENDAR_UID",
+            "EVENT_TWO",
+            [
+                "DTSTART:20201231T180000Z",
+                "SUMMARY:Event two",
+                "LAST-MODIFIED:20210501T090000Z",
+            ],
+        );
+
+        let statuses = |values: &[&str]| {
+            Value::Bulk(
+                values
+                    .iter()
+                    .map(|value| Value::Status(value.to_string()))
+                    .collect()
+            )
+        };
+
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-GROUP-BY:UID")
+                .query(connection)?;
+
+        // Each event is returned once (ordered by its first event instance), with each of its
+        // event instances only including the properties differing from the base event.
+        assert_eq!(
+            calendar_query_result,
+            vec![
+                Value::Bulk(vec![
+                    Value::Status(String::from("uid")),
+                    Value::Data(String::from("EVENT_ONE").into_bytes()),
+                    Value::Status(String::from("event")),
+                    statuses(&[
+                        "DTSTART:20201231T170000Z",
+                        "LAST-MODIFIED:20210501T090000Z",
+                        "RRULE:COUNT=2;FREQ=DAILY;INTERVAL=1",
+                        "SUMMARY:Event one",
+                        "UID:EVENT_ONE",
+                    ]),
+                    Value::Status(String::from("instances")),
+                    Value::Bulk(vec![
+                        Value::Bulk(vec![
+                            statuses(&["DTSTART:20201231T170000Z"]),
+                            statuses(&[
+                                "DTEND:20201231T170000Z",
+                                "DURATION:PT0S",
+                                "RECURRENCE-ID;VALUE=DATE-TIME:20201231T170000Z",
+                            ]),
+                        ]),
+                        Value::Bulk(vec![
+                            statuses(&["DTSTART:20210101T170000Z"]),
+                            statuses(&[
+                                "DTEND:20210101T170000Z",
+                                "DTSTART:20210101T170000Z",
+                                "DURATION:PT0S",
+                                "RECURRENCE-ID;VALUE=DATE-TIME:20210101T170000Z",
+                                "SUMMARY:Overridden event one",
+                            ]),
+                        ]),
+                    ]),
+                ]),
+                Value::Bulk(vec![
+                    Value::Status(String::from("uid")),
+                    Value::Data(String::from("EVENT_TWO").into_bytes()),
+                    Value::Status(String::from("event")),
+                    statuses(&[
+                        "DTSTART:20201231T180000Z",
+                        "LAST-MODIFIED:20210501T090000Z",
+                        "SUMMARY:Event two",
+                        "UID:EVENT_TWO",
+                    ]),
+                    Value::Status(String::from("instances")),
+                    Value::Bulk(vec![
+                        Value::Bulk(vec![
+                            statuses(&["DTSTART:20201231T180000Z"]),
+                            statuses(&[
+                                "DTEND:20201231T180000Z",
+                                "DURATION:PT0S",
+                                "RECURRENCE-ID;VALUE=DATE-TIME:20201231T180000Z",
+                            ]),
+                        ]),
+                    ]),
+                ]),
+            ],
+        );
+
+        // The offset and limit still apply to the event instances rather than the events.
+        let calendar_query_result: Vec<Value> =
+            redis::cmd("rdcl.evi_query")
+                .arg("TEST_CALENDAR_UID")
+                .arg("X-GROUP-BY:UID")
+                .arg("X-OFFSET:2")
+                .arg("X-LIMIT:1")
+                .query(connection)?;
+
+        assert_eq!(calendar_query_result.len(), 1);
+
+        let Value::Bulk(event_instance_group) = &calendar_query_result[0] else {
+            panic!("Expected grouped event instances, got: {calendar_query_result:#?}");
+        };
+
+        assert_eq!(event_instance_group[1], Value::Data(String::from("EVENT_ONE").into_bytes()));
+        assert_eq!(event_instance_group[5], Value::Bulk(vec![
+            Value::Bulk(vec![
+                statuses(&["DTSTART:20210101T170000Z"]),
+                statuses(&[
+                    "DTEND:20210101T170000Z",
+                    "DTSTART:20210101T170000Z",
+                    "DURATION:PT0S",
+                    "RECURRENCE-ID;VALUE=DATE-TIME:20210101T170000Z",
+                    "SUMMARY:Overridden event one",
+                ]),
+            ]),
+        ]));
+
+        Ok(())
+    }
+
     run_all_integration_tests_sequentially!(
         test_calendar_get_set_del,
+        test_calendar_memory_usage,
+        test_calendar_copy,
+        test_calendar_digest,
         test_event_get_set_del_list,
+        test_event_mset,
+        test_event_import,
+        test_calendar_export,
         test_event_set_last_modified,
         test_event_prune,
         test_event_override_get_set_del_list,
         test_event_override_set_last_modified,
+        test_event_override_mset,
         test_event_override_prune,
         test_event_instance_list,
+        test_event_instance_next,
         test_event_timezone_handling,
         test_calendar_event_instance_query,
         test_calendar_event_query,
         test_calendar_index_disable_rebuild,
+        test_json_output,
+        test_resp3_replies,
+        test_command_getkeys,
+        test_replication,
         test_rdb_save_load,
         test_key_expire_eviction_keyspace_events,
+        test_flush_keyspace_events,
         test_redical_ical_parser_timeout_ms_config,
+        test_redical_notification_verbosity_config,
+        test_redical_query_execution_timeout_ms_config,
+        test_redical_max_events_per_calendar_config,
+        test_redical_max_query_results_config,
+        test_redical_occurrence_expansion_limits_config,
+        test_event_expire,
+        test_query_explain,
+        test_event_scan,
+        test_change_feed,
+        test_query_subscriptions,
+        test_calendar_default_timezone,
+        test_event_del_query,
+        test_event_update_query,
+        test_query_profile,
+        test_calendar_index_verify,
+        test_calendar_dump_restore,
+        test_query_pagination,
+        test_query_group_by_uid,
+        test_event_instance_get,
+        test_event_instance_add,
+        test_event_children,
+        test_calendar_index_terms,
+        test_last_modified_conflict_strategy,
+        test_set_reply_modes,
+        test_if_match_conditional_writes,
+        test_existence_conditional_writes,
+        test_set_with_now,
+        test_query_return_properties,
+        test_query_count_only,
+        test_line_separated_content_line_args,
+        test_calendar_get_with_metadata,
+        test_calendar_passive_properties,
+        test_command_metrics,
+        test_scheduled_pruning,
+        test_query_merge_intervals,
+        test_calendar_description_properties,
+        test_event_display_and_conference_properties,
+        test_event_structured_data_and_participants,
+        test_event_alarms,
+        test_event_location_query,
+        test_degenerate_query_warnings,
+        test_event_multiple_reltype_query,
+        test_event_list_valued_and_query,
+        test_event_wildcard_query,
+        test_event_regex_query,
+        test_calendar_term_normalization,
+        test_calendar_indexed_x_properties,
+        test_calendar_event_schema,
+        test_calendar_event_schema_aof_reload,
     );
 }
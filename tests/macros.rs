@@ -128,6 +128,20 @@ macro_rules! disable_calendar_indexes {
     }
 }
 
+#[macro_export]
+macro_rules! assert_calendar_stats {
+    ($connection:expr, $calendar_uid:expr, [$(($field:expr, $expected_value:expr)),* $(,)*] $(,)*) => {
+        let calendar_stats: std::collections::HashMap<String, Value> =
+            redis::cmd("rdcl.cal_stats")
+            .arg($calendar_uid)
+            .query($connection)?;
+
+        $(
+            assert_eq!(calendar_stats.get($field), Some(&$expected_value), "calendar stats field: {}", $field);
+        )*
+    }
+}
+
 #[macro_export]
 macro_rules! rebuild_calendar_indexes {
     ($connection:expr, $calendar_uid:expr $(,)*) => {
@@ -592,6 +606,45 @@ macro_rules! set_and_assert_event_override {
             ],
         );
     };
+
+    ($connection:expr, $calendar_uid:expr, $event_uid:expr, $override_date_string:expr, DETACHED, [$($ical_property:expr),+ $(,)*] $(,)*) => {
+        let mut ical_properties: Vec<String> = vec![
+            $(
+                String::from($ical_property),
+            )+
+        ];
+
+        let joined_ical_properties = ical_properties.join(" ");
+
+        let event_override_set_result: Vec<String> = redis::cmd("rdcl.evo_set")
+            .arg("TEST_CALENDAR_UID")
+            .arg($event_uid)
+            .arg($override_date_string)
+            .arg("DETACHED")
+            .arg(joined_ical_properties)
+            .query($connection)
+            .with_context(|| {
+                format!(
+                    "failed to set detached override for event with UID: '{}' at '{}' via rdcl.evo_set", $event_uid, $override_date_string,
+                )
+            })?;
+
+        ical_properties.push(format!("DTSTART:{}", $override_date_string));
+
+        assert_matching_ical_properties!(event_override_set_result, ical_properties);
+
+        assert_event_override_present!(
+            $connection,
+            $calendar_uid, 
+            $event_uid,
+            $override_date_string,
+            [
+                $(
+                    $ical_property,
+                )+
+            ],
+        );
+    };
 }
 
 #[macro_export]
@@ -836,6 +889,53 @@ macro_rules! list_and_assert_matching_event_instances {
     };
 }
 
+#[macro_export]
+macro_rules! next_and_assert_matching_event_instances {
+    ($connection:expr, $calendar_uid:expr, $event_uid:expr, $count:expr, $anchor:expr, [] $(,)*) => {
+        let event_instance_next_result: Vec<Vec<String>> = redis::cmd("rdcl.evi_next")
+            .arg($calendar_uid)
+            .arg($event_uid)
+            .arg($count)
+            .arg($anchor)
+            .query($connection)
+            .with_context(|| {
+                format!(
+                    "failed to get next instances for event UID: '{}' events via rdcl.evi_next", $event_uid,
+                )
+            })?;
+
+        let expected_event_instance_next_result: Vec<Vec<String>> = vec![];
+
+        assert_eq!(event_instance_next_result, expected_event_instance_next_result);
+    };
+
+    ($connection:expr, $calendar_uid:expr, $event_uid:expr, $count:expr, $anchor:expr, [$([$($ical_component_property:expr),+ $(,)*]),+ $(,)*] $(,)*) => {
+        let expected_event_instance_next_result: Vec<Vec<String>> = vec![
+            $(
+                vec![
+                    $(
+                        String::from($ical_component_property),
+                    )+
+                ],
+            )+
+        ];
+
+        let event_instance_next_result: Vec<Vec<String>> = redis::cmd("rdcl.evi_next")
+            .arg($calendar_uid)
+            .arg($event_uid)
+            .arg($count)
+            .arg($anchor)
+            .query($connection)
+            .with_context(|| {
+                format!(
+                    "failed to get next instances for event UID: '{}' events via rdcl.evi_next", $event_uid,
+                )
+            })?;
+
+        assert_matching_ical_components!(event_instance_next_result, expected_event_instance_next_result);
+    };
+}
+
 #[macro_export]
 macro_rules! query_calendar_and_assert_matching_event_instances {
     ($connection:expr, $calendar_uid:expr, [], [$([$([$($ical_component_meta_property:expr),+ $(,)*], [$($ical_component_property:expr),+ $(,)*]),+ $(,)*]),+ $(,)*] $(,)*) => {
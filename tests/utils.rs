@@ -2,6 +2,8 @@ use anyhow::{Context, Result};
 
 use redis::Connection;
 use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
 use std::thread;
 use std::path::PathBuf;
 use std::process::Command;
@@ -121,6 +123,38 @@ pub fn get_redis_connection(port: u16) -> Result<Connection> {
     }
 }
 
+// Enable the AOF (triggering a rewrite of the current dataset to it), wait for it to be written,
+// and reload the dataset from it, asserting what is replayed from the AOF rather than sent by a
+// client. The RDB preamble is disabled so that the dataset is rewritten as RediCal commands. The
+// AOF is disabled again once reloaded.
+pub fn reload_from_aof(connection: &mut Connection) -> Result<()> {
+    redis::cmd("CONFIG").arg("SET").arg("aof-use-rdb-preamble").arg("no").query::<()>(connection)?;
+    redis::cmd("CONFIG").arg("SET").arg("appendonly").arg("yes").query::<()>(connection)?;
+
+    let deadline = std::time::Instant::now() + Duration::from_secs(10);
+
+    loop {
+        let persistence_info = redis::cmd("INFO").arg("persistence").query::<String>(connection)?;
+
+        if persistence_info.contains("aof_rewrite_in_progress:0") && persistence_info.contains("aof_rewrite_scheduled:0") {
+            break;
+        }
+
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!("AOF rewrite not completed within 10 seconds");
+        }
+
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    redis::cmd("DEBUG").arg("LOADAOF").query::<()>(connection)?;
+
+    redis::cmd("CONFIG").arg("SET").arg("appendonly").arg("no").query::<()>(connection)?;
+    redis::cmd("CONFIG").arg("SET").arg("aof-use-rdb-preamble").arg("yes").query::<()>(connection)?;
+
+    Ok(())
+}
+
 pub fn listen_for_keyspace_events(port: u16, mut handler: impl FnMut(&mut Arc<Mutex<VecDeque<redis::Msg>>>) -> Result<()>) -> Result<()> {
     let (kill_tx, kill_rx): (mpsc::Sender<()>, mpsc::Receiver<()>) = mpsc::channel();
 
@@ -178,3 +212,92 @@ pub fn listen_for_keyspace_events(port: u16, mut handler: impl FnMut(&mut Arc<Mu
 
     Ok(())
 }
+
+// Wait (up to the timeout) for a keyspace event on the key with a payload starting with the
+// provided prefix to be received by the `listen_for_keyspace_events` listener, removing it from
+// the message queue and returning its payload. This is needed for keyspace events published
+// asynchronously (once background work is complete) or with varying payloads.
+pub fn wait_for_keyspace_event(message_queue: &Arc<Mutex<VecDeque<redis::Msg>>>, event_prefix: &str, keyname: &str, timeout: Duration) -> Result<String> {
+    let deadline = std::time::Instant::now() + timeout;
+
+    loop {
+        {
+            let mut message_queue_lock = message_queue.lock().unwrap();
+
+            let message_index =
+                message_queue_lock
+                    .iter()
+                    .position(|message| {
+                        message.get_channel_name().replace("__keyspace@0__:", "") == keyname &&
+                        message.get_payload::<String>().is_ok_and(|payload| payload.starts_with(event_prefix))
+                    });
+
+            if let Some(message) = message_index.and_then(|message_index| message_queue_lock.remove(message_index)) {
+                return Ok(message.get_payload::<String>()?);
+            }
+        }
+
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!("Keyspace event: {event_prefix} on key: {keyname} not received within {timeout:?}");
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+// Send a command over a raw TCP connection returning the raw (unparsed) reply. This is needed for
+// asserting RESP3 replies as they are not supported by the redis client crate.
+pub fn send_raw_redis_command(stream: &mut TcpStream, args: &[&str]) -> Result<String> {
+    let mut command = format!("*{}\r\n", args.len());
+
+    for arg in args {
+        command.push_str(format!("${}\r\n{arg}\r\n", arg.len()).as_str());
+    }
+
+    stream.write_all(command.as_bytes())?;
+
+    stream.set_read_timeout(Some(Duration::from_millis(250)))?;
+
+    let mut reply: Vec<u8> = Vec::new();
+    let mut buffer = [0; 4096];
+
+    // Keep reading until the reply has been received in full (no more bytes within the timeout).
+    loop {
+        match stream.read(&mut buffer) {
+            Ok(0) => break,
+
+            Ok(read_bytes) => reply.extend_from_slice(&buffer[..read_bytes]),
+
+            Err(error) if matches!(error.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) && !reply.is_empty() => break,
+
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    Ok(String::from_utf8(reply)?)
+}
+
+// Build the header expected to precede query results, comprised of the pagination metadata (if
+// requested via WITHPAGINATION) followed by the truncated and partial flags, and any warnings.
+pub fn query_results_header(pagination: &[(&str, i64)], truncated: i64, partial: i64, warnings: &[&str]) -> redis::Value {
+    let mut header = Vec::new();
+
+    for (key, value) in pagination {
+        header.push(redis::Value::Status(key.to_string()));
+        header.push(redis::Value::Int(*value));
+    }
+
+    header.push(redis::Value::Status(String::from("truncated")));
+    header.push(redis::Value::Int(truncated));
+    header.push(redis::Value::Status(String::from("partial")));
+    header.push(redis::Value::Int(partial));
+    header.push(redis::Value::Status(String::from("warnings")));
+    header.push(redis::Value::Bulk(
+        warnings
+            .iter()
+            .map(|warning| redis::Value::Status(warning.to_string()))
+            .collect()
+    ));
+
+    redis::Value::Bulk(header)
+}